@@ -1,6 +1,51 @@
 use crate::api::client::ApiClient;
-use crate::api::types::{ApiErrorResponse, CreateBundleRequest, CreateBundleResponse};
+use crate::api::types::{
+    ApiErrorResponse, CasObject, CasUploadRequest, CreateBundleRequest, CreateBundleResponse,
+    CreateChunkedBundleRequest,
+};
+use crate::authorship::internal_db::InternalDatabase;
 use crate::error::GitAiError;
+use crate::utils::debug_log;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Bundles whose data is larger than this are uploaded in chunks of this size
+pub const BUNDLE_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Chunks sent per CAS upload request
+const CHUNKS_PER_REQUEST: usize = 4;
+
+/// Split `data` into pieces of at most `max_len` bytes, on UTF-8 character boundaries
+pub fn split_into_chunks(data: &str, max_len: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(max_len);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        // A single character wider than max_len still has to go somewhere
+        if end == 0 {
+            end = rest
+                .chars()
+                .next()
+                .map(char::len_utf8)
+                .unwrap_or(rest.len());
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// CAS hash for a chunk: SHA256 of its canonical JSON, as for every other CAS object
+fn chunk_hash(chunk: &str) -> Result<String, GitAiError> {
+    let canonical =
+        serde_json_canonicalizer::to_string(&serde_json::Value::String(chunk.to_string()))
+            .map_err(|e| GitAiError::Generic(format!("Failed to canonicalize JSON: {}", e)))?;
+    Ok(format!("{:x}", Sha256::digest(canonical.as_bytes())))
+}
 
 /// Bundle API endpoints
 impl ApiClient {
@@ -22,45 +67,171 @@ impl ApiClient {
         request: CreateBundleRequest,
     ) -> Result<CreateBundleResponse, GitAiError> {
         let response = self.context().post_json("/api/bundles", &request)?;
-        let status_code = response.status_code;
+        parse_bundle_response(response)
+    }
 
-        let body = response
-            .as_str()
-            .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+    /// Create a bundle, uploading large bundle data in resumable chunks
+    ///
+    /// Small bundles are posted in one request, as with [`ApiClient::create_bundle`].
+    /// Larger ones are split into content-addressed chunks uploaded through CAS. Each
+    /// chunk that reaches the server is recorded in the internal database, so running
+    /// the same upload again after a network failure only sends the missing chunks.
+    ///
+    /// # Arguments
+    /// * `request` - The bundle creation request
+    ///
+    /// # Returns
+    /// * `Ok(CreateBundleResponse)` - Success response with bundle ID and URL
+    /// * `Err(GitAiError)` - Error response; chunks uploaded so far are kept for the next attempt
+    pub fn create_bundle_resumable(
+        &self,
+        request: CreateBundleRequest,
+    ) -> Result<CreateBundleResponse, GitAiError> {
+        let data = serde_json::to_string(&request.data)?;
+        if data.len() <= BUNDLE_CHUNK_SIZE {
+            return self.create_bundle(request);
+        }
 
-        match status_code {
-            200 => {
-                let bundle_response: CreateBundleResponse =
-                    serde_json::from_str(body).map_err(GitAiError::JsonError)?;
-                Ok(bundle_response)
-            }
-            400 => {
-                // Try to parse error response
-                let error_response: ApiErrorResponse =
-                    serde_json::from_str(body).unwrap_or_else(|_| ApiErrorResponse {
-                        error: "Invalid request body".to_string(),
-                        details: Some(serde_json::Value::String(body.to_string())),
-                    });
-                Err(GitAiError::Generic(format!(
-                    "Bad Request: {}",
-                    error_response.error
-                )))
+        let chunks = split_into_chunks(&data, BUNDLE_CHUNK_SIZE);
+        let chunk_hashes = chunks
+            .iter()
+            .map(|chunk| chunk_hash(chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Identifies this exact upload: same title and data resume, anything else starts fresh
+        let bundle_hash = format!(
+            "{:x}",
+            Sha256::digest(format!("{}\n{}", request.title, chunk_hashes.join(",")).as_bytes())
+        );
+
+        let db = InternalDatabase::global()?;
+        let uploaded = db
+            .lock()
+            .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?
+            .uploaded_bundle_chunks(&bundle_hash)?;
+
+        let pending: Vec<usize> = (0..chunks.len())
+            .filter(|i| uploaded.get(i) != Some(&chunk_hashes[*i]))
+            .collect();
+        debug_log(&format!(
+            "Bundle upload {}: {} chunks, {} already uploaded",
+            &bundle_hash[..12],
+            chunks.len(),
+            chunks.len() - pending.len()
+        ));
+
+        for batch in pending.chunks(CHUNKS_PER_REQUEST) {
+            let objects = batch
+                .iter()
+                .map(|&i| CasObject {
+                    content: serde_json::Value::String(chunks[i].to_string()),
+                    hash: chunk_hashes[i].clone(),
+                    metadata: HashMap::from([("kind".to_string(), "bundle_chunk".to_string())]),
+                })
+                .collect();
+            let response = self.upload_cas(CasUploadRequest { objects })?;
+
+            let mut db_guard = db
+                .lock()
+                .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?;
+            for result in &response.results {
+                if result.status != "ok" {
+                    continue;
+                }
+                for &i in batch.iter().filter(|&&i| chunk_hashes[i] == result.hash) {
+                    db_guard.mark_bundle_chunk_uploaded(&bundle_hash, i, &chunk_hashes[i])?;
+                }
             }
-            500 => {
-                let error_response: ApiErrorResponse =
-                    serde_json::from_str(body).unwrap_or_else(|_| ApiErrorResponse {
-                        error: "Internal server error".to_string(),
-                        details: None,
-                    });
-                Err(GitAiError::Generic(format!(
-                    "Internal Server Error: {}",
-                    error_response.error
-                )))
+            drop(db_guard);
+
+            if response.failure_count > 0 {
+                return Err(GitAiError::Generic(format!(
+                    "Failed to upload {} bundle chunk(s); run again to resume",
+                    response.failure_count
+                )));
             }
-            _ => Err(GitAiError::Generic(format!(
-                "Unexpected status code {}: {}",
-                status_code, body
-            ))),
         }
+
+        let response = self.context().post_json(
+            "/api/bundles/chunked",
+            &CreateChunkedBundleRequest {
+                title: request.title,
+                chunks: chunk_hashes,
+            },
+        )?;
+        let bundle = parse_bundle_response(response)?;
+
+        if let Ok(mut db_guard) = db.lock() {
+            let _ = db_guard.clear_bundle_upload(&bundle_hash);
+        }
+        Ok(bundle)
+    }
+}
+
+fn parse_bundle_response(response: minreq::Response) -> Result<CreateBundleResponse, GitAiError> {
+    let status_code = response.status_code;
+
+    let body = response
+        .as_str()
+        .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+
+    match status_code {
+        200 => {
+            let bundle_response: CreateBundleResponse =
+                serde_json::from_str(body).map_err(GitAiError::JsonError)?;
+            Ok(bundle_response)
+        }
+        400 => {
+            // Try to parse error response
+            let error_response: ApiErrorResponse =
+                serde_json::from_str(body).unwrap_or_else(|_| ApiErrorResponse {
+                    error: "Invalid request body".to_string(),
+                    details: Some(serde_json::Value::String(body.to_string())),
+                });
+            Err(GitAiError::Generic(format!(
+                "Bad Request: {}",
+                error_response.error
+            )))
+        }
+        500 => {
+            let error_response: ApiErrorResponse =
+                serde_json::from_str(body).unwrap_or_else(|_| ApiErrorResponse {
+                    error: "Internal server error".to_string(),
+                    details: None,
+                });
+            Err(GitAiError::Generic(format!(
+                "Internal Server Error: {}",
+                error_response.error
+            )))
+        }
+        _ => Err(GitAiError::Generic(format!(
+            "Unexpected status code {}: {}",
+            status_code, body
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_chunks_respects_char_boundaries() {
+        let data = "aé".repeat(5);
+        let chunks = split_into_chunks(&data, 4);
+        assert_eq!(chunks.concat(), data);
+        assert!(chunks.iter().all(|c| c.len() <= 4));
+
+        assert_eq!(split_into_chunks("abcdef", 4), vec!["abcd", "ef"]);
+        assert!(split_into_chunks("", 4).is_empty());
+        // A character wider than the limit gets a chunk of its own
+        assert_eq!(split_into_chunks("é", 1), vec!["é"]);
+    }
+
+    #[test]
+    fn test_chunk_hash_is_stable() {
+        let hash = chunk_hash("{\"prompts\":").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, chunk_hash("{\"prompts\":").unwrap());
+        assert_ne!(hash, chunk_hash("{\"files\":").unwrap());
     }
 }
@@ -1,8 +1,20 @@
 use crate::api::client::ApiClient;
 use crate::api::types::{
-    ApiErrorResponse, CAPromptStoreReadResponse, CasUploadRequest, CasUploadResponse,
+    ApiErrorResponse, CAPromptStoreReadResponse, CasChunk, CasStoredObject, CasUploadManifest,
+    CasUploadRequest, CasUploadResponse, CasUploadSession,
 };
 use crate::error::GitAiError;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Objects larger than this are uploaded in chunks of this size instead of
+/// inside a batch, so a dropped connection only costs one chunk
+pub const CAS_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Manifest round-trips per upload. Each one re-sends only the chunks the
+/// server still reports missing.
+const MAX_RESUME_ROUNDS: usize = 3;
 
 /// CAS API endpoints
 impl ApiClient {
@@ -98,4 +110,187 @@ impl ApiClient {
             ))),
         }
     }
+
+    /// Upload one large CAS object in chunks, resuming whatever an earlier
+    /// attempt left unfinished.
+    ///
+    /// `data` must be the exact bytes `hash` was computed from (the
+    /// canonicalized JSON). Every chunk is checked against what the server
+    /// stored, and the assembled object against `hash`, before it counts as
+    /// uploaded.
+    pub fn upload_cas_chunked(
+        &self,
+        hash: &str,
+        data: &[u8],
+        metadata: &HashMap<String, String>,
+    ) -> Result<(), GitAiError> {
+        let manifest = build_upload_manifest(data, CAS_CHUNK_SIZE, metadata.clone());
+        if manifest.hash != hash {
+            return Err(GitAiError::Generic(format!(
+                "CAS object {} does not match its content (hashes to {})",
+                hash, manifest.hash
+            )));
+        }
+
+        let mut upload_id = None;
+        let mut last_error = None;
+        for _ in 0..MAX_RESUME_ROUNDS {
+            let session: CasUploadSession = parse_cas_response(
+                self.context().post_json("/worker/cas/uploads", &manifest)?,
+                "CAS upload manifest",
+            )?;
+            last_error = None;
+            for index in &session.missing_chunks {
+                let Some(chunk) = manifest.chunks.get(*index) else {
+                    return Err(GitAiError::Generic(format!(
+                        "Server asked for chunk {} of a {}-chunk upload",
+                        index,
+                        manifest.chunks.len()
+                    )));
+                };
+                // Keep going: the next round only re-sends what still failed
+                let start = chunk.index * manifest.chunk_size;
+                let bytes = &data[start..start + chunk.size];
+                if let Err(e) = self.upload_cas_chunk(&session.upload_id, chunk, bytes) {
+                    last_error = Some(e);
+                }
+            }
+            upload_id = Some(session.upload_id);
+            if last_error.is_none() {
+                break;
+            }
+        }
+        if let Some(e) = last_error {
+            return Err(e);
+        }
+        let Some(upload_id) = upload_id else {
+            return Err(GitAiError::Generic("CAS upload never started".to_string()));
+        };
+
+        let stored: CasStoredObject = parse_cas_response(
+            self.context().post_json(
+                &format!("/worker/cas/uploads/{}/complete", upload_id),
+                &serde_json::json!({}),
+            )?,
+            "CAS upload completion",
+        )?;
+        verify_stored(&stored, &manifest.hash, manifest.size, "object")
+    }
+
+    fn upload_cas_chunk(
+        &self,
+        upload_id: &str,
+        chunk: &CasChunk,
+        bytes: &[u8],
+    ) -> Result<(), GitAiError> {
+        let stored: CasStoredObject = parse_cas_response(
+            self.context().post_bytes_with_headers(
+                &format!("/worker/cas/uploads/{}/chunks/{}", upload_id, chunk.index),
+                bytes,
+                &[("X-Chunk-Hash", &chunk.hash)],
+            )?,
+            "CAS chunk upload",
+        )?;
+        verify_stored(
+            &stored,
+            &chunk.hash,
+            chunk.size,
+            &format!("chunk {}", chunk.index),
+        )
+    }
+}
+
+/// Split `data` into `chunk_size` pieces and describe them
+pub fn build_upload_manifest(
+    data: &[u8],
+    chunk_size: usize,
+    metadata: HashMap<String, String>,
+) -> CasUploadManifest {
+    let chunks = data
+        .chunks(chunk_size.max(1))
+        .enumerate()
+        .map(|(index, bytes)| CasChunk {
+            index,
+            hash: sha256_hex(bytes),
+            size: bytes.len(),
+        })
+        .collect();
+    CasUploadManifest {
+        hash: sha256_hex(data),
+        size: data.len(),
+        chunk_size,
+        chunks,
+        metadata,
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+fn verify_stored(
+    stored: &CasStoredObject,
+    hash: &str,
+    size: usize,
+    what: &str,
+) -> Result<(), GitAiError> {
+    if stored.hash != hash || stored.size != size {
+        return Err(GitAiError::Generic(format!(
+            "CAS {} failed verification: expected {} ({} bytes), server stored {} ({} bytes)",
+            what, hash, size, stored.hash, stored.size
+        )));
+    }
+    Ok(())
+}
+
+fn parse_cas_response<T: DeserializeOwned>(
+    response: minreq::Response,
+    operation: &str,
+) -> Result<T, GitAiError> {
+    let status_code = response.status_code;
+    let body = response
+        .as_str()
+        .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+    if status_code == 200 {
+        return serde_json::from_str(body).map_err(GitAiError::JsonError);
+    }
+    let error = serde_json::from_str::<ApiErrorResponse>(body)
+        .map(|response| response.error)
+        .unwrap_or_else(|_| body.to_string());
+    Err(GitAiError::Generic(format!(
+        "{} failed with status {}: {}",
+        operation, status_code, error
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_addresses_each_chunk_by_content() {
+        let data = b"abcdefghij";
+        let manifest = build_upload_manifest(data, 4, HashMap::new());
+        assert_eq!(manifest.hash, sha256_hex(data));
+        assert_eq!(manifest.size, 10);
+        let sizes: Vec<usize> = manifest.chunks.iter().map(|c| c.size).collect();
+        assert_eq!(sizes, vec![4, 4, 2]);
+        assert_eq!(manifest.chunks[1].index, 1);
+        assert_eq!(manifest.chunks[1].hash, sha256_hex(b"efgh"));
+        assert_eq!(manifest.chunks[2].hash, sha256_hex(b"ij"));
+    }
+
+    #[test]
+    fn stored_objects_must_match_hash_and_size() {
+        let stored = CasStoredObject {
+            hash: "abc".to_string(),
+            size: 3,
+        };
+        assert!(verify_stored(&stored, "abc", 3, "object").is_ok());
+        let err = verify_stored(&stored, "abc", 4, "chunk 2").unwrap_err();
+        assert!(err.to_string().contains("chunk 2 failed verification"));
+        assert!(verify_stored(&stored, "abd", 3, "object").is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::api::rate_limit;
 use crate::auth::{CredentialStore, OAuthClient};
 use crate::config;
 use crate::error::GitAiError;
@@ -70,6 +71,9 @@ pub struct ApiContext {
     pub api_key: Option<String>,
     /// Request timeout in seconds
     pub timeout_secs: Option<u64>,
+    /// Retries on 429/5xx/network errors. Zero unless a background worker opts in,
+    /// so user-facing commands never wait on backoff.
+    pub max_retries: u32,
 }
 
 impl ApiContext {
@@ -109,6 +113,7 @@ impl ApiContext {
             auth_token: try_load_auth_token(),
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            max_retries: 0,
         }
     }
 
@@ -122,6 +127,7 @@ impl ApiContext {
             auth_token: None,
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            max_retries: 0,
         }
     }
 
@@ -135,6 +141,7 @@ impl ApiContext {
             auth_token: Some(auth_token),
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            max_retries: 0,
         }
     }
 
@@ -144,6 +151,12 @@ impl ApiContext {
         self
     }
 
+    /// Retry failed requests up to `max_retries` times with backoff
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     /// Build the full URL for an endpoint
     fn build_url(&self, endpoint: &str) -> Result<String, GitAiError> {
         let base = Url::parse(&self.base_url)
@@ -159,62 +172,112 @@ impl ApiContext {
         &self,
         endpoint: &str,
         body: &T,
+    ) -> Result<minreq::Response, GitAiError> {
+        self.post_json_with_headers(endpoint, body, &[])
+    }
+
+    /// Make a POST request with JSON body and additional headers
+    pub fn post_json_with_headers<T: serde::Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+        headers: &[(&str, &str)],
     ) -> Result<minreq::Response, GitAiError> {
         let url = self.build_url(endpoint)?;
         let body_json = serde_json::to_string(body).map_err(GitAiError::JsonError)?;
 
-        let mut request = Self::http_post(&url)
-            .with_header("Content-Type", "application/json")
-            .with_body(body_json);
-
-        // Add authentication header if token is present
-        if let Some(token) = &self.auth_token {
-            request = request.with_header("Authorization", format!("Bearer {}", token));
-        }
-
-        // Add API key header if present
-        if let Some(api_key) = &self.api_key {
-            request = request.with_header("X-API-Key", api_key);
-        }
-
-        // Set timeout if specified
-        if let Some(timeout) = self.timeout_secs {
-            request = request.with_timeout(timeout);
-        }
+        self.send(&url, || {
+            let mut request = Self::http_post(&url)
+                .with_header("Content-Type", "application/json")
+                .with_body(body_json.clone());
+            for (name, value) in headers {
+                request = request.with_header(*name, *value);
+            }
+            request
+        })
+    }
 
-        let response = request
-            .send()
-            .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
+    /// Make a POST request with a raw binary body
+    pub fn post_bytes_with_headers(
+        &self,
+        endpoint: &str,
+        body: &[u8],
+        headers: &[(&str, &str)],
+    ) -> Result<minreq::Response, GitAiError> {
+        let url = self.build_url(endpoint)?;
 
-        Ok(response)
+        self.send(&url, || {
+            let mut request = Self::http_post(&url)
+                .with_header("Content-Type", "application/octet-stream")
+                .with_body(body.to_vec());
+            for (name, value) in headers {
+                request = request.with_header(*name, *value);
+            }
+            request
+        })
     }
 
-    /// Make a GET request
+    /// Make a GET request. Identical GETs already in flight share one response.
     pub fn get(&self, endpoint: &str) -> Result<minreq::Response, GitAiError> {
         let url = self.build_url(endpoint)?;
+        let key = format!("{}\n{:?}\n{:?}", url, self.auth_token, self.api_key);
+        rate_limit::coalesce(&key, || self.send(&url, || Self::http_get(&url)))
+    }
 
-        let mut request = Self::http_get(&url);
-
-        // Add authentication header if token is present
-        if let Some(token) = &self.auth_token {
-            request = request.with_header("Authorization", format!("Bearer {}", token));
-        }
-
-        // Add API key header if present
-        if let Some(api_key) = &self.api_key {
-            request = request.with_header("X-API-Key", api_key);
-        }
-
-        // Set timeout if specified
-        if let Some(timeout) = self.timeout_secs {
-            request = request.with_timeout(timeout);
+    /// Send a request with auth headers, rate limiting, the circuit breaker and (if
+    /// enabled with `with_retries`) jittered retries on 429/5xx and network errors
+    fn send<F>(&self, url: &str, build_request: F) -> Result<minreq::Response, GitAiError>
+    where
+        F: Fn() -> minreq::Request,
+    {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let mut attempt = 0;
+        loop {
+            rate_limit::check_circuit(&host)?;
+            rate_limit::throttle();
+
+            let mut request = build_request();
+
+            // Add authentication header if token is present
+            if let Some(token) = &self.auth_token {
+                request = request.with_header("Authorization", format!("Bearer {}", token));
+            }
+
+            // Add API key header if present
+            if let Some(api_key) = &self.api_key {
+                request = request.with_header("X-API-Key", api_key);
+            }
+
+            // Set timeout if specified
+            if let Some(timeout) = self.timeout_secs {
+                request = request.with_timeout(timeout);
+            }
+
+            let result = request
+                .send()
+                .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)));
+            let (failed, retry_after) = match &result {
+                Ok(response) => (
+                    rate_limit::is_retryable_status(response.status_code),
+                    response
+                        .headers
+                        .get("retry-after")
+                        .and_then(|v| rate_limit::parse_retry_after(v)),
+                ),
+                Err(_) => (true, None),
+            };
+            rate_limit::record_outcome(&host, !failed);
+
+            if !failed || attempt >= self.max_retries {
+                return result;
+            }
+            std::thread::sleep(rate_limit::backoff_delay(attempt, retry_after));
+            attempt += 1;
         }
-
-        let response = request
-            .send()
-            .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
-
-        Ok(response)
     }
 }
 
@@ -279,6 +342,13 @@ mod tests {
         assert_eq!(ctx.timeout_secs, Some(60));
     }
 
+    #[test]
+    fn test_api_context_retries_are_opt_in() {
+        let ctx = ApiContext::without_auth(Some("https://example.com".to_string()));
+        assert_eq!(ctx.max_retries, 0);
+        assert_eq!(ctx.with_retries(3).max_retries, 3);
+    }
+
     #[test]
     fn test_api_context_default_timeout() {
         let ctx = ApiContext::without_auth(Some("https://example.com".to_string()));
@@ -4,8 +4,10 @@ use crate::api::client::ApiClient;
 use crate::api::types::ApiErrorResponse;
 use crate::error::GitAiError;
 use crate::metrics::MetricsBatch;
+use crate::metrics::db::MetricsDatabase;
 use crate::observability::log_error;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Retry delay in seconds: single retry after 60s
 const RETRY_DELAYS_SECS: [u64; 1] = [60];
@@ -38,6 +40,21 @@ impl MetricsUploadResponse {
     }
 }
 
+/// Drop events that are duplicated within the batch or were already uploaded.
+fn dedup_batch(batch: &MetricsBatch, already_uploaded: &HashSet<String>) -> MetricsBatch {
+    let mut seen = HashSet::new();
+    let events = batch
+        .events
+        .iter()
+        .filter(|event| {
+            let key = event.dedup_key();
+            !already_uploaded.contains(&key) && seen.insert(key)
+        })
+        .cloned()
+        .collect();
+    MetricsBatch::new(events)
+}
+
 /// Upload metrics batch with retry logic.
 ///
 /// Returns Ok(()) on success (200 response, even with partial errors).
@@ -45,11 +62,27 @@ impl MetricsUploadResponse {
 ///
 /// Partial errors (200 + errors array) are logged to Sentry but not retried,
 /// since validation errors won't succeed on retry.
+///
+/// Uploads are idempotent: events already accepted by the server (per the
+/// metrics DB ledger) are skipped, and every attempt for the same events sends
+/// the same `Idempotency-Key` header.
 pub fn upload_metrics_with_retry(
     client: &ApiClient,
     batch: &MetricsBatch,
     operation: &str,
 ) -> Result<(), GitAiError> {
+    let db = MetricsDatabase::global().ok();
+    let keys: Vec<String> = batch.events.iter().map(|e| e.dedup_key()).collect();
+    let already_uploaded = db
+        .and_then(|db| db.lock().ok())
+        .and_then(|db_lock| db_lock.uploaded_keys(&keys).ok())
+        .unwrap_or_default();
+
+    let batch = dedup_batch(batch, &already_uploaded);
+    if batch.events.is_empty() {
+        return Ok(());
+    }
+
     // First attempt (no delay), then retry with delays
     for (attempt, delay_secs) in std::iter::once(&0u64)
         .chain(RETRY_DELAYS_SECS.iter())
@@ -65,8 +98,20 @@ pub fn upload_metrics_with_retry(
             std::thread::sleep(std::time::Duration::from_secs(*delay_secs));
         }
 
-        match client.upload_metrics(batch) {
+        match client.upload_metrics(&batch) {
             Ok(response) => {
+                if let Some(db) = db
+                    && let Ok(mut db_lock) = db.lock()
+                {
+                    let uploaded: Vec<String> =
+                        batch.events.iter().map(|e| e.dedup_key()).collect();
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    let _ = db_lock.mark_uploaded(&uploaded, now);
+                }
+
                 // 200 response - log any validation errors to Sentry
                 for error in &response.errors {
                     log_error(
@@ -112,7 +157,12 @@ impl ApiClient {
         &self,
         batch: &MetricsBatch,
     ) -> Result<MetricsUploadResponse, GitAiError> {
-        let response = self.context().post_json("/worker/metrics/upload", batch)?;
+        let idempotency_key = batch.idempotency_key();
+        let response = self.context().post_json_with_headers(
+            "/worker/metrics/upload",
+            batch,
+            &[("Idempotency-Key", idempotency_key.as_str())],
+        )?;
         let status_code = response.status_code;
 
         let body = response
@@ -203,4 +253,21 @@ mod tests {
         let successful = response.successful_indices(2);
         assert!(successful.is_empty());
     }
+
+    #[test]
+    fn test_dedup_batch_removes_duplicates_and_uploaded() {
+        use crate::metrics::types::SparseArray;
+        use crate::metrics::{AgentUsageValues, MetricEvent};
+
+        let a = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+        let b = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+        let c = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+        let batch = MetricsBatch::new(vec![a.clone(), b.clone(), a.clone(), c.clone()]);
+
+        let already_uploaded = HashSet::from([b.dedup_key()]);
+        let deduped = dedup_batch(&batch, &already_uploaded);
+
+        let keys: Vec<String> = deduped.events.iter().map(|e| e.dedup_key()).collect();
+        assert_eq!(keys, vec![a.dedup_key(), c.dedup_key()]);
+    }
 }
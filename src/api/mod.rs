@@ -2,6 +2,9 @@ pub mod bundle;
 pub mod cas;
 pub mod client;
 pub mod metrics;
+pub mod object_storage;
+pub mod rate_limit;
+pub mod sink;
 pub mod types;
 
 pub use client::{ApiClient, ApiContext};
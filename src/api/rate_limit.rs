@@ -0,0 +1,331 @@
+//! Client-side protection for the git-ai API.
+//!
+//! - A per-process token bucket caps requests per second (`api_max_rps`).
+//! - 429 and 5xx responses are retried with jittered exponential backoff, but only
+//!   by background workers that opt in with [`ApiContext::with_retries`]. Commands
+//!   the user is waiting on fail fast instead of blocking on retries.
+//! - Consecutive failures open a circuit breaker. Its state lives in the metrics DB
+//!   so every process on the machine backs off together after an outage.
+//! - Identical GETs in flight at the same time are coalesced into one request.
+//!
+//! [`ApiContext::with_retries`]: crate::api::ApiContext::with_retries
+
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::metrics::db::MetricsDatabase;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Retries allowed for background workers (flush-logs, flush-cas, ...)
+pub const BACKGROUND_MAX_RETRIES: u32 = 3;
+/// Consecutive failures before the circuit opens
+pub const FAILURE_THRESHOLD: u32 = 5;
+/// How long an open circuit rejects requests before letting one through again
+pub const OPEN_DURATION_SECS: u64 = 5 * 60;
+
+const BASE_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Circuit breaker state for one API host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CircuitState {
+    pub consecutive_failures: u32,
+    /// Requests are rejected until this time (unix seconds)
+    pub open_until_ts: u64,
+}
+
+impl CircuitState {
+    pub fn is_open(&self, now_ts: u64) -> bool {
+        now_ts < self.open_until_ts
+    }
+
+    pub fn record_success(&mut self) {
+        *self = CircuitState::default();
+    }
+
+    /// Count a failure, opening the circuit once the threshold is reached. After the
+    /// open period a single trial request goes through; if it fails too the
+    /// circuit reopens straight away.
+    pub fn record_failure(&mut self, now_ts: u64) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures >= FAILURE_THRESHOLD {
+            self.open_until_ts = now_ts + OPEN_DURATION_SECS;
+        }
+    }
+}
+
+/// Responses worth retrying: rate limited or server-side failures
+pub fn is_retryable_status(status_code: i32) -> bool {
+    status_code == 429 || (500..600).contains(&status_code)
+}
+
+/// `Retry-After` in seconds (HTTP dates aren't used by the API)
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// Delay before retry number `attempt` (0-based). Honours `Retry-After` when the
+/// server sends one; otherwise exponential backoff with jitter so a fleet of
+/// clients doesn't retry in lockstep.
+pub fn backoff_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    let max = Duration::from_millis(MAX_BACKOFF_MS);
+    if let Some(retry_after) = retry_after {
+        return retry_after.min(max);
+    }
+    let exp_ms = BASE_BACKOFF_MS
+        .saturating_mul(1u64 << attempt.min(16))
+        .min(MAX_BACKOFF_MS);
+    // Uniform in [exp/2, exp]
+    let jitter = (uuid::Uuid::new_v4().as_u128() % 1000) as u64;
+    Duration::from_millis(exp_ms / 2 + exp_ms / 2 * jitter / 1000)
+}
+
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        // Allow short bursts of up to one second's worth of requests
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last: Instant::now(),
+        }
+    }
+
+    /// Take a token, returning how long the caller must wait for it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+static LIMITER: Lazy<Mutex<TokenBucket>> =
+    Lazy::new(|| Mutex::new(TokenBucket::new(Config::get().api_max_rps())));
+
+/// Block until the rate limiter allows another request
+pub fn throttle() {
+    let wait = match LIMITER.lock() {
+        Ok(mut bucket) => bucket.reserve(Instant::now()),
+        Err(_) => Duration::ZERO,
+    };
+    if !wait.is_zero() {
+        std::thread::sleep(wait);
+    }
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Fail fast while the circuit for `host` is open. DB errors never block requests.
+pub fn check_circuit(host: &str) -> Result<(), GitAiError> {
+    let Some(state) = MetricsDatabase::global()
+        .ok()
+        .and_then(|db| db.lock().ok()?.api_circuit_state(host).ok())
+    else {
+        return Ok(());
+    };
+    let now = now_ts();
+    if state.is_open(now) {
+        return Err(GitAiError::Generic(format!(
+            "API requests to {} paused for {}s after repeated failures",
+            host,
+            state.open_until_ts - now
+        )));
+    }
+    Ok(())
+}
+
+/// Update the circuit for `host` after a request
+pub fn record_outcome(host: &str, success: bool) {
+    let Ok(db) = MetricsDatabase::global() else {
+        return;
+    };
+    let Ok(mut db) = db.lock() else {
+        return;
+    };
+    let Ok(mut state) = db.api_circuit_state(host) else {
+        return;
+    };
+    // Skip the write in the common all-good case
+    if success && state == CircuitState::default() {
+        return;
+    }
+    if success {
+        state.record_success();
+    } else {
+        state.record_failure(now_ts());
+    }
+    let _ = db.set_api_circuit_state(host, &state);
+}
+
+type SharedResult = Result<minreq::Response, String>;
+
+#[derive(Default)]
+struct InFlight {
+    result: Mutex<Option<SharedResult>>,
+    done: Condvar,
+}
+
+static IN_FLIGHT: Lazy<Mutex<HashMap<String, Arc<InFlight>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Run `request` unless an identical request (same `key`) is already in flight,
+/// in which case wait for and share its response.
+pub fn coalesce<F>(key: &str, request: F) -> Result<minreq::Response, GitAiError>
+where
+    F: FnOnce() -> Result<minreq::Response, GitAiError>,
+{
+    let (flight, leader) = {
+        let Ok(mut in_flight) = IN_FLIGHT.lock() else {
+            return request();
+        };
+        match in_flight.get(key) {
+            Some(flight) => (flight.clone(), false),
+            None => {
+                let flight = Arc::new(InFlight::default());
+                in_flight.insert(key.to_string(), flight.clone());
+                (flight, true)
+            }
+        }
+    };
+
+    if leader {
+        let result = request();
+        let shared = match &result {
+            Ok(response) => Ok(response.clone()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Ok(mut slot) = flight.result.lock() {
+            *slot = Some(shared);
+        }
+        flight.done.notify_all();
+        if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+            in_flight.remove(key);
+        }
+        return result;
+    }
+
+    let Ok(mut slot) = flight.result.lock() else {
+        return Err(GitAiError::Generic("Coalesced request failed".to_string()));
+    };
+    while slot.is_none() {
+        slot = match flight.done.wait(slot) {
+            Ok(slot) => slot,
+            Err(_) => return Err(GitAiError::Generic("Coalesced request failed".to_string())),
+        };
+    }
+    match slot.as_ref() {
+        Some(Ok(response)) => Ok(response.clone()),
+        Some(Err(e)) => Err(GitAiError::Generic(e.clone())),
+        None => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_circuit_opens_after_threshold_and_resets_on_success() {
+        let mut state = CircuitState::default();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            state.record_failure(1000);
+        }
+        assert!(!state.is_open(1000));
+
+        state.record_failure(1000);
+        assert!(state.is_open(1000));
+        assert!(state.is_open(1000 + OPEN_DURATION_SECS - 1));
+        assert!(!state.is_open(1000 + OPEN_DURATION_SECS));
+
+        // A failed trial request reopens immediately
+        state.record_failure(2000);
+        assert!(state.is_open(2000));
+
+        state.record_success();
+        assert_eq!(state, CircuitState::default());
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable_status(429));
+        assert!(is_retryable_status(503));
+        assert!(!is_retryable_status(200));
+        assert!(!is_retryable_status(401));
+    }
+
+    #[test]
+    fn test_backoff_delay_is_jittered_and_capped() {
+        for attempt in 0..4 {
+            let exp = BASE_BACKOFF_MS * (1 << attempt);
+            let delay = backoff_delay(attempt, None).as_millis() as u64;
+            assert!(
+                delay >= exp / 2 && delay <= exp,
+                "attempt {attempt}: {delay}"
+            );
+        }
+        assert!(backoff_delay(30, None) <= Duration::from_millis(MAX_BACKOFF_MS));
+        assert_eq!(
+            backoff_delay(0, parse_retry_after("7")),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            backoff_delay(0, Some(Duration::from_secs(3600))),
+            Duration::from_millis(MAX_BACKOFF_MS)
+        );
+    }
+
+    #[test]
+    fn test_token_bucket_paces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        // Tokens refill over time
+        let later = start + Duration::from_secs(2);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_coalesce_shares_one_request() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let calls = calls.clone();
+                std::thread::spawn(move || {
+                    coalesce("test-coalesce", || {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(200));
+                        Err(GitAiError::Generic("offline".to_string()))
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap().is_err());
+        }
+        assert!(calls.load(Ordering::SeqCst) < 4);
+    }
+}
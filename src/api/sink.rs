@@ -0,0 +1,269 @@
+//! Destinations that keep a copy of what git-ai sends to the hosted API.
+//!
+//! With `dual_write.destination` set, metrics batches and bundles are also
+//! written to a [`Sink`] so teams piloting the hosted product keep their own
+//! copy of all telemetry. Objects are stored under stable keys:
+//!
+//! - `metrics/<YYYY-MM-DD>/<batch idempotency key>.json` - a metrics batch
+//! - `bundles/<bundle id>.json` - a bundle with its title, URL and data
+//!
+//! A destination is an absolute local directory, an http(s) URL that accepts
+//! `PUT <url>/<key>` (a presigned prefix, an internal collector), or an
+//! `s3://bucket/prefix` or `gs://bucket/prefix` URL (see [`object_storage`]).
+//! Writing a copy never blocks or fails the upload itself; errors are logged.
+//!
+//! [`object_storage`]: crate::api::object_storage
+
+use crate::api::object_storage::{is_object_url, object_sink};
+use crate::api::types::{CreateBundleRequest, CreateBundleResponse};
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::metrics::MetricsBatch;
+use crate::observability::log_error;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Timeout for PUT requests to http(s) sinks
+const HTTP_SINK_TIMEOUT_SECS: u64 = 30;
+
+/// Somewhere copies of uploads are stored
+pub trait Sink {
+    /// Human-readable location, for logs
+    fn describe(&self) -> String;
+
+    /// Store `body` under `key`, a relative `/`-separated path, replacing any
+    /// object already there
+    fn put(&self, key: &str, body: &[u8]) -> Result<(), GitAiError>;
+}
+
+/// Writes objects as files below a local directory
+pub struct DirectorySink {
+    root: PathBuf,
+}
+
+impl DirectorySink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Sink for DirectorySink {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn put(&self, key: &str, body: &[u8]) -> Result<(), GitAiError> {
+        let path = key
+            .split('/')
+            .fold(self.root.clone(), |path, part| path.join(part));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temp file first so readers never see a partial object
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, body)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// PUTs objects to `<base_url>/<key>`
+pub struct HttpSink {
+    base_url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl HttpSink {
+    pub fn new(base_url: &str, headers: BTreeMap<String, String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            headers,
+        }
+    }
+}
+
+impl Sink for HttpSink {
+    fn describe(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn put(&self, key: &str, body: &[u8]) -> Result<(), GitAiError> {
+        http_put(
+            &format!("{}/{}", self.base_url, key),
+            key,
+            &self.headers,
+            body,
+        )
+    }
+}
+
+/// PUT `body` to `url`, typed by the extension of `key`. Non-2xx responses are errors.
+pub(crate) fn http_put(
+    url: &str,
+    key: &str,
+    headers: &BTreeMap<String, String>,
+    body: &[u8],
+) -> Result<(), GitAiError> {
+    let mut request = minreq::put(url)
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_header("Content-Type", content_type_for_key(key))
+        .with_timeout(HTTP_SINK_TIMEOUT_SECS)
+        .with_body(body.to_vec());
+    for (name, value) in headers {
+        request = request.with_header(name, value);
+    }
+    let response = request
+        .send()
+        .map_err(|e| GitAiError::Generic(format!("PUT {} failed: {}", url, e)))?;
+    if !(200..300).contains(&response.status_code) {
+        return Err(GitAiError::Generic(format!(
+            "PUT {} failed with status {}: {}",
+            url,
+            response.status_code,
+            response.as_str().unwrap_or_default().trim()
+        )));
+    }
+    Ok(())
+}
+
+fn content_type_for_key(key: &str) -> &'static str {
+    match key.rsplit('.').next() {
+        Some("json") => "application/json",
+        Some("html") => "text/html; charset=utf-8",
+        Some("zst") => "application/zstd",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Whether `destination` is usable as a sink: an absolute directory, an
+/// http(s) URL, or an `s3://` / `gs://` bucket URL
+pub fn is_valid_destination(destination: &str) -> bool {
+    destination.starts_with("http://")
+        || destination.starts_with("https://")
+        || is_object_url(destination)
+        || Path::new(destination).is_absolute()
+}
+
+/// The sink for a `dual_write.destination` value
+pub fn sink_for_destination(destination: &str, headers: BTreeMap<String, String>) -> Box<dyn Sink> {
+    if let Some(sink) = object_sink(destination) {
+        sink
+    } else if destination.starts_with("http://") || destination.starts_with("https://") {
+        Box::new(HttpSink::new(destination, headers))
+    } else {
+        Box::new(DirectorySink::new(destination))
+    }
+}
+
+/// The configured dual-write sink, if any
+pub fn dual_write_sink() -> Option<Box<dyn Sink>> {
+    let config = Config::get();
+    let destination = config.dual_write_destination()?;
+    Some(sink_for_destination(
+        destination,
+        config.dual_write_headers().clone(),
+    ))
+}
+
+/// Write a copy of `body` to the dual-write sink, if one is configured. Failures
+/// are logged and otherwise ignored.
+pub fn dual_write(key: &str, body: &[u8]) {
+    let Some(sink) = dual_write_sink() else {
+        return;
+    };
+    if let Err(e) = sink.put(key, body) {
+        log_error(
+            &e,
+            Some(serde_json::json!({
+                "operation": "dual_write",
+                "destination": sink.describe(),
+                "key": key,
+            })),
+        );
+    }
+}
+
+/// Copy a metrics batch to the dual-write sink. The key is derived from the
+/// batch's idempotency key, so writing the same batch twice replaces it.
+pub fn dual_write_metrics(batch: &MetricsBatch) {
+    if Config::get().dual_write_destination().is_none() || batch.events.is_empty() {
+        return;
+    }
+    let key = format!(
+        "metrics/{}/{}.json",
+        Utc::now().format("%Y-%m-%d"),
+        batch.idempotency_key()
+    );
+    match serde_json::to_vec(batch) {
+        Ok(body) => dual_write(&key, &body),
+        Err(e) => log_error(&GitAiError::JsonError(e), None),
+    }
+}
+
+/// Copy a bundle the API accepted to the dual-write sink
+pub fn dual_write_bundle(request: &CreateBundleRequest, response: &CreateBundleResponse) {
+    if Config::get().dual_write_destination().is_none() {
+        return;
+    }
+    let bundle = serde_json::json!({
+        "id": response.id,
+        "url": response.url,
+        "title": request.title,
+        "data": request.data,
+    });
+    match serde_json::to_vec(&bundle) {
+        Ok(body) => dual_write(&format!("bundles/{}.json", response.id), &body),
+        Err(e) => log_error(&GitAiError::JsonError(e), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_directory_sink_writes_nested_keys() {
+        let dir = TempDir::new().unwrap();
+        let sink = DirectorySink::new(dir.path());
+
+        sink.put("metrics/2026-10-17/abc.json", b"{\"v\":1}")
+            .unwrap();
+        sink.put("metrics/2026-10-17/abc.json", b"{\"v\":2}")
+            .unwrap();
+
+        let path = dir
+            .path()
+            .join("metrics")
+            .join("2026-10-17")
+            .join("abc.json");
+        assert_eq!(fs::read_to_string(path).unwrap(), "{\"v\":2}");
+        let entries: Vec<_> = fs::read_dir(dir.path().join("metrics").join("2026-10-17"))
+            .unwrap()
+            .collect();
+        assert_eq!(entries.len(), 1, "no temp files are left behind");
+    }
+
+    #[test]
+    fn test_destination_selects_sink() {
+        let http = sink_for_destination("https://bucket.example.com/git-ai/", BTreeMap::new());
+        assert_eq!(http.describe(), "https://bucket.example.com/git-ai");
+
+        let local = sink_for_destination("/var/lib/git-ai", BTreeMap::new());
+        assert_eq!(local.describe(), "/var/lib/git-ai");
+
+        let s3 = sink_for_destination("s3://artifacts/git-ai/", BTreeMap::new());
+        assert_eq!(s3.describe(), "s3://artifacts/git-ai");
+        let gcs = sink_for_destination("gs://artifacts", BTreeMap::new());
+        assert_eq!(gcs.describe(), "gs://artifacts");
+
+        assert!(is_valid_destination("gs://artifacts/prefix"));
+        assert!(!is_valid_destination("gs://"));
+        assert!(!is_valid_destination("relative/dir"));
+    }
+}
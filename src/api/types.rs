@@ -64,6 +64,15 @@ pub struct CreateBundleRequest {
     // TODO PR Metadata if linked to PR
 }
 
+/// Request body for creating a bundle from data already uploaded to CAS in chunks.
+/// The server concatenates the chunks in order to get the bundle's `data` JSON.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CreateChunkedBundleRequest {
+    pub title: String,
+    /// CAS hashes of the chunks, in order
+    pub chunks: Vec<String>,
+}
+
 /// Success response from bundle creation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CreateBundleResponse {
@@ -112,6 +121,40 @@ pub struct CasUploadResponse {
     pub failure_count: usize,
 }
 
+/// One piece of a chunked CAS upload, addressed by the SHA-256 of its bytes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CasChunk {
+    pub index: usize,
+    pub hash: String,
+    pub size: usize,
+}
+
+/// Describes a large CAS object before its chunks are sent. The server keys
+/// the upload by `hash`, so posting the same manifest again resumes it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CasUploadManifest {
+    pub hash: String,
+    pub size: usize,
+    pub chunk_size: usize,
+    pub chunks: Vec<CasChunk>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Response to a manifest: the chunks the server doesn't have yet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CasUploadSession {
+    pub upload_id: String,
+    pub missing_chunks: Vec<usize>,
+}
+
+/// What the server stored for a chunk or for the assembled object
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CasStoredObject {
+    pub hash: String,
+    pub size: usize,
+}
+
 /// Wrapper for messages stored in CAS
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CasMessagesObject {
@@ -85,6 +85,138 @@ impl CredentialBackend for KeyringBackend {
     }
 }
 
+/// Secret Service (GNOME Keyring, KWallet) storage through libsecret's `secret-tool`
+#[cfg(not(test))]
+pub struct SecretToolBackend {
+    service_name: String,
+    username: String,
+}
+
+#[cfg(not(test))]
+impl SecretToolBackend {
+    pub fn new(service_name: &str, username: &str) -> Self {
+        Self {
+            service_name: service_name.to_string(),
+            username: username.to_string(),
+        }
+    }
+
+    /// Test if a Secret Service is reachable by attempting a store/clear cycle
+    pub fn is_available(service_name: &str) -> bool {
+        let probe = Self::new(service_name, "test-availability");
+        if probe.store("test").is_err() {
+            return false;
+        }
+        let _ = probe.clear();
+        true
+    }
+
+    fn attributes(&self) -> [&str; 4] {
+        ["service", &self.service_name, "username", &self.username]
+    }
+
+    fn run(&self, args: &[&str], stdin: Option<&str>) -> Result<std::process::Output, String> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new("secret-tool")
+            .args(args)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+        if let (Some(value), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(value.as_bytes())
+                .map_err(|e| format!("Failed to write to secret-tool: {}", e))?;
+        }
+        child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run secret-tool: {}", e))
+    }
+}
+
+#[cfg(not(test))]
+impl CredentialBackend for SecretToolBackend {
+    fn store(&self, value: &str) -> Result<(), String> {
+        let label = format!("--label={} credentials", self.service_name);
+        let mut args = vec!["store", label.as_str()];
+        args.extend(self.attributes());
+        let output = self.run(&args, Some(value))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to store in Secret Service: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn load(&self) -> Result<Option<String>, String> {
+        let mut args = vec!["lookup"];
+        args.extend(self.attributes());
+        let output = self.run(&args, None)?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match (output.status.success(), stderr.trim()) {
+            (true, _) => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+            // secret-tool exits non-zero without a message when nothing matches
+            (false, "") => Ok(None),
+            (false, stderr) => Err(format!("Failed to read from Secret Service: {}", stderr)),
+        }
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        let mut args = vec!["clear"];
+        args.extend(self.attributes());
+        let output = self.run(&args, None)?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || stderr.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Failed to clear Secret Service: {}", stderr.trim()))
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "secret-service"
+    }
+}
+
+/// Stands in when the configured store can't be used, so every operation
+/// reports why instead of silently writing credentials somewhere else
+pub struct UnavailableBackend {
+    reason: String,
+}
+
+impl UnavailableBackend {
+    pub fn new(reason: String) -> Self {
+        Self { reason }
+    }
+}
+
+impl CredentialBackend for UnavailableBackend {
+    fn store(&self, _value: &str) -> Result<(), String> {
+        Err(self.reason.clone())
+    }
+
+    fn load(&self) -> Result<Option<String>, String> {
+        Err(self.reason.clone())
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        Err(self.reason.clone())
+    }
+
+    fn name(&self) -> &'static str {
+        "unavailable"
+    }
+}
+
 /// File-based credential storage as fallback
 pub struct FileBackend {
     path: PathBuf,
@@ -1,24 +1,131 @@
 #[cfg(all(not(test), feature = "keyring"))]
 use crate::auth::credential_backend::KeyringBackend;
-use crate::auth::credential_backend::{CredentialBackend, FileBackend};
-use crate::auth::types::StoredCredentials;
 #[cfg(not(test))]
-use crate::config::Config;
+use crate::auth::credential_backend::SecretToolBackend;
+use crate::auth::credential_backend::{CredentialBackend, FileBackend, UnavailableBackend};
+use crate::auth::types::StoredCredentials;
+use crate::config::{Config, CredentialBackendKind};
 use std::path::PathBuf;
 
-#[cfg(all(not(test), feature = "keyring"))]
+#[cfg(not(test))]
 const SERVICE_NAME: &str = "git-ai";
-#[cfg(all(not(test), feature = "keyring"))]
+#[cfg(not(test))]
 const USERNAME: &str = "oauth-tokens";
 
 /// Cross-platform credential storage
-/// Uses system keyring when available, falls back to file storage
+/// Uses the platform's native store (Keychain, Secret Service, Credential Manager)
+/// or a plaintext file, as configured by `credential_backend`
 pub struct CredentialStore {
     backend: Box<dyn CredentialBackend>,
 }
 
+/// The store `credential_backend` asks for; when unset, the native store if the
+/// `auth_keyring` feature flag is on and the plaintext file otherwise
+pub fn configured_backend_kind(config: &Config) -> CredentialBackendKind {
+    config.credential_backend().unwrap_or_else(|| {
+        if config.get_feature_flags().auth_keyring {
+            CredentialBackendKind::Auto
+        } else {
+            CredentialBackendKind::File
+        }
+    })
+}
+
+/// The native store `auto` resolves to on this platform
+fn platform_backend_kind() -> CredentialBackendKind {
+    if cfg!(target_os = "macos") {
+        CredentialBackendKind::Keychain
+    } else if cfg!(windows) {
+        CredentialBackendKind::WindowsCredentialManager
+    } else {
+        CredentialBackendKind::SecretService
+    }
+}
+
+/// Pick the backend for `kind`. When the native store can't be used, credentials
+/// only go to the plaintext file if `file_fallback` opts into it; otherwise every
+/// operation fails with the reason.
+fn select_backend(
+    kind: CredentialBackendKind,
+    file_fallback: bool,
+    file_path: PathBuf,
+    native_backend: impl FnOnce(CredentialBackendKind) -> Result<Box<dyn CredentialBackend>, String>,
+) -> Box<dyn CredentialBackend> {
+    let kind = match kind {
+        CredentialBackendKind::File => return Box::new(FileBackend::new(file_path)),
+        CredentialBackendKind::Auto => platform_backend_kind(),
+        kind => kind,
+    };
+
+    match native_backend(kind) {
+        Ok(backend) => backend,
+        Err(reason) if file_fallback => {
+            use std::io::IsTerminal;
+            if std::io::stderr().is_terminal() {
+                eprintln!(
+                    "Note: {}; credentials will be stored in a plaintext file",
+                    reason
+                );
+            }
+            Box::new(FileBackend::new(file_path))
+        }
+        Err(reason) => Box::new(UnavailableBackend::new(format!(
+            "{}. Set credential_file_fallback to true to store credentials in a plaintext file instead",
+            reason
+        ))),
+    }
+}
+
+/// Open the native store `kind`, checking that it actually works here
+#[cfg(not(test))]
+fn native_backend(kind: CredentialBackendKind) -> Result<Box<dyn CredentialBackend>, String> {
+    match kind {
+        CredentialBackendKind::SecretService => {
+            if SecretToolBackend::is_available(SERVICE_NAME) {
+                Ok(Box::new(SecretToolBackend::new(SERVICE_NAME, USERNAME)))
+            } else {
+                Err("Secret Service is not available (needs libsecret's secret-tool and a running keyring daemon)".to_string())
+            }
+        }
+        CredentialBackendKind::Keychain => {
+            keyring_backend("macOS Keychain", cfg!(target_os = "macos"))
+        }
+        CredentialBackendKind::WindowsCredentialManager => {
+            keyring_backend("Windows Credential Manager", cfg!(windows))
+        }
+        CredentialBackendKind::Auto | CredentialBackendKind::File => {
+            unreachable!("resolved by select_backend")
+        }
+    }
+}
+
+#[cfg(all(not(test), feature = "keyring"))]
+fn keyring_backend(
+    label: &str,
+    on_this_platform: bool,
+) -> Result<Box<dyn CredentialBackend>, String> {
+    if !on_this_platform {
+        return Err(format!("{} is not available on this platform", label));
+    }
+    if !KeyringBackend::is_available(SERVICE_NAME) {
+        return Err(format!("{} is not available", label));
+    }
+    Ok(Box::new(KeyringBackend::new(SERVICE_NAME, USERNAME)))
+}
+
+#[cfg(all(not(test), not(feature = "keyring")))]
+fn keyring_backend(
+    label: &str,
+    on_this_platform: bool,
+) -> Result<Box<dyn CredentialBackend>, String> {
+    if !on_this_platform {
+        return Err(format!("{} is not available on this platform", label));
+    }
+    Err(format!("this binary was built without {} support", label))
+}
+
 impl CredentialStore {
-    /// Create a new credential store, testing keyring availability
+    /// Create a new credential store for the configured backend
     pub fn new() -> Self {
         // In test builds, always use file-based storage to avoid keyring blocking issues
         #[cfg(test)]
@@ -29,44 +136,16 @@ impl CredentialStore {
             }
         }
 
-        // Production build with keyring feature enabled
-        #[cfg(all(not(test), feature = "keyring"))]
-        {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
-
-            if use_keyring && KeyringBackend::is_available(SERVICE_NAME) {
-                Self {
-                    backend: Box::new(KeyringBackend::new(SERVICE_NAME, USERNAME)),
-                }
-            } else {
-                if use_keyring {
-                    // User wanted keyring but it's not available
-                    eprintln!(
-                        "Note: System keyring not available, credentials will be stored in file"
-                    );
-                }
-                Self {
-                    backend: Box::new(FileBackend::new(Self::default_production_path())),
-                }
-            }
-        }
-
-        // Production build without keyring feature
-        #[cfg(all(not(test), not(feature = "keyring")))]
+        #[cfg(not(test))]
         {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
-
-            if use_keyring {
-                // User wanted keyring but binary was built without keyring support
-                use std::io::IsTerminal;
-                if std::io::stderr().is_terminal() {
-                    eprintln!(
-                        "Note: auth_keyring is enabled but this binary was built without keyring support. Using file-based storage."
-                    );
-                }
-            }
+            let config = Config::get();
             Self {
-                backend: Box::new(FileBackend::new(Self::default_production_path())),
+                backend: select_backend(
+                    configured_backend_kind(config),
+                    config.credential_file_fallback(),
+                    Self::default_production_path(),
+                    native_backend,
+                ),
             }
         }
     }
@@ -133,7 +212,6 @@ impl CredentialStore {
     }
 
     /// Get the backend name (for logging/debugging)
-    #[allow(dead_code)]
     pub fn backend_name(&self) -> &'static str {
         self.backend.name()
     }
@@ -489,4 +567,68 @@ mod tests {
         let file_store = CredentialStore::new();
         assert_eq!(file_store.backend_name(), "file");
     }
+
+    // ============= Backend Selection Tests =============
+
+    fn native_mock(kind: CredentialBackendKind) -> Result<Box<dyn CredentialBackend>, String> {
+        assert_ne!(kind, CredentialBackendKind::Auto);
+        Ok(Box::new(MockBackend::new()))
+    }
+
+    fn native_missing(_kind: CredentialBackendKind) -> Result<Box<dyn CredentialBackend>, String> {
+        Err("Secret Service is not available".to_string())
+    }
+
+    #[test]
+    fn test_select_backend_uses_native_store() {
+        let backend = select_backend(
+            CredentialBackendKind::Auto,
+            false,
+            test_fallback_path(),
+            native_mock,
+        );
+        assert_eq!(backend.name(), "mock");
+
+        let backend = select_backend(
+            CredentialBackendKind::SecretService,
+            true,
+            test_fallback_path(),
+            native_mock,
+        );
+        assert_eq!(backend.name(), "mock");
+    }
+
+    #[test]
+    fn test_select_backend_file_is_explicit() {
+        let backend = select_backend(
+            CredentialBackendKind::File,
+            false,
+            test_fallback_path(),
+            |_| panic!("file backend should not open the native store"),
+        );
+        assert_eq!(backend.name(), "file");
+    }
+
+    #[test]
+    fn test_select_backend_falls_back_to_file_only_when_opted_in() {
+        let backend = select_backend(
+            CredentialBackendKind::Auto,
+            true,
+            test_fallback_path(),
+            native_missing,
+        );
+        assert_eq!(backend.name(), "file");
+
+        let backend = select_backend(
+            CredentialBackendKind::Auto,
+            false,
+            test_fallback_path(),
+            native_missing,
+        );
+        assert_eq!(backend.name(), "unavailable");
+        let err = backend.store("secret").unwrap_err();
+        assert!(err.contains("Secret Service is not available"), "{}", err);
+        assert!(err.contains("credential_file_fallback"), "{}", err);
+        assert!(backend.load().is_err());
+    }
 }
@@ -0,0 +1,364 @@
+//! Persistent cache of authorship notes, keyed by note blob OID.
+//!
+//! Blame needs the authorship note of every commit that last touched a line.
+//! Reading them one `git notes show` at a time is what makes the first blame
+//! after pulling a large PR slow. Note blobs are content addressed, so a cached
+//! entry never goes stale: when a note is rewritten it gets a new OID and the
+//! old entry is simply no longer looked up.
+//!
+//! The cache is filled lazily by blame and, when `warm_blame_cache_on_fetch` is
+//! enabled, ahead of time by a background `warm-blame-cache` process spawned
+//! after `git fetch`/`git pull`.
+//!
+//! The same store backs the ownership and blame result caches, whose keys hash
+//! everything the result depends on. Reads refresh an entry, so pruning evicts
+//! the least recently used ones; `git-ai cache clear` empties them all.
+
+use crate::authorship::authorship_log_serialization::{AuthorshipLog, is_supported_schema_version};
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::refs::{authorship_notes_ref, note_blob_oids_for_commits};
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ENV_WARM_BLAME_CACHE_WORKER: &str = "GIT_AI_WARM_BLAME_CACHE_WORKER";
+
+/// Commits whose notes are pre-loaded by a single warm run
+const MAX_WARM_COMMITS: usize = 2000;
+/// Above this many changed files, warm the whole range instead of passing pathspecs
+const MAX_WARM_PATHSPECS: usize = 500;
+/// Oldest entries are evicted once the cache grows past this
+const MAX_CACHE_ENTRIES: usize = 20_000;
+
+pub struct AttributionCache {
+    dir: PathBuf,
+}
+
+impl AttributionCache {
+    pub fn for_repo(repo: &Repository) -> Self {
+        Self {
+            dir: repo.storage.attribution_cache.clone(),
+        }
+    }
+
+    /// File ownership summaries from `git-ai ownership`, keyed by a hash of the
+    /// commit, notes ref and path they were computed for
+    pub fn for_ownership(repo: &Repository) -> Self {
+        Self {
+            dir: repo.storage.ownership_cache.clone(),
+        }
+    }
+
+    /// Per-file blame results from `VirtualAttributions`, keyed by a hash of the
+    /// commit, file blob, notes ref and blame range they were computed for
+    pub fn for_blame(repo: &Repository) -> Self {
+        Self {
+            dir: repo.storage.blame_cache.clone(),
+        }
+    }
+
+    fn entry_path(&self, note_oid: &str) -> PathBuf {
+        if note_oid.len() <= 2 {
+            self.dir.join(note_oid)
+        } else {
+            self.dir.join(&note_oid[..2]).join(&note_oid[2..])
+        }
+    }
+
+    pub fn get(&self, note_oid: &str) -> Option<String> {
+        let path = self.entry_path(note_oid);
+        let content = fs::read_to_string(&path).ok()?;
+        // Mark the entry as recently used so pruning keeps it
+        let _ = fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        Some(content)
+    }
+
+    pub fn put(&self, note_oid: &str, content: &str) -> Result<(), GitAiError> {
+        let path = self.entry_path(note_oid);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so concurrent readers never see a partial entry
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Evict the least recently used entries beyond `max_entries`
+    pub fn prune(&self, max_entries: usize) -> Result<usize, GitAiError> {
+        let Ok(shards) = fs::read_dir(&self.dir) else {
+            return Ok(0);
+        };
+        let mut entries = Vec::new();
+        for shard in shards.flatten() {
+            let Ok(files) = fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let modified = file
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .unwrap_or(UNIX_EPOCH);
+                entries.push((modified, file.path()));
+            }
+        }
+        if entries.len() <= max_entries {
+            return Ok(0);
+        }
+        entries.sort();
+        let excess = entries.len() - max_entries;
+        for (_, path) in entries.iter().take(excess) {
+            let _ = fs::remove_file(path);
+        }
+        Ok(excess)
+    }
+
+    /// Remove every entry, returning how many there were
+    pub fn clear(&self) -> Result<usize, GitAiError> {
+        let Ok(shards) = fs::read_dir(&self.dir) else {
+            return Ok(0);
+        };
+        let mut removed = 0;
+        for shard in shards.flatten() {
+            let path = shard.path();
+            if !path.is_dir() {
+                continue;
+            }
+            removed += fs::read_dir(&path)?.flatten().count();
+            fs::remove_dir_all(&path)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// The authorship notes ref's current commit, or an empty string when there are
+/// no notes. Cache keys include it so rewriting any note invalidates results
+/// derived from notes.
+pub fn notes_ref_oid(repo: &Repository) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "for-each-ref".to_string(),
+        "--format=%(objectname)".to_string(),
+    ]);
+    args.push(authorship_notes_ref());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Load the authorship logs for `commit_shas`, reading note contents from the
+/// cache and adding whatever had to be read from git. Commits without a note,
+/// or with a note in an unsupported format, are left out of the result.
+pub fn load_authorship_logs(
+    repo: &Repository,
+    commit_shas: &[String],
+) -> Result<HashMap<String, AuthorshipLog>, GitAiError> {
+    let note_oids = note_blob_oids_for_commits(repo, commit_shas)?;
+    let cache = AttributionCache::for_repo(repo);
+
+    let mut contents: HashMap<String, String> = HashMap::new();
+    let mut missing = Vec::new();
+    for oid in note_oids.values() {
+        if contents.contains_key(oid) {
+            continue;
+        }
+        match cache.get(oid) {
+            Some(content) => {
+                contents.insert(oid.clone(), content);
+            }
+            None => missing.push(oid.clone()),
+        }
+    }
+
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        for (oid, content) in batch_read_blobs_with_oids(&repo.global_args_for_exec(), &missing)? {
+            let content = content.trim().to_string();
+            if let Err(e) = cache.put(&oid, &content) {
+                debug_log(&format!("failed to cache authorship note {}: {}", oid, e));
+            }
+            contents.insert(oid, content);
+        }
+    }
+
+    let mut logs = HashMap::new();
+    for (commit_sha, oid) in note_oids {
+        let Some(content) = contents.get(&oid) else {
+            continue;
+        };
+        let Ok(mut log) = AuthorshipLog::deserialize_from_string(content) else {
+            continue;
+        };
+        if !is_supported_schema_version(&log.metadata.schema_version) {
+            continue;
+        }
+        // Keep metadata aligned with the commit where this note is attached.
+        log.metadata.base_commit_sha = commit_sha.clone();
+        logs.insert(commit_sha, log);
+    }
+    Ok(logs)
+}
+
+/// Pre-load the notes blame will need for files changed between `from` and `to`:
+/// the most recent commits (up to `to`) touching those files. Returns how many
+/// commits had notes.
+pub fn warm_for_range(repo: &Repository, from: &str, to: &str) -> Result<usize, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["diff", "--name-only", "-z", from, to]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    let changed_files: Vec<String> = String::from_utf8(output.stdout)?
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+        .collect();
+    if changed_files.is_empty() {
+        return Ok(0);
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push(format!("--max-count={}", MAX_WARM_COMMITS));
+    if changed_files.len() > MAX_WARM_PATHSPECS {
+        args.push(format!("{}..{}", from, to));
+    } else {
+        args.push(to.to_string());
+        args.push("--".to_string());
+        args.extend(changed_files);
+    }
+    let output = exec_git(&args)?;
+    let commits: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let warmed = load_authorship_logs(repo, &commits)?.len();
+    let _ = AttributionCache::for_repo(repo).prune(MAX_CACHE_ENTRIES);
+    Ok(warmed)
+}
+
+/// Spawn a background `warm-blame-cache` for `from..to`, debounced per repository
+/// the same way background log flushes are.
+pub fn spawn_background_warm(repo: &Repository, from: &str, to: &str) {
+    if from == to || !should_spawn_warm(repo) {
+        return;
+    }
+    let Ok(workdir) = repo.workdir() else {
+        return;
+    };
+    let workdir = workdir.to_string_lossy().to_string();
+    debug_log(&format!("warming blame cache for {}..{}", from, to));
+    let _ = crate::utils::spawn_internal_git_ai_subcommand(
+        "warm-blame-cache",
+        &[&workdir, from, to],
+        ENV_WARM_BLAME_CACHE_WORKER,
+        &[],
+    );
+}
+
+/// Debounce warm runs so back-to-back fetches don't stack up background processes.
+fn should_spawn_warm(repo: &Repository) -> bool {
+    const MIN_WARM_INTERVAL_SECS: u64 = 60;
+
+    let cache_dir = &repo.storage.attribution_cache;
+    let _ = fs::create_dir_all(cache_dir);
+
+    let marker = cache_dir.join("last_warm_trigger_ts");
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    if let Ok(previous) = fs::read_to_string(&marker)
+        && let Ok(previous_secs) = previous.trim().parse::<u64>()
+        && now_secs.saturating_sub(previous_secs) < MIN_WARM_INTERVAL_SECS
+    {
+        return false;
+    }
+
+    let _ = fs::write(&marker, now_secs.to_string());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_warm_for_range_caches_notes_for_changed_files() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("base.txt", "base\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        let from = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("feature.txt", "ai line\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("model"), Some("tool"))
+            .unwrap();
+        tmp_repo.commit_with_message("feature").unwrap();
+        let to = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(warm_for_range(repo, &from, &to).unwrap(), 1);
+
+        let note_oid = note_blob_oids_for_commits(repo, std::slice::from_ref(&to))
+            .unwrap()
+            .remove(&to)
+            .unwrap();
+        let cached = AttributionCache::for_repo(repo).get(&note_oid).unwrap();
+        assert!(cached.contains("feature.txt"));
+
+        let logs = load_authorship_logs(repo, &[to.clone(), from.clone()]).unwrap();
+        assert_eq!(logs[&to].metadata.base_commit_sha, to);
+    }
+
+    #[test]
+    fn test_prune_evicts_oldest_entries() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let cache = AttributionCache::for_repo(tmp_repo.gitai_repo());
+        for oid in ["aa01", "bb02", "cc03"] {
+            cache.put(oid, oid).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert_eq!(cache.prune(2).unwrap(), 1);
+        assert!(cache.get("aa01").is_none());
+        assert_eq!(cache.get("cc03").as_deref(), Some("cc03"));
+    }
+
+    #[test]
+    fn test_prune_keeps_recently_read_entries_and_clear_removes_all() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let cache = AttributionCache::for_blame(tmp_repo.gitai_repo());
+        for oid in ["aa01", "bb02", "cc03"] {
+            cache.put(oid, oid).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(cache.get("aa01").is_some());
+
+        assert_eq!(cache.prune(2).unwrap(), 1);
+        assert!(cache.get("bb02").is_none());
+        assert!(cache.get("aa01").is_some());
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert!(cache.get("cc03").is_none());
+    }
+}
@@ -3,6 +3,7 @@
 //! This library maintains attribution ranges as files are edited, preserving
 //! authorship information even through moves, edits, and whitespace changes.
 
+use crate::authorship::authorship_log::CharRange;
 use crate::authorship::imara_diff_utils::{ByteDiff, ByteDiffOp, DiffOp, capture_diff_slices};
 use crate::authorship::move_detection::{DeletedLine, InsertedLine, detect_moves};
 use crate::authorship::working_log::CheckpointKind;
@@ -2083,6 +2084,87 @@ fn find_dominant_author_for_line_candidates(
     (latest_author.author_id.clone(), overrode)
 }
 
+/// AI-written parts of each line, as (author, range) pairs ordered by line.
+/// Each character belongs to the attribution covering it with the latest timestamp,
+/// so a line a human lightly edited keeps the AI-written characters around the edit.
+/// Runs that are only whitespace are left out.
+pub fn ai_char_ranges_by_line(
+    attributions: &[Attribution],
+    content: &str,
+) -> Vec<(String, CharRange)> {
+    let mut result = Vec::new();
+    if content.is_empty() || attributions.is_empty() {
+        return result;
+    }
+
+    let human = CheckpointKind::Human.to_str();
+    let boundaries = LineBoundaries::new(content);
+    let mut sorted: Vec<&Attribution> = attributions.iter().filter(|a| !a.is_empty()).collect();
+    sorted.sort_by_key(|a| (a.start, a.end));
+
+    let mut next_idx = 0usize;
+    let mut active: Vec<&Attribution> = Vec::new();
+
+    for line_num in 1..=boundaries.line_count() {
+        let Some((line_start, line_end)) = boundaries.get_line_range(line_num) else {
+            continue;
+        };
+
+        while next_idx < sorted.len() && sorted[next_idx].start < line_end {
+            active.push(sorted[next_idx]);
+            next_idx += 1;
+        }
+        active.retain(|attr| attr.end > line_start);
+        if active.iter().all(|attr| attr.author_id == human) {
+            continue;
+        }
+
+        // (author, range, has non-whitespace)
+        let mut runs: Vec<(String, CharRange, bool)> = Vec::new();
+        for (column, (offset, c)) in content[line_start..line_end].char_indices().enumerate() {
+            if c == '\n' || c == '\r' {
+                continue;
+            }
+            let pos = line_start + offset;
+            let mut owner: Option<&Attribution> = None;
+            for attr in active
+                .iter()
+                .filter(|attr| attr.start <= pos && attr.end > pos)
+            {
+                if owner.is_none_or(|current| attr.ts > current.ts) {
+                    owner = Some(attr);
+                }
+            }
+            let Some(owner) = owner.filter(|owner| owner.author_id != human) else {
+                continue;
+            };
+
+            let column = column as u32;
+            match runs.last_mut() {
+                Some((author, range, has_text))
+                    if *author == owner.author_id && range.end == column =>
+                {
+                    range.end = column + 1;
+                    *has_text |= !c.is_whitespace();
+                }
+                _ => runs.push((
+                    owner.author_id.clone(),
+                    CharRange::new(line_num, column, column + 1),
+                    !c.is_whitespace(),
+                )),
+            }
+        }
+
+        result.extend(
+            runs.into_iter()
+                .filter(|(_, _, has_text)| *has_text)
+                .map(|(author, range, _)| (author, range)),
+        );
+    }
+
+    result
+}
+
 /// Merge consecutive lines with the same author into LineAttribution ranges
 fn merge_consecutive_line_attributions(
     line_authorship: Vec<Option<(String, Option<String>)>>,
@@ -2187,6 +2269,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ai_char_ranges_by_line_keeps_ai_parts_of_human_edited_lines() {
+        let content = "human only\nlet total = a + b;\n";
+        let line_two = content.find("let").unwrap();
+        let b_pos = content.find('b').unwrap();
+        let attributions = vec![
+            Attribution::new(0, line_two, CheckpointKind::Human.to_str(), TEST_TS),
+            Attribution::new(line_two, content.len(), "ai-1".into(), TEST_TS),
+            Attribution::new(
+                b_pos,
+                b_pos + 1,
+                CheckpointKind::Human.to_str(),
+                TEST_TS + 1,
+            ),
+        ];
+
+        let ranges = ai_char_ranges_by_line(&attributions, content);
+        assert_eq!(
+            ranges,
+            vec![
+                ("ai-1".to_string(), CharRange::new(2, 0, 16)),
+                ("ai-1".to_string(), CharRange::new(2, 17, 18)),
+            ]
+        );
+    }
+
     #[test]
     fn substantive_token_change_switches_author() {
         let tracker = AttributionTracker::new();
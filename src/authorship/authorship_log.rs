@@ -1,5 +1,6 @@
 use crate::authorship::transcript::Message;
-use crate::authorship::working_log::AgentId;
+use crate::authorship::working_log::{AgentId, AiClassification};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -186,8 +187,40 @@ impl fmt::Display for LineRange {
     }
 }
 
+/// Part of a single line, in characters (not bytes) from the start of the line.
+/// `start` is inclusive and `end` exclusive. Written as `line:start-end`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CharRange {
+    pub line: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl CharRange {
+    pub fn new(line: u32, start: u32, end: u32) -> Self {
+        Self { line, start, end }
+    }
+
+    /// Shift the line number like [`LineRange::shift`]
+    pub fn shift(&self, insertion_point: u32, offset: i32) -> Option<CharRange> {
+        LineRange::Single(self.line)
+            .shift(insertion_point, offset)
+            .map(|shifted| match shifted {
+                LineRange::Single(line) | LineRange::Range(line, _) => {
+                    CharRange::new(line, self.start, self.end)
+                }
+            })
+    }
+}
+
+impl fmt::Display for CharRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}-{}", self.line, self.start, self.end)
+    }
+}
+
 /// Prompt session details stored in the top-level prompts map keyed by short hash (agent_id + tool)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PromptRecord {
     pub agent_id: AgentId,
     pub human_author: Option<String>,
@@ -203,6 +236,12 @@ pub struct PromptRecord {
     /// Full URL to CAS-stored messages (format: {api_base_url}/cas/{hash})
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub messages_url: Option<String>,
+    /// Blob id of messages interned under the prompts notes ref (`refs/notes/ai-prompts`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_ref: Option<String>,
+    /// Whether the session assisted a human (completions) or generated code (agents)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<AiClassification>,
 }
 
 impl Eq for PromptRecord {}
@@ -249,6 +288,8 @@ mod tests {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         }
     }
 
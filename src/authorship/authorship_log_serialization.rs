@@ -1,8 +1,13 @@
-use crate::authorship::authorship_log::{Author, LineRange, PromptRecord};
+use crate::authorship::authorship_log::{Author, CharRange, LineRange, PromptRecord};
 use crate::authorship::working_log::CheckpointKind;
+use crate::config::{Config, PromptHashAlgorithm, PromptHashScheme};
 use crate::git::repository::Repository;
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::{Mac, SimpleHmac};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Sha3_256;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io::{BufRead, Write};
@@ -11,6 +16,15 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// Authorship log format version identifier
 pub const AUTHORSHIP_LOG_VERSION: &str = "authorship/3.0.0";
 
+/// Version for logs whose attestations also carry sub-line character ranges.
+/// Only written when a log has some, so line-level logs stay readable by older clients.
+pub const AUTHORSHIP_LOG_VERSION_V4: &str = "authorship/4.0.0";
+
+/// Whether this build can read authorship logs with the given `schema_version`
+pub fn is_supported_schema_version(version: &str) -> bool {
+    version == AUTHORSHIP_LOG_VERSION || version == AUTHORSHIP_LOG_VERSION_V4
+}
+
 #[cfg(all(debug_assertions, test))]
 pub const GIT_AI_VERSION: &str = "development";
 
@@ -21,12 +35,31 @@ pub const GIT_AI_VERSION: &str = concat!("development:", env!("CARGO_PKG_VERSION
 pub const GIT_AI_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Metadata section that goes below the divider as JSON
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AuthorshipMetadata {
     pub schema_version: String,
     pub git_ai_version: Option<String>,
     pub base_commit_sha: String,
     pub prompts: BTreeMap<String, PromptRecord>,
+    /// Everyone who shared the human work on this commit, driver first. Empty for
+    /// solo commits.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub humans: Vec<String>,
+    /// The software that produced this log, when `capture_environment` is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Box<EnvironmentFingerprint>>,
+    /// Files matching `tracking.generated` rules, summarized per rule instead of
+    /// attributed line by line
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub generated: BTreeMap<String, GeneratedFiles>,
+    /// What stats need of the attestations and prompts `git-ai gc` dropped from
+    /// an old commit's note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned: Option<Box<PrunedAttribution>>,
+    /// Submodules whose recorded commit this commit changed, by path. Their
+    /// lines are attributed in each submodule's own notes.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub submodules: BTreeMap<String, SubmoduleUpdate>,
 }
 
 impl AuthorshipMetadata {
@@ -36,10 +69,114 @@ impl AuthorshipMetadata {
             git_ai_version: Some(GIT_AI_VERSION.to_string()),
             base_commit_sha: String::new(),
             prompts: BTreeMap::new(),
+            humans: Vec::new(),
+            environment: None,
+            generated: BTreeMap::new(),
+            pruned: None,
+            submodules: BTreeMap::new(),
+        }
+    }
+
+    /// Add the generated-file counts of `other`, the metadata of a note this
+    /// one combines or was rewritten from
+    pub fn merge_generated(&mut self, other: &AuthorshipMetadata) {
+        for (rule, files) in &other.generated {
+            let merged = self.generated.entry(rule.clone()).or_default();
+            merged.files += files.files;
+            merged.bytes += files.bytes;
+            merged.ai_files += files.ai_files;
+        }
+    }
+
+    /// Add the submodule bumps of `other`, the metadata of a later note this one
+    /// combines or was rewritten from: a combined bump starts where the first
+    /// one did and ends where the last one does
+    pub fn merge_submodules(&mut self, other: &AuthorshipMetadata) {
+        for (path, update) in &other.submodules {
+            match self.submodules.get_mut(path) {
+                Some(merged) => {
+                    merged.to = update.to.clone();
+                    merged.added_lines = merged
+                        .added_lines
+                        .zip(update.added_lines)
+                        .map(|(a, b)| a + b);
+                    merged.ai_accepted = merged
+                        .ai_accepted
+                        .zip(update.ai_accepted)
+                        .map(|(a, b)| a + b);
+                }
+                None => {
+                    self.submodules.insert(path.clone(), update.clone());
+                }
+            }
         }
     }
 }
 
+/// The files a commit changed under one `tracking.generated` rule
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct GeneratedFiles {
+    pub files: u32,
+    /// Combined size of the files at the commit
+    pub bytes: u64,
+    /// How many of the files an agent edited
+    pub ai_files: u32,
+}
+
+/// A submodule pointer bump
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SubmoduleUpdate {
+    /// Previously recorded commit, `None` when the submodule was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    pub to: String,
+    /// Lines added in the submodule between the two commits. Absent when the
+    /// submodule isn't checked out or the range was too large to blame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_lines: Option<u32>,
+    /// How many of `added_lines` blame to an AI prompt in the submodule's notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_accepted: Option<u32>,
+}
+
+/// Aggregate record left in place of a commit's line attestations by
+/// `git-ai gc --prune-notes-older-than`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PrunedAttribution {
+    /// Lines of the commit's diff attributed to AI when the note was pruned
+    pub ai_accepted: u32,
+    /// Totals per `tool::model`
+    pub tools: BTreeMap<String, PrunedToolTotals>,
+}
+
+/// One tool and model's share of a pruned commit
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PrunedToolTotals {
+    pub prompts: u32,
+    pub ai_accepted: u32,
+    pub total_additions: u32,
+    pub total_deletions: u32,
+    pub overriden_lines: u32,
+    /// Seconds spent waiting for the agent, from the dropped transcripts
+    pub time_waiting_for_ai: u64,
+}
+
+/// OS, git and agent versions behind a commit's attribution, for tracking down
+/// odd attributions to the release that produced them
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvironmentFingerprint {
+    /// e.g. "macos", "linux", "windows"
+    pub os: String,
+    /// e.g. "aarch64", "x86_64"
+    pub arch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_version: Option<String>,
+    pub git_ai_version: String,
+    /// Agent tool -> version, for agents whose hooks report one
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, String>,
+}
+
 impl Default for AuthorshipMetadata {
     fn default() -> Self {
         Self::new()
@@ -56,14 +193,19 @@ pub struct AttestationEntry {
     pub hash: String,
     /// Line ranges that this prompt is responsible for
     pub line_ranges: Vec<LineRange>,
+    /// Parts of otherwise human lines that this prompt wrote (authorship/4.0.0 only)
+    pub char_ranges: Vec<CharRange>,
 }
 
 impl AttestationEntry {
     pub fn new(hash: String, line_ranges: Vec<LineRange>) -> Self {
-        Self { hash, line_ranges }
+        Self {
+            hash,
+            line_ranges,
+            char_ranges: Vec::new(),
+        }
     }
 
-    #[allow(dead_code)]
     pub fn remove_line_ranges(&mut self, to_remove: &[LineRange]) {
         let mut current_ranges = self.line_ranges.clone();
 
@@ -76,10 +218,11 @@ impl AttestationEntry {
         }
 
         self.line_ranges = current_ranges;
+        self.char_ranges
+            .retain(|range| !to_remove.iter().any(|r| r.contains(range.line)));
     }
 
     /// Shift line ranges by a given offset starting at insertion_point
-    #[allow(dead_code)]
     pub fn shift_line_ranges(&mut self, insertion_point: u32, offset: i32) {
         let mut shifted_ranges = Vec::new();
         for range in &self.line_ranges {
@@ -88,6 +231,11 @@ impl AttestationEntry {
             }
         }
         self.line_ranges = shifted_ranges;
+        self.char_ranges = self
+            .char_ranges
+            .iter()
+            .filter_map(|range| range.shift(insertion_point, offset))
+            .collect();
     }
 }
 
@@ -151,6 +299,37 @@ impl AuthorshipLog {
             .unwrap()
     }
 
+    /// Attest that `hash` wrote `range` of `file`, unless a whole-line entry
+    /// already covers that line. Returns whether the range was added; the file
+    /// section is only created when it is.
+    pub fn add_char_range(&mut self, file: &str, hash: &str, range: CharRange) -> bool {
+        let whole_line_is_ai = self
+            .attestations
+            .iter()
+            .filter(|f| f.file_path == file)
+            .flat_map(|f| f.entries.iter())
+            .any(|e| e.line_ranges.iter().any(|r| r.contains(range.line)));
+        if whole_line_is_ai {
+            return false;
+        }
+        let file_attestation = self.get_or_create_file(file);
+        match file_attestation.entries.iter_mut().find(|e| e.hash == hash) {
+            Some(existing) => {
+                if existing.char_ranges.contains(&range) {
+                    return false;
+                }
+                existing.char_ranges.push(range);
+            }
+            None => {
+                let mut new_entry = AttestationEntry::new(hash.to_string(), Vec::new());
+                new_entry.char_ranges.push(range);
+                file_attestation.add_entry(new_entry);
+            }
+        }
+        self.metadata.schema_version = AUTHORSHIP_LOG_VERSION_V4.to_string();
+        true
+    }
+
     /// Serialize to the new text format
     pub fn serialize_to_string(&self) -> Result<String, fmt::Error> {
         let mut output = String::new();
@@ -170,7 +349,7 @@ impl AuthorshipLog {
                 output.push_str("  ");
                 output.push_str(&entry.hash);
                 output.push(' ');
-                output.push_str(&format_line_ranges(&entry.line_ranges));
+                output.push_str(&format_ranges(&entry.line_ranges, &entry.char_ranges));
                 output.push('\n');
             }
         }
@@ -298,6 +477,45 @@ impl AuthorshipLog {
         None
     }
 
+    /// Share of a line's non-whitespace characters covered by sub-line ranges, with
+    /// the prompt hash that wrote most of them. `None` when no sub-line range is on
+    /// the line. `line_content` is the line as committed, without its newline.
+    pub fn get_line_ai_share(
+        &self,
+        file: &str,
+        line: u32,
+        line_content: &str,
+    ) -> Option<(String, f64)> {
+        let file_attestation = self.attestations.iter().find(|f| f.file_path == file)?;
+
+        let chars: Vec<char> = line_content.chars().collect();
+        let mut owners: Vec<Option<&str>> = vec![None; chars.len()];
+        for entry in &file_attestation.entries {
+            for range in entry.char_ranges.iter().filter(|range| range.line == line) {
+                let end = (range.end as usize).min(chars.len());
+                for owner in owners.iter_mut().take(end).skip(range.start as usize) {
+                    *owner = Some(&entry.hash);
+                }
+            }
+        }
+
+        let mut total = 0usize;
+        let mut per_hash: BTreeMap<&str, usize> = BTreeMap::new();
+        for (c, owner) in chars.iter().zip(&owners) {
+            if c.is_whitespace() {
+                continue;
+            }
+            total += 1;
+            if let Some(hash) = owner {
+                *per_hash.entry(hash).or_default() += 1;
+            }
+        }
+
+        let (hash, _) = per_hash.iter().max_by_key(|(_, count)| **count)?;
+        let ai_chars: usize = per_hash.values().sum();
+        Some((hash.to_string(), ai_chars as f64 / total as f64))
+    }
+
     /// Convert authorship log to working log checkpoints for merge --squash
     ///
     /// Creates one checkpoint per file per session that touched that file. This ensures that:
@@ -528,6 +746,40 @@ fn format_line_ranges(ranges: &[LineRange]) -> String {
         .join(",")
 }
 
+/// Line ranges followed by sub-line ranges, e.g. "1,2,19-222,7:4-19"
+fn format_ranges(line_ranges: &[LineRange], char_ranges: &[CharRange]) -> String {
+    let mut sorted_char_ranges = char_ranges.to_vec();
+    sorted_char_ranges.sort();
+
+    let mut parts = Vec::new();
+    if !line_ranges.is_empty() {
+        parts.push(format_line_ranges(line_ranges));
+    }
+    parts.extend(sorted_char_ranges.iter().map(CharRange::to_string));
+    parts.join(",")
+}
+
+/// Parse ranges written by [`format_ranges`]. Parts with a `:` are sub-line ranges.
+fn parse_ranges(
+    input: &str,
+) -> Result<(Vec<LineRange>, Vec<CharRange>), Box<dyn std::error::Error>> {
+    let mut line_parts = Vec::new();
+    let mut char_ranges = Vec::new();
+
+    for part in input.split(',') {
+        if let Some((line, columns)) = part.split_once(':') {
+            let (start, end) = columns
+                .split_once('-')
+                .ok_or_else(|| format!("Invalid character range: {}", part))?;
+            char_ranges.push(CharRange::new(line.parse()?, start.parse()?, end.parse()?));
+        } else {
+            line_parts.push(part);
+        }
+    }
+
+    Ok((parse_line_ranges(&line_parts.join(","))?, char_ranges))
+}
+
 /// Parse line ranges from a string like "1,2,19-222"
 /// No spaces are expected in the format
 fn parse_line_ranges(input: &str) -> Result<Vec<LineRange>, Box<dyn std::error::Error>> {
@@ -577,9 +829,10 @@ fn parse_attestation_section(
             if let Some(space_pos) = entry_line.find(' ') {
                 let hash = entry_line[..space_pos].to_string();
                 let ranges_str = &entry_line[space_pos + 1..];
-                let line_ranges = parse_line_ranges(ranges_str)?;
+                let (line_ranges, char_ranges) = parse_ranges(ranges_str)?;
 
-                let entry = AttestationEntry::new(hash, line_ranges);
+                let mut entry = AttestationEntry::new(hash, line_ranges);
+                entry.char_ranges = char_ranges;
 
                 if let Some(ref mut file_attestation) = current_file {
                     file_attestation.add_entry(entry);
@@ -625,14 +878,75 @@ fn needs_quoting(path: &str) -> bool {
     path.contains(' ') || path.contains('\t') || path.contains('\n')
 }
 
-/// Generate a short hash (7 characters) from agent_id and tool
+/// Generate the 16-character prompt id for an agent session from agent_id and
+/// tool, with the configured `prompt_hashing` scheme
 pub fn generate_short_hash(agent_id: &str, tool: &str) -> String {
+    short_hash_with_scheme(&Config::get().prompt_hashing().current, agent_id, tool)
+}
+
+/// The ids the session had under the schemes retired by `prompt_hashing` rotations,
+/// most recent first
+pub fn previous_short_hashes(agent_id: &str, tool: &str) -> Vec<String> {
+    Config::get()
+        .prompt_hashing()
+        .previous
+        .iter()
+        .map(|scheme| short_hash_with_scheme(scheme, agent_id, tool))
+        .collect()
+}
+
+/// Whether `id` is the session's id under a `prompt_hashing` scheme other than the
+/// current one. Always false until a rotation leaves a previous scheme behind.
+pub fn is_previous_short_hash(id: &str, agent_id: &str, tool: &str) -> bool {
+    let hashing = Config::get().prompt_hashing();
+    !hashing.previous.is_empty()
+        && (short_hash_with_scheme(&hashing.current, agent_id, tool) == id
+            || previous_short_hashes(agent_id, tool)
+                .iter()
+                .any(|hash| hash == id))
+}
+
+/// The prompt `prompts` records as `id`, or, after a `prompt_hashing` rotation, the
+/// one recorded for the same session under another scheme
+pub fn find_prompt_by_id<'a>(
+    prompts: &'a BTreeMap<String, PromptRecord>,
+    id: &str,
+) -> Option<(&'a String, &'a PromptRecord)> {
+    prompts.get_key_value(id).or_else(|| {
+        prompts.iter().find(|(_, prompt)| {
+            is_previous_short_hash(id, &prompt.agent_id.id, &prompt.agent_id.tool)
+        })
+    })
+}
+
+/// The prompt id under `scheme`. Unsalted SHA-256 is the original scheme, so ids
+/// from before prompt hashing was configurable keep their value.
+pub fn short_hash_with_scheme(scheme: &PromptHashScheme, agent_id: &str, tool: &str) -> String {
     let combined = format!("{}:{}", tool, agent_id);
-    let mut hasher = Sha256::new();
-    hasher.update(combined.as_bytes());
-    let result = hasher.finalize();
-    // Take first 7 characters of the hex representation
-    format!("{:x}", result)[..16].to_string()
+    let salt = scheme.salt.as_deref();
+    let digest = match scheme.algorithm {
+        PromptHashAlgorithm::Sha256 => keyed_digest::<Sha256>(salt, combined.as_bytes()),
+        PromptHashAlgorithm::Sha512 => keyed_digest::<Sha512>(salt, combined.as_bytes()),
+        PromptHashAlgorithm::Sha3_256 => keyed_digest::<Sha3_256>(salt, combined.as_bytes()),
+    };
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// `D(data)`, or HMAC-`D` keyed with `salt` when there is one
+fn keyed_digest<D: Digest + BlockSizeUser>(salt: Option<&str>, data: &[u8]) -> Vec<u8> {
+    match salt {
+        None => D::digest(data).to_vec(),
+        Some(salt) => {
+            let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(salt.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(data);
+            mac.finalize().into_bytes().to_vec()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -657,6 +971,53 @@ mod tests {
         assert_debug_snapshot!(ranges);
     }
 
+    #[test]
+    fn test_char_ranges_roundtrip_after_line_ranges() {
+        let mut log = AuthorshipLog::new();
+        let mut file = FileAttestation::new("src/calc.rs".to_string());
+        let mut entry = AttestationEntry::new("abc123".to_string(), vec![LineRange::Range(1, 2)]);
+        entry.char_ranges = vec![CharRange::new(7, 30, 31), CharRange::new(7, 0, 23)];
+        file.add_entry(entry);
+        let mut char_only = AttestationEntry::new("def456".to_string(), Vec::new());
+        char_only.char_ranges = vec![CharRange::new(9, 4, 12)];
+        file.add_entry(char_only);
+        log.attestations.push(file);
+
+        let serialized = log.serialize_to_string().unwrap();
+        assert!(
+            serialized.starts_with("src/calc.rs\n  abc123 1-2,7:0-23,7:30-31\n  def456 9:4-12\n")
+        );
+
+        let deserialized = AuthorshipLog::deserialize_from_string(&serialized).unwrap();
+        let entries = &deserialized.attestations[0].entries;
+        assert_eq!(entries[0].line_ranges, vec![LineRange::Range(1, 2)]);
+        assert_eq!(
+            entries[0].char_ranges,
+            vec![CharRange::new(7, 0, 23), CharRange::new(7, 30, 31)]
+        );
+        assert!(entries[1].line_ranges.is_empty());
+        assert_eq!(entries[1].char_ranges, vec![CharRange::new(9, 4, 12)]);
+
+        assert!(parse_ranges("7:4").is_err());
+    }
+
+    #[test]
+    fn test_get_line_ai_share_counts_non_whitespace() {
+        let mut log = AuthorshipLog::new();
+        let file = log.get_or_create_file("src/calc.rs");
+        let mut entry = AttestationEntry::new("abc123".to_string(), Vec::new());
+        entry.char_ranges = vec![CharRange::new(3, 0, 4)];
+        file.add_entry(entry);
+
+        // "let " is 3 of the 5 non-whitespace characters in "let ab"
+        let (hash, share) = log.get_line_ai_share("src/calc.rs", 3, "let ab").unwrap();
+        assert_eq!(hash, "abc123");
+        assert!((share - 0.6).abs() < 1e-9, "{}", share);
+
+        assert!(log.get_line_ai_share("src/calc.rs", 4, "let ab").is_none());
+        assert!(log.get_line_ai_share("src/other.rs", 3, "let ab").is_none());
+    }
+
     #[test]
     fn test_serialize_deserialize_roundtrip() {
         let mut log = AuthorshipLog::new();
@@ -775,6 +1136,8 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -842,6 +1205,8 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -891,6 +1256,8 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -908,6 +1275,22 @@ mod tests {
         assert_eq!(deserialized.attestations.len(), 0);
     }
 
+    #[test]
+    fn test_humans_only_serialized_when_pairing() {
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = "abc123".to_string();
+        let serialized = log.serialize_to_string().unwrap();
+        assert!(!serialized.contains("\"humans\""));
+
+        log.metadata.humans = vec![
+            "Me <me@corp.com>".to_string(),
+            "Ana <ana@corp.com>".to_string(),
+        ];
+        let serialized = log.serialize_to_string().unwrap();
+        let deserialized = AuthorshipLog::deserialize_from_string(&serialized).unwrap();
+        assert_eq!(deserialized.metadata.humans, log.metadata.humans);
+    }
+
     #[test]
     fn test_remove_line_ranges_complete_removal() {
         let mut entry =
@@ -1070,6 +1453,8 @@ mod tests {
                 accepted_lines: 11,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1241,6 +1626,8 @@ mod tests {
                 accepted_lines: 10,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1265,6 +1652,8 @@ mod tests {
                 accepted_lines: 20,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1344,4 +1733,45 @@ mod tests {
             .sum();
         assert_eq!(lines_session2, 20);
     }
+
+    #[test]
+    fn test_prompt_hash_schemes() {
+        let default = PromptHashScheme::default();
+        let id = short_hash_with_scheme(&default, "session-1", "cursor");
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // The default scheme is the original unsalted SHA-256 prefix
+        let sha = format!("{:x}", Sha256::digest(b"cursor:session-1"));
+        assert_eq!(id, sha[..16]);
+
+        let salted = PromptHashScheme {
+            algorithm: PromptHashAlgorithm::Sha256,
+            salt: Some("org-salt".to_string()),
+        };
+        let resalted = PromptHashScheme {
+            salt: Some("org-salt-2".to_string()),
+            ..salted.clone()
+        };
+        let sha3 = PromptHashScheme {
+            algorithm: PromptHashAlgorithm::Sha3_256,
+            salt: None,
+        };
+        let ids = [
+            id.clone(),
+            short_hash_with_scheme(&salted, "session-1", "cursor"),
+            short_hash_with_scheme(&resalted, "session-1", "cursor"),
+            short_hash_with_scheme(&sha3, "session-1", "cursor"),
+        ];
+        for (i, a) in ids.iter().enumerate() {
+            assert_eq!(a.len(), 16);
+            for b in &ids[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_eq!(
+            ids[1],
+            short_hash_with_scheme(&salted, "session-1", "cursor")
+        );
+    }
 }
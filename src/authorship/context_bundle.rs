@@ -0,0 +1,227 @@
+//! Differential context bundles captured with AI checkpoints.
+//!
+//! A bundle holds the edited regions of each file plus a few lines of context,
+//! never whole files, so reviewers in the hosted product can see what an agent
+//! changed. Everything that limits what leaves the machine happens here, before
+//! the bundle is queued for upload:
+//!
+//! - excluded paths (`context_capture.exclude` plus key/env files) are skipped
+//! - secrets in the captured lines are redacted
+//! - files that would push the bundle past `context_capture.max_bytes` are
+//!   listed in `omitted` instead of captured
+
+use crate::authorship::ignore::{build_ignore_matcher, should_ignore_file_with_matcher};
+use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::secrets::redact_secrets_in_text;
+use crate::config::ContextCaptureSettings;
+use serde::{Deserialize, Serialize};
+
+/// Never captured, whatever the config says
+const ALWAYS_EXCLUDED: &[&str] = &[".env", ".env.*", "*.pem", "*.key", "id_rsa*", "*.p12"];
+
+/// Metadata key linking a checkpoint to its bundle's CAS hash
+pub const CONTEXT_BUNDLE_METADATA_KEY: &str = "context_bundle";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextFile {
+    pub path: String,
+    /// Unified diff hunks (`@@ -a,b +c,d @@` headers, no file header)
+    pub hunks: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextBundle {
+    pub context_lines: u32,
+    pub files: Vec<ContextFile>,
+    /// Files left out because the bundle hit its size cap
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub omitted: Vec<String>,
+}
+
+impl ContextBundle {
+    /// Build a bundle from `(path, previous content, current content)` triples.
+    /// Returns `None` when nothing is left to capture.
+    pub fn build<'a>(
+        files: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+        settings: &ContextCaptureSettings,
+    ) -> Option<Self> {
+        let mut exclude: Vec<String> = ALWAYS_EXCLUDED.iter().map(|p| p.to_string()).collect();
+        exclude.extend(settings.exclude.iter().cloned());
+        let matcher = build_ignore_matcher(&exclude);
+
+        let mut bundle = ContextBundle {
+            context_lines: settings.context_lines,
+            ..Default::default()
+        };
+        let mut size = 0;
+        for (path, previous, current) in files {
+            if should_ignore_file_with_matcher(path, &matcher) {
+                continue;
+            }
+            let hunks = unified_hunks(previous, current, settings.context_lines as usize);
+            if hunks.is_empty() {
+                continue;
+            }
+            let (hunks, _) = redact_secrets_in_text(&hunks);
+            let file_size = path.len() + hunks.len();
+            if size + file_size > settings.max_bytes {
+                bundle.omitted.push(path.to_string());
+                continue;
+            }
+            size += file_size;
+            bundle.files.push(ContextFile {
+                path: path.to_string(),
+                hunks,
+            });
+        }
+
+        if bundle.files.is_empty() && bundle.omitted.is_empty() {
+            None
+        } else {
+            Some(bundle)
+        }
+    }
+}
+
+/// Unified diff hunks between `old` and `new` with `context` unchanged lines
+/// around each change. Empty when the contents are the same.
+pub fn unified_hunks(old: &str, new: &str, context: usize) -> String {
+    let changes = compute_line_changes(old, new);
+    let is_equal = |i: usize| *changes[i].tag() == LineChangeTag::Equal;
+
+    // Line numbers (0-based) in old/new at each position in `changes`
+    let mut old_line = Vec::with_capacity(changes.len() + 1);
+    let mut new_line = Vec::with_capacity(changes.len() + 1);
+    let (mut o, mut n) = (0, 0);
+    for change in &changes {
+        old_line.push(o);
+        new_line.push(n);
+        match change.tag() {
+            LineChangeTag::Equal => {
+                o += 1;
+                n += 1;
+            }
+            LineChangeTag::Delete => o += 1,
+            LineChangeTag::Insert => n += 1,
+        }
+    }
+    old_line.push(o);
+    new_line.push(n);
+
+    let mut output = String::new();
+    let mut i = 0;
+    while i < changes.len() {
+        if is_equal(i) {
+            i += 1;
+            continue;
+        }
+
+        // Extend the hunk while the unchanged gap to the next change is small
+        // enough that the two context windows would touch
+        let start = i.saturating_sub(context);
+        let mut last_change = i;
+        let mut j = i + 1;
+        while j < changes.len() {
+            if !is_equal(j) {
+                last_change = j;
+                j += 1;
+                continue;
+            }
+            let mut next_change = j;
+            while next_change < changes.len() && is_equal(next_change) {
+                next_change += 1;
+            }
+            if next_change < changes.len() && next_change - j <= 2 * context {
+                j = next_change;
+            } else {
+                break;
+            }
+        }
+        let end = (last_change + 1 + context).min(changes.len());
+
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_line[start] + 1,
+            old_line[end] - old_line[start],
+            new_line[start] + 1,
+            new_line[end] - new_line[start]
+        ));
+        for change in &changes[start..end] {
+            output.push(match change.tag() {
+                LineChangeTag::Equal => ' ',
+                LineChangeTag::Delete => '-',
+                LineChangeTag::Insert => '+',
+            });
+            output.push_str(change.value());
+            if !change.value().ends_with('\n') {
+                output.push('\n');
+            }
+        }
+        i = end;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(lines: std::ops::Range<u32>) -> String {
+        lines.map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_unified_hunks_keeps_only_context_around_edits() {
+        let old = numbered(1..21);
+        let new = old
+            .replace("line 3\n", "line three\n")
+            .replace("line 17\n", "line 17\nadded\n");
+
+        let hunks = unified_hunks(&old, &new, 1);
+        assert_eq!(
+            hunks,
+            "@@ -2,3 +2,3 @@\n line 2\n-line 3\n+line three\n line 4\n\
+             @@ -17,2 +17,3 @@\n line 17\n+added\n line 18\n"
+        );
+        assert!(unified_hunks(&old, &old, 3).is_empty());
+    }
+
+    #[test]
+    fn test_unified_hunks_merges_nearby_edits() {
+        let old = numbered(1..10);
+        let new = old.replace("line 3\n", "x\n").replace("line 6\n", "y\n");
+
+        let hunks = unified_hunks(&old, &new, 2);
+        assert_eq!(hunks.matches("@@ -").count(), 1);
+        assert!(hunks.starts_with("@@ -1,8 +1,8 @@\n"));
+    }
+
+    #[test]
+    fn test_bundle_applies_exclusions_and_size_cap() {
+        let settings = ContextCaptureSettings {
+            enabled: true,
+            context_lines: 0,
+            max_bytes: 40,
+            exclude: vec!["secrets/*".to_string()],
+        };
+        let files = [
+            ("src/a.rs", "a\n", "b\n"),
+            ("secrets/creds.txt", "", "token\n"),
+            (".env", "", "KEY=1\n"),
+            (
+                "src/big.rs",
+                "",
+                "0123456789012345678901234567890123456789\n",
+            ),
+            ("src/same.rs", "same\n", "same\n"),
+        ];
+
+        let bundle = ContextBundle::build(files, &settings).unwrap();
+        let paths: Vec<_> = bundle.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/a.rs"]);
+        assert_eq!(bundle.files[0].hunks, "@@ -1,1 +1,1 @@\n-a\n+b\n");
+        assert_eq!(bundle.omitted, vec!["src/big.rs"]);
+
+        assert!(ContextBundle::build([(".env", "", "KEY=1\n")], &settings).is_none());
+    }
+}
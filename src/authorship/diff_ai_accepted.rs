@@ -10,6 +10,14 @@ pub struct DiffAiAcceptedStats {
     pub total_ai_accepted: u32,
     pub per_tool_model: BTreeMap<String, u32>,
     pub per_prompt: BTreeMap<String, u32>,
+    pub per_file: BTreeMap<String, FileAcceptedLines>,
+}
+
+/// Added lines in one file, and how many of them blame to an AI prompt
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FileAcceptedLines {
+    pub added_lines: u32,
+    pub ai_accepted: u32,
 }
 
 pub fn diff_ai_accepted_stats(
@@ -40,6 +48,13 @@ pub fn diff_ai_accepted_stats(
         if line_ranges.is_empty() {
             continue;
         }
+        stats.per_file.insert(
+            file_path.clone(),
+            FileAcceptedLines {
+                added_lines: lines.len() as u32,
+                ai_accepted: 0,
+            },
+        );
 
         let mut options = GitAiBlameOptions::default();
         #[allow(clippy::field_reassign_with_default)]
@@ -68,6 +83,9 @@ pub fn diff_ai_accepted_stats(
                 && prompt_records.contains_key(prompt_hash)
             {
                 stats.total_ai_accepted += 1;
+                if let Some(file_stats) = stats.per_file.get_mut(&file_path) {
+                    file_stats.ai_accepted += 1;
+                }
                 *stats.per_prompt.entry(prompt_hash.clone()).or_insert(0) += 1;
                 if let Some(tool_model) = prompt_tool_map.get(prompt_hash) {
                     *stats.per_tool_model.entry(tool_model.clone()).or_insert(0) += 1;
@@ -183,6 +201,7 @@ mod tests {
             total_ai_accepted: 10,
             per_tool_model: BTreeMap::new(),
             per_prompt: BTreeMap::new(),
+            per_file: BTreeMap::new(),
         };
         let debug_str = format!("{:?}", stats);
         assert!(debug_str.contains("DiffAiAcceptedStats"));
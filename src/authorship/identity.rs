@@ -0,0 +1,249 @@
+//! Author identity resolution.
+//!
+//! People commit from several machines with different emails and names. The
+//! resolver maps every known alias to one canonical `Name <email>` so stats,
+//! metrics and reports count each human once. Aliases come from
+//! `identities.aliases`; authors without an alias can optionally be looked up in
+//! an org directory (`identities.lookup_url`), with answers cached on disk.
+
+use crate::api::ApiContext;
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Cached org lookups are refreshed after a week
+const LOOKUP_CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+const LOOKUP_TIMEOUT_SECS: u64 = 5;
+
+/// Split `Name <email>` into its parts. Either may be missing.
+pub fn split_author(author: &str) -> (Option<&str>, Option<&str>) {
+    let author = author.trim();
+    match (author.rfind('<'), author.rfind('>')) {
+        (Some(open), Some(close)) if open < close => {
+            let name = author[..open].trim();
+            let email = author[open + 1..close].trim();
+            (
+                (!name.is_empty()).then_some(name),
+                (!email.is_empty()).then_some(email),
+            )
+        }
+        _ if author.contains('@') => (None, Some(author)),
+        _ if author.is_empty() => (None, None),
+        _ => (Some(author), None),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedLookup {
+    /// Canonical identity, `None` if the directory doesn't know the email
+    canonical: Option<String>,
+    fetched_at: u64,
+}
+
+/// `GET <lookup_url>?email=<email>` response
+#[derive(Debug, Deserialize)]
+struct LookupResponse {
+    canonical: Option<String>,
+}
+
+/// Maps author strings to canonical identities
+#[derive(Debug, Default)]
+pub struct IdentityResolver {
+    /// Lowercased alias (full author, email or name) -> canonical identity
+    aliases: HashMap<String, String>,
+    lookup_url: Option<String>,
+    lookup_cache: Mutex<BTreeMap<String, CachedLookup>>,
+    lookup_cache_path: Option<PathBuf>,
+}
+
+impl IdentityResolver {
+    /// Build a resolver from an alias table without org lookups
+    pub fn new(aliases: &BTreeMap<String, Vec<String>>) -> Self {
+        let mut map = HashMap::new();
+        for (canonical, alias_list) in aliases {
+            // The canonical identity's own email and name resolve to it too
+            let (name, email) = split_author(canonical);
+            let own = [Some(canonical.as_str()), email, name];
+            for alias in own
+                .into_iter()
+                .flatten()
+                .chain(alias_list.iter().map(String::as_str))
+            {
+                map.entry(alias.trim().to_lowercase())
+                    .or_insert_with(|| canonical.clone());
+            }
+        }
+        Self {
+            aliases: map,
+            ..Default::default()
+        }
+    }
+
+    /// Resolver for the configured aliases. Cached org lookups are always used;
+    /// with `allow_network`, unknown authors are also looked up in the org directory.
+    pub fn from_config(allow_network: bool) -> Self {
+        let config = Config::get();
+        let mut resolver = Self::new(config.identity_aliases());
+        if let Some(url) = config.identity_lookup_url() {
+            resolver.lookup_cache_path = lookup_cache_path();
+            if let Some(path) = &resolver.lookup_cache_path
+                && let Ok(contents) = std::fs::read_to_string(path)
+                && let Ok(cache) = serde_json::from_str(&contents)
+            {
+                resolver.lookup_cache = Mutex::new(cache);
+            }
+            if allow_network {
+                resolver.lookup_url = Some(url.to_string());
+            }
+        }
+        resolver
+    }
+
+    /// Canonical identity for `author`, or `author` itself when unknown
+    pub fn resolve(&self, author: &str) -> String {
+        let (name, email) = split_author(author);
+        for key in [Some(author), email, name].into_iter().flatten() {
+            if let Some(canonical) = self.aliases.get(&key.trim().to_lowercase()) {
+                return canonical.clone();
+            }
+        }
+        if let Some(email) = email
+            && let Some(canonical) = self.lookup(email)
+        {
+            return canonical;
+        }
+        author.trim().to_string()
+    }
+
+    fn lookup(&self, email: &str) -> Option<String> {
+        let key = email.to_lowercase();
+        let now = now_secs();
+        if let Ok(cache) = self.lookup_cache.lock()
+            && let Some(entry) = cache.get(&key)
+            && (self.lookup_url.is_none()
+                || now.saturating_sub(entry.fetched_at) < LOOKUP_CACHE_TTL_SECS)
+        {
+            return entry.canonical.clone();
+        }
+
+        let url = self.lookup_url.as_ref()?;
+        let canonical = fetch_canonical(url, email)?;
+        if let Ok(mut cache) = self.lookup_cache.lock() {
+            cache.insert(
+                key,
+                CachedLookup {
+                    canonical: canonical.clone(),
+                    fetched_at: now,
+                },
+            );
+            if let Some(path) = &self.lookup_cache_path
+                && let Ok(json) = serde_json::to_string(&*cache)
+            {
+                if let Some(dir) = path.parent() {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+                let _ = std::fs::write(path, json);
+            }
+        }
+        canonical
+    }
+}
+
+/// Query the org directory. `None` on network errors (so the miss isn't cached),
+/// `Some(None)` when the directory doesn't know the email.
+fn fetch_canonical(url: &str, email: &str) -> Option<Option<String>> {
+    let encoded: String = url::form_urlencoded::byte_serialize(email.as_bytes()).collect();
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let response = ApiContext::http_get(&format!("{}{}email={}", url, separator, encoded))
+        .with_timeout(LOOKUP_TIMEOUT_SECS)
+        .send()
+        .ok()?;
+    match response.status_code {
+        200 => serde_json::from_str::<LookupResponse>(response.as_str().ok()?)
+            .ok()
+            .map(|r| r.canonical.filter(|c| !c.trim().is_empty())),
+        404 => Some(None),
+        _ => None,
+    }
+}
+
+fn lookup_cache_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join(".git-ai")
+            .join("internal")
+            .join("identity_cache.json")
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> IdentityResolver {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            "Jane Doe <jane@corp.com>".to_string(),
+            vec!["jane@home.net".to_string(), "jdoe".to_string()],
+        );
+        IdentityResolver::new(&aliases)
+    }
+
+    #[test]
+    fn test_split_author() {
+        assert_eq!(
+            split_author("Jane Doe <jane@corp.com>"),
+            (Some("Jane Doe"), Some("jane@corp.com"))
+        );
+        assert_eq!(split_author("jane@corp.com"), (None, Some("jane@corp.com")));
+        assert_eq!(split_author("jdoe"), (Some("jdoe"), None));
+        assert_eq!(
+            split_author("<jane@corp.com>"),
+            (None, Some("jane@corp.com"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_by_email_name_and_canonical() {
+        let resolver = resolver();
+        let canonical = "Jane Doe <jane@corp.com>";
+        assert_eq!(resolver.resolve("Jane <JANE@home.net>"), canonical);
+        assert_eq!(resolver.resolve("jdoe <jd@laptop.local>"), canonical);
+        assert_eq!(resolver.resolve("J. Doe <jane@corp.com>"), canonical);
+        assert_eq!(resolver.resolve(canonical), canonical);
+    }
+
+    #[test]
+    fn test_unknown_author_is_unchanged() {
+        let resolver = resolver();
+        assert_eq!(
+            resolver.resolve("Bob <bob@example.com>"),
+            "Bob <bob@example.com>"
+        );
+    }
+
+    #[test]
+    fn test_cached_lookup_used_without_network() {
+        let resolver = resolver();
+        resolver.lookup_cache.lock().unwrap().insert(
+            "bob@laptop.local".to_string(),
+            CachedLookup {
+                canonical: Some("Bob <bob@corp.com>".to_string()),
+                fetched_at: 0,
+            },
+        );
+        assert_eq!(
+            resolver.resolve("bob <bob@laptop.local>"),
+            "Bob <bob@corp.com>"
+        );
+    }
+}
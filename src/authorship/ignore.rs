@@ -1,3 +1,4 @@
+use crate::config::Config;
 use crate::git::repository::Repository;
 use glob::Pattern;
 use std::collections::HashSet;
@@ -51,12 +52,17 @@ impl IgnoreMatcher {
     }
 
     pub fn is_ignored(&self, path: &str) -> bool {
+        self.first_match(path).is_some()
+    }
+
+    /// Index of the first pattern that matches `path`
+    pub fn first_match(&self, path: &str) -> Option<usize> {
         let filename = std::path::Path::new(path)
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("");
 
-        self.patterns.iter().any(|pattern| match pattern {
+        self.patterns.iter().position(|pattern| match pattern {
             CompiledPattern::Glob(glob_pattern) => {
                 glob_pattern.matches(path) || glob_pattern.matches(filename)
             }
@@ -159,6 +165,11 @@ pub fn effective_ignore_patterns(
     patterns.extend(load_linguist_generated_patterns_from_root_gitattributes(
         repo,
     ));
+    patterns.extend(Config::get().exclude_paths().iter().cloned());
+    // Generated files are counted at commit time rather than attributed by line
+    let tracking = Config::get().tracking();
+    patterns.extend(tracking.exclude.iter().cloned());
+    patterns.extend(tracking.generated.iter().cloned());
     patterns.extend(extra_patterns.iter().cloned());
     patterns.extend(user_patterns.iter().cloned());
     dedupe_patterns(patterns)
@@ -1,16 +1,20 @@
-use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::authorship_log_serialization::{
+    generate_short_hash, is_previous_short_hash,
+};
 use crate::authorship::transcript::AiTranscript;
 use crate::authorship::working_log::Checkpoint;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::utils::debug_log;
 use dirs;
 use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 
 /// Current schema version (must match MIGRATIONS.len())
-const SCHEMA_VERSION: usize = 3;
+const SCHEMA_VERSION: usize = 5;
 
 /// Database migrations - each migration upgrades the schema by one version
 /// Migration at index N upgrades from version N to version N+1
@@ -77,13 +81,45 @@ const MIGRATIONS: &[&str] = &[
         cached_at INTEGER NOT NULL
     );
     "#,
+    // Migration 3 -> 4: Track uploaded chunks so large bundle uploads can resume
+    r#"
+    CREATE TABLE bundle_upload_chunks (
+        bundle_hash TEXT NOT NULL,
+        chunk_index INTEGER NOT NULL,
+        chunk_hash TEXT NOT NULL,
+        uploaded_at INTEGER NOT NULL,
+        PRIMARY KEY (bundle_hash, chunk_index)
+    );
+    "#,
+    // Migration 4 -> 5: Link sessions to every repository (and commit) they touched,
+    // since a prompt row only remembers the last one
+    r#"
+    CREATE TABLE session_repos (
+        prompt_id TEXT NOT NULL,
+        external_thread_id TEXT NOT NULL,
+        tool TEXT NOT NULL,
+        workdir TEXT NOT NULL,
+        commit_sha TEXT NOT NULL DEFAULT '',
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (prompt_id, workdir, commit_sha)
+    );
+
+    CREATE INDEX idx_session_repos_external_thread_id
+        ON session_repos(external_thread_id);
+
+    INSERT OR IGNORE INTO session_repos (
+        prompt_id, external_thread_id, tool, workdir, commit_sha, updated_at
+    )
+    SELECT id, external_thread_id, tool, workdir, COALESCE(commit_sha, ''), updated_at
+    FROM prompts WHERE workdir IS NOT NULL;
+    "#,
 ];
 
 /// Global database singleton
 static INTERNAL_DB: OnceLock<Mutex<InternalDatabase>> = OnceLock::new();
 
 /// Prompt record for database storage
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptDbRecord {
     pub id: String,                                      // 16-char short hash
     pub workdir: Option<String>,                         // Repository working directory
@@ -161,6 +197,8 @@ impl PromptDbRecord {
             accepted_lines: self.accepted_lines.unwrap_or(0),
             overriden_lines: self.overridden_lines.unwrap_or(0),
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         }
     }
 
@@ -269,6 +307,51 @@ pub struct CasSyncRecord {
     pub attempts: u32,
 }
 
+/// A repository one agent session edited, with a commit it produced there.
+/// `commit_sha` is `None` for checkpoints not yet committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRepoLink {
+    pub prompt_id: String,
+    pub external_thread_id: String,
+    pub tool: String,
+    pub workdir: String,
+    pub commit_sha: Option<String>,
+    pub updated_at: i64,
+}
+
+/// Record that `record`'s session touched its workdir, and the commit if any. A
+/// commit supersedes the pending (uncommitted) link for the same repository.
+fn link_session_repo(conn: &Connection, record: &PromptDbRecord) -> Result<(), GitAiError> {
+    let Some(workdir) = record.workdir.as_deref() else {
+        return Ok(());
+    };
+    let commit_sha = record.commit_sha.as_deref().unwrap_or("");
+    if !commit_sha.is_empty() {
+        conn.execute(
+            "DELETE FROM session_repos WHERE prompt_id = ?1 AND workdir = ?2 AND commit_sha = ''",
+            params![record.id, workdir],
+        )?;
+    }
+    conn.execute(
+        r#"
+        INSERT INTO session_repos (
+            prompt_id, external_thread_id, tool, workdir, commit_sha, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(prompt_id, workdir, commit_sha) DO UPDATE SET
+            updated_at = MAX(updated_at, excluded.updated_at)
+        "#,
+        params![
+            record.id,
+            record.external_thread_id,
+            record.tool,
+            workdir,
+            commit_sha,
+            record.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
 /// Database wrapper for internal git-ai storage
 pub struct InternalDatabase {
     conn: Connection,
@@ -351,7 +434,7 @@ impl InternalDatabase {
 
     /// Get database path: ~/.git-ai/internal/db
     /// In test mode, can be overridden via GIT_AI_TEST_DB_PATH environment variable
-    fn database_path() -> Result<PathBuf, GitAiError> {
+    pub(crate) fn database_path() -> Result<PathBuf, GitAiError> {
         // Allow test override via environment variable
         #[cfg(any(test, feature = "test-support"))]
         if let Ok(test_path) = std::env::var("GIT_AI_TEST_DB_PATH") {
@@ -503,7 +586,8 @@ impl InternalDatabase {
             .as_ref()
             .and_then(|m| serde_json::to_string(m).ok());
 
-        self.conn.execute(
+        let tx = self.conn.transaction()?;
+        tx.execute(
             r#"
             INSERT INTO prompts (
                 id, workdir, tool, model, external_thread_id,
@@ -542,6 +626,8 @@ impl InternalDatabase {
                 record.updated_at,
             ],
         )?;
+        link_session_repo(&tx, record)?;
+        tx.commit()?;
 
         Ok(())
     }
@@ -603,6 +689,7 @@ impl InternalDatabase {
                     record.created_at,
                     record.updated_at,
                 ])?;
+                link_session_repo(&tx, record)?;
             }
         }
 
@@ -612,6 +699,34 @@ impl InternalDatabase {
 
     /// Get a prompt by ID
     pub fn get_prompt(&self, id: &str) -> Result<Option<PromptDbRecord>, GitAiError> {
+        if let Some(record) = self.get_prompt_exact(id)? {
+            return Ok(Some(record));
+        }
+        // After a `prompt_hashing` rotation the session may be stored under its id
+        // from another scheme
+        if Config::get().prompt_hashing().previous.is_empty() {
+            return Ok(None);
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, tool, external_thread_id FROM prompts")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (stored_id, tool, external_thread_id) = row?;
+            if is_previous_short_hash(id, &external_thread_id, &tool) {
+                return self.get_prompt_exact(&stored_id);
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_prompt_exact(&self, id: &str) -> Result<Option<PromptDbRecord>, GitAiError> {
         let mut stmt = self.conn.prepare(
             "SELECT id, workdir, tool, model, external_thread_id, messages,
                     commit_sha, agent_metadata, human_author,
@@ -660,6 +775,34 @@ impl InternalDatabase {
         }
     }
 
+    /// Every repository and commit linked to the session behind `prompt_id`, or
+    /// behind an agent session id shared across repositories, oldest first
+    pub fn session_repo_links(&self, id: &str) -> Result<Vec<SessionRepoLink>, GitAiError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT prompt_id, external_thread_id, tool, workdir, commit_sha, updated_at
+             FROM session_repos
+             WHERE prompt_id = ?1 OR external_thread_id = ?1
+             ORDER BY updated_at ASC, rowid ASC",
+        )?;
+        let rows = stmt.query_map(params![id], |row| {
+            let commit_sha: String = row.get(4)?;
+            Ok(SessionRepoLink {
+                prompt_id: row.get(0)?,
+                external_thread_id: row.get(1)?,
+                tool: row.get(2)?,
+                workdir: row.get(3)?,
+                commit_sha: Some(commit_sha).filter(|sha| !sha.is_empty()),
+                updated_at: row.get(5)?,
+            })
+        })?;
+
+        let mut links = Vec::new();
+        for row in rows {
+            links.push(row?);
+        }
+        Ok(links)
+    }
+
     /// Get all prompts for a given commit (future use)
     #[allow(dead_code)]
     pub fn get_prompts_by_commit(
@@ -729,7 +872,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts WHERE workdir = ?1 AND updated_at >= ?2 ORDER BY updated_at DESC LIMIT ?3 OFFSET ?4".to_string(),
+                 FROM prompts WHERE workdir = ?1 AND updated_at >= ?2 ORDER BY updated_at DESC, id DESC LIMIT ?3 OFFSET ?4".to_string(),
                 vec![Box::new(wd.to_string()), Box::new(ts), Box::new(limit as i64), Box::new(offset as i64)],
             ),
             (Some(wd), None) => (
@@ -737,7 +880,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts WHERE workdir = ?1 ORDER BY updated_at DESC LIMIT ?2 OFFSET ?3".to_string(),
+                 FROM prompts WHERE workdir = ?1 ORDER BY updated_at DESC, id DESC LIMIT ?2 OFFSET ?3".to_string(),
                 vec![Box::new(wd.to_string()), Box::new(limit as i64), Box::new(offset as i64)],
             ),
             (None, Some(ts)) => (
@@ -745,7 +888,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts WHERE updated_at >= ?1 ORDER BY updated_at DESC LIMIT ?2 OFFSET ?3".to_string(),
+                 FROM prompts WHERE updated_at >= ?1 ORDER BY updated_at DESC, id DESC LIMIT ?2 OFFSET ?3".to_string(),
                 vec![Box::new(ts), Box::new(limit as i64), Box::new(offset as i64)],
             ),
             (None, None) => (
@@ -753,7 +896,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts ORDER BY updated_at DESC LIMIT ?1 OFFSET ?2".to_string(),
+                 FROM prompts ORDER BY updated_at DESC, id DESC LIMIT ?1 OFFSET ?2".to_string(),
                 vec![Box::new(limit as i64), Box::new(offset as i64)],
             ),
         };
@@ -818,7 +961,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts WHERE messages LIKE ?1 AND workdir = ?2 ORDER BY updated_at DESC LIMIT ?3 OFFSET ?4".to_string(),
+                 FROM prompts WHERE messages LIKE ?1 AND workdir = ?2 ORDER BY updated_at DESC, id DESC LIMIT ?3 OFFSET ?4".to_string(),
                 vec![Box::new(search_pattern), Box::new(wd.to_string()), Box::new(limit as i64), Box::new(offset as i64)],
             ),
             None => (
@@ -826,7 +969,7 @@ impl InternalDatabase {
                         commit_sha, agent_metadata, human_author,
                         total_additions, total_deletions, accepted_lines,
                         overridden_lines, created_at, updated_at
-                 FROM prompts WHERE messages LIKE ?1 ORDER BY updated_at DESC LIMIT ?2 OFFSET ?3".to_string(),
+                 FROM prompts WHERE messages LIKE ?1 ORDER BY updated_at DESC, id DESC LIMIT ?2 OFFSET ?3".to_string(),
                 vec![Box::new(search_pattern), Box::new(limit as i64), Box::new(offset as i64)],
             ),
         };
@@ -1016,6 +1159,59 @@ impl InternalDatabase {
         Ok(())
     }
 
+    /// Chunk hashes already uploaded for a bundle, so an interrupted upload can resume
+    pub fn uploaded_bundle_chunks(
+        &self,
+        bundle_hash: &str,
+    ) -> Result<HashMap<usize, String>, GitAiError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT chunk_index, chunk_hash FROM bundle_upload_chunks WHERE bundle_hash = ?1",
+        )?;
+        let rows = stmt.query_map(params![bundle_hash], |row| {
+            Ok((row.get::<_, i64>(0)? as usize, row.get::<_, String>(1)?))
+        })?;
+
+        let mut chunks = HashMap::new();
+        for row in rows {
+            let (index, hash) = row?;
+            chunks.insert(index, hash);
+        }
+        Ok(chunks)
+    }
+
+    /// Record that a bundle chunk reached the server
+    pub fn mark_bundle_chunk_uploaded(
+        &mut self,
+        bundle_hash: &str,
+        chunk_index: usize,
+        chunk_hash: &str,
+    ) -> Result<(), GitAiError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO bundle_upload_chunks (
+                bundle_hash, chunk_index, chunk_hash, uploaded_at
+            ) VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![bundle_hash, chunk_index as i64, chunk_hash, now],
+        )?;
+
+        Ok(())
+    }
+
+    /// Forget a bundle's chunks once the bundle has been created
+    pub fn clear_bundle_upload(&mut self, bundle_hash: &str) -> Result<(), GitAiError> {
+        self.conn.execute(
+            "DELETE FROM bundle_upload_chunks WHERE bundle_hash = ?1",
+            params![bundle_hash],
+        )?;
+        Ok(())
+    }
+
     /// Update CAS sync record on failure (release lock, increment attempts, set next retry)
     pub fn update_cas_sync_failure(&mut self, id: i64, error: &str) -> Result<(), GitAiError> {
         let now = std::time::SystemTime::now()
@@ -1135,7 +1331,7 @@ mod tests {
                 |row| row.get(0),
             )
             .unwrap();
-        assert_eq!(version, "3");
+        assert_eq!(version, "5");
     }
 
     #[test]
@@ -1732,4 +1928,52 @@ mod tests {
         assert_eq!(calculate_next_retry(6, now), now + 24 * 60 * 60); // 24 hours
         assert_eq!(calculate_next_retry(7, now), now + 24 * 60 * 60); // 24 hours (max)
     }
+
+    #[test]
+    fn test_bundle_upload_chunks_round_trip() {
+        let (mut db, _temp_dir) = create_test_db();
+        assert!(db.uploaded_bundle_chunks("bundle").unwrap().is_empty());
+
+        db.mark_bundle_chunk_uploaded("bundle", 0, "h0").unwrap();
+        db.mark_bundle_chunk_uploaded("bundle", 2, "h2").unwrap();
+        db.mark_bundle_chunk_uploaded("other", 0, "x0").unwrap();
+
+        let chunks = db.uploaded_bundle_chunks("bundle").unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.get(&2).map(String::as_str), Some("h2"));
+
+        db.clear_bundle_upload("bundle").unwrap();
+        assert!(db.uploaded_bundle_chunks("bundle").unwrap().is_empty());
+        assert_eq!(db.uploaded_bundle_chunks("other").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_session_repo_links_span_repositories() {
+        let (mut db, _temp_dir) = create_test_db();
+        let mut record = create_test_record();
+        db.upsert_prompt(&record).unwrap();
+
+        record.workdir = Some("/test/other-repo".to_string());
+        record.updated_at += 10;
+        db.upsert_prompt(&record).unwrap();
+
+        // Committing in the first repo replaces its pending link
+        record.workdir = Some("/test/repo".to_string());
+        record.commit_sha = Some("commit-a".to_string());
+        record.updated_at += 10;
+        db.batch_upsert_prompts(std::slice::from_ref(&record))
+            .unwrap();
+
+        let links = db.session_repo_links("test-session-123").unwrap();
+        let summary: Vec<(&str, Option<&str>)> = links
+            .iter()
+            .map(|link| (link.workdir.as_str(), link.commit_sha.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("/test/other-repo", None), ("/test/repo", Some("commit-a"))]
+        );
+        assert_eq!(db.session_repo_links(&record.id).unwrap(), links);
+        assert!(db.session_repo_links("unknown").unwrap().is_empty());
+    }
 }
@@ -1,18 +1,26 @@
+pub mod attribution_cache;
 pub mod attribution_tracker;
 pub mod authorship_log;
 pub mod authorship_log_serialization;
+pub mod context_bundle;
 pub mod diff_ai_accepted;
+pub mod identity;
 pub mod ignore;
 pub mod imara_diff_utils;
 pub mod internal_db;
 pub mod move_detection;
+pub mod note_composition;
+pub mod pairing;
 pub mod post_commit;
 pub mod pre_commit;
+pub mod prompt_interning;
 pub mod prompt_utils;
 pub mod range_authorship;
 pub mod rebase_authorship;
 pub mod secrets;
+pub mod stale_working_log;
 pub mod stats;
+pub mod tag_attribution;
 pub mod transcript;
 pub mod virtual_attribution;
 pub mod working_log;
@@ -0,0 +1,370 @@
+//! Compose the authorship notes of a run of commits into one note for a commit
+//! that replaces them (a local squash, or a fixup folded into its target).
+//!
+//! Rather than re-deriving attribution with blame, this walks the commits in
+//! order and carries each line's owner forward through the diffs: a line keeps
+//! its owner while it survives unchanged, and a line a commit inserts takes the
+//! owner that commit's note attests. Lines that came from before the run stay
+//! unowned, so the composed note only attests what the run itself wrote.
+
+use crate::authorship::authorship_log::{CharRange, LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{
+    AUTHORSHIP_LOG_VERSION, AUTHORSHIP_LOG_VERSION_V4, AttestationEntry, AuthorshipLog,
+    FileAttestation,
+};
+use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
+use crate::error::GitAiError;
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::{Repository, exec_git};
+use std::collections::{BTreeMap, HashMap};
+
+/// Who wrote one line, as far as the notes say
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LineOwner {
+    /// Prompt that wrote the whole line
+    prompt: Option<String>,
+    /// Prompts that wrote part of an otherwise human line: (prompt, start, end)
+    chars: Vec<(String, u32, u32)>,
+}
+
+/// A file that changed between two trees
+#[derive(Debug, Clone, PartialEq)]
+struct TreeChange {
+    /// Path before, for modifications, renames and deletions
+    old_path: Option<String>,
+    /// Path after; `None` when deleted
+    new_path: Option<String>,
+}
+
+/// The note for `result_sha`, which replaces `commits` (oldest first) on top of
+/// `base` (`None` for a root). `None` when none of the commits has a note.
+pub fn compose_authorship_for_commits(
+    repo: &Repository,
+    base: Option<&str>,
+    commits: &[String],
+    result_sha: &str,
+) -> Result<Option<AuthorshipLog>, GitAiError> {
+    let logs: Vec<Option<AuthorshipLog>> = commits
+        .iter()
+        .map(|sha| get_reference_as_authorship_log_v3(repo, sha).ok())
+        .collect();
+    if logs.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let mut owners: HashMap<String, Vec<LineOwner>> = HashMap::new();
+    let mut prev = base.unwrap_or(EMPTY_TREE_HASH).to_string();
+    for (sha, log) in commits.iter().zip(&logs) {
+        apply_commit(repo, &mut owners, &prev, sha, log.as_ref())?;
+        prev = sha.clone();
+    }
+    // The result may not match the last commit exactly (e.g. a fixup moved
+    // before later commits); lines only it has are unowned
+    if prev != result_sha {
+        apply_commit(repo, &mut owners, &prev, result_sha, None)?;
+    }
+
+    let mut composed = build_log(&owners);
+    composed.metadata.base_commit_sha = result_sha.to_string();
+    merge_metadata(&mut composed, logs.iter().flatten());
+    Ok(Some(composed))
+}
+
+/// Carry `owners` from `from` to `to`, taking inserted lines' owners from `log`
+fn apply_commit(
+    repo: &Repository,
+    owners: &mut HashMap<String, Vec<LineOwner>>,
+    from: &str,
+    to: &str,
+    log: Option<&AuthorshipLog>,
+) -> Result<(), GitAiError> {
+    for change in tree_changes(repo, from, to)? {
+        let previous = change
+            .old_path
+            .as_ref()
+            .and_then(|old_path| owners.remove(old_path));
+        let Some(new_path) = change.new_path else {
+            continue;
+        };
+
+        let old_content = match &change.old_path {
+            Some(old_path) => file_text(repo, from, old_path)?,
+            None => String::new(),
+        };
+        let new_content = file_text(repo, to, &new_path)?;
+        let attested = log.map(|log| attested_lines(log, &new_path));
+
+        let mut old_owners = previous.unwrap_or_default().into_iter();
+        let mut new_owners = Vec::new();
+        for line in compute_line_changes(&old_content, &new_content) {
+            match line.tag() {
+                LineChangeTag::Equal => new_owners.push(old_owners.next().unwrap_or_default()),
+                LineChangeTag::Delete => {
+                    old_owners.next();
+                }
+                LineChangeTag::Insert => {
+                    let line_no = new_owners.len() as u32 + 1;
+                    let owner = attested
+                        .as_ref()
+                        .and_then(|attested| attested.get(&line_no).cloned())
+                        .unwrap_or_default();
+                    new_owners.push(owner);
+                }
+            }
+        }
+        if new_owners
+            .iter()
+            .any(|owner| *owner != LineOwner::default())
+        {
+            owners.insert(new_path, new_owners);
+        }
+    }
+    Ok(())
+}
+
+/// Owners `log` attests for `path`, by line; later entries win, as in blame
+fn attested_lines(log: &AuthorshipLog, path: &str) -> HashMap<u32, LineOwner> {
+    let mut lines: HashMap<u32, LineOwner> = HashMap::new();
+    let Some(file) = log.attestations.iter().find(|file| file.file_path == path) else {
+        return lines;
+    };
+    for entry in &file.entries {
+        for range in &entry.line_ranges {
+            for line in range.expand() {
+                lines.entry(line).or_default().prompt = Some(entry.hash.clone());
+            }
+        }
+        for range in &entry.char_ranges {
+            lines.entry(range.line).or_default().chars.push((
+                entry.hash.clone(),
+                range.start,
+                range.end,
+            ));
+        }
+    }
+    lines
+}
+
+fn build_log(owners: &HashMap<String, Vec<LineOwner>>) -> AuthorshipLog {
+    let mut log = AuthorshipLog::new();
+    let mut has_char_ranges = false;
+
+    let mut paths: Vec<&String> = owners.keys().collect();
+    paths.sort();
+    for path in paths {
+        let mut lines_by_prompt: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+        let mut chars_by_prompt: BTreeMap<&str, Vec<CharRange>> = BTreeMap::new();
+        for (index, owner) in owners[path].iter().enumerate() {
+            let line = index as u32 + 1;
+            if let Some(prompt) = &owner.prompt {
+                lines_by_prompt.entry(prompt).or_default().push(line);
+            }
+            for (prompt, start, end) in &owner.chars {
+                chars_by_prompt
+                    .entry(prompt)
+                    .or_default()
+                    .push(CharRange::new(line, *start, *end));
+            }
+        }
+
+        let mut file = FileAttestation::new(path.clone());
+        let mut prompts: Vec<&str> = lines_by_prompt
+            .keys()
+            .chain(chars_by_prompt.keys())
+            .copied()
+            .collect();
+        prompts.sort_unstable();
+        prompts.dedup();
+        for prompt in prompts {
+            let lines = lines_by_prompt.remove(prompt).unwrap_or_default();
+            let mut entry =
+                AttestationEntry::new(prompt.to_string(), LineRange::compress_lines(&lines));
+            entry.char_ranges = chars_by_prompt.remove(prompt).unwrap_or_default();
+            has_char_ranges |= !entry.char_ranges.is_empty();
+            file.add_entry(entry);
+        }
+        if !file.entries.is_empty() {
+            log.attestations.push(file);
+        }
+    }
+
+    log.metadata.schema_version = if has_char_ranges {
+        AUTHORSHIP_LOG_VERSION_V4
+    } else {
+        AUTHORSHIP_LOG_VERSION
+    }
+    .to_string();
+    log
+}
+
+/// Union of the constituent notes' prompts, humans, environment, generated
+/// files and submodule bumps. Session and generated-file totals add up; accepted
+/// lines are recounted from the composed attestations.
+fn merge_metadata<'a>(composed: &mut AuthorshipLog, logs: impl Iterator<Item = &'a AuthorshipLog>) {
+    let mut prompts: BTreeMap<String, PromptRecord> = BTreeMap::new();
+    for log in logs {
+        for (prompt_id, record) in &log.metadata.prompts {
+            match prompts.get_mut(prompt_id) {
+                Some(merged) => {
+                    let (additions, deletions, overridden) = (
+                        merged
+                            .total_additions
+                            .saturating_add(record.total_additions),
+                        merged
+                            .total_deletions
+                            .saturating_add(record.total_deletions),
+                        merged
+                            .overriden_lines
+                            .saturating_add(record.overriden_lines),
+                    );
+                    // The latest record has the fullest transcript
+                    *merged = record.clone();
+                    merged.total_additions = additions;
+                    merged.total_deletions = deletions;
+                    merged.overriden_lines = overridden;
+                }
+                None => {
+                    prompts.insert(prompt_id.clone(), record.clone());
+                }
+            }
+        }
+        for human in &log.metadata.humans {
+            if !composed.metadata.humans.contains(human) {
+                composed.metadata.humans.push(human.clone());
+            }
+        }
+        if log.metadata.environment.is_some() {
+            composed.metadata.environment = log.metadata.environment.clone();
+        }
+        composed.metadata.merge_generated(&log.metadata);
+        // Logs come oldest first
+        composed.metadata.merge_submodules(&log.metadata);
+    }
+
+    for (prompt_id, record) in prompts.iter_mut() {
+        record.accepted_lines = composed
+            .attestations
+            .iter()
+            .flat_map(|file| &file.entries)
+            .filter(|entry| entry.hash == *prompt_id)
+            .flat_map(|entry| &entry.line_ranges)
+            .map(|range| range.expand().len() as u32)
+            .sum();
+    }
+    composed.metadata.prompts = prompts;
+}
+
+fn file_text(repo: &Repository, commit: &str, path: &str) -> Result<String, GitAiError> {
+    let content = repo.get_file_content(path, commit)?;
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// Files that differ between two trees, with renames paired up
+fn tree_changes(repo: &Repository, from: &str, to: &str) -> Result<Vec<TreeChange>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["diff-tree", "-r", "-z", "-M", "--name-status", from, to].map(String::from));
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_name_status(&stdout))
+}
+
+/// Parse `diff-tree -z --name-status` output: a status, then one path (two for
+/// renames and copies), each NUL-terminated
+fn parse_name_status(output: &str) -> Vec<TreeChange> {
+    let mut changes = Vec::new();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let Some(status) = fields.next() {
+        let Some(path) = fields.next() else {
+            break;
+        };
+        let change = match status.chars().next() {
+            Some('A') => TreeChange {
+                old_path: None,
+                new_path: Some(path.to_string()),
+            },
+            Some('D') => TreeChange {
+                old_path: Some(path.to_string()),
+                new_path: None,
+            },
+            Some('R') | Some('C') => {
+                let Some(new_path) = fields.next() else {
+                    break;
+                };
+                TreeChange {
+                    // A copy leaves the original where it was
+                    old_path: status.starts_with('R').then(|| path.to_string()),
+                    new_path: Some(new_path.to_string()),
+                }
+            }
+            _ => TreeChange {
+                old_path: Some(path.to_string()),
+                new_path: Some(path.to_string()),
+            },
+        };
+        changes.push(change);
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\0src/lib.rs\0R087\0old.rs\0new.rs\0A\0added.rs\0D\0gone.rs\0";
+        assert_eq!(
+            parse_name_status(output),
+            vec![
+                TreeChange {
+                    old_path: Some("src/lib.rs".to_string()),
+                    new_path: Some("src/lib.rs".to_string()),
+                },
+                TreeChange {
+                    old_path: Some("old.rs".to_string()),
+                    new_path: Some("new.rs".to_string()),
+                },
+                TreeChange {
+                    old_path: None,
+                    new_path: Some("added.rs".to_string()),
+                },
+                TreeChange {
+                    old_path: Some("gone.rs".to_string()),
+                    new_path: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_log_groups_lines_by_prompt() {
+        let ai = |prompt: &str| LineOwner {
+            prompt: Some(prompt.to_string()),
+            chars: Vec::new(),
+        };
+        let mut owners = HashMap::new();
+        owners.insert(
+            "lib.rs".to_string(),
+            vec![
+                ai("aaaaaaa"),
+                ai("aaaaaaa"),
+                LineOwner::default(),
+                ai("bbbbbbb"),
+                LineOwner {
+                    prompt: None,
+                    chars: vec![("aaaaaaa".to_string(), 4, 9)],
+                },
+            ],
+        );
+
+        let log = build_log(&owners);
+        assert_eq!(log.metadata.schema_version, AUTHORSHIP_LOG_VERSION_V4);
+        let entries = &log.attestations[0].entries;
+        assert_eq!(entries[0].hash, "aaaaaaa");
+        assert_eq!(entries[0].line_ranges, vec![LineRange::Range(1, 2)]);
+        assert_eq!(entries[0].char_ranges, vec![CharRange::new(5, 4, 9)]);
+        assert_eq!(entries[1].hash, "bbbbbbb");
+        assert_eq!(entries[1].line_ranges, vec![LineRange::Single(4)]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Shared attribution for pairing and mob programming.
+//!
+//! A commit's human lines are normally credited to the committer alone. When
+//! other people worked on it too — named with `git-ai checkpoint --with <user>` or
+//! a `Co-authored-by:` trailer — every participant is recorded in the authorship
+//! log and the human additions are split evenly between them.
+
+use crate::authorship::identity::IdentityResolver;
+use crate::authorship::working_log::Checkpoint;
+use std::collections::BTreeMap;
+
+const CO_AUTHORED_BY_TRAILER: &str = "co-authored-by:";
+
+/// `Co-authored-by:` trailers in a commit message, in order
+pub fn parse_co_authored_by(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let prefix = line.get(..CO_AUTHORED_BY_TRAILER.len())?;
+            if !prefix.eq_ignore_ascii_case(CO_AUTHORED_BY_TRAILER) {
+                return None;
+            }
+            let value = line[CO_AUTHORED_BY_TRAILER.len()..].trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })
+        .collect()
+}
+
+/// Everyone who worked on a commit: the driver first, then checkpoint co-authors,
+/// then trailer co-authors. Identities are canonicalised and deduplicated. Returns
+/// an empty list for solo work so single-author logs stay unchanged.
+pub fn collect_humans(
+    driver: &str,
+    checkpoints: &[Checkpoint],
+    trailer_co_authors: &[String],
+    resolver: &IdentityResolver,
+) -> Vec<String> {
+    let mut humans: Vec<String> = Vec::new();
+    let candidates = std::iter::once(driver)
+        .chain(
+            checkpoints
+                .iter()
+                .flat_map(|c| c.co_authors.iter().map(String::as_str)),
+        )
+        .chain(trailer_co_authors.iter().map(String::as_str));
+    for candidate in candidates {
+        if candidate.trim().is_empty() {
+            continue;
+        }
+        let canonical = resolver.resolve(candidate);
+        if !humans.iter().any(|h| h.eq_ignore_ascii_case(&canonical)) {
+            humans.push(canonical);
+        }
+    }
+
+    if humans.len() > 1 { humans } else { Vec::new() }
+}
+
+/// Split `human_additions` evenly across `humans`. Leftover lines go to the
+/// earliest entries, so the driver never gets less than a partner.
+pub fn split_human_additions(human_additions: u32, humans: &[String]) -> BTreeMap<String, u32> {
+    let mut shares = BTreeMap::new();
+    if humans.is_empty() {
+        return shares;
+    }
+    let count = humans.len() as u32;
+    let (base, remainder) = (human_additions / count, human_additions % count);
+    for (i, human) in humans.iter().enumerate() {
+        let extra = u32::from((i as u32) < remainder);
+        *shares.entry(human.clone()).or_insert(0) += base + extra;
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::CheckpointKind;
+
+    #[test]
+    fn test_parse_co_authored_by() {
+        let message = "Fix parser\n\nLonger description.\n\nCo-authored-by: Ana <ana@corp.com>\nco-authored-by:   Bo <bo@corp.com>  \nSigned-off-by: Me <me@corp.com>\nCo-authored-by:\n";
+        assert_eq!(
+            parse_co_authored_by(message),
+            vec!["Ana <ana@corp.com>", "Bo <bo@corp.com>"]
+        );
+    }
+
+    #[test]
+    fn test_collect_humans_dedupes_and_keeps_driver_first() {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            "Ana <ana@corp.com>".to_string(),
+            vec!["ana@home.net".to_string()],
+        );
+        let resolver = IdentityResolver::new(&aliases);
+
+        let mut checkpoint = Checkpoint::new(
+            CheckpointKind::Human,
+            String::new(),
+            "Me".to_string(),
+            vec![],
+        );
+        checkpoint.co_authors = vec!["ana@home.net".to_string()];
+
+        let humans = collect_humans(
+            "Me <me@corp.com>",
+            &[checkpoint],
+            &[
+                "Ana <ana@corp.com>".to_string(),
+                "Bo <bo@corp.com>".to_string(),
+            ],
+            &resolver,
+        );
+        assert_eq!(
+            humans,
+            vec!["Me <me@corp.com>", "Ana <ana@corp.com>", "Bo <bo@corp.com>"]
+        );
+
+        assert!(collect_humans("Me <me@corp.com>", &[], &[], &resolver).is_empty());
+    }
+
+    #[test]
+    fn test_split_human_additions() {
+        let humans = vec!["Me".to_string(), "Ana".to_string(), "Bo".to_string()];
+        let shares = split_human_additions(10, &humans);
+        assert_eq!(shares["Me"], 4);
+        assert_eq!(shares["Ana"], 3);
+        assert_eq!(shares["Bo"], 3);
+        assert_eq!(shares.values().sum::<u32>(), 10);
+
+        assert!(split_human_additions(10, &[]).is_empty());
+    }
+}
@@ -1,19 +1,36 @@
 use crate::api::{ApiClient, ApiContext};
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::attribution_tracker::ai_char_ranges_by_line;
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::{
+    AuthorshipLog, EnvironmentFingerprint, GIT_AI_VERSION, GeneratedFiles, SubmoduleUpdate,
+};
+use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::authorship::identity::IdentityResolver;
 use crate::authorship::ignore::{
     build_ignore_matcher, effective_ignore_patterns, should_ignore_file_with_matcher,
 };
+use crate::authorship::pairing::{collect_humans, parse_co_authored_by};
+use crate::authorship::prompt_interning::intern_prompt_messages;
 use crate::authorship::prompt_utils::{PromptUpdateResult, update_prompt_from_tool};
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
 use crate::authorship::secrets::{redact_secrets_from_prompts, strip_prompt_messages};
 use crate::authorship::stats::{stats_for_commit_stats, write_stats_to_terminal};
-use crate::authorship::virtual_attribution::VirtualAttributions;
-use crate::authorship::working_log::{Checkpoint, CheckpointKind, WorkingLogEntry};
-use crate::config::{Config, PromptStorageMode};
+use crate::authorship::virtual_attribution::{VirtualAttributions, collect_committed_hunks};
+use crate::authorship::working_log::{
+    AGENT_VERSION_METADATA_KEY, AiClassification, Checkpoint, CheckpointKind, WorkingLogEntry,
+};
+use crate::config::{AttributionGranularity, Config, PromptStorageMode};
 use crate::error::GitAiError;
 use crate::git::refs::notes_add;
-use crate::git::repository::Repository;
+use crate::git::repository::{
+    GitMarker, Repository, exec_git, exec_git_stdin, find_repository_in_path, git_marker,
+};
+use crate::observability::budgets::time_phase;
+use crate::observability::profile;
+use crate::observability::webhook::{LocalEventKind, emit_local_event};
 use crate::utils::debug_log;
-use std::collections::{HashMap, HashSet};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::IsTerminal;
 
 /// Skip expensive post-commit stats when this threshold is exceeded.
@@ -56,6 +73,7 @@ pub fn post_commit(
     human_author: String,
     supress_output: bool,
 ) -> Result<(String, AuthorshipLog), GitAiError> {
+    let _profile_scope = profile::scope("post_commit");
     // Use base_commit parameter if provided, otherwise use "initial" for empty repos
     // This matches the convention in checkpoint.rs
     let parent_sha = base_commit.unwrap_or_else(|| "initial".to_string());
@@ -124,16 +142,77 @@ pub fn post_commit(
     }
 
     // Split VirtualAttributions into committed (authorship log) and uncommitted (INITIAL)
-    let (mut authorship_log, initial_attributions) = working_va
-        .to_authorship_log_and_initial_working_log(
+    let (mut authorship_log, initial_attributions) = time_phase("attribution", || {
+        working_va.to_authorship_log_and_initial_working_log(
             repo,
             &parent_sha,
             &commit_sha,
             Some(&pathspecs),
-        )?;
+        )
+    })?;
 
     authorship_log.metadata.base_commit_sha = commit_sha.clone();
 
+    if Config::get().attribution_granularity() == AttributionGranularity::Char {
+        time_phase("sub_line_attribution", || {
+            record_sub_line_attributions(
+                repo,
+                &parent_sha,
+                &commit_sha,
+                &parent_working_log,
+                &mut authorship_log,
+            )
+        })?;
+    }
+
+    // Record pairing partners from `checkpoint --with` and Co-authored-by trailers
+    let trailer_co_authors = repo
+        .find_commit(commit_sha.clone())
+        .and_then(|commit| commit.body())
+        .map(|body| parse_co_authored_by(&body))
+        .unwrap_or_default();
+    authorship_log.metadata.humans = collect_humans(
+        &human_author,
+        &parent_working_log,
+        &trailer_co_authors,
+        &IdentityResolver::from_config(false),
+    );
+
+    if Config::get().capture_environment() {
+        authorship_log.metadata.environment =
+            Some(Box::new(capture_environment(&parent_working_log)));
+    }
+
+    // Paths under tracking rules get no line attribution, even from attributions
+    // carried over from before the rules were added
+    let tracking = Config::get().tracking();
+    if !tracking.exclude.is_empty() || !tracking.generated.is_empty() {
+        let untracked = build_ignore_matcher(
+            &[tracking.exclude.as_slice(), tracking.generated.as_slice()].concat(),
+        );
+        authorship_log
+            .attestations
+            .retain(|attestation| !untracked.is_ignored(&attestation.file_path));
+    }
+    if !tracking.generated.is_empty() {
+        match summarize_generated_files(repo, &commit_sha, &tracking.generated, &parent_working_log)
+        {
+            Ok(generated) => authorship_log.metadata.generated = generated,
+            Err(e) => debug_log(&format!(
+                "[Warning] Failed to summarize generated files: {}",
+                e
+            )),
+        }
+    }
+
+    match submodule_updates(repo, &parent_sha, &commit_sha) {
+        Ok(submodules) => authorship_log.metadata.submodules = submodules,
+        Err(e) => debug_log(&format!(
+            "[Warning] Failed to record submodule updates: {}",
+            e
+        )),
+    }
+
     // Handle prompts based on effective prompt storage mode for this repository
     // The effective mode considers include/exclude lists and fallback settings
     let effective_storage = Config::get().effective_prompt_storage(&Some(repo.clone()));
@@ -149,6 +228,16 @@ pub fn post_commit(
             if count > 0 {
                 debug_log(&format!("Redacted {} secrets from prompts", count));
             }
+            if Config::get().feature_flags().intern_prompts
+                && let Err(e) =
+                    intern_prompt_messages(repo, authorship_log.metadata.prompts.values_mut())
+            {
+                // Messages stay inline in the note
+                debug_log(&format!(
+                    "[Warning] Failed to intern prompt messages: {}",
+                    e
+                ));
+            }
         }
         PromptStorageMode::Default => {
             // "default" - attempt CAS upload, NEVER keep messages in notes
@@ -194,7 +283,9 @@ pub fn post_commit(
         .serialize_to_string()
         .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
 
-    notes_add(repo, &commit_sha, &authorship_json)?;
+    time_phase("note_write", || {
+        notes_add(repo, &commit_sha, &authorship_json)
+    })?;
 
     // Compute stats once (needed for both metrics and terminal output), unless preflight
     // estimate predicts this would be too expensive for the commit hook path.
@@ -219,7 +310,9 @@ pub fn post_commit(
     };
 
     if skip_reason.is_none() {
-        let computed = stats_for_commit_stats(repo, &commit_sha, &ignore_patterns)?;
+        let computed = time_phase("stats", || {
+            stats_for_commit_stats(repo, &commit_sha, &ignore_patterns)
+        })?;
         // Record metrics only when we have full stats.
         record_commit_metrics(
             repo,
@@ -262,6 +355,17 @@ pub fn post_commit(
     // // Clean up old working log
     repo_storage.delete_working_log_for_base_commit(&parent_sha)?;
 
+    emit_local_event(
+        LocalEventKind::CommitProcessed,
+        Some(repo.canonical_workdir().display().to_string()),
+        serde_json::json!({
+            "commit_sha": commit_sha,
+            "parent_sha": parent_sha,
+            "human_author": human_author,
+            "stats": stats,
+        }),
+    );
+
     if !supress_output && !Config::get().is_quiet() {
         // Only print stats if we're in an interactive terminal and quiet mode is disabled
         let is_interactive = std::io::stdout().is_terminal();
@@ -297,6 +401,227 @@ enum StatsSkipReason {
     Expensive(StatsCostEstimate),
 }
 
+/// Add the AI-written parts of lines this commit added but that count as human,
+/// because a human edited them after the agent (`attribution_granularity = "char"`).
+/// Uses each file's last checkpoint, and only when it matches the committed content,
+/// so partially staged files keep line-level attribution. Moves the log to
+/// authorship/4.0.0 when anything was added.
+pub(crate) fn record_sub_line_attributions(
+    repo: &Repository,
+    parent_sha: &str,
+    commit_sha: &str,
+    checkpoints: &[Checkpoint],
+    authorship_log: &mut AuthorshipLog,
+) -> Result<(), GitAiError> {
+    let mut latest_entries: HashMap<&str, &WorkingLogEntry> = HashMap::new();
+    for checkpoint in checkpoints {
+        for entry in &checkpoint.entries {
+            latest_entries.insert(entry.file.as_str(), entry);
+        }
+    }
+    latest_entries.retain(|_, entry| {
+        entry
+            .attributions
+            .iter()
+            .any(|attr| attr.author_id != CheckpointKind::Human.to_str())
+    });
+    if latest_entries.is_empty() {
+        return Ok(());
+    }
+
+    let pathspecs: HashSet<String> = latest_entries.keys().map(|f| f.to_string()).collect();
+    let committed_hunks = collect_committed_hunks(repo, parent_sha, commit_sha, Some(&pathspecs))?;
+
+    for (file, entry) in latest_entries {
+        let Some(hunks) = committed_hunks.get(file) else {
+            continue;
+        };
+        let Ok(content) = repo
+            .get_file_content(file, commit_sha)
+            .map(String::from_utf8)
+        else {
+            continue;
+        };
+        let Ok(content) = content else {
+            continue;
+        };
+        if format!("{:x}", Sha256::digest(content.as_bytes())) != entry.blob_sha {
+            debug_log(&format!(
+                "sub-line attribution: {} changed since its last checkpoint, keeping lines only",
+                file
+            ));
+            continue;
+        }
+
+        for (author_id, range) in ai_char_ranges_by_line(&entry.attributions, &content) {
+            if !hunks.iter().any(|hunk| hunk.contains(range.line))
+                || !authorship_log.metadata.prompts.contains_key(&author_id)
+            {
+                continue;
+            }
+            authorship_log.add_char_range(file, &author_id, range);
+        }
+    }
+    Ok(())
+}
+
+/// Files the commit changed under each `tracking.generated` rule (the first
+/// matching rule wins), with their size at the commit and how many of them an
+/// agent edited
+pub(crate) fn summarize_generated_files(
+    repo: &Repository,
+    commit_sha: &str,
+    rules: &[String],
+    checkpoints: &[Checkpoint],
+) -> Result<BTreeMap<String, GeneratedFiles>, GitAiError> {
+    let matcher = build_ignore_matcher(rules);
+    let mut matched: Vec<(String, &str)> = repo
+        .list_commit_files(commit_sha, None)?
+        .into_iter()
+        .filter_map(|path| {
+            let rule = rules.get(matcher.first_match(&path)?)?;
+            Some((path, rule.as_str()))
+        })
+        .collect();
+    if matched.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    matched.sort();
+
+    let ai_edited: HashSet<&str> = checkpoints
+        .iter()
+        .filter(|checkpoint| checkpoint.kind != CheckpointKind::Human)
+        .flat_map(|checkpoint| &checkpoint.generated_files)
+        .map(String::as_str)
+        .collect();
+
+    // Deleted files come back missing and aren't counted
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file", "--batch-check=%(objectsize)"].map(String::from));
+    let stdin: String = matched
+        .iter()
+        .map(|(path, _)| format!("{}:{}\n", commit_sha, path))
+        .collect();
+    let output = exec_git_stdin(&args, stdin.as_bytes())?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    let mut generated: BTreeMap<String, GeneratedFiles> = BTreeMap::new();
+    for ((path, rule), line) in matched.iter().zip(stdout.lines()) {
+        let Ok(bytes) = line.trim().parse::<u64>() else {
+            continue;
+        };
+        let summary = generated.entry(rule.to_string()).or_default();
+        summary.files += 1;
+        summary.bytes += bytes;
+        if ai_edited.contains(path.as_str()) {
+            summary.ai_files += 1;
+        }
+    }
+    Ok(generated)
+}
+
+/// Submodule pointers the commit changed, with the AI share of the lines
+/// added in each submodule between the old and new commits
+pub(crate) fn submodule_updates(
+    repo: &Repository,
+    parent_sha: &str,
+    commit_sha: &str,
+) -> Result<BTreeMap<String, SubmoduleUpdate>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["diff-tree", "-r", "-z", "--no-commit-id", "--no-renames"].map(String::from));
+    if parent_sha == "initial" {
+        args.push("--root".to_string());
+    } else {
+        args.push(parent_sha.to_string());
+    }
+    args.push(commit_sha.to_string());
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    // `:<old mode> <new mode> <old sha> <new sha> <status>\0<path>\0` per file
+    let mut updates = BTreeMap::new();
+    let mut fields = stdout.split('\0');
+    while let (Some(header), Some(path)) = (fields.next(), fields.next()) {
+        let parts: Vec<&str> = header.trim_start_matches(':').split(' ').collect();
+        let [old_mode, new_mode, old_sha, new_sha, _status] = parts[..] else {
+            continue;
+        };
+        // Removed submodules need no attribution
+        if new_mode != "160000" {
+            continue;
+        }
+        let mut update = SubmoduleUpdate {
+            from: (old_mode == "160000").then(|| old_sha.to_string()),
+            to: new_sha.to_string(),
+            ..Default::default()
+        };
+        if let Some((added_lines, ai_accepted)) =
+            submodule_ai_lines(repo, path, update.from.as_deref(), &update.to)
+        {
+            update.added_lines = Some(added_lines);
+            update.ai_accepted = Some(ai_accepted);
+        }
+        updates.insert(path.to_string(), update);
+    }
+    Ok(updates)
+}
+
+/// Lines added in the submodule at `path` from `from` (or nothing) to `to`, and
+/// how many of them its notes attribute to AI. `None` when the submodule isn't
+/// checked out, lacks the commits, or the range is too large to blame here.
+fn submodule_ai_lines(
+    repo: &Repository,
+    path: &str,
+    from: Option<&str>,
+    to: &str,
+) -> Option<(u32, u32)> {
+    let dir = repo.workdir().ok()?.join(path);
+    // An uninitialized submodule is an empty directory inside the superproject
+    if !matches!(
+        git_marker(&dir),
+        Some(GitMarker::Submodule | GitMarker::Repository)
+    ) {
+        return None;
+    }
+    let submodule = find_repository_in_path(&dir.to_string_lossy()).ok()?;
+    let from = from.unwrap_or(EMPTY_TREE_HASH);
+
+    let added = submodule.diff_added_lines(from, to, None).ok()?;
+    let added_lines: usize = added.values().map(Vec::len).sum();
+    if added.len() > STATS_SKIP_MAX_FILES_WITH_ADDITIONS || added_lines > STATS_SKIP_MAX_ADDED_LINES
+    {
+        return None;
+    }
+    let stats = diff_ai_accepted_stats(&submodule, from, to, None, &[]).ok()?;
+    Some((added_lines as u32, stats.total_ai_accepted))
+}
+
+/// The OS, git, git-ai and agent versions behind this commit. Agent versions come
+/// from the checkpoints; when an agent reports several, the latest wins.
+fn capture_environment(checkpoints: &[Checkpoint]) -> EnvironmentFingerprint {
+    let mut agents = std::collections::BTreeMap::new();
+    for checkpoint in checkpoints {
+        if let (Some(agent_id), Some(version)) = (
+            &checkpoint.agent_id,
+            checkpoint
+                .agent_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(AGENT_VERSION_METADATA_KEY)),
+        ) {
+            agents.insert(agent_id.tool.clone(), version.clone());
+        }
+    }
+    EnvironmentFingerprint {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        git_version: crate::git::real_git::git_version(std::path::Path::new(
+            Config::get().git_cmd(),
+        )),
+        git_ai_version: GIT_AI_VERSION.to_string(),
+        agents,
+    }
+}
+
 fn should_skip_expensive_post_commit_stats(estimate: &StatsCostEstimate) -> bool {
     estimate.hunk_ranges >= STATS_SKIP_MAX_HUNKS
         || estimate.added_lines >= STATS_SKIP_MAX_ADDED_LINES
@@ -539,6 +864,33 @@ fn enqueue_prompt_messages_to_cas(
     Ok(())
 }
 
+/// Committed AI lines split into (assisted, generated) using each prompt's
+/// classification. Prompts recorded before classifications existed count as generated.
+fn ai_lines_by_classification(authorship_log: &AuthorshipLog) -> (u32, u32) {
+    let mut assisted = 0u32;
+    let mut generated = 0u32;
+    for file in &authorship_log.attestations {
+        for entry in &file.entries {
+            let Some(prompt) = authorship_log.metadata.prompts.get(&entry.hash) else {
+                continue;
+            };
+            let lines: u32 = entry
+                .line_ranges
+                .iter()
+                .map(|range| match range {
+                    LineRange::Single(_) => 1,
+                    LineRange::Range(start, end) => end.saturating_sub(*start) + 1,
+                })
+                .sum();
+            match prompt.classification {
+                Some(AiClassification::Assisted) => assisted += lines,
+                _ => generated += lines,
+            }
+        }
+    }
+    (assisted, generated)
+}
+
 /// Record metrics for a committed change.
 /// This is a best-effort operation - failures are silently ignored.
 fn record_commit_metrics(
@@ -546,7 +898,7 @@ fn record_commit_metrics(
     commit_sha: &str,
     parent_sha: &str,
     human_author: &str,
-    _authorship_log: &AuthorshipLog,
+    authorship_log: &AuthorshipLog,
     stats: &crate::authorship::stats::CommitStats,
     checkpoints: &[Checkpoint],
 ) {
@@ -585,6 +937,11 @@ fn record_commit_metrics(
         .total_ai_deletions(total_ai_deletions)
         .time_waiting_for_ai(time_waiting_for_ai);
 
+    let (assisted, generated) = ai_lines_by_classification(authorship_log);
+    let values = values
+        .ai_assisted_additions(assisted)
+        .ai_generated_additions(generated);
+
     // Add first checkpoint timestamp (null if no checkpoints)
     let values = if let Some(first) = checkpoints.first() {
         values.first_checkpoint_ts(first.timestamp)
@@ -610,7 +967,7 @@ fn record_commit_metrics(
     let mut attrs = EventAttributes::with_version(env!("CARGO_PKG_VERSION"));
 
     attrs = attrs
-        .author(human_author)
+        .author(IdentityResolver::from_config(false).resolve(human_author))
         .commit_sha(commit_sha)
         .base_commit_sha(parent_sha);
 
@@ -642,6 +999,57 @@ mod tests {
     };
     use crate::git::test_utils::TmpRepo;
 
+    #[test]
+    fn test_ai_lines_by_classification() {
+        use super::ai_lines_by_classification;
+        use crate::authorship::authorship_log::{LineRange, PromptRecord};
+        use crate::authorship::authorship_log_serialization::{AttestationEntry, AuthorshipLog};
+        use crate::authorship::working_log::{AgentId, AiClassification};
+
+        let prompt = |classification| PromptRecord {
+            agent_id: AgentId {
+                tool: "tool".to_string(),
+                id: "id".to_string(),
+                model: "model".to_string(),
+            },
+            human_author: None,
+            messages: vec![],
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            messages_url: None,
+            messages_ref: None,
+            classification,
+        };
+        let mut log = AuthorshipLog::new();
+        log.metadata
+            .prompts
+            .insert("tab".to_string(), prompt(Some(AiClassification::Assisted)));
+        log.metadata.prompts.insert(
+            "agent".to_string(),
+            prompt(Some(AiClassification::Generated)),
+        );
+        log.metadata
+            .prompts
+            .insert("legacy".to_string(), prompt(None));
+        let file = log.get_or_create_file("src/lib.rs");
+        file.add_entry(AttestationEntry::new(
+            "tab".to_string(),
+            vec![LineRange::Single(1), LineRange::Range(3, 4)],
+        ));
+        file.add_entry(AttestationEntry::new(
+            "agent".to_string(),
+            vec![LineRange::Range(10, 14)],
+        ));
+        file.add_entry(AttestationEntry::new(
+            "legacy".to_string(),
+            vec![LineRange::Single(20)],
+        ));
+
+        assert_eq!(ai_lines_by_classification(&log), (3, 6));
+    }
+
     #[test]
     fn test_count_line_ranges_handles_scattered_and_contiguous_lines() {
         assert_eq!(count_line_ranges(&[]), 0);
@@ -1,12 +1,45 @@
-use crate::authorship::working_log::CheckpointKind;
+use crate::authorship::working_log::{AGENT_INFERRED_METADATA_KEY, CheckpointKind};
+use crate::commands::checkpoint_agent::agent_detection::{infer_agent, inferred_run_result};
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
 
 pub fn pre_commit(repo: &Repository, default_author: String) -> Result<(), GitAiError> {
+    // An agent committing without its own hooks installed would otherwise have
+    // everything since the last checkpoint recorded as human
+    if Config::get().feature_flags().infer_agent
+        && !agent_hooks_reporting(repo)
+        && let Ok(workdir) = repo.workdir()
+        && let Some(agent) = infer_agent(&workdir)
+    {
+        let edited_filepaths = repo
+            .get_staged_and_unstaged_filenames()
+            .map(|files| files.into_iter().collect())
+            .unwrap_or_default();
+        let run = inferred_run_result(
+            agent,
+            workdir.to_string_lossy().to_string(),
+            edited_filepaths,
+        );
+        return crate::commands::checkpoint::run(
+            repo,
+            &default_author,
+            &[],
+            CheckpointKind::AiAgent,
+            false,
+            false,
+            true,
+            Some(run),
+            false,
+        )
+        .map(|_| ());
+    }
+
     // Run checkpoint as human editor.
     let result: Result<(usize, usize, usize), GitAiError> = crate::commands::checkpoint::run(
         repo,
         &default_author,
+        &[],
         CheckpointKind::Human,
         false,
         false,
@@ -17,6 +50,26 @@ pub fn pre_commit(repo: &Repository, default_author: String) -> Result<(), GitAi
     result.map(|_| ())
 }
 
+/// Whether an agent has checkpointed itself since HEAD. Its hooks work, so
+/// whatever is left over at commit time really is human.
+fn agent_hooks_reporting(repo: &Repository) -> bool {
+    let Ok(head) = repo.head().and_then(|head| head.target()) else {
+        return false;
+    };
+    repo.storage
+        .working_log_for_base_commit(&head)
+        .read_all_checkpoints()
+        .unwrap_or_default()
+        .iter()
+        .any(|checkpoint| {
+            checkpoint.kind != CheckpointKind::Human
+                && !checkpoint
+                    .agent_metadata
+                    .as_ref()
+                    .is_some_and(|metadata| metadata.contains_key(AGENT_INFERRED_METADATA_KEY))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
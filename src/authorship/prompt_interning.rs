@@ -0,0 +1,134 @@
+//! Prompt interning. A session that spans many commits carries the same transcript
+//! in every one of their notes. With the `intern_prompts` feature flag, the messages
+//! are written once as a blob keyed by its object id under the prompts notes ref
+//! (`refs/notes/ai-prompts`), and each commit's note keeps only `messages_ref`.
+//! The ref keeps the blobs reachable and travels with notes sync. Readers call
+//! `resolve_interned_messages` to get the transcript back.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::transcript::Message;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::refs::{interned_prompts_ref, notes_add_blob_batch_to_ref};
+use crate::git::repository::{Repository, exec_git_stdin};
+use crate::utils::debug_log;
+use std::collections::{BTreeSet, HashSet};
+
+/// Move the messages of every prompt that has some into the prompts notes ref,
+/// leaving `messages_ref` behind. Identical transcripts share one entry. Returns
+/// the number of prompts interned.
+pub fn intern_prompt_messages<'a>(
+    repo: &Repository,
+    prompts: impl IntoIterator<Item = &'a mut PromptRecord>,
+) -> Result<usize, GitAiError> {
+    let mut interned = Vec::new();
+    for prompt in prompts {
+        if prompt.messages.is_empty() {
+            continue;
+        }
+        let messages_json = serde_json::to_string(&prompt.messages)?;
+        let oid = write_blob(repo, messages_json.as_bytes())?;
+        interned.push((prompt, oid));
+    }
+    if interned.is_empty() {
+        return Ok(0);
+    }
+
+    let oids: BTreeSet<String> = interned.iter().map(|(_, oid)| oid.clone()).collect();
+    let present = interned_oids(repo, &oids)?;
+    let entries: Vec<(String, String)> = oids
+        .into_iter()
+        .filter(|oid| !present.contains(oid))
+        .map(|oid| (oid.clone(), oid))
+        .collect();
+    notes_add_blob_batch_to_ref(repo, &interned_prompts_ref(), &entries)?;
+
+    let count = interned.len();
+    for (prompt, oid) in interned {
+        prompt.messages.clear();
+        prompt.messages_ref = Some(oid);
+    }
+    Ok(count)
+}
+
+/// Fill in the messages of prompts whose note only has a `messages_ref`. Blobs
+/// that aren't available locally (the prompts ref was never fetched) are left
+/// for the other fallbacks.
+pub fn resolve_interned_messages<'a>(
+    repo: &Repository,
+    prompts: impl IntoIterator<Item = &'a mut PromptRecord>,
+) {
+    let pending: Vec<&mut PromptRecord> = prompts
+        .into_iter()
+        .filter(|prompt| prompt.messages.is_empty() && prompt.messages_ref.is_some())
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let oids: Vec<String> = pending
+        .iter()
+        .filter_map(|prompt| prompt.messages_ref.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let blobs = match batch_read_blobs_with_oids(&repo.global_args_for_exec(), &oids) {
+        Ok(blobs) => blobs,
+        Err(e) => {
+            debug_log(&format!("Failed to read interned prompt messages: {}", e));
+            return;
+        }
+    };
+
+    for prompt in pending {
+        let Some(content) = prompt.messages_ref.as_ref().and_then(|oid| blobs.get(oid)) else {
+            continue;
+        };
+        match serde_json::from_str::<Vec<Message>>(content) {
+            Ok(messages) => prompt.messages = messages,
+            Err(e) => debug_log(&format!("Malformed interned prompt messages: {}", e)),
+        }
+    }
+}
+
+fn write_blob(repo: &Repository, content: &[u8]) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["hash-object", "-w", "--stdin"].map(String::from));
+    let output = exec_git_stdin(&args, content)?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Which of `oids` already have an entry under the prompts notes ref
+fn interned_oids(
+    repo: &Repository,
+    oids: &BTreeSet<String>,
+) -> Result<HashSet<String>, GitAiError> {
+    let notes_ref = interned_prompts_ref();
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file", "--batch-check"].map(String::from));
+    // Entries may sit at flat (<oid>) or fanout (<aa>/<bb...>) paths
+    let stdin: String = oids
+        .iter()
+        .map(|oid| {
+            format!(
+                "{0}:{1}\n{0}:{2}/{3}\n",
+                notes_ref,
+                oid,
+                &oid[..2],
+                &oid[2..]
+            )
+        })
+        .collect();
+    let output = exec_git_stdin(&args, stdin.as_bytes())?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines = stdout.lines();
+    let mut present = HashSet::new();
+    for oid in oids {
+        let flat = lines.next().unwrap_or_default();
+        let fanout = lines.next().unwrap_or_default();
+        if !flat.ends_with("missing") || !fanout.ends_with("missing") {
+            present.insert(oid.clone());
+        }
+    }
+    Ok(present)
+}
@@ -1,11 +1,16 @@
 use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::{
+    find_prompt_by_id, generate_short_hash, previous_short_hashes,
+};
 use crate::authorship::internal_db::InternalDatabase;
-use crate::authorship::transcript::AiTranscript;
+use crate::authorship::prompt_interning::resolve_interned_messages;
+use crate::authorship::transcript::{AiTranscript, NO_REDACT_METADATA_KEY};
 use crate::commands::checkpoint_agent::agent_presets::{
     ClaudePreset, CodexPreset, ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset,
     GithubCopilotPreset,
 };
 use crate::commands::checkpoint_agent::opencode_preset::OpenCodePreset;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::refs::{get_authorship, grep_ai_notes};
 use crate::git::repository::Repository;
@@ -51,11 +56,8 @@ pub fn find_prompt_in_commit(
     })?;
 
     // Look for the prompt in the log
-    authorship_log
-        .metadata
-        .prompts
-        .get(prompt_id)
-        .map(|prompt| (commit_sha, prompt.clone()))
+    find_prompt_by_id(&authorship_log.metadata.prompts, prompt_id)
+        .map(|(_, prompt)| (commit_sha, prompt.clone()))
         .ok_or_else(|| {
             GitAiError::Generic(format!(
                 "Prompt '{}' not found in commit {}",
@@ -73,7 +75,17 @@ pub fn find_prompt_in_history(
 ) -> Result<(String, PromptRecord), GitAiError> {
     // Use git grep to search for the prompt ID in authorship notes
     // grep_ai_notes returns commits sorted by date (newest first)
-    let shas = grep_ai_notes(repo, &format!("\"{}\"", prompt_id)).unwrap_or_default();
+    let mut shas = grep_ai_notes(repo, &format!("\"{}\"", prompt_id)).unwrap_or_default();
+    if shas.is_empty() {
+        // Notes written before a `prompt_hashing` rotation know the session by
+        // another id; the local database can tell which
+        for alias in prompt_id_aliases_from_db(prompt_id) {
+            shas = grep_ai_notes(repo, &format!("\"{}\"", alias)).unwrap_or_default();
+            if !shas.is_empty() {
+                break;
+            }
+        }
+    }
 
     if shas.is_empty() {
         return Err(GitAiError::Generic(format!(
@@ -86,7 +98,8 @@ pub fn find_prompt_in_history(
     let mut found_count = 0;
     for sha in &shas {
         if let Some(authorship_log) = get_authorship(repo, sha)
-            && let Some(prompt) = authorship_log.metadata.prompts.get(prompt_id)
+            && let Some((_, prompt)) =
+                find_prompt_by_id(&authorship_log.metadata.prompts, prompt_id)
         {
             if found_count == offset {
                 return Ok((sha.clone(), prompt.clone()));
@@ -112,6 +125,30 @@ pub fn find_prompt_in_history(
     }
 }
 
+/// The session's ids under every `prompt_hashing` scheme other than `prompt_id`'s,
+/// if the local database knows the session
+fn prompt_id_aliases_from_db(prompt_id: &str) -> Vec<String> {
+    if Config::get().prompt_hashing().previous.is_empty() {
+        return Vec::new();
+    }
+    let Some(record) = InternalDatabase::global()
+        .ok()
+        .and_then(|db| db.lock().ok()?.get_prompt(prompt_id).ok().flatten())
+    else {
+        return Vec::new();
+    };
+    std::iter::once(generate_short_hash(
+        &record.external_thread_id,
+        &record.tool,
+    ))
+    .chain(previous_short_hashes(
+        &record.external_thread_id,
+        &record.tool,
+    ))
+    .filter(|alias| alias != prompt_id)
+    .collect()
+}
+
 /// Find a prompt, trying the database first, then falling back to repository if provided
 ///
 /// Returns `(Option<commit_sha>, PromptRecord)` where commit_sha is None if found in DB
@@ -167,12 +204,12 @@ pub fn update_prompt_from_tool(
     agent_metadata: Option<&HashMap<String, String>>,
     current_model: &str,
 ) -> PromptUpdateResult {
-    match tool {
+    let result = match tool {
         "cursor" => update_cursor_prompt(external_thread_id, agent_metadata, current_model),
         "claude" => update_claude_prompt(agent_metadata, current_model),
         "codex" => update_codex_prompt(agent_metadata, current_model),
         "gemini" => update_gemini_prompt(agent_metadata, current_model),
-        "github-copilot" => update_github_copilot_prompt(agent_metadata, current_model),
+        "github-copilot" | "copilot" => update_github_copilot_prompt(agent_metadata, current_model),
         "continue-cli" => update_continue_cli_prompt(agent_metadata, current_model),
         "droid" => update_droid_prompt(agent_metadata, current_model),
         "opencode" => update_opencode_prompt(external_thread_id, agent_metadata, current_model),
@@ -180,6 +217,16 @@ pub fn update_prompt_from_tool(
             debug_log(&format!("Unknown tool: {}", tool));
             PromptUpdateResult::Unchanged
         }
+    };
+    // Refetched transcripts come straight from the agent's files, unredacted,
+    // unless the checkpoint opted out with --no-redact
+    let keep_as_is = agent_metadata.is_some_and(|m| m.contains_key(NO_REDACT_METADATA_KEY));
+    match result {
+        PromptUpdateResult::Updated(mut transcript, model) if !keep_as_is => {
+            transcript.redact_for_storage();
+            PromptUpdateResult::Updated(transcript, model)
+        }
+        other => other,
     }
 }
 
@@ -537,14 +584,24 @@ fn update_opencode_prompt(
     }
 }
 
-/// Enrich prompts that have empty messages by falling back to the InternalDatabase (SQLite).
+/// Enrich prompts that have empty messages from interned messages, falling back to the
+/// InternalDatabase (SQLite).
 ///
 /// For each prompt in `prompts` whose ID is in `referenced_ids` and whose `messages` field
-/// is empty, attempts to load the messages from the database.
+/// is empty, attempts to load the messages from the prompts notes ref, then the database.
 pub fn enrich_prompt_messages(
+    repo: &Repository,
     prompts: &mut HashMap<String, PromptRecord>,
     referenced_ids: &HashSet<&String>,
 ) {
+    resolve_interned_messages(
+        repo,
+        prompts
+            .iter_mut()
+            .filter(|(id, _)| referenced_ids.contains(id))
+            .map(|(_, prompt)| prompt),
+    );
+
     let ids_needing_messages: Vec<String> = prompts
         .iter()
         .filter(|(k, prompt)| referenced_ids.contains(k) && prompt.messages.is_empty())
@@ -636,6 +693,8 @@ mod tests {
             accepted_lines: 8,
             overriden_lines: 2,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         }
     }
 
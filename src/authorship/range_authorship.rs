@@ -1,22 +1,26 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::authorship::identity::IdentityResolver;
 use crate::authorship::ignore::{build_ignore_matcher, should_ignore_file_with_matcher};
 use crate::authorship::stats::{CommitStats, stats_for_commit_stats, stats_from_authorship_log};
 use crate::error::GitAiError;
 use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
 use crate::git::repository::{CommitRange, Repository};
+use crate::reporting::filters::AuthorFilter;
 use crate::utils::debug_log;
 
 use std::io::IsTerminal;
 
 /// The git empty tree hash - represents an empty repository state
 /// This is the hash of the empty tree object that git uses internally
-const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+pub(crate) const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
 
 /// Check if a file path should be ignored based on the provided patterns
 /// Supports both exact matches and glob patterns (e.g., "*.lock", "**/*.generated.js")
@@ -25,12 +29,63 @@ pub fn should_ignore_file(path: &str, ignore_patterns: &[String]) -> bool {
     crate::authorship::ignore::should_ignore_file(path, ignore_patterns)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RangeAuthorshipStats {
     pub authorship_stats: RangeAuthorshipStatsData,
     pub range_stats: CommitStats,
+    /// Per-author, per-tool and per-model totals (only computed on request)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<RangeBreakdown>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/// Line totals for one author across a range
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AuthorLineTotals {
+    pub commits: u32,
+    pub ai_additions: u32,
+    pub human_additions: u32,
+}
+
+/// AI line totals for one tool or model across a range
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AiLineTotals {
+    pub ai_additions: u32,
+    pub ai_accepted: u32,
+    pub mixed_additions: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RangeBreakdown {
+    /// Keyed by canonical identity (see `identity.aliases`)
+    pub by_author: BTreeMap<String, AuthorLineTotals>,
+    pub by_tool: BTreeMap<String, AiLineTotals>,
+    pub by_model: BTreeMap<String, AiLineTotals>,
+}
+
+impl RangeBreakdown {
+    /// Fold one commit's stats into the totals for `author`
+    pub fn add_commit(&mut self, author: &str, stats: &CommitStats) {
+        let totals = self.by_author.entry(author.to_string()).or_default();
+        totals.commits += 1;
+        totals.ai_additions += stats.ai_additions;
+        totals.human_additions += stats.human_additions;
+
+        for (tool_model, tool_stats) in &stats.tool_model_breakdown {
+            let (tool, model) = tool_model
+                .split_once("::")
+                .unwrap_or((tool_model.as_str(), "unknown"));
+            for totals in [
+                self.by_tool.entry(tool.to_string()).or_default(),
+                self.by_model.entry(model.to_string()).or_default(),
+            ] {
+                totals.ai_additions += tool_stats.ai_additions;
+                totals.ai_accepted += tool_stats.ai_accepted;
+                totals.mixed_additions += tool_stats.mixed_additions;
+            }
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RangeAuthorshipStatsData {
     pub total_commits: usize,
     pub commits_with_authorship: usize,
@@ -38,12 +93,40 @@ pub struct RangeAuthorshipStatsData {
     pub authors_not_committing_authorship: HashSet<String>,
     pub commits_without_authorship: Vec<String>,
     pub commits_without_authorship_with_authors: Vec<(String, String)>, // (sha, git_author)
+    /// Commits left out by the report's author filter (sha, git_author)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_commits_with_authors: Vec<(String, String)>,
 }
 
+/// Authorship stats for `commit_range`, counting every commit
 pub fn range_authorship(
     commit_range: CommitRange,
     pre_fetch_contents: bool,
     ignore_patterns: &[String],
+) -> Result<RangeAuthorshipStats, GitAiError> {
+    range_authorship_filtered(
+        commit_range,
+        pre_fetch_contents,
+        ignore_patterns,
+        &AuthorFilter::default(),
+        false,
+    )
+}
+
+/// Like [`range_authorship`], but only counts commits whose author passes `author_filter`.
+///
+/// When the filter drops any commit, range stats are the sum of per-commit stats for
+/// the remaining commits instead of a squash of the whole range, since a squash can't
+/// separate lines written by excluded authors.
+///
+/// With `breakdown`, also totals per-commit stats by author, tool and model. That
+/// needs stats for every commit in the range, so it's slower on long ranges.
+pub fn range_authorship_filtered(
+    commit_range: CommitRange,
+    pre_fetch_contents: bool,
+    ignore_patterns: &[String],
+    author_filter: &AuthorFilter,
+    breakdown: bool,
 ) -> Result<RangeAuthorshipStats, GitAiError> {
     commit_range.is_valid()?;
 
@@ -111,11 +194,48 @@ pub fn range_authorship(
         .into_iter()
         .map(|c| c.id().to_string())
         .collect();
-    let commit_authorship = get_commits_with_notes_from_list(repository, &commit_shas)?;
+    // Filter on canonical identities so all of a person's emails are treated alike
+    let identities = IdentityResolver::from_config(true);
+    let (commit_authorship, excluded): (Vec<_>, Vec<_>) =
+        get_commits_with_notes_from_list(repository, &commit_shas)?
+            .into_iter()
+            .partition(|ca| {
+                let author = commit_git_author(ca);
+                author_filter.allows_identity(author, &identities.resolve(author))
+            });
+
+    let per_commit_stats = if breakdown || !excluded.is_empty() {
+        commit_authorship
+            .iter()
+            .map(|ca| stats_for_commit_stats(repository, commit_sha(ca), ignore_patterns))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        Vec::new()
+    };
 
     // Calculate range stats - now just pass start, end, and commits
-    let range_stats =
-        calculate_range_stats_direct(repository, commit_range_clone, ignore_patterns)?;
+    let range_stats = if excluded.is_empty() {
+        calculate_range_stats_direct(repository, commit_range_clone, ignore_patterns)?
+    } else {
+        debug_log(&format!(
+            "Author filter excluded {} of {} commits; summing per-commit stats",
+            excluded.len(),
+            commit_shas.len()
+        ));
+        let mut total = CommitStats::default();
+        for stats in &per_commit_stats {
+            total.accumulate(stats);
+        }
+        total
+    };
+
+    let breakdown = breakdown.then(|| {
+        let mut breakdown = RangeBreakdown::default();
+        for (ca, stats) in commit_authorship.iter().zip(&per_commit_stats) {
+            breakdown.add_commit(&identities.resolve(commit_git_author(ca)), stats);
+        }
+        breakdown
+    });
 
     Ok(RangeAuthorshipStats {
         authorship_stats: RangeAuthorshipStatsData {
@@ -154,11 +274,35 @@ pub fn range_authorship(
                     _ => None,
                 })
                 .collect(),
+            excluded_commits_with_authors: excluded
+                .iter()
+                .map(|ca| {
+                    (
+                        commit_sha(ca).to_string(),
+                        identities.resolve(commit_git_author(ca)),
+                    )
+                })
+                .collect(),
         },
         range_stats,
+        breakdown,
     })
 }
 
+fn commit_sha(ca: &CommitAuthorship) -> &str {
+    match ca {
+        CommitAuthorship::NoLog { sha, .. } | CommitAuthorship::Log { sha, .. } => sha,
+    }
+}
+
+fn commit_git_author(ca: &CommitAuthorship) -> &str {
+    match ca {
+        CommitAuthorship::NoLog { git_author, .. } | CommitAuthorship::Log { git_author, .. } => {
+            git_author
+        }
+    }
+}
+
 /// Create an in-memory authorship log for a commit range by treating it as a squash
 /// Similar to rewrite_authorship_after_squash_or_rebase but tailored for ranges
 fn create_authorship_log_for_range(
@@ -205,6 +349,11 @@ fn create_authorship_log_for_range(
                     ),
                     base_commit_sha: end_sha.to_string(),
                     prompts: std::collections::BTreeMap::new(),
+                    humans: Vec::new(),
+                    environment: None,
+                    generated: std::collections::BTreeMap::new(),
+                    pruned: None,
+                    submodules: BTreeMap::new(),
                 },
             },
         );
@@ -442,6 +591,20 @@ pub fn print_range_authorship_stats(stats: &RangeAuthorshipStats) {
     let is_interactive = std::io::stdout().is_terminal();
     write_stats_to_terminal(&stats.range_stats, is_interactive);
 
+    let excluded = &stats.authorship_stats.excluded_commits_with_authors;
+    if !excluded.is_empty() {
+        let commit_word = if excluded.len() == 1 {
+            "commit"
+        } else {
+            "commits"
+        };
+        println!(
+            "  {} {} excluded by author filter",
+            excluded.len(),
+            commit_word
+        );
+    }
+
     // Check if all individual commits have authorship logs (for optional breakdown)
     let all_have_authorship =
         stats.authorship_stats.commits_with_authorship == stats.authorship_stats.total_commits;
@@ -468,6 +631,57 @@ pub fn print_range_authorship_stats(stats: &RangeAuthorshipStats) {
             println!("    {} {}", &sha[0..7], author);
         }
     }
+
+    if let Some(breakdown) = &stats.breakdown {
+        print_range_breakdown(breakdown);
+    }
+}
+
+fn print_range_breakdown(breakdown: &RangeBreakdown) {
+    println!("\nBy author:");
+    let width = breakdown
+        .by_author
+        .keys()
+        .map(|a| a.len())
+        .max()
+        .unwrap_or(0);
+    for (author, totals) in &breakdown.by_author {
+        let commit_word = if totals.commits == 1 {
+            "commit"
+        } else {
+            "commits"
+        };
+        println!(
+            "  {:<width$}  {:>6} ai  {:>6} human  ({} {})",
+            author,
+            totals.ai_additions,
+            totals.human_additions,
+            totals.commits,
+            commit_word,
+            width = width
+        );
+    }
+
+    for (title, totals) in [
+        ("By tool:", &breakdown.by_tool),
+        ("By model:", &breakdown.by_model),
+    ] {
+        if totals.is_empty() {
+            continue;
+        }
+        println!("\n{}", title);
+        let width = totals.keys().map(|k| k.len()).max().unwrap_or(0);
+        for (name, totals) in totals {
+            println!(
+                "  {:<width$}  {:>6} ai  ({} accepted, {} mixed)",
+                name,
+                totals.ai_additions,
+                totals.ai_accepted,
+                totals.mixed_additions,
+                width = width
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -518,6 +732,116 @@ mod tests {
         assert_eq!(stats.range_stats.git_diff_added_lines, 2);
     }
 
+    #[test]
+    fn test_range_authorship_filtered_by_author() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        file.append("AI Line 2\nAI Line 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI adds lines").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let range = || {
+            CommitRange::new(
+                tmp_repo.gitai_repo(),
+                first_sha.clone(),
+                second_sha.clone(),
+                "HEAD".to_string(),
+            )
+            .unwrap()
+        };
+
+        // A filter that matches nobody in the range leaves stats untouched
+        let bots = AuthorFilter {
+            exclude: vec!["dependabot".to_string()],
+            include: Vec::new(),
+        };
+        let stats = range_authorship_filtered(range(), false, &[], &bots, false).unwrap();
+        assert_eq!(stats.authorship_stats.total_commits, 1);
+        assert!(
+            stats
+                .authorship_stats
+                .excluded_commits_with_authors
+                .is_empty()
+        );
+        assert_eq!(stats.range_stats.ai_additions, 2);
+
+        // Excluding the committer drops their commit and its lines
+        let exclude_test_user = AuthorFilter {
+            exclude: vec!["test@example.com".to_string()],
+            include: Vec::new(),
+        };
+        let stats =
+            range_authorship_filtered(range(), false, &[], &exclude_test_user, false).unwrap();
+        assert_eq!(stats.authorship_stats.total_commits, 0);
+        assert_eq!(
+            stats.authorship_stats.excluded_commits_with_authors,
+            vec![(
+                second_sha.clone(),
+                "Test User <test@example.com>".to_string()
+            )]
+        );
+        assert_eq!(stats.range_stats.ai_additions, 0);
+        assert_eq!(stats.range_stats.git_diff_added_lines, 0);
+        assert!(stats.breakdown.is_none());
+    }
+
+    #[test]
+    fn test_range_authorship_breakdown() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        file.append("AI Line 2\nAI Line 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI adds lines").unwrap();
+
+        file.append("Human Line 4\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human adds a line").unwrap();
+        let third_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let range = CommitRange::new(
+            tmp_repo.gitai_repo(),
+            first_sha,
+            third_sha,
+            "HEAD".to_string(),
+        )
+        .unwrap();
+        let stats =
+            range_authorship_filtered(range, false, &[], &AuthorFilter::default(), true).unwrap();
+        let breakdown = stats.breakdown.unwrap();
+
+        assert_eq!(
+            breakdown.by_author.get("Test User <test@example.com>"),
+            Some(&AuthorLineTotals {
+                commits: 2,
+                ai_additions: 2,
+                human_additions: 1,
+            })
+        );
+        assert_eq!(breakdown.by_tool["cursor"].ai_additions, 2);
+        assert_eq!(breakdown.by_model["claude-3-sonnet"].ai_additions, 2);
+    }
+
     #[test]
     fn test_range_authorship_from_empty_tree() {
         let tmp_repo = TmpRepo::new().unwrap();
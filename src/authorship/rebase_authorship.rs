@@ -1,14 +1,23 @@
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::{
+    AUTHORSHIP_LOG_VERSION_V4, AttestationEntry, AuthorshipLog,
+};
+use crate::authorship::identity::IdentityResolver;
+use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::pairing::{collect_humans, parse_co_authored_by};
 use crate::authorship::post_commit;
+use crate::config::{Config, HistorySettings};
 use crate::error::GitAiError;
 use crate::git::authorship_traversal::{
-    commits_have_authorship_notes, load_ai_touched_files_for_commits,
+    cat_file_batch, commits_have_authorship_notes, load_ai_touched_files_for_commits,
 };
 use crate::git::refs::{
     commits_with_authorship_notes, get_reference_as_authorship_log_v3, note_blob_oids_for_commits,
 };
 use crate::git::repository::{CommitRange, Repository, exec_git, exec_git_stdin};
 use crate::git::rewrite_log::RewriteLogEvent;
+use crate::observability::profile;
+use crate::observability::webhook::{LocalEventKind, emit_local_event};
 use crate::utils::{debug_log, debug_performance_log};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
@@ -41,6 +50,7 @@ pub fn rewrite_authorship_if_needed(
     _full_log: &Vec<RewriteLogEvent>,
     supress_output: bool,
 ) -> Result<(), GitAiError> {
+    let _profile_scope = profile::scope("rewrite_authorship");
     match last_event {
         RewriteLogEvent::Commit { commit } => {
             // This is going to become the regualar post-commit
@@ -64,6 +74,12 @@ pub fn rewrite_authorship_if_needed(
                 "Ammended commit {} now has authorship log {}",
                 &commit_amend.original_commit, &commit_amend.amended_commit_sha
             ));
+            emit_rewrite_completed(
+                repo,
+                "amend",
+                std::slice::from_ref(&commit_amend.original_commit),
+                std::slice::from_ref(&commit_amend.amended_commit_sha),
+            );
         }
         RewriteLogEvent::MergeSquash { merge_squash } => {
             // --squash always fails if repo is not clean
@@ -103,6 +119,12 @@ pub fn rewrite_authorship_if_needed(
                 "✓ Rewrote authorship for {} rebased commits",
                 rebase_complete.new_commits.len()
             ));
+            emit_rewrite_completed(
+                repo,
+                "rebase",
+                &rebase_complete.original_commits,
+                &rebase_complete.new_commits,
+            );
         }
         RewriteLogEvent::CherryPickComplete {
             cherry_pick_complete,
@@ -118,6 +140,31 @@ pub fn rewrite_authorship_if_needed(
                 "✓ Rewrote authorship for {} cherry-picked commits",
                 cherry_pick_complete.new_commits.len()
             ));
+            emit_rewrite_completed(
+                repo,
+                "cherry_pick",
+                &cherry_pick_complete.source_commits,
+                &cherry_pick_complete.new_commits,
+            );
+        }
+        RewriteLogEvent::Revert { revert } => {
+            rewrite_authorship_after_revert(
+                repo,
+                &revert.reverted_commits,
+                &revert.new_commits,
+                &commit_author,
+            )?;
+
+            debug_log(&format!(
+                "✓ Rewrote authorship for {} revert commits",
+                revert.new_commits.len()
+            ));
+            emit_rewrite_completed(
+                repo,
+                "revert",
+                &revert.reverted_commits,
+                &revert.new_commits,
+            );
         }
         _ => {}
     }
@@ -125,6 +172,23 @@ pub fn rewrite_authorship_if_needed(
     Ok(())
 }
 
+fn emit_rewrite_completed(
+    repo: &Repository,
+    operation: &str,
+    original_commits: &[String],
+    new_commits: &[String],
+) {
+    emit_local_event(
+        LocalEventKind::RewriteCompleted,
+        Some(repo.canonical_workdir().display().to_string()),
+        serde_json::json!({
+            "operation": operation,
+            "original_commits": original_commits,
+            "new_commits": new_commits,
+        }),
+    );
+}
+
 /// Migrate working log from the pre-rebase HEAD to the post-rebase HEAD.
 /// Rebase rewrites commit SHAs, but working logs are keyed by SHA. Without this
 /// migration, uncommitted attributions stored in the working log are orphaned on
@@ -285,6 +349,7 @@ pub fn rewrite_authorship_after_squash_or_rebase(
     merge_commit_sha: &str,
     _suppress_output: bool,
 ) -> Result<(), GitAiError> {
+    let _profile_scope = profile::scope("rewrite_after_squash_or_rebase");
     use crate::authorship::virtual_attribution::{
         VirtualAttributions, merge_attributions_favoring_first,
     };
@@ -395,6 +460,14 @@ pub fn rewrite_authorship_after_squash_or_rebase(
     let mut summed_totals: HashMap<String, (u32, u32)> = HashMap::new();
     for commit_sha in &source_commits {
         if let Ok(log) = get_reference_as_authorship_log_v3(repo, commit_sha) {
+            carry_commit_metadata(&log, &mut authorship_log);
+            carry_char_ranges(
+                repo,
+                &log,
+                commit_sha,
+                &mut authorship_log,
+                merge_commit_sha,
+            );
             for (prompt_id, record) in log.metadata.prompts {
                 let entry = summed_totals.entry(prompt_id).or_insert((0, 0));
                 entry.0 = entry.0.saturating_add(record.total_additions);
@@ -438,6 +511,7 @@ pub fn rewrite_authorship_after_rebase_v2(
     new_commits: &[String],
     _human_author: &str,
 ) -> Result<(), GitAiError> {
+    let _profile_scope = profile::scope("rewrite_after_rebase");
     // Handle edge case: no commits to process
     if new_commits.is_empty() {
         return Ok(());
@@ -446,8 +520,11 @@ pub fn rewrite_authorship_after_rebase_v2(
     // Filter out commits that already have authorship logs (these are commits from the target branch).
     // Only process newly created rebased commits.
     let commits_with_logs = commits_with_authorship_notes(repo, new_commits)?;
+    // Squashed commits can list the same rebased commit more than once
+    let mut seen_new_commits = HashSet::new();
     let commits_to_process: Vec<String> = new_commits
         .iter()
+        .filter(|commit| seen_new_commits.insert(commit.as_str()))
         .filter(|commit| {
             let has_log = commits_with_logs.contains(commit.as_str());
             if has_log {
@@ -473,6 +550,25 @@ pub fn rewrite_authorship_after_rebase_v2(
     ));
     let commits_to_process_lookup: HashSet<&str> =
         commits_to_process.iter().map(String::as_str).collect();
+
+    // Interactive rebases can squash, fixup, drop or reorder commits, so the two lists
+    // don't necessarily line up. Pair them from the reflog when it has the whole story.
+    let todo_outcome = match rebase_todo_outcome(repo, original_commits, new_commits)? {
+        Some(pairs) => Some(pairs),
+        None => autosquash_outcome(repo, original_commits, new_commits)?,
+    };
+    let (original_commits, new_commits): (Vec<String>, Vec<String>) = match &todo_outcome {
+        Some(pairs) => {
+            debug_log(&format!(
+                "Rebase todo outcome: {} of {} original commits kept",
+                pairs.len(),
+                original_commits.len()
+            ));
+            pairs.iter().cloned().unzip()
+        }
+        None => (original_commits.to_vec(), new_commits.to_vec()),
+    };
+    let (original_commits, new_commits) = (original_commits.as_slice(), new_commits.as_slice());
     let commit_pairs_to_process: Vec<(String, String)> = original_commits
         .iter()
         .zip(new_commits.iter())
@@ -493,10 +589,49 @@ pub fn rewrite_authorship_after_rebase_v2(
     if pathspecs.is_empty() {
         // No AI-touched files were rewritten. Preserve metadata-only / prompt-only notes by remapping
         // existing source notes to their corresponding rebased commits.
+        // Commits squashed together get one note carrying all of their prompts.
+        let mut originals_by_new_commit: HashMap<&str, Vec<String>> = HashMap::new();
+        for (original_commit, new_commit) in &commit_pairs_to_process {
+            originals_by_new_commit
+                .entry(new_commit.as_str())
+                .or_default()
+                .push(original_commit.clone());
+        }
+        let (single_pairs, squashed_pairs): (Vec<_>, Vec<_>) = commit_pairs_to_process
+            .iter()
+            .cloned()
+            .partition(|(_original_commit, new_commit)| {
+                originals_by_new_commit[new_commit.as_str()].len() == 1
+            });
         let original_note_contents =
             load_note_contents_for_commits(repo, &original_commits_for_processing)?;
-        let remapped_count =
-            remap_notes_for_commit_pairs(repo, &commit_pairs_to_process, &original_note_contents)?;
+        let mut remapped_count =
+            remap_notes_for_commit_pairs(repo, &single_pairs, &original_note_contents)?;
+        let mut merged_entries = Vec::new();
+        for new_commit in squashed_pairs.iter().map(|(_, new_commit)| new_commit) {
+            if merged_entries
+                .iter()
+                .any(|(commit, _)| commit == new_commit)
+            {
+                continue;
+            }
+            if let Some(log) = build_metadata_only_authorship_log_from_source_notes(
+                repo,
+                &originals_by_new_commit[new_commit.as_str()],
+                new_commit,
+            )? {
+                merged_entries.push((
+                    new_commit.clone(),
+                    log.serialize_to_string().map_err(|_| {
+                        GitAiError::Generic("Failed to serialize authorship log".to_string())
+                    })?,
+                ));
+            }
+        }
+        if !merged_entries.is_empty() {
+            remapped_count += merged_entries.len();
+            crate::git::refs::notes_add_batch(repo, &merged_entries)?;
+        }
         if remapped_count > 0 {
             debug_log(&format!(
                 "Remapped {} metadata-only authorship notes for rebase commits",
@@ -619,9 +754,15 @@ pub fn rewrite_authorship_after_rebase_v2(
         &current_attributions,
         &existing_files,
     );
-    let rebase_ts = current_va.timestamp();
+    let rebase_ts = current_va.rewrite_timestamp();
 
-    let commit_tree_pairs = build_first_parent_tree_pairs(repo, &commits_to_process)?;
+    // When the todo outcome is known, diff each commit against the state before it rather
+    // than its parent, so files a dropped or later commit changed don't linger in earlier notes
+    let commit_tree_pairs = if todo_outcome.is_some() {
+        build_sequential_tree_pairs(repo, original_head, &commits_to_process)?
+    } else {
+        build_first_parent_tree_pairs(repo, &commits_to_process)?
+    };
     let mut changed_contents_by_commit = collect_changed_file_contents_for_commit_pairs(
         repo,
         &commit_tree_pairs,
@@ -633,6 +774,31 @@ pub fn rewrite_authorship_after_rebase_v2(
     let mut pending_note_debug: Vec<(String, usize)> = Vec::with_capacity(commits_to_process.len());
     let mut original_note_content_by_new_commit: HashMap<String, String> = HashMap::new();
     let mut original_note_content_loaded = false;
+    let original_prompts_by_new_commit = if todo_outcome.is_some() {
+        let original_note_contents =
+            load_note_contents_for_commits(repo, &original_commits_for_processing)?;
+        let mut prompts_by_new_commit: HashMap<
+            String,
+            BTreeMap<String, crate::authorship::authorship_log::PromptRecord>,
+        > = HashMap::new();
+        for (original_commit, new_commit) in &commit_pairs_to_process {
+            let Some(log) = original_note_contents
+                .get(original_commit)
+                .and_then(|raw_note| AuthorshipLog::deserialize_from_string(raw_note).ok())
+            else {
+                continue;
+            };
+            prompts_by_new_commit
+                .entry(new_commit.clone())
+                .or_default()
+                .extend(log.metadata.prompts);
+        }
+        Some(prompts_by_new_commit)
+    } else {
+        None
+    };
+
+    let source_logs = source_logs_for_commit_pairs(repo, &commit_pairs_to_process)?;
 
     // Step 3: Process each new commit in order (oldest to newest)
     for (idx, new_commit) in commits_to_process.iter().enumerate() {
@@ -714,11 +880,57 @@ pub fn rewrite_authorship_after_rebase_v2(
 
         current_authorship_log.metadata.base_commit_sha = new_commit.clone();
         current_authorship_log.metadata.prompts = flatten_prompts_for_metadata(&current_prompts);
+        if let Some(original_prompts) = original_prompts_by_new_commit.as_ref() {
+            // Keep only the prompts this commit carries, so a dropped commit's prompts
+            // don't leak into the commits rebased around it
+            let mut prompts = original_prompts
+                .get(new_commit)
+                .cloned()
+                .unwrap_or_default();
+            let attested: HashSet<&str> = current_authorship_log
+                .attestations
+                .iter()
+                .flat_map(|attestation| attestation.entries.iter())
+                .map(|entry| entry.hash.as_str())
+                .collect();
+            for (prompt_id, record) in &current_authorship_log.metadata.prompts {
+                if attested.contains(prompt_id.as_str()) || prompts.contains_key(prompt_id) {
+                    prompts.insert(prompt_id.clone(), record.clone());
+                }
+            }
+            current_authorship_log.metadata.prompts = prompts;
+        }
 
-        let computed_note_has_payload = !current_authorship_log.attestations.is_empty()
-            || !current_authorship_log.metadata.prompts.is_empty();
+        // Commit-level metadata comes from the commits this one was rewritten from
+        let sources = source_logs.get(new_commit);
+        current_authorship_log.metadata.generated.clear();
+        current_authorship_log.metadata.submodules.clear();
+        for (_, source_log) in sources.into_iter().flatten() {
+            carry_commit_metadata(source_log, &mut current_authorship_log);
+        }
+
+        // Sub-line ranges are per note, so carry them onto a copy of the running state
+        let carried_log;
+        let note_log = match sources.filter(|sources| {
+            sources.iter().any(|(_, source_log)| {
+                source_log.metadata.schema_version == AUTHORSHIP_LOG_VERSION_V4
+            })
+        }) {
+            Some(sources) => {
+                let mut log = current_authorship_log.clone();
+                for (source_commit, source_log) in sources {
+                    carry_char_ranges(repo, source_log, source_commit, &mut log, new_commit);
+                }
+                carried_log = log;
+                &carried_log
+            }
+            None => &current_authorship_log,
+        };
+
+        let computed_note_has_payload =
+            !note_log.attestations.is_empty() || !note_log.metadata.prompts.is_empty();
         let authorship_json = if computed_note_has_payload {
-            Some(current_authorship_log.serialize_to_string().map_err(|_| {
+            Some(note_log.serialize_to_string().map_err(|_| {
                 GitAiError::Generic("Failed to serialize authorship log".to_string())
             })?)
         } else {
@@ -733,10 +945,7 @@ pub fn rewrite_authorship_after_rebase_v2(
         };
         if let Some(authorship_json) = authorship_json {
             pending_note_entries.push((new_commit.clone(), authorship_json));
-            pending_note_debug.push((
-                new_commit.clone(),
-                current_authorship_log.attestations.len(),
-            ));
+            pending_note_debug.push((new_commit.clone(), note_log.attestations.len()));
         }
     }
 
@@ -754,6 +963,263 @@ pub fn rewrite_authorship_after_rebase_v2(
     Ok(())
 }
 
+/// One commit HEAD moved to while a rebase ran, with the commit it was built on
+struct RebaseStep {
+    commit: String,
+    first_parent: String,
+}
+
+/// Recover how an interactive rebase's todo list played out from the HEAD reflog:
+/// each original commit paired with the rebased commit its changes ended up in.
+/// Squashed and fixed-up commits pair with the commit they were folded into, and
+/// dropped commits are left out. Returns `None` when the reflog doesn't end with
+/// this rebase or a step can't be traced back to an original commit.
+fn rebase_todo_outcome(
+    repo: &Repository,
+    original_commits: &[String],
+    new_commits: &[String],
+) -> Result<Option<Vec<(String, String)>>, GitAiError> {
+    let Some(new_head) = new_commits.last() else {
+        return Ok(None);
+    };
+
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "reflog".to_string(),
+        "show".to_string(),
+        "--format=%H%x09%P%x09%gs".to_string(),
+        // Every step applies one todo line; amends from `edit` stops add a few more
+        format!("-n{}", original_commits.len() * 3 + 16),
+        "HEAD".to_string(),
+    ]);
+    let Ok(output) = exec_git(&args) else {
+        return Ok(None);
+    };
+    let stdout = String::from_utf8(output.stdout)?;
+
+    // Newest first: the rebase's `(finish)` entry, its steps, then `(start)`
+    let mut entries = stdout.lines().map(|line| {
+        let mut fields = line.splitn(3, '\t');
+        let commit = fields.next().unwrap_or_default();
+        let first_parent = fields
+            .next()
+            .unwrap_or_default()
+            .split(' ')
+            .next()
+            .unwrap_or_default();
+        let action = rebase_reflog_action(fields.next().unwrap_or_default());
+        (commit, first_parent, action)
+    });
+    match entries.next() {
+        Some((commit, _, Some("finish"))) if commit == new_head => {}
+        _ => return Ok(None),
+    }
+    let mut steps = Vec::new();
+    let mut onto = None;
+    for (commit, first_parent, action) in entries {
+        match action {
+            Some("start") => {
+                onto = Some(commit.to_string());
+                break;
+            }
+            Some(_) => steps.push(RebaseStep {
+                commit: commit.to_string(),
+                first_parent: first_parent.to_string(),
+            }),
+            None => return Ok(None),
+        }
+    }
+    let Some(onto) = onto else {
+        return Ok(None);
+    };
+    steps.reverse();
+
+    // Commits the rebase could reuse unchanged are already in place when it starts
+    let new_commit_lookup: HashSet<&str> = new_commits.iter().map(String::as_str).collect();
+    let mut pairs: HashMap<&str, String> = original_commits
+        .iter()
+        .filter(|commit| new_commit_lookup.contains(commit.as_str()))
+        .map(|commit| (commit.as_str(), commit.clone()))
+        .collect();
+
+    let patch_ids = patch_ids_for_rebase(repo, original_commits, &onto, &steps)?;
+    let mut unmatched_originals: Vec<&String> = original_commits
+        .iter()
+        .filter(|commit| !pairs.contains_key(commit.as_str()))
+        .collect();
+
+    // A step built on the previous step starts a new commit (pick, reword, edit); one
+    // built on the previous step's parent replaces it (squash, fixup, amend)
+    let mut chains: Vec<(Vec<&String>, String)> = Vec::new();
+    let mut previous = (onto.clone(), String::new());
+    for step in &steps {
+        let folds_into_previous = step.first_parent != previous.0;
+        if folds_into_previous && (chains.is_empty() || step.first_parent != previous.1) {
+            return Ok(None);
+        }
+
+        let original = patch_ids.get(&step.commit).and_then(|patch_id| {
+            let idx = unmatched_originals
+                .iter()
+                .position(|commit| patch_ids.get(commit.as_str()) == Some(patch_id))?;
+            Some(unmatched_originals.remove(idx))
+        });
+        if folds_into_previous {
+            let chain = chains.last_mut().expect("checked above");
+            chain.0.extend(original);
+            chain.1 = step.commit.clone();
+        } else {
+            // Without an original a new commit can't be attributed to anything
+            let Some(original) = original else {
+                return Ok(None);
+            };
+            chains.push((vec![original], step.commit.clone()));
+        }
+        previous = (step.commit.clone(), step.first_parent.clone());
+    }
+
+    for (originals, new_commit) in chains {
+        if !new_commit_lookup.contains(new_commit.as_str()) {
+            return Ok(None);
+        }
+        for original in originals {
+            pairs.insert(original.as_str(), new_commit.clone());
+        }
+    }
+
+    // Originals no step picked up were dropped
+    Ok(Some(
+        original_commits
+            .iter()
+            .filter_map(|original| {
+                pairs
+                    .get(original.as_str())
+                    .map(|new_commit| (original.clone(), new_commit.clone()))
+            })
+            .collect(),
+    ))
+}
+
+/// Pair original and rebased commits the way `rebase --autosquash` arranges its
+/// todo list: each `fixup!`, `squash!` or `amend!` commit folds into the commit its
+/// subject names, everything else is picked in order. A fallback for when the reflog
+/// can't tell how the rebase played out. Returns `None` when nothing was folded or
+/// the resulting commits don't line up with the rebased ones.
+fn autosquash_outcome(
+    repo: &Repository,
+    original_commits: &[String],
+    new_commits: &[String],
+) -> Result<Option<Vec<(String, String)>>, GitAiError> {
+    let mut subjects = Vec::with_capacity(original_commits.len());
+    for commit in original_commits {
+        subjects.push(repo.find_commit(commit.clone())?.summary()?);
+    }
+    let targets = autosquash_targets(original_commits, &subjects);
+    if targets
+        .iter()
+        .enumerate()
+        .all(|(idx, target)| *target == idx)
+    {
+        return Ok(None);
+    }
+
+    let kept: Vec<usize> = targets
+        .iter()
+        .enumerate()
+        .filter(|(idx, target)| idx == *target)
+        .map(|(idx, _)| idx)
+        .collect();
+    if kept.len() != new_commits.len() {
+        return Ok(None);
+    }
+    let new_commit_for: HashMap<usize, &String> = kept.into_iter().zip(new_commits).collect();
+    Ok(Some(
+        original_commits
+            .iter()
+            .zip(&targets)
+            .map(|(original, target)| (original.clone(), new_commit_for[target].clone()))
+            .collect(),
+    ))
+}
+
+/// For each commit, the index of the commit it ends up folded into under
+/// `--autosquash` (its own index when it isn't a fixup). Like git, a fixup names
+/// an earlier commit by exact subject, then by hash prefix, then by subject prefix,
+/// and a fixup of a fixup folds into the same commit.
+fn autosquash_targets(commits: &[String], subjects: &[String]) -> Vec<usize> {
+    let mut targets: Vec<usize> = Vec::with_capacity(subjects.len());
+    for (idx, subject) in subjects.iter().enumerate() {
+        let mut rest = subject.as_str();
+        while let Some(stripped) = ["fixup! ", "squash! ", "amend! "]
+            .iter()
+            .find_map(|prefix| rest.strip_prefix(prefix))
+        {
+            rest = stripped;
+        }
+        let target = if rest.len() == subject.len() {
+            None
+        } else {
+            let earlier = &subjects[..idx];
+            earlier
+                .iter()
+                .position(|other| other == rest)
+                .or_else(|| {
+                    (rest.len() >= 4 && !rest.contains(' '))
+                        .then(|| commits[..idx].iter().position(|sha| sha.starts_with(rest)))
+                        .flatten()
+                })
+                .or_else(|| earlier.iter().position(|other| other.starts_with(rest)))
+        };
+        targets.push(target.map_or(idx, |target| targets[target]));
+    }
+    targets
+}
+
+/// The todo command in a rebase reflog subject such as `rebase (squash): ...` or
+/// `rebase -i (finish): ...`
+fn rebase_reflog_action(subject: &str) -> Option<&str> {
+    let rest = subject.strip_prefix("rebase")?;
+    let (_, rest) = rest.split_once('(')?;
+    let (action, _) = rest.split_once("):")?;
+    Some(action)
+}
+
+/// `git patch-id --stable` for each original commit (against its parent) and each
+/// rebase step (against the step before it), keyed by commit
+fn patch_ids_for_rebase(
+    repo: &Repository,
+    original_commits: &[String],
+    onto: &str,
+    steps: &[RebaseStep],
+) -> Result<HashMap<String, String>, GitAiError> {
+    let mut diff_input = String::new();
+    for commit in original_commits {
+        diff_input.push_str(commit);
+        diff_input.push('\n');
+    }
+    let mut previous = onto;
+    for step in steps {
+        diff_input.push_str(&format!("{} {}\n", step.commit, previous));
+        previous = &step.commit;
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["diff-tree", "-p", "--stdin"].map(String::from));
+    let diff = exec_git_stdin(&args, diff_input.as_bytes())?;
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["patch-id", "--stable"].map(String::from));
+    let output = exec_git_stdin(&args, &diff.stdout)?;
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (patch_id, commit) = line.split_once(' ')?;
+            Some((commit.to_string(), patch_id.to_string()))
+        })
+        .collect())
+}
+
 /// Rewrite authorship logs after cherry-pick using VirtualAttributions
 ///
 /// This is the new implementation that uses VirtualAttributions to transform authorship
@@ -771,6 +1237,7 @@ pub fn rewrite_authorship_after_cherry_pick(
     new_commits: &[String],
     _human_author: &str,
 ) -> Result<(), GitAiError> {
+    let _profile_scope = profile::scope("rewrite_after_cherry_pick");
     // Handle edge case: no commits to process
     if new_commits.is_empty() {
         debug_log("Cherry-pick resulted in no new commits");
@@ -872,6 +1339,8 @@ pub fn rewrite_authorship_after_cherry_pick(
         )
     };
 
+    let source_logs = source_logs_for_commit_pairs(repo, &commit_pairs)?;
+
     // Step 3: Process each new commit in order (oldest to newest)
     for (idx, new_commit) in new_commits.iter().enumerate() {
         debug_log(&format!(
@@ -926,6 +1395,16 @@ pub fn rewrite_authorship_after_cherry_pick(
         });
 
         authorship_log.metadata.base_commit_sha = new_commit.clone();
+        for (source_commit, source_log) in source_logs.get(new_commit).into_iter().flatten() {
+            carry_commit_metadata(source_log, &mut authorship_log);
+            carry_char_ranges(
+                repo,
+                source_log,
+                source_commit,
+                &mut authorship_log,
+                new_commit,
+            );
+        }
 
         // Save computed note when it has payload; otherwise preserve original metadata-only notes.
         let computed_note_has_payload =
@@ -961,6 +1440,125 @@ pub fn rewrite_authorship_after_cherry_pick(
     Ok(())
 }
 
+/// Rewrite authorship for commits created by `git revert`.
+///
+/// `reverted_commits[i]` is the commit undone by `new_commits[i]`. Lines the revert
+/// brings back get the attribution they had before the reverted commit, and every
+/// prompt whose lines were undone is carried into the revert's note with those
+/// lines counted as overridden.
+pub fn rewrite_authorship_after_revert(
+    repo: &Repository,
+    reverted_commits: &[String],
+    new_commits: &[String],
+    _human_author: &str,
+) -> Result<(), GitAiError> {
+    for (reverted_commit, new_commit) in reverted_commits.iter().zip(new_commits) {
+        let authorship_log = build_revert_authorship_log(repo, reverted_commit, new_commit)?;
+        let authorship_json = authorship_log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        crate::git::refs::notes_add(repo, new_commit, &authorship_json)?;
+
+        debug_log(&format!(
+            "Saved authorship log for revert {} of {} ({} files)",
+            new_commit,
+            reverted_commit,
+            authorship_log.attestations.len()
+        ));
+    }
+    Ok(())
+}
+
+fn build_revert_authorship_log(
+    repo: &Repository,
+    reverted_commit: &str,
+    new_commit: &str,
+) -> Result<AuthorshipLog, GitAiError> {
+    let pathspecs = get_pathspecs_from_commits(repo, &[reverted_commit.to_string()])?;
+    let revert_parent = repo.find_commit(new_commit.to_string())?.parent(0)?.id();
+    // State before the reverted commit, where re-added lines came from
+    let before_reverted = repo
+        .find_commit(reverted_commit.to_string())?
+        .parent(0)
+        .ok()
+        .map(|parent| parent.id());
+
+    let mut authorship_log = if pathspecs.is_empty() {
+        AuthorshipLog::new()
+    } else {
+        let load_va = |base: String| {
+            let repo = repo.clone();
+            let pathspecs = pathspecs.clone();
+            smol::block_on(async move {
+                crate::authorship::virtual_attribution::VirtualAttributions::new_for_base_commit(
+                    repo, base, &pathspecs, None,
+                )
+                .await
+            })
+        };
+        let parent_va = load_va(revert_parent)?;
+        let before_va = before_reverted.map(load_va).transpose()?;
+
+        let mut final_state = get_committed_files_content(repo, new_commit, &pathspecs)?;
+        for path in &pathspecs {
+            final_state.entry(path.clone()).or_default();
+        }
+        let final_va =
+            transform_attributions_to_final_state(&parent_va, final_state, before_va.as_ref())?;
+
+        let mut authorship_log = final_va.to_authorship_log()?;
+        authorship_log.attestations.retain(|attestation| {
+            final_va
+                .get_file_content(&attestation.file_path)
+                .is_some_and(|content| !content.is_empty())
+        });
+        authorship_log
+    };
+    authorship_log.metadata.base_commit_sha = new_commit.to_string();
+
+    if let Ok(reverted_log) = get_reference_as_authorship_log_v3(repo, reverted_commit) {
+        apply_reverted_prompt_lines(&mut authorship_log, &reverted_log);
+    }
+    Ok(authorship_log)
+}
+
+/// Count every line the reverted commit attributed to a prompt as overridden in
+/// the revert's note, adding the prompt record when the revert doesn't have it.
+fn apply_reverted_prompt_lines(authorship_log: &mut AuthorshipLog, reverted_log: &AuthorshipLog) {
+    let mut undone_lines: BTreeMap<&str, u32> = BTreeMap::new();
+    for attestation in &reverted_log.attestations {
+        for entry in &attestation.entries {
+            let line_count: u32 = entry
+                .line_ranges
+                .iter()
+                .map(|range| match range {
+                    crate::authorship::authorship_log::LineRange::Single(_) => 1,
+                    crate::authorship::authorship_log::LineRange::Range(start, end) => {
+                        end.saturating_sub(*start).saturating_add(1)
+                    }
+                })
+                .sum();
+            *undone_lines.entry(entry.hash.as_str()).or_default() += line_count;
+        }
+    }
+
+    for (hash, line_count) in undone_lines {
+        let Some(reverted_record) = reverted_log.metadata.prompts.get(hash) else {
+            continue;
+        };
+        let record = authorship_log
+            .metadata
+            .prompts
+            .entry(hash.to_string())
+            .or_insert_with(|| crate::authorship::authorship_log::PromptRecord {
+                accepted_lines: 0,
+                overriden_lines: 0,
+                ..reverted_record.clone()
+            });
+        record.overriden_lines = record.overriden_lines.saturating_add(line_count);
+    }
+}
+
 /// Get file contents from a commit tree for specified pathspecs
 fn get_committed_files_content(
     repo: &Repository,
@@ -1062,14 +1660,8 @@ fn batch_read_blob_contents(
         return Ok(HashMap::new());
     }
 
-    let mut args = repo.global_args_for_exec();
-    args.push("cat-file".to_string());
-    args.push("--batch".to_string());
-
-    let stdin_data = blob_oids.join("\n") + "\n";
-    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
-
-    parse_cat_file_batch_output_with_oids(&output.stdout)
+    let output = cat_file_batch(&repo.global_args_for_exec(), blob_oids)?;
+    parse_cat_file_batch_output_with_oids(&output)
 }
 
 fn parse_cat_file_batch_output_with_oids(
@@ -1299,6 +1891,39 @@ fn build_first_parent_tree_pairs(
     Ok(pairs)
 }
 
+/// Like [`build_first_parent_tree_pairs`], but each commit is paired with the tree of
+/// the commit before it in `commit_shas` (the first with `base_commit`'s tree)
+fn build_sequential_tree_pairs(
+    repo: &Repository,
+    base_commit: &str,
+    commit_shas: &[String],
+) -> Result<Vec<(String, String, String)>, GitAiError> {
+    let mut commits = Vec::with_capacity(commit_shas.len() + 1);
+    commits.push(base_commit.to_string());
+    commits.extend(commit_shas.iter().cloned());
+    let commit_metadata = load_commit_metadata_batch(repo, &commits)?;
+
+    let mut pairs = Vec::with_capacity(commit_shas.len());
+    for window in commits.windows(2) {
+        let [previous, commit_sha] = window else {
+            continue;
+        };
+        let tree_oid = |sha: &String| {
+            commit_metadata
+                .get(sha)
+                .map(|meta| meta.tree_oid.clone())
+                .filter(|tree_oid| !tree_oid.is_empty())
+                .ok_or_else(|| GitAiError::Generic(format!("Missing tree oid for commit {}", sha)))
+        };
+        pairs.push((
+            commit_sha.clone(),
+            tree_oid(previous)?,
+            tree_oid(commit_sha)?,
+        ));
+    }
+    Ok(pairs)
+}
+
 fn collect_changed_file_contents_for_commit_pairs(
     repo: &Repository,
     commit_pairs: &[(String, String, String)],
@@ -1502,6 +2127,92 @@ pub fn rewrite_authorship_after_commit_amend(
     // Update base commit SHA
     authorship_log.metadata.base_commit_sha = amended_commit.to_string();
 
+    let original_log = get_reference_as_authorship_log_v3(repo, original_commit).ok();
+    let checkpoints = working_log.read_all_checkpoints().unwrap_or_default();
+    let config = crate::config::Config::get();
+
+    // Sub-line ranges: the original commit's, then any the amend's own edits wrote
+    if let Some(original_log) = &original_log {
+        carry_char_ranges(
+            repo,
+            original_log,
+            original_commit,
+            &mut authorship_log,
+            amended_commit,
+        );
+    }
+    if config.attribution_granularity() == crate::config::AttributionGranularity::Char
+        && let Err(e) = post_commit::record_sub_line_attributions(
+            repo,
+            &parent_sha,
+            amended_commit,
+            &checkpoints,
+            &mut authorship_log,
+        )
+    {
+        debug_log(&format!("Failed to record sub-line attributions: {}", e));
+    }
+
+    // Generated files: recount them at the amended commit, keeping those the
+    // original commit's agents edited
+    let generated_rules = &config.tracking().generated;
+    if generated_rules.is_empty() {
+        if let Some(original_log) = &original_log {
+            authorship_log
+                .metadata
+                .merge_generated(&original_log.metadata);
+        }
+    } else {
+        match post_commit::summarize_generated_files(
+            repo,
+            amended_commit,
+            generated_rules,
+            &checkpoints,
+        ) {
+            Ok(mut generated) => {
+                for (rule, files) in generated.iter_mut() {
+                    if let Some(original) = original_log
+                        .as_ref()
+                        .and_then(|log| log.metadata.generated.get(rule))
+                    {
+                        files.ai_files = files.ai_files.max(original.ai_files).min(files.files);
+                    }
+                }
+                authorship_log.metadata.generated = generated;
+            }
+            Err(e) => debug_log(&format!("Failed to summarize generated files: {}", e)),
+        }
+    }
+
+    // Submodule bumps: the amended commit's tree decides them
+    match post_commit::submodule_updates(repo, &parent_sha, amended_commit) {
+        Ok(submodules) => {
+            authorship_log.metadata.submodules = submodules;
+        }
+        Err(e) => {
+            debug_log(&format!("Failed to record submodule updates: {}", e));
+            if let Some(original_log) = &original_log {
+                authorship_log
+                    .metadata
+                    .merge_submodules(&original_log.metadata);
+            }
+        }
+    }
+
+    // Keep pairing partners from the original commit and pick up trailers added by the amend
+    let mut co_authors = original_log
+        .map(|log| log.metadata.humans)
+        .unwrap_or_default();
+    co_authors.extend(parse_co_authored_by(
+        &amended_commit_obj.body().unwrap_or_default(),
+    ));
+    authorship_log.metadata.humans = collect_humans(
+        &_human_author,
+        &checkpoints,
+        &co_authors,
+        &IdentityResolver::from_config(false),
+    );
+
     // Save authorship log
     let authorship_json = authorship_log
         .serialize_to_string()
@@ -1526,6 +2237,16 @@ pub fn walk_commits_to_base(
     repository: &Repository,
     head: &str,
     base: &str,
+) -> Result<Vec<String>, crate::error::GitAiError> {
+    walk_commits_to_base_with(repository, head, base, Config::get().history())
+}
+
+/// `walk_commits_to_base`, deepening a shallow clone as `history` allows
+fn walk_commits_to_base_with(
+    repository: &Repository,
+    head: &str,
+    base: &str,
+    history: &HistorySettings,
 ) -> Result<Vec<String>, crate::error::GitAiError> {
     if head == base {
         return Ok(Vec::new());
@@ -1533,16 +2254,22 @@ pub fn walk_commits_to_base(
 
     // Validate commit-ish values early so callers get a clear error.
     repository.find_commit(head.to_string())?;
-    repository.find_commit(base.to_string())?;
 
     // Guard against pathological traversals when `base` is not actually an ancestor.
     // The old BFS fallback could walk huge histories in this case.
-    let mut is_ancestor_args = repository.global_args_for_exec();
-    is_ancestor_args.push("merge-base".to_string());
-    is_ancestor_args.push("--is-ancestor".to_string());
-    is_ancestor_args.push(base.to_string());
-    is_ancestor_args.push(head.to_string());
-    if exec_git(&is_ancestor_args).is_err() {
+    let reaches_base = || {
+        let mut is_ancestor_args = repository.global_args_for_exec();
+        is_ancestor_args.push("merge-base".to_string());
+        is_ancestor_args.push("--is-ancestor".to_string());
+        is_ancestor_args.push(base.to_string());
+        is_ancestor_args.push(head.to_string());
+        exec_git(&is_ancestor_args).is_ok()
+    };
+    if !reaches_base() && !repository.deepen_until(history, reaches_base) {
+        if repository.is_shallow() {
+            return walk_available_commits_to_base(repository, head, base);
+        }
+        repository.find_commit(base.to_string())?;
         return Err(GitAiError::Generic(format!(
             "Base commit {} is not an ancestor of {}",
             base, head
@@ -1557,7 +2284,34 @@ pub fn walk_commits_to_base(
     args.push("--ancestry-path".to_string());
     args.push(format!("{}..{}", base, head));
 
-    let output = exec_git(&args)?;
+    rev_list_commits(&args)
+}
+
+/// `walk_commits_to_base` for a shallow clone whose history stops before `head`
+/// reaches `base`: the commits above `base` (or all of them, when `base` itself
+/// wasn't fetched) down to the shallow boundary, so attribution covers what's present.
+fn walk_available_commits_to_base(
+    repository: &Repository,
+    head: &str,
+    base: &str,
+) -> Result<Vec<String>, GitAiError> {
+    debug_log(&format!(
+        "Shallow clone does not reach {} from {}; limiting to available history",
+        base, head
+    ));
+    let mut args = repository.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push("--topo-order".to_string());
+    if repository.find_commit(base.to_string()).is_ok() {
+        args.push(format!("{}..{}", base, head));
+    } else {
+        args.push(head.to_string());
+    }
+    rev_list_commits(&args)
+}
+
+fn rev_list_commits(args: &[String]) -> Result<Vec<String>, GitAiError> {
+    let output = exec_git(args)?;
     let stdout = String::from_utf8(output.stdout)?;
     let commits = stdout
         .lines()
@@ -1854,6 +2608,33 @@ fn load_note_contents_for_commit_pairs(
     Ok(source_note_content_by_target_commit)
 }
 
+/// Source notes with their commits, keyed by the commit each was rewritten to
+fn source_logs_for_commit_pairs(
+    repo: &Repository,
+    commit_pairs: &[(String, String)],
+) -> Result<HashMap<String, Vec<(String, AuthorshipLog)>>, GitAiError> {
+    let source_commits: Vec<String> = commit_pairs
+        .iter()
+        .map(|(source_commit, _target_commit)| source_commit.clone())
+        .collect();
+    let source_note_contents = load_note_contents_for_commits(repo, &source_commits)?;
+
+    let mut sources: HashMap<String, Vec<(String, AuthorshipLog)>> = HashMap::new();
+    for (source_commit, target_commit) in commit_pairs {
+        let Some(log) = source_note_contents
+            .get(source_commit)
+            .and_then(|raw_note| AuthorshipLog::deserialize_from_string(raw_note).ok())
+        else {
+            continue;
+        };
+        sources
+            .entry(target_commit.clone())
+            .or_default()
+            .push((source_commit.clone(), log));
+    }
+    Ok(sources)
+}
+
 fn remap_note_content_for_target_commit(note_content: &str, target_commit: &str) -> String {
     if let Some(remapped_note) = try_remap_base_commit_sha_field(note_content, target_commit) {
         return remapped_note;
@@ -2385,6 +3166,92 @@ fn upsert_file_attestation(
     }
 }
 
+/// Add the commit-level metadata of `source`, a note `target` was rewritten
+/// from, to `target`. Notes squashed together sum their generated-file counts
+/// and chain their submodule bumps, so pass them oldest first.
+fn carry_commit_metadata(source: &AuthorshipLog, target: &mut AuthorshipLog) {
+    target.metadata.merge_generated(&source.metadata);
+    target.metadata.merge_submodules(&source.metadata);
+}
+
+/// Carry `source`'s sub-line ranges, attested at `source_commit`, onto `target`,
+/// the note for `target_commit`. Each range follows its line through the diff
+/// between the two versions of the file; ranges on lines the rewrite changed, or
+/// that `target` now attests as whole AI lines, are dropped.
+fn carry_char_ranges(
+    repo: &Repository,
+    source: &AuthorshipLog,
+    source_commit: &str,
+    target: &mut AuthorshipLog,
+    target_commit: &str,
+) {
+    let file_text = |path: &str, commit: &str| {
+        repo.get_file_content(path, commit)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    };
+
+    for file in &source.attestations {
+        let mut entries: Vec<AttestationEntry> = file
+            .entries
+            .iter()
+            .filter(|entry| !entry.char_ranges.is_empty())
+            .map(|entry| {
+                let mut carried = AttestationEntry::new(entry.hash.clone(), Vec::new());
+                carried.char_ranges = entry.char_ranges.clone();
+                carried
+            })
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        let (Some(old_content), Some(new_content)) = (
+            file_text(&file.file_path, source_commit),
+            file_text(&file.file_path, target_commit),
+        ) else {
+            continue;
+        };
+
+        if old_content != new_content {
+            // Lines before `line` are in the new file's numbering, the rest still in the old
+            let mut line = 1u32;
+            for change in compute_line_changes(&old_content, &new_content) {
+                match change.tag() {
+                    LineChangeTag::Equal => line += 1,
+                    LineChangeTag::Delete => {
+                        for entry in &mut entries {
+                            entry.remove_line_ranges(&[LineRange::Single(line)]);
+                            entry.shift_line_ranges(line + 1, -1);
+                        }
+                    }
+                    LineChangeTag::Insert => {
+                        for entry in &mut entries {
+                            entry.shift_line_ranges(line, 1);
+                        }
+                        line += 1;
+                    }
+                }
+            }
+        }
+
+        for entry in entries {
+            let mut added = false;
+            for range in entry.char_ranges {
+                added |= target.add_char_range(&file.file_path, &entry.hash, range);
+            }
+            if added
+                && !target.metadata.prompts.contains_key(&entry.hash)
+                && let Some(record) = source.metadata.prompts.get(&entry.hash)
+            {
+                target
+                    .metadata
+                    .prompts
+                    .insert(entry.hash.clone(), record.clone());
+            }
+        }
+    }
+}
+
 fn build_authorship_log_from_state(
     base_commit_sha: &str,
     prompts: &BTreeMap<String, BTreeMap<String, crate::authorship::authorship_log::PromptRecord>>,
@@ -2684,7 +3551,7 @@ fn transform_attributions_to_final_state(
     use crate::authorship::virtual_attribution::VirtualAttributions;
 
     let tracker = AttributionTracker::new();
-    let ts = source_va.timestamp();
+    let ts = source_va.rewrite_timestamp();
     let repo = source_va.repo().clone();
     let base_commit = source_va.base_commit().to_string();
 
@@ -2901,9 +3768,10 @@ fn transform_attributions_to_final_state(
 #[cfg(test)]
 mod tests {
     use super::{
-        collect_changed_file_contents_from_diff, get_pathspecs_from_commits,
-        parse_cat_file_batch_output_with_oids, transform_attributions_to_final_state,
-        try_fast_path_rebase_note_remap, walk_commits_to_base,
+        autosquash_targets, collect_changed_file_contents_from_diff, get_pathspecs_from_commits,
+        parse_cat_file_batch_output_with_oids, rebase_reflog_action,
+        transform_attributions_to_final_state, try_fast_path_rebase_note_remap,
+        walk_commits_to_base, walk_commits_to_base_with,
     };
     use crate::authorship::attribution_tracker::{Attribution, LineAttribution};
     use crate::authorship::authorship_log::{LineRange, PromptRecord};
@@ -2912,6 +3780,7 @@ mod tests {
     };
     use crate::authorship::virtual_attribution::VirtualAttributions;
     use crate::authorship::working_log::{AgentId, Checkpoint, CheckpointKind};
+    use crate::config::HistorySettings;
     use crate::git::refs::{notes_add, show_authorship_note};
     use crate::git::rewrite_log::{RebaseCompleteEvent, RewriteLogEvent};
     use crate::git::test_utils::TmpRepo;
@@ -3024,6 +3893,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn walk_commits_to_base_deepens_shallow_clone() {
+        let source = TmpRepo::new().expect("tmp repo");
+        let mut commits = Vec::new();
+        for line in ["a", "b", "c", "d"] {
+            repo_append_line(&source, line);
+            commits.push(source.get_head_commit_sha().expect("head sha"));
+        }
+
+        let clone = source
+            .clone_with_args(&["--depth=1"])
+            .expect("shallow clone");
+        assert!(clone.gitai_repo().is_shallow());
+
+        let history = HistorySettings {
+            deepen: true,
+            deepen_limit: 1000,
+        };
+        let walked =
+            walk_commits_to_base_with(clone.gitai_repo(), &commits[3], &commits[0], &history)
+                .expect("walk should deepen");
+        assert_eq!(
+            walked,
+            vec![commits[3].clone(), commits[2].clone(), commits[1].clone()]
+        );
+        assert!(!clone.gitai_repo().is_shallow());
+    }
+
+    #[test]
+    fn walk_commits_to_base_limits_shallow_clone_to_available_history() {
+        let source = TmpRepo::new().expect("tmp repo");
+        let mut commits = Vec::new();
+        for line in ["a", "b", "c", "d"] {
+            repo_append_line(&source, line);
+            commits.push(source.get_head_commit_sha().expect("head sha"));
+        }
+
+        let clone = source
+            .clone_with_args(&["--depth=2"])
+            .expect("shallow clone");
+        // Without a remote there is nothing to deepen from
+        clone
+            .git_command(&["remote", "remove", "origin"])
+            .expect("remove remote");
+
+        let walked = walk_commits_to_base(clone.gitai_repo(), &commits[3], &commits[0])
+            .expect("walk should degrade");
+        assert_eq!(walked, vec![commits[3].clone(), commits[2].clone()]);
+    }
+
+    fn repo_append_line(repo: &TmpRepo, line: &str) {
+        let path = repo.path().join("f.txt");
+        let mut contents = std::fs::read_to_string(&path).unwrap_or_default();
+        contents.push_str(line);
+        contents.push('\n');
+        repo.write_file("f.txt", &contents, true)
+            .expect("write file");
+        repo.commit_with_message(line).expect("commit");
+    }
+
     #[test]
     fn get_pathspecs_from_commits_keeps_hex_filenames() {
         let repo = TmpRepo::new().expect("tmp repo");
@@ -3380,6 +4309,8 @@ mod tests {
                 accepted_lines: 5,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -3568,6 +4499,8 @@ mod tests {
                 accepted_lines: 13,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
         prompts.insert(
@@ -3585,6 +4518,8 @@ mod tests {
                 accepted_lines: 6,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -3691,6 +4626,8 @@ mod tests {
                 accepted_lines: 3,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -3830,6 +4767,8 @@ mod tests {
                 accepted_lines: 4,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
         let old_wl = repo
@@ -3952,6 +4891,8 @@ mod tests {
                 accepted_lines: 8,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
         let v1_wl = repo
@@ -4119,6 +5060,8 @@ mod tests {
                 accepted_lines: 13,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
         prompts.insert(
@@ -4136,6 +5079,8 @@ mod tests {
                 accepted_lines: 16,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -4223,4 +5168,54 @@ mod tests {
         assert_eq!(copilot_prompt.agent_id.tool, "copilot");
         assert_eq!(copilot_prompt.total_additions, 16);
     }
+
+    #[test]
+    fn test_autosquash_targets_follow_git_matching() {
+        let commits: Vec<String> = ["aaaa1111", "bbbb2222", "cccc3333", "dddd4444", "eeee5555"]
+            .map(String::from)
+            .to_vec();
+        let subjects: Vec<String> = [
+            "Add parser",
+            "Add lexer",
+            "fixup! Add parser",
+            "squash! fixup! Add parser",
+            "amend! bbbb",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(autosquash_targets(&commits, &subjects), vec![0, 1, 0, 0, 1]);
+
+        // A subject prefix matches too; a fixup naming nothing earlier stays put
+        let subjects: Vec<String> = [
+            "Add parser for expressions",
+            "fixup! Add parser",
+            "fixup! Remove lexer",
+            "Add lexer",
+            "fixup! Add lexer",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(autosquash_targets(&commits, &subjects), vec![0, 0, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_rebase_reflog_action_reads_todo_command() {
+        assert_eq!(
+            rebase_reflog_action("rebase (start): checkout main"),
+            Some("start")
+        );
+        assert_eq!(
+            rebase_reflog_action("rebase (squash): Add feature"),
+            Some("squash")
+        );
+        assert_eq!(
+            rebase_reflog_action("rebase -i (finish): returning to refs/heads/feature"),
+            Some("finish")
+        );
+        assert_eq!(
+            rebase_reflog_action("commit (amend): Fix (typo): oops"),
+            None
+        );
+        assert_eq!(rebase_reflog_action("checkout: moving from a to b"), None);
+    }
 }
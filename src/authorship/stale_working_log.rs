@@ -0,0 +1,314 @@
+//! Working logs are keyed by the commit HEAD pointed at when their checkpoints were
+//! written. Tools that move HEAD without running git-ai's hooks (IDE git clients,
+//! libgit2-based GUIs, `git -c core.hooksPath=...`) leave that working log behind,
+//! and the next checkpoint would quietly start an empty one on the new HEAD. The
+//! checkpoint calls `migrate_stale_working_log` first to carry the attributions over.
+//!
+//! Commits made while post-commit wasn't running (a hook disabled for a while) are
+//! found in the HEAD reflog by `catch_up_missed_commits`, which writes the notes
+//! post-commit would have from the working log they left behind. Only checkpoints
+//! (including the one pre-commit runs) do this, so read-only commands never write notes.
+
+use crate::authorship::attribution_tracker::line_attributions_to_attributions;
+use crate::authorship::post_commit;
+use crate::authorship::virtual_attribution::{VirtualAttributions, restore_stashed_va};
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::refs::show_authorship_note;
+use crate::git::repo_storage::PersistedWorkingLog;
+use crate::git::repository::{Commit, Repository, exec_git};
+use crate::utils::debug_log;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+
+/// How many HEAD reflog entries are searched for commits post-commit missed
+const MISSED_COMMIT_REFLOG_DEPTH: usize = 50;
+
+/// If the last checkpoint was written against a commit other than `head` and its
+/// working log is still around, move its attributions onto `head`. When `head` is a
+/// commit made straight on top of it that never got a note, that commit is noted the
+/// way post-commit would have; any other child takes the working log as it is. When
+/// `head` isn't a child of the stale base, the attributions are rebuilt against the
+/// working tree as INITIAL attributions for `head`. Returns the stale base that was
+/// migrated.
+pub fn migrate_stale_working_log(
+    repo: &Repository,
+    head: &str,
+) -> Result<Option<String>, GitAiError> {
+    // Commits post-commit missed are noted first, which moves the base forward
+    if let Err(e) = catch_up_missed_commits(repo) {
+        debug_log(&format!("Failed to catch up missed commits: {}", e));
+    }
+    let Some(stale_base) = repo.storage.last_checkpoint_base() else {
+        return Ok(None);
+    };
+    if stale_base == head || !repo.storage.has_working_log(&stale_base) {
+        return Ok(None);
+    }
+    // HEAD moves step by step while these run and their hooks migrate at the end
+    if operation_in_progress(repo) {
+        return Ok(None);
+    }
+
+    let stale_log = repo.storage.working_log_for_base_commit(&stale_base);
+    let stale_checkpoints = stale_log.read_all_checkpoints().unwrap_or_default();
+    if stale_checkpoints.is_empty() && stale_log.read_initial_attributions().files.is_empty() {
+        return Ok(None);
+    }
+    if repo.storage.has_working_log(head) {
+        let head_log = repo.storage.working_log_for_base_commit(head);
+        let head_started = !head_log
+            .read_all_checkpoints()
+            .unwrap_or_default()
+            .is_empty()
+            || !head_log.read_initial_attributions().files.is_empty();
+        if head_started {
+            return Ok(None);
+        }
+    }
+
+    debug_log(&format!(
+        "Working log base {} no longer matches HEAD {}; migrating attributions",
+        stale_base, head
+    ));
+
+    // A commit made straight on top of the stale base is what post-commit missed;
+    // its attributions can be split the usual way as long as the working tree still
+    // matches what the checkpoints saw
+    let head_commit = repo.find_commit(head.to_string())?;
+    let committed_on_stale_base = match head_commit.parent(0) {
+        Ok(parent) => parent.id() == stale_base,
+        Err(_) => stale_base == "initial",
+    };
+    if committed_on_stale_base
+        && show_authorship_note(repo, head).is_none()
+        && working_tree_matches_checkpoints(&stale_log, &stale_checkpoints)
+    {
+        post_commit::post_commit(
+            repo,
+            Some(stale_base.clone()),
+            head.to_string(),
+            author_identity(&head_commit)?,
+            true,
+        )?;
+        return Ok(Some(stale_base));
+    }
+
+    // HEAD went somewhere else entirely (a reset, another branch): the files may
+    // differ from what the checkpoints saw, so the attributions are carried onto
+    // the working tree and kept as INITIAL for HEAD, as a checkout does
+    if !committed_on_stale_base {
+        let stale_va =
+            stale_working_log_attributions(repo, &stale_base, &stale_log, &stale_checkpoints)?;
+        repo.storage
+            .delete_working_log_for_base_commit(&stale_base)?;
+        restore_stashed_va(repo, &stale_base, head, stale_va);
+        return Ok(Some(stale_base));
+    }
+
+    // Otherwise the checkpoints move over as they are: each one records the file
+    // contents it saw, so the next checkpoint diffs from there, and lines HEAD
+    // already has simply won't show up in the next commit's diff
+    let head_dir = repo.storage.working_logs.join(head);
+    if head_dir.exists() {
+        fs::remove_dir_all(&head_dir)?;
+    }
+    repo.storage.rename_working_log(&stale_base, head)?;
+    Ok(Some(stale_base))
+}
+
+/// Write the notes post-commit missed for commits made on top of the last
+/// checkpoint's base. The HEAD reflog is searched for the last time HEAD sat on that
+/// base, and the plain commits made one on top of the other from there are noted in
+/// order: each one's uncommitted lines carry over to the next, as they would have
+/// with the hook running. Returns the commits that were noted.
+pub fn catch_up_missed_commits(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let Some(base) = repo.storage.last_checkpoint_base() else {
+        return Ok(Vec::new());
+    };
+    if !repo.storage.has_working_log(&base) || operation_in_progress(repo) {
+        return Ok(Vec::new());
+    }
+    let missed = missed_commits_since(repo, &base)?;
+    if missed.is_empty() {
+        return Ok(missed);
+    }
+
+    // Attributions are split against the working tree, which has to be the one the
+    // checkpoints saw for the oldest commit; later ones pick up from its INITIAL
+    let base_log = repo.storage.working_log_for_base_commit(&base);
+    let checkpoints = base_log.read_all_checkpoints().unwrap_or_default();
+    // A log of human checkpoints alone has no attribution to reconstruct
+    let has_ai_attribution = checkpoints
+        .iter()
+        .any(|checkpoint| checkpoint.kind != CheckpointKind::Human)
+        || !base_log.read_initial_attributions().files.is_empty();
+    if !has_ai_attribution || !working_tree_matches_checkpoints(&base_log, &checkpoints) {
+        return Ok(Vec::new());
+    }
+
+    let mut noted = Vec::new();
+    let mut parent = base;
+    for commit_sha in missed {
+        if show_authorship_note(repo, &commit_sha).is_some() {
+            break;
+        }
+        debug_log(&format!(
+            "Commit {} has no authorship note; writing it from the working log of {}",
+            commit_sha, parent
+        ));
+        let commit = repo.find_commit(commit_sha.clone())?;
+        post_commit::post_commit(
+            repo,
+            Some(parent.clone()),
+            commit_sha.clone(),
+            author_identity(&commit)?,
+            true,
+        )?;
+        parent = commit_sha.clone();
+        noted.push(commit_sha);
+    }
+    if let Some(last) = noted.last() {
+        repo.storage.set_last_checkpoint_base(last)?;
+    }
+    Ok(noted)
+}
+
+/// The commits made one on top of the other, oldest first, since the last time
+/// the HEAD reflog shows HEAD on `base` ("initial" for an unborn branch). Amends,
+/// merges and anything else that isn't a plain commit end the chain.
+fn missed_commits_since(repo: &Repository, base: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "reflog".to_string(),
+        "show".to_string(),
+        "--format=%H%x09%P%x09%gs".to_string(),
+        format!("-n{}", MISSED_COMMIT_REFLOG_DEPTH),
+        "HEAD".to_string(),
+    ]);
+    let Ok(output) = exec_git(&args) else {
+        return Ok(Vec::new());
+    };
+    let stdout = String::from_utf8(output.stdout)?;
+    let entries: Vec<(&str, &str, &str)> = stdout
+        .lines()
+        .rev()
+        .map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            (
+                fields.next().unwrap_or_default(),
+                fields.next().unwrap_or_default(),
+                fields.next().unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let start = if base == "initial" {
+        0
+    } else {
+        match entries.iter().rposition(|(commit, _, _)| *commit == base) {
+            Some(position) => position + 1,
+            None => return Ok(Vec::new()),
+        }
+    };
+    let mut missed: Vec<String> = Vec::new();
+    for (commit, parents, subject) in &entries[start..] {
+        let plain_commit =
+            subject.starts_with("commit: ") || subject.starts_with("commit (initial): ");
+        let parent = missed.last().map(String::as_str).unwrap_or(base);
+        let on_parent = if parent == "initial" {
+            parents.is_empty()
+        } else {
+            *parents == parent
+        };
+        if !plain_commit || !on_parent {
+            break;
+        }
+        missed.push(commit.to_string());
+    }
+    Ok(missed)
+}
+
+/// The stale working log's attributions, laid over the file contents its last
+/// checkpoints saw rather than today's working tree, so they can be carried onto it
+fn stale_working_log_attributions(
+    repo: &Repository,
+    stale_base: &str,
+    stale_log: &PersistedWorkingLog,
+    checkpoints: &[Checkpoint],
+) -> Result<VirtualAttributions, GitAiError> {
+    let va =
+        VirtualAttributions::from_just_working_log(repo.clone(), stale_base.to_string(), None)?;
+    let mut latest_blobs: HashMap<&str, &str> = HashMap::new();
+    for entry in checkpoints
+        .iter()
+        .flat_map(|checkpoint| &checkpoint.entries)
+    {
+        latest_blobs.insert(&entry.file, &entry.blob_sha);
+    }
+
+    let mut attributions = HashMap::new();
+    let mut file_contents = HashMap::new();
+    for file in va.files() {
+        let Some(line_attrs) = va.get_line_attributions(&file) else {
+            continue;
+        };
+        // Files only in INITIAL have no recorded content; theirs is the working tree's
+        let content = latest_blobs
+            .get(file.as_str())
+            .and_then(|blob_sha| stale_log.get_file_version(blob_sha).ok())
+            .or_else(|| va.get_file_content(&file).cloned())
+            .unwrap_or_default();
+        let char_attrs = line_attributions_to_attributions(line_attrs, &content, 0);
+        attributions.insert(file.clone(), (char_attrs, line_attrs.clone()));
+        file_contents.insert(file, content);
+    }
+    Ok(VirtualAttributions::new_with_prompts(
+        repo.clone(),
+        stale_base.to_string(),
+        attributions,
+        file_contents,
+        va.prompts().clone(),
+        0,
+    ))
+}
+
+fn author_identity(commit: &Commit) -> Result<String, GitAiError> {
+    let author = commit.author()?;
+    Ok(format!(
+        "{} <{}>",
+        author.name().unwrap_or("unknown"),
+        author.email().unwrap_or_default()
+    ))
+}
+
+/// Whether every file the checkpoints recorded still has the content the last of
+/// them saw, so their line attributions line up with the working tree
+fn working_tree_matches_checkpoints(
+    working_log: &PersistedWorkingLog,
+    checkpoints: &[Checkpoint],
+) -> bool {
+    let mut latest_blobs: HashMap<&str, &str> = HashMap::new();
+    for entry in checkpoints
+        .iter()
+        .flat_map(|checkpoint| &checkpoint.entries)
+    {
+        latest_blobs.insert(&entry.file, &entry.blob_sha);
+    }
+    latest_blobs.into_iter().all(|(file, blob_sha)| {
+        let content = working_log
+            .read_current_file_content(file)
+            .unwrap_or_default();
+        format!("{:x}", Sha256::digest(content.as_bytes())) == blob_sha
+    })
+}
+
+fn operation_in_progress(repo: &Repository) -> bool {
+    let git_dir = repo.path();
+    git_dir.join("rebase-merge").is_dir()
+        || git_dir.join("rebase-apply").is_dir()
+        || git_dir.join("MERGE_HEAD").is_file()
+        || git_dir.join("CHERRY_PICK_HEAD").is_file()
+        || git_dir.join("REVERT_HEAD").is_file()
+}
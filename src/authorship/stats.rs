@@ -4,11 +4,14 @@ use crate::authorship::transcript::Message;
 use crate::error::GitAiError;
 use crate::git::refs::get_authorship;
 use crate::git::repository::Repository;
+use crate::i18n::{self, Catalog};
+use crate::output::{self, OutputMode};
 use crate::utils::debug_log;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct ToolModelHeadlineStats {
     #[serde(default)]
     pub ai_additions: u32, // Number of lines committed with AI attribution (full and/or mixed)
@@ -24,7 +27,7 @@ pub struct ToolModelHeadlineStats {
     pub time_waiting_for_ai: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct CommitStats {
     #[serde(default)]
     pub human_additions: u32, // Number of lines committed with human attribution (full and/or mixed)
@@ -46,6 +49,36 @@ pub struct CommitStats {
     pub git_diff_added_lines: u32,
     #[serde(default)]
     pub tool_model_breakdown: BTreeMap<String, ToolModelHeadlineStats>,
+    /// Human additions split across pairing partners, when more than one human worked on the commit
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub human_contributors: BTreeMap<String, u32>,
+}
+
+impl CommitStats {
+    /// Add another commit's stats into this one (used to total per-commit stats).
+    pub fn accumulate(&mut self, other: &CommitStats) {
+        self.human_additions += other.human_additions;
+        self.mixed_additions += other.mixed_additions;
+        self.ai_additions += other.ai_additions;
+        self.ai_accepted += other.ai_accepted;
+        self.total_ai_additions += other.total_ai_additions;
+        self.total_ai_deletions += other.total_ai_deletions;
+        self.time_waiting_for_ai += other.time_waiting_for_ai;
+        self.git_diff_deleted_lines += other.git_diff_deleted_lines;
+        self.git_diff_added_lines += other.git_diff_added_lines;
+        for (key, tool_stats) in &other.tool_model_breakdown {
+            let entry = self.tool_model_breakdown.entry(key.clone()).or_default();
+            entry.ai_additions += tool_stats.ai_additions;
+            entry.mixed_additions += tool_stats.mixed_additions;
+            entry.ai_accepted += tool_stats.ai_accepted;
+            entry.total_ai_additions += tool_stats.total_ai_additions;
+            entry.total_ai_deletions += tool_stats.total_ai_deletions;
+            entry.time_waiting_for_ai += tool_stats.time_waiting_for_ai;
+        }
+        for (human, additions) in &other.human_contributors {
+            *self.human_contributors.entry(human.clone()).or_default() += additions;
+        }
+    }
 }
 
 pub fn stats_command(
@@ -54,6 +87,31 @@ pub fn stats_command(
     json: bool,
     ignore_patterns: &[String],
 ) -> Result<(), GitAiError> {
+    let (target, refname) = resolve_stats_target(repo, commit_sha)?;
+
+    debug_log(&format!(
+        "Stats command found commit: {} refname: {}",
+        target, refname
+    ));
+
+    let stats = stats_for_commit_stats(repo, &target, ignore_patterns)?;
+
+    if json {
+        let json_str = serde_json::to_string(&stats)?;
+        println!("{}", json_str);
+    } else {
+        write_stats_to_terminal(&stats, true);
+    }
+
+    Ok(())
+}
+
+/// Resolve the commit `stats` reports on: `commit_sha` if given, otherwise HEAD.
+/// Returns (full sha, refname).
+pub fn resolve_stats_target(
+    repo: &Repository,
+    commit_sha: Option<&str>,
+) -> Result<(String, String), GitAiError> {
     let (target, refname) = if let Some(sha) = commit_sha {
         // Validate that the commit exists using revparse_single
         match repo.revparse_single(sha) {
@@ -75,24 +133,19 @@ pub fn stats_command(
         (target, name)
     };
 
-    debug_log(&format!(
-        "Stats command found commit: {} refname: {}",
-        target, refname
-    ));
-
-    let stats = stats_for_commit_stats(repo, &target, ignore_patterns)?;
-
-    if json {
-        let json_str = serde_json::to_string(&stats)?;
-        println!("{}", json_str);
-    } else {
-        write_stats_to_terminal(&stats, true);
-    }
-
-    Ok(())
+    Ok((target, refname))
 }
 
 pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
+    write_stats_to_terminal_with_mode(stats, print, output::mode(), i18n::catalog())
+}
+
+fn write_stats_to_terminal_with_mode(
+    stats: &CommitStats,
+    print: bool,
+    mode: &OutputMode,
+    catalog: &Catalog,
+) -> String {
     let mut output = String::new();
 
     // Set maximum bar width to 40 characters
@@ -100,12 +153,23 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
 
     // Handle deletion-only commits (no additions)
     if stats.git_diff_added_lines == 0 && stats.git_diff_deleted_lines > 0 {
+        if mode.plain {
+            return emit_stats_lines(
+                &[
+                    catalog.message("stats-plain-additions", &[("count", 0.into())]),
+                    catalog.message(
+                        "stats-plain-deletions",
+                        &[("count", stats.git_diff_deleted_lines.into())],
+                    ),
+                ],
+                print,
+            );
+        }
+
         // Show gray bar for deletion-only commit
         let mut progress_bar = String::new();
         progress_bar.push_str("you  ");
-        progress_bar.push_str("\x1b[90m"); // Gray color
-        progress_bar.push_str(&" ".repeat(bar_width)); // Gray bar
-        progress_bar.push_str("\x1b[0m"); // Reset color
+        progress_bar.push_str(&mode.paint("90", &" ".repeat(bar_width))); // Gray bar
         progress_bar.push_str(" ai");
 
         output.push_str(&progress_bar);
@@ -115,7 +179,13 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         }
 
         // Show "(no additions)" message below the bar
-        let no_additions_msg = format!("     \x1b[90m{:^40}\x1b[0m", "(no additions)");
+        let no_additions_msg = format!(
+            "     {}",
+            mode.paint(
+                "90",
+                &format!("{:^40}", catalog.message("stats-no-additions", &[]))
+            )
+        );
         output.push_str(&no_additions_msg);
         output.push('\n');
         if print {
@@ -187,13 +257,13 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     progress_bar.push_str("you  ");
 
     // Pure human bars (darkest)
-    progress_bar.push_str(&"█".repeat(final_pure_human_bars));
+    progress_bar.push_str(&mode.pick("█", "#").repeat(final_pure_human_bars));
 
     // Mixed bars (medium) - AI-generated but human-edited
-    progress_bar.push_str(&"▒".repeat(final_mixed_bars));
+    progress_bar.push_str(&mode.pick("▒", "=").repeat(final_mixed_bars));
 
     // AI bars (lightest) - pure AI, untouched
-    progress_bar.push_str(&"░".repeat(final_ai_bars));
+    progress_bar.push_str(&mode.pick("░", ".").repeat(final_ai_bars));
 
     progress_bar.push_str(" ai");
 
@@ -202,10 +272,20 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     let waiting_time_str = if stats.time_waiting_for_ai > 0 {
         let minutes = stats.time_waiting_for_ai / 60;
         let seconds = stats.time_waiting_for_ai % 60;
+        // Durations are passed as text: they read like clock values, not counts to group
         if minutes > 0 {
-            format!("{}m {}s", minutes, seconds)
+            catalog.message(
+                "duration-minutes-seconds",
+                &[
+                    ("minutes", minutes.to_string().into()),
+                    ("seconds", seconds.to_string().into()),
+                ],
+            )
         } else {
-            format!("{}s", seconds)
+            catalog.message(
+                "duration-seconds",
+                &[("seconds", seconds.to_string().into())],
+            )
         }
     } else {
         "0s".to_string()
@@ -228,6 +308,51 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         0
     };
 
+    if mode.plain {
+        let share = |id: &str, percentage: u32, lines: u32| {
+            catalog.message(
+                id,
+                &[
+                    ("percent", catalog.percent(percentage).into()),
+                    ("lines", lines.into()),
+                ],
+            )
+        };
+        let mut lines = vec![share(
+            "stats-plain-human",
+            pure_human_percentage,
+            pure_human,
+        )];
+        if mixed_percentage > 0 {
+            lines.push(share(
+                "stats-plain-mixed",
+                mixed_percentage,
+                stats.mixed_additions,
+            ));
+        }
+        lines.push(share("stats-plain-ai", ai_percentage, stats.ai_additions));
+        if stats.ai_additions > 0 {
+            lines.push(
+                catalog.message(
+                    "stats-plain-accepted",
+                    &[(
+                        "percent",
+                        catalog
+                            .percent(format!("{:.0}", _ai_acceptance_percentage))
+                            .into(),
+                    )],
+                ),
+            );
+            if stats.time_waiting_for_ai > 0 {
+                lines.push(catalog.message(
+                    "stats-plain-waited",
+                    &[("duration", waiting_time_str.into())],
+                ));
+            }
+        }
+        return emit_stats_lines(&lines, print);
+    }
+
     // Print the stats
     output.push_str(&progress_bar);
     output.push('\n');
@@ -241,12 +366,18 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         // Show all three: human, mixed, ai
         // Human% at left edge, mixed% in middle, AI% at right edge
         let percentage_line = format!(
-            "     {:<3}{:>12}mixed {:>3}%{:>12}{:>3}%",
-            format!("{}%", pure_human_percentage),
+            "     {:<3}{:>12}{}{:>12}{:>4}",
+            catalog.percent(pure_human_percentage),
             "",
-            mixed_percentage,
+            catalog.message(
+                "stats-mixed",
+                &[(
+                    "percent",
+                    format!("{:>4}", catalog.percent(mixed_percentage)).into()
+                )],
+            ),
             "",
-            ai_percentage
+            catalog.percent(ai_percentage)
         );
         output.push_str(&percentage_line);
         output.push('\n');
@@ -256,10 +387,10 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     } else {
         // No mixed, just show human and ai at bar edges
         let percentage_line = format!(
-            "     {:<3}{:>33}{:>3}%",
-            format!("{}%", pure_human_percentage),
+            "     {:<3}{:>33}{:>4}",
+            catalog.percent(pure_human_percentage),
             "",
-            ai_percentage
+            catalog.percent(ai_percentage)
         );
         output.push_str(&percentage_line);
         output.push('\n');
@@ -274,17 +405,44 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
             let minutes = stats.time_waiting_for_ai / 60;
             let seconds = stats.time_waiting_for_ai % 60;
             if minutes > 0 {
-                format!(" | waited {}m for ai", minutes)
+                format!(
+                    " | {}",
+                    catalog.message(
+                        "stats-waited-minutes",
+                        &[("minutes", minutes.to_string().into())]
+                    )
+                )
             } else {
-                format!(" | waited {}s for ai", seconds)
+                format!(
+                    " | {}",
+                    catalog.message(
+                        "stats-waited-seconds",
+                        &[("seconds", seconds.to_string().into())]
+                    )
+                )
             }
         } else {
             "".to_string()
         };
 
         let ai_acceptance_str = format!(
-            "     \x1b[90m{:.0}% AI code accepted{}\x1b[0m",
-            _ai_acceptance_percentage, waiting_time_str
+            "     {}",
+            mode.paint(
+                "90",
+                &format!(
+                    "{}{}",
+                    catalog.message(
+                        "stats-ai-accepted",
+                        &[(
+                            "percent",
+                            catalog
+                                .percent(format!("{:.0}", _ai_acceptance_percentage))
+                                .into()
+                        )],
+                    ),
+                    waiting_time_str
+                )
+            )
         );
         output.push_str(&ai_acceptance_str);
         output.push('\n');
@@ -295,6 +453,19 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     output
 }
 
+/// Plain-mode stats: one `label: value` line each
+fn emit_stats_lines(lines: &[String], print: bool) -> String {
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(line);
+        output.push('\n');
+        if print {
+            println!("{}", line);
+        }
+    }
+    output
+}
+
 #[allow(dead_code)]
 pub fn write_stats_to_markdown(stats: &CommitStats) -> String {
     let mut output = String::new();
@@ -463,6 +634,7 @@ pub fn stats_from_authorship_log(
         total_ai_deletions: 0,
         time_waiting_for_ai: 0,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
         git_diff_deleted_lines,
         git_diff_added_lines,
     };
@@ -492,6 +664,23 @@ pub fn stats_from_authorship_log(
             commit_stats.time_waiting_for_ai += waiting;
             tool_stats.time_waiting_for_ai += waiting;
         }
+
+        // Pruned notes keep only per-tool totals
+        for (tool_model, totals) in log.metadata.pruned.iter().flat_map(|p| &p.tools) {
+            commit_stats.total_ai_additions += totals.total_additions;
+            commit_stats.total_ai_deletions += totals.total_deletions;
+            commit_stats.mixed_additions += totals.overriden_lines;
+            commit_stats.time_waiting_for_ai += totals.time_waiting_for_ai;
+
+            let tool_stats = commit_stats
+                .tool_model_breakdown
+                .entry(tool_model.clone())
+                .or_default();
+            tool_stats.total_ai_additions += totals.total_additions;
+            tool_stats.total_ai_deletions += totals.total_deletions;
+            tool_stats.mixed_additions += totals.overriden_lines;
+            tool_stats.time_waiting_for_ai += totals.time_waiting_for_ai;
+        }
     }
 
     // TODO: Mixed additions come from prompt overrides and can exceed the final diff when we
@@ -525,6 +714,13 @@ pub fn stats_from_authorship_log(
         git_diff_added_lines.saturating_sub(commit_stats.ai_accepted),
     );
 
+    if let Some(log) = authorship_log {
+        commit_stats.human_contributors = crate::authorship::pairing::split_human_additions(
+            commit_stats.human_additions,
+            &log.metadata.humans,
+        );
+    }
+
     commit_stats
 }
 
@@ -599,6 +795,13 @@ fn accepted_lines_from_attestations(
         return (0, per_tool_model);
     };
 
+    if let Some(pruned) = &log.metadata.pruned {
+        total_ai_accepted += pruned.ai_accepted;
+        for (tool_model, totals) in &pruned.tools {
+            *per_tool_model.entry(tool_model.clone()).or_insert(0) += totals.ai_accepted;
+        }
+    }
+
     for file_attestation in &log.attestations {
         let Some(added_lines) = added_lines_by_file.get(&file_attestation.file_path) else {
             continue;
@@ -630,7 +833,8 @@ fn accepted_lines_from_attestations(
     (total_ai_accepted, per_tool_model)
 }
 
-fn line_range_overlap_len(range: &LineRange, added_lines: &[u32]) -> u32 {
+/// How many of `added_lines` (sorted, deduplicated) fall inside `range`
+pub fn line_range_overlap_len(range: &LineRange, added_lines: &[u32]) -> u32 {
     match range {
         LineRange::Single(line) => u32::from(added_lines.binary_search(line).is_ok()),
         LineRange::Range(start, end) => {
@@ -761,6 +965,7 @@ mod tests {
 
     use super::*;
     use crate::git::test_utils::TmpRepo;
+    use crate::i18n::Locale;
 
     #[test]
     fn test_terminal_stats_display() {
@@ -776,6 +981,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let mixed_output = write_stats_to_terminal(&stats, true);
@@ -793,6 +999,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let ai_only_output = write_stats_to_terminal(&ai_stats, true);
@@ -810,6 +1017,7 @@ mod tests {
             total_ai_additions: 0,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let human_only_output = write_stats_to_terminal(&human_stats, true);
@@ -827,6 +1035,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let minimal_human_output = write_stats_to_terminal(&minimal_human_stats, true);
@@ -844,12 +1053,86 @@ mod tests {
             total_ai_additions: 0,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let deletion_only_output = write_stats_to_terminal(&deletion_only_stats, true);
         assert_debug_snapshot!(deletion_only_output);
     }
 
+    #[test]
+    fn test_terminal_stats_ascii_and_plain_modes() {
+        let stats = CommitStats {
+            human_additions: 50,
+            mixed_additions: 10,
+            ai_additions: 50,
+            ai_accepted: 40,
+            time_waiting_for_ai: 90,
+            git_diff_deleted_lines: 0,
+            git_diff_added_lines: 100,
+            total_ai_additions: 50,
+            total_ai_deletions: 0,
+            tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
+        };
+
+        let ascii = OutputMode {
+            color: false,
+            unicode: false,
+            plain: false,
+        };
+        let en = Catalog::new(Locale::En);
+        let ascii_output = write_stats_to_terminal_with_mode(&stats, false, &ascii, &en);
+        assert!(ascii_output.is_ascii(), "{}", ascii_output);
+        assert!(ascii_output.starts_with("you  ####"), "{}", ascii_output);
+
+        let plain_output =
+            write_stats_to_terminal_with_mode(&stats, false, &OutputMode::PLAIN, &en);
+        assert_eq!(
+            plain_output,
+            "human: 40% (40 lines)\nmixed: 10% (10 lines)\nai: 50% (50 lines)\nai code accepted: 80%\nwaited for ai: 1m 30s\n"
+        );
+
+        let deletion_only = CommitStats {
+            git_diff_added_lines: 0,
+            git_diff_deleted_lines: 7,
+            ..stats
+        };
+        assert_eq!(
+            write_stats_to_terminal_with_mode(&deletion_only, false, &OutputMode::PLAIN, &en),
+            "additions: 0\ndeletions: 7\n"
+        );
+    }
+
+    #[test]
+    fn test_terminal_stats_german_locale() {
+        let stats = CommitStats {
+            human_additions: 1200,
+            ai_additions: 1800,
+            ai_accepted: 1800,
+            time_waiting_for_ai: 45,
+            git_diff_added_lines: 3000,
+            total_ai_additions: 1800,
+            ..Default::default()
+        };
+        let de = Catalog::new(Locale::De);
+
+        assert_eq!(
+            write_stats_to_terminal_with_mode(&stats, false, &OutputMode::PLAIN, &de),
+            "Mensch: 40 % (1.200 Zeilen)\nKI: 60 % (1.800 Zeilen)\nKI-Code übernommen: 100 %\nauf KI gewartet: 45 s\n"
+        );
+        let mode = OutputMode {
+            color: false,
+            ..OutputMode::default()
+        };
+        let terminal = write_stats_to_terminal_with_mode(&stats, false, &mode, &de);
+        assert!(
+            terminal.contains("100 % KI-Code übernommen | 45 s auf KI gewartet"),
+            "{}",
+            terminal
+        );
+    }
+
     #[test]
     fn test_markdown_stats_display() {
         // Test with mixed human/AI stats
@@ -864,6 +1147,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let mixed_output = write_stats_to_markdown(&stats);
@@ -881,6 +1165,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let ai_only_output = write_stats_to_markdown(&ai_stats);
@@ -898,6 +1183,7 @@ mod tests {
             total_ai_additions: 0,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let human_only_output = write_stats_to_markdown(&human_stats);
@@ -915,6 +1201,7 @@ mod tests {
             total_ai_additions: 100,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let minimal_human_output = write_stats_to_markdown(&minimal_human_stats);
@@ -932,6 +1219,7 @@ mod tests {
             total_ai_additions: 0,
             total_ai_deletions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            human_contributors: BTreeMap::new(),
         };
 
         let deletion_only_output = write_stats_to_markdown(&deletion_only_stats);
@@ -1310,6 +1598,8 @@ mod tests {
                 accepted_lines: 5,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1355,6 +1645,8 @@ mod tests {
                 accepted_lines: 3,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1402,6 +1694,8 @@ mod tests {
                 accepted_lines: 3,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -1770,6 +2064,8 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 100, // Unrealistically high
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
@@ -0,0 +1,137 @@
+//! Aggregate attribution notes on annotated tags.
+//!
+//! With `annotate_tags` on, creating an annotated tag through git-ai attaches a
+//! [`TagAttribution`] to the tag object: totals for the commits since the previous
+//! tag, so each release carries its own AI disclosure record. The note lives in
+//! its own notes ref (`refs/notes/ai-tags`), which syncs alongside the authorship
+//! notes, so tools that read every note under the authorship ref only ever see
+//! authorship logs. Read it with `git-ai show --tag <tag>`.
+
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::range_authorship::{EMPTY_TREE_HASH, range_authorship};
+use crate::error::GitAiError;
+use crate::git::refs::{
+    notes_add_batch_to_ref, show_authorship_note, show_note_in_ref, tag_notes_ref,
+};
+use crate::git::repository::{CommitRange, Repository, exec_git};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const TAG_ATTRIBUTION_SCHEMA_VERSION: &str = "tag-attribution/1.0.0";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TagAttribution {
+    pub schema_version: String,
+    pub tag: String,
+    /// The tagged commit
+    pub commit: String,
+    /// The tag the totals start from; `None` when they cover all history
+    pub previous_tag: Option<String>,
+    pub commits: usize,
+    pub commits_with_authorship: usize,
+    pub ai_additions: u32,
+    pub human_additions: u32,
+    /// `ai_additions` as a share of all attributed added lines, 0-100
+    pub ai_percent: u32,
+    /// AI lines added per tool
+    pub tools: BTreeMap<String, u32>,
+}
+
+/// Totals for the commits between the tag before `tag` and `tag`
+pub fn compute_tag_attribution(repo: &Repository, tag: &str) -> Result<TagAttribution, GitAiError> {
+    let commit = repo.revparse_single(&format!("{}^{{commit}}", tag))?.id();
+    let previous_tag = previous_tag(repo, &commit);
+    let start = match &previous_tag {
+        Some(previous) => format!("{}^{{commit}}", previous),
+        None => EMPTY_TREE_HASH.to_string(),
+    };
+    let range = CommitRange::new(repo, start, commit.clone(), format!("refs/tags/{}", tag))?;
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+    let stats = range_authorship(range, false, &ignore_patterns)?;
+
+    let range_stats = &stats.range_stats;
+    let mut tools: BTreeMap<String, u32> = BTreeMap::new();
+    for (tool_model, tool_stats) in &range_stats.tool_model_breakdown {
+        let tool = tool_model
+            .split_once("::")
+            .map_or(tool_model.as_str(), |(t, _)| t);
+        *tools.entry(tool.to_string()).or_default() += tool_stats.ai_additions;
+    }
+    let total = range_stats.ai_additions + range_stats.human_additions;
+    let ai_percent = if total > 0 {
+        ((range_stats.ai_additions as f64 / total as f64) * 100.0).round() as u32
+    } else {
+        0
+    };
+
+    Ok(TagAttribution {
+        schema_version: TAG_ATTRIBUTION_SCHEMA_VERSION.to_string(),
+        tag: tag.to_string(),
+        commit,
+        previous_tag,
+        commits: stats.authorship_stats.total_commits,
+        commits_with_authorship: stats.authorship_stats.commits_with_authorship,
+        ai_additions: range_stats.ai_additions,
+        human_additions: range_stats.human_additions,
+        ai_percent,
+        tools,
+    })
+}
+
+/// The nearest tag reachable from the tagged commit's parent
+fn previous_tag(repo: &Repository, commit: &str) -> Option<String> {
+    let mut args = repo.global_args_for_exec();
+    args.push("describe".to_string());
+    args.push("--tags".to_string());
+    args.push("--abbrev=0".to_string());
+    args.push(format!("{}^", commit));
+    let output = exec_git(&args).ok()?;
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// The tag object `tag` names, or an error for lightweight tags, which have none
+fn tag_object(repo: &Repository, tag: &str) -> Result<String, GitAiError> {
+    let refname = format!("refs/tags/{}", tag);
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("-t".to_string());
+    args.push(refname.clone());
+    let kind = String::from_utf8(exec_git(&args)?.stdout)?;
+    if kind.trim() != "tag" {
+        return Err(GitAiError::Generic(format!(
+            "{} is not an annotated tag",
+            tag
+        )));
+    }
+    Ok(repo.revparse_single(&refname)?.id())
+}
+
+/// Compute and attach the attribution note for annotated tag `tag`
+pub fn annotate_tag(repo: &Repository, tag: &str) -> Result<TagAttribution, GitAiError> {
+    let tag_oid = tag_object(repo, tag)?;
+    let attribution = compute_tag_attribution(repo, tag)?;
+    let content = serde_json::to_string_pretty(&attribution)?;
+    notes_add_batch_to_ref(repo, &tag_notes_ref(), &[(tag_oid, content)])?;
+    Ok(attribution)
+}
+
+/// The attribution note attached to annotated tag `tag`, if any
+pub fn read_tag_attribution(
+    repo: &Repository,
+    tag: &str,
+) -> Result<Option<TagAttribution>, GitAiError> {
+    let tag_oid = tag_object(repo, tag)?;
+    // Tags annotated before tag notes had their own ref carry them in the authorship ref
+    let Some(content) = show_note_in_ref(repo, &tag_notes_ref(), &tag_oid)
+        .or_else(|| show_authorship_note(repo, &tag_oid))
+    else {
+        return Ok(None);
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| GitAiError::Generic(format!("Invalid attribution note on tag {}: {}", tag, e)))
+}
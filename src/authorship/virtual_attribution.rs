@@ -1,15 +1,22 @@
+use crate::authorship::attribution_cache::{AttributionCache, notes_ref_oid};
 use crate::authorship::attribution_tracker::{
     Attribution, LineAttribution, line_attributions_to_attributions,
 };
 use crate::authorship::authorship_log::{LineRange, PromptRecord};
 use crate::authorship::working_log::CheckpointKind;
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
+use crate::observability::profile;
+use crate::utils::{debug_log, map_blocking_bounded};
+use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Least recently used blame results are evicted once the cache grows past this
+const MAX_BLAME_CACHE_ENTRIES: usize = 20_000;
+
 pub struct VirtualAttributions {
     repo: Repository,
     base_commit: String,
@@ -97,38 +104,21 @@ impl VirtualAttributions {
         Ok(())
     }
 
-    /// Load multiple prompts concurrently using MAX_CONCURRENT limit
+    /// Load multiple prompts concurrently, `blame_parallelism` at a time
     async fn load_prompts_concurrent(
         &self,
         missing_ids: &[String],
     ) -> Result<Vec<(String, String, PromptRecord)>, GitAiError> {
-        const MAX_CONCURRENT: usize = 30;
-
-        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
-        let mut tasks = Vec::new();
-
-        for missing_id in missing_ids {
-            let missing_id = missing_id.clone();
-            let repo = self.repo.clone();
-            let semaphore = Arc::clone(&semaphore);
-
-            let task = smol::spawn(async move {
-                // Acquire semaphore permit to limit concurrency
-                let _permit = semaphore.acquire().await;
-
-                // Wrap blocking git operations in smol::unblock
-                smol::unblock(move || {
-                    Self::find_prompt_in_history_static(&repo, &missing_id)
-                        .map(|(commit_sha, prompt)| (missing_id.clone(), commit_sha, prompt))
-                })
-                .await
-            });
-
-            tasks.push(task);
-        }
-
-        // Await all tasks concurrently
-        let results = futures::future::join_all(tasks).await;
+        let repo = self.repo.clone();
+        let results = map_blocking_bounded(
+            missing_ids.to_vec(),
+            Config::get().blame_parallelism(),
+            move |missing_id| {
+                Self::find_prompt_in_history_static(&repo, &missing_id)
+                    .map(|(commit_sha, prompt)| (missing_id.clone(), commit_sha, prompt))
+            },
+        )
+        .await;
 
         // Process results and collect successful prompts
         let mut prompts = Vec::new();
@@ -174,43 +164,32 @@ impl VirtualAttributions {
         self.add_pathspecs_concurrent(&[pathspec.to_string()]).await
     }
 
-    /// Add multiple pathspecs concurrently
+    /// Blame multiple pathspecs in parallel, `blame_parallelism` files at a time.
+    /// Results are applied in pathspec order, so the first failing file is the
+    /// one reported regardless of which blame finished first.
     async fn add_pathspecs_concurrent(&mut self, pathspecs: &[String]) -> Result<(), GitAiError> {
-        const MAX_CONCURRENT: usize = 30;
-
-        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
-        let mut tasks = Vec::new();
-
-        for pathspec in pathspecs {
-            let pathspec = pathspec.clone();
-            let repo = self.repo.clone();
-            let base_commit = self.base_commit.clone();
-            let ts = self.ts;
-            let blame_start_commit = self.blame_start_commit.clone();
-            let semaphore = Arc::clone(&semaphore);
-
-            let task = smol::spawn(async move {
-                // Acquire semaphore permit to limit concurrency
-                let _permit = semaphore.acquire().await;
-
-                // Wrap blocking git operations in smol::unblock
-                smol::unblock(move || {
-                    compute_attributions_for_file(
-                        &repo,
-                        &base_commit,
-                        &pathspec,
-                        ts,
-                        blame_start_commit,
-                    )
-                })
-                .await
-            });
-
-            tasks.push(task);
-        }
-
-        // Await all tasks
-        let results = futures::future::join_all(tasks).await;
+        let repo = self.repo.clone();
+        let base_commit = self.base_commit.clone();
+        let ts = self.ts;
+        let blame_start_commit = self.blame_start_commit.clone();
+        let notes_oid = notes_ref_oid(&repo)?;
+
+        let results = map_blocking_bounded(
+            pathspecs.to_vec(),
+            Config::get().blame_parallelism(),
+            move |pathspec| {
+                compute_attributions_for_file(
+                    &repo,
+                    &base_commit,
+                    &pathspec,
+                    ts,
+                    blame_start_commit.clone(),
+                    &notes_oid,
+                )
+            },
+        )
+        .await;
+        let _ = AttributionCache::for_blame(&self.repo).prune(MAX_BLAME_CACHE_ENTRIES);
 
         // Process results and store in HashMap
         for result in results {
@@ -253,9 +232,11 @@ impl VirtualAttributions {
             .map(|(_, line_attrs)| line_attrs)
     }
 
-    /// List all tracked files
+    /// List all tracked files, sorted so callers iterate in a stable order
     pub fn files(&self) -> Vec<String> {
-        self.attributions.keys().cloned().collect()
+        let mut files: Vec<String> = self.attributions.keys().cloned().collect();
+        files.sort();
+        files
     }
 
     /// Get the base commit SHA
@@ -268,6 +249,22 @@ impl VirtualAttributions {
         self.ts
     }
 
+    /// Get a timestamp for attributions created while rewriting history from this state.
+    /// Clamped past every existing attribution so rewritten lines still sort as newest
+    /// when the recorded attributions came from a machine with a clock in the future.
+    pub fn rewrite_timestamp(&self) -> u128 {
+        let max_existing = self
+            .attributions
+            .values()
+            .flat_map(|(char_attrs, _)| char_attrs.iter())
+            .map(|attr| attr.ts)
+            .max();
+        match max_existing {
+            Some(existing) if existing >= self.ts => existing + 1,
+            _ => self.ts,
+        }
+    }
+
     /// Get the prompts metadata (prompt_id -> commit_sha -> PromptRecord)
     pub fn prompts(&self) -> &BTreeMap<String, BTreeMap<String, PromptRecord>> {
         &self.prompts
@@ -297,6 +294,7 @@ impl VirtualAttributions {
         base_commit: String,
         human_author: Option<String>,
     ) -> Result<Self, GitAiError> {
+        let _profile_scope = profile::scope("working_log_attributions");
         let working_log = repo.storage.working_log_for_base_commit(&base_commit);
         let initial_attributions = working_log.read_initial_attributions();
         let checkpoints = working_log.read_all_checkpoints().unwrap_or_default();
@@ -362,6 +360,8 @@ impl VirtualAttributions {
                     accepted_lines: 0,
                     overriden_lines: 0,
                     messages_url: None,
+                    messages_ref: None,
+                    classification: checkpoint.classification(),
                 };
 
                 prompts
@@ -603,12 +603,26 @@ impl VirtualAttributions {
             }
         }
 
+        // AI-written parts of lines that count as human
+        for (file_path, (char_attrs, _)) in &self.attributions {
+            let Some(content) = self.file_contents.get(file_path) else {
+                continue;
+            };
+            for (author_id, range) in
+                crate::authorship::attribution_tracker::ai_char_ranges_by_line(char_attrs, content)
+            {
+                if authorship_log.metadata.prompts.contains_key(&author_id) {
+                    authorship_log.add_char_range(file_path, &author_id, range);
+                }
+            }
+        }
+
         Ok(authorship_log)
     }
 }
 
 /// Helper function to collect committed line ranges from git diff
-fn collect_committed_hunks(
+pub(crate) fn collect_committed_hunks(
     repo: &Repository,
     parent_sha: &str,
     commit_sha: &str,
@@ -1444,13 +1458,11 @@ pub fn merge_attributions_favoring_first(
 /// 3. Merges the stashed VA with the new VA, favoring the stashed one
 /// 4. Writes the result as INITIAL attributions for the new HEAD
 pub fn restore_stashed_va(
-    repository: &mut Repository,
+    repository: &Repository,
     old_head: &str,
     new_head: &str,
     stashed_va: VirtualAttributions,
 ) {
-    use crate::utils::debug_log;
-
     debug_log(&format!(
         "Restoring stashed VA: {} -> {}",
         old_head, new_head
@@ -1665,7 +1677,9 @@ fn ceil_char_boundary(content: &str, idx: usize) -> usize {
     i
 }
 
-/// Compute attributions for a single file at a specific commit
+/// Compute attributions for a single file at a specific commit. Blame results are
+/// cached per file version, so rebase and cherry-pick flows that attribute the
+/// same commit again skip blame.
 #[allow(clippy::type_complexity)]
 fn compute_attributions_for_file(
     repo: &Repository,
@@ -1673,7 +1687,62 @@ fn compute_attributions_for_file(
     file_path: &str,
     ts: u128,
     blame_start_commit: Option<String>,
+    notes_oid: &str,
 ) -> Result<Option<(String, String, Vec<Attribution>, Vec<LineAttribution>)>, GitAiError> {
+    // A file that doesn't exist at this commit can't be blamed, skip it
+    let Some((blob_oid, file_content)) = file_blob_at_commit(repo, base_commit, file_path)? else {
+        return Ok(None);
+    };
+
+    let cache = AttributionCache::for_blame(repo);
+    let key = blame_cache_key(
+        base_commit,
+        &blob_oid,
+        notes_oid,
+        blame_start_commit.as_deref(),
+        file_path,
+    );
+    let line_attributions = match cache
+        .get(&key)
+        .and_then(|content| serde_json::from_str::<Vec<LineAttribution>>(&content).ok())
+    {
+        Some(line_attributions) => line_attributions,
+        None => {
+            let Some(line_attributions) =
+                blame_line_attributions(repo, base_commit, file_path, blame_start_commit)
+            else {
+                return Ok(None);
+            };
+            let stored = serde_json::to_string(&line_attributions)
+                .map_err(GitAiError::from)
+                .and_then(|content| cache.put(&key, &content));
+            if let Err(e) = stored {
+                debug_log(&format!("failed to cache blame of {}: {}", file_path, e));
+            }
+            line_attributions
+        }
+    };
+
+    // Convert line attributions to character attributions
+    let char_attributions =
+        line_attributions_to_attributions(&line_attributions, &file_content, ts);
+
+    Ok(Some((
+        file_path.to_string(),
+        file_content,
+        char_attributions,
+        line_attributions,
+    )))
+}
+
+/// Blame `file_path` at the base commit, keeping the lines attributed to AI.
+/// None when the file can't be blamed.
+fn blame_line_attributions(
+    repo: &Repository,
+    base_commit: &str,
+    file_path: &str,
+    blame_start_commit: Option<String>,
+) -> Option<Vec<LineAttribution>> {
     // Set up blame options
     let mut ai_blame_opts = GitAiBlameOptions::default();
     #[allow(clippy::field_reassign_with_default)]
@@ -1687,65 +1756,71 @@ fn compute_attributions_for_file(
     }
 
     // Run blame at the base commit
-    let ai_blame = repo.blame(file_path, &ai_blame_opts);
-
-    match ai_blame {
-        Ok((blames, _)) => {
-            // Convert blame results to line attributions
-            let mut line_attributions = Vec::new();
-            for (line, author) in blames {
-                // Skip human-only lines as they don't need tracking
-                if author == CheckpointKind::Human.to_str() {
-                    continue;
-                }
-                line_attributions.push(LineAttribution {
-                    start_line: line,
-                    end_line: line,
-                    author_id: author.clone(),
-                    overrode: None,
-                });
-            }
+    let (blames, _) = repo.blame(file_path, &ai_blame_opts).ok()?;
 
-            // Get the file content at this commit to convert to character attributions
-            // We need to read the file content that blame operated on
-            let file_content = get_file_content_at_commit(repo, base_commit, file_path)?;
-
-            // Convert line attributions to character attributions
-            let char_attributions =
-                line_attributions_to_attributions(&line_attributions, &file_content, ts);
-
-            Ok(Some((
-                file_path.to_string(),
-                file_content,
-                char_attributions,
-                line_attributions,
-            )))
-        }
-        Err(_) => {
-            // File doesn't exist at this commit or can't be blamed, skip it
-            Ok(None)
+    // Convert blame results to line attributions
+    let mut line_attributions = Vec::new();
+    for (line, author) in blames {
+        // Skip human-only lines as they don't need tracking
+        if author == CheckpointKind::Human.to_str() {
+            continue;
         }
+        line_attributions.push(LineAttribution {
+            start_line: line,
+            end_line: line,
+            author_id: author.clone(),
+            overrode: None,
+        });
     }
+    Some(line_attributions)
 }
 
-fn get_file_content_at_commit(
+/// Key for a file's blame: the file version, the commit whose history was blamed,
+/// the notes that attributed it and where blame stopped
+fn blame_cache_key(
+    base_commit: &str,
+    blob_oid: &str,
+    notes_oid: &str,
+    blame_start_commit: Option<&str>,
+    file_path: &str,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(
+        format!(
+            "{}\0{}\0{}\0{}\0{}",
+            base_commit,
+            blob_oid,
+            notes_oid,
+            blame_start_commit.unwrap_or_default(),
+            file_path
+        )
+        .as_bytes(),
+    );
+    format!("{:x}", hasher.finalize())
+}
+
+/// The blob OID and content of `file_path` at a commit, or None if the commit
+/// doesn't have the file
+fn file_blob_at_commit(
     repo: &Repository,
     commit_sha: &str,
     file_path: &str,
-) -> Result<String, GitAiError> {
+) -> Result<Option<(String, String)>, GitAiError> {
     let commit = repo.find_commit(commit_sha.to_string())?;
     let tree = commit.tree()?;
 
     match tree.get_path(std::path::Path::new(file_path)) {
         Ok(entry) => {
-            if let Ok(blob) = repo.find_blob(entry.id()) {
-                let blob_content = blob.content().unwrap_or_default();
-                Ok(String::from_utf8_lossy(&blob_content).to_string())
-            } else {
-                Ok(String::new())
-            }
+            let content = match repo.find_blob(entry.id()) {
+                Ok(blob) => {
+                    let blob_content = blob.content().unwrap_or_default();
+                    String::from_utf8_lossy(&blob_content).to_string()
+                }
+                Err(_) => String::new(),
+            };
+            Ok(Some((entry.id(), content)))
         }
-        Err(_) => Ok(String::new()),
+        Err(_) => Ok(None),
     }
 }
 
@@ -1818,4 +1893,81 @@ mod tests {
 
         assert!(!virtual_attributions.files().is_empty());
     }
+
+    #[test]
+    fn test_blame_results_are_cached_per_file_version() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("ai.rs", "fn ai() {}\n", true).unwrap();
+        repo.trigger_checkpoint_with_ai("Claude", Some("model"), Some("tool"))
+            .unwrap();
+        repo.commit_with_message("AI commit").unwrap();
+        let commit_sha = repo.head_commit_sha().unwrap();
+
+        let line_attributions = |repo: &TmpRepo| {
+            let va = smol::block_on(VirtualAttributions::new_for_base_commit(
+                repo.gitai_repo().clone(),
+                commit_sha.clone(),
+                &["ai.rs".to_string()],
+                None,
+            ))
+            .unwrap();
+            va.get_line_attributions("ai.rs").unwrap().clone()
+        };
+        let blamed = line_attributions(&repo);
+        assert_eq!(blamed.len(), 1);
+
+        // A planted entry under the same key is served instead of blaming again
+        let gitai_repo = repo.gitai_repo();
+        let (blob_oid, _) = file_blob_at_commit(gitai_repo, &commit_sha, "ai.rs")
+            .unwrap()
+            .unwrap();
+        let key = blame_cache_key(
+            &commit_sha,
+            &blob_oid,
+            &notes_ref_oid(gitai_repo).unwrap(),
+            None,
+            "ai.rs",
+        );
+        let cache = AttributionCache::for_blame(gitai_repo);
+        assert!(cache.get(&key).is_some());
+        let mut planted = blamed.clone();
+        planted[0].author_id = "cached-prompt".to_string();
+        cache
+            .put(&key, &serde_json::to_string(&planted).unwrap())
+            .unwrap();
+        assert_eq!(line_attributions(&repo), planted);
+
+        cache.clear().unwrap();
+        assert_eq!(line_attributions(&repo), blamed);
+    }
+
+    #[test]
+    fn test_rewrite_timestamp_clamps_past_future_attributions() {
+        let repo = TmpRepo::new().unwrap();
+        let mut attrs = HashMap::new();
+        attrs.insert(
+            "a.rs".to_string(),
+            (
+                vec![Attribution::new(0, 5, "ai".to_string(), 9_000)],
+                Vec::new(),
+            ),
+        );
+        let skewed = VirtualAttributions::new(
+            repo.gitai_repo().clone(),
+            "HEAD".to_string(),
+            attrs.clone(),
+            HashMap::new(),
+            1_000,
+        );
+        assert_eq!(skewed.rewrite_timestamp(), 9_001);
+
+        let normal = VirtualAttributions::new(
+            repo.gitai_repo().clone(),
+            "HEAD".to_string(),
+            attrs,
+            HashMap::new(),
+            10_000,
+        );
+        assert_eq!(normal.rewrite_timestamp(), 10_000);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::authorship::attribution_tracker::{Attribution, LineAttribution};
 use crate::authorship::authorship_log_serialization::GIT_AI_VERSION;
 use crate::authorship::transcript::AiTranscript;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -39,7 +40,14 @@ impl WorkingLogEntry {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// `agent_metadata` key under which presets record the agent's own version
+pub const AGENT_VERSION_METADATA_KEY: &str = "agent_version";
+
+/// `agent_metadata` key marking a checkpoint whose agent was guessed rather than
+/// reported; the value says what gave it away
+pub const AGENT_INFERRED_METADATA_KEY: &str = "inferred";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct AgentId {
     pub tool: String, // e.g., "cursor", "windsurf"
     pub id: String,   // id in their domain
@@ -86,6 +94,42 @@ impl CheckpointKind {
     }
 }
 
+/// How AI contributed to a change. Inline completions assist a human who is
+/// typing; agents generate edits (often across files) on their own. Orgs
+/// usually report the two separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AiClassification {
+    Assisted,
+    Generated,
+}
+
+impl AiClassification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AiClassification::Assisted => "assisted",
+            AiClassification::Generated => "generated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "assisted" => Some(AiClassification::Assisted),
+            "generated" => Some(AiClassification::Generated),
+            _ => None,
+        }
+    }
+
+    /// Default classification for a checkpoint kind; `None` for human checkpoints
+    pub fn for_kind(kind: CheckpointKind) -> Option<Self> {
+        match kind {
+            CheckpointKind::Human => None,
+            CheckpointKind::AiAgent => Some(AiClassification::Generated),
+            CheckpointKind::AiTab => Some(AiClassification::Assisted),
+        }
+    }
+}
+
 /// Line-level statistics tracked per checkpoint kind
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -106,12 +150,27 @@ pub struct Checkpoint {
     pub kind: CheckpointKind,
     pub diff: String,
     pub author: String,
+    /// Other humans working with `author` (pairing / mob sessions)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<String>,
     pub entries: Vec<WorkingLogEntry>,
+    /// Files matching `tracking.generated` that the agent edited. They get no
+    /// entries; post-commit only counts them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generated_files: Vec<String>,
+    /// Wall-clock time (seconds since epoch). Informational only; may be skewed.
     pub timestamp: u64,
+    /// Logical position within the working log, assigned on append. Checkpoints
+    /// are read back in this order, so a skewed `timestamp` can't reorder them.
+    #[serde(default)]
+    pub seq: u64,
     pub transcript: Option<AiTranscript>,
     pub agent_id: Option<AgentId>,
     #[serde(default)]
     pub agent_metadata: Option<HashMap<String, String>>,
+    /// Assisted vs generated. Set from the kind unless the agent reports otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<AiClassification>,
     #[serde(default)]
     pub line_stats: CheckpointLineStats,
     #[serde(default)]
@@ -136,16 +195,97 @@ impl Checkpoint {
             kind,
             diff,
             author,
+            co_authors: Vec::new(),
             entries,
+            generated_files: Vec::new(),
             timestamp,
+            seq: 0,
             transcript: None,
             agent_id: None,
             agent_metadata: None,
+            classification: AiClassification::for_kind(kind),
             line_stats: CheckpointLineStats::default(),
             api_version: CHECKPOINT_API_VERSION.to_string(),
             git_ai_version: Some(GIT_AI_VERSION.to_string()),
         }
     }
+
+    /// Classification of this checkpoint, falling back to the kind for logs
+    /// written before the field existed
+    pub fn classification(&self) -> Option<AiClassification> {
+        self.classification
+            .or_else(|| AiClassification::for_kind(self.kind))
+    }
+
+    /// Whether the transcript can be refetched from the tool's own storage
+    /// before post-commit, so the working log doesn't need to keep it.
+    ///
+    /// Tools that DON'T support refetch (transcript must be kept):
+    /// - "opencode" - uses agent-v1 format, transcript provided inline
+    /// - "mock_ai" - test preset, transcript not stored externally
+    /// - Any other agent-v1 custom tools (detected by lack of tool-specific metadata)
+    pub fn can_refetch_transcript(&self) -> bool {
+        let tool = self
+            .agent_id
+            .as_ref()
+            .map(|a| a.tool.as_str())
+            .unwrap_or("");
+        let has_metadata = |key: &str| {
+            self.agent_metadata
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .is_some()
+        };
+
+        match tool {
+            "opencode" | "mock_ai" => false,
+            // human checkpoints have no transcript anyway
+            "human" => true,
+            // cursor can always refetch from its database
+            "cursor" => true,
+            // claude, codex, gemini, continue-cli need transcript_path
+            "claude" | "codex" | "gemini" | "continue-cli" => has_metadata("transcript_path"),
+            // github-copilot and the Copilot CLI need chat_session_path
+            "github-copilot" | "copilot" => has_metadata("chat_session_path"),
+            // Unknown tools (like custom agent-v1 tools) can't refetch
+            _ => false,
+        }
+    }
+
+    /// Whether an AI checkpoint captured no conversation: its transcript is
+    /// empty and can't be refetched either
+    pub fn lacks_transcript(&self) -> bool {
+        !self.can_refetch_transcript()
+            && self
+                .transcript
+                .as_ref()
+                .is_none_or(|transcript| transcript.messages().is_empty())
+    }
+}
+
+/// Returns the sequence number for a checkpoint appended after `checkpoints`.
+/// Logs written before `seq` existed have all zeros, so the position in the
+/// log is used as a floor.
+pub fn next_checkpoint_seq(checkpoints: &[Checkpoint]) -> u64 {
+    let max_seq = checkpoints.iter().map(|c| c.seq).max().unwrap_or(0);
+    max_seq.max(checkpoints.len() as u64) + 1
+}
+
+/// Clamps an attribution timestamp (ms) so it sorts after every attribution already
+/// recorded in `checkpoints`. Attribution ordering decides which author "wins" a line,
+/// so a clock that jumped backwards (or an earlier checkpoint written with a clock in
+/// the future) must not let an older edit override a newer one.
+pub fn monotonic_attribution_ts(now_ms: u128, checkpoints: &[Checkpoint]) -> u128 {
+    let max_prior = checkpoints
+        .iter()
+        .flat_map(|c| c.entries.iter())
+        .flat_map(|e| e.attributions.iter())
+        .map(|a| a.ts)
+        .max();
+    match max_prior {
+        Some(prior) if prior >= now_ms => prior + 1,
+        _ => now_ms,
+    }
 }
 
 #[cfg(test)]
@@ -305,4 +445,65 @@ mod tests {
         assert_eq!(deserialized_agent.tool, "cursor");
         assert_eq!(deserialized_agent.id, "session-abc123");
     }
+
+    fn checkpoint_with_attribution_ts(ts: u128) -> Checkpoint {
+        let entry = WorkingLogEntry::new(
+            "src/lib.rs".to_string(),
+            "sha".to_string(),
+            vec![Attribution::new(0, 10, "human".to_string(), ts)],
+            vec![],
+        );
+        Checkpoint::new(
+            CheckpointKind::Human,
+            "".to_string(),
+            "human".to_string(),
+            vec![entry],
+        )
+    }
+
+    #[test]
+    fn test_seq_defaults_for_legacy_checkpoints() {
+        let checkpoint = checkpoint_with_attribution_ts(1);
+        let mut json: serde_json::Value = serde_json::to_value(&checkpoint).unwrap();
+        json.as_object_mut().unwrap().remove("seq");
+        let deserialized: Checkpoint = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.seq, 0);
+    }
+
+    #[test]
+    fn test_next_checkpoint_seq() {
+        assert_eq!(next_checkpoint_seq(&[]), 1);
+
+        // Legacy logs without seq fall back to their position
+        let legacy = vec![
+            checkpoint_with_attribution_ts(1),
+            checkpoint_with_attribution_ts(2),
+        ];
+        assert_eq!(next_checkpoint_seq(&legacy), 3);
+
+        let mut sequenced = legacy.clone();
+        sequenced[0].seq = 7;
+        sequenced[1].seq = 8;
+        assert_eq!(next_checkpoint_seq(&sequenced), 9);
+    }
+
+    #[test]
+    fn test_monotonic_attribution_ts_tolerates_clock_skew() {
+        assert_eq!(monotonic_attribution_ts(1_000, &[]), 1_000);
+
+        // Clock is ahead of everything previously recorded
+        let prior = vec![checkpoint_with_attribution_ts(500)];
+        assert_eq!(monotonic_attribution_ts(1_000, &prior), 1_000);
+
+        // Clock went backwards (or a prior checkpoint was written with a future clock)
+        let future = vec![
+            checkpoint_with_attribution_ts(500),
+            checkpoint_with_attribution_ts(5_000),
+        ];
+        assert_eq!(monotonic_attribution_ts(1_000, &future), 5_001);
+
+        // Same millisecond still strictly increases
+        let same = vec![checkpoint_with_attribution_ts(1_000)];
+        assert_eq!(monotonic_attribution_ts(1_000, &same), 1_001);
+    }
 }
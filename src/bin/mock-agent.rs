@@ -0,0 +1,274 @@
+//! A scripted stand-in for an AI coding agent, used by the integration tests.
+//!
+//! It behaves like a real agent integration: before each turn's edits it fires a
+//! pre-edit hook, applies the edits to the working tree, then fires a post-edit
+//! hook with the transcript so far. Both hooks run `git-ai checkpoint agent-v1
+//! --hook-input stdin` as a separate process, so tests exercise the same
+//! hook → checkpoint → commit pipeline an installed agent does.
+//!
+//! Usage: mock-agent [--git-ai <path>] [--cwd <dir>] [<script.json>|-]
+//!
+//! Script format:
+//! ```json
+//! {
+//!   "agent_name": "mock-agent",
+//!   "model": "mock-model",
+//!   "conversation_id": "mock-1",
+//!   "turns": [
+//!     {
+//!       "prompt": "Add a greeting",
+//!       "response": "Added greet()",
+//!       "edits": [
+//!         { "op": "write", "path": "src/lib.rs", "contents": "fn greet() {}\n" },
+//!         { "op": "append", "path": "README.md", "contents": "Greets.\n" },
+//!         { "op": "replace", "path": "src/main.rs", "from": "old", "to": "new" },
+//!         { "op": "delete", "path": "old.rs" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Deserialize)]
+struct Script {
+    #[serde(default = "default_agent_name")]
+    agent_name: String,
+    #[serde(default = "default_model")]
+    model: String,
+    #[serde(default = "default_conversation_id")]
+    conversation_id: String,
+    turns: Vec<Turn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Turn {
+    prompt: String,
+    #[serde(default)]
+    response: Option<String>,
+    #[serde(default)]
+    edits: Vec<Edit>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Edit {
+    Write {
+        path: String,
+        contents: String,
+    },
+    Append {
+        path: String,
+        contents: String,
+    },
+    Replace {
+        path: String,
+        from: String,
+        to: String,
+    },
+    Delete {
+        path: String,
+    },
+}
+
+impl Edit {
+    fn path(&self) -> &str {
+        match self {
+            Edit::Write { path, .. }
+            | Edit::Append { path, .. }
+            | Edit::Replace { path, .. }
+            | Edit::Delete { path } => path,
+        }
+    }
+
+    fn tool_name(&self) -> &'static str {
+        match self {
+            Edit::Write { .. } => "Write",
+            Edit::Append { .. } | Edit::Replace { .. } => "Edit",
+            Edit::Delete { .. } => "Delete",
+        }
+    }
+
+    fn apply(&self, file: &Path) -> Result<(), String> {
+        let result = match self {
+            Edit::Write { contents, .. } => {
+                if let Some(parent) = file.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(file, contents)
+            }
+            Edit::Append { contents, .. } => std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file)
+                .and_then(|mut f| f.write_all(contents.as_bytes())),
+            Edit::Replace { from, to, .. } => {
+                let existing = std::fs::read_to_string(file)
+                    .map_err(|e| format!("{}: {}", file.display(), e))?;
+                if !existing.contains(from.as_str()) {
+                    return Err(format!("{:?} not found in {}", from, file.display()));
+                }
+                std::fs::write(file, existing.replacen(from.as_str(), to, 1))
+            }
+            Edit::Delete { .. } => std::fs::remove_file(file),
+        };
+        result.map_err(|e| format!("{}: {}", file.display(), e))
+    }
+}
+
+fn default_agent_name() -> String {
+    "mock-agent".to_string()
+}
+
+fn default_model() -> String {
+    "mock-model".to_string()
+}
+
+fn default_conversation_id() -> String {
+    "mock-conversation".to_string()
+}
+
+fn main() {
+    if let Err(e) = run(std::env::args().skip(1).collect()) {
+        eprintln!("mock-agent: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut git_ai = std::env::var("GIT_AI_BIN").unwrap_or_else(|_| "git-ai".to_string());
+    let mut cwd: Option<PathBuf> = None;
+    let mut script_path: Option<String> = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--git-ai" => git_ai = args.next().ok_or("--git-ai requires a value")?,
+            "--cwd" => cwd = Some(args.next().ok_or("--cwd requires a value")?.into()),
+            other if other.starts_with("--") => return Err(format!("unknown option {}", other)),
+            other => script_path = Some(other.to_string()),
+        }
+    }
+
+    let script_json = match script_path.as_deref() {
+        None | Some("-") => {
+            let mut buffer = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("failed to read script from stdin: {}", e))?;
+            buffer
+        }
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read script {}: {}", path, e))?,
+    };
+    let script: Script =
+        serde_json::from_str(&script_json).map_err(|e| format!("invalid script: {}", e))?;
+
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir().map_err(|e| e.to_string())?,
+    };
+    let repo_dir = git_toplevel(&cwd)?;
+    let repo_working_dir = repo_dir.to_string_lossy().to_string();
+
+    let mut transcript = AiTranscript::new();
+    for turn in &script.turns {
+        transcript.add_message(Message::user(turn.prompt.clone(), None));
+
+        let files: Vec<PathBuf> = turn.edits.iter().map(|e| cwd.join(e.path())).collect();
+        let file_paths: Vec<String> = files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+
+        if !turn.edits.is_empty() {
+            run_hook(
+                &git_ai,
+                &repo_dir,
+                json!({
+                    "type": "human",
+                    "repo_working_dir": repo_working_dir,
+                    "will_edit_filepaths": file_paths,
+                }),
+            )?;
+        }
+
+        for (edit, file) in turn.edits.iter().zip(&files) {
+            edit.apply(file)?;
+            transcript.add_message(Message::tool_use(
+                edit.tool_name().to_string(),
+                json!({ "file_path": file.to_string_lossy() }),
+            ));
+        }
+        if let Some(response) = &turn.response {
+            transcript.add_message(Message::assistant(response.clone(), None));
+        }
+
+        if !turn.edits.is_empty() {
+            run_hook(
+                &git_ai,
+                &repo_dir,
+                json!({
+                    "type": "ai_agent",
+                    "repo_working_dir": repo_working_dir,
+                    "edited_filepaths": file_paths,
+                    "transcript": transcript,
+                    "agent_name": script.agent_name,
+                    "model": script.model,
+                    "conversation_id": script.conversation_id,
+                }),
+            )?;
+        }
+    }
+
+    println!(
+        "mock-agent: completed {} turn(s) as {}",
+        script.turns.len(),
+        script.agent_name
+    );
+    Ok(())
+}
+
+fn git_toplevel(cwd: &Path) -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("{} is not inside a git repository", cwd.display()));
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    ))
+}
+
+/// Fire a hook the way an agent integration does: `git-ai checkpoint agent-v1`
+/// in the repo, with the payload on stdin
+fn run_hook(git_ai: &str, repo_dir: &Path, payload: serde_json::Value) -> Result<(), String> {
+    let mut child = Command::new(git_ai)
+        .args(["checkpoint", "agent-v1", "--hook-input", "stdin"])
+        .current_dir(repo_dir)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run {}: {}", git_ai, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(payload.to_string().as_bytes())
+            .map_err(|e| format!("failed to write hook input: {}", e))?;
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!(
+            "hook `{} checkpoint agent-v1` failed: {}",
+            git_ai, status
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! AI-changed files for CI test selection.
+//!
+//! `git-ai ci changed-ai-files --range <base>..<head>` lists the files whose
+//! added lines in a diff include AI-authored ones, and the packages they belong
+//! to, so a pipeline can run stricter suites or extra static analysis only
+//! where machine-generated code changed. The diff is taken from the merge base
+//! of `base` and `head`, like a pull request's. A file's package is the nearest
+//! directory at `head` with a build manifest (`Cargo.toml`, `package.json`,
+//! `go.mod`, ...).
+
+use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+/// Files that make the directory they are in a package
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+    "mix.exs",
+    "Package.swift",
+    "pubspec.yaml",
+];
+
+/// A changed file with AI-authored added lines
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+pub struct ChangedAiFile {
+    pub path: String,
+    pub added_lines: u32,
+    pub ai_lines: u32,
+    /// Directory of the package the file belongs to (`.` for the repository
+    /// root), if any
+    pub package: Option<String>,
+}
+
+/// Files and packages with AI-authored changes in a range
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct ChangedAiFiles {
+    pub range: String,
+    /// Files changed in the range, AI-authored or not
+    pub changed_files: usize,
+    /// Files with AI-authored added lines, most AI lines first
+    pub files: Vec<ChangedAiFile>,
+    /// Packages containing those files, sorted
+    pub packages: Vec<String>,
+}
+
+impl ChangedAiFiles {
+    pub fn for_range(
+        repo: &Repository,
+        range: &str,
+        ignore_patterns: &[String],
+    ) -> Result<Self, GitAiError> {
+        let (base, head) = range
+            .split_once("..")
+            .filter(|(base, head)| !base.is_empty() && !head.is_empty() && !head.starts_with('.'))
+            .ok_or_else(|| {
+                GitAiError::Generic(format!("Invalid range {}: expected <base>..<head>", range))
+            })?;
+
+        let head_sha = repo.revparse_single(head)?.id();
+        let base_sha = repo.merge_base(repo.revparse_single(base)?.id(), head_sha.clone())?;
+        if base_sha.is_empty() {
+            return Err(GitAiError::Generic(format!(
+                "{} and {} have no common ancestor",
+                base, head
+            )));
+        }
+
+        let per_file =
+            diff_ai_accepted_stats(repo, &base_sha, &head_sha, None, ignore_patterns)?.per_file;
+        let changed_files = per_file.len();
+        let ai_files: Vec<(String, u32, u32)> = per_file
+            .into_iter()
+            .filter(|(_, lines)| lines.ai_accepted > 0)
+            .map(|(path, lines)| (path, lines.added_lines, lines.ai_accepted))
+            .collect();
+
+        let manifest_dirs = if ai_files.is_empty() {
+            HashSet::new()
+        } else {
+            package_dirs_at(repo, &head_sha)?
+        };
+        let mut files: Vec<ChangedAiFile> = ai_files
+            .into_iter()
+            .map(|(path, added_lines, ai_lines)| ChangedAiFile {
+                package: package_for(&path, &manifest_dirs),
+                path,
+                added_lines,
+                ai_lines,
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.ai_lines
+                .cmp(&a.ai_lines)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let packages: BTreeSet<String> = files
+            .iter()
+            .filter_map(|file| file.package.clone())
+            .collect();
+
+        Ok(ChangedAiFiles {
+            range: range.to_string(),
+            changed_files,
+            files,
+            packages: packages.into_iter().collect(),
+        })
+    }
+
+    /// Human-readable summary, for stderr next to the list
+    pub fn summary(&self) -> String {
+        format!(
+            "[git-ai] {} of {} changed files in {} have AI-authored lines ({} packages)",
+            self.files.len(),
+            self.changed_files,
+            self.range,
+            self.packages.len()
+        )
+    }
+}
+
+/// Directories (`""` for the root) holding a package manifest at `commit`
+fn package_dirs_at(repo: &Repository, commit: &str) -> Result<HashSet<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "-c".to_string(),
+        "core.quotepath=false".to_string(),
+        "ls-tree".to_string(),
+        "-r".to_string(),
+        "--name-only".to_string(),
+        commit.to_string(),
+    ]);
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout
+        .lines()
+        .filter_map(|path| {
+            let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+            (PACKAGE_MANIFESTS.contains(&name) || name.ends_with(".csproj"))
+                .then(|| dir.to_string())
+        })
+        .collect())
+}
+
+/// The nearest enclosing package directory of `path`, `.` for the root
+fn package_for(path: &str, manifest_dirs: &HashSet<String>) -> Option<String> {
+    let mut dir = path;
+    while let Some((parent, _)) = dir.rsplit_once('/') {
+        if manifest_dirs.contains(parent) {
+            return Some(parent.to_string());
+        }
+        dir = parent;
+    }
+    manifest_dirs.contains("").then(|| ".".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_for_picks_nearest_manifest_dir() {
+        let dirs: HashSet<String> = ["", "crates/core", "web"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(
+            package_for("crates/core/src/lib.rs", &dirs).as_deref(),
+            Some("crates/core")
+        );
+        assert_eq!(package_for("web/app.ts", &dirs).as_deref(), Some("web"));
+        assert_eq!(package_for("README.md", &dirs).as_deref(), Some("."));
+        assert_eq!(package_for("docs/guide.md", &dirs).as_deref(), Some("."));
+
+        let nested_only: HashSet<String> = ["web".to_string()].into_iter().collect();
+        assert_eq!(package_for("scripts/build.sh", &nested_only), None);
+    }
+}
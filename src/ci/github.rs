@@ -1,12 +1,17 @@
+use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::authorship::range_authorship::{RangeAuthorshipStats, range_authorship_filtered};
 use crate::ci::ci_context::{CiContext, CiEvent};
 use crate::error::GitAiError;
 use crate::git::repository::exec_git;
 use crate::git::repository::find_repository_in_path;
+use crate::git::repository::{CommitRange, Repository};
+use crate::reporting::filters::AuthorFilter;
+use crate::reporting::templates::{StatsTemplateContext, render_template};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-const GITHUB_CI_TEMPLATE_YAML: &str = include_str!("workflow_templates/github.yaml");
+pub const GITHUB_CI_TEMPLATE_YAML: &str = include_str!("workflow_templates/github.yaml");
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 struct GithubCiEventPayload {
@@ -131,3 +136,340 @@ pub fn install_github_ci_workflow() -> Result<PathBuf, GitAiError> {
 
     Ok(dest_path)
 }
+
+/// Hidden marker identifying the comment `git-ai ci annotate-pr` owns, so reruns
+/// update it instead of posting another one
+pub const PR_ANNOTATION_MARKER: &str = "<!-- git-ai:annotate-pr -->";
+
+/// Template used for the PR comment (overridable like other report templates)
+pub const PR_ANNOTATION_TEMPLATE: &str = "pr-annotation";
+
+/// Most files listed in a PR comment, to stay well under GitHub's comment size limit
+const MAX_ANNOTATED_FILES: usize = 100;
+
+/// AI/human breakdown for one file changed in a PR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileAnnotation {
+    pub path: String,
+    pub added_lines: u32,
+    pub ai_lines: u32,
+    pub ai_percent: u32,
+}
+
+/// AI authorship of a PR's commit range, ready to render as a comment
+#[derive(Debug)]
+pub struct PrAnnotation {
+    pub title: String,
+    pub stats: RangeAuthorshipStats,
+    /// Files with added lines, most AI lines first
+    pub files: Vec<FileAnnotation>,
+    /// Files left out of `files` to keep the comment short
+    pub files_omitted: usize,
+}
+
+#[derive(Serialize)]
+struct PrAnnotationContext<'a> {
+    #[serde(flatten)]
+    stats: StatsTemplateContext<'a>,
+    files: &'a [FileAnnotation],
+    files_omitted: usize,
+}
+
+impl PrAnnotation {
+    /// Compute the breakdown for the commits `head` adds on top of its merge base
+    /// with `base`
+    pub fn compute(
+        repo: &Repository,
+        base: &str,
+        head: &str,
+        ignore_patterns: &[String],
+    ) -> Result<Self, GitAiError> {
+        let head_sha = repo.revparse_single(head)?.id();
+        let base_sha = repo.merge_base(repo.revparse_single(base)?.id(), head_sha.clone())?;
+        if base_sha.is_empty() {
+            return Err(GitAiError::Generic(format!(
+                "{} and {} have no common ancestor",
+                base, head
+            )));
+        }
+        if base_sha == head_sha {
+            return Err(GitAiError::Generic(format!(
+                "{} has no commits that aren't in {}",
+                head, base
+            )));
+        }
+
+        let title = format!("{}..{}", short_sha(&base_sha), short_sha(&head_sha));
+        let range = CommitRange::new_infer_refname(repo, base_sha.clone(), head_sha.clone(), None)?;
+        let stats = range_authorship_filtered(
+            range,
+            false,
+            ignore_patterns,
+            &AuthorFilter::default(),
+            false,
+        )?;
+
+        let per_file =
+            diff_ai_accepted_stats(repo, &base_sha, &head_sha, None, ignore_patterns)?.per_file;
+        let mut files: Vec<FileAnnotation> = per_file
+            .into_iter()
+            .map(|(path, lines)| FileAnnotation {
+                path,
+                added_lines: lines.added_lines,
+                ai_lines: lines.ai_accepted,
+                ai_percent: percent(lines.ai_accepted, lines.added_lines),
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.ai_lines
+                .cmp(&a.ai_lines)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let files_omitted = files.len().saturating_sub(MAX_ANNOTATED_FILES);
+        files.truncate(MAX_ANNOTATED_FILES);
+
+        Ok(PrAnnotation {
+            title,
+            stats,
+            files,
+            files_omitted,
+        })
+    }
+
+    /// Render the comment body with `template`, prefixed with the marker
+    pub fn render(&self, template: &str) -> Result<String, GitAiError> {
+        let context = PrAnnotationContext {
+            stats: StatsTemplateContext::new(
+                &self.title,
+                &self.stats.range_stats,
+                Some(&self.stats.authorship_stats),
+            ),
+            files: &self.files,
+            files_omitted: self.files_omitted,
+        };
+        let body = render_template(template, &context)?;
+        Ok(format!("{}\n{}", PR_ANNOTATION_MARKER, body))
+    }
+}
+
+fn percent(part: u32, total: u32) -> u32 {
+    if total == 0 {
+        0
+    } else {
+        ((part as f64 / total as f64) * 100.0).round() as u32
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GithubPrEventPayload {
+    #[serde(default)]
+    pull_request: Option<GithubPrEventPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubPrEventPullRequest {
+    number: u64,
+    base: GithubPrEventRef,
+    head: GithubPrEventRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubPrEventRef {
+    sha: String,
+}
+
+/// The PR a GitHub Actions run was triggered for, from `GITHUB_EVENT_PATH`:
+/// (number, base sha, head sha)
+pub fn pr_from_github_event() -> Option<(u64, String, String)> {
+    let event_path = std::env::var("GITHUB_EVENT_PATH").ok()?;
+    let payload: GithubPrEventPayload =
+        serde_json::from_str(&fs::read_to_string(event_path).ok()?).ok()?;
+    let pull_request = payload.pull_request?;
+    Some((
+        pull_request.number,
+        pull_request.base.sha,
+        pull_request.head.sha,
+    ))
+}
+
+/// Whether `annotate-pr` created a new comment or updated its earlier one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrCommentUpdate {
+    Created { url: String },
+    Updated { url: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubIssueComment {
+    id: u64,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    html_url: String,
+}
+
+/// Minimal GitHub REST client for PR comments, authenticated with a token
+pub struct GithubCommentClient {
+    api_url: String,
+    token: String,
+}
+
+impl GithubCommentClient {
+    /// `api_url` is e.g. `https://api.github.com` (or `$GITHUB_API_URL` on GHES)
+    pub fn new(api_url: &str, token: String) -> Self {
+        GithubCommentClient {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn request(&self, method: minreq::Method, url: &str) -> minreq::Request {
+        minreq::Request::new(method, url)
+            .with_header("Authorization", format!("Bearer {}", self.token))
+            .with_header("Accept", "application/vnd.github+json")
+            .with_header("X-GitHub-Api-Version", "2022-11-28")
+            .with_header(
+                "User-Agent",
+                format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .with_timeout(30)
+    }
+
+    fn send(&self, request: minreq::Request) -> Result<minreq::Response, GitAiError> {
+        let response = request
+            .send()
+            .map_err(|e| GitAiError::Generic(format!("GitHub API request failed: {}", e)))?;
+        if !(200..300).contains(&response.status_code) {
+            return Err(GitAiError::Generic(format!(
+                "GitHub API returned status {}: {}",
+                response.status_code,
+                response.as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(response)
+    }
+
+    fn list_comments(
+        &self,
+        repo_slug: &str,
+        pr_number: u64,
+    ) -> Result<Vec<GithubIssueComment>, GitAiError> {
+        let mut comments = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/repos/{}/issues/{}/comments?per_page=100&page={}",
+                self.api_url, repo_slug, pr_number, page
+            );
+            let response = self.send(self.request(minreq::Method::Get, &url))?;
+            let batch: Vec<GithubIssueComment> =
+                serde_json::from_str(response.as_str().unwrap_or("[]")).map_err(|e| {
+                    GitAiError::Generic(format!("Failed to parse GitHub comments: {}", e))
+                })?;
+            let done = batch.len() < 100;
+            comments.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(comments)
+    }
+
+    /// Post `body` on the PR, or replace the comment an earlier run posted
+    pub fn upsert_pr_comment(
+        &self,
+        repo_slug: &str,
+        pr_number: u64,
+        body: &str,
+    ) -> Result<PrCommentUpdate, GitAiError> {
+        let comments = self.list_comments(repo_slug, pr_number)?;
+        let payload = serde_json::to_string(&serde_json::json!({ "body": body }))?;
+
+        let (request, created) = match find_annotation_comment(&comments) {
+            Some(id) => (
+                self.request(
+                    minreq::Method::Patch,
+                    &format!(
+                        "{}/repos/{}/issues/comments/{}",
+                        self.api_url, repo_slug, id
+                    ),
+                ),
+                false,
+            ),
+            None => (
+                self.request(
+                    minreq::Method::Post,
+                    &format!(
+                        "{}/repos/{}/issues/{}/comments",
+                        self.api_url, repo_slug, pr_number
+                    ),
+                ),
+                true,
+            ),
+        };
+        let response = self.send(
+            request
+                .with_header("Content-Type", "application/json")
+                .with_body(payload),
+        )?;
+
+        let url = serde_json::from_str::<GithubIssueComment>(response.as_str().unwrap_or("{}"))
+            .map(|comment| comment.html_url)
+            .unwrap_or_default();
+        Ok(if created {
+            PrCommentUpdate::Created { url }
+        } else {
+            PrCommentUpdate::Updated { url }
+        })
+    }
+}
+
+/// The comment an earlier `annotate-pr` run posted, if any
+fn find_annotation_comment(comments: &[GithubIssueComment]) -> Option<u64> {
+    comments
+        .iter()
+        .find(|comment| {
+            comment
+                .body
+                .as_deref()
+                .is_some_and(|body| body.contains(PR_ANNOTATION_MARKER))
+        })
+        .map(|comment| comment.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u64, body: Option<&str>) -> GithubIssueComment {
+        GithubIssueComment {
+            id,
+            body: body.map(str::to_string),
+            html_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_annotation_comment() {
+        let comments = vec![
+            comment(1, Some("LGTM")),
+            comment(2, None),
+            comment(
+                3,
+                Some(&format!("{}\n### 🤖 AI authorship", PR_ANNOTATION_MARKER)),
+            ),
+        ];
+        assert_eq!(find_annotation_comment(&comments), Some(3));
+        assert_eq!(find_annotation_comment(&comments[..2]), None);
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(1, 3), 33);
+        assert_eq!(percent(2, 3), 67);
+        assert_eq!(percent(0, 0), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::ci::ci_context::{CiContext, CiEvent};
+use crate::ci::github::{PR_ANNOTATION_MARKER, PrCommentUpdate};
 use crate::error::GitAiError;
 use crate::git::repository::exec_git;
 use crate::git::repository::find_repository_in_path;
@@ -282,6 +283,164 @@ pub fn print_gitlab_ci_yaml() {
     println!("{}", GITLAB_CI_TEMPLATE_YAML);
 }
 
+/// The MR a GitLab merge request pipeline runs for: (iid, base sha, head sha)
+pub fn mr_from_gitlab_env() -> Option<(u64, String, String)> {
+    let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    let iid = var("CI_MERGE_REQUEST_IID")?.parse().ok()?;
+    Some((
+        iid,
+        var("CI_MERGE_REQUEST_DIFF_BASE_SHA")?,
+        var("CI_COMMIT_SHA")?,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNote {
+    id: u64,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// Minimal GitLab REST client for MR notes. Authenticates with `GITLAB_TOKEN` when
+/// set, else with the pipeline's `CI_JOB_TOKEN`.
+pub struct GitlabNoteClient {
+    api_url: String,
+    auth_header: &'static str,
+    token: String,
+}
+
+impl GitlabNoteClient {
+    /// `api_url` is e.g. `https://gitlab.com/api/v4` (`$CI_API_V4_URL`)
+    pub fn from_env(api_url: &str) -> Result<Self, GitAiError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let (auth_header, token) = if let Some(token) = var("GITLAB_TOKEN") {
+            ("PRIVATE-TOKEN", token)
+        } else if let Some(token) = var("CI_JOB_TOKEN") {
+            ("JOB-TOKEN", token)
+        } else {
+            return Err(GitAiError::Generic(
+                "GITLAB_TOKEN or CI_JOB_TOKEN must be set to comment on the MR".to_string(),
+            ));
+        };
+        Ok(GitlabNoteClient {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            auth_header,
+            token,
+        })
+    }
+
+    fn request(&self, method: minreq::Method, url: &str) -> minreq::Request {
+        minreq::Request::new(method, url)
+            .with_header(self.auth_header, &self.token)
+            .with_header(
+                "User-Agent",
+                format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .with_timeout(30)
+    }
+
+    fn send(&self, request: minreq::Request) -> Result<minreq::Response, GitAiError> {
+        let response = request
+            .send()
+            .map_err(|e| GitAiError::Generic(format!("GitLab API request failed: {}", e)))?;
+        if !(200..300).contains(&response.status_code) {
+            return Err(GitAiError::Generic(format!(
+                "GitLab API returned status {}: {}",
+                response.status_code,
+                response.as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(response)
+    }
+
+    fn notes_url(&self, project: &str, mr_iid: u64) -> String {
+        format!(
+            "{}/projects/{}/merge_requests/{}/notes",
+            self.api_url,
+            encode_project(project),
+            mr_iid
+        )
+    }
+
+    fn list_notes(&self, project: &str, mr_iid: u64) -> Result<Vec<GitLabNote>, GitAiError> {
+        let mut notes = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}?per_page=100&page={}",
+                self.notes_url(project, mr_iid),
+                page
+            );
+            let response = self.send(self.request(minreq::Method::Get, &url))?;
+            let batch: Vec<GitLabNote> = serde_json::from_str(response.as_str().unwrap_or("[]"))
+                .map_err(|e| GitAiError::Generic(format!("Failed to parse GitLab notes: {}", e)))?;
+            let done = batch.len() < 100;
+            notes.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(notes)
+    }
+
+    /// Post `body` on the MR, or replace the note an earlier run posted. `project`
+    /// is a numeric id or a `group/project` path.
+    pub fn upsert_mr_note(
+        &self,
+        project: &str,
+        mr_iid: u64,
+        body: &str,
+    ) -> Result<PrCommentUpdate, GitAiError> {
+        let notes = self.list_notes(project, mr_iid)?;
+        let payload = serde_json::to_string(&serde_json::json!({ "body": body }))?;
+
+        let existing = find_annotation_note(&notes);
+        let request = match existing {
+            Some(id) => self.request(
+                minreq::Method::Put,
+                &format!("{}/{}", self.notes_url(project, mr_iid), id),
+            ),
+            None => self.request(minreq::Method::Post, &self.notes_url(project, mr_iid)),
+        };
+        let response = self.send(
+            request
+                .with_header("Content-Type", "application/json")
+                .with_body(payload),
+        )?;
+
+        let note_id = serde_json::from_str::<GitLabNote>(response.as_str().unwrap_or("{}"))
+            .map(|note| note.id)
+            .ok()
+            .or(existing);
+        let url = match (std::env::var("CI_MERGE_REQUEST_PROJECT_URL"), note_id) {
+            (Ok(project_url), Some(id)) => {
+                format!("{}/-/merge_requests/{}#note_{}", project_url, mr_iid, id)
+            }
+            _ => String::new(),
+        };
+        Ok(match existing {
+            Some(_) => PrCommentUpdate::Updated { url },
+            None => PrCommentUpdate::Created { url },
+        })
+    }
+}
+
+/// Numeric project ids are used as-is; paths must be URL-encoded
+fn encode_project(project: &str) -> String {
+    project.replace('/', "%2F")
+}
+
+/// The note an earlier `annotate-mr` run posted, if any
+fn find_annotation_note(notes: &[GitLabNote]) -> Option<u64> {
+    notes
+        .iter()
+        .find(|note| {
+            note.body
+                .as_deref()
+                .is_some_and(|body| body.contains(PR_ANNOTATION_MARKER))
+        })
+        .map(|note| note.id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,4 +509,21 @@ mod tests {
             "GitLab CI template YAML should not be empty"
         );
     }
+
+    #[test]
+    fn test_find_annotation_note() {
+        let notes: Vec<GitLabNote> = serde_json::from_str(&format!(
+            r#"[{{"id": 1, "body": "LGTM"}}, {{"id": 2}}, {{"id": 3, "body": "{}\n### 🤖 AI authorship"}}]"#,
+            PR_ANNOTATION_MARKER
+        ))
+        .unwrap();
+        assert_eq!(find_annotation_note(&notes), Some(3));
+        assert_eq!(find_annotation_note(&notes[..2]), None);
+    }
+
+    #[test]
+    fn test_encode_project() {
+        assert_eq!(encode_project("42"), "42");
+        assert_eq!(encode_project("group/sub/project"), "group%2Fsub%2Fproject");
+    }
 }
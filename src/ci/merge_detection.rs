@@ -0,0 +1,317 @@
+//! Recover the source branch of a merge from the merge commit alone.
+//!
+//! Merge queues (GitHub merge queue, GitLab merge trains, merge bots) often delete
+//! the source branch before the post-merge CI job runs, so the head ref and SHA
+//! that `CiContext` rewrites authorship from are no longer known. Given only the
+//! merge commit, [`detect_merge_source`] finds the original head from, in order:
+//!
+//! 1. the PR/MR number in the merge commit message, fetching the provider's
+//!    `refs/pull/N/head` or `refs/merge-requests/N/head`, which outlive the branch;
+//! 2. an unmerged commit (a remote or prefetched branch tip, or a commit with an
+//!    authorship note) whose tree is identical to the merge commit's;
+//! 3. an unmerged branch tip whose change since it forked has the same
+//!    `git patch-id` as the squash commit.
+
+use crate::ci::prefetch::PrProvider;
+use crate::error::GitAiError;
+use crate::git::refs::authorship_notes_ref_arg;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::utils::debug_log;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Local namespace for PR heads fetched during detection
+const DETECTION_REF_PREFIX: &str = "refs/git-ai/merge-detection";
+
+/// Most branch tips compared by patch-id, newest first
+const MAX_PATCH_ID_CANDIDATES: usize = 50;
+
+static GITHUB_MERGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Merge pull request #(\d+) from ").unwrap());
+static GITHUB_SQUASH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(#(\d+)\)\s*$").unwrap());
+static GITLAB_MERGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^See merge request \S*!(\d+)\s*$").unwrap());
+
+/// How the source head was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DetectionMethod {
+    /// The merge commit names a PR/MR whose head ref was fetched
+    PullRequestRef {
+        provider: PrProvider,
+        number: String,
+    },
+    /// Second parent of a true merge commit
+    MergeParent,
+    /// A commit with the same tree as the merge commit
+    TreeMatch,
+    /// A branch whose change has the same patch-id as the merge commit
+    PatchId,
+}
+
+impl DetectionMethod {
+    pub fn describe(&self) -> String {
+        match self {
+            DetectionMethod::PullRequestRef {
+                provider: PrProvider::GitHub,
+                number,
+            } => format!("pull request #{}", number),
+            DetectionMethod::PullRequestRef {
+                provider: PrProvider::GitLab,
+                number,
+            } => format!("merge request !{}", number),
+            DetectionMethod::MergeParent => "merge parent".to_string(),
+            DetectionMethod::TreeMatch => "identical tree".to_string(),
+            DetectionMethod::PatchId => "matching patch-id".to_string(),
+        }
+    }
+}
+
+/// The branch a merge commit was made from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeSource {
+    pub head_sha: String,
+    /// The ref the head was found under, or the SHA when it was found by content
+    pub head_ref: String,
+    pub method: DetectionMethod,
+}
+
+/// Find the original head of `merge_commit_sha`, fetching PR refs from `remote`.
+/// `Ok(None)` means nothing matched, e.g. a direct push to the base branch.
+pub fn detect_merge_source(
+    repo: &Repository,
+    merge_commit_sha: &str,
+    remote: &str,
+) -> Result<Option<MergeSource>, GitAiError> {
+    let merge_commit = repo.find_commit(merge_commit_sha.to_string())?;
+    let parents: Vec<String> = merge_commit.parents().map(|p| p.id()).collect();
+    if parents.len() > 1 {
+        return Ok(Some(MergeSource {
+            head_sha: parents[1].clone(),
+            head_ref: parents[1].clone(),
+            method: DetectionMethod::MergeParent,
+        }));
+    }
+    let Some(parent) = parents.first() else {
+        return Ok(None);
+    };
+
+    let message = format!("{}\n\n{}", merge_commit.summary()?, merge_commit.body()?);
+    if let Some((provider, number)) = pull_request_from_message(&message) {
+        match fetch_pull_request_head(repo, remote, provider, &number) {
+            Ok(head_sha) => {
+                return Ok(Some(MergeSource {
+                    head_sha,
+                    head_ref: provider.head_ref(&number),
+                    method: DetectionMethod::PullRequestRef { provider, number },
+                }));
+            }
+            Err(e) => debug_log(&format!(
+                "merge detection: could not fetch head of PR {}: {}",
+                number, e
+            )),
+        }
+    }
+
+    let candidates = unmerged_candidates(repo, merge_commit_sha)?;
+    let merge_tree = merge_commit.tree()?.id();
+    debug_log(&format!(
+        "merge detection: {} unmerged candidates for {}",
+        candidates.len(),
+        merge_commit_sha
+    ));
+    if let Some(candidate) = candidates.iter().find(|c| c.tree == merge_tree) {
+        return Ok(Some(candidate.to_source(DetectionMethod::TreeMatch)));
+    }
+
+    let Some(merge_patch_id) = patch_id(repo, parent, merge_commit_sha)? else {
+        return Ok(None);
+    };
+    for candidate in candidates
+        .iter()
+        .filter(|c| c.is_tip)
+        .take(MAX_PATCH_ID_CANDIDATES)
+    {
+        let Ok(base) = repo.merge_base(candidate.sha.clone(), parent.clone()) else {
+            continue;
+        };
+        if patch_id(repo, &base, &candidate.sha)?.as_deref() == Some(merge_patch_id.as_str()) {
+            return Ok(Some(candidate.to_source(DetectionMethod::PatchId)));
+        }
+    }
+    Ok(None)
+}
+
+/// The PR/MR a merge commit message refers to, from the messages GitHub and
+/// GitLab write for merge and squash commits
+fn pull_request_from_message(message: &str) -> Option<(PrProvider, String)> {
+    let subject = message.lines().next().unwrap_or("").trim();
+    if let Some(caps) = GITHUB_MERGE_RE.captures(subject) {
+        return Some((PrProvider::GitHub, caps[1].to_string()));
+    }
+    if let Some(caps) = GITLAB_MERGE_RE.captures(message) {
+        return Some((PrProvider::GitLab, caps[1].to_string()));
+    }
+    GITHUB_SQUASH_RE
+        .captures(subject)
+        .map(|caps| (PrProvider::GitHub, caps[1].to_string()))
+}
+
+fn fetch_pull_request_head(
+    repo: &Repository,
+    remote: &str,
+    provider: PrProvider,
+    number: &str,
+) -> Result<String, GitAiError> {
+    let local_ref = format!("{}/{}", DETECTION_REF_PREFIX, number);
+    let mut args = repo.global_args_for_exec();
+    args.push("fetch".to_string());
+    args.push("--no-tags".to_string());
+    args.push(remote.to_string());
+    args.push(format!("+{}:{}", provider.head_ref(number), local_ref));
+    exec_git(&args)?;
+
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-parse".to_string());
+    args.push("--verify".to_string());
+    args.push(format!("{}^{{commit}}", local_ref));
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+struct Candidate {
+    sha: String,
+    tree: String,
+    /// Not the parent of another candidate
+    is_tip: bool,
+    refname: Option<String>,
+}
+
+impl Candidate {
+    fn to_source(&self, method: DetectionMethod) -> MergeSource {
+        MergeSource {
+            head_sha: self.sha.clone(),
+            head_ref: self.refname.clone().unwrap_or_else(|| self.sha.clone()),
+            method,
+        }
+    }
+}
+
+/// Commits that are not in the merge commit's history, reachable from a ref or
+/// from a commit with an authorship note, newest first
+fn unmerged_candidates(
+    repo: &Repository,
+    merge_commit_sha: &str,
+) -> Result<Vec<Candidate>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("for-each-ref".to_string());
+    args.push("--format=%(objectname) %(refname)".to_string());
+    args.push("refs/heads/".to_string());
+    args.push("refs/remotes/".to_string());
+    args.push("refs/git-ai/".to_string());
+    let output = exec_git(&args)?;
+    let mut refnames: HashMap<String, String> = HashMap::new();
+    let mut starts: Vec<String> = Vec::new();
+    for line in String::from_utf8(output.stdout)?.lines() {
+        if let Some((sha, refname)) = line.split_once(' ') {
+            refnames
+                .entry(sha.to_string())
+                .or_insert_with(|| refname.to_string());
+            starts.push(sha.to_string());
+        }
+    }
+
+    // Notes survive branch deletion; their commits are only here if fetched before
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(authorship_notes_ref_arg());
+    args.push("list".to_string());
+    if let Ok(output) = exec_git(&args) {
+        starts.extend(
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_whitespace().nth(1))
+                .map(str::to_string),
+        );
+    }
+    if starts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H %T %P".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push("--stdin".to_string());
+    args.push(format!("^{}", merge_commit_sha));
+    let output = exec_git_stdin(&args, format!("{}\n", starts.join("\n")).as_bytes())?;
+
+    let mut commits: Vec<(String, String)> = Vec::new();
+    let mut has_child: HashSet<String> = HashSet::new();
+    for line in String::from_utf8(output.stdout)?.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(sha), Some(tree)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        has_child.extend(fields.map(str::to_string));
+        commits.push((sha.to_string(), tree.to_string()));
+    }
+    Ok(commits
+        .into_iter()
+        .map(|(sha, tree)| Candidate {
+            is_tip: !has_child.contains(&sha),
+            refname: refnames.get(&sha).cloned(),
+            sha,
+            tree,
+        })
+        .collect())
+}
+
+/// Stable patch-id of the diff `from..to`, or `None` if it is empty
+fn patch_id(repo: &Repository, from: &str, to: &str) -> Result<Option<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff-tree".to_string());
+    args.push("-p".to_string());
+    args.push("--no-color".to_string());
+    args.push(from.to_string());
+    args.push(to.to_string());
+    let diff = exec_git(&args)?.stdout;
+    if diff.is_empty() {
+        return Ok(None);
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("patch-id".to_string());
+    args.push("--stable".to_string());
+    let output = exec_git_stdin(&args, &diff)?;
+    Ok(String::from_utf8(output.stdout)?
+        .split_whitespace()
+        .next()
+        .map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_request_from_message() {
+        assert_eq!(
+            pull_request_from_message("Add login form (#123)\n\n* wip\n* fix"),
+            Some((PrProvider::GitHub, "123".to_string()))
+        );
+        assert_eq!(
+            pull_request_from_message("Merge pull request #45 from acme/feature\n\nAdd feature"),
+            Some((PrProvider::GitHub, "45".to_string()))
+        );
+        assert_eq!(
+            pull_request_from_message(
+                "Merge branch 'feature' into 'main'\n\nAdd feature\n\nSee merge request group/project!67\n"
+            ),
+            Some((PrProvider::GitLab, "67".to_string()))
+        );
+        assert_eq!(pull_request_from_message("Fix typo"), None);
+        // Issue references in the body aren't PRs
+        assert_eq!(pull_request_from_message("Fix crash\n\nCloses (#9)"), None);
+    }
+}
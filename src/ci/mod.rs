@@ -1,3 +1,7 @@
+pub mod changed_ai_files;
 pub mod ci_context;
 pub mod github;
 pub mod gitlab;
+pub mod merge_detection;
+pub mod note_coverage;
+pub mod prefetch;
@@ -0,0 +1,124 @@
+//! Note presence gate for CI.
+//!
+//! `git-ai ci require-notes --range <base>..<head>` checks that every commit in a
+//! range that changes source files has an authorship note, so attribution
+//! coverage can be made a merge requirement. Commits that only touch ignored
+//! paths (lockfiles, generated code, `--ignore` patterns) are exempt, as are
+//! merge commits, which don't carry notes of their own.
+
+use crate::authorship::ignore::IgnoreMatcher;
+use crate::error::GitAiError;
+use crate::git::refs::note_blob_oids_for_commits;
+use crate::git::repository::{Repository, exec_git};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// A commit that changes source files but has no authorship note
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+pub struct CommitMissingNote {
+    pub sha: String,
+    pub summary: String,
+    /// The source files it changes
+    pub files: Vec<String>,
+}
+
+/// Authorship note coverage of the source-changing commits in a range
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct RangeNoteCoverage {
+    pub range: String,
+    /// Non-merge commits in the range
+    pub commits: usize,
+    /// Commits that change source files, and so need a note
+    pub required: usize,
+    pub missing: Vec<CommitMissingNote>,
+    pub passed: bool,
+}
+
+impl RangeNoteCoverage {
+    pub fn for_range(
+        repo: &Repository,
+        range: &str,
+        matcher: &IgnoreMatcher,
+    ) -> Result<Self, GitAiError> {
+        let (base, head) = range
+            .split_once("..")
+            .filter(|(base, head)| !base.is_empty() && !head.is_empty() && !head.starts_with('.'))
+            .ok_or_else(|| {
+                GitAiError::Generic(format!("Invalid range {}: expected <base>..<head>", range))
+            })?;
+
+        let mut args = repo.global_args_for_exec();
+        args.extend([
+            "-c".to_string(),
+            "core.quotepath=false".to_string(),
+            "log".to_string(),
+            "--no-merges".to_string(),
+            "--no-renames".to_string(),
+            "--format=%x00%H%x09%s".to_string(),
+            "--name-only".to_string(),
+            format!("{}..{}", base, head),
+            "--".to_string(),
+        ]);
+        let output = exec_git(&args)?;
+        let stdout = String::from_utf8(output.stdout)?;
+
+        let mut commits = Vec::new();
+        for entry in stdout.split('\0').filter(|e| !e.trim().is_empty()) {
+            let mut lines = entry.lines();
+            let header = lines.next().unwrap_or_default();
+            let (sha, summary) = header.split_once('\t').unwrap_or((header, ""));
+            let files: Vec<String> = lines
+                .map(str::trim)
+                .filter(|file| !file.is_empty() && !matcher.is_ignored(file))
+                .map(str::to_string)
+                .collect();
+            commits.push((sha.trim().to_string(), summary.to_string(), files));
+        }
+
+        let required: Vec<String> = commits
+            .iter()
+            .filter(|(_, _, files)| !files.is_empty())
+            .map(|(sha, _, _)| sha.clone())
+            .collect();
+        let with_notes = note_blob_oids_for_commits(repo, &required)?;
+
+        let missing: Vec<CommitMissingNote> = commits
+            .iter()
+            .filter(|(sha, _, files)| !files.is_empty() && !with_notes.contains_key(sha))
+            .map(|(sha, summary, files)| CommitMissingNote {
+                sha: sha.clone(),
+                summary: summary.clone(),
+                files: files.clone(),
+            })
+            .collect();
+
+        Ok(RangeNoteCoverage {
+            range: range.to_string(),
+            commits: commits.len(),
+            required: required.len(),
+            passed: missing.is_empty(),
+            missing,
+        })
+    }
+
+    /// Human-readable summary, for stderr next to the JSON report
+    pub fn summary(&self) -> String {
+        if self.passed {
+            return format!(
+                "[git-ai] All {} commits in {} that change source files have authorship notes",
+                self.required, self.range
+            );
+        }
+        let mut lines = vec![format!(
+            "[git-ai] {} of {} commits in {} that change source files have no authorship note:",
+            self.missing.len(),
+            self.required,
+            self.range
+        )];
+        for commit in &self.missing {
+            let short_sha = &commit.sha[..commit.sha.len().min(7)];
+            lines.push(format!("  {} {}", short_sha, commit.summary));
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,162 @@
+//! Merge-queue note prefetch.
+//!
+//! Run ahead of a merge queue, this fetches the authorship notes and head commits
+//! of every queued PR and warms the attribution cache for them, so the CI run that
+//! reconciles each merge doesn't start from a cold runner.
+
+use crate::authorship::attribution_cache::warm_for_range;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::git::sync_authorship::fetch_authorship_notes;
+
+/// Local namespace for fetched PR heads
+const PREFETCH_REF_PREFIX: &str = "refs/git-ai/prefetch";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrProvider {
+    GitHub,
+    GitLab,
+}
+
+impl PrProvider {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            _ => None,
+        }
+    }
+
+    /// GitLab when running in GitLab CI, GitHub otherwise
+    pub fn detect() -> Self {
+        if std::env::var("GITLAB_CI").is_ok() {
+            Self::GitLab
+        } else {
+            Self::GitHub
+        }
+    }
+
+    /// Remote ref holding the head of PR/MR `number`
+    pub(crate) fn head_ref(self, number: &str) -> String {
+        match self {
+            Self::GitHub => format!("refs/pull/{}/head", number),
+            Self::GitLab => format!("refs/merge-requests/{}/head", number),
+        }
+    }
+}
+
+/// A queued PR: a number, a full ref or a branch name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedPr {
+    pub name: String,
+    pub remote_ref: String,
+    pub local_ref: String,
+}
+
+impl QueuedPr {
+    pub fn parse(spec: &str, provider: PrProvider) -> Self {
+        let remote_ref = if !spec.is_empty() && spec.chars().all(|c| c.is_ascii_digit()) {
+            provider.head_ref(spec)
+        } else if spec.starts_with("refs/") {
+            spec.to_string()
+        } else {
+            format!("refs/heads/{}", spec)
+        };
+        let sanitized: String = spec
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Self {
+            name: spec.to_string(),
+            remote_ref,
+            local_ref: format!("{}/{}", PREFETCH_REF_PREFIX, sanitized),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PrefetchSummary {
+    pub prs: usize,
+    pub notes: usize,
+    pub failed: Vec<String>,
+}
+
+/// Fetch notes, the base branch and every queued PR head in one round trip each,
+/// then warm the attribution cache for each PR's commits.
+pub fn prefetch_merge_queue(
+    repo: &Repository,
+    remote: &str,
+    base_ref: &str,
+    prs: &[QueuedPr],
+) -> Result<PrefetchSummary, GitAiError> {
+    println!("Fetching authorship history");
+    fetch_authorship_notes(repo, remote)?;
+
+    println!(
+        "Fetching base branch {} and {} queued PRs",
+        base_ref,
+        prs.len()
+    );
+    let mut args = repo.global_args_for_exec();
+    args.push("fetch".to_string());
+    args.push("--no-tags".to_string());
+    args.push(remote.to_string());
+    args.push(format!(
+        "+refs/heads/{}:refs/remotes/{}/{}",
+        base_ref, remote, base_ref
+    ));
+    for pr in prs {
+        args.push(format!("+{}:{}", pr.remote_ref, pr.local_ref));
+    }
+    exec_git(&args)?;
+
+    let base = format!("refs/remotes/{}/{}", remote, base_ref);
+    let mut summary = PrefetchSummary::default();
+    for pr in prs {
+        match warm_pr(repo, &base, pr) {
+            Ok(notes) => {
+                println!("{}: warmed {} authorship notes", pr.name, notes);
+                summary.prs += 1;
+                summary.notes += notes;
+            }
+            Err(e) => {
+                eprintln!("{}: prefetch failed: {}", pr.name, e);
+                summary.failed.push(pr.name.clone());
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn warm_pr(repo: &Repository, base: &str, pr: &QueuedPr) -> Result<usize, GitAiError> {
+    let merge_base = repo.merge_base(base.to_string(), pr.local_ref.clone())?;
+    warm_for_range(repo, &merge_base, &pr.local_ref)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queued_pr_parse() {
+        let pr = QueuedPr::parse("42", PrProvider::GitHub);
+        assert_eq!(pr.remote_ref, "refs/pull/42/head");
+        assert_eq!(pr.local_ref, "refs/git-ai/prefetch/42");
+
+        let mr = QueuedPr::parse("42", PrProvider::GitLab);
+        assert_eq!(mr.remote_ref, "refs/merge-requests/42/head");
+
+        let branch = QueuedPr::parse("feature/login", PrProvider::GitHub);
+        assert_eq!(branch.remote_ref, "refs/heads/feature/login");
+        assert_eq!(branch.local_ref, "refs/git-ai/prefetch/feature_login");
+
+        let full = QueuedPr::parse("refs/heads/gh-readonly-queue/main/pr-7", PrProvider::GitHub);
+        assert_eq!(full.remote_ref, "refs/heads/gh-readonly-queue/main/pr-7");
+    }
+}
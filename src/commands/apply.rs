@@ -0,0 +1,435 @@
+//! `git-ai apply <manifest>`: onboard a repository from one YAML file. The
+//! manifest declares the repository config keys to set (the same keys as
+//! `.git-ai.toml`), which hooks to install, the remotes authorship notes are
+//! synced with, and CI setup. Applying is idempotent: each step compares what
+//! is there with what the manifest asks for and only writes the difference, so
+//! the manifest can be rerun after every edit.
+//!
+//! ```yaml
+//! config_file: shared        # or `local` for .git/ai/config.toml
+//! exclude_paths: ["vendor/**"]
+//! policy:
+//!   rules: [...]
+//! hooks:
+//!   repo: true               # git hooks for this repository
+//!   agents: true             # coding agent and git client hooks
+//! notes:
+//!   remotes:
+//!     - name: origin
+//!     - name: notes-mirror
+//!       url: git@example.com:team/notes.git
+//!   fetch: true
+//! ci:
+//!   github: true
+//! ```
+
+use crate::ci::github::{GITHUB_CI_TEMPLATE_YAML, install_github_ci_workflow};
+use crate::commands::git_hook_handlers::{ensure_repo_hooks_installed, mark_repo_hooks_enabled};
+use crate::commands::install_hooks;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use crate::git::sync_authorship::{NotesExistence, fetch_authorship_notes};
+use crate::output::{self, Status};
+use crate::repo_config::{
+    RepoConfigLayer, RepoConfigScope, RepoFileConfig, repo_config_paths, repo_dirs_from_cwd,
+};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Which repository config file the manifest's config keys are written to
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ManifestConfigFile {
+    #[default]
+    Shared,
+    Local,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct ManifestHooks {
+    repo: bool,
+    agents: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ManifestRemote {
+    name: String,
+    /// Required when the remote doesn't exist yet
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct ManifestNotes {
+    remotes: Vec<ManifestRemote>,
+    /// Fetch authorship notes from each remote after configuring it
+    fetch: bool,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct ManifestCi {
+    github: bool,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct OnboardingManifest {
+    config_file: ManifestConfigFile,
+    hooks: Option<ManifestHooks>,
+    notes: Option<ManifestNotes>,
+    ci: Option<ManifestCi>,
+    /// Repository config keys; anything this doesn't recognize lands in
+    /// `unsupported` and is rejected
+    #[serde(flatten)]
+    config: RepoFileConfig,
+}
+
+impl OnboardingManifest {
+    fn parse(yaml: &str) -> Result<Self, String> {
+        let manifest: OnboardingManifest = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+        if let Some(key) = manifest.config.unsupported.keys().next() {
+            return Err(format!(
+                "unknown key '{}' (expected config_file, hooks, notes, ci or a .git-ai.toml key)",
+                key
+            ));
+        }
+        Ok(manifest)
+    }
+}
+
+pub fn handle_apply(args: &[String]) {
+    let mut manifest_path: Option<&str> = None;
+    let mut dry_run = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                print_apply_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_apply_help();
+                std::process::exit(1);
+            }
+            other if manifest_path.is_none() => manifest_path = Some(other),
+            other => {
+                eprintln!("Unexpected argument: {}", other);
+                print_apply_help();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(manifest_path) = manifest_path else {
+        print_apply_help();
+        std::process::exit(1);
+    };
+    let manifest = match fs::read_to_string(manifest_path)
+        .map_err(|e| e.to_string())
+        .and_then(|yaml| OnboardingManifest::parse(&yaml))
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Failed to load {}: {}", manifest_path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    if let Err(e) = apply_config(&manifest, dry_run) {
+        eprintln!("  {}", e);
+        failed = true;
+    }
+    if let Some(hooks) = &manifest.hooks
+        && let Err(e) = apply_hooks(&repo, hooks, dry_run)
+    {
+        eprintln!("  {}", e);
+        failed = true;
+    }
+    if let Some(notes) = &manifest.notes
+        && let Err(e) = apply_notes(&repo, notes, dry_run)
+    {
+        eprintln!("  {}", e);
+        failed = true;
+    }
+    if let Some(ci) = &manifest.ci
+        && let Err(e) = apply_ci(&repo, ci, dry_run)
+    {
+        eprintln!("  {}", e);
+        failed = true;
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    println!();
+    if dry_run {
+        println!(
+            "{}",
+            output::mode().status(Status::Warning, "Dry run. No changes were made.")
+        );
+    } else {
+        println!(
+            "{}",
+            output::mode().status(Status::Success, &format!("Applied {}", manifest_path))
+        );
+    }
+}
+
+fn section(title: &str) {
+    println!("\n{}", output::mode().paint("1", title));
+}
+
+fn report(changed: bool, dry_run: bool, what: &str) {
+    let verb = match (changed, dry_run) {
+        (false, _) => "Unchanged",
+        (true, true) => "Would update",
+        (true, false) => "Updated",
+    };
+    println!("  {} {}", verb, what);
+}
+
+/// Set the manifest's config keys in the repository config file. Keys the
+/// manifest doesn't mention are left as they are.
+fn apply_config(manifest: &OnboardingManifest, dry_run: bool) -> Result<(), String> {
+    let values = toml::Table::try_from(&manifest.config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    if values.is_empty() {
+        return Ok(());
+    }
+    section("Config");
+
+    let (workdir, common_dir) = repo_dirs_from_cwd()
+        .ok_or_else(|| "Failed to locate the repository config files".to_string())?;
+    let scope = match manifest.config_file {
+        ManifestConfigFile::Shared => RepoConfigScope::Shared,
+        ManifestConfigFile::Local => RepoConfigScope::Local,
+    };
+    let (_, path) = repo_config_paths(&workdir, &common_dir)
+        .into_iter()
+        .find(|(candidate, _)| *candidate == scope)
+        .expect("both scopes have a path");
+
+    let layer = RepoConfigLayer {
+        scope,
+        path: path.clone(),
+        config: manifest.config.clone(),
+    };
+    for warning in layer.warnings() {
+        eprintln!("  {}", output::mode().status(Status::Warning, &warning));
+    }
+
+    let changed = merge_config_file(&path, values, dry_run)?;
+    report(changed, dry_run, &path.display().to_string());
+    Ok(())
+}
+
+/// Overlay `values` on the top-level keys of the TOML file at `path`, writing
+/// it only if that changes anything. Returns whether it did (or would).
+fn merge_config_file(path: &Path, values: toml::Table, dry_run: bool) -> Result<bool, String> {
+    let existing = match fs::read_to_string(path) {
+        Ok(data) => data
+            .parse::<toml::Table>()
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let mut desired = existing.clone();
+    desired.extend(values);
+    if desired == existing {
+        return Ok(false);
+    }
+    if !dry_run {
+        let data = toml::to_string_pretty(&desired)
+            .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(true)
+}
+
+fn apply_hooks(repo: &Repository, hooks: &ManifestHooks, dry_run: bool) -> Result<(), String> {
+    if hooks.repo {
+        section("Repository Hooks");
+        let installed = ensure_repo_hooks_installed(repo, dry_run)
+            .map_err(|e| format!("Failed to install repository hooks: {}", e))?;
+        let mut changed = installed.changed;
+        if !dry_run {
+            changed |= mark_repo_hooks_enabled(repo)
+                .map_err(|e| format!("Failed to enable repository hooks: {}", e))?;
+        }
+        report(
+            changed,
+            dry_run,
+            &installed.managed_hooks_path.display().to_string(),
+        );
+    }
+    if hooks.agents {
+        let args: Vec<String> = if dry_run {
+            vec!["--dry-run".to_string()]
+        } else {
+            Vec::new()
+        };
+        install_hooks::run(&args).map_err(|e| format!("Failed to install agent hooks: {}", e))?;
+    }
+    Ok(())
+}
+
+fn apply_notes(repo: &Repository, notes: &ManifestNotes, dry_run: bool) -> Result<(), String> {
+    if notes.remotes.is_empty() {
+        return Ok(());
+    }
+    section("Notes Remotes");
+    let existing = repo
+        .remotes_with_urls()
+        .map_err(|e| format!("Failed to list remotes: {}", e))?;
+
+    for remote in &notes.remotes {
+        let current_url = existing
+            .iter()
+            .find(|(name, _)| *name == remote.name)
+            .map(|(_, url)| url.as_str());
+        let command = match (current_url, remote.url.as_deref()) {
+            (None, None) => {
+                return Err(format!(
+                    "Remote '{}' doesn't exist and the manifest gives no url for it",
+                    remote.name
+                ));
+            }
+            (None, Some(url)) => Some(["remote", "add", remote.name.as_str(), url]),
+            (Some(current), Some(url)) if current != url => {
+                Some(["remote", "set-url", remote.name.as_str(), url])
+            }
+            _ => None,
+        };
+        if let Some(command) = command
+            && !dry_run
+        {
+            repo.git(&command)
+                .map_err(|e| format!("Failed to configure remote '{}': {}", remote.name, e))?;
+        }
+        report(
+            command.is_some(),
+            dry_run,
+            &format!("remote {}", remote.name),
+        );
+
+        if notes.fetch && !dry_run {
+            match fetch_authorship_notes(repo, &remote.name) {
+                Ok(NotesExistence::Found) => {
+                    println!("  Fetched authorship notes from {}", remote.name)
+                }
+                Ok(NotesExistence::NotFound) => {
+                    println!("  No authorship notes on {} yet", remote.name)
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "Failed to fetch authorship notes from '{}': {}",
+                        remote.name, e
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_ci(repo: &Repository, ci: &ManifestCi, dry_run: bool) -> Result<(), String> {
+    if !ci.github {
+        return Ok(());
+    }
+    section("CI");
+    let workdir = repo.workdir().map_err(|e| e.to_string())?;
+    let path = workdir
+        .join(".github")
+        .join("workflows")
+        .join("git-ai.yaml");
+    let changed = fs::read_to_string(&path).ok().as_deref() != Some(GITHUB_CI_TEMPLATE_YAML);
+    if changed && !dry_run {
+        install_github_ci_workflow()
+            .map_err(|e| format!("Failed to install GitHub workflow: {}", e))?;
+    }
+    report(changed, dry_run, &path.display().to_string());
+    Ok(())
+}
+
+fn print_apply_help() {
+    eprintln!("Usage: git-ai apply <manifest.yaml> [--dry-run]");
+    eprintln!();
+    eprintln!("Onboards the current repository from a YAML manifest: repository config keys");
+    eprintln!("(as in .git-ai.toml), hooks, notes remotes and CI. Rerunning only applies");
+    eprintln!("what changed.");
+    eprintln!();
+    eprintln!("Manifest keys:");
+    eprintln!("  config_file: shared|local   Config file to write (default: shared .git-ai.toml)");
+    eprintln!("  hooks: {{repo, agents}}       Install repository and/or agent hooks");
+    eprintln!("  notes: {{remotes, fetch}}     Remotes ({{name, url}}) to sync notes with");
+    eprintln!("  ci: {{github}}                Install the GitHub Actions workflow");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --dry-run   Show what would change without changing anything");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_separates_sections_from_config_keys() {
+        let manifest = OnboardingManifest::parse(
+            "config_file: local\nexclude_paths: [\"vendor/**\"]\nnotes_ref: team\nhooks:\n  repo: true\nci:\n  github: true\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.config_file, ManifestConfigFile::Local);
+        assert_eq!(
+            manifest.config.exclude_paths,
+            Some(vec!["vendor/**".to_string()])
+        );
+        assert_eq!(manifest.config.notes_ref.as_deref(), Some("team"));
+        assert!(manifest.hooks.unwrap().repo);
+        assert!(manifest.ci.unwrap().github);
+        assert!(manifest.notes.is_none());
+    }
+
+    #[test]
+    fn test_manifest_rejects_unknown_keys() {
+        let err = OnboardingManifest::parse("api_key: secret\n").unwrap_err();
+        assert!(err.contains("unknown key 'api_key'"), "{}", err);
+        assert!(OnboardingManifest::parse("hooks:\n  everything: true\n").is_err());
+    }
+
+    #[test]
+    fn test_merge_config_file_keeps_other_keys_and_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".git-ai.toml");
+        fs::write(&path, "notes_ref = \"old\"\ncapture_environment = true\n").unwrap();
+
+        let values: toml::Table = toml::from_str("notes_ref = \"team\"").unwrap();
+        assert!(merge_config_file(&path, values.clone(), false).unwrap());
+        let written: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(written["notes_ref"].as_str(), Some("team"));
+        assert_eq!(written["capture_environment"].as_bool(), Some(true));
+
+        assert!(!merge_config_file(&path, values, false).unwrap());
+    }
+}
@@ -0,0 +1,471 @@
+//! `git-ai archive` / `git-ai unbundle`: a `git bundle` of the requested history
+//! that also carries the authorship notes ref, so a repository can be archived or
+//! handed over as a single file without losing attribution.
+//!
+//! `--json <path>` additionally writes a sidecar with each commit's note and the
+//! prompt records they reference, readable without git and importable on the
+//! other side with `git-ai unbundle --json`.
+
+use crate::api::object_storage::{is_object_url, staging_path, upload_staged};
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::internal_db::{InternalDatabase, PromptDbRecord};
+use crate::commands::export::load_prompts;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::find_repository;
+use crate::git::refs::{
+    authorship_notes_ref, authorship_notes_ref_name, copy_ref, interned_prompts_ref,
+    merge_notes_from_ref, merge_notes_into_ref, note_blob_oids_for_commits, ref_exists,
+};
+use crate::git::repository::{Repository, exec_git};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const SIDECAR_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Sidecar {
+    version: u32,
+    created_at: i64,
+    revs: Vec<String>,
+    notes_ref: String,
+    /// Commit -> raw authorship note
+    notes: BTreeMap<String, String>,
+    prompts: Vec<PromptDbRecord>,
+}
+
+struct ArchiveSummary {
+    commits: usize,
+    notes: usize,
+}
+
+struct UnbundleSummary {
+    refs: usize,
+    skipped_current: Option<String>,
+    notes: usize,
+    prompts: usize,
+}
+
+pub fn handle_archive(args: &[String]) {
+    let mut revs: Vec<String> = Vec::new();
+    // Paths, or s3:// / gs:// URLs to upload to
+    let mut output: Option<String> = None;
+    let mut json: Option<String> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--out" | "--output" | "--json" => {
+                let Some(path) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a path", args[i]);
+                    std::process::exit(1);
+                };
+                if args[i] == "--json" {
+                    json = Some(path.clone());
+                } else {
+                    output = Some(path.clone());
+                }
+                i += 1;
+            }
+            "--all" => revs.push("--all".to_string()),
+            "-h" | "--help" => {
+                print_archive_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_archive_help();
+                std::process::exit(1);
+            }
+            other => revs.push(other.to_string()),
+        }
+        i += 1;
+    }
+
+    let Some(output) = output else {
+        eprintln!("Error: --out <file.bundle> is required");
+        print_archive_help();
+        std::process::exit(1);
+    };
+    if revs.is_empty() {
+        revs.push("HEAD".to_string());
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let local_output = local_path(&output);
+    let local_json = json.as_deref().map(local_path);
+    let result = archive(&repo, &revs, &local_output, local_json.as_deref()).and_then(|summary| {
+        if is_object_url(&output) {
+            upload_staged(&local_output, &output)?;
+        }
+        if let (Some(url), Some(path)) = (&json, &local_json)
+            && is_object_url(url)
+        {
+            upload_staged(path, url)?;
+        }
+        Ok(summary)
+    });
+    match result {
+        Ok(summary) => {
+            println!(
+                "Archived {} commits with {} authorship notes to {}",
+                summary.commits, summary.notes, output
+            );
+            if let Some(json) = json {
+                println!("Wrote notes and prompts to {}", json);
+            }
+        }
+        Err(e) => {
+            eprintln!("Archive failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn handle_unbundle(args: &[String]) {
+    let mut bundle: Option<PathBuf> = None;
+    let mut json: Option<PathBuf> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => {
+                let Some(path) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a path", args[i]);
+                    std::process::exit(1);
+                };
+                json = Some(absolute(path));
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_unbundle_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_unbundle_help();
+                std::process::exit(1);
+            }
+            other => {
+                if bundle.is_some() {
+                    eprintln!("Error: only one bundle can be restored at a time");
+                    std::process::exit(1);
+                }
+                bundle = Some(absolute(other));
+            }
+        }
+        i += 1;
+    }
+
+    let Some(bundle) = bundle else {
+        print_unbundle_help();
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match unbundle(&repo, &bundle, json.as_deref()) {
+        Ok(summary) => {
+            println!(
+                "Restored {} refs and {} authorship notes from {}",
+                summary.refs,
+                summary.notes,
+                bundle.display()
+            );
+            if let Some(branch) = summary.skipped_current {
+                println!(
+                    "Skipped {} because it is checked out; fetch it from the bundle after switching away",
+                    branch
+                );
+            }
+            if json.is_some() {
+                println!("Imported {} prompts", summary.prompts);
+            }
+        }
+        Err(e) => {
+            eprintln!("Unbundle failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn archive(
+    repo: &Repository,
+    revs: &[String],
+    output: &Path,
+    json: Option<&Path>,
+) -> Result<ArchiveSummary, GitAiError> {
+    let commits = rev_list(repo, revs)?;
+    let note_oids = note_blob_oids_for_commits(repo, &commits)?;
+
+    let notes_ref = authorship_notes_ref();
+    let mut args = repo.global_args_for_exec();
+    args.extend(["bundle", "create", "--quiet"].map(String::from));
+    args.push(output.to_string_lossy().to_string());
+    args.extend(revs.iter().cloned());
+    if ref_exists(repo, &notes_ref) {
+        args.push(notes_ref.clone());
+    }
+    // Messages the notes reference through `messages_ref`
+    let prompts_ref = interned_prompts_ref();
+    if ref_exists(repo, &prompts_ref) {
+        args.push(prompts_ref);
+    }
+    exec_git(&args)?;
+
+    if let Some(json) = json {
+        let blob_oids: Vec<String> = note_oids
+            .values()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let blobs = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &blob_oids)?;
+        let notes: BTreeMap<String, String> = note_oids
+            .iter()
+            .filter_map(|(commit, oid)| Some((commit.clone(), blobs.get(oid)?.clone())))
+            .collect();
+        let prompt_ids: BTreeSet<String> = notes
+            .values()
+            .filter_map(|content| AuthorshipLog::deserialize_from_string(content).ok())
+            .flat_map(|log| log.metadata.prompts.into_keys())
+            .collect();
+        let sidecar = Sidecar {
+            version: SIDECAR_VERSION,
+            created_at: chrono::Utc::now().timestamp(),
+            revs: revs.to_vec(),
+            notes_ref,
+            notes,
+            prompts: load_prompts(&prompt_ids),
+        };
+        std::fs::write(json, serde_json::to_vec_pretty(&sidecar)?)?;
+    }
+
+    Ok(ArchiveSummary {
+        commits: commits.len(),
+        notes: note_oids.len(),
+    })
+}
+
+fn unbundle(
+    repo: &Repository,
+    bundle: &Path,
+    json: Option<&Path>,
+) -> Result<UnbundleSummary, GitAiError> {
+    let bundle_arg = bundle.to_string_lossy().to_string();
+
+    // Fails with the missing prerequisite commits when the bundle is incremental
+    let mut args = repo.global_args_for_exec();
+    args.extend(["bundle", "verify", "--quiet"].map(String::from));
+    args.push(bundle_arg.clone());
+    exec_git(&args)?;
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["bundle", "list-heads"].map(String::from));
+    args.push(bundle_arg.clone());
+    let heads = String::from_utf8(exec_git(&args)?.stdout)?;
+    let heads: Vec<&str> = heads
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .collect();
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["symbolic-ref", "--quiet", "HEAD"].map(String::from));
+    let current_branch = exec_git(&args)
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|branch| branch.trim().to_string());
+    let unborn = repo.head().and_then(|head| head.target()).is_err();
+
+    let mut summary = UnbundleSummary {
+        refs: 0,
+        skipped_current: None,
+        notes: 0,
+        prompts: 0,
+    };
+    let mut refspecs = Vec::new();
+    let mut fill_worktree = false;
+    for head in &heads {
+        if !head.starts_with("refs/heads/") && !head.starts_with("refs/tags/") {
+            continue;
+        }
+        // The checked-out branch can't move under the working tree, unless
+        // there is nothing checked out yet
+        if current_branch.as_deref() == Some(*head) {
+            if !unborn {
+                summary.skipped_current = Some(head.trim_start_matches("refs/heads/").to_string());
+                continue;
+            }
+            fill_worktree = true;
+        }
+        refspecs.push(format!("{}:{}", head, head));
+    }
+    if !refspecs.is_empty() {
+        let mut args = repo.global_args_for_exec();
+        args.extend(["fetch", "--quiet", "--update-head-ok"].map(String::from));
+        args.push(bundle_arg.clone());
+        args.extend(refspecs.iter().cloned());
+        exec_git(&args)?;
+        summary.refs = refspecs.len();
+    }
+    if fill_worktree {
+        // Refuses to overwrite untracked files rather than clobbering them
+        let mut args = repo.global_args_for_exec();
+        args.extend(["read-tree", "-m", "-u", "HEAD"].map(String::from));
+        exec_git(&args)?;
+    }
+
+    let local_notes_ref = authorship_notes_ref();
+    let prompts_ref = interned_prompts_ref();
+    let bundled_notes_ref = heads
+        .iter()
+        .find(|head| **head == local_notes_ref)
+        .or_else(|| {
+            heads
+                .iter()
+                .find(|head| head.starts_with("refs/notes/") && !head.ends_with("-prompts"))
+        });
+    if let Some(bundled_notes_ref) = bundled_notes_ref {
+        let staging_ref = format!("refs/notes/{}-bundle", authorship_notes_ref_name());
+        let mut args = repo.global_args_for_exec();
+        args.extend(["fetch", "--quiet"].map(String::from));
+        args.push(bundle_arg.clone());
+        args.push(format!("+{}:{}", bundled_notes_ref, staging_ref));
+        exec_git(&args)?;
+
+        let mut args = repo.global_args_for_exec();
+        args.extend(["notes", &format!("--ref={}", staging_ref), "list"].map(String::from));
+        summary.notes = String::from_utf8(exec_git(&args)?.stdout)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+
+        let merged = if ref_exists(repo, &local_notes_ref) {
+            merge_notes_from_ref(repo, &staging_ref)
+        } else {
+            copy_ref(repo, &staging_ref, &local_notes_ref)
+        };
+        let mut args = repo.global_args_for_exec();
+        args.extend(["update-ref", "-d", &staging_ref].map(String::from));
+        exec_git(&args)?;
+        merged?;
+    }
+
+    if heads.contains(&prompts_ref.as_str()) {
+        let staging_ref = format!("{}-bundle", prompts_ref);
+        let mut args = repo.global_args_for_exec();
+        args.extend(["fetch", "--quiet"].map(String::from));
+        args.push(bundle_arg);
+        args.push(format!("+{}:{}", prompts_ref, staging_ref));
+        exec_git(&args)?;
+
+        let merged = if ref_exists(repo, &prompts_ref) {
+            merge_notes_into_ref(repo, &prompts_ref, &staging_ref)
+        } else {
+            copy_ref(repo, &staging_ref, &prompts_ref)
+        };
+        let mut args = repo.global_args_for_exec();
+        args.extend(["update-ref", "-d", &staging_ref].map(String::from));
+        exec_git(&args)?;
+        merged?;
+    }
+
+    if let Some(json) = json {
+        let sidecar: Sidecar = serde_json::from_slice(&std::fs::read(json)?)?;
+        if sidecar.version > SIDECAR_VERSION {
+            return Err(GitAiError::Generic(format!(
+                "sidecar format version {} is newer than this git-ai supports ({}); upgrade git-ai",
+                sidecar.version, SIDECAR_VERSION
+            )));
+        }
+        if !sidecar.prompts.is_empty() {
+            let db = InternalDatabase::global()?;
+            let mut db = db
+                .lock()
+                .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?;
+            let mut prompts = Vec::new();
+            for prompt in sidecar.prompts {
+                if db.get_prompt(&prompt.id)?.is_none() {
+                    prompts.push(prompt);
+                }
+            }
+            db.batch_upsert_prompts(&prompts)?;
+            summary.prompts = prompts.len();
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Every commit reachable from `revs` (anything `git rev-list` accepts)
+fn rev_list(repo: &Repository, revs: &[String]) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.extend(revs.iter().cloned());
+    args.push("--".to_string());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Paths are resolved against the invoking directory; git runs from the repo root
+/// Where to write `output`: the absolute path, or a staging file for an
+/// object storage URL
+fn local_path(output: &str) -> PathBuf {
+    if is_object_url(output) {
+        staging_path(output)
+    } else {
+        absolute(output)
+    }
+}
+
+fn absolute(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return path;
+    }
+    std::env::current_dir()
+        .map(|cwd| cwd.join(&path))
+        .unwrap_or(path)
+}
+
+fn print_archive_help() {
+    eprintln!("Usage: git-ai archive [<rev>...] --out <file.bundle> [--json <path>]");
+    eprintln!();
+    eprintln!("Write a git bundle of <rev> (default: HEAD) that includes the authorship notes.");
+    eprintln!("Revisions must name refs or ranges, as with `git bundle create`.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  -o, --out <path>   Bundle to write");
+    eprintln!("  --all              Bundle every ref");
+    eprintln!("  --json <path>      Also write the notes and their prompts as JSON");
+    eprintln!();
+    eprintln!("Either path may be an s3://bucket/key or gs://bucket/key URL to upload to.");
+}
+
+fn print_unbundle_help() {
+    eprintln!("Usage: git-ai unbundle <file.bundle> [--json <path>]");
+    eprintln!();
+    eprintln!("Fetch the branches, tags and authorship notes of a `git-ai archive` bundle");
+    eprintln!("into this repository. Notes are merged with any that already exist.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --json <path>      Import prompts from the sidecar written by --json");
+}
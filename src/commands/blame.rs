@@ -1,15 +1,19 @@
 use crate::auth::CredentialStore;
+use crate::authorship::attribution_cache::load_authorship_logs;
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::prompt_utils::enrich_prompt_messages;
 use crate::authorship::working_log::CheckpointKind;
+use crate::config::Config;
 use crate::error::GitAiError;
-use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::refs::{get_authorship_from_notes_ref, get_reference_as_authorship_log_v3};
 use crate::git::repository::Repository;
 use crate::git::repository::{exec_git, exec_git_stdin};
+use crate::observability::profile;
 #[cfg(windows)]
 use crate::utils::normalize_to_posix;
 use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
@@ -24,6 +28,10 @@ pub static OLDEST_AI_BLAME_DATE: LazyLock<DateTime<FixedOffset>> = LazyLock::new
         .unwrap()
 });
 
+/// Author shown for lines a human edited whose characters are still mostly AI-written
+/// (see `ai_assisted_threshold`)
+pub const AI_ASSISTED_AUTHOR: &str = "AI-assisted";
+
 #[derive(Debug, Clone)]
 pub struct BlameHunk {
     /// Line range [start, end] (inclusive) - current line numbers in the file
@@ -136,16 +144,29 @@ pub struct GitAiBlameOptions {
     // JSON output format
     pub json: bool,
 
+    // One machine-readable record per line: tab-separated (--ai-porcelain) or
+    // newline-delimited JSON (--json-lines)
+    pub ai_porcelain: bool,
+    pub json_lines: bool,
+
     // Mark lines from commits without authorship logs as "Unknown"
     pub mark_unknown: bool,
 
     // Show prompt hashes inline and dump prompts when piped
     pub show_prompt: bool,
 
+    // Show the human who ran the prompt as the author of AI lines, with the
+    // agent moved to a secondary column
+    pub ignore_ai: bool,
+
     // Split hunks when lines have different AI human authors
     // When true, a single git blame hunk may be split into multiple hunks
     // if different lines were authored by different humans working with AI
     pub split_hunks_by_ai_author: bool,
+
+    // Notes ref read for commits the authorship ref has no note for (never
+    // merged into it), e.g. a fork's notes fetched by `git-ai pr view`
+    pub fallback_notes_ref: Option<String>,
 }
 
 impl Default for GitAiBlameOptions {
@@ -188,9 +209,13 @@ impl Default for GitAiBlameOptions {
             no_output: false,
             ignore_whitespace: false,
             json: false,
+            ai_porcelain: false,
+            json_lines: false,
             mark_unknown: false,
             show_prompt: false,
+            ignore_ai: false,
             split_hunks_by_ai_author: true,
+            fallback_notes_ref: None,
         }
     }
 }
@@ -202,6 +227,7 @@ impl Repository {
         file_path: &str,
         options: &GitAiBlameOptions,
     ) -> Result<(HashMap<u32, String>, HashMap<String, PromptRecord>), GitAiError> {
+        let _profile_scope = profile::scope("blame");
         // Use repo root for file system operations
         let repo_root = self.workdir().map_err(|e| {
             GitAiError::Generic(format!("Repository has no working directory: {}", e))
@@ -271,7 +297,11 @@ impl Repository {
             }
             opts.use_prompt_hashes_as_names = true;
             opts
-        } else if options.show_prompt {
+        } else if options.show_prompt
+            || options.ignore_ai
+            || options.ai_porcelain
+            || options.json_lines
+        {
             let mut opts = options.clone();
             opts.use_prompt_hashes_as_names = true;
             opts
@@ -360,14 +390,32 @@ impl Repository {
 
         // Step 2: Overlay AI authorship information
         let (line_authors, prompt_records, authorship_logs, prompt_commits) =
-            overlay_ai_authorship(self, &all_blame_hunks, &relative_file_path, &options)?;
+            overlay_ai_authorship(
+                self,
+                &all_blame_hunks,
+                &relative_file_path,
+                Some(&lines),
+                &options,
+            )?;
 
         if options.no_output {
             return Ok((line_authors, prompt_records));
         }
 
         // Output based on format
-        if options.json {
+        if options.ai_porcelain || options.json_lines {
+            let records = line_records(&all_blame_hunks, &line_authors, &prompt_records);
+            for record in &records {
+                if options.json_lines {
+                    let json = serde_json::to_string(record).map_err(|e| {
+                        GitAiError::Generic(format!("Failed to serialize JSON output: {}", e))
+                    })?;
+                    println!("{}", json);
+                } else {
+                    println!("{}", record.to_porcelain());
+                }
+            }
+        } else if options.json {
             output_json_format(
                 self,
                 &line_authors,
@@ -715,7 +763,7 @@ impl Repository {
         options: &GitAiBlameOptions,
     ) -> Result<Vec<BlameHunk>, GitAiError> {
         // Cache authorship logs by commit SHA to avoid repeated lookups
-        let mut commit_authorship_cache: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+        let mut commit_authorship_cache = prefetch_authorship_logs(self, &hunks, options);
         // Cache for foreign prompts to avoid repeated grepping
         let mut foreign_prompts_cache: HashMap<String, Option<PromptRecord>> = HashMap::new();
 
@@ -727,7 +775,7 @@ impl Repository {
             {
                 cached.clone()
             } else {
-                let authorship = get_reference_as_authorship_log_v3(self, &hunk.commit_sha).ok();
+                let authorship = authorship_log_for_commit(self, &hunk.commit_sha, options);
                 commit_authorship_cache.insert(hunk.commit_sha.clone(), authorship.clone());
                 authorship
             };
@@ -808,11 +856,53 @@ impl Repository {
     }
 }
 
+/// Load the authorship logs for every commit in `hunks` in one batch, going
+/// through the persistent attribution cache. Commits are left out on failure so
+/// callers fall back to looking them up one at a time.
+fn prefetch_authorship_logs(
+    repo: &Repository,
+    hunks: &[BlameHunk],
+    options: &GitAiBlameOptions,
+) -> HashMap<String, Option<AuthorshipLog>> {
+    let mut commit_shas: Vec<String> = hunks.iter().map(|h| h.commit_sha.clone()).collect();
+    commit_shas.sort();
+    commit_shas.dedup();
+
+    match load_authorship_logs(repo, &commit_shas) {
+        Ok(mut logs) => commit_shas
+            .into_iter()
+            .map(|sha| {
+                let log = logs.remove(&sha).or_else(|| {
+                    let fallback = options.fallback_notes_ref.as_deref()?;
+                    get_authorship_from_notes_ref(repo, fallback, &sha)
+                });
+                (sha, log)
+            })
+            .collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// The authorship log for `commit_sha`, falling back to `fallback_notes_ref`
+fn authorship_log_for_commit(
+    repo: &Repository,
+    commit_sha: &str,
+    options: &GitAiBlameOptions,
+) -> Option<AuthorshipLog> {
+    get_reference_as_authorship_log_v3(repo, commit_sha)
+        .ok()
+        .or_else(|| {
+            let fallback = options.fallback_notes_ref.as_deref()?;
+            get_authorship_from_notes_ref(repo, fallback, commit_sha)
+        })
+}
+
 #[allow(clippy::type_complexity)]
-fn overlay_ai_authorship(
+pub(crate) fn overlay_ai_authorship(
     repo: &Repository,
     blame_hunks: &[BlameHunk],
     file_path: &str,
+    file_lines: Option<&[&str]>,
     options: &GitAiBlameOptions,
 ) -> Result<
     (
@@ -829,9 +919,14 @@ fn overlay_ai_authorship(
     let mut prompt_commits: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
 
     // Group hunks by commit SHA to avoid repeated lookups
-    let mut commit_authorship_cache: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+    let mut commit_authorship_cache = prefetch_authorship_logs(repo, blame_hunks, options);
     // Cache for foreign prompts to avoid repeated grepping
     let mut foreign_prompts_cache: HashMap<String, Option<PromptRecord>> = HashMap::new();
+    // Mixed lines are only called out in human-readable output
+    let show_ai_assisted = !options.use_prompt_hashes_as_names
+        && !options.return_human_authors_as_human
+        && !options.mark_unknown;
+    let ai_assisted_threshold = Config::get().ai_assisted_threshold();
 
     for hunk in blame_hunks {
         // Check if we've already looked up this commit's authorship
@@ -839,7 +934,7 @@ fn overlay_ai_authorship(
             cached.clone()
         } else {
             // Try to get authorship log for this commit
-            let authorship = get_reference_as_authorship_log_v3(repo, &hunk.commit_sha).ok();
+            let authorship = authorship_log_for_commit(repo, &hunk.commit_sha, options);
             commit_authorship_cache.insert(hunk.commit_sha.clone(), authorship.clone());
             authorship
         };
@@ -885,6 +980,15 @@ fn overlay_ai_authorship(
                             line_authors.insert(current_line_num, author.username.clone());
                         }
                     }
+                } else if show_ai_assisted
+                    && let Some(line_content) =
+                        file_lines.and_then(|lines| lines.get(current_line_num as usize - 1))
+                    && authorship_log
+                        .get_line_ai_share(file_path, orig_line_num, line_content)
+                        .is_some_and(|(_, share)| share >= ai_assisted_threshold)
+                {
+                    // A human edited the line, but most of it is still the agent's
+                    line_authors.insert(current_line_num, AI_ASSISTED_AUTHOR.to_string());
                 } else {
                     // Has authorship log but no attribution found = human-authored
                     if options.return_human_authors_as_human {
@@ -935,29 +1039,86 @@ fn overlay_ai_authorship(
 }
 
 /// Metadata about user's auth state and git identity
-#[derive(Debug, Serialize)]
-struct BlameMetadata {
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct BlameMetadata {
     is_logged_in: bool,
     current_user: Option<String>,
 }
 
 /// JSON output structure for blame
-#[derive(Debug, Serialize)]
-struct JsonBlameOutput {
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct JsonBlameOutput {
     lines: std::collections::BTreeMap<String, String>,
     prompts: HashMap<String, PromptRecordWithOtherFiles>,
     metadata: BlameMetadata,
 }
 
 /// Read model that patches PromptRecord with other_files and commits fields
-#[derive(Debug, Serialize)]
-struct PromptRecordWithOtherFiles {
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct PromptRecordWithOtherFiles {
     #[serde(flatten)]
     prompt_record: PromptRecord,
     other_files: Vec<String>,
     commits: Vec<String>,
 }
 
+/// One line of `--ai-porcelain` / `--json-lines` output
+#[derive(Debug, Serialize, PartialEq, JsonSchema)]
+pub(crate) struct BlameLineRecord {
+    line: u32,
+    commit: String,
+    /// Email of the commit author; for AI lines, the human who committed the agent's work
+    author_id: String,
+    prompt_id: Option<String>,
+    tool: Option<String>,
+    model: Option<String>,
+}
+
+impl BlameLineRecord {
+    /// `<line>\t<commit>\t<author_id>\t<prompt_id>\t<tool>\t<model>`, `-` for absent fields
+    fn to_porcelain(&self) -> String {
+        [
+            self.line.to_string(),
+            self.commit.clone(),
+            self.author_id.clone(),
+            self.prompt_id.clone().unwrap_or_else(|| "-".to_string()),
+            self.tool.clone().unwrap_or_else(|| "-".to_string()),
+            self.model.clone().unwrap_or_else(|| "-".to_string()),
+        ]
+        .join("\t")
+    }
+}
+
+/// Build one record per blamed line. Expects `line_authors` keyed by prompt hash for
+/// AI lines (`use_prompt_hashes_as_names`).
+fn line_records(
+    blame_hunks: &[BlameHunk],
+    line_authors: &HashMap<u32, String>,
+    prompt_records: &HashMap<String, PromptRecord>,
+) -> Vec<BlameLineRecord> {
+    let mut records: Vec<BlameLineRecord> = blame_hunks
+        .iter()
+        .flat_map(|hunk| {
+            (hunk.range.0..=hunk.range.1).map(move |line| {
+                let prompt = line_authors
+                    .get(&line)
+                    .and_then(|author| prompt_records.get_key_value(author));
+                BlameLineRecord {
+                    line,
+                    commit: hunk.commit_sha.clone(),
+                    author_id: hunk.author_email.clone(),
+                    prompt_id: prompt.map(|(id, _)| id.clone()),
+                    tool: prompt.map(|(_, record)| record.agent_id.tool.clone()),
+                    model: prompt.map(|(_, record)| record.agent_id.model.clone()),
+                }
+            })
+        })
+        .collect();
+    records.sort_by_key(|record| record.line);
+    records.dedup_by_key(|record| record.line);
+    records
+}
+
 /// Helper function to get all files touched by a prompt hash across authorship logs
 fn get_files_for_prompt_hash(
     prompt_hash: &str,
@@ -1050,7 +1211,7 @@ fn output_json_format(
 
     // Enrich prompts that have empty messages by falling back through storage layers
     let mut enriched_prompts = prompt_records.clone();
-    enrich_prompt_messages(&mut enriched_prompts, &referenced_prompt_ids);
+    enrich_prompt_messages(repo, &mut enriched_prompts, &referenced_prompt_ids);
 
     // Create read models with other_files and commits populated
     let filtered_prompts: HashMap<String, PromptRecordWithOtherFiles> = enriched_prompts
@@ -1341,24 +1502,41 @@ fn output_default_format(
 
     // Calculate the maximum author name width for proper padding
     let mut max_author_width = 0;
-    for (start_line, end_line) in line_ranges {
-        let h = repo.blame_hunks(file_path, *start_line, *end_line, &no_split_options)?;
-        for hunk in h {
-            let author = line_authors
-                .get(&hunk.range.0)
-                .unwrap_or(&hunk.original_author);
-            let author_display = if options.suppress_author {
-                "".to_string()
-            } else if options.show_prompt && prompt_records.contains_key(author) {
-                let prompt = &prompt_records[author];
-                let short_hash = &author[..7.min(author.len())];
-                format!("{} [{}]", prompt.agent_id.tool, short_hash)
-            } else if options.show_email {
-                format!("{} <{}>", author, &hunk.author_email)
-            } else {
-                author.to_string()
-            };
-            max_author_width = max_author_width.max(author_display.len());
+    // With --ignore-ai the author column holds humans and is followed by the agent column
+    let mut max_human_width = 0;
+    if options.ignore_ai {
+        let mut max_agent_width = 0;
+        for (line_num, hunk) in &line_to_hunk {
+            let author = line_authors.get(line_num).unwrap_or(&hunk.original_author);
+            let (human, agent) = ignore_ai_columns(author, hunk, prompt_records, options);
+            max_human_width = max_human_width.max(human.len());
+            max_agent_width = max_agent_width.max(agent.len());
+        }
+        max_author_width = if max_agent_width > 0 {
+            max_human_width + 1 + max_agent_width
+        } else {
+            max_human_width
+        };
+    } else {
+        for (start_line, end_line) in line_ranges {
+            let h = repo.blame_hunks(file_path, *start_line, *end_line, &no_split_options)?;
+            for hunk in h {
+                let author = line_authors
+                    .get(&hunk.range.0)
+                    .unwrap_or(&hunk.original_author);
+                let author_display = if options.suppress_author {
+                    "".to_string()
+                } else if options.show_prompt && prompt_records.contains_key(author) {
+                    let prompt = &prompt_records[author];
+                    let short_hash = &author[..7.min(author.len())];
+                    format!("{} [{}]", prompt.agent_id.tool, short_hash)
+                } else if options.show_email {
+                    format!("{} <{}>", author, &hunk.author_email)
+                } else {
+                    author.to_string()
+                };
+                max_author_width = max_author_width.max(author_display.len());
+            }
         }
     }
 
@@ -1407,6 +1585,13 @@ fn output_default_format(
                 // Handle different output formats based on flags
                 let author_display = if options.suppress_author {
                     "".to_string()
+                } else if options.ignore_ai {
+                    let (human, agent) = ignore_ai_columns(author, hunk, prompt_records, options);
+                    if agent.is_empty() {
+                        human
+                    } else {
+                        format!("{:<width$} {}", human, agent, width = max_human_width)
+                    }
                 } else if options.show_prompt && prompt_records.contains_key(author) {
                     let prompt = &prompt_records[author];
                     let short_hash = &author[..7.min(author.len())];
@@ -1511,7 +1696,7 @@ fn output_default_format(
 
         if !referenced_ids.is_empty() {
             let mut enriched_prompts = prompt_records.clone();
-            enrich_prompt_messages(&mut enriched_prompts, &referenced_ids);
+            enrich_prompt_messages(repo, &mut enriched_prompts, &referenced_ids);
 
             output.push_str("---\n");
 
@@ -1571,6 +1756,48 @@ fn output_default_format(
     Ok(())
 }
 
+/// Author and agent columns for `--ignore-ai`. AI lines are credited to the human who
+/// ran the prompt (the commit author if the prompt didn't record one), with the agent
+/// in the second column; other lines leave it empty.
+fn ignore_ai_columns(
+    author: &str,
+    hunk: &BlameHunk,
+    prompt_records: &HashMap<String, PromptRecord>,
+    options: &GitAiBlameOptions,
+) -> (String, String) {
+    let Some(prompt) = prompt_records.get(author) else {
+        let human = if options.show_email {
+            format!("{} <{}>", author, hunk.author_email)
+        } else {
+            author.to_string()
+        };
+        return (human, String::new());
+    };
+
+    let (name, email) = match prompt.human_author.as_deref() {
+        Some(human_author) => match human_author.split_once(" <") {
+            Some((name, email)) => (name.to_string(), email.trim_end_matches('>').to_string()),
+            None => (human_author.to_string(), hunk.author_email.clone()),
+        },
+        None => (hunk.original_author.clone(), hunk.author_email.clone()),
+    };
+    let human = if options.show_email {
+        format!("{} <{}>", name, email)
+    } else {
+        name
+    };
+    let agent = if options.show_prompt {
+        format!(
+            "{} [{}]",
+            prompt.agent_id.tool,
+            &author[..7.min(author.len())]
+        )
+    } else {
+        prompt.agent_id.tool.clone()
+    };
+    (human, agent)
+}
+
 fn format_blame_date(author_time: i64, author_tz: &str, options: &GitAiBlameOptions) -> String {
     let dt = DateTime::from_timestamp(author_time, 0)
         .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
@@ -1852,6 +2079,14 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
                 options.json = true;
                 i += 1;
             }
+            "--json-lines" => {
+                options.json_lines = true;
+                i += 1;
+            }
+            "--ai-porcelain" => {
+                options.ai_porcelain = true;
+                i += 1;
+            }
 
             // Mark unknown authorship
             "--mark-unknown" => {
@@ -1865,6 +2100,12 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
                 i += 1;
             }
 
+            // Attribute AI lines to the human who ran the prompt
+            "--ignore-ai" => {
+                options.ignore_ai = true;
+                i += 1;
+            }
+
             // File path (non-option argument)
             arg if !arg.starts_with('-') => {
                 if file_path.is_none() {
@@ -1887,6 +2128,28 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
     let file_path =
         file_path.ok_or_else(|| GitAiError::Generic("No file path specified".to_string()))?;
 
+    // These formats report git's own author fields (and JSON the prompts behind each
+    // line), so there is no author column for --ignore-ai to swap
+    if options.ignore_ai {
+        let conflicting = if options.line_porcelain {
+            Some("--line-porcelain")
+        } else if options.porcelain {
+            Some("--porcelain")
+        } else if options.incremental {
+            Some("--incremental")
+        } else if options.json {
+            Some("--json")
+        } else {
+            None
+        };
+        if let Some(flag) = conflicting {
+            return Err(GitAiError::Generic(format!(
+                "--ignore-ai cannot be combined with {}",
+                flag
+            )));
+        }
+    }
+
     Ok((file_path, options))
 }
 
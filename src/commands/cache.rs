@@ -0,0 +1,61 @@
+//! `git-ai cache clear`: empty the repository's note, ownership and blame caches
+//! under `.git/ai/cache`, e.g. after an upgrade changes how results are computed.
+
+use crate::authorship::attribution_cache::AttributionCache;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+
+pub fn handle_cache(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("clear") => {}
+        Some("-h") | Some("--help") => {
+            print_cache_help();
+            std::process::exit(0);
+        }
+        Some(other) => {
+            eprintln!("Unknown cache subcommand: {}", other);
+            print_cache_help();
+            std::process::exit(1);
+        }
+        None => {
+            print_cache_help();
+            std::process::exit(1);
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match clear_caches(&repo) {
+        Ok(removed) => println!("Cleared {} cache entries", removed),
+        Err(e) => {
+            eprintln!("Clearing the cache failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn clear_caches(repo: &Repository) -> Result<usize, GitAiError> {
+    let mut removed = 0;
+    for cache in [
+        AttributionCache::for_repo(repo),
+        AttributionCache::for_ownership(repo),
+        AttributionCache::for_blame(repo),
+    ] {
+        removed += cache.clear()?;
+    }
+    Ok(removed)
+}
+
+fn print_cache_help() {
+    eprintln!("Usage: git-ai cache clear");
+    eprintln!();
+    eprintln!("Remove cached authorship notes, ownership summaries and blame results");
+    eprintln!("for this repository. They are rebuilt on demand.");
+}
@@ -3,12 +3,14 @@ use crate::authorship::attribution_tracker::{
 };
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::context_bundle::{CONTEXT_BUNDLE_METADATA_KEY, ContextBundle};
 use crate::authorship::ignore::{
     IgnoreMatcher, build_ignore_matcher, effective_ignore_patterns, should_ignore_file_with_matcher,
 };
 use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
-use crate::authorship::working_log::CheckpointKind;
-use crate::authorship::working_log::{Checkpoint, WorkingLogEntry};
+use crate::authorship::stale_working_log::migrate_stale_working_log;
+use crate::authorship::working_log::{AiClassification, CheckpointKind};
+use crate::authorship::working_log::{Checkpoint, WorkingLogEntry, monotonic_attribution_ts};
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
 use crate::config::Config;
@@ -16,6 +18,8 @@ use crate::error::GitAiError;
 use crate::git::repo_storage::{PersistedWorkingLog, RepoStorage};
 use crate::git::repository::Repository;
 use crate::git::status::{EntryKind, StatusCode};
+use crate::observability::budgets::record_phase;
+use crate::observability::profile;
 use crate::utils::{debug_log, normalize_to_posix};
 use futures::stream::{self, StreamExt};
 use sha2::{Digest, Sha256};
@@ -115,6 +119,7 @@ fn should_emit_agent_usage(_agent_id: &AgentId) -> bool {
 pub fn run(
     repo: &Repository,
     author: &str,
+    co_authors: &[String],
     kind: CheckpointKind,
     show_working_log: bool,
     reset: bool,
@@ -122,6 +127,7 @@ pub fn run(
     agent_run_result: Option<AgentRunResult>,
     is_pre_commit: bool,
 ) -> Result<(usize, usize, usize), GitAiError> {
+    let _profile_scope = profile::scope("checkpoint");
     let checkpoint_start = Instant::now();
     debug_log("[BENCHMARK] Starting checkpoint run");
 
@@ -150,6 +156,20 @@ pub fn run(
     // Initialize the new storage system
     let storage_start = Instant::now();
     let repo_storage = RepoStorage::for_repo_path(repo.path(), &repo.workdir()?);
+    if base_commit != "initial" {
+        // HEAD may have moved without our hooks since the last checkpoint
+        match migrate_stale_working_log(repo, &base_commit) {
+            Ok(Some(stale_base)) => debug_log(&format!(
+                "Migrated stale working log from {} to {}",
+                stale_base, base_commit
+            )),
+            Ok(None) => {}
+            Err(e) => debug_log(&format!("Failed to migrate stale working log: {}", e)),
+        }
+    }
+    if let Err(e) = repo_storage.set_last_checkpoint_base(&base_commit) {
+        debug_log(&format!("Failed to record checkpoint base: {}", e));
+    }
     let mut working_log = repo_storage.working_log_for_base_commit(&base_commit);
     debug_log(&format!(
         "[BENCHMARK] Storage initialization took {:?}",
@@ -187,7 +207,7 @@ pub fn run(
     }
 
     // Get the current timestamp in milliseconds since the Unix epoch
-    let ts = SystemTime::now()
+    let now_ms = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis();
@@ -262,6 +282,29 @@ pub fn run(
         pathspec_start.elapsed()
     ));
 
+    // Generated files an agent edited are recorded by path only. Without paths
+    // from the agent, every changed file under a rule counts, as it does for
+    // attribution.
+    let generated_rules = &Config::get().tracking().generated;
+    let generated_files: Vec<String> =
+        if kind == CheckpointKind::Human || generated_rules.is_empty() {
+            Vec::new()
+        } else {
+            let generated_matcher = build_ignore_matcher(generated_rules);
+            match pathspec_filter {
+                Some(paths) => paths
+                    .iter()
+                    .filter(|path| generated_matcher.is_ignored(path))
+                    .cloned()
+                    .collect(),
+                None => repo
+                    .changed_files_including_untracked()?
+                    .into_iter()
+                    .filter(|path| generated_matcher.is_ignored(path))
+                    .collect(),
+            }
+        };
+
     let files_start = Instant::now();
     let files = get_all_tracked_files(
         repo,
@@ -291,6 +334,10 @@ pub fn run(
         read_checkpoints_start.elapsed()
     ));
 
+    // Never go backwards relative to attributions already in the working log, so a
+    // skewed clock can't make this checkpoint lose to an older one.
+    let ts = monotonic_attribution_ts(now_ms, &checkpoints);
+
     if show_working_log {
         if checkpoints.is_empty() {
             eprintln!("No working log entries found.");
@@ -388,7 +435,7 @@ pub fn run(
     ));
 
     // Skip adding checkpoint if there are no changes
-    if !entries.is_empty() {
+    if !entries.is_empty() || !generated_files.is_empty() {
         let checkpoint_create_start = Instant::now();
         let mut checkpoint = Checkpoint::new(
             kind,
@@ -396,6 +443,8 @@ pub fn run(
             author.to_string(),
             entries.clone(),
         );
+        checkpoint.co_authors = co_authors.to_vec();
+        checkpoint.generated_files = generated_files;
 
         // Aggregate line stats from in-memory stats (computed during entry creation)
         checkpoint.line_stats = compute_line_stats(&file_stats)?;
@@ -407,12 +456,37 @@ pub fn run(
             checkpoint.transcript = Some(agent_run.transcript.clone().unwrap_or_default());
             checkpoint.agent_id = Some(agent_run.agent_id.clone());
             checkpoint.agent_metadata = agent_run.agent_metadata.clone();
+            // Agents that also do inline completions (or vice versa) can say which this was
+            if let Some(classification) = agent_run
+                .agent_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("classification"))
+                .and_then(|name| AiClassification::from_name(name))
+            {
+                checkpoint.classification = Some(classification);
+            }
         }
         debug_log(&format!(
             "[BENCHMARK] Checkpoint creation took {:?}",
             checkpoint_create_start.elapsed()
         ));
 
+        if kind != CheckpointKind::Human && Config::get().context_capture().enabled {
+            let capture_start = Instant::now();
+            if let Err(e) =
+                capture_context_bundle(repo, &working_log, &checkpoints, &mut checkpoint)
+            {
+                debug_log(&format!(
+                    "[Warning] Failed to capture context bundle: {}",
+                    e
+                ));
+            }
+            debug_log(&format!(
+                "[BENCHMARK] Context bundle capture took {:?}",
+                capture_start.elapsed()
+            ));
+        }
+
         // Upsert prompt to database (non-fatal if it fails)
         if kind != CheckpointKind::Human
             && checkpoint.agent_id.is_some()
@@ -438,7 +512,11 @@ pub fn run(
 
         // Append checkpoint to the working log
         let append_start = Instant::now();
-        working_log.append_checkpoint(&checkpoint)?;
+        {
+            let _scope = profile::scope("working_log_write");
+            working_log.append_checkpoint(&checkpoint)?;
+        }
+        record_phase("working_log_write", append_start.elapsed());
         debug_log(&format!(
             "[BENCHMARK] Appending checkpoint to working log took {:?}",
             append_start.elapsed()
@@ -468,6 +546,10 @@ pub fn run(
                 .lines_deleted(file_stat.deletions)
                 .lines_added_sloc(file_stat.additions_sloc)
                 .lines_deleted_sloc(file_stat.deletions_sloc);
+            let values = match checkpoint.classification() {
+                Some(classification) => values.classification(classification.as_str()),
+                None => values.classification_null(),
+            };
 
             // Add checkpoint author to attrs for this event
             let file_attrs = attrs.clone().author(&checkpoint.author);
@@ -552,7 +634,11 @@ fn get_status_of_files(
     };
 
     let status_start = Instant::now();
-    let statuses = repo.status(edited_filepaths_option, skip_untracked)?;
+    let statuses = {
+        let _scope = profile::scope("status_scan");
+        repo.status(edited_filepaths_option, skip_untracked)?
+    };
+    record_phase("status_scan", status_start.elapsed());
     debug_log(&format!(
         "[BENCHMARK]   git status call took {:?}",
         status_start.elapsed()
@@ -844,6 +930,69 @@ fn get_previous_content_from_head(
     }
 }
 
+/// Queue a context bundle of this checkpoint's edits for upload and link it from the
+/// checkpoint's agent metadata. Size caps and exclusions are applied in
+/// [`ContextBundle::build`], before anything is queued.
+fn capture_context_bundle(
+    repo: &Repository,
+    working_log: &PersistedWorkingLog,
+    previous_checkpoints: &[Checkpoint],
+    checkpoint: &mut Checkpoint,
+) -> Result<(), GitAiError> {
+    use crate::authorship::internal_db::InternalDatabase;
+
+    let head_tree_id = repo
+        .head()
+        .ok()
+        .and_then(|h| h.target().ok())
+        .and_then(|oid| repo.find_commit(oid).ok())
+        .and_then(|c| c.tree().ok())
+        .map(|t| t.id().to_string());
+
+    let contents: Vec<(String, String, String)> = checkpoint
+        .entries
+        .iter()
+        .map(|entry| {
+            let previous = previous_checkpoints
+                .iter()
+                .rev()
+                .find_map(|c| c.entries.iter().find(|e| e.file == entry.file))
+                .map(|e| {
+                    working_log
+                        .get_file_version(&e.blob_sha)
+                        .unwrap_or_default()
+                })
+                .unwrap_or_else(|| {
+                    get_previous_content_from_head(repo, &entry.file, &head_tree_id)
+                });
+            let current = working_log
+                .get_file_version(&entry.blob_sha)
+                .unwrap_or_default();
+            (entry.file.clone(), previous, current)
+        })
+        .collect();
+
+    let Some(bundle) = ContextBundle::build(
+        contents
+            .iter()
+            .map(|(path, previous, current)| (path.as_str(), previous.as_str(), current.as_str())),
+        Config::get().context_capture(),
+    ) else {
+        return Ok(());
+    };
+
+    let metadata = HashMap::from([("kind".to_string(), "context_bundle".to_string())]);
+    let hash = InternalDatabase::global()?
+        .lock()
+        .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?
+        .enqueue_cas_object(&serde_json::to_value(&bundle)?, Some(&metadata))?;
+    checkpoint
+        .agent_metadata
+        .get_or_insert_with(HashMap::new)
+        .insert(CONTEXT_BUNDLE_METADATA_KEY.to_string(), hash);
+    Ok(())
+}
+
 fn working_log_entry_has_non_human_attribution(entry: &WorkingLogEntry) -> bool {
     entry
         .line_attributions
@@ -1007,6 +1156,7 @@ fn get_checkpoint_entry_for_file(
             Some((line_authors, prompt_records))
         };
 
+        record_phase("blame", blame_start.elapsed());
         debug_log(&format!(
             "[BENCHMARK] Blame for {} took {:?}",
             file_path,
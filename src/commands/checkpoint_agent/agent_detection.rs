@@ -0,0 +1,379 @@
+//! Best-effort guess at the agent behind a checkpoint that arrived without
+//! one, for machines where git hooks are installed but the agent's own hooks
+//! aren't.
+//!
+//! Evidence, strongest first: environment variables agents set for the
+//! commands they run, an agent among this process's ancestors, and an agent
+//! session file for this repository written in the last few minutes. Inferred
+//! checkpoints carry [`AGENT_INFERRED_METADATA_KEY`] with the evidence, so they
+//! can be told apart from checkpoints an agent reported itself.
+//!
+//! Off unless the `checkpoint_infer_agent` feature flag is set.
+
+use crate::authorship::working_log::{AGENT_INFERRED_METADATA_KEY, AgentId, CheckpointKind};
+use crate::commands::checkpoint_agent::agent_presets::{AgentRunResult, ClaudePreset};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Variables agents export to the shell commands they run
+const ENV_MARKERS: &[(&str, &str)] = &[
+    ("CLAUDECODE", "claude"),
+    ("GEMINI_CLI", "gemini"),
+    ("CODEX_SANDBOX", "codex"),
+    ("CODEX_SANDBOX_NETWORK_DISABLED", "codex"),
+    ("CURSOR_AGENT", "cursor"),
+];
+
+/// Executable (or script) names of agent CLIs
+const PROCESS_NAMES: &[(&str, &str)] = &[
+    ("claude", "claude"),
+    ("codex", "codex"),
+    ("gemini", "gemini"),
+    ("cursor-agent", "cursor"),
+    ("aider", "aider"),
+    ("opencode", "opencode"),
+    ("droid", "droid"),
+];
+
+/// How recently a session file must have been written to count
+const SESSION_FRESHNESS: Duration = Duration::from_secs(10 * 60);
+
+/// How far up the process tree to look
+const MAX_ANCESTORS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferredAgent {
+    pub tool: &'static str,
+    /// Stable for one agent session where the evidence allows it, so repeated
+    /// inferred checkpoints group into one prompt
+    pub session_id: String,
+    /// Human-readable reason, recorded on the checkpoint
+    pub evidence: String,
+    pub transcript_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Process name as the OS reports it; agents that set their process title
+    /// show up here even when argv is `node cli.js`
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+/// Infer the agent editing `workdir` from this process's surroundings
+pub fn infer_agent(workdir: &Path) -> Option<InferredAgent> {
+    let home = dirs::home_dir();
+    infer_agent_from(
+        |key| std::env::var(key).ok(),
+        &ancestor_processes(),
+        workdir,
+        home.as_deref(),
+        SystemTime::now(),
+    )
+}
+
+pub fn infer_agent_from(
+    env: impl Fn(&str) -> Option<String>,
+    ancestors: &[ProcessInfo],
+    workdir: &Path,
+    home: Option<&Path>,
+    now: SystemTime,
+) -> Option<InferredAgent> {
+    let from_process = ancestors
+        .iter()
+        .find_map(|process| agent_for_process(process).map(|tool| (tool, process)));
+    let session = |tool: &str| match tool {
+        "claude" => home.and_then(|home| fresh_claude_session(home, workdir, now)),
+        "aider" => fresh_file(&workdir.join(".aider.chat.history.md"), now),
+        _ => None,
+    };
+    let with_session = |tool: &'static str, evidence: String| {
+        let transcript_path = session(tool);
+        let session_id = match (&transcript_path, from_process) {
+            (Some(path), _) if tool == "claude" => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            (_, Some((process_tool, process))) if process_tool == tool => {
+                format!("pid-{}", process.pid)
+            }
+            _ => "unknown".to_string(),
+        };
+        InferredAgent {
+            tool,
+            session_id,
+            evidence,
+            transcript_path,
+        }
+    };
+
+    if let Some((key, tool)) = ENV_MARKERS
+        .iter()
+        .find(|(key, _)| env(key).is_some_and(|value| !value.is_empty()))
+    {
+        return Some(with_session(tool, format!("environment variable {}", key)));
+    }
+    if let Some((tool, process)) = from_process {
+        return Some(with_session(
+            tool,
+            format!("parent process {} (pid {})", tool, process.pid),
+        ));
+    }
+    for tool in ["claude", "aider"] {
+        let inferred = with_session(tool, String::new());
+        if let Some(path) = &inferred.transcript_path {
+            return Some(InferredAgent {
+                evidence: format!("session file {}", path.display()),
+                ..inferred
+            });
+        }
+    }
+    None
+}
+
+fn agent_for_process(process: &ProcessInfo) -> Option<&'static str> {
+    // Agents written in node or python show up as `node /path/to/cli`
+    std::iter::once(&process.name)
+        .chain(process.args.iter().take(2))
+        .find_map(|arg| {
+            let name = Path::new(arg).file_stem()?.to_str()?;
+            PROCESS_NAMES
+                .iter()
+                .find(|(process_name, _)| *process_name == name)
+                .map(|(_, tool)| *tool)
+        })
+}
+
+/// Newest Claude Code session for `workdir`. Claude keeps one JSONL per session
+/// under `~/.claude/projects/<workdir with every non-alphanumeric as '-'>`.
+fn fresh_claude_session(home: &Path, workdir: &Path, now: SystemTime) -> Option<PathBuf> {
+    let slug: String = workdir
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let dir = home.join(".claude").join("projects").join(slug);
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| Some((modified_within(&path, now)?, path)))
+        .min_by_key(|(age, _)| *age)
+        .map(|(_, path)| path)
+}
+
+fn fresh_file(path: &Path, now: SystemTime) -> Option<PathBuf> {
+    modified_within(path, now).map(|_| path.to_path_buf())
+}
+
+/// Age of `path` when it was written within [`SESSION_FRESHNESS`] of `now`
+fn modified_within(path: &Path, now: SystemTime) -> Option<Duration> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let age = now.duration_since(modified).unwrap_or_default();
+    (age <= SESSION_FRESHNESS).then_some(age)
+}
+
+/// This process's ancestors, parent first
+#[cfg(target_os = "linux")]
+fn ancestor_processes() -> Vec<ProcessInfo> {
+    let mut ancestors = Vec::new();
+    let mut pid = std::os::unix::process::parent_id();
+    while pid > 1 && ancestors.len() < MAX_ANCESTORS {
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+            break;
+        };
+        // `pid (comm) state ppid ...`; comm may itself contain spaces and parens
+        let Some((comm_part, rest)) = stat.rsplit_once(')') else {
+            break;
+        };
+        let comm = comm_part
+            .split_once('(')
+            .map(|(_, comm)| comm.to_string())
+            .unwrap_or_default();
+        let args: Vec<String> = std::fs::read(format!("/proc/{}/cmdline", pid))
+            .map(|cmdline| {
+                cmdline
+                    .split(|byte| *byte == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        ancestors.push(ProcessInfo {
+            pid,
+            name: comm,
+            args,
+        });
+        pid = match rest
+            .split_whitespace()
+            .nth(1)
+            .and_then(|ppid| ppid.parse().ok())
+        {
+            Some(ppid) => ppid,
+            None => break,
+        };
+    }
+    ancestors
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn ancestor_processes() -> Vec<ProcessInfo> {
+    let mut ancestors = Vec::new();
+    let mut pid = std::os::unix::process::parent_id();
+    while pid > 1 && ancestors.len() < MAX_ANCESTORS {
+        let Ok(output) = std::process::Command::new("ps")
+            .args(["-o", "ppid=", "-o", "command=", "-p", &pid.to_string()])
+            .output()
+        else {
+            break;
+        };
+        let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let Some((ppid, command)) = line.split_once(char::is_whitespace) else {
+            break;
+        };
+        let args: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+        ancestors.push(ProcessInfo {
+            pid,
+            name: args.first().cloned().unwrap_or_default(),
+            args,
+        });
+        pid = match ppid.trim().parse() {
+            Ok(ppid) => ppid,
+            Err(_) => break,
+        };
+    }
+    ancestors
+}
+
+#[cfg(not(unix))]
+fn ancestor_processes() -> Vec<ProcessInfo> {
+    Vec::new()
+}
+
+/// An AI checkpoint for `agent` covering `edited_filepaths`
+pub fn inferred_run_result(
+    agent: InferredAgent,
+    repo_working_dir: String,
+    edited_filepaths: Vec<String>,
+) -> AgentRunResult {
+    let mut metadata = HashMap::from([(
+        AGENT_INFERRED_METADATA_KEY.to_string(),
+        agent.evidence.clone(),
+    )]);
+    let mut transcript = None;
+    let mut model = "unknown".to_string();
+    if let Some(path) = &agent.transcript_path {
+        metadata.insert(
+            "transcript_path".to_string(),
+            path.to_string_lossy().to_string(),
+        );
+        if agent.tool == "claude"
+            && let Ok((parsed, parsed_model)) =
+                ClaudePreset::transcript_and_model_from_claude_code_jsonl(&path.to_string_lossy())
+        {
+            transcript = Some(parsed);
+            model = parsed_model.unwrap_or(model);
+        }
+    }
+
+    AgentRunResult {
+        agent_id: AgentId {
+            tool: agent.tool.to_string(),
+            id: format!("inferred-{}", agent.session_id),
+            model,
+        },
+        agent_metadata: Some(metadata),
+        checkpoint_kind: CheckpointKind::AiAgent,
+        transcript,
+        repo_working_dir: Some(repo_working_dir),
+        edited_filepaths: Some(edited_filepaths),
+        will_edit_filepaths: None,
+        dirty_files: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, args: &[&str]) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: args[0].to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn environment_markers_win_over_ancestors() {
+        let workdir = Path::new("/work/repo");
+        let ancestors = [
+            process(40, &["/bin/bash"]),
+            process(30, &["node", "/usr/lib/node_modules/.bin/claude"]),
+        ];
+        let inferred = infer_agent_from(
+            |key| (key == "GEMINI_CLI").then(|| "1".to_string()),
+            &ancestors,
+            workdir,
+            None,
+            SystemTime::now(),
+        )
+        .unwrap();
+        assert_eq!(inferred.tool, "gemini");
+        assert_eq!(inferred.evidence, "environment variable GEMINI_CLI");
+        assert_eq!(inferred.session_id, "unknown");
+
+        let inferred =
+            infer_agent_from(|_| None, &ancestors, workdir, None, SystemTime::now()).unwrap();
+        assert_eq!(inferred.tool, "claude");
+        assert_eq!(inferred.session_id, "pid-30");
+        assert_eq!(inferred.evidence, "parent process claude (pid 30)");
+
+        assert_eq!(
+            infer_agent_from(
+                |_| None,
+                &[process(40, &["/bin/zsh"]), process(2, &["sshd"])],
+                workdir,
+                None,
+                SystemTime::now()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn only_fresh_claude_sessions_for_this_workdir_count() {
+        let home = tempfile::tempdir().unwrap();
+        let workdir = Path::new("/work/my.repo");
+        let sessions = home.path().join(".claude/projects/-work-my-repo");
+        std::fs::create_dir_all(&sessions).unwrap();
+        std::fs::write(sessions.join("abc-123.jsonl"), "").unwrap();
+
+        let now = SystemTime::now();
+        let inferred = infer_agent_from(|_| None, &[], workdir, Some(home.path()), now).unwrap();
+        assert_eq!(inferred.tool, "claude");
+        assert_eq!(inferred.session_id, "abc-123");
+        assert_eq!(
+            inferred.transcript_path,
+            Some(sessions.join("abc-123.jsonl"))
+        );
+
+        let later = now + SESSION_FRESHNESS + Duration::from_secs(60);
+        assert_eq!(
+            infer_agent_from(|_| None, &[], workdir, Some(home.path()), later),
+            None
+        );
+        assert_eq!(
+            infer_agent_from(
+                |_| None,
+                &[],
+                Path::new("/work/other"),
+                Some(home.path()),
+                now
+            ),
+            None
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     authorship::{
         transcript::{AiTranscript, Message},
-        working_log::{AgentId, CheckpointKind},
+        working_log::{AGENT_VERSION_METADATA_KEY, AgentId, CheckpointKind},
     },
     error::GitAiError,
     observability::log_error,
@@ -1318,14 +1318,17 @@ impl AgentCheckpointPreset for CursorPreset {
         // Store cursor database path in metadata for refetching during post-commit.
         // This is only needed when GIT_AI_CURSOR_GLOBAL_DB_PATH env var is set (i.e., in tests),
         // because the env var isn't passed to git hook subprocesses.
-        let agent_metadata = if std::env::var("GIT_AI_CURSOR_GLOBAL_DB_PATH").is_ok() {
-            Some(HashMap::from([(
+        let mut agent_metadata = HashMap::new();
+        if std::env::var("GIT_AI_CURSOR_GLOBAL_DB_PATH").is_ok() {
+            agent_metadata.insert(
                 "__test_cursor_db_path".to_string(),
                 global_db.to_string_lossy().to_string(),
-            )]))
-        } else {
-            None
-        };
+            );
+        }
+        if let Some(version) = hook_data.get("cursor_version").and_then(|v| v.as_str()) {
+            agent_metadata.insert(AGENT_VERSION_METADATA_KEY.to_string(), version.to_string());
+        }
+        let agent_metadata = (!agent_metadata.is_empty()).then_some(agent_metadata);
 
         Ok(AgentRunResult {
             agent_id,
@@ -3300,6 +3303,13 @@ impl GithubCopilotPreset {
     }
 }
 
+/// Inline completions and other editor-driven AI edits.
+///
+/// Editors report an edit either as a `before_edit` / `after_edit` pair, or as a
+/// single `completion_accepted` event carrying the document text before and after
+/// the completion was accepted. The single event is what the VS Code and JetBrains
+/// plugins send for inline completions: it records the human baseline and the
+/// assisted edit in one call instead of spawning git-ai twice per keystroke burst.
 pub struct AiTabPreset;
 
 // Droid (Factory) to checkpoint preset
@@ -3315,6 +3325,63 @@ struct AiTabHookInput {
     edited_filepaths: Option<Vec<String>>,
     completion_id: Option<String>,
     dirty_files: Option<HashMap<String, String>>,
+    /// `completion_accepted`: the file the completion was accepted in
+    #[serde(default)]
+    file_path: Option<String>,
+    /// `completion_accepted`: document text before the completion was accepted
+    #[serde(default)]
+    content_before: Option<String>,
+    /// `completion_accepted`: document text after the completion was accepted
+    #[serde(default)]
+    content_after: Option<String>,
+}
+
+impl AiTabPreset {
+    /// For `completion_accepted` events that include `content_before`, the human
+    /// checkpoint to record before the completion itself. `None` for other events.
+    pub fn completion_baseline(hook_input: &str) -> Option<AgentRunResult> {
+        let input: AiTabHookInput = serde_json::from_str(hook_input).ok()?;
+        if input.hook_event_name != "completion_accepted" {
+            return None;
+        }
+        let file_path = input.file_path.filter(|p| !p.trim().is_empty())?;
+        let content_before = input.content_before?;
+        let repo_working_dir = completion_working_dir(&file_path, input.repo_working_dir);
+
+        Some(AgentRunResult {
+            agent_id: AgentId {
+                tool: input.tool.trim().to_string(),
+                id: format!(
+                    "ai_tab-{}",
+                    input
+                        .completion_id
+                        .unwrap_or_else(|| Utc::now().timestamp_millis().to_string())
+                ),
+                model: input.model.trim().to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::Human,
+            transcript: None,
+            repo_working_dir,
+            edited_filepaths: None,
+            will_edit_filepaths: Some(vec![file_path.clone()]),
+            dirty_files: Some(HashMap::from([(file_path, content_before)])),
+        })
+    }
+}
+
+/// Completions touch a single file, so its directory pins down the repository
+/// even when the editor workspace spans several.
+fn completion_working_dir(file_path: &str, repo_working_dir: Option<String>) -> Option<String> {
+    let path = std::path::Path::new(file_path);
+    if path.is_absolute()
+        && let Some(parent) = path.parent()
+    {
+        return Some(parent.to_string_lossy().to_string());
+    }
+    repo_working_dir
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
 }
 
 impl AgentCheckpointPreset for AiTabPreset {
@@ -3335,11 +3402,17 @@ impl AgentCheckpointPreset for AiTabPreset {
             edited_filepaths,
             completion_id,
             dirty_files,
+            file_path,
+            content_before: _,
+            content_after,
         } = hook_input;
 
-        if hook_event_name != "before_edit" && hook_event_name != "after_edit" {
+        if !matches!(
+            hook_event_name.as_str(),
+            "before_edit" | "after_edit" | "completion_accepted"
+        ) {
             return Err(GitAiError::PresetError(format!(
-                "Unsupported hook_event_name '{}' for ai_tab preset (expected 'before_edit' or 'after_edit')",
+                "Unsupported hook_event_name '{}' for ai_tab preset (expected 'before_edit', 'after_edit' or 'completion_accepted')",
                 hook_event_name
             )));
         }
@@ -3371,6 +3444,29 @@ impl AgentCheckpointPreset for AiTabPreset {
             model,
         };
 
+        if hook_event_name == "completion_accepted" {
+            let file_path = file_path.filter(|p| !p.trim().is_empty()).ok_or_else(|| {
+                GitAiError::PresetError(
+                    "file_path is required for completion_accepted events".to_string(),
+                )
+            })?;
+            let content_after = content_after.ok_or_else(|| {
+                GitAiError::PresetError(
+                    "content_after is required for completion_accepted events".to_string(),
+                )
+            })?;
+            return Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::AiTab,
+                transcript: None,
+                repo_working_dir: completion_working_dir(&file_path, repo_working_dir),
+                edited_filepaths: Some(vec![file_path.clone()]),
+                will_edit_filepaths: None,
+                dirty_files: Some(HashMap::from([(file_path, content_after)])),
+            });
+        }
+
         if hook_event_name == "before_edit" {
             return Ok(AgentRunResult {
                 agent_id,
@@ -3396,3 +3492,250 @@ impl AgentCheckpointPreset for AiTabPreset {
         })
     }
 }
+
+// Windsurf (Cascade) to checkpoint preset
+pub struct WindsurfPreset;
+
+impl AgentCheckpointPreset for WindsurfPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Windsurf preset".to_string())
+        })?;
+
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let trajectory_id = hook_data
+            .get("trajectory_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                GitAiError::PresetError("trajectory_id not found in hook_input".to_string())
+            })?;
+
+        let action_name = hook_data
+            .get("agent_action_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                GitAiError::PresetError("agent_action_name not found in hook_input".to_string())
+            })?;
+
+        // Cascade hooks don't carry the model; the transcript isn't exposed either
+        let agent_id = AgentId {
+            tool: "windsurf".to_string(),
+            id: trajectory_id.to_string(),
+            model: hook_data
+                .get("model_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        };
+
+        let file_path_as_vec = hook_data
+            .get("tool_info")
+            .and_then(|ti| ti.get("file_path"))
+            .and_then(|v| v.as_str())
+            .map(|path| vec![path.to_string()]);
+
+        match action_name {
+            "pre_write_code" => Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::Human,
+                transcript: None,
+                repo_working_dir: None,
+                edited_filepaths: None,
+                will_edit_filepaths: file_path_as_vec,
+                dirty_files: None,
+            }),
+            "post_write_code" => Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::AiAgent,
+                transcript: Some(AiTranscript::new()),
+                repo_working_dir: None,
+                edited_filepaths: file_path_as_vec,
+                will_edit_filepaths: None,
+                dirty_files: None,
+            }),
+            other => Err(GitAiError::PresetError(format!(
+                "Unsupported Windsurf hook event: {}",
+                other
+            ))),
+        }
+    }
+}
+
+// Aider to checkpoint preset
+//
+// Aider runs its lint command with the files it just edited as arguments; there is
+// no hook payload. The model and the user's prompts come from Aider's chat history
+// file in the repo root.
+pub struct AiderPreset;
+
+const AIDER_CHAT_HISTORY_FILE: &str = ".aider.chat.history.md";
+const AIDER_SESSION_HEADER: &str = "# aider chat started at ";
+
+impl AiderPreset {
+    pub fn run_for_files(
+        &self,
+        repo_working_dir: &str,
+        edited_filepaths: Vec<String>,
+    ) -> AgentRunResult {
+        let history_path = Path::new(repo_working_dir).join(AIDER_CHAT_HISTORY_FILE);
+        let (session, history_model, transcript) = std::fs::read_to_string(&history_path)
+            .map(|history| Self::parse_chat_history(&history))
+            .unwrap_or_else(|_| (None, None, AiTranscript::new()));
+
+        // AIDER_MODEL is what `--model` reads from the environment
+        let model = env::var("AIDER_MODEL")
+            .ok()
+            .filter(|m| !m.trim().is_empty())
+            .or(history_model)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let agent_metadata = history_path.exists().then(|| {
+            HashMap::from([(
+                "chat_history_path".to_string(),
+                history_path.to_string_lossy().to_string(),
+            )])
+        });
+
+        AgentRunResult {
+            agent_id: AgentId {
+                tool: "aider".to_string(),
+                id: session.unwrap_or_else(|| "aider".to_string()),
+                model,
+            },
+            agent_metadata,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: Some(transcript),
+            repo_working_dir: Some(repo_working_dir.to_string()),
+            edited_filepaths: if edited_filepaths.is_empty() {
+                None
+            } else {
+                Some(edited_filepaths)
+            },
+            will_edit_filepaths: None,
+            dirty_files: None,
+        }
+    }
+
+    /// Session id, model and user prompts of the latest session in an Aider chat
+    /// history file
+    pub fn parse_chat_history(history: &str) -> (Option<String>, Option<String>, AiTranscript) {
+        let latest_session = history
+            .rfind(AIDER_SESSION_HEADER)
+            .map(|idx| &history[idx..])
+            .unwrap_or(history);
+
+        let mut session = None;
+        let mut model = None;
+        let mut transcript = AiTranscript::new();
+        for line in latest_session.lines() {
+            if let Some(started_at) = line.strip_prefix(AIDER_SESSION_HEADER) {
+                session = Some(format!("aider-{}", started_at.trim().replace(' ', "T")));
+            } else if let Some(models) = line
+                .strip_prefix("> Main model: ")
+                .or_else(|| line.strip_prefix("> Models: "))
+                .or_else(|| line.strip_prefix("> Model: "))
+            {
+                let name = models.split([',', ' ']).next().unwrap_or_default().trim();
+                if !name.is_empty() {
+                    model = Some(name.to_string());
+                }
+            } else if let Some(prompt) = line.strip_prefix("#### ") {
+                let prompt = prompt.trim();
+                if !prompt.is_empty() {
+                    transcript.add_message(Message::User {
+                        text: prompt.to_string(),
+                        timestamp: None,
+                    });
+                }
+            }
+        }
+
+        (session, model, transcript)
+    }
+}
+
+// Zed to checkpoint preset
+//
+// Zed's agent has no edit hooks. git-ai registers itself as a Zed context server
+// (`git-ai mcp zed`) whose checkpoint tool the agent calls before and after it
+// edits files; the server forwards each call here as the hook input.
+pub struct ZedPreset;
+
+impl AgentCheckpointPreset for ZedPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Zed preset".to_string())
+        })?;
+
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let phase = hook_data
+            .get("phase")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| GitAiError::PresetError("phase not found in hook_input".to_string()))?;
+
+        let filepaths: Vec<String> = hook_data
+            .get("edited_filepaths")
+            .and_then(|v| v.as_array())
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let filepaths = (!filepaths.is_empty()).then_some(filepaths);
+
+        let agent_id = AgentId {
+            tool: "zed".to_string(),
+            id: hook_data
+                .get("thread_id")
+                .and_then(|v| v.as_str())
+                .filter(|id| !id.is_empty())
+                .unwrap_or("zed")
+                .to_string(),
+            model: hook_data
+                .get("model")
+                .and_then(|v| v.as_str())
+                .filter(|model| !model.is_empty())
+                .unwrap_or("unknown")
+                .to_string(),
+        };
+        let repo_working_dir = hook_data
+            .get("repo_working_dir")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        match phase {
+            "before_edit" => Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::Human,
+                transcript: None,
+                repo_working_dir,
+                edited_filepaths: None,
+                will_edit_filepaths: filepaths,
+                dirty_files: None,
+            }),
+            "after_edit" => Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::AiAgent,
+                transcript: Some(AiTranscript::new()),
+                repo_working_dir,
+                edited_filepaths: filepaths,
+                will_edit_filepaths: None,
+                dirty_files: None,
+            }),
+            other => Err(GitAiError::PresetError(format!(
+                "Unsupported Zed checkpoint phase: {}",
+                other
+            ))),
+        }
+    }
+}
@@ -1,20 +1,21 @@
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     authorship::{
         transcript::AiTranscript,
-        working_log::{AgentId, CheckpointKind},
+        working_log::{AGENT_VERSION_METADATA_KEY, AgentId, CheckpointKind},
     },
     commands::checkpoint_agent::agent_presets::{AgentCheckpointPreset, AgentRunResult},
 };
 
 pub struct AgentV1Preset;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum AgentV1Input {
+pub(crate) enum AgentV1Input {
     Human {
         repo_working_dir: String,
         will_edit_filepaths: Option<Vec<String>>,
@@ -30,6 +31,9 @@ enum AgentV1Input {
         conversation_id: String,
         #[serde(default)]
         dirty_files: Option<HashMap<String, String>>,
+        /// Recorded in authorship logs when `capture_environment` is on
+        #[serde(default)]
+        agent_version: Option<String>,
     },
     // AiTab
 }
@@ -80,13 +84,16 @@ impl AgentCheckpointPreset for AgentV1Preset {
                 conversation_id,
                 repo_working_dir,
                 dirty_files,
+                agent_version,
             } => Ok(AgentRunResult {
                 agent_id: AgentId {
                     tool: agent_name,
                     id: conversation_id,
                     model,
                 },
-                agent_metadata: None,
+                agent_metadata: agent_version.map(|version| {
+                    HashMap::from([(AGENT_VERSION_METADATA_KEY.to_string(), version)])
+                }),
                 repo_working_dir: Some(repo_working_dir),
                 transcript: Some(transcript),
                 checkpoint_kind: CheckpointKind::AiAgent,
@@ -0,0 +1,233 @@
+use crate::{
+    authorship::{
+        transcript::AiTranscript,
+        working_log::{AgentId, CheckpointKind},
+    },
+    commands::checkpoint_agent::agent_presets::{
+        AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, GithubCopilotPreset,
+    },
+    error::GitAiError,
+    observability::log_error,
+};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+// GitHub Copilot CLI (and agent-mode hosts that share its hooks) to checkpoint preset
+//
+// Copilot's `preToolUse`/`postToolUse` hooks carry the working directory, the tool
+// name and its arguments as a JSON string; without an event name, a tool result
+// marks the post-tool call. The session and model come from the session-state
+// event stream Copilot writes under ~/.copilot.
+pub struct CopilotPreset;
+
+/// Copilot tools that write files; other tools don't checkpoint
+const COPILOT_EDIT_TOOLS: &[&str] = &[
+    "edit",
+    "create",
+    "write",
+    "str_replace",
+    "str_replace_editor",
+    "apply_patch",
+];
+
+impl AgentCheckpointPreset for CopilotPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Copilot preset".to_string())
+        })?;
+
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let cwd = hook_data
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| GitAiError::PresetError("cwd not found in hook_input".to_string()))?;
+        let tool_name = hook_data
+            .get("toolName")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                GitAiError::PresetError("toolName not found in hook_input".to_string())
+            })?;
+        if !COPILOT_EDIT_TOOLS.contains(&tool_name) {
+            return Err(GitAiError::PresetError(format!(
+                "Copilot tool {} does not edit files",
+                tool_name
+            )));
+        }
+
+        let is_post_tool = match hook_data.get("hookEventName").and_then(|v| v.as_str()) {
+            Some("preToolUse") => false,
+            Some("postToolUse") => true,
+            Some(other) => {
+                return Err(GitAiError::PresetError(format!(
+                    "Unsupported Copilot hook event: {}",
+                    other
+                )));
+            }
+            None => hook_data.get("toolResult").is_some(),
+        };
+
+        // toolArgs is a JSON-encoded string
+        let tool_args = hook_data
+            .get("toolArgs")
+            .and_then(|v| v.as_str())
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+            .or_else(|| hook_data.get("toolArgs").cloned())
+            .unwrap_or(serde_json::Value::Null);
+        let file_path_as_vec = tool_args
+            .get("path")
+            .or_else(|| tool_args.get("file_path"))
+            .or_else(|| tool_args.get("filePath"))
+            .and_then(|v| v.as_str())
+            .map(|path| vec![path.to_string()]);
+
+        let session_path = hook_data
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .and_then(|id| Self::session_path_for_id(&Self::session_state_dir(), id))
+            .or_else(|| Self::latest_session_path(&Self::session_state_dir()));
+        let session_id = hook_data
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| session_path.as_deref().and_then(Self::session_id_from_path))
+            .unwrap_or_else(|| "copilot".to_string());
+
+        let (transcript, transcript_model) = match &session_path {
+            Some(path) if is_post_tool => {
+                match GithubCopilotPreset::transcript_and_model_from_copilot_session_json(
+                    &path.to_string_lossy(),
+                ) {
+                    Ok((transcript, model, _)) => (transcript, model),
+                    Err(e) => {
+                        log_error(
+                            &e,
+                            Some(serde_json::json!({
+                                "agent_tool": "copilot",
+                                "operation": "transcript_and_model_from_copilot_session_json"
+                            })),
+                        );
+                        (AiTranscript::new(), None)
+                    }
+                }
+            }
+            _ => (AiTranscript::new(), None),
+        };
+
+        let agent_id = AgentId {
+            tool: "copilot".to_string(),
+            id: session_id,
+            model: hook_data
+                .get("model")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or(transcript_model)
+                .or_else(Self::configured_model)
+                .unwrap_or_else(|| "unknown".to_string()),
+        };
+
+        if !is_post_tool {
+            return Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::Human,
+                transcript: None,
+                repo_working_dir: Some(cwd.to_string()),
+                edited_filepaths: None,
+                will_edit_filepaths: file_path_as_vec,
+                dirty_files: None,
+            });
+        }
+
+        let agent_metadata = session_path.map(|path| {
+            HashMap::from([(
+                "chat_session_path".to_string(),
+                path.to_string_lossy().to_string(),
+            )])
+        });
+
+        Ok(AgentRunResult {
+            agent_id,
+            agent_metadata,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: Some(transcript),
+            repo_working_dir: Some(cwd.to_string()),
+            edited_filepaths: file_path_as_vec,
+            will_edit_filepaths: None,
+            dirty_files: None,
+        })
+    }
+}
+
+impl CopilotPreset {
+    pub fn copilot_home_dir() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("~"))
+            .join(".copilot")
+    }
+
+    fn session_state_dir() -> PathBuf {
+        Self::copilot_home_dir().join("session-state")
+    }
+
+    /// A session's event stream: `<id>.jsonl`, or `<id>/events.jsonl` in newer CLIs
+    pub fn session_path_for_id(session_state_dir: &Path, session_id: &str) -> Option<PathBuf> {
+        [
+            session_state_dir.join(format!("{}.jsonl", session_id)),
+            session_state_dir.join(session_id).join("events.jsonl"),
+        ]
+        .into_iter()
+        .find(|path| path.is_file())
+    }
+
+    /// The most recently written session event stream, for hooks that don't say
+    /// which session they belong to
+    pub fn latest_session_path(session_state_dir: &Path) -> Option<PathBuf> {
+        std::fs::read_dir(session_state_dir)
+            .ok()?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let path = entry.path();
+                let events = if path.is_dir() {
+                    path.join("events.jsonl")
+                } else {
+                    path
+                };
+                if events.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    return None;
+                }
+                let modified = events.metadata().ok()?.modified().ok()?;
+                Some((modified, events))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+    }
+
+    fn session_id_from_path(path: &Path) -> Option<String> {
+        let stem = if path.file_name().and_then(|n| n.to_str()) == Some("events.jsonl") {
+            path.parent()?.file_name()
+        } else {
+            path.file_stem()
+        };
+        stem.and_then(|s| s.to_str()).map(str::to_string)
+    }
+
+    /// The model picked with `--model` (exported as COPILOT_MODEL) or `/model`,
+    /// which the CLI saves to its config
+    fn configured_model() -> Option<String> {
+        env::var("COPILOT_MODEL")
+            .ok()
+            .filter(|m| !m.trim().is_empty())
+            .or_else(|| {
+                let config =
+                    std::fs::read_to_string(Self::copilot_home_dir().join("config.json")).ok()?;
+                let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+                config
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            })
+    }
+}
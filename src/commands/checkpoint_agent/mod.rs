@@ -1,3 +1,6 @@
+pub mod agent_detection;
 pub mod agent_presets;
 pub mod agent_v1_preset;
+pub mod copilot_preset;
 pub mod opencode_preset;
+pub mod plugin_source_preset;
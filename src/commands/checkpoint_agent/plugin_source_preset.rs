@@ -0,0 +1,107 @@
+//! Checkpoint preset backed by a `git-ai-source-<name>` plugin.
+//!
+//! `git-ai checkpoint <name> --hook-input <json|stdin>` runs
+//! `git-ai-source-<name> checkpoint` with the agent's raw hook payload on stdin.
+//! The plugin answers on stdout with an agent-v1 payload
+//! (https://usegitai.com/docs/cli/add-your-agent), which is then handled
+//! exactly like `git-ai checkpoint agent-v1`. A non-zero exit code skips the
+//! checkpoint and surfaces the plugin's stderr.
+
+use crate::commands::checkpoint_agent::agent_presets::{
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult,
+};
+use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Preset;
+use crate::error::GitAiError;
+use crate::plugins::{Plugin, find_plugin, run_plugin};
+
+/// Executable name prefix for checkpoint source plugins
+pub const SOURCE_PLUGIN_PREFIX: &str = "git-ai-source-";
+
+pub struct PluginSourcePreset {
+    plugin: Plugin,
+}
+
+impl PluginSourcePreset {
+    /// Look up the source plugin for a preset name that isn't built in
+    pub fn find(name: &str) -> Option<Self> {
+        find_plugin(SOURCE_PLUGIN_PREFIX, name).map(|plugin| Self { plugin })
+    }
+}
+
+impl AgentCheckpointPreset for PluginSourcePreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let output = run_plugin(
+            &self.plugin,
+            "checkpoint",
+            flags.hook_input.as_deref().unwrap_or(""),
+        )?;
+
+        if output.exit_code != Some(0) {
+            return Err(GitAiError::PresetError(format!(
+                "{} exited with {}: {}",
+                self.plugin.path.display(),
+                output
+                    .exit_code
+                    .map(|code| code.to_string())
+                    .unwrap_or_else(|| "signal".to_string()),
+                output.stderr.trim()
+            )));
+        }
+
+        AgentV1Preset.run(AgentCheckpointFlags {
+            hook_input: Some(output.stdout),
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::CheckpointKind;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn preset_with_script(dir: &std::path::Path, body: &str) -> PluginSourcePreset {
+        let path = dir.join("git-ai-source-test");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        PluginSourcePreset {
+            plugin: Plugin {
+                name: "test".to_string(),
+                path,
+            },
+        }
+    }
+
+    #[test]
+    fn test_plugin_output_is_parsed_as_agent_v1() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset = preset_with_script(
+            dir.path(),
+            r#"cat >/dev/null
+echo '{"type":"ai_agent","repo_working_dir":"/tmp/r","edited_filepaths":["a.rs"],"transcript":{"messages":[]},"agent_name":"my-agent","model":"m1","conversation_id":"c1"}'"#,
+        );
+
+        let result = preset
+            .run(AgentCheckpointFlags {
+                hook_input: Some("{}".to_string()),
+            })
+            .unwrap();
+
+        assert_eq!(result.agent_id.tool, "my-agent");
+        assert_eq!(result.agent_id.id, "c1");
+        assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+        assert_eq!(result.edited_filepaths, Some(vec!["a.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_plugin_failure_is_preset_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset = preset_with_script(dir.path(), "echo 'bad payload' >&2; exit 2");
+
+        let err = preset
+            .run(AgentCheckpointFlags { hook_input: None })
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("bad payload"));
+    }
+}
@@ -1,7 +1,18 @@
+use crate::authorship::ignore::{IgnoreMatcher, effective_ignore_patterns};
+use crate::ci::changed_ai_files::ChangedAiFiles;
 use crate::ci::ci_context::{CiContext, CiEvent, CiRunResult};
-use crate::ci::github::{get_github_ci_context, install_github_ci_workflow};
-use crate::ci::gitlab::{get_gitlab_ci_context, print_gitlab_ci_yaml};
+use crate::ci::github::{
+    GithubCommentClient, PR_ANNOTATION_TEMPLATE, PrAnnotation, PrCommentUpdate,
+    get_github_ci_context, install_github_ci_workflow, pr_from_github_event,
+};
+use crate::ci::gitlab::{
+    GitlabNoteClient, get_gitlab_ci_context, mr_from_gitlab_env, print_gitlab_ci_yaml,
+};
+use crate::ci::merge_detection::detect_merge_source;
+use crate::ci::note_coverage::RangeNoteCoverage;
+use crate::ci::prefetch::{PrProvider, QueuedPr, prefetch_merge_queue};
 use crate::git::repository::find_repository_in_path;
+use crate::git::sync_authorship::fetch_authorship_notes;
 use crate::utils::debug_log;
 
 /// Print a human-readable message for a CiRunResult
@@ -43,6 +54,18 @@ pub fn handle_ci(args: &[String]) {
         "local" => {
             handle_ci_local(&args[1..]);
         }
+        "prefetch" => {
+            handle_ci_prefetch(&args[1..]);
+        }
+        "annotate-pr" => {
+            handle_ci_annotate_pr(&args[1..]);
+        }
+        "require-notes" => {
+            handle_ci_require_notes(&args[1..]);
+        }
+        "changed-ai-files" => {
+            handle_ci_changed_ai_files(&args[1..]);
+        }
         _ => {
             eprintln!("Unknown ci subcommand: {}", args[0]);
             print_ci_help_and_exit();
@@ -157,6 +180,9 @@ fn handle_ci_gitlab(args: &[String]) {
             print_gitlab_ci_yaml();
             std::process::exit(0);
         }
+        "annotate-mr" => {
+            handle_ci_gitlab_annotate_mr(&args[1..]);
+        }
         other => {
             eprintln!("Unknown ci gitlab subcommand: {}", other);
             print_ci_help_and_exit();
@@ -217,29 +243,58 @@ fn handle_ci_local(args: &[String]) {
                 }
             };
 
-            // All flags required for merge
-            let head_ref = match flag("--head-ref") {
-                Some(v) => v,
-                None => {
-                    eprintln!("--head-ref is required");
-                    std::process::exit(1);
-                }
-            };
-
-            let head_sha = match flag("--head-sha") {
-                Some(v) => v,
+            // Without --head-sha (e.g. the branch was deleted by a merge queue), work
+            // out the source branch from the merge commit itself
+            let (head_ref, head_sha) = match flag("--head-sha") {
+                Some(head_sha) => (
+                    flag("--head-ref").unwrap_or_else(|| head_sha.clone()),
+                    head_sha,
+                ),
                 None => {
-                    eprintln!("--head-sha is required");
-                    std::process::exit(1);
+                    // Noted commits are detection candidates, so fetch notes first
+                    if let Err(e) = fetch_authorship_notes(&repo, "origin") {
+                        debug_log(&format!("Failed to fetch authorship notes: {}", e));
+                    }
+                    match detect_merge_source(&repo, &merge_commit_sha, "origin") {
+                        Ok(Some(source)) => {
+                            println!(
+                                "Detected source head {} ({}) via {}",
+                                source.head_sha,
+                                source.head_ref,
+                                source.method.describe()
+                            );
+                            (
+                                flag("--head-ref").unwrap_or(source.head_ref),
+                                source.head_sha,
+                            )
+                        }
+                        Ok(None) => {
+                            println!(
+                                "Local CI (merge): could not detect the source branch of {}; pass --head-sha",
+                                merge_commit_sha
+                            );
+                            std::process::exit(0);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to detect source branch: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
                 }
             };
 
             let base_sha = match flag("--base-sha") {
                 Some(v) => v,
-                None => {
-                    eprintln!("--base-sha is required");
-                    std::process::exit(1);
-                }
+                None => match repo
+                    .find_commit(merge_commit_sha.clone())
+                    .and_then(|commit| commit.parent(0))
+                {
+                    Ok(parent) => parent.id(),
+                    Err(e) => {
+                        eprintln!("--base-sha not given and merge commit has no parent: {}", e);
+                        std::process::exit(1);
+                    }
+                },
             };
 
             let ctx = CiContext {
@@ -275,6 +330,454 @@ fn handle_ci_local(args: &[String]) {
     }
 }
 
+fn handle_ci_prefetch(args: &[String]) {
+    let mut remote = "origin".to_string();
+    let mut base_ref = "main".to_string();
+    let mut provider = PrProvider::detect();
+    let mut pr_specs = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--remote" | "--base" | "--provider" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Missing value for flag {}", arg);
+                    std::process::exit(1);
+                };
+                match arg {
+                    "--remote" => remote = value.clone(),
+                    "--base" => base_ref = value.clone(),
+                    _ => match PrProvider::from_name(value) {
+                        Some(p) => provider = p,
+                        None => {
+                            eprintln!("Unknown provider: {} (expected github or gitlab)", value);
+                            std::process::exit(1);
+                        }
+                    },
+                }
+                i += 2;
+            }
+            "--help" | "-h" => print_ci_prefetch_help_and_exit(),
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown flag: {}", arg);
+                print_ci_prefetch_help_and_exit();
+            }
+            _ => {
+                // Accept "12 34" as well as "12,34" so a queue can be passed as one value
+                pr_specs.extend(arg.split(',').filter(|s| !s.is_empty()));
+                i += 1;
+            }
+        }
+    }
+
+    if pr_specs.is_empty() {
+        print_ci_prefetch_help_and_exit();
+    }
+    let prs: Vec<QueuedPr> = pr_specs
+        .into_iter()
+        .map(|spec| QueuedPr::parse(spec, provider))
+        .collect();
+
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to open repository in current directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match prefetch_merge_queue(&repo, &remote, &base_ref, &prs) {
+        Ok(summary) => {
+            println!(
+                "Prefetched {} PRs ({} authorship notes)",
+                summary.prs, summary.notes
+            );
+            if !summary.failed.is_empty() {
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Prefetch failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_ci_annotate_pr(args: &[String]) {
+    let mut base: Option<String> = None;
+    let mut head: Option<String> = None;
+    let mut pr_number: Option<u64> = None;
+    let mut repo_slug = std::env::var("GITHUB_REPOSITORY").ok();
+    let mut template = PR_ANNOTATION_TEMPLATE.to_string();
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--base" | "--head" | "--pr" | "--repo" | "--template" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Missing value for flag {}", arg);
+                    std::process::exit(1);
+                };
+                match arg {
+                    "--base" => base = Some(value.clone()),
+                    "--head" => head = Some(value.clone()),
+                    "--repo" => repo_slug = Some(value.clone()),
+                    "--template" => template = value.clone(),
+                    _ => match value.trim_start_matches('#').parse() {
+                        Ok(number) => pr_number = Some(number),
+                        Err(_) => {
+                            eprintln!("Invalid PR number: {}", value);
+                            std::process::exit(1);
+                        }
+                    },
+                }
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--help" | "-h" => print_ci_annotate_pr_help_and_exit(),
+            _ => {
+                eprintln!("Unknown flag: {}", arg);
+                print_ci_annotate_pr_help_and_exit();
+            }
+        }
+    }
+
+    // Anything not given on the command line comes from the pull_request event
+    if let Some((number, event_base, event_head)) = pr_from_github_event() {
+        pr_number.get_or_insert(number);
+        base.get_or_insert(event_base);
+        head.get_or_insert(event_head);
+    }
+    let head = head.unwrap_or_else(|| "HEAD".to_string());
+    let Some(base) = base else {
+        eprintln!("--base is required outside a GitHub pull_request workflow");
+        std::process::exit(1);
+    };
+
+    let body = annotation_body(&base, &head, &template, "PR");
+    if dry_run {
+        print!("{}", body);
+        return;
+    }
+
+    let Some(pr_number) = pr_number else {
+        eprintln!("--pr is required outside a GitHub pull_request workflow");
+        std::process::exit(1);
+    };
+    let Some(repo_slug) = repo_slug else {
+        eprintln!("--repo is required when GITHUB_REPOSITORY isn't set");
+        std::process::exit(1);
+    };
+    let Some(token) = std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()) else {
+        eprintln!("GITHUB_TOKEN must be set to comment on the PR");
+        std::process::exit(1);
+    };
+    let api_url =
+        std::env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".to_string());
+
+    match GithubCommentClient::new(&api_url, token).upsert_pr_comment(&repo_slug, pr_number, &body)
+    {
+        Ok(PrCommentUpdate::Created { url }) => {
+            println!("Posted AI authorship comment on #{} {}", pr_number, url)
+        }
+        Ok(PrCommentUpdate::Updated { url }) => {
+            println!("Updated AI authorship comment on #{} {}", pr_number, url)
+        }
+        Err(e) => {
+            eprintln!("Failed to comment on #{}: {}", pr_number, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The rendered AI authorship comment for `base..head`, exiting on failure
+fn annotation_body(base: &str, head: &str, template: &str, kind: &str) -> String {
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to open repository in current directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let ignore_patterns = effective_ignore_patterns(&repo, &[], &[]);
+    match PrAnnotation::compute(&repo, base, head, &ignore_patterns)
+        .and_then(|annotation| annotation.render(template))
+    {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("Failed to compute AI authorship for the {}: {}", kind, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_ci_gitlab_annotate_mr(args: &[String]) {
+    let mut base: Option<String> = None;
+    let mut head: Option<String> = None;
+    let mut mr_iid: Option<u64> = None;
+    let mut project = std::env::var("CI_PROJECT_ID").ok();
+    let mut template = PR_ANNOTATION_TEMPLATE.to_string();
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--base" | "--head" | "--mr" | "--project" | "--template" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Missing value for flag {}", arg);
+                    std::process::exit(1);
+                };
+                match arg {
+                    "--base" => base = Some(value.clone()),
+                    "--head" => head = Some(value.clone()),
+                    "--project" => project = Some(value.clone()),
+                    "--template" => template = value.clone(),
+                    _ => match value.trim_start_matches('!').parse() {
+                        Ok(iid) => mr_iid = Some(iid),
+                        Err(_) => {
+                            eprintln!("Invalid MR number: {}", value);
+                            std::process::exit(1);
+                        }
+                    },
+                }
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--help" | "-h" => print_ci_gitlab_annotate_mr_help_and_exit(),
+            _ => {
+                eprintln!("Unknown flag: {}", arg);
+                print_ci_gitlab_annotate_mr_help_and_exit();
+            }
+        }
+    }
+
+    // Anything not given on the command line comes from the merge request pipeline
+    if let Some((iid, env_base, env_head)) = mr_from_gitlab_env() {
+        mr_iid.get_or_insert(iid);
+        base.get_or_insert(env_base);
+        head.get_or_insert(env_head);
+    }
+    let head = head.unwrap_or_else(|| "HEAD".to_string());
+    let Some(base) = base else {
+        eprintln!("--base is required outside a GitLab merge request pipeline");
+        std::process::exit(1);
+    };
+
+    let body = annotation_body(&base, &head, &template, "MR");
+    if dry_run {
+        print!("{}", body);
+        return;
+    }
+
+    let Some(mr_iid) = mr_iid else {
+        eprintln!("--mr is required outside a GitLab merge request pipeline");
+        std::process::exit(1);
+    };
+    let Some(project) = project else {
+        eprintln!("--project is required when CI_PROJECT_ID isn't set");
+        std::process::exit(1);
+    };
+    let api_url =
+        std::env::var("CI_API_V4_URL").unwrap_or_else(|_| "https://gitlab.com/api/v4".to_string());
+    let client = match GitlabNoteClient::from_env(&api_url) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match client.upsert_mr_note(&project, mr_iid, &body) {
+        Ok(PrCommentUpdate::Created { url }) => {
+            println!("Posted AI authorship note on !{} {}", mr_iid, url)
+        }
+        Ok(PrCommentUpdate::Updated { url }) => {
+            println!("Updated AI authorship note on !{} {}", mr_iid, url)
+        }
+        Err(e) => {
+            eprintln!("Failed to comment on !{}: {}", mr_iid, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_ci_require_notes(args: &[String]) {
+    let mut range: Option<String> = None;
+    let mut remote = Some("origin".to_string());
+    let mut user_patterns = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--range" | "--remote" | "--ignore" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Missing value for flag {}", arg);
+                    std::process::exit(1);
+                };
+                match arg {
+                    "--range" => range = Some(value.clone()),
+                    "--remote" => remote = Some(value.clone()),
+                    _ => user_patterns.push(value.clone()),
+                }
+                i += 2;
+            }
+            "--no-fetch" => {
+                remote = None;
+                i += 1;
+            }
+            "--help" | "-h" => print_ci_require_notes_help_and_exit(),
+            _ => {
+                eprintln!("Unknown flag: {}", arg);
+                print_ci_require_notes_help_and_exit();
+            }
+        }
+    }
+    let Some(range) = range else {
+        eprintln!("--range is required");
+        print_ci_require_notes_help_and_exit();
+    };
+
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to open repository in current directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // CI checkouts don't fetch notes on their own
+    if let Some(remote) = &remote
+        && let Err(e) = fetch_authorship_notes(&repo, remote)
+    {
+        debug_log(&format!("Failed to fetch authorship notes: {}", e));
+    }
+
+    let matcher = IgnoreMatcher::new(&effective_ignore_patterns(&repo, &user_patterns, &[]));
+    let coverage = match RangeNoteCoverage::for_range(&repo, &range, &matcher) {
+        Ok(coverage) => coverage,
+        Err(e) => {
+            eprintln!("Failed to check authorship notes: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match serde_json::to_string_pretty(&coverage) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Failed to serialize report: {}", e);
+            std::process::exit(1);
+        }
+    }
+    eprintln!("{}", coverage.summary());
+    if !coverage.passed {
+        std::process::exit(1);
+    }
+}
+
+fn handle_ci_changed_ai_files(args: &[String]) {
+    let mut range: Option<String> = None;
+    let mut remote = Some("origin".to_string());
+    let mut user_patterns = Vec::new();
+    let mut json = false;
+    let mut packages = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--range" | "--remote" | "--ignore" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Missing value for flag {}", arg);
+                    std::process::exit(1);
+                };
+                match arg {
+                    "--range" => range = Some(value.clone()),
+                    "--remote" => remote = Some(value.clone()),
+                    _ => user_patterns.push(value.clone()),
+                }
+                i += 2;
+            }
+            "--no-fetch" => {
+                remote = None;
+                i += 1;
+            }
+            "--json" => {
+                json = true;
+                i += 1;
+            }
+            "--packages" => {
+                packages = true;
+                i += 1;
+            }
+            "--help" | "-h" => print_ci_changed_ai_files_help_and_exit(),
+            _ => {
+                eprintln!("Unknown flag: {}", arg);
+                print_ci_changed_ai_files_help_and_exit();
+            }
+        }
+    }
+    let Some(range) = range else {
+        eprintln!("--range is required");
+        print_ci_changed_ai_files_help_and_exit();
+    };
+
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to open repository in current directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // CI checkouts don't fetch notes on their own
+    if let Some(remote) = &remote
+        && let Err(e) = fetch_authorship_notes(&repo, remote)
+    {
+        debug_log(&format!("Failed to fetch authorship notes: {}", e));
+    }
+
+    let ignore_patterns = effective_ignore_patterns(&repo, &user_patterns, &[]);
+    let changed = match ChangedAiFiles::for_range(&repo, &range, &ignore_patterns) {
+        Ok(changed) => changed,
+        Err(e) => {
+            eprintln!("Failed to find AI-changed files: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&changed) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if packages {
+        for package in &changed.packages {
+            println!("{}", package);
+        }
+    } else {
+        for file in &changed.files {
+            println!("{}", file.path);
+        }
+    }
+    eprintln!("{}", changed.summary());
+}
+
 fn print_ci_help_and_exit() -> ! {
     eprintln!("git-ai ci - Continuous integration utilities");
     eprintln!();
@@ -287,12 +790,90 @@ fn print_ci_help_and_exit() -> ! {
     eprintln!("  gitlab           GitLab CI");
     eprintln!("    run [--no-cleanup]  Run GitLab CI in current repo");
     eprintln!("    install        Print YAML snippet to add to .gitlab-ci.yml");
+    eprintln!("    annotate-mr    Post (or update) an AI authorship note on a merge request");
     eprintln!("  local            Run CI locally by event name and flags");
     eprintln!("                   Usage: git-ai ci local <event> [flags]");
     eprintln!("                   Events:");
     eprintln!(
         "                     merge  --merge-commit-sha <sha> --base-ref <ref> --head-ref <ref> --head-sha <sha> --base-sha <sha>"
     );
+    eprintln!("  prefetch <pr>... Fetch notes and warm caches for PRs in a merge queue");
+    eprintln!("  annotate-pr      Post (or update) an AI authorship comment on a GitHub PR");
+    eprintln!("  require-notes    Fail if commits in a range are missing authorship notes");
+    eprintln!("  changed-ai-files List files and packages with AI-authored changes in a range");
+    std::process::exit(1);
+}
+
+fn print_ci_changed_ai_files_help_and_exit() -> ! {
+    eprintln!("git-ai ci changed-ai-files - Files with AI-authored changes, for test selection");
+    eprintln!();
+    eprintln!("Usage: git-ai ci changed-ai-files --range <base>..<head> [options]");
+    eprintln!();
+    eprintln!("Prints the files whose added lines (since the merge base of base and head)");
+    eprintln!("include AI-authored ones, one per line, so CI can run stricter checks on them.");
+    eprintln!("Ignored files (lockfiles, generated code) are left out.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --range <base>..<head>  Diff to inspect (required)");
+    eprintln!("  --packages              Print package directories instead of files");
+    eprintln!("  --json                  Print files, their AI line counts and packages as JSON");
+    eprintln!("  --remote <name>         Remote to fetch notes from first (default: origin)");
+    eprintln!("  --no-fetch              Use the local notes as they are");
+    eprintln!("  --ignore <pattern>      Also leave out files matching pattern (repeatable)");
+    std::process::exit(1);
+}
+
+fn print_ci_require_notes_help_and_exit() -> ! {
+    eprintln!("git-ai ci require-notes - Require authorship notes on every commit in a range");
+    eprintln!();
+    eprintln!("Usage: git-ai ci require-notes --range <base>..<head> [options]");
+    eprintln!();
+    eprintln!("Prints a JSON report of the non-merge commits in the range that change source");
+    eprintln!("files but have no authorship note, and exits 1 if there are any.");
+    eprintln!("Commits that only touch ignored files (lockfiles, generated code) are exempt.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --range <base>..<head>  Commits to check (required)");
+    eprintln!("  --remote <name>         Remote to fetch notes from first (default: origin)");
+    eprintln!("  --no-fetch              Use the local notes as they are");
+    eprintln!("  --ignore <pattern>      Also exempt files matching pattern (repeatable)");
+    std::process::exit(1);
+}
+
+fn print_ci_annotate_pr_help_and_exit() -> ! {
+    eprintln!("git-ai ci annotate-pr - Comment AI authorship stats on a GitHub pull request");
+    eprintln!();
+    eprintln!("Usage: git-ai ci annotate-pr [options]");
+    eprintln!();
+    eprintln!("Computes the AI/human breakdown (per file and per tool) for the commits the PR");
+    eprintln!("adds, and posts it as a PR comment. Reruns update the same comment.");
+    eprintln!("In a pull_request workflow the PR, base and head come from the event payload.");
+    eprintln!("Requires GITHUB_TOKEN (and full history, e.g. fetch-depth: 0).");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --base <rev>         Base of the PR (default: from the event)");
+    eprintln!("  --head <rev>         Head of the PR (default: from the event, else HEAD)");
+    eprintln!("  --pr <number>        PR to comment on (default: from the event)");
+    eprintln!("  --repo <owner/name>  Repository (default: $GITHUB_REPOSITORY)");
+    eprintln!("  --template <name>    Comment template (default: pr-annotation)");
+    eprintln!("  --dry-run            Print the comment instead of posting it");
+    std::process::exit(1);
+}
+
+fn print_ci_prefetch_help_and_exit() -> ! {
+    eprintln!("git-ai ci prefetch - Warm a CI runner for the PRs in a merge queue");
+    eprintln!();
+    eprintln!("Usage: git-ai ci prefetch [options] <pr>...");
+    eprintln!();
+    eprintln!("Each <pr> is a PR/MR number, a branch name or a full ref.");
+    eprintln!("Numbers and comma-separated lists are both accepted.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --remote <name>      Remote to fetch from (default: origin)");
+    eprintln!("  --base <branch>      Branch the queue merges into (default: main)");
+    eprintln!(
+        "  --provider <name>    github or gitlab (default: gitlab in GitLab CI, else github)"
+    );
     std::process::exit(1);
 }
 
@@ -303,8 +884,11 @@ fn print_ci_local_help_and_exit() -> ! {
     eprintln!();
     eprintln!("Events:");
     eprintln!(
-        "  merge  --merge-commit-sha <sha> --base-ref <ref> --head-ref <ref> --head-sha <sha> --base-sha <sha>"
+        "  merge  --merge-commit-sha <sha> --base-ref <ref> [--head-ref <ref>] [--head-sha <sha>] [--base-sha <sha>]"
     );
+    eprintln!();
+    eprintln!("Without --head-sha, the source branch is detected from the merge commit's");
+    eprintln!("PR/MR reference, or from a commit with the same tree or patch-id.");
     std::process::exit(1);
 }
 
@@ -329,5 +913,27 @@ fn print_ci_gitlab_help_and_exit() -> ! {
     eprintln!("  run [--no-cleanup]   Run GitLab CI in current repo");
     eprintln!("                       --no-cleanup  Skip teardown after run");
     eprintln!("  install              Print YAML snippet to add to .gitlab-ci.yml");
+    eprintln!("  annotate-mr          Post (or update) an AI authorship note on a merge request");
+    std::process::exit(1);
+}
+
+fn print_ci_gitlab_annotate_mr_help_and_exit() -> ! {
+    eprintln!("git-ai ci gitlab annotate-mr - Comment AI authorship stats on a GitLab MR");
+    eprintln!();
+    eprintln!("Usage: git-ai ci gitlab annotate-mr [options]");
+    eprintln!();
+    eprintln!("Computes the AI/human breakdown (per file and per tool) for the commits the MR");
+    eprintln!("adds, and posts it as an MR note. Reruns update the same note.");
+    eprintln!("In a merge request pipeline the MR, base and head come from CI_MERGE_REQUEST_*");
+    eprintln!("variables. Authenticates with GITLAB_TOKEN, else CI_JOB_TOKEN, and needs full");
+    eprintln!("history (GIT_DEPTH: 0).");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --base <rev>         Base of the MR (default: CI_MERGE_REQUEST_DIFF_BASE_SHA)");
+    eprintln!("  --head <rev>         Head of the MR (default: CI_COMMIT_SHA, else HEAD)");
+    eprintln!("  --mr <iid>           MR to comment on (default: CI_MERGE_REQUEST_IID)");
+    eprintln!("  --project <id|path>  Project (default: $CI_PROJECT_ID)");
+    eprintln!("  --template <name>    Note template (default: pr-annotation)");
+    eprintln!("  --dry-run            Print the note instead of posting it");
     std::process::exit(1);
 }
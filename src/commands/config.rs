@@ -1,6 +1,9 @@
 use dirs;
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+use crate::config::TrustTier;
 use crate::git::repository::find_repository_in_path;
 
 /// Determines the type of pattern value provided
@@ -93,9 +96,19 @@ fn print_config_help() {
     eprintln!("  git-ai config set <key> <value> --add    Add to array (extends existing)");
     eprintln!("  git-ai config --add <key> <value>        Add to array or upsert into object");
     eprintln!("  git-ai config unset <key>    Remove config value (reverts to default)");
+    eprintln!("  git-ai config --show-origin  Show each set value and the file it comes from");
+    eprintln!();
+    eprintln!("Precedence (later wins):");
+    eprintln!("  ~/.git-ai/config.json        Global config, written by `git-ai config set`");
+    eprintln!("  <repo>/.git-ai.toml          Checked-in overrides shared by the repository");
+    eprintln!("  <repo>/.git/ai/config.toml   Local overrides for one clone");
+    eprintln!("  Repository files may set attribution_granularity, capture_environment,");
+    eprintln!("  context_capture.*, exclude_paths and tracking.* (added to the global lists),");
+    eprintln!("  redaction.* and notes_ref. Only the local file can turn redaction settings off.");
     eprintln!();
     eprintln!("Configuration Keys:");
     eprintln!("  git_path                     Path to git binary");
+    eprintln!("  git                          Pinned git binary (set with `git-ai git-path pin`)");
     eprintln!("  exclude_prompts_in_repositories  Repos to exclude prompts from (array)");
     eprintln!("  allow_repositories           Allowed repos (array)");
     eprintln!("  exclude_repositories         Excluded repos (array)");
@@ -106,10 +119,104 @@ fn print_config_help() {
     eprintln!("  update_channel               Update channel (latest/next)");
     eprintln!("  feature_flags                Feature flags (object)");
     eprintln!("  api_key                      API key for X-API-Key header");
+    eprintln!(
+        "  api_max_rps                  Max API requests per second per process (default 10)"
+    );
     eprintln!("  prompt_storage               Prompt storage mode (default/notes/local)");
     eprintln!("  include_prompts_in_repositories  Repos to include for prompt storage (array)");
     eprintln!("  default_prompt_storage       Fallback storage mode for non-included repos");
     eprintln!("  quiet                        Suppress chart output after commits (bool)");
+    eprintln!(
+        "  verify_push                  Check pushed commits have authorship notes (off/warn/block)"
+    );
+    eprintln!(
+        "  blame_parallelism            Files blamed at once during rebase/merge rewrites (default 30)"
+    );
+    eprintln!(
+        "  attribution_granularity      line (default) or char: also record AI parts of edited lines"
+    );
+    eprintln!(
+        "  ai_assisted_threshold        AI share for blame to show a mixed line as AI-assisted (0.5)"
+    );
+    eprintln!(
+        "  capture_environment          Record OS, git and agent versions in authorship logs (bool)"
+    );
+    eprintln!(
+        "  annotate_tags                Attach AI attribution totals to annotated tags (bool)"
+    );
+    eprintln!("  exclude_paths                Path globs left out of attribution stats (array)");
+    eprintln!("  tracking.exclude             Path globs never tracked by checkpoints (array)");
+    eprintln!(
+        "  tracking.generated           Generated/binary path globs, noted as byte counts (array)"
+    );
+    eprintln!(
+        "  trust.trusted / .untrusted   Agent globs (tool or tool/model) for trust tiers (array)"
+    );
+    eprintln!("  trust.default                Tier for other agents (trusted/standard/untrusted)");
+    eprintln!("  trust.weights                tier=weight of an AI line in policy totals (object)");
+    eprintln!("  notes_ref                    Notes ref for authorship logs (default \"ai\")");
+    eprintln!(
+        "  credential_backend           Where login tokens are kept (auto/keychain/secret-service/"
+    );
+    eprintln!("                               windows-credential-manager/file)");
+    eprintln!(
+        "  credential_file_fallback     Use a plaintext file when the native store is unavailable (bool)"
+    );
+    eprintln!(
+        "  watch_debounce_ms            Quiet period before `git-ai watch` checkpoints (2000)"
+    );
+    eprintln!("  report.timezone              Time zone for daily/weekly report buckets");
+    eprintln!("                               (IANA name, UTC offset, \"local\"; default UTC)");
+    eprintln!(
+        "  report.bot_authors           Authors left out of reports, e.g. \"renovate\" (array)"
+    );
+    eprintln!("  report.authors               Restrict reports to these authors (array)");
+    eprintln!("  events.webhook_url           POST local events as JSON to this URL");
+    eprintln!(
+        "  events.webhook_events        Events to send (commit_processed, rewrite_completed,"
+    );
+    eprintln!("                               policy_violation; array, default all)");
+    eprintln!("  dual_write.destination       Also write metrics and bundles to this directory,");
+    eprintln!(
+        "                               http(s) URL (PUT <url>/<key>) or s3:// / gs:// bucket"
+    );
+    eprintln!(
+        "  dual_write.headers           Headers for dual-write PUTs; use --add \"Name=value\""
+    );
+    eprintln!(
+        "  identities.aliases           Map other emails/names to one identity (object); use"
+    );
+    eprintln!("                               --add identities.aliases \"Name <email>=alias\"");
+    eprintln!(
+        "  identities.lookup_url        Org directory queried for unknown authors in reports"
+    );
+    eprintln!(
+        "  context_capture.enabled      Capture edited regions with AI checkpoints for review (bool)"
+    );
+    eprintln!("  context_capture.context_lines  Unchanged lines kept around each edit (default 3)");
+    eprintln!("  context_capture.max_bytes    Bundle size cap in bytes (default 65536)");
+    eprintln!("  context_capture.exclude      Path globs never captured (array)");
+    eprintln!(
+        "  redaction.enabled            Redact transcripts before storing them (default true)"
+    );
+    eprintln!("  redaction.entropy            Also redact high-entropy key-like strings (bool)");
+    eprintln!("  redaction.emails             Also redact email addresses (bool)");
+    eprintln!("  redaction.rules              Extra patterns (object); use --add redaction.rules");
+    eprintln!("                               \"name=regex\"");
+    eprintln!("  prompt_hashing.algorithm     Prompt id hash: sha256 (default), sha512, sha3-256");
+    eprintln!(
+        "  prompt_hashing.salt          Org salt for prompt ids (HMAC); or GIT_AI_PROMPT_HASH_SALT"
+    );
+    eprintln!(
+        "  prompt_hashing.previous      Schemes replaced by set (still read); unset to finish"
+    );
+    eprintln!(
+        "  history.deepen               Fetch history cut off by shallow clones (default: in CI only)"
+    );
+    eprintln!("  history.deepen_limit         Most commits fetched on demand (default 1000)");
+    eprintln!("  config.remote_url            Org-hosted JSON config layered beneath this file");
+    eprintln!("  config.ttl_secs              Seconds the org config is cached (default 3600)");
+    eprintln!("  config.allow_endpoints       Let the org config set api_base_url (default false)");
     eprintln!();
     eprintln!("Repository Patterns:");
     eprintln!("  For exclude/allow/exclude_prompts_in_repositories, you can provide:");
@@ -146,6 +253,14 @@ pub fn handle_config(args: &[String]) {
         return;
     }
 
+    if args[0] == "--show-origin" {
+        if let Err(e) = show_config_origin() {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Check for --add flag anywhere in args
     let is_add_mode = args.iter().any(|a| a == "--add");
     let filtered_args: Vec<&String> = args.iter().filter(|a| *a != "--add").collect();
@@ -208,6 +323,58 @@ pub fn handle_config(args: &[String]) {
     }
 }
 
+/// Print every value set in a config file as `file:<path>\t<key>=<value>`, like
+/// `git config --show-origin --list`: in precedence order, so later lines win.
+fn show_config_origin() -> Result<(), String> {
+    let mut lines = Vec::new();
+
+    if let Some(path) = crate::config::config_file_path_public()
+        && path.exists()
+    {
+        let file_config = crate::config::load_file_config_public()?;
+        let value = serde_json::to_value(&file_config).map_err(|e| e.to_string())?;
+        push_origin_lines(&mut lines, &path, "", &value);
+    }
+
+    let (layers, warnings) = crate::repo_config::find_repo_config_layers_from_cwd();
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    for layer in layers {
+        let value = serde_json::to_value(&layer.config).map_err(|e| e.to_string())?;
+        push_origin_lines(&mut lines, &layer.path, "", &value);
+    }
+
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn push_origin_lines(lines: &mut Vec<String>, path: &Path, prefix: &str, value: &Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                push_origin_lines(lines, path, &key, value);
+            }
+        }
+        Value::Null => {}
+        _ => {
+            let rendered = match value {
+                _ if prefix == "api_key" => "********".to_string(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            lines.push(format!("file:{}\t{}={}", path.display(), prefix, rendered));
+        }
+    }
+}
+
 fn show_all_config() -> Result<(), String> {
     let file_config = crate::config::load_file_config_public()?;
 
@@ -223,6 +390,13 @@ fn show_all_config() -> Result<(), String> {
         Value::String(runtime_config.git_cmd().to_string()),
     );
 
+    if let Some(ref git) = file_config.git {
+        effective_config.insert(
+            "git".to_string(),
+            serde_json::to_value(git).unwrap_or(Value::Null),
+        );
+    }
+
     // Arrays
     if let Some(ref repos) = file_config.exclude_prompts_in_repositories {
         effective_config.insert(
@@ -302,7 +476,152 @@ fn show_all_config() -> Result<(), String> {
         );
     }
 
+    effective_config.insert(
+        "api_max_rps".to_string(),
+        serde_json::to_value(runtime_config.api_max_rps()).unwrap_or(Value::Null),
+    );
     effective_config.insert("quiet".to_string(), Value::Bool(runtime_config.is_quiet()));
+    effective_config.insert(
+        "verify_push".to_string(),
+        Value::String(runtime_config.verify_push().as_str().to_string()),
+    );
+    effective_config.insert(
+        "blame_parallelism".to_string(),
+        Value::from(runtime_config.blame_parallelism()),
+    );
+    effective_config.insert(
+        "attribution_granularity".to_string(),
+        Value::String(
+            runtime_config
+                .attribution_granularity()
+                .as_str()
+                .to_string(),
+        ),
+    );
+    effective_config.insert(
+        "ai_assisted_threshold".to_string(),
+        Value::from(runtime_config.ai_assisted_threshold()),
+    );
+    effective_config.insert(
+        "capture_environment".to_string(),
+        Value::Bool(runtime_config.capture_environment()),
+    );
+    effective_config.insert(
+        "annotate_tags".to_string(),
+        Value::Bool(runtime_config.annotate_tags()),
+    );
+    effective_config.insert(
+        "exclude_paths".to_string(),
+        serde_json::to_value(runtime_config.exclude_paths()).unwrap_or(Value::Null),
+    );
+    effective_config.insert(
+        "notes_ref".to_string(),
+        Value::String(runtime_config.notes_ref().to_string()),
+    );
+    effective_config.insert(
+        "credential_backend".to_string(),
+        Value::String(
+            crate::auth::credentials::configured_backend_kind(runtime_config)
+                .as_str()
+                .to_string(),
+        ),
+    );
+    effective_config.insert(
+        "credential_file_fallback".to_string(),
+        Value::Bool(runtime_config.credential_file_fallback()),
+    );
+    effective_config.insert(
+        "watch_debounce_ms".to_string(),
+        Value::from(runtime_config.watch_debounce().as_millis() as u64),
+    );
+
+    if let Some(ref report) = file_config.report {
+        effective_config.insert(
+            "report".to_string(),
+            serde_json::to_value(report).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref events) = file_config.events {
+        effective_config.insert(
+            "events".to_string(),
+            serde_json::to_value(events).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref dual_write) = file_config.dual_write {
+        effective_config.insert("dual_write".to_string(), masked_dual_write(dual_write));
+    }
+
+    if let Some(ref identities) = file_config.identities {
+        effective_config.insert(
+            "identities".to_string(),
+            serde_json::to_value(identities).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref context_capture) = file_config.context_capture {
+        effective_config.insert(
+            "context_capture".to_string(),
+            serde_json::to_value(context_capture).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref redaction) = file_config.redaction {
+        effective_config.insert(
+            "redaction".to_string(),
+            serde_json::to_value(redaction).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref tracking) = file_config.tracking {
+        effective_config.insert(
+            "tracking".to_string(),
+            serde_json::to_value(tracking).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref trust) = file_config.trust {
+        effective_config.insert(
+            "trust".to_string(),
+            serde_json::to_value(trust).unwrap_or(Value::Null),
+        );
+    }
+
+    if !runtime_config.policy().rules.is_empty() {
+        effective_config.insert(
+            "policy".to_string(),
+            serde_json::to_value(runtime_config.policy()).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref jetbrains_plugin) = file_config.jetbrains_plugin {
+        effective_config.insert(
+            "jetbrains_plugin".to_string(),
+            serde_json::to_value(jetbrains_plugin).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref history) = file_config.history {
+        effective_config.insert(
+            "history".to_string(),
+            serde_json::to_value(history).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref org_config) = file_config.config {
+        effective_config.insert(
+            "config".to_string(),
+            serde_json::to_value(org_config).unwrap_or(Value::Null),
+        );
+    }
+
+    if let Some(ref prompt_hashing) = file_config.prompt_hashing {
+        effective_config.insert(
+            "prompt_hashing".to_string(),
+            masked_prompt_hashing(prompt_hashing),
+        );
+    }
 
     // Feature flags - show effective flags with defaults applied
     let flags_value = serde_json::to_value(runtime_config.get_feature_flags())
@@ -332,6 +651,8 @@ fn get_config_value(key: &str) -> Result<(), String> {
     if key_path.len() == 1 {
         let value = match key_path[0].as_str() {
             "git_path" => Value::String(runtime_config.git_cmd().to_string()),
+            "git" => serde_json::to_value(file_config.git.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
             "exclude_prompts_in_repositories" => {
                 if let Some(ref repos) = file_config.exclude_prompts_in_repositories {
                     serde_json::to_value(repos).unwrap()
@@ -391,7 +712,59 @@ fn get_config_value(key: &str) -> Result<(), String> {
                     Value::Null
                 }
             }
+            "api_max_rps" => {
+                serde_json::to_value(runtime_config.api_max_rps()).unwrap_or(Value::Null)
+            }
             "quiet" => Value::Bool(runtime_config.is_quiet()),
+            "verify_push" => Value::String(runtime_config.verify_push().as_str().to_string()),
+            "blame_parallelism" => Value::from(runtime_config.blame_parallelism()),
+            "attribution_granularity" => Value::String(
+                runtime_config
+                    .attribution_granularity()
+                    .as_str()
+                    .to_string(),
+            ),
+            "ai_assisted_threshold" => Value::from(runtime_config.ai_assisted_threshold()),
+            "capture_environment" => Value::Bool(runtime_config.capture_environment()),
+            "annotate_tags" => Value::Bool(runtime_config.annotate_tags()),
+            "exclude_paths" => {
+                serde_json::to_value(runtime_config.exclude_paths()).unwrap_or(Value::Null)
+            }
+            "notes_ref" => Value::String(runtime_config.notes_ref().to_string()),
+            "credential_backend" => Value::String(
+                crate::auth::credentials::configured_backend_kind(runtime_config)
+                    .as_str()
+                    .to_string(),
+            ),
+            "credential_file_fallback" => Value::Bool(runtime_config.credential_file_fallback()),
+            "watch_debounce_ms" => Value::from(runtime_config.watch_debounce().as_millis() as u64),
+            "report" => serde_json::to_value(file_config.report.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "events" => serde_json::to_value(file_config.events.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "dual_write" => masked_dual_write(&file_config.dual_write.clone().unwrap_or_default()),
+            "identities" => {
+                serde_json::to_value(file_config.identities.clone().unwrap_or_default())
+                    .unwrap_or(Value::Null)
+            }
+            "context_capture" => {
+                serde_json::to_value(file_config.context_capture.clone().unwrap_or_default())
+                    .unwrap_or(Value::Null)
+            }
+            "redaction" => serde_json::to_value(file_config.redaction.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "tracking" => serde_json::to_value(file_config.tracking.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "trust" => serde_json::to_value(file_config.trust.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "policy" => serde_json::to_value(runtime_config.policy()).unwrap_or(Value::Null),
+            "prompt_hashing" => {
+                masked_prompt_hashing(&file_config.prompt_hashing.clone().unwrap_or_default())
+            }
+            "history" => serde_json::to_value(file_config.history.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
+            "config" => serde_json::to_value(file_config.config.clone().unwrap_or_default())
+                .unwrap_or(Value::Null),
             _ => return Err(format!("Unknown config key: {}", key)),
         };
 
@@ -420,7 +793,54 @@ fn get_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err("Nested keys are only supported for feature_flags".to_string())
+    if key_path[0] == "report" {
+        return get_report_value(key);
+    }
+
+    if key_path[0] == "events" {
+        return get_events_value(key);
+    }
+
+    if key_path[0] == "dual_write" {
+        return get_dual_write_value(key);
+    }
+
+    if key_path[0] == "identities" {
+        return get_identities_value(key);
+    }
+
+    if key_path[0] == "context_capture" {
+        return get_context_capture_value(key);
+    }
+
+    if key_path[0] == "redaction" {
+        return get_redaction_value(key);
+    }
+
+    if key_path[0] == "tracking" {
+        return get_tracking_value(key);
+    }
+
+    if key_path[0] == "trust" {
+        return get_trust_value(key);
+    }
+
+    if key_path[0] == "prompt_hashing" {
+        return get_prompt_hashing_value(key);
+    }
+
+    if key_path[0] == "history" {
+        return get_history_value(key);
+    }
+
+    if key_path[0] == "config" {
+        return get_org_config_value(key);
+    }
+
+    Err(
+        "Nested keys are only supported for feature_flags, report, events, dual_write, identities, context_capture, redaction, tracking, trust, prompt_hashing, history and config"
+            .to_string(),
+    )
 }
 
 fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String> {
@@ -495,6 +915,109 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[update_channel]: {}", value);
             }
+            "verify_push" => {
+                let mode = crate::config::VerifyPushMode::parse(value).ok_or_else(|| {
+                    "Invalid verify_push value. Expected 'off', 'warn' or 'block'".to_string()
+                })?;
+                file_config.verify_push = Some(mode.as_str().to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[verify_push]: {}", mode.as_str());
+            }
+            "blame_parallelism" => {
+                let parallelism = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| format!("Invalid blame_parallelism '{}'", value))?;
+                file_config.blame_parallelism = Some(parallelism);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[blame_parallelism]: {}", parallelism);
+            }
+            "attribution_granularity" => {
+                let granularity =
+                    crate::config::AttributionGranularity::parse(value).ok_or_else(|| {
+                        format!(
+                            "Invalid attribution_granularity '{}'. Expected 'line' or 'char'",
+                            value
+                        )
+                    })?;
+                file_config.attribution_granularity = Some(granularity.as_str().to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[attribution_granularity]: {}", granularity.as_str());
+            }
+            "ai_assisted_threshold" => {
+                let threshold = value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(crate::config::parse_ai_assisted_threshold)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid ai_assisted_threshold '{}'. Expected a number in (0, 1]",
+                            value
+                        )
+                    })?;
+                file_config.ai_assisted_threshold = Some(threshold);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[ai_assisted_threshold]: {}", threshold);
+            }
+            "capture_environment" => {
+                let bool_value = parse_bool(value)?;
+                file_config.capture_environment = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[capture_environment]: {}", bool_value);
+            }
+            "annotate_tags" => {
+                let bool_value = parse_bool(value)?;
+                file_config.annotate_tags = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[annotate_tags]: {}", bool_value);
+            }
+            "exclude_paths" => {
+                let list = &mut file_config.exclude_paths;
+                if add_mode {
+                    let existing = list.get_or_insert_with(Vec::new);
+                    if !existing.iter().any(|v| v == value) {
+                        existing.push(value.to_string());
+                    }
+                } else {
+                    *list = Some(vec![value.to_string()]);
+                }
+                crate::config::save_file_config(&file_config)?;
+                log_array_changes(&[value.to_string()], add_mode);
+            }
+            "notes_ref" => {
+                let notes_ref = crate::config::parse_notes_ref(value).ok_or_else(|| {
+                    format!(
+                        "Invalid notes_ref '{}'. Expected a ref name under refs/notes/",
+                        value
+                    )
+                })?;
+                file_config.notes_ref = Some(notes_ref.clone());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[notes_ref]: {}", notes_ref);
+            }
+            "credential_backend" => {
+                let kind = crate::config::CredentialBackendKind::parse(value).ok_or_else(|| {
+                    "Invalid credential_backend value. Expected 'auto', 'keychain', 'secret-service', 'windows-credential-manager' or 'file'".to_string()
+                })?;
+                file_config.credential_backend = Some(kind.as_str().to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[credential_backend]: {}", kind.as_str());
+            }
+            "credential_file_fallback" => {
+                let bool_value = parse_bool(value)?;
+                file_config.credential_file_fallback = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[credential_file_fallback]: {}", bool_value);
+            }
+            "watch_debounce_ms" => {
+                let debounce = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid watch_debounce_ms '{}'", value))?;
+                file_config.watch_debounce_ms = Some(debounce);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[watch_debounce_ms]: {}", debounce);
+            }
             "feature_flags" => {
                 if add_mode {
                     return Err("Cannot use --add with feature_flags at top level. Use dot notation: feature_flags.key".to_string());
@@ -547,6 +1070,16 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[default_prompt_storage]: {}", value);
             }
+            "api_max_rps" => {
+                let rps: f64 = value
+                    .parse()
+                    .ok()
+                    .filter(|rps: &f64| rps.is_finite() && *rps > 0.0)
+                    .ok_or_else(|| "api_max_rps must be a positive number".to_string())?;
+                file_config.api_max_rps = Some(rps);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[api_max_rps]: {}", rps);
+            }
             "quiet" => {
                 let bool_value = parse_bool(value)?;
                 file_config.quiet = Some(bool_value);
@@ -609,7 +1142,54 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
         return Ok(());
     }
 
-    Err("Nested keys are only supported for feature_flags".to_string())
+    if key_path[0] == "report" {
+        return set_report_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "events" {
+        return set_events_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "dual_write" {
+        return set_dual_write_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "identities" {
+        return set_identities_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "context_capture" {
+        return set_context_capture_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "redaction" {
+        return set_redaction_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "tracking" {
+        return set_tracking_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "trust" {
+        return set_trust_value(&mut file_config, key, value, add_mode);
+    }
+
+    if key_path[0] == "prompt_hashing" {
+        return set_prompt_hashing_value(&mut file_config, key, value);
+    }
+
+    if key_path[0] == "history" {
+        return set_history_value(&mut file_config, key, value);
+    }
+
+    if key_path[0] == "config" {
+        return set_org_config_value(&mut file_config, key, value);
+    }
+
+    Err(
+        "Nested keys are only supported for feature_flags, report, events, dual_write, identities, context_capture, redaction, tracking, trust, prompt_hashing, history and config"
+            .to_string(),
+    )
 }
 
 fn unset_config_value(key: &str) -> Result<(), String> {
@@ -682,6 +1262,83 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [update_channel]: {}", v);
                 }
             }
+            "verify_push" => {
+                let old_value = file_config.verify_push.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [verify_push]: {}", v);
+                }
+            }
+            "blame_parallelism" => {
+                let old_value = file_config.blame_parallelism.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [blame_parallelism]: {}", v);
+                }
+            }
+            "attribution_granularity" => {
+                let old_value = file_config.attribution_granularity.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [attribution_granularity]: {}", v);
+                }
+            }
+            "ai_assisted_threshold" => {
+                let old_value = file_config.ai_assisted_threshold.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [ai_assisted_threshold]: {}", v);
+                }
+            }
+            "capture_environment" => {
+                let old_value = file_config.capture_environment.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [capture_environment]: {}", v);
+                }
+            }
+            "annotate_tags" => {
+                let old_value = file_config.annotate_tags.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [annotate_tags]: {}", v);
+                }
+            }
+            "exclude_paths" => {
+                let old_value = file_config.exclude_paths.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [exclude_paths]: {:?}", v);
+                }
+            }
+            "notes_ref" => {
+                let old_value = file_config.notes_ref.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [notes_ref]: {}", v);
+                }
+            }
+            "credential_backend" => {
+                let old_value = file_config.credential_backend.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [credential_backend]: {}", v);
+                }
+            }
+            "credential_file_fallback" => {
+                let old_value = file_config.credential_file_fallback.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [credential_file_fallback]: {}", v);
+                }
+            }
+            "watch_debounce_ms" => {
+                let old_value = file_config.watch_debounce_ms.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [watch_debounce_ms]: {}", v);
+                }
+            }
             "feature_flags" => {
                 let old_value = file_config.feature_flags.take();
                 crate::config::save_file_config(&file_config)?;
@@ -717,6 +1374,13 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [default_prompt_storage]: {}", v);
                 }
             }
+            "api_max_rps" => {
+                let old_value = file_config.api_max_rps.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [api_max_rps]: {}", v);
+                }
+            }
             "quiet" => {
                 let old_value = file_config.quiet.take();
                 crate::config::save_file_config(&file_config)?;
@@ -724,8 +1388,122 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [quiet]: {}", v);
                 }
             }
-            _ => return Err(format!("Unknown config key: {}", key)),
-        }
+            "report" => {
+                let old_value = file_config.report.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [report]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "events" => {
+                let old_value = file_config.events.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [events]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "identities" => {
+                let old_value = file_config.identities.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [identities]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "context_capture" => {
+                let old_value = file_config.context_capture.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [context_capture]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "redaction" => {
+                let old_value = file_config.redaction.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [redaction]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "tracking" => {
+                let old_value = file_config.tracking.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [tracking]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "dual_write" => {
+                let old_value = file_config.dual_write.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [dual_write]: {}", masked_dual_write(&v));
+                }
+            }
+            "trust" => {
+                let old_value = file_config.trust.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [trust]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "policy" => {
+                let old_value = file_config.policy.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [policy]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "prompt_hashing" => {
+                let old_value = file_config.prompt_hashing.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [prompt_hashing]: {}", masked_prompt_hashing(&v));
+                }
+            }
+            "history" => {
+                let old_value = file_config.history.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [history]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            "config" => {
+                let old_value = file_config.config.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!(
+                        "- [config]: {}",
+                        serde_json::to_string(&v).unwrap_or_default()
+                    );
+                }
+            }
+            _ => return Err(format!("Unknown config key: {}", key)),
+        }
 
         return Ok(());
     }
@@ -784,7 +1562,968 @@ fn unset_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err("Nested keys are only supported for feature_flags".to_string())
+    if key_path[0] == "report" {
+        return unset_report_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "events" {
+        return unset_events_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "dual_write" {
+        return unset_dual_write_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "identities" {
+        return unset_identities_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "context_capture" {
+        return unset_context_capture_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "redaction" {
+        return unset_redaction_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "tracking" {
+        return unset_tracking_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "trust" {
+        return unset_trust_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "prompt_hashing" {
+        return unset_prompt_hashing_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "history" {
+        return unset_history_value(&mut file_config, key);
+    }
+
+    if key_path[0] == "config" {
+        return unset_org_config_value(&mut file_config, key);
+    }
+
+    Err(
+        "Nested keys are only supported for feature_flags, report, events, dual_write, identities, context_capture, redaction, tracking, trust, prompt_hashing, history and config"
+            .to_string(),
+    )
+}
+
+fn get_report_value(key: &str) -> Result<(), String> {
+    let value = match key {
+        "report.timezone" => Value::String(
+            crate::config::Config::get()
+                .report_timezone()
+                .unwrap_or("UTC")
+                .to_string(),
+        ),
+        "report.bot_authors" => {
+            serde_json::to_value(crate::config::Config::get().report_bot_authors())
+                .unwrap_or(Value::Array(vec![]))
+        }
+        "report.authors" => serde_json::to_value(crate::config::Config::get().report_authors())
+            .unwrap_or(Value::Array(vec![])),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_report_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let report = file_config.report.get_or_insert_with(Default::default);
+    let list = match key {
+        "report.timezone" => {
+            value.parse::<crate::reporting::buckets::ReportTimezone>()?;
+            report.timezone = Some(value.to_string());
+            crate::config::save_file_config(file_config)?;
+            eprintln!("[{}]: {}", key, value);
+            return Ok(());
+        }
+        "report.bot_authors" => &mut report.bot_authors,
+        "report.authors" => &mut report.authors,
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    if add_mode {
+        let existing = list.get_or_insert_with(Vec::new);
+        if !existing.iter().any(|v| v == value) {
+            existing.push(value.to_string());
+        }
+    } else {
+        *list = Some(vec![value.to_string()]);
+    }
+    crate::config::save_file_config(file_config)?;
+    log_array_changes(&[value.to_string()], add_mode);
+    Ok(())
+}
+
+fn unset_report_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(report) = file_config.report.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "report.timezone" => report.timezone.take(),
+        "report.bot_authors" => report.bot_authors.take().map(|v| format!("{:?}", v)),
+        "report.authors" => report.authors.take().map(|v| format!("{:?}", v)),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_events_value(key: &str) -> Result<(), String> {
+    let value = match key {
+        "events.webhook_url" => crate::config::Config::get()
+            .events_webhook_url()
+            .map(|url| Value::String(url.to_string()))
+            .unwrap_or(Value::Null),
+        "events.webhook_events" => {
+            serde_json::to_value(crate::config::Config::get().events_webhook_events())
+                .unwrap_or(Value::Array(vec![]))
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_events_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let events = file_config.events.get_or_insert_with(Default::default);
+    match key {
+        "events.webhook_url" => {
+            if !value.starts_with("http://") && !value.starts_with("https://") {
+                return Err(format!(
+                    "Invalid webhook URL '{}': must start with http:// or https://",
+                    value
+                ));
+            }
+            events.webhook_url = Some(value.to_string());
+            crate::config::save_file_config(file_config)?;
+            eprintln!("[{}]: {}", key, value);
+            Ok(())
+        }
+        "events.webhook_events" => {
+            if crate::observability::webhook::LocalEventKind::from_name(value).is_none() {
+                return Err(format!(
+                    "Unknown event '{}': expected one of {}",
+                    value,
+                    crate::observability::webhook::LocalEventKind::ALL_NAMES.join(", ")
+                ));
+            }
+            let list = &mut events.webhook_events;
+            if add_mode {
+                let existing = list.get_or_insert_with(Vec::new);
+                if !existing.iter().any(|v| v == value) {
+                    existing.push(value.to_string());
+                }
+            } else {
+                *list = Some(vec![value.to_string()]);
+            }
+            crate::config::save_file_config(file_config)?;
+            log_array_changes(&[value.to_string()], add_mode);
+            Ok(())
+        }
+        _ => Err(format!("Unknown config key: {}", key)),
+    }
+}
+
+fn unset_events_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(events) = file_config.events.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "events.webhook_url" => events.webhook_url.take(),
+        "events.webhook_events" => events.webhook_events.take().map(|v| format!("{:?}", v)),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_dual_write_value(key: &str) -> Result<(), String> {
+    let config = crate::config::Config::get();
+    let value = match key {
+        "dual_write.destination" => config
+            .dual_write_destination()
+            .map(|destination| Value::String(destination.to_string()))
+            .unwrap_or(Value::Null),
+        "dual_write.headers" => Value::Object(
+            config
+                .dual_write_headers()
+                .iter()
+                .map(|(name, value)| (name.clone(), Value::String(mask_api_key(value))))
+                .collect(),
+        ),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// `dual_write.headers` takes `Name=value`; without --add the headers are replaced
+/// by the single given one.
+fn set_dual_write_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let dual_write = file_config.dual_write.get_or_insert_with(Default::default);
+    match key {
+        "dual_write.destination" => {
+            if !crate::api::sink::is_valid_destination(value) {
+                return Err(format!(
+                    "Invalid destination '{}': must be an absolute directory, an http(s) URL, or an s3:// or gs:// URL",
+                    value
+                ));
+            }
+            dual_write.destination = Some(value.to_string());
+            crate::config::save_file_config(file_config)?;
+            eprintln!("[{}]: {}", key, value);
+        }
+        "dual_write.headers" => {
+            let (name, header_value) = value
+                .split_once('=')
+                .map(|(n, v)| (n.trim(), v.trim()))
+                .filter(|(n, _)| !n.is_empty())
+                .ok_or_else(|| "dual_write.headers expects \"Name=value\"".to_string())?;
+            let headers = dual_write.headers.get_or_insert_with(Default::default);
+            if !add_mode {
+                headers.clear();
+            }
+            headers.insert(name.to_string(), header_value.to_string());
+            crate::config::save_file_config(file_config)?;
+            eprintln!(
+                "{}[{}]: {}={}",
+                if add_mode { "+ " } else { "" },
+                key,
+                name,
+                mask_api_key(header_value)
+            );
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    Ok(())
+}
+
+fn unset_dual_write_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(dual_write) = file_config.dual_write.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "dual_write.destination" => dual_write.destination.take(),
+        "dual_write.headers" => dual_write
+            .headers
+            .take()
+            .map(|headers| format!("{:?}", headers.keys().collect::<Vec<_>>())),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_identities_value(key: &str) -> Result<(), String> {
+    let value = match key {
+        "identities.aliases" => {
+            serde_json::to_value(crate::config::Config::get().identity_aliases())
+                .unwrap_or(Value::Null)
+        }
+        "identities.lookup_url" => crate::config::Config::get()
+            .identity_lookup_url()
+            .map(|url| Value::String(url.to_string()))
+            .unwrap_or(Value::Null),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// `identities.aliases` takes `Canonical Name <email>=alias`; without --add the
+/// canonical identity's alias list is replaced.
+fn set_identities_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let identities = file_config.identities.get_or_insert_with(Default::default);
+    match key {
+        "identities.lookup_url" => {
+            if !value.starts_with("http://") && !value.starts_with("https://") {
+                return Err(format!(
+                    "Invalid lookup URL '{}': must start with http:// or https://",
+                    value
+                ));
+            }
+            identities.lookup_url = Some(value.to_string());
+        }
+        "identities.aliases" => {
+            let (canonical, alias) = value
+                .split_once('=')
+                .map(|(c, a)| (c.trim(), a.trim()))
+                .filter(|(c, a)| !c.is_empty() && !a.is_empty())
+                .ok_or_else(|| {
+                    "identities.aliases expects \"Canonical Name <email>=alias\"".to_string()
+                })?;
+            let aliases = identities
+                .aliases
+                .get_or_insert_with(Default::default)
+                .entry(canonical.to_string())
+                .or_default();
+            if !add_mode {
+                aliases.clear();
+            }
+            if !aliases.iter().any(|a| a == alias) {
+                aliases.push(alias.to_string());
+            }
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("{}[{}]: {}", if add_mode { "+ " } else { "" }, key, value);
+    Ok(())
+}
+
+fn unset_identities_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(identities) = file_config.identities.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "identities.aliases" => identities
+            .aliases
+            .take()
+            .map(|v| serde_json::to_string(&v).unwrap_or_default()),
+        "identities.lookup_url" => identities.lookup_url.take(),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_context_capture_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().context_capture();
+    let value = match key {
+        "context_capture.enabled" => Value::Bool(settings.enabled),
+        "context_capture.context_lines" => Value::from(settings.context_lines),
+        "context_capture.max_bytes" => Value::from(settings.max_bytes),
+        "context_capture.exclude" => {
+            serde_json::to_value(&settings.exclude).unwrap_or(Value::Array(vec![]))
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_context_capture_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let context_capture = file_config
+        .context_capture
+        .get_or_insert_with(Default::default);
+    match key {
+        "context_capture.enabled" => {
+            context_capture.enabled = Some(parse_bool(value)?);
+        }
+        "context_capture.context_lines" => {
+            let lines = value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid line count '{}'", value))?;
+            context_capture.context_lines = Some(lines);
+        }
+        "context_capture.max_bytes" => {
+            let bytes = value
+                .parse::<usize>()
+                .ok()
+                .filter(|bytes| *bytes > 0)
+                .ok_or_else(|| format!("Invalid byte count '{}'", value))?;
+            context_capture.max_bytes = Some(bytes);
+        }
+        "context_capture.exclude" => {
+            let list = &mut context_capture.exclude;
+            if add_mode {
+                let existing = list.get_or_insert_with(Vec::new);
+                if !existing.iter().any(|v| v == value) {
+                    existing.push(value.to_string());
+                }
+            } else {
+                *list = Some(vec![value.to_string()]);
+            }
+            crate::config::save_file_config(file_config)?;
+            log_array_changes(&[value.to_string()], add_mode);
+            return Ok(());
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("[{}]: {}", key, value);
+    Ok(())
+}
+
+fn unset_context_capture_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(context_capture) = file_config.context_capture.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "context_capture.enabled" => context_capture.enabled.take().map(|v| v.to_string()),
+        "context_capture.context_lines" => {
+            context_capture.context_lines.take().map(|v| v.to_string())
+        }
+        "context_capture.max_bytes" => context_capture.max_bytes.take().map(|v| v.to_string()),
+        "context_capture.exclude" => context_capture.exclude.take().map(|v| format!("{:?}", v)),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_redaction_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().redaction();
+    let value = match key {
+        "redaction.enabled" => Value::Bool(settings.enabled),
+        "redaction.entropy" => Value::Bool(settings.entropy),
+        "redaction.emails" => Value::Bool(settings.emails),
+        "redaction.rules" => serde_json::to_value(&settings.rules).unwrap_or(Value::Null),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// `redaction.rules` takes `name=regex`; without --add the rules are replaced by
+/// the single given rule.
+fn set_redaction_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let redaction = file_config.redaction.get_or_insert_with(Default::default);
+    match key {
+        "redaction.enabled" => redaction.enabled = Some(parse_bool(value)?),
+        "redaction.entropy" => redaction.entropy = Some(parse_bool(value)?),
+        "redaction.emails" => redaction.emails = Some(parse_bool(value)?),
+        "redaction.rules" => {
+            let (name, pattern) = value
+                .split_once('=')
+                .map(|(n, p)| (n.trim(), p.trim()))
+                .filter(|(n, p)| !n.is_empty() && !p.is_empty())
+                .ok_or_else(|| "redaction.rules expects \"name=regex\"".to_string())?;
+            regex::Regex::new(pattern)
+                .map_err(|e| format!("Invalid pattern for rule '{}': {}", name, e))?;
+            let rules = redaction.rules.get_or_insert_with(Default::default);
+            if !add_mode {
+                rules.clear();
+            }
+            rules.insert(name.to_string(), pattern.to_string());
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("{}[{}]: {}", if add_mode { "+ " } else { "" }, key, value);
+    Ok(())
+}
+
+fn unset_redaction_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(redaction) = file_config.redaction.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "redaction.enabled" => redaction.enabled.take().map(|v| v.to_string()),
+        "redaction.entropy" => redaction.entropy.take().map(|v| v.to_string()),
+        "redaction.emails" => redaction.emails.take().map(|v| v.to_string()),
+        "redaction.rules" => redaction
+            .rules
+            .take()
+            .map(|v| serde_json::to_string(&v).unwrap_or_default()),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_tracking_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().tracking();
+    let value = match key {
+        "tracking.exclude" => serde_json::to_value(&settings.exclude),
+        "tracking.generated" => serde_json::to_value(&settings.generated),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    .unwrap_or(Value::Array(vec![]));
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_tracking_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let tracking = file_config.tracking.get_or_insert_with(Default::default);
+    let list = match key {
+        "tracking.exclude" => &mut tracking.exclude,
+        "tracking.generated" => &mut tracking.generated,
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    if add_mode {
+        let existing = list.get_or_insert_with(Vec::new);
+        if !existing.iter().any(|v| v == value) {
+            existing.push(value.to_string());
+        }
+    } else {
+        *list = Some(vec![value.to_string()]);
+    }
+    crate::config::save_file_config(file_config)?;
+    log_array_changes(&[value.to_string()], add_mode);
+    Ok(())
+}
+
+fn unset_tracking_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(tracking) = file_config.tracking.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "tracking.exclude" => tracking.exclude.take(),
+        "tracking.generated" => tracking.generated.take(),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {:?}", key, v);
+    }
+    Ok(())
+}
+
+fn get_trust_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().trust();
+    let value = match key {
+        "trust.trusted" => serde_json::to_value(&settings.trusted),
+        "trust.untrusted" => serde_json::to_value(&settings.untrusted),
+        "trust.default" => Ok(Value::String(settings.default.as_str().to_string())),
+        "trust.weights" => {
+            let weights: BTreeMap<&str, f64> = TrustTier::ALL
+                .iter()
+                .map(|tier| (tier.as_str(), settings.weight(*tier)))
+                .collect();
+            serde_json::to_value(weights)
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    .unwrap_or(Value::Null);
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// `trust.weights` takes `tier=weight`; without --add the weights are replaced by
+/// the single given one.
+fn set_trust_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+    add_mode: bool,
+) -> Result<(), String> {
+    let trust = file_config.trust.get_or_insert_with(Default::default);
+    match key {
+        "trust.trusted" | "trust.untrusted" => {
+            let list = if key == "trust.trusted" {
+                &mut trust.trusted
+            } else {
+                &mut trust.untrusted
+            };
+            if add_mode {
+                let existing = list.get_or_insert_with(Vec::new);
+                if !existing.iter().any(|v| v == value) {
+                    existing.push(value.to_string());
+                }
+            } else {
+                *list = Some(vec![value.to_string()]);
+            }
+            crate::config::save_file_config(file_config)?;
+            log_array_changes(&[value.to_string()], add_mode);
+            return Ok(());
+        }
+        "trust.default" => {
+            let tier = TrustTier::parse(value).ok_or_else(|| {
+                "trust.default must be trusted, standard or untrusted".to_string()
+            })?;
+            trust.default = Some(tier.as_str().to_string());
+        }
+        "trust.weights" => {
+            let (tier, weight) = value
+                .split_once('=')
+                .ok_or_else(|| "trust.weights expects \"tier=weight\"".to_string())?;
+            let tier = TrustTier::parse(tier)
+                .ok_or_else(|| format!("Unknown trust tier '{}'", tier.trim()))?;
+            let weight = weight
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| *weight >= 0.0)
+                .ok_or_else(|| format!("Invalid weight '{}'", weight.trim()))?;
+            let weights = trust.weights.get_or_insert_with(Default::default);
+            if !add_mode {
+                weights.clear();
+            }
+            weights.insert(tier.as_str().to_string(), weight);
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("{}[{}]: {}", if add_mode { "+ " } else { "" }, key, value);
+    Ok(())
+}
+
+fn unset_trust_value(file_config: &mut crate::config::FileConfig, key: &str) -> Result<(), String> {
+    let Some(trust) = file_config.trust.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "trust.trusted" => trust.trusted.take().map(|v| format!("{:?}", v)),
+        "trust.untrusted" => trust.untrusted.take().map(|v| format!("{:?}", v)),
+        "trust.default" => trust.default.take(),
+        "trust.weights" => trust
+            .weights
+            .take()
+            .map(|v| serde_json::to_string(&v).unwrap_or_default()),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+/// `dual_write` with its header values (usually credentials) masked like API keys
+fn masked_dual_write(config: &crate::config::DualWriteConfig) -> Value {
+    let mut masked = config.clone();
+    for value in masked
+        .headers
+        .iter_mut()
+        .flat_map(|headers| headers.values_mut())
+    {
+        *value = mask_api_key(value);
+    }
+    serde_json::to_value(masked).unwrap_or(Value::Null)
+}
+
+/// `prompt_hashing` with its salts masked like API keys
+fn masked_prompt_hashing(config: &crate::config::PromptHashingConfig) -> Value {
+    let mut masked = config.clone();
+    let previous = masked.previous.iter_mut().flatten();
+    for salt in std::iter::once(&mut masked.salt).chain(previous.map(|scheme| &mut scheme.salt)) {
+        if let Some(value) = salt.as_mut() {
+            *value = mask_api_key(value);
+        }
+    }
+    serde_json::to_value(masked).unwrap_or(Value::Null)
+}
+
+fn get_history_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().history();
+    let value = match key {
+        "history.deepen" => Value::Bool(settings.deepen),
+        "history.deepen_limit" => Value::from(settings.deepen_limit),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_history_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let history = file_config.history.get_or_insert_with(Default::default);
+    match key {
+        "history.deepen" => {
+            history.deepen = Some(parse_bool(value)?);
+        }
+        "history.deepen_limit" => {
+            let limit = value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid commit count '{}'", value))?;
+            history.deepen_limit = Some(limit);
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("[{}]: {}", key, value);
+    Ok(())
+}
+
+fn unset_history_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(history) = file_config.history.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "history.deepen" => history.deepen.take().map(|v| v.to_string()),
+        "history.deepen_limit" => history.deepen_limit.take().map(|v| v.to_string()),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_org_config_value(key: &str) -> Result<(), String> {
+    let file_config = crate::config::load_file_config_public()?;
+    let org_config = file_config.config.unwrap_or_default();
+    let value = match key {
+        "config.remote_url" => org_config
+            .remote_url
+            .map(Value::String)
+            .unwrap_or(Value::Null),
+        "config.ttl_secs" => org_config.ttl_secs.map(Value::from).unwrap_or(Value::Null),
+        "config.allow_endpoints" => org_config
+            .allow_endpoints
+            .map(Value::Bool)
+            .unwrap_or(Value::Null),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn set_org_config_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let org_config = file_config.config.get_or_insert_with(Default::default);
+    match key {
+        "config.remote_url" => {
+            if !crate::org_config::is_allowed_url(value) {
+                return Err(format!(
+                    "Invalid config.remote_url '{}': expected an https URL (http only for localhost)",
+                    value
+                ));
+            }
+            org_config.remote_url = Some(value.to_string());
+        }
+        "config.ttl_secs" => {
+            let ttl = value
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid number of seconds '{}'", value))?;
+            org_config.ttl_secs = Some(ttl);
+        }
+        "config.allow_endpoints" => {
+            org_config.allow_endpoints = Some(parse_bool(value)?);
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    crate::config::save_file_config(file_config)?;
+    eprintln!("[{}]: {}", key, value);
+    Ok(())
+}
+
+fn unset_org_config_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(org_config) = file_config.config.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "config.remote_url" => org_config.remote_url.take(),
+        "config.ttl_secs" => org_config.ttl_secs.take().map(|v| v.to_string()),
+        "config.allow_endpoints" => org_config.allow_endpoints.take().map(|v| v.to_string()),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
+}
+
+fn get_prompt_hashing_value(key: &str) -> Result<(), String> {
+    let settings = crate::config::Config::get().prompt_hashing();
+    let value = match key {
+        "prompt_hashing.algorithm" => {
+            Value::String(settings.current.algorithm.as_str().to_string())
+        }
+        "prompt_hashing.salt" => settings
+            .current
+            .salt
+            .as_deref()
+            .map(|salt| Value::String(mask_api_key(salt)))
+            .unwrap_or(Value::Null),
+        "prompt_hashing.previous" => Value::Array(
+            settings
+                .previous
+                .iter()
+                .map(|scheme| {
+                    serde_json::json!({
+                        "algorithm": scheme.algorithm.as_str(),
+                        "salt": scheme.salt.as_deref().map(mask_api_key),
+                    })
+                })
+                .collect(),
+        ),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize value: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Changing the algorithm or salt is a rotation: the replaced scheme moves to the
+/// front of `prompt_hashing.previous` so ids already in working logs still resolve.
+fn set_prompt_hashing_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let prompt_hashing = file_config
+        .prompt_hashing
+        .get_or_insert_with(Default::default);
+    let scheme_of =
+        |config: &crate::config::PromptHashingConfig| crate::config::PromptHashSchemeConfig {
+            algorithm: config.algorithm.clone(),
+            salt: config.salt.clone(),
+        };
+    let retired = scheme_of(prompt_hashing);
+    match key {
+        "prompt_hashing.algorithm" => {
+            let algorithm = crate::config::PromptHashAlgorithm::parse(value).ok_or_else(|| {
+                format!(
+                    "Invalid prompt_hashing.algorithm '{}'. Expected 'sha256', 'sha512', or 'sha3-256'",
+                    value
+                )
+            })?;
+            prompt_hashing.algorithm = Some(algorithm.as_str().to_string());
+        }
+        "prompt_hashing.salt" => {
+            if value.is_empty() {
+                return Err("prompt_hashing.salt cannot be empty; unset it instead".to_string());
+            }
+            prompt_hashing.salt = Some(value.to_string());
+        }
+        _ => return Err(format!("Unknown config key: {}", key)),
+    }
+    let rotated = scheme_of(prompt_hashing) != retired;
+    if rotated {
+        let previous = prompt_hashing.previous.get_or_insert_with(Vec::new);
+        previous.retain(|scheme| *scheme != retired);
+        previous.insert(0, retired);
+    }
+    crate::config::save_file_config(file_config)?;
+    let shown = if key == "prompt_hashing.salt" {
+        mask_api_key(value)
+    } else {
+        value.to_string()
+    };
+    eprintln!("[{}]: {}", key, shown);
+    if rotated {
+        eprintln!("+ [prompt_hashing.previous]: replaced scheme kept for reading");
+    }
+    Ok(())
+}
+
+fn unset_prompt_hashing_value(
+    file_config: &mut crate::config::FileConfig,
+    key: &str,
+) -> Result<(), String> {
+    let Some(prompt_hashing) = file_config.prompt_hashing.as_mut() else {
+        return Err(format!("Config key not found: {}", key));
+    };
+    let old_value = match key {
+        "prompt_hashing.algorithm" => prompt_hashing.algorithm.take(),
+        "prompt_hashing.salt" => prompt_hashing.salt.take().map(|salt| mask_api_key(&salt)),
+        "prompt_hashing.previous" => prompt_hashing
+            .previous
+            .take()
+            .map(|previous| format!("{} schemes", previous.len())),
+        _ => return Err(format!("Unknown config key: {}", key)),
+    };
+    crate::config::save_file_config(file_config)?;
+    if let Some(v) = old_value {
+        eprintln!("- [{}]: {}", key, v);
+    }
+    Ok(())
 }
 
 fn parse_key_path(key: &str) -> Vec<String> {
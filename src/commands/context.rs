@@ -0,0 +1,384 @@
+//! `git-ai context --file <f> --lines <a>,<b>`: why a span of code exists, in a
+//! form meant to be handed back to an agent. Gathers the sessions that wrote the
+//! lines (with their transcripts, from the notes or the local prompt database),
+//! the commits blame attributes them to, and every commit that touched them.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::prompt_interning::resolve_interned_messages;
+use crate::authorship::transcript::Message;
+use crate::commands::blame::{BlameHunk, GitAiBlameOptions};
+use crate::commands::explain::{is_uncommitted, repo_relative_path};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// An agent session that wrote some of the lines
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ContextSession {
+    pub prompt_id: String,
+    pub tool: String,
+    pub model: String,
+    /// The agent's own id for the session
+    pub session_id: String,
+    pub human_author: Option<String>,
+    /// Lines in the requested span this session wrote
+    pub lines: Vec<u32>,
+    /// What the user asked, in order
+    pub prompts: Vec<String>,
+    /// The full transcript, when one was stored
+    pub messages: Vec<Message>,
+}
+
+/// A commit blame attributes lines in the span to
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ContextCommit {
+    pub sha: String,
+    pub subject: String,
+    pub author: String,
+    /// Unix timestamp
+    pub author_time: i64,
+    /// Line ranges (inclusive) in the span that come from this commit
+    pub lines: Vec<(u32, u32)>,
+}
+
+/// A commit that changed lines in the span at some point
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RelatedCommit {
+    pub sha: String,
+    pub subject: String,
+    /// Whether it has an authorship note with AI sessions
+    pub ai: bool,
+}
+
+/// Output of `git-ai context --json`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ContextOutput {
+    pub file: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub sessions: Vec<ContextSession>,
+    pub commits: Vec<ContextCommit>,
+    /// Every commit that touched the span, newest first
+    pub related_commits: Vec<RelatedCommit>,
+}
+
+pub fn handle_context(args: &[String]) {
+    let (file, start, end, json) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai context --file <path> --lines <start>[,<end>] [--json]");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let context = match line_context(&repo, &file, start, end) {
+        Ok(context) => context,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&context) {
+            Ok(out) => println!("{}", out),
+            Err(e) => {
+                eprintln!("Failed to serialize context: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", context.render());
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(String, u32, u32, bool), String> {
+    let mut file = None;
+    let mut lines = None;
+    let mut json = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--file" | "--lines" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("missing value for {}", args[i]))?;
+                if args[i] == "--file" {
+                    file = Some(value.clone());
+                } else {
+                    lines = Some(value.clone());
+                }
+                i += 2;
+            }
+            "--json" => {
+                json = true;
+                i += 1;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+
+    let file = file.ok_or("missing --file")?;
+    let lines = lines.ok_or("missing --lines")?;
+    let parse_line = |value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|line| *line > 0)
+            .ok_or_else(|| format!("invalid line number '{}'", value))
+    };
+    let (start, end) = match lines.split_once(',') {
+        Some((start, end)) => (parse_line(start)?, parse_line(end)?),
+        None => {
+            let line = parse_line(&lines)?;
+            (line, line)
+        }
+    };
+    if end < start {
+        return Err(format!("invalid line range {}: end before start", lines));
+    }
+    Ok((file, start, end, json))
+}
+
+fn line_context(
+    repo: &Repository,
+    file: &str,
+    start: u32,
+    end: u32,
+) -> Result<ContextOutput, GitAiError> {
+    let file = repo_relative_path(repo, file)?;
+    let line_count = std::fs::read_to_string(repo.workdir()?.join(&file))?
+        .lines()
+        .count() as u32;
+    if start > line_count {
+        return Err(GitAiError::Generic(format!(
+            "{} has {} lines, can't start at line {}",
+            file, line_count, start
+        )));
+    }
+    let end = end.min(line_count);
+
+    let options = GitAiBlameOptions {
+        line_ranges: vec![(start, end)],
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let (line_authors, mut prompt_records) = repo.blame(&file, &options)?;
+    resolve_interned_messages(repo, prompt_records.values_mut());
+    let hunks = repo.blame_hunks(&file, start, end, &options)?;
+
+    let mut session_lines: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    for (line, author) in &line_authors {
+        if prompt_records.contains_key(author) {
+            session_lines.entry(author.clone()).or_default().push(*line);
+        }
+    }
+    let sessions = session_lines
+        .into_iter()
+        .map(|(prompt_id, mut lines)| {
+            lines.sort_unstable();
+            session(&prompt_id, &prompt_records[&prompt_id], lines)
+        })
+        .collect();
+
+    let commits = blamed_commits(repo, &hunks)?;
+    let related_commits = related_commits(repo, &file, start, end, &hunks);
+
+    Ok(ContextOutput {
+        file,
+        start_line: start,
+        end_line: end,
+        sessions,
+        commits,
+        related_commits,
+    })
+}
+
+fn session(prompt_id: &str, record: &PromptRecord, lines: Vec<u32>) -> ContextSession {
+    // Notes may omit the transcript (prompt storage off, or stored in CAS)
+    let messages = if record.messages.is_empty() {
+        InternalDatabase::global()
+            .ok()
+            .and_then(|db| db.lock().ok()?.get_prompt(prompt_id).ok()?)
+            .map(|stored| stored.messages.messages)
+            .unwrap_or_default()
+    } else {
+        record.messages.clone()
+    };
+    let prompts = messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::User { text, .. } if !text.trim().is_empty() => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
+
+    ContextSession {
+        prompt_id: prompt_id.to_string(),
+        tool: record.agent_id.tool.clone(),
+        model: record.agent_id.model.clone(),
+        session_id: record.agent_id.id.clone(),
+        human_author: record.human_author.clone(),
+        lines,
+        prompts,
+        messages,
+    }
+}
+
+/// The committed hunks grouped by commit, in the order they first appear
+fn blamed_commits(
+    repo: &Repository,
+    hunks: &[BlameHunk],
+) -> Result<Vec<ContextCommit>, GitAiError> {
+    let mut commits: Vec<ContextCommit> = Vec::new();
+    for hunk in hunks.iter().filter(|hunk| !is_uncommitted(hunk)) {
+        match commits.iter_mut().find(|c| c.sha == hunk.commit_sha) {
+            Some(commit) => commit.lines.push(hunk.range),
+            None => commits.push(ContextCommit {
+                sha: hunk.commit_sha.clone(),
+                subject: String::new(),
+                author: format!("{} <{}>", hunk.original_author, hunk.author_email),
+                author_time: hunk.author_time,
+                lines: vec![hunk.range],
+            }),
+        }
+    }
+
+    if !commits.is_empty() {
+        let mut args = repo.global_args_for_exec();
+        args.extend(["log", "--no-walk=unsorted", "--format=%s"].map(String::from));
+        args.extend(commits.iter().map(|c| c.sha.clone()));
+        let output = exec_git(&args)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for (commit, subject) in commits.iter_mut().zip(stdout.lines()) {
+            commit.subject = subject.to_string();
+        }
+    }
+    Ok(commits)
+}
+
+/// Every commit that changed the span, from `git log -L` at HEAD
+fn related_commits(
+    repo: &Repository,
+    file: &str,
+    start: u32,
+    end: u32,
+    hunks: &[BlameHunk],
+) -> Vec<RelatedCommit> {
+    // Nothing to trace for lines that only exist in the working tree
+    if hunks.iter().all(is_uncommitted) {
+        return Vec::new();
+    }
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "log".to_string(),
+        format!("-L{},{}:{}", start, end, file),
+        "--no-patch".to_string(),
+        "--format=%H%x09%s".to_string(),
+        "HEAD".to_string(),
+    ]);
+    // Like explain's history, this is best effort: uncommitted edits that shift
+    // the span, renames or shallow clones can make `log -L` fail
+    let Ok(output) = exec_git(&args) else {
+        return Vec::new();
+    };
+    let mut commits: Vec<RelatedCommit> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (sha, subject) = line.split_once('\t')?;
+            Some(RelatedCommit {
+                sha: sha.to_string(),
+                subject: subject.to_string(),
+                ai: false,
+            })
+        })
+        .collect();
+    for commit in &mut commits {
+        commit.ai =
+            get_authorship(repo, &commit.sha).is_some_and(|log| !log.metadata.prompts.is_empty());
+    }
+    commits
+}
+
+impl ContextOutput {
+    fn render(&self) -> String {
+        let mut out = format!("{}:{}-{}\n", self.file, self.start_line, self.end_line);
+
+        if self.sessions.is_empty() {
+            out.push_str("\nNo AI sessions wrote these lines.\n");
+        }
+        for session in &self.sessions {
+            out.push_str(&format!(
+                "\nSession {} ({}, {}) wrote {} line{}\n",
+                session.prompt_id,
+                session.tool,
+                session.model,
+                session.lines.len(),
+                if session.lines.len() == 1 { "" } else { "s" }
+            ));
+            for prompt in &session.prompts {
+                let prompt = prompt.split_whitespace().collect::<Vec<_>>().join(" ");
+                out.push_str(&format!("  > {}\n", prompt));
+            }
+        }
+
+        if !self.commits.is_empty() {
+            out.push_str("\nCommitted in:\n");
+            for commit in &self.commits {
+                out.push_str(&format!("  {} {}\n", &commit.sha[..7], commit.subject));
+            }
+        }
+        if !self.related_commits.is_empty() {
+            out.push_str("\nHistory:\n");
+            for commit in &self.related_commits {
+                out.push_str(&format!(
+                    "  {} {}{}\n",
+                    &commit.sha[..7],
+                    commit.subject,
+                    if commit.ai { " [AI]" } else { "" }
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args(&["--file", "src/lib.rs", "--lines", "3,7", "--json"])).unwrap(),
+            ("src/lib.rs".to_string(), 3, 7, true)
+        );
+        assert_eq!(
+            parse_args(&args(&["--lines", "5", "--file", "a.rs"])).unwrap(),
+            ("a.rs".to_string(), 5, 5, false)
+        );
+        assert!(parse_args(&args(&["--file", "a.rs", "--lines", "7,3"])).is_err());
+        assert!(parse_args(&args(&["--file", "a.rs", "--lines", "0"])).is_err());
+        assert!(parse_args(&args(&["--file", "a.rs"])).is_err());
+    }
+}
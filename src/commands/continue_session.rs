@@ -14,6 +14,8 @@ use crate::commands::search::{
 use crate::error::GitAiError;
 use crate::git::find_repository_in_path;
 use crate::git::repository::{Repository, exec_git};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::io::{BufRead, IsTerminal, Write};
@@ -1049,12 +1051,63 @@ fn format_context_block(ctx: &SessionContext) -> String {
     output
 }
 
+/// `git-ai continue --json` output
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ContinueJsonOutput {
+    /// The commit the session was restored from, if any
+    source: Option<ContinueJsonSource>,
+    /// `null` when no commit diffs were collected
+    commit_diffs: Option<Vec<ContinueJsonDiff>>,
+    project_context: Option<String>,
+    git_status: Option<String>,
+    prompts: Vec<ContinueJsonPrompt>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ContinueJsonSource {
+    sha: String,
+    author: String,
+    date: String,
+    message: String,
+    full_message: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ContinueJsonDiff {
+    sha: String,
+    diff: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ContinueJsonPrompt {
+    id: String,
+    tool: String,
+    model: String,
+    author: Option<String>,
+    /// The most recent messages, without tool calls
+    messages: Vec<ContinueJsonMessage>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ContinueJsonMessage {
+    role: ContinueJsonRole,
+    text: String,
+    timestamp: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ContinueJsonRole {
+    User,
+    Assistant,
+    Thinking,
+    Plan,
+}
+
 /// Format prompts as JSON for machine consumption
 fn format_context_json(ctx: &SessionContext) -> String {
-    use serde_json::json;
-
     let max_messages = ctx.max_messages;
-    let prompts_json: Vec<serde_json::Value> = ctx
+    let prompts = ctx
         .prompts
         .iter()
         .map(|(id, prompt)| {
@@ -1068,69 +1121,69 @@ fn format_context_json(ctx: &SessionContext) -> String {
             } else {
                 &non_tool
             };
-            let messages_json: Vec<serde_json::Value> = to_show
+            let messages = to_show
                 .iter()
-                .map(|m| match m {
-                    Message::User { text, timestamp } => json!({
-                        "role": "user",
-                        "text": text,
-                        "timestamp": timestamp
-                    }),
-                    Message::Assistant { text, timestamp } => json!({
-                        "role": "assistant",
-                        "text": text,
-                        "timestamp": timestamp
-                    }),
-                    Message::Thinking { text, timestamp } => json!({
-                        "role": "thinking",
-                        "text": text,
-                        "timestamp": timestamp
-                    }),
-                    Message::Plan { text, timestamp } => json!({
-                        "role": "plan",
-                        "text": text,
-                        "timestamp": timestamp
-                    }),
-                    Message::ToolUse { .. } => json!(null),
+                .filter_map(|m| {
+                    let (role, text, timestamp) = match m {
+                        Message::User { text, timestamp } => {
+                            (ContinueJsonRole::User, text, timestamp)
+                        }
+                        Message::Assistant { text, timestamp } => {
+                            (ContinueJsonRole::Assistant, text, timestamp)
+                        }
+                        Message::Thinking { text, timestamp } => {
+                            (ContinueJsonRole::Thinking, text, timestamp)
+                        }
+                        Message::Plan { text, timestamp } => {
+                            (ContinueJsonRole::Plan, text, timestamp)
+                        }
+                        Message::ToolUse { .. } => return None,
+                    };
+                    Some(ContinueJsonMessage {
+                        role,
+                        text: text.clone(),
+                        timestamp: timestamp.clone(),
+                    })
                 })
-                .filter(|v| !v.is_null())
                 .collect();
-            json!({
-                "id": id,
-                "tool": prompt.agent_id.tool,
-                "model": prompt.agent_id.model,
-                "author": prompt.human_author,
-                "messages": messages_json
-            })
+            ContinueJsonPrompt {
+                id: id.clone(),
+                tool: prompt.agent_id.tool.clone(),
+                model: prompt.agent_id.model.clone(),
+                author: prompt.human_author.clone(),
+                messages,
+            }
         })
         .collect();
 
-    let commit_diffs_json: serde_json::Value = if ctx.commit_diffs.is_empty() {
-        json!(null)
-    } else {
-        json!(
-            ctx.commit_diffs
-                .iter()
-                .map(|(sha, diff)| json!({"sha": sha, "diff": diff}))
-                .collect::<Vec<_>>()
-        )
-    };
-
-    let output = json!({
-        "source": ctx.commit_info.as_ref().map(|info| json!({
-            "sha": info.sha,
-            "author": info.author,
-            "date": info.date,
-            "message": info.message,
-            "full_message": info.full_message
-        })),
-        "commit_diffs": commit_diffs_json,
-        "project_context": ctx.project_context,
-        "git_status": ctx.git_status,
-        "prompts": prompts_json
+    let commit_diffs = (!ctx.commit_diffs.is_empty()).then(|| {
+        ctx.commit_diffs
+            .iter()
+            .map(|(sha, diff)| ContinueJsonDiff {
+                sha: sha.clone(),
+                diff: diff.clone(),
+            })
+            .collect()
     });
 
-    serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+    let output = ContinueJsonOutput {
+        source: ctx.commit_info.as_ref().map(|info| ContinueJsonSource {
+            sha: info.sha.clone(),
+            author: info.author.clone(),
+            date: info.date.clone(),
+            message: info.message.clone(),
+            full_message: info.full_message.clone(),
+        }),
+        commit_diffs,
+        project_context: ctx.project_context.clone(),
+        git_status: ctx.git_status.clone(),
+        prompts,
+    };
+
+    // Going through `Value` keeps object keys sorted, as they always have been
+    serde_json::to_value(&output)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| "{}".to_string())
 }
 
 fn parse_line_range(s: &str) -> Result<(u32, u32), String> {
@@ -1386,6 +1439,8 @@ mod tests {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         }
     }
 
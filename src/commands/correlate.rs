@@ -0,0 +1,610 @@
+//! `git-ai correlate --sarif <report>`: defect density of AI vs human authored
+//! code, per static analysis rule.
+//!
+//! Each SARIF result is attributed to whoever wrote the first line of its
+//! region, using blame at the analyzed commit (HEAD unless `--commit` says
+//! otherwise). Densities are findings per thousand lines of the files the report
+//! mentions, either in a result or in its `artifacts` list, so run the analyzer
+//! on the same commit and let it list what it scanned.
+
+use crate::authorship::ignore::{
+    build_ignore_matcher, effective_ignore_patterns, should_ignore_file_with_matcher,
+};
+use crate::commands::blame::{GitAiBlameOptions, overlay_ai_authorship};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use crate::utils::normalize_to_posix;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Rule id for results that name none
+const NO_RULE: &str = "(none)";
+
+/// Output of `git-ai correlate --json`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub(crate) struct CorrelationReport {
+    /// The commit findings were attributed at
+    commit: String,
+    /// Files in scope: those named by a result or listed as an artifact
+    files: usize,
+    ai_lines: u32,
+    human_lines: u32,
+    /// All rules together
+    total: RuleCorrelation,
+    /// One entry per rule, most findings first
+    rules: Vec<RuleCorrelation>,
+    /// Results outside the repository, missing at `commit`, or past the end of
+    /// their file
+    skipped_results: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub(crate) struct RuleCorrelation {
+    rule_id: String,
+    ai_findings: u32,
+    human_findings: u32,
+    /// AI findings per thousand AI-authored lines
+    ai_per_kloc: f64,
+    /// Human findings per thousand human-authored lines
+    human_per_kloc: f64,
+}
+
+// The subset of SARIF 2.1.0 needed to place results
+#[derive(Debug, Deserialize)]
+struct SarifLog {
+    #[serde(default)]
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRun {
+    #[serde(default)]
+    results: Vec<SarifResult>,
+    #[serde(default)]
+    artifacts: Vec<SarifArtifact>,
+    #[serde(default)]
+    original_uri_base_ids: HashMap<String, SarifArtifactLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SarifArtifact {
+    #[serde(default)]
+    location: Option<SarifArtifactLocation>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    #[serde(default)]
+    uri: Option<String>,
+    #[serde(default)]
+    uri_base_id: Option<String>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(default)]
+    rule_id: Option<String>,
+    #[serde(default)]
+    rule: Option<SarifRuleReference>,
+    #[serde(default)]
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SarifRuleReference {
+    #[serde(default)]
+    id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    #[serde(default)]
+    physical_location: Option<SarifPhysicalLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    #[serde(default)]
+    artifact_location: Option<SarifArtifactLocation>,
+    #[serde(default)]
+    region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    #[serde(default)]
+    start_line: Option<u32>,
+}
+
+/// A result placed in the repository
+struct Finding {
+    rule_id: String,
+    file: String,
+    line: u32,
+}
+
+/// Which lines of a file are AI-authored
+struct FileAttribution {
+    total_lines: u32,
+    ai_lines: HashSet<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+pub fn handle_correlate(args: &[String]) {
+    let mut sarif_path: Option<String> = None;
+    let mut commit = "HEAD".to_string();
+    let mut format = OutputFormat::Text;
+    let mut output: Option<String> = None;
+    let mut ignore_patterns: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        let takes_value = matches!(
+            args[i].as_str(),
+            "--sarif" | "--commit" | "-o" | "--output" | "--ignore"
+        );
+        if takes_value && i + 1 >= args.len() {
+            eprintln!("{} requires a value", args[i]);
+            std::process::exit(1);
+        }
+        match args[i].as_str() {
+            "-h" | "--help" => {
+                print_correlate_help();
+                return;
+            }
+            "--sarif" => sarif_path = Some(args[i + 1].clone()),
+            "--commit" => commit = args[i + 1].clone(),
+            "-o" | "--output" => output = Some(args[i + 1].clone()),
+            "--ignore" => ignore_patterns.push(args[i + 1].clone()),
+            "--json" => format = OutputFormat::Json,
+            "--csv" => format = OutputFormat::Csv,
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                print_correlate_help();
+                std::process::exit(1);
+            }
+        }
+        i += if takes_value { 2 } else { 1 };
+    }
+    let Some(sarif_path) = sarif_path else {
+        print_correlate_help();
+        std::process::exit(1);
+    };
+
+    let sarif: SarifLog = match fs::read_to_string(&sarif_path)
+        .map_err(GitAiError::from)
+        .and_then(|contents| serde_json::from_str(&contents).map_err(GitAiError::from))
+    {
+        Ok(sarif) => sarif,
+        Err(e) => {
+            eprintln!("Failed to read SARIF report {}: {}", sarif_path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let ignore_patterns = effective_ignore_patterns(&repo, &ignore_patterns, &[]);
+    let report = match correlate(&repo, &sarif, &commit, &ignore_patterns) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Correlation failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if report.skipped_results > 0 {
+        eprintln!(
+            "Skipped {} results outside the repository or not found at {}",
+            report.skipped_results, commit
+        );
+    }
+
+    let rendered = match format {
+        OutputFormat::Text => report.render(),
+        OutputFormat::Csv => report.to_csv(),
+        OutputFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => json + "\n",
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, rendered) {
+                eprintln!("Failed to write {}: {}", path, e);
+                std::process::exit(1);
+            }
+            eprintln!("Wrote correlation report to {}", path);
+        }
+        None => print!("{}", rendered),
+    }
+}
+
+fn correlate(
+    repo: &Repository,
+    sarif: &SarifLog,
+    commit: &str,
+    ignore_patterns: &[String],
+) -> Result<CorrelationReport, GitAiError> {
+    let commit = repo.revparse_single(commit)?.id();
+    let root = repo.workdir()?.canonicalize()?;
+    let ignore_matcher = build_ignore_matcher(ignore_patterns);
+
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut scope: BTreeSet<String> = BTreeSet::new();
+    let mut skipped_results = 0;
+    for run in &sarif.runs {
+        let resolve = |location: &SarifArtifactLocation| {
+            let location = match (location.uri.as_ref(), location.index) {
+                (None, Some(index)) => run
+                    .artifacts
+                    .get(index)
+                    .and_then(|artifact| artifact.location.as_ref())?,
+                _ => location,
+            };
+            let base = location
+                .uri_base_id
+                .as_ref()
+                .and_then(|id| run.original_uri_base_ids.get(id))
+                .and_then(|base| base.uri.as_deref());
+            repo_path_for_uri(location.uri.as_deref()?, base, &root)
+        };
+
+        scope.extend(
+            run.artifacts
+                .iter()
+                .filter_map(|artifact| artifact.location.as_ref())
+                .filter_map(resolve),
+        );
+        for result in &run.results {
+            let rule_id = result
+                .rule_id
+                .clone()
+                .or_else(|| result.rule.as_ref().and_then(|rule| rule.id.clone()))
+                .unwrap_or_else(|| NO_RULE.to_string());
+            let placed = result
+                .locations
+                .first()
+                .and_then(|location| location.physical_location.as_ref())
+                .and_then(|physical| {
+                    let file = resolve(physical.artifact_location.as_ref()?)?;
+                    let line = physical.region.as_ref()?.start_line?;
+                    Some((file, line))
+                });
+            match placed {
+                Some((file, line)) => {
+                    scope.insert(file.clone());
+                    findings.push(Finding {
+                        rule_id,
+                        file,
+                        line,
+                    });
+                }
+                None => skipped_results += 1,
+            }
+        }
+    }
+    scope.retain(|file| !should_ignore_file_with_matcher(file, &ignore_matcher));
+
+    let mut attributions: HashMap<String, FileAttribution> = HashMap::new();
+    for file in &scope {
+        if let Some(attribution) = attribute_file(repo, file, &commit)? {
+            attributions.insert(file.clone(), attribution);
+        }
+    }
+
+    let mut rules: BTreeMap<String, RuleCorrelation> = BTreeMap::new();
+    for finding in &findings {
+        if should_ignore_file_with_matcher(&finding.file, &ignore_matcher) {
+            continue;
+        }
+        let Some(attribution) = attributions
+            .get(&finding.file)
+            .filter(|attribution| (1..=attribution.total_lines).contains(&finding.line))
+        else {
+            skipped_results += 1;
+            continue;
+        };
+        let rule = rules
+            .entry(finding.rule_id.clone())
+            .or_insert_with(|| RuleCorrelation {
+                rule_id: finding.rule_id.clone(),
+                ..Default::default()
+            });
+        if attribution.ai_lines.contains(&finding.line) {
+            rule.ai_findings += 1;
+        } else {
+            rule.human_findings += 1;
+        }
+    }
+
+    let ai_lines: u32 = attributions
+        .values()
+        .map(|attribution| attribution.ai_lines.len() as u32)
+        .sum();
+    let total_lines: u32 = attributions
+        .values()
+        .map(|attribution| attribution.total_lines)
+        .sum();
+    let human_lines = total_lines - ai_lines;
+
+    let mut total = RuleCorrelation {
+        rule_id: "all".to_string(),
+        ..Default::default()
+    };
+    let mut rules: Vec<RuleCorrelation> = rules
+        .into_values()
+        .map(|mut rule| {
+            total.ai_findings += rule.ai_findings;
+            total.human_findings += rule.human_findings;
+            rule.set_densities(ai_lines, human_lines);
+            rule
+        })
+        .collect();
+    total.set_densities(ai_lines, human_lines);
+    rules.sort_by(|a, b| {
+        (b.ai_findings + b.human_findings)
+            .cmp(&(a.ai_findings + a.human_findings))
+            .then_with(|| a.rule_id.cmp(&b.rule_id))
+    });
+
+    Ok(CorrelationReport {
+        commit,
+        files: attributions.len(),
+        ai_lines,
+        human_lines,
+        total,
+        rules,
+        skipped_results,
+    })
+}
+
+/// AI-authored lines of `file` at `commit`, `None` if it doesn't exist there
+fn attribute_file(
+    repo: &Repository,
+    file: &str,
+    commit: &str,
+) -> Result<Option<FileAttribution>, GitAiError> {
+    let tree = repo.find_commit(commit.to_string())?.tree()?;
+    let Ok(entry) = tree.get_path(Path::new(file)) else {
+        return Ok(None);
+    };
+    let content = repo.find_blob(entry.id())?.content().unwrap_or_default();
+    let total_lines = String::from_utf8_lossy(&content).lines().count() as u32;
+    let mut attribution = FileAttribution {
+        total_lines,
+        ai_lines: HashSet::new(),
+    };
+    if total_lines == 0 {
+        return Ok(Some(attribution));
+    }
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(commit.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let hunks = repo.blame_hunks(file, 1, total_lines, &options)?;
+    let (line_authors, prompt_records, _, _) =
+        overlay_ai_authorship(repo, &hunks, file, None, &options)?;
+    attribution.ai_lines = line_authors
+        .iter()
+        .filter(|(_, author)| prompt_records.contains_key(*author))
+        .map(|(line, _)| *line)
+        .collect();
+    Ok(Some(attribution))
+}
+
+/// Resolve a SARIF artifact URI, relative to `base` when it has one, to a path
+/// relative to the repository `root`. `None` for anything outside it.
+fn repo_path_for_uri(uri: &str, base: Option<&str>, root: &Path) -> Option<String> {
+    let uri = percent_decode(uri);
+    let path = match uri.strip_prefix("file://") {
+        Some(path) => path.to_string(),
+        None if uri.contains("://") => return None,
+        None => match base.map(percent_decode) {
+            Some(base) if !Path::new(&uri).is_absolute() => {
+                let base = base.strip_prefix("file://").unwrap_or(&base).to_string();
+                if base.contains("://") {
+                    return None;
+                }
+                format!("{}/{}", base.trim_end_matches('/'), uri)
+            }
+            _ => uri,
+        },
+    };
+
+    let path = Path::new(&path);
+    let relative = if path.is_absolute() {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        path.strip_prefix(root).ok()?.to_path_buf()
+    } else {
+        path.to_path_buf()
+    };
+    let mut components: Vec<String> = Vec::new();
+    for component in relative.components() {
+        match component {
+            std::path::Component::Normal(part) => {
+                components.push(part.to_string_lossy().to_string())
+            }
+            std::path::Component::ParentDir => {
+                components.pop()?;
+            }
+            std::path::Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!components.is_empty()).then(|| normalize_to_posix(&components.join("/")))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn per_kloc(findings: u32, lines: u32) -> f64 {
+    if lines == 0 {
+        0.0
+    } else {
+        findings as f64 * 1000.0 / lines as f64
+    }
+}
+
+impl RuleCorrelation {
+    fn set_densities(&mut self, ai_lines: u32, human_lines: u32) {
+        self.ai_per_kloc = per_kloc(self.ai_findings, ai_lines);
+        self.human_per_kloc = per_kloc(self.human_findings, human_lines);
+    }
+}
+
+impl CorrelationReport {
+    fn render(&self) -> String {
+        let width = self
+            .rules
+            .iter()
+            .map(|rule| rule.rule_id.len())
+            .chain([4])
+            .max()
+            .unwrap_or(4);
+        let mut out = format!(
+            "Findings at {} in {} files ({} AI lines, {} human lines)\n\n",
+            &self.commit[..self.commit.len().min(7)],
+            self.files,
+            self.ai_lines,
+            self.human_lines
+        );
+        out.push_str(&format!(
+            "{:<width$}  {:>6}  {:>6}  {:>9}  {:>12}\n",
+            "Rule", "AI", "Human", "AI/kLOC", "Human/kLOC"
+        ));
+        for rule in std::iter::once(&self.total).chain(&self.rules) {
+            out.push_str(&format!(
+                "{:<width$}  {:>6}  {:>6}  {:>9.2}  {:>12.2}\n",
+                rule.rule_id,
+                rule.ai_findings,
+                rule.human_findings,
+                rule.ai_per_kloc,
+                rule.human_per_kloc
+            ));
+        }
+        out
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = "rule_id,ai_findings,human_findings,ai_per_kloc,human_per_kloc\n".to_string();
+        for rule in std::iter::once(&self.total).chain(&self.rules) {
+            out.push_str(&format!(
+                "{},{},{},{:.4},{:.4}\n",
+                csv_field(&rule.rule_id),
+                rule.ai_findings,
+                rule.human_findings,
+                rule.ai_per_kloc,
+                rule.human_per_kloc
+            ));
+        }
+        out
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn print_correlate_help() {
+    eprintln!("Usage: git-ai correlate --sarif <report.sarif> [options]");
+    eprintln!();
+    eprintln!("Join a static analysis SARIF report with AI attribution and report, per");
+    eprintln!("rule, findings per thousand AI-authored and human-authored lines. Each");
+    eprintln!("finding counts for the author of its first line.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --commit <rev>        Commit the report was produced for (default: HEAD)");
+    eprintln!("  --json                Output in JSON format");
+    eprintln!("  --csv                 Output one CSV row per rule");
+    eprintln!("  -o, --output <file>   Write the report to <file> instead of stdout");
+    eprintln!("  --ignore <pattern>    Leave matching files out (repeatable)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_path_for_uri_handles_relative_absolute_and_base_ids() {
+        let root = Path::new("/work/repo");
+        assert_eq!(
+            repo_path_for_uri("src/main.rs", None, root).as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(
+            repo_path_for_uri("file:///work/repo/src/my%20file.rs", None, root).as_deref(),
+            Some("src/my file.rs")
+        );
+        assert_eq!(
+            repo_path_for_uri("lib.rs", Some("file:///work/repo/crates/core/"), root).as_deref(),
+            Some("crates/core/lib.rs")
+        );
+        assert_eq!(repo_path_for_uri("/elsewhere/lib.rs", None, root), None);
+        assert_eq!(repo_path_for_uri("../lib.rs", None, root), None);
+        assert_eq!(
+            repo_path_for_uri("https://example.com/lib.rs", None, root),
+            None
+        );
+    }
+
+    #[test]
+    fn test_csv_quotes_rule_ids_and_densities_handle_empty_scope() {
+        assert_eq!(csv_field("rust/unused"), "rust/unused");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(per_kloc(3, 0), 0.0);
+        assert_eq!(per_kloc(3, 1500), 2.0);
+    }
+}
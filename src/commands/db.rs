@@ -0,0 +1,142 @@
+//! `git-ai db`: maintenance for the local metrics database
+//! (`~/.git-ai/internal/metrics-db`).
+//!
+//! - `upgrade` migrates it to the current schema, backing it up first
+//! - `vacuum` reclaims the space left by uploaded and pruned rows
+//! - `prune --older-than <duration>` drops data older than the given age
+
+use crate::error::GitAiError;
+use crate::metrics::db::MetricsDatabase;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn handle_db(args: &[String]) {
+    let result = match args.first().map(String::as_str) {
+        Some("upgrade") => upgrade(),
+        Some("vacuum") => vacuum(),
+        Some("prune") => prune(&args[1..]),
+        Some("-h") | Some("--help") => {
+            print_db_help();
+            std::process::exit(0);
+        }
+        Some(other) => {
+            eprintln!("Unknown db subcommand: {}", other);
+            print_db_help();
+            std::process::exit(1);
+        }
+        None => {
+            print_db_help();
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn upgrade() -> Result<(), GitAiError> {
+    let upgrade = MetricsDatabase::upgrade()?;
+    if upgrade.from == upgrade.to {
+        println!(
+            "Metrics database is up to date (schema version {})",
+            upgrade.to
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Upgraded metrics database from schema version {} to {}",
+        upgrade.from, upgrade.to
+    );
+    if let Some(backup) = upgrade.backup {
+        println!("Backup of the previous version: {}", backup.display());
+    }
+    Ok(())
+}
+
+fn vacuum() -> Result<(), GitAiError> {
+    let db_path = MetricsDatabase::database_path()?;
+    let db = MetricsDatabase::global()?;
+    let mut db = db
+        .lock()
+        .map_err(|e| GitAiError::Generic(format!("Failed to lock metrics database: {}", e)))?;
+
+    let before = file_size(&db_path);
+    db.vacuum()?;
+    println!(
+        "Vacuumed metrics database: {} KB -> {} KB",
+        before / 1024,
+        file_size(&db_path) / 1024
+    );
+    Ok(())
+}
+
+fn prune(args: &[String]) -> Result<(), GitAiError> {
+    let mut older_than: Option<std::time::Duration> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--older-than" => {
+                let Some(value) = args.get(i + 1) else {
+                    return Err(GitAiError::Generic(
+                        "--older-than requires a duration (e.g. 30d, 12h)".to_string(),
+                    ));
+                };
+                older_than = Some(humantime::parse_duration(value).map_err(|e| {
+                    GitAiError::Generic(format!("Invalid --older-than '{}': {}", value, e))
+                })?);
+                i += 1;
+            }
+            other => {
+                return Err(GitAiError::Generic(format!(
+                    "Unknown option for db prune: {}",
+                    other
+                )));
+            }
+        }
+        i += 1;
+    }
+
+    let older_than = older_than.ok_or_else(|| {
+        GitAiError::Generic("db prune requires --older-than <duration>".to_string())
+    })?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = now.saturating_sub(older_than.as_secs());
+
+    let db = MetricsDatabase::global()?;
+    let mut db = db
+        .lock()
+        .map_err(|e| GitAiError::Generic(format!("Failed to lock metrics database: {}", e)))?;
+    let pruned = db.prune_older_than(cutoff)?;
+    println!(
+        "Pruned {} queued events, {} uploaded keys, {} pending envelopes and {} throttle entries",
+        pruned.metrics, pruned.uploaded_keys, pruned.envelopes, pruned.throttle
+    );
+    Ok(())
+}
+
+/// Size of the database including its write-ahead log
+fn file_size(path: &std::path::Path) -> u64 {
+    let wal = std::path::PathBuf::from(format!("{}-wal", path.display()));
+    [path, wal.as_path()]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn print_db_help() {
+    eprintln!("Usage: git-ai db <command>");
+    eprintln!();
+    eprintln!("Maintain the local metrics database.");
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  upgrade                      Migrate to the current schema (backs up first)");
+    eprintln!("  vacuum                       Reclaim unused space");
+    eprintln!("  prune --older-than <dur>     Delete data older than <dur> (e.g. 30d, 12h)");
+}
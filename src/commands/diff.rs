@@ -2,6 +2,8 @@ use crate::authorship::authorship_log::{LineRange, PromptRecord};
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, exec_git};
+use crate::output;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
 use std::io::IsTerminal;
@@ -41,7 +43,7 @@ pub struct DiffLineKey {
 }
 
 /// JSON output format for git-ai diff --json
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DiffJson {
     /// Per-file diff information with annotations
     pub files: BTreeMap<String, FileDiffJson>,
@@ -50,11 +52,12 @@ pub struct DiffJson {
 }
 
 /// Per-file diff information in JSON output
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileDiffJson {
     /// Annotations mapping prompt hash to line ranges
     /// Line ranges are serialized as JSON tuples: [start, end] or single number
     #[serde(serialize_with = "serialize_annotations")]
+    #[schemars(with = "BTreeMap<String, Vec<AnnotationRange>>")]
     pub annotations: BTreeMap<String, Vec<LineRange>>,
     /// The unified diff for this file
     pub diff: String,
@@ -62,6 +65,15 @@ pub struct FileDiffJson {
     pub base_content: String,
 }
 
+/// Schema of a range written by `serialize_annotations`
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)] // Only describes the output shape, never constructed
+enum AnnotationRange {
+    Single(u32),
+    Range(u32, u32),
+}
+
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub enum LineSide {
     Old, // For deleted lines
@@ -448,7 +460,7 @@ pub fn overlay_diff_attributions(
 
 /// Convert a sorted list of line numbers to contiguous ranges
 /// e.g., [1, 2, 3, 5, 6, 10] -> [(1, 3), (5, 6), (10, 10)]
-fn lines_to_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
+pub(crate) fn lines_to_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
     if lines.is_empty() {
         return Vec::new();
     }
@@ -688,7 +700,6 @@ fn collect_file_annotations(
 // Output Formatting
 // ============================================================================
 
-#[allow(clippy::if_same_then_else)]
 pub fn format_annotated_diff(
     repo: &Repository,
     from_commit: &str,
@@ -707,15 +718,37 @@ pub fn format_annotated_diff(
         .map_err(|e| GitAiError::Generic(format!("Failed to parse diff output: {}", e)))?;
 
     // Check if we should use colors
-    let use_color = std::io::stdout().is_terminal();
+    let use_color = std::io::stdout().is_terminal() && output::mode().color;
+
+    Ok(annotate_diff_text(
+        &diff_text,
+        attributions,
+        use_color,
+        &HashMap::new(),
+    ))
+}
 
-    // Parse and annotate diff
+/// Annotate a unified diff with line attributions. `hunk_footers` maps
+/// (file, new-side start line of a hunk) to a note printed after that hunk.
+#[allow(clippy::if_same_then_else)]
+pub(crate) fn annotate_diff_text(
+    diff_text: &str,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    use_color: bool,
+    hunk_footers: &HashMap<(String, u32), String>,
+) -> String {
     let mut result = String::new();
+    let mut pending_footer: Option<&String> = None;
     let mut current_file = String::new();
     let mut old_line_num = 0u32;
     let mut new_line_num = 0u32;
 
     for line in diff_text.lines() {
+        if (line.starts_with("diff --git") || line.starts_with("@@ "))
+            && let Some(footer) = pending_footer.take()
+        {
+            result.push_str(&format_footer(footer, use_color));
+        }
         if line.starts_with("diff --git") {
             // Diff header
             result.push_str(&format_line(line, LineType::DiffHeader, use_color, None));
@@ -748,6 +781,7 @@ pub fn format_annotated_diff(
             if let Some((old_start, new_start)) = parse_hunk_header_for_line_nums(line) {
                 old_line_num = old_start;
                 new_line_num = new_start;
+                pending_footer = hunk_footers.get(&(current_file.clone(), new_start));
             }
             result.push_str(&format_line(line, LineType::HunkHeader, use_color, None));
         } else if line.starts_with('-') && !line.starts_with("---") {
@@ -793,8 +827,19 @@ pub fn format_annotated_diff(
             result.push_str(&format_line(line, LineType::Context, use_color, None));
         }
     }
+    if let Some(footer) = pending_footer {
+        result.push_str(&format_footer(footer, use_color));
+    }
+
+    result
+}
 
-    Ok(result)
+fn format_footer(footer: &str, use_color: bool) -> String {
+    if use_color {
+        format!("\x1b[2m{}\x1b[0m\n", footer) // Dim
+    } else {
+        format!("{}\n", footer)
+    }
 }
 
 fn parse_hunk_header_for_line_nums(line: &str) -> Option<(u32, u32)> {
@@ -891,9 +936,10 @@ fn format_line(
 }
 
 fn format_attribution(attribution: &Attribution) -> String {
+    let mode = output::mode();
     match attribution {
-        Attribution::Ai(tool) => format!("🤖{}", tool),
-        Attribution::Human(username) => format!("👤{}", username),
+        Attribution::Ai(tool) => format!("{}{}", mode.pick("🤖", "ai:"), tool),
+        Attribution::Human(username) => format!("{}{}", mode.pick("👤", "human:"), username),
         Attribution::NoData => "[no-data]".to_string(),
     }
 }
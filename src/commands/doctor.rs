@@ -0,0 +1,628 @@
+//! `git-ai doctor`: check that git-ai is wired up correctly on this machine,
+//! and say how to fix whatever isn't.
+//!
+//! Checks only read state; fixes are left to the commands named in the hints.
+//! Exits non-zero when any check fails, so it can gate setup scripts.
+
+use crate::auth::CredentialStore;
+use crate::authorship::internal_db::InternalDatabase;
+use crate::commands::git_hook_handlers::has_repo_hook_state;
+use crate::config::{self, Config};
+use crate::git::find_repository;
+use crate::git::real_git::{self, PinStatus};
+use crate::git::refs::authorship_notes_ref;
+use crate::git::repository::{GitMarker, Repository, exec_git, git_marker};
+use crate::git::self_invocation::is_self;
+use crate::mdm::agents::get_all_installers;
+use crate::mdm::hook_installer::HookInstallerParams;
+use crate::mdm::utils::get_current_binary_path;
+use crate::metrics::db::MetricsDatabase;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Oldest git the test matrix covers (`blame --ignore-revs-file` arrived here)
+const MIN_GIT_VERSION: (u32, u32) = (2, 23);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to run or change to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub fn handle_doctor(args: &[String]) {
+    if let Some(arg) = args.first() {
+        eprintln!("Unknown argument: {}", arg);
+        eprintln!("Usage: git-ai doctor");
+        std::process::exit(1);
+    }
+
+    let results = run_checks();
+    print_table(&results);
+    if results.iter().any(|r| r.status == CheckStatus::Fail) {
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+pub fn run_checks() -> Vec<CheckResult> {
+    let repo = find_repository(&[]).ok();
+    let mut results = vec![
+        check_git_pin(),
+        check_git_version(),
+        check_git_shim(repo.as_ref()),
+        check_libexec_symlink(),
+    ];
+    results.extend(check_agent_hooks());
+    if let Some(repo) = &repo {
+        results.push(check_notes_refspecs(repo));
+        results.push(check_nested_repositories(repo));
+    }
+    results.push(check_database(
+        "internal database",
+        InternalDatabase::database_path().ok(),
+    ));
+    results.push(check_database(
+        "metrics database",
+        MetricsDatabase::database_path().ok(),
+    ));
+    results.push(check_credentials());
+    results
+}
+
+fn print_table(results: &[CheckResult]) {
+    let width = results
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("CHECK".len());
+    println!("{:<width$}  STATUS  DETAIL", "CHECK", width = width);
+    for result in results {
+        println!(
+            "{:<width$}  {:<6}  {}",
+            result.name,
+            result.status.as_str(),
+            result.detail,
+            width = width
+        );
+        if let Some(hint) = &result.hint {
+            println!("{:<width$}          fix: {}", "", hint, width = width);
+        }
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    println!();
+    println!(
+        "{} passed, {} warnings, {} failed",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail)
+    );
+}
+
+fn check_git_pin() -> CheckResult {
+    let name = "git pin";
+    let file_config = config::load_file_config_public().unwrap_or_default();
+    match real_git::check_pin(file_config.git.as_ref()) {
+        PinStatus::NotPinned => CheckResult::pass(
+            name,
+            format!("not pinned; using {}", Config::get().git_cmd()),
+        ),
+        PinStatus::Unchanged { path, version } => {
+            CheckResult::pass(name, format!("{} ({}), unchanged", path, version))
+        }
+        PinStatus::Missing { path } => CheckResult::warn(
+            name,
+            format!("pinned git {} no longer exists", path),
+            "run `git-ai git-path pin` to pin another",
+        ),
+        PinStatus::Changed {
+            path,
+            pinned_version,
+            version,
+        } => CheckResult::warn(
+            name,
+            format!(
+                "pinned git {} changed since it was pinned ({} -> {})",
+                path,
+                pinned_version.as_deref().unwrap_or("unknown"),
+                version.as_deref().unwrap_or("unknown")
+            ),
+            format!(
+                "run `git-ai git-path pin {}` if the new binary is expected",
+                path
+            ),
+        ),
+    }
+}
+
+fn check_git_version() -> CheckResult {
+    let name = "git version";
+    let git = Config::get().git_cmd();
+    let Some(version) = real_git::git_version(Path::new(git)) else {
+        return CheckResult::fail(
+            name,
+            format!("{} did not report a git version", git),
+            "install git, or point `git-ai git-path pin <path>` at a working one",
+        );
+    };
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    let (major, minor) = (parts.next().flatten(), parts.next().flatten());
+    match major.zip(minor) {
+        Some(found) if found >= MIN_GIT_VERSION => CheckResult::pass(name, version),
+        Some(_) => CheckResult::fail(
+            name,
+            format!(
+                "git {} is older than {}.{}",
+                version, MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            ),
+            format!(
+                "upgrade git to {}.{} or newer",
+                MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            ),
+        ),
+        None => CheckResult::warn(
+            name,
+            format!("unrecognized git version {}", version),
+            "check that the git binary in use is a standard git build",
+        ),
+    }
+}
+
+/// The `git` that shells resolve first should be git-ai, or commits made from
+/// a terminal go unattributed, unless the repo runs git-ai from its git hooks
+fn check_git_shim(repo: Option<&Repository>) -> CheckResult {
+    let name = "git on PATH";
+    let exe_name = if cfg!(windows) { "git.exe" } else { "git" };
+    let first = std::env::var_os("PATH").and_then(|path| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(exe_name))
+            .find(|candidate| candidate.is_file())
+    });
+    match first {
+        Some(path) if is_self(&path) => {
+            CheckResult::pass(name, format!("{} is git-ai", path.display()))
+        }
+        Some(path) if has_repo_hook_state(repo) => CheckResult::pass(
+            name,
+            format!(
+                "{} is not git-ai, but this repo's git hooks run it",
+                path.display()
+            ),
+        ),
+        Some(path) => CheckResult::warn(
+            name,
+            format!(
+                "{} is not git-ai; commits made with it are not attributed",
+                path.display()
+            ),
+            "put git-ai's bin directory (e.g. ~/.git-ai/bin) first in PATH, or reinstall git-ai",
+        ),
+        None => CheckResult::warn(name, "no git found on PATH", "reinstall git-ai"),
+    }
+}
+
+/// `<install dir>/libexec` must point at real git's libexec for Fork and other
+/// clients that derive git's exec path from the binary location
+fn check_libexec_symlink() -> CheckResult {
+    let name = "libexec symlink";
+    let Ok(exe) = std::env::current_exe() else {
+        return CheckResult::warn(
+            name,
+            "cannot locate the git-ai executable",
+            "reinstall git-ai",
+        );
+    };
+    if exe.to_string_lossy().contains("/nix/store") {
+        return CheckResult::pass(name, "managed by the Nix package");
+    }
+    let Some(symlink) = exe
+        .parent()
+        .and_then(Path::parent)
+        .map(|base| base.join("libexec"))
+    else {
+        return CheckResult::warn(
+            name,
+            "cannot locate the git-ai install directory",
+            "reinstall git-ai",
+        );
+    };
+    let expected = exec_git(&["--exec-path".to_string()])
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|path| PathBuf::from(path.trim()).parent().map(Path::to_path_buf));
+    let Some(expected) = expected else {
+        return CheckResult::warn(
+            name,
+            "cannot determine git's exec path",
+            "check that `git --exec-path` works with the git in use",
+        );
+    };
+
+    let resolves_to =
+        |path: &Path| path.canonicalize().ok() == expected.canonicalize().ok() && path.exists();
+    if resolves_to(&symlink) {
+        CheckResult::pass(
+            name,
+            format!("{} -> {}", symlink.display(), expected.display()),
+        )
+    } else if symlink.symlink_metadata().is_ok() {
+        CheckResult::warn(
+            name,
+            format!(
+                "{} does not point at {}",
+                symlink.display(),
+                expected.display()
+            ),
+            "run `git-ai install-hooks` to recreate it",
+        )
+    } else {
+        CheckResult::warn(
+            name,
+            format!("{} is missing", symlink.display()),
+            "run `git-ai install-hooks` to create it",
+        )
+    }
+}
+
+/// One row per agent or IDE that is installed on this machine
+fn check_agent_hooks() -> Vec<CheckResult> {
+    let binary_path = match get_current_binary_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return vec![CheckResult::warn(
+                "agent hooks",
+                format!("cannot locate the git-ai executable: {}", e),
+                "reinstall git-ai",
+            )];
+        }
+    };
+    let params = HookInstallerParams { binary_path };
+
+    let mut results = Vec::new();
+    for installer in get_all_installers() {
+        let name = format!("hooks: {}", installer.name());
+        match installer.check_hooks(&params) {
+            Ok(check) if !check.tool_installed => {}
+            Ok(check) if check.hooks_installed => {
+                results.push(CheckResult::pass(name, "installed"))
+            }
+            Ok(_) => results.push(CheckResult::warn(
+                name,
+                "not installed; its edits are not attributed",
+                "run `git-ai install-hooks`",
+            )),
+            Err(e) => results.push(CheckResult::fail(
+                name,
+                format!("cannot check hooks: {}", e),
+                "run `git-ai install-hooks --verbose` to see what is wrong",
+            )),
+        }
+    }
+    if results.is_empty() {
+        results.push(CheckResult::pass(
+            "agent hooks",
+            "no supported agents or IDEs found",
+        ));
+    }
+    results
+}
+
+/// Refspecs that would clobber authorship notes. git-ai fetches and merges
+/// `refs/notes/ai` itself, so a fetch refspec writing straight into it replaces
+/// local notes that haven't been pushed, and a forced push of it replaces notes
+/// other people pushed.
+fn check_notes_refspecs(repo: &Repository) -> CheckResult {
+    let name = "notes sync";
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "config".to_string(),
+        "--get-regexp".to_string(),
+        r"^remote\..*\.(fetch|push)$".to_string(),
+    ]);
+    // Exits 1 when nothing matches
+    let stdout = exec_git(&args)
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+
+    let conflicts: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(key, refspec)| {
+            let is_push = key.ends_with(".push");
+            refspec_clobbers_notes(refspec, is_push)
+        })
+        .map(|(key, refspec)| format!("{} = {}", key, refspec))
+        .collect();
+
+    if conflicts.is_empty() {
+        return CheckResult::pass(
+            name,
+            format!("no refspecs overwrite {}", authorship_notes_ref()),
+        );
+    }
+    CheckResult::warn(
+        name,
+        format!("{} overwrites authorship notes", conflicts.join(", ")),
+        format!(
+            "remove the refspec (`git config --unset remote.<name>.fetch <refspec>`); \
+             git-ai syncs {} on fetch and push",
+            authorship_notes_ref()
+        ),
+    )
+}
+
+fn refspec_clobbers_notes(refspec: &str, is_push: bool) -> bool {
+    let refspec = refspec.trim();
+    let (forced, refspec) = match refspec.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, refspec),
+    };
+    let (src, dst) = refspec.split_once(':').unwrap_or((refspec, refspec));
+    // A push refspec writes the remote's ref, so what matters is its source
+    let local = if is_push { src } else { dst };
+    let matches = local == authorship_notes_ref() || local == "refs/notes/*" || local == "refs/*";
+    // Any fetch into it skips git-ai's notes merge; a push only replaces the
+    // remote's notes when forced
+    matches && (forced || !is_push)
+}
+
+/// Repositories inside this one. Checkpoints go to the innermost repository
+/// holding a file, so layouts where that isn't the repository that tracks it
+/// are reported: files committed both in here and in a nested checkout, repos
+/// committed as bare gitlinks with no `.gitmodules` entry, and `.git` entries
+/// that aren't repositories at all (left behind by vendoring).
+fn check_nested_repositories(repo: &Repository) -> CheckResult {
+    let name = "nested repos";
+    let Ok(workdir) = repo.workdir() else {
+        return CheckResult::pass(name, "bare repository");
+    };
+    let git_lines = |extra: &[&str]| -> Vec<String> {
+        let mut args = repo.global_args_for_exec();
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        exec_git(&args)
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|stdout| stdout.split('\0').map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+
+    let mut tracked_dirs = BTreeSet::new();
+    let mut gitlinks = Vec::new();
+    for entry in git_lines(&["ls-files", "-s", "-z"]) {
+        let Some((meta, path)) = entry.split_once('\t') else {
+            continue;
+        };
+        if meta.starts_with("160000") {
+            gitlinks.push(path.to_string());
+        } else {
+            tracked_dirs.extend(Path::new(path).ancestors().skip(1).map(Path::to_path_buf));
+        }
+    }
+    tracked_dirs.remove(Path::new(""));
+    let untracked_dirs: Vec<String> =
+        git_lines(&["ls-files", "-o", "--directory", "--exclude-standard", "-z"])
+            .into_iter()
+            .filter_map(|path| path.strip_suffix('/').map(str::to_string))
+            .collect();
+    // Exits 1 when there is no .gitmodules or it lists nothing
+    let submodules: BTreeSet<String> = git_lines(&[
+        "config",
+        "-z",
+        "-f",
+        ".gitmodules",
+        "--get-regexp",
+        r"^submodule\..*\.path$",
+    ])
+    .iter()
+    .filter_map(|entry| entry.split_once('\n').map(|(_, path)| path.to_string()))
+    .collect();
+
+    let findings = nested_repository_findings(
+        &workdir,
+        &tracked_dirs,
+        &gitlinks,
+        &untracked_dirs,
+        &submodules,
+    );
+    if findings.problems.is_empty() {
+        return CheckResult::pass(
+            name,
+            match findings.nested {
+                0 => "none".to_string(),
+                1 => "1 nested repository, attributed on its own".to_string(),
+                n => format!("{} nested repositories, attributed on their own", n),
+            },
+        );
+    }
+    CheckResult::warn(
+        name,
+        findings.problems.join("; "),
+        "keep each file in exactly one repository: add vendored checkouts to .gitignore \
+         or register them with `git submodule add`, and delete stray .git entries",
+    )
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct NestedRepositoryFindings {
+    nested: usize,
+    problems: Vec<String>,
+}
+
+fn nested_repository_findings(
+    workdir: &Path,
+    tracked_dirs: &BTreeSet<PathBuf>,
+    gitlinks: &[String],
+    untracked_dirs: &[String],
+    submodules: &BTreeSet<String>,
+) -> NestedRepositoryFindings {
+    let mut findings = NestedRepositoryFindings::default();
+    for dir in tracked_dirs {
+        match git_marker(&workdir.join(dir)) {
+            Some(GitMarker::Repository) => {
+                findings.nested += 1;
+                findings.problems.push(format!(
+                    "{} is its own repository but files in it are also tracked here",
+                    dir.display()
+                ));
+            }
+            Some(GitMarker::Broken) => findings
+                .problems
+                .push(format!("{}/.git is not a repository", dir.display())),
+            Some(GitMarker::Submodule) | None => {}
+        }
+    }
+    for path in gitlinks {
+        if !submodules.contains(path) {
+            findings.problems.push(format!(
+                "{} is committed as an embedded repository without a .gitmodules entry",
+                path
+            ));
+        }
+    }
+    for path in untracked_dirs {
+        match git_marker(&workdir.join(path)) {
+            Some(GitMarker::Repository) => findings.nested += 1,
+            Some(GitMarker::Broken) => findings
+                .problems
+                .push(format!("{}/.git is not a repository", path)),
+            Some(GitMarker::Submodule) | None => {}
+        }
+    }
+    findings
+}
+
+fn check_database(name: &str, path: Option<PathBuf>) -> CheckResult {
+    let Some(path) = path else {
+        return CheckResult::warn(
+            name,
+            "cannot determine the database path",
+            "check that HOME is set",
+        );
+    };
+    if !path.exists() {
+        return CheckResult::pass(name, format!("{} not created yet", path.display()));
+    }
+    let hint = format!(
+        "move {} aside and git-ai will recreate it (history stored only there is lost)",
+        path.display()
+    );
+    let conn = match rusqlite::Connection::open_with_flags(
+        &path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    ) {
+        Ok(conn) => conn,
+        Err(e) => return CheckResult::fail(name, format!("cannot open: {}", e), hint),
+    };
+    match conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)) {
+        Ok(result) if result == "ok" => CheckResult::pass(name, path.display().to_string()),
+        Ok(result) => CheckResult::fail(name, format!("integrity check: {}", result), hint),
+        Err(e) => CheckResult::fail(name, format!("integrity check failed: {}", e), hint),
+    }
+}
+
+fn check_credentials() -> CheckResult {
+    let name = "credentials";
+    if Config::get().api_key().is_some() {
+        return CheckResult::pass(name, "using api_key from config");
+    }
+    let store = CredentialStore::new();
+    match store.load() {
+        Ok(None) => CheckResult::pass(name, format!("not logged in ({})", store.backend_name())),
+        Ok(Some(creds)) if creds.is_refresh_token_expired() => {
+            CheckResult::fail(name, "login has expired", "run `git-ai login`")
+        }
+        Ok(Some(_)) => CheckResult::pass(name, format!("logged in ({})", store.backend_name())),
+        Err(e) => CheckResult::fail(
+            name,
+            format!("cannot read stored credentials: {}", e),
+            "run `git-ai logout` and then `git-ai login`",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refspecs_writing_into_ai_notes_are_flagged() {
+        assert!(refspec_clobbers_notes("+refs/notes/*:refs/notes/*", false));
+        assert!(refspec_clobbers_notes("refs/notes/ai:refs/notes/ai", false));
+        assert!(refspec_clobbers_notes("+refs/notes/ai:refs/notes/ai", true));
+        assert!(!refspec_clobbers_notes("refs/notes/ai", true));
+        assert!(!refspec_clobbers_notes(
+            "+refs/heads/*:refs/remotes/origin/*",
+            false
+        ));
+        assert!(!refspec_clobbers_notes(
+            "+refs/notes/ai:refs/notes/ai-remote/origin",
+            false
+        ));
+    }
+
+    #[test]
+    fn corrupt_database_fails_with_a_hint() {
+        let temp = tempfile::tempdir().unwrap();
+        let missing = temp.path().join("db");
+        assert_eq!(
+            check_database("db", Some(missing.clone())).status,
+            CheckStatus::Pass
+        );
+
+        std::fs::write(&missing, b"not a sqlite database, just some bytes").unwrap();
+        let result = check_database("db", Some(missing));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.hint.unwrap().contains("move"));
+    }
+}
@@ -9,6 +9,7 @@
 
 use crate::auth::CredentialStore;
 use crate::auth::client::OAuthClient;
+use crate::output::{self, Status};
 
 /// Handle the exchange-nonce command (internal - called by install scripts)
 ///
@@ -49,6 +50,9 @@ fn exchange_nonce(nonce: &str, api_base: &str) -> Result<(), String> {
     let store = CredentialStore::new();
     store.store(&credentials)?;
 
-    eprintln!("\x1b[32m✓ Logged in automatically\x1b[0m");
+    eprintln!(
+        "{}",
+        output::mode().status(Status::Success, "Logged in automatically")
+    );
     Ok(())
 }
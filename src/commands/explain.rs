@@ -0,0 +1,345 @@
+//! `git-ai explain <file>:<line>`: a plain-language account of where one line came
+//! from, stitched together from git blame, authorship notes and stored sessions.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::transcript::Message;
+use crate::commands::blame::{BlameHunk, GitAiBlameOptions};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git};
+use chrono::DateTime;
+
+/// How much of the session's opening prompt to quote
+const SNIPPET_CHARS: usize = 200;
+
+pub fn handle_explain(args: &[String]) {
+    let (file, line) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai explain <file>:<line>");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match explain_line(&repo, &file, line) {
+        Ok(explanation) => print!("{}", explanation.render()),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parse `<file>:<line>`. The line number is taken after the last colon so paths
+/// containing colons still work.
+fn parse_args(args: &[String]) -> Result<(String, u32), String> {
+    let target = match args {
+        [target] => target,
+        [] => return Err("missing <file>:<line>".to_string()),
+        _ => return Err(format!("unexpected argument: {}", args[1])),
+    };
+    let (file, line) = target
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected <file>:<line>, got '{}'", target))?;
+    let line = line
+        .parse::<u32>()
+        .ok()
+        .filter(|line| *line > 0)
+        .ok_or_else(|| format!("invalid line number '{}'", line))?;
+    if file.is_empty() {
+        return Err(format!("expected <file>:<line>, got '{}'", target));
+    }
+    Ok((file.to_string(), line))
+}
+
+/// A commit that touched the line, as shown by `git log -L`
+struct LineChange {
+    short_sha: String,
+    subject: String,
+}
+
+struct LineExplanation {
+    file: String,
+    line: u32,
+    content: String,
+    hunk: BlameHunk,
+    subject: String,
+    prompt: Option<(String, PromptRecord)>,
+    /// The session's opening request, when a transcript is available
+    snippet: Option<String>,
+    /// Commits that touched the line, newest (the blamed commit) first
+    history: Vec<LineChange>,
+}
+
+fn explain_line(repo: &Repository, file: &str, line: u32) -> Result<LineExplanation, GitAiError> {
+    let file = repo_relative_path(repo, file)?;
+    let options = GitAiBlameOptions {
+        line_ranges: vec![(line, line)],
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let (line_authors, prompt_records) = repo.blame(&file, &options)?;
+    let hunk = repo
+        .blame_hunks(&file, line, line, &options)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| GitAiError::Generic(format!("git blame returned nothing for {}", file)))?;
+
+    let content = std::fs::read_to_string(repo.workdir()?.join(&file))?
+        .lines()
+        .nth(line as usize - 1)
+        .unwrap_or_default()
+        .to_string();
+
+    let prompt = line_authors.get(&line).and_then(|author| {
+        prompt_records
+            .get(author)
+            .map(|record| (author.clone(), record.clone()))
+    });
+    let snippet = prompt
+        .as_ref()
+        .and_then(|(prompt_id, record)| opening_request(prompt_id, record));
+
+    let (subject, history) = if is_uncommitted(&hunk) {
+        (String::new(), Vec::new())
+    } else {
+        (
+            commit_subject(repo, &hunk.commit_sha)?,
+            line_history(repo, &file, &hunk)?,
+        )
+    };
+
+    Ok(LineExplanation {
+        file,
+        line,
+        content,
+        hunk,
+        subject,
+        prompt,
+        snippet,
+        history,
+    })
+}
+
+/// Resolve `file` (relative to the current directory) to a path relative to the
+/// repository root, the form blame and the authorship notes use
+pub(crate) fn repo_relative_path(repo: &Repository, file: &str) -> Result<String, GitAiError> {
+    let path = std::env::current_dir()?.join(file);
+    let path = path
+        .canonicalize()
+        .map_err(|e| GitAiError::Generic(format!("Cannot open '{}': {}", file, e)))?;
+    let root = repo.workdir()?.canonicalize()?;
+    let relative = path
+        .strip_prefix(&root)
+        .map_err(|_| GitAiError::Generic(format!("'{}' is not inside the repository", file)))?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+pub(crate) fn is_uncommitted(hunk: &BlameHunk) -> bool {
+    hunk.commit_sha.chars().all(|c| c == '0')
+}
+
+fn commit_subject(repo: &Repository, commit_sha: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["log", "-1", "--format=%s", commit_sha].map(String::from));
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Every commit that changed the line up to the blamed commit, newest first
+fn line_history(
+    repo: &Repository,
+    file: &str,
+    hunk: &BlameHunk,
+) -> Result<Vec<LineChange>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "log".to_string(),
+        format!("-L{},{}:{}", hunk.orig_range.0, hunk.orig_range.0, file),
+        "--no-patch".to_string(),
+        "--format=%h%x09%s".to_string(),
+        hunk.commit_sha.clone(),
+    ]);
+    // History is a nice-to-have; renames or shallow clones can make `log -L` fail
+    let Ok(output) = exec_git(&args) else {
+        return Ok(Vec::new());
+    };
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (short_sha, subject) = line.split_once('\t')?;
+            Some(LineChange {
+                short_sha: short_sha.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// The first thing the user asked in the session, from the note's transcript or the
+/// local prompt database
+fn opening_request(prompt_id: &str, record: &PromptRecord) -> Option<String> {
+    let first_user_message = |messages: &[Message]| {
+        messages.iter().find_map(|message| match message {
+            Message::User { text, .. } if !text.trim().is_empty() => Some(text.clone()),
+            _ => None,
+        })
+    };
+
+    let text = first_user_message(&record.messages).or_else(|| {
+        let db = InternalDatabase::global().ok()?;
+        let db = db.lock().ok()?;
+        let stored = db.get_prompt(prompt_id).ok()??;
+        first_user_message(&stored.messages.messages)
+    })?;
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > SNIPPET_CHARS {
+        let truncated: String = text.chars().take(SNIPPET_CHARS).collect();
+        Some(format!("{}...", truncated.trim_end()))
+    } else {
+        Some(text)
+    }
+}
+
+impl LineExplanation {
+    fn render(&self) -> String {
+        let mut out = format!(
+            "{}:{}\n    {}\n\n",
+            self.file,
+            self.line,
+            self.content.trim()
+        );
+
+        if is_uncommitted(&self.hunk) {
+            out.push_str("This line hasn't been committed yet.\n");
+            if let Some((_, record)) = &self.prompt {
+                out.push_str(&format!(
+                    "It was written by {} and is waiting in the working log for the next commit.\n",
+                    describe_agent(record)
+                ));
+            }
+            return out;
+        }
+
+        let short_sha = &self.hunk.commit_sha[..self.hunk.commit_sha.len().min(7)];
+        let date = DateTime::from_timestamp(self.hunk.author_time, 0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let author = format!("{} <{}>", self.hunk.original_author, self.hunk.author_email);
+
+        match &self.prompt {
+            Some((prompt_id, record)) => {
+                let human = record.human_author.as_deref().unwrap_or(&author);
+                out.push_str(&format!(
+                    "Written by AI: {}, working with {}.\n",
+                    describe_agent(record),
+                    human
+                ));
+                out.push_str(&format!(
+                    "Committed in {} \"{}\" by {} on {}.\n",
+                    short_sha, self.subject, author, date
+                ));
+                out.push_str(&format!(
+                    "Prompt {} added {} and removed {} lines in that commit.\n",
+                    prompt_id, record.total_additions, record.total_deletions
+                ));
+                match &self.snippet {
+                    Some(snippet) => {
+                        out.push_str(&format!("The session began with: \"{}\"\n", snippet))
+                    }
+                    None => out.push_str("No transcript was stored for this session.\n"),
+                }
+                out.push_str(&format!(
+                    "Run `git-ai show-prompt {}` for the full prompt record.\n",
+                    prompt_id
+                ));
+            }
+            None => {
+                out.push_str(&format!(
+                    "Written by {}, with no AI involvement recorded.\n",
+                    author
+                ));
+                out.push_str(&format!(
+                    "Committed in {} \"{}\" on {}.\n",
+                    short_sha, self.subject, date
+                ));
+            }
+        }
+
+        out.push('\n');
+        if self.hunk.orig_range.0 != self.line {
+            out.push_str(&format!(
+                "It was line {} when it was committed; changes around it have since moved it to line {}.\n",
+                self.hunk.orig_range.0, self.line
+            ));
+        }
+        match self.history.split_last() {
+            Some((first, rest)) if !rest.is_empty() => {
+                out.push_str(&format!(
+                    "It was first written in {} \"{}\" and rewritten in {} later commit{} before reaching its current form.\n",
+                    first.short_sha,
+                    first.subject,
+                    rest.len(),
+                    if rest.len() == 1 { "" } else { "s" }
+                ));
+            }
+            _ => out.push_str("It hasn't been changed since that commit.\n"),
+        }
+        out
+    }
+}
+
+fn describe_agent(record: &PromptRecord) -> String {
+    let agent = &record.agent_id;
+    let mut description = agent.tool.clone();
+    if !agent.model.is_empty() && agent.model != "unknown" {
+        description.push_str(&format!(" ({})", agent.model));
+    }
+    if !agent.id.is_empty() {
+        description.push_str(&format!(" in session {}", agent.id));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_splits_on_last_colon() {
+        assert_eq!(
+            parse_args(&args(&["src/lib.rs:12"])).unwrap(),
+            ("src/lib.rs".to_string(), 12)
+        );
+        assert_eq!(
+            parse_args(&args(&["weird:name.rs:3"])).unwrap(),
+            ("weird:name.rs".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn test_parse_args_rejects_bad_targets() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["src/lib.rs"])).is_err());
+        assert!(parse_args(&args(&["src/lib.rs:0"])).is_err());
+        assert!(parse_args(&args(&["src/lib.rs:x"])).is_err());
+        assert!(parse_args(&args(&[":4"])).is_err());
+        assert!(parse_args(&args(&["a.rs:1", "b.rs:2"])).is_err());
+    }
+}
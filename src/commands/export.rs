@@ -0,0 +1,553 @@
+//! `git-ai export` / `git-ai import`: move attribution between clones that can't
+//! share notes through a remote (air-gapped mirrors, vendored snapshots).
+//!
+//! The archive is a zstd-compressed tarball:
+//!
+//! - `manifest.json`: format version, source range and entry counts
+//! - `notes/<commit>`: the `refs/notes/ai` authorship note for each commit
+//! - `prompts/<id>.json`: prompt records from the local database that the notes
+//!   reference, including transcripts that were never written into the notes
+//! - `working_logs/<commit>/...`: uncommitted checkpoints for base commits in range
+
+use crate::api::object_storage::{is_object_url, staging_path, upload_staged};
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::internal_db::{InternalDatabase, PromptDbRecord};
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::find_repository;
+use crate::git::refs::{FrozenNotes, note_blob_oids_for_commits, notes_add_batch_with};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::i18n::tr;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+const ARCHIVE_VERSION: u32 = 1;
+const MANIFEST_PATH: &str = "manifest.json";
+const NOTES_DIR: &str = "notes";
+const PROMPTS_DIR: &str = "prompts";
+const WORKING_LOGS_DIR: &str = "working_logs";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    created_at: i64,
+    range: String,
+    commits: usize,
+    notes: usize,
+    prompts: usize,
+    working_logs: usize,
+}
+
+pub fn handle_export(args: &[String]) {
+    let mut range: Option<String> = None;
+    let mut output: Option<PathBuf> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--output" => {
+                let Some(path) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a path", args[i]);
+                    std::process::exit(1);
+                };
+                output = Some(PathBuf::from(path));
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_export_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_export_help();
+                std::process::exit(1);
+            }
+            other => {
+                if range.is_some() {
+                    eprintln!("Error: only one range can be exported at a time");
+                    std::process::exit(1);
+                }
+                range = Some(other.to_string());
+            }
+        }
+        i += 1;
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let range = range.unwrap_or_else(|| "HEAD".to_string());
+    let output = output.unwrap_or_else(|| PathBuf::from("git-ai-export.tar.zst"));
+    // Archives bound for object storage are staged locally first
+    let object_url = Some(output.to_string_lossy().to_string()).filter(|o| is_object_url(o));
+    let local_output = match &object_url {
+        Some(url) => staging_path(url),
+        None => output.clone(),
+    };
+    let result = export_archive(&repo, &range, &local_output).and_then(|manifest| {
+        if let Some(url) = &object_url {
+            upload_staged(&local_output, url)?;
+        }
+        Ok(manifest)
+    });
+    match result {
+        Ok(manifest) => println!(
+            "{}",
+            tr(
+                "export-done",
+                &[
+                    ("notes", manifest.notes.into()),
+                    ("prompts", manifest.prompts.into()),
+                    ("working_logs", manifest.working_logs.into()),
+                    ("commits", manifest.commits.into()),
+                    ("path", output.display().to_string().into()),
+                ],
+            )
+        ),
+        Err(e) => {
+            eprintln!("Export failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn handle_import(args: &[String]) {
+    let mut archive: Option<PathBuf> = None;
+    let mut force = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--force" => force = true,
+            "-h" | "--help" => {
+                print_import_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_import_help();
+                std::process::exit(1);
+            }
+            other => {
+                if archive.is_some() {
+                    eprintln!("Error: only one archive can be imported at a time");
+                    std::process::exit(1);
+                }
+                archive = Some(PathBuf::from(other));
+            }
+        }
+    }
+
+    let Some(archive) = archive else {
+        print_import_help();
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match import_archive(&repo, &archive, force) {
+        Ok(summary) => {
+            println!(
+                "{}",
+                tr(
+                    "import-done",
+                    &[
+                        ("notes", summary.notes.into()),
+                        ("prompts", summary.prompts.into()),
+                        ("working_logs", summary.working_logs.into()),
+                    ],
+                )
+            );
+            if summary.notes_existing > 0 {
+                println!(
+                    "{}",
+                    tr(
+                        "import-skipped-existing",
+                        &[("count", summary.notes_existing.into())]
+                    )
+                );
+            }
+            if summary.commits_missing > 0 {
+                println!(
+                    "{}",
+                    tr(
+                        "import-skipped-missing",
+                        &[("count", summary.commits_missing.into())]
+                    )
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Import failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn export_archive(repo: &Repository, range: &str, output: &Path) -> Result<Manifest, GitAiError> {
+    let commits = commits_in_range(repo, range)?;
+
+    let note_oids = note_blob_oids_for_commits(repo, &commits)?;
+    let blob_oids: Vec<String> = note_oids
+        .values()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let blobs = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &blob_oids)?;
+    let notes: Vec<(&String, &String)> = commits
+        .iter()
+        .filter_map(|commit| Some((commit, blobs.get(note_oids.get(commit)?)?)))
+        .collect();
+
+    let prompt_ids: BTreeSet<String> = notes
+        .iter()
+        .filter_map(|(_, content)| AuthorshipLog::deserialize_from_string(content).ok())
+        .flat_map(|log| log.metadata.prompts.into_keys())
+        .collect();
+    let prompts = load_prompts(&prompt_ids);
+
+    let working_logs: Vec<&String> = commits
+        .iter()
+        .filter(|commit| repo.storage.has_working_log(commit))
+        .collect();
+
+    let manifest = Manifest {
+        version: ARCHIVE_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        range: range.to_string(),
+        commits: commits.len(),
+        notes: notes.len(),
+        prompts: prompts.len(),
+        working_logs: working_logs.len(),
+    };
+
+    let encoder = zstd::Encoder::new(File::create(output)?, 0)?;
+    let mut builder = tar::Builder::new(encoder);
+    append_bytes(
+        &mut builder,
+        MANIFEST_PATH,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    for (commit, content) in &notes {
+        append_bytes(
+            &mut builder,
+            &format!("{}/{}", NOTES_DIR, commit),
+            content.as_bytes(),
+        )?;
+    }
+    for prompt in &prompts {
+        append_bytes(
+            &mut builder,
+            &format!("{}/{}.json", PROMPTS_DIR, prompt.id),
+            &serde_json::to_vec(prompt)?,
+        )?;
+    }
+    for commit in &working_logs {
+        builder.append_dir_all(
+            format!("{}/{}", WORKING_LOGS_DIR, commit),
+            repo.storage.working_logs.join(commit),
+        )?;
+    }
+    builder.into_inner()?.finish()?;
+
+    Ok(manifest)
+}
+
+/// Every commit reachable from `range` (anything `git rev-list` accepts)
+fn commits_in_range(repo: &Repository, range: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push(range.to_string());
+    args.push("--".to_string());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Prompt records stored locally for `ids`. Prompts that were never saved to the
+/// database (or were pruned) are left out; the notes still carry their metadata.
+pub(crate) fn load_prompts(ids: &BTreeSet<String>) -> Vec<PromptDbRecord> {
+    let Ok(db) = InternalDatabase::global() else {
+        return Vec::new();
+    };
+    let Ok(db) = db.lock() else {
+        return Vec::new();
+    };
+    ids.iter()
+        .filter_map(|id| db.get_prompt(id).ok().flatten())
+        .collect()
+}
+
+fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+) -> Result<(), GitAiError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct ImportSummary {
+    notes: usize,
+    notes_existing: usize,
+    commits_missing: usize,
+    prompts: usize,
+    working_logs: usize,
+}
+
+/// The parts of an archive, read fully into memory before anything is written so a
+/// corrupt archive leaves the repository untouched
+#[derive(Default)]
+struct ArchiveContents {
+    manifest: Option<Manifest>,
+    notes: Vec<(String, String)>,
+    prompts: Vec<PromptDbRecord>,
+    /// Base commit -> files relative to that commit's working log directory
+    working_logs: HashMap<String, Vec<(PathBuf, Vec<u8>)>>,
+}
+
+fn import_archive(
+    repo: &Repository,
+    archive: &Path,
+    force: bool,
+) -> Result<ImportSummary, GitAiError> {
+    let contents = read_archive(archive)?;
+    match &contents.manifest {
+        Some(manifest) if manifest.version > ARCHIVE_VERSION => {
+            return Err(GitAiError::Generic(format!(
+                "archive format version {} is newer than this git-ai supports ({}); upgrade git-ai",
+                manifest.version, ARCHIVE_VERSION
+            )));
+        }
+        Some(_) => {}
+        None => {
+            return Err(GitAiError::Generic(format!(
+                "{} is not a git-ai export (no {})",
+                archive.display(),
+                MANIFEST_PATH
+            )));
+        }
+    }
+
+    let mut summary = ImportSummary::default();
+    let mut referenced: Vec<String> = contents
+        .notes
+        .iter()
+        .map(|(commit, _)| commit.clone())
+        .collect();
+    referenced.extend(contents.working_logs.keys().cloned());
+    let present = existing_commits(repo, &referenced)?;
+
+    let mut notes = Vec::new();
+    let candidates: Vec<String> = contents
+        .notes
+        .iter()
+        .map(|(commit, _)| commit.clone())
+        .filter(|commit| present.contains(commit))
+        .collect();
+    let annotated = if force {
+        HashMap::new()
+    } else {
+        note_blob_oids_for_commits(repo, &candidates)?
+    };
+    for (commit, content) in contents.notes {
+        if !present.contains(&commit) {
+            summary.commits_missing += 1;
+        } else if annotated.contains_key(&commit) {
+            summary.notes_existing += 1;
+        } else {
+            notes.push((commit, content));
+        }
+    }
+    let frozen = if force {
+        FrozenNotes::Overwrite
+    } else {
+        FrozenNotes::Refuse
+    };
+    notes_add_batch_with(repo, &notes, frozen)?;
+    summary.notes = notes.len();
+
+    if !contents.prompts.is_empty() {
+        let db = InternalDatabase::global()?;
+        let mut db = db
+            .lock()
+            .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?;
+        let mut prompts = Vec::new();
+        for prompt in contents.prompts {
+            if force || db.get_prompt(&prompt.id)?.is_none() {
+                prompts.push(prompt);
+            }
+        }
+        db.batch_upsert_prompts(&prompts)?;
+        summary.prompts = prompts.len();
+    }
+
+    for (commit, files) in contents.working_logs {
+        if !present.contains(&commit) {
+            summary.commits_missing += 1;
+            continue;
+        }
+        let dir = repo.storage.working_logs.join(&commit);
+        if dir.exists() {
+            if !force {
+                continue;
+            }
+            std::fs::remove_dir_all(&dir)?;
+        }
+        for (relative, data) in files {
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+        }
+        summary.working_logs += 1;
+    }
+
+    Ok(summary)
+}
+
+fn read_archive(archive: &Path) -> Result<ArchiveContents, GitAiError> {
+    let decoder = zstd::Decoder::new(File::open(archive)?)?;
+    let mut tar = tar::Archive::new(decoder);
+    let mut contents = ArchiveContents::default();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        let components = safe_components(&path).ok_or_else(|| {
+            GitAiError::Generic(format!("unsafe path in archive: {}", path.display()))
+        })?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+
+        match components.as_slice() {
+            [name] if name == MANIFEST_PATH => {
+                contents.manifest = Some(serde_json::from_slice(&data)?);
+            }
+            [dir, commit] if dir == NOTES_DIR => {
+                contents
+                    .notes
+                    .push((commit.clone(), String::from_utf8(data)?));
+            }
+            [dir, _] if dir == PROMPTS_DIR => {
+                contents.prompts.push(serde_json::from_slice(&data)?);
+            }
+            [dir, commit, rest @ ..] if dir == WORKING_LOGS_DIR && !rest.is_empty() => {
+                contents
+                    .working_logs
+                    .entry(commit.clone())
+                    .or_default()
+                    .push((rest.iter().collect(), data));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(contents)
+}
+
+/// The path's components, or `None` if it is absolute or climbs out with `..`
+fn safe_components(path: &Path) -> Option<Vec<String>> {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str().map(str::to_string),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The subset of `commits` that exist in this repository
+pub(crate) fn existing_commits(
+    repo: &Repository,
+    commits: &[String],
+) -> Result<HashSet<String>, GitAiError> {
+    if commits.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("--batch-check=%(objectname) %(objecttype)".to_string());
+    let stdin_data = commits.join("\n") + "\n";
+    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (oid, kind) = line.split_once(' ')?;
+            (kind == "commit").then(|| oid.to_string())
+        })
+        .collect())
+}
+
+fn print_export_help() {
+    eprintln!("git-ai export - Bundle attribution into a portable archive");
+    eprintln!();
+    eprintln!("Usage: git-ai export [<range>] [-o <path>]");
+    eprintln!();
+    eprintln!("Writes the authorship notes, prompt records and working logs for every commit");
+    eprintln!("in <range> (default: HEAD and its history) to a .tar.zst archive that");
+    eprintln!("`git-ai import` can load into another clone.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  -o, --output <path>   Archive to write (default: git-ai-export.tar.zst);");
+    eprintln!("                        s3://bucket/key and gs://bucket/key upload it");
+}
+
+fn print_import_help() {
+    eprintln!("git-ai import - Load an archive written by `git-ai export`");
+    eprintln!();
+    eprintln!("Usage: git-ai import <archive> [--force]");
+    eprintln!();
+    eprintln!("Adds the archive's notes for commits that exist in this clone, saves its");
+    eprintln!("prompt records to the local database and restores its working logs.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --force   Replace notes, prompts and working logs that already exist,");
+    eprintln!("            including notes of commits frozen with `git-ai freeze`");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_components_rejects_escaping_paths() {
+        assert_eq!(
+            safe_components(Path::new("notes/abc")),
+            Some(vec!["notes".to_string(), "abc".to_string()])
+        );
+        assert_eq!(
+            safe_components(Path::new("./manifest.json")),
+            Some(vec!["manifest.json".to_string()])
+        );
+        assert_eq!(safe_components(Path::new("working_logs/../../x")), None);
+        assert_eq!(safe_components(Path::new("/etc/passwd")), None);
+    }
+}
@@ -1,7 +1,11 @@
+use crate::api::cas::CAS_CHUNK_SIZE;
+use crate::api::rate_limit::BACKGROUND_MAX_RETRIES;
 use crate::api::{ApiClient, ApiContext, CasObject, CasUploadRequest};
 use crate::authorship::internal_db::{CasSyncRecord, InternalDatabase};
 use crate::observability::log_error;
+use crate::output::{self, Status};
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 const ENV_CAS_FLUSH_WORKER: &str = "GIT_AI_CAS_FLUSH_WORKER";
 
@@ -14,7 +18,7 @@ pub fn spawn_background_cas_flush() {
 /// Handle the flush-cas command
 pub fn handle_flush_cas(_args: &[String]) {
     // Create API client to check login status
-    let context = ApiContext::new(None);
+    let context = ApiContext::new(None).with_retries(BACKGROUND_MAX_RETRIES);
     let api_base_url = context.base_url.clone();
     let client = ApiClient::new(context);
 
@@ -63,6 +67,12 @@ pub fn handle_flush_cas(_args: &[String]) {
         let mut record_map: HashMap<String, CasSyncRecord> = HashMap::new();
 
         for record in &batch {
+            if record.data.len() > CAS_CHUNK_SIZE {
+                if upload_chunked(&client, db, record, &api_base_url) {
+                    total_synced += 1;
+                }
+                continue;
+            }
             let content: serde_json::Value = match serde_json::from_str(&record.data) {
                 Ok(v) => v,
                 Err(e) => {
@@ -71,7 +81,8 @@ pub fn handle_flush_cas(_args: &[String]) {
                     let _ = db_lock
                         .update_cas_sync_failure(record.id, &format!("JSON parse error: {}", e));
                     eprintln!(
-                        "  ✗ Failed {} (parse error): {}",
+                        "  {} Failed {} (parse error): {}",
+                        output::mode().status_symbol(Status::Error),
                         &record.hash[..16.min(record.hash.len())],
                         e
                     );
@@ -106,9 +117,18 @@ pub fn handle_flush_cas(_args: &[String]) {
                         if result.status == "ok" {
                             // Success - delete from queue
                             if let Err(e) = db_lock.delete_cas_sync_record(record.id) {
-                                eprintln!("  ✗ Failed to delete record for {}: {}", hash_short, e);
+                                eprintln!(
+                                    "  {} Failed to delete record for {}: {}",
+                                    output::mode().status_symbol(Status::Error),
+                                    hash_short,
+                                    e
+                                );
                             } else {
-                                eprintln!("  ✓ Synced {}", hash_short);
+                                eprintln!(
+                                    "  {} Synced {}",
+                                    output::mode().status_symbol(Status::Success),
+                                    hash_short
+                                );
                                 total_synced += 1;
                             }
                         } else {
@@ -128,10 +148,16 @@ pub fn handle_flush_cas(_args: &[String]) {
                             );
 
                             if let Err(e) = db_lock.update_cas_sync_failure(record.id, &error) {
-                                eprintln!("  ✗ Failed to update error for {}: {}", hash_short, e);
+                                eprintln!(
+                                    "  {} Failed to update error for {}: {}",
+                                    output::mode().status_symbol(Status::Error),
+                                    hash_short,
+                                    e
+                                );
                             } else {
                                 eprintln!(
-                                    "  ✗ Failed {} (attempt {}): {}",
+                                    "  {} Failed {} (attempt {}): {}",
+                                    output::mode().status_symbol(Status::Error),
                                     hash_short,
                                     record.attempts + 1,
                                     error
@@ -164,12 +190,15 @@ pub fn handle_flush_cas(_args: &[String]) {
                     if let Err(update_err) = db_lock.update_cas_sync_failure(record.id, &error_msg)
                     {
                         eprintln!(
-                            "  ✗ Failed to update error for {}: {}",
-                            hash_short, update_err
+                            "  {} Failed to update error for {}: {}",
+                            output::mode().status_symbol(Status::Error),
+                            hash_short,
+                            update_err
                         );
                     } else {
                         eprintln!(
-                            "  ✗ Failed {} (attempt {}): {}",
+                            "  {} Failed {} (attempt {}): {}",
+                            output::mode().status_symbol(Status::Error),
                             hash_short,
                             record.attempts + 1,
                             error_msg
@@ -182,8 +211,70 @@ pub fn handle_flush_cas(_args: &[String]) {
     }
 
     if total_synced > 0 {
-        eprintln!("\n✓ Successfully synced {} objects", total_synced);
+        eprintln!(
+            "\n{} Successfully synced {} objects",
+            output::mode().status_symbol(Status::Success),
+            total_synced
+        );
     } else {
-        eprintln!("\n○ No objects were synced");
+        eprintln!(
+            "\n{} No objects were synced",
+            output::mode().status_symbol(Status::Skipped)
+        );
+    }
+}
+
+/// Upload one object too large for a batch in chunks, recording the outcome in
+/// the queue. A failed upload stays queued and resumes from the chunks the
+/// server already has on the next flush.
+fn upload_chunked(
+    client: &ApiClient,
+    db: &Mutex<InternalDatabase>,
+    record: &CasSyncRecord,
+    api_base_url: &str,
+) -> bool {
+    let hash_short = &record.hash[..16.min(record.hash.len())];
+    let result = client.upload_cas_chunked(&record.hash, record.data.as_bytes(), &record.metadata);
+    let mut db_lock = db.lock().unwrap();
+    match result {
+        Ok(()) => {
+            if let Err(e) = db_lock.delete_cas_sync_record(record.id) {
+                eprintln!(
+                    "  {} Failed to delete record for {}: {}",
+                    output::mode().status_symbol(Status::Error),
+                    hash_short,
+                    e
+                );
+                return false;
+            }
+            eprintln!(
+                "  {} Synced {} ({} bytes, chunked)",
+                output::mode().status_symbol(Status::Success),
+                hash_short,
+                record.data.len()
+            );
+            true
+        }
+        Err(e) => {
+            log_error(
+                &e,
+                Some(serde_json::json!({
+                    "operation": "cas_flush_chunked",
+                    "api_host": api_base_url,
+                    "object_hash": record.hash,
+                    "object_size": record.data.len(),
+                    "attempt": record.attempts + 1,
+                })),
+            );
+            let _ = db_lock.update_cas_sync_failure(record.id, &e.to_string());
+            eprintln!(
+                "  {} Failed {} (attempt {}): {}",
+                output::mode().status_symbol(Status::Error),
+                hash_short,
+                record.attempts + 1,
+                e
+            );
+            false
+        }
     }
 }
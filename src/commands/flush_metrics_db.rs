@@ -2,6 +2,7 @@
 //!
 //! Drains the metrics database queue by uploading batches to the API.
 
+use crate::api::rate_limit::BACKGROUND_MAX_RETRIES;
 use crate::api::{ApiClient, ApiContext, upload_metrics_with_retry};
 use crate::metrics::db::MetricsDatabase;
 use crate::metrics::{MetricEvent, MetricsBatch};
@@ -27,22 +28,28 @@ pub fn spawn_background_metrics_db_flush() {}
 
 /// Handle the flush-metrics-db command
 pub fn handle_flush_metrics_db(_args: &[String]) {
+    let _ = upload_queued_metrics();
+}
+
+/// Upload queued metrics until the queue is empty or an upload fails.
+///
+/// Returns the number of events uploaded, or `None` if uploads aren't possible
+/// (using the default API while logged out, or no database).
+pub fn upload_queued_metrics() -> Option<usize> {
     // Check conditions: (!using_default_api) || is_logged_in()
-    let context = ApiContext::new(None);
+    let context = ApiContext::new(None).with_retries(BACKGROUND_MAX_RETRIES);
     let api_base_url = context.base_url.clone();
     let client = ApiClient::new(context);
 
     let using_default_api = api_base_url == crate::config::DEFAULT_API_BASE_URL;
     if using_default_api && !client.is_logged_in() {
         // Conditions not met - exit silently
-        return;
+        return None;
     }
 
     // Get database connection
-    let db = match MetricsDatabase::global() {
-        Ok(db) => db,
-        Err(_) => return,
-    };
+    let db = MetricsDatabase::global().ok()?;
+    let mut uploaded = 0;
 
     loop {
         // Get batch from DB
@@ -92,6 +99,7 @@ pub fn handle_flush_metrics_db(_args: &[String]) {
                 if let Ok(mut db_lock) = db.lock() {
                     let _ = db_lock.delete_records(&record_ids);
                 }
+                uploaded += record_ids.len();
             }
             Err(_) => {
                 // All retries failed - keep records in DB for next time
@@ -99,4 +107,6 @@ pub fn handle_flush_metrics_db(_args: &[String]) {
             }
         }
     }
+
+    Some(uploaded)
 }
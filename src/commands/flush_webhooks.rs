@@ -0,0 +1,53 @@
+//! Handle flush-webhooks command (internal).
+//!
+//! Delivers queued local events to the configured `events.webhook_url`.
+
+use crate::observability::webhook::{deliver_pending, webhook_queue_dir};
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Spawn a background process to deliver queued webhook events
+#[cfg(not(any(test, feature = "test-support")))]
+pub fn spawn_background_webhook_flush() {
+    const ENV_FLUSH_WEBHOOKS_WORKER: &str = "GIT_AI_FLUSH_WEBHOOKS_WORKER";
+
+    let _ = crate::utils::spawn_internal_git_ai_subcommand(
+        "flush-webhooks",
+        &[],
+        ENV_FLUSH_WEBHOOKS_WORKER,
+        &[],
+    );
+}
+
+/// No-op in test mode.
+#[cfg(any(test, feature = "test-support"))]
+pub fn spawn_background_webhook_flush() {}
+
+/// Handle the flush-webhooks command
+pub fn handle_flush_webhooks(_args: &[String]) {
+    let Some(url) = crate::config::Config::get().events_webhook_url() else {
+        return;
+    };
+    let Some(dir) = webhook_queue_dir() else {
+        return;
+    };
+
+    let report = deliver_pending(&dir, url, |url, body| {
+        minreq::post(url)
+            .with_header("Content-Type", "application/json")
+            .with_header(
+                "User-Agent",
+                format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .with_body(body)
+            .with_timeout(WEBHOOK_TIMEOUT_SECS)
+            .send()
+            .map(|response| response.status_code as u16)
+            .map_err(|e| e.to_string())
+    });
+
+    crate::utils::debug_log(&format!(
+        "flush-webhooks: delivered={} dropped={} remaining={}",
+        report.delivered, report.dropped, report.remaining
+    ));
+}
@@ -0,0 +1,238 @@
+//! `git-ai freeze`: mark commits whose authorship has been audited, so later note
+//! rewrites (intern-prompts, imports, replays) refuse to touch them without `--force`.
+//! Markers live as notes under the frozen notes ref, one per commit.
+
+use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::find_repository;
+use crate::git::refs::{frozen_commits, frozen_notes_ref, notes_add_batch_to_ref};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use serde::{Deserialize, Serialize};
+
+/// Who froze a commit, when and why
+#[derive(Debug, Serialize, Deserialize)]
+struct FreezeMarker {
+    frozen_at: String,
+    frozen_by: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+struct FreezeSummary {
+    frozen: usize,
+    already_frozen: usize,
+}
+
+pub fn handle_freeze(args: &[String]) {
+    let mut revs: Vec<String> = Vec::new();
+    let mut reason: Option<String> = None;
+    let mut list = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--list" => list = true,
+            "--reason" => {
+                i += 1;
+                let Some(value) = args.get(i) else {
+                    eprintln!("Error: --reason requires a value");
+                    std::process::exit(1);
+                };
+                reason = Some(value.clone());
+            }
+            "-h" | "--help" => {
+                print_freeze_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_freeze_help();
+                std::process::exit(1);
+            }
+            other => revs.push(other.to_string()),
+        }
+        i += 1;
+    }
+
+    if !list && revs.is_empty() {
+        print_freeze_help();
+        std::process::exit(1);
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if list {
+        if let Err(e) = print_frozen(&repo) {
+            eprintln!("Listing frozen commits failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    match freeze_commits(&repo, &revs, reason) {
+        Ok(summary) => {
+            println!("Froze {} commits", summary.frozen);
+            if summary.already_frozen > 0 {
+                println!("  {} already frozen", summary.already_frozen);
+            }
+        }
+        Err(e) => {
+            eprintln!("Freeze failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn handle_unfreeze(args: &[String]) {
+    let mut revs: Vec<String> = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print_unfreeze_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_unfreeze_help();
+                std::process::exit(1);
+            }
+            other => revs.push(other.to_string()),
+        }
+    }
+
+    if revs.is_empty() {
+        print_unfreeze_help();
+        std::process::exit(1);
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match unfreeze_commits(&repo, &revs) {
+        Ok(count) => println!("Unfroze {} commits", count),
+        Err(e) => {
+            eprintln!("Unfreeze failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn rev_list(repo: &Repository, revs: &[String]) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.extend(revs.iter().cloned());
+    Ok(String::from_utf8(exec_git(&args)?.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+fn freeze_commits(
+    repo: &Repository,
+    revs: &[String],
+    reason: Option<String>,
+) -> Result<FreezeSummary, GitAiError> {
+    let commits = rev_list(repo, revs)?;
+    let frozen = frozen_commits(repo)?;
+
+    let marker = FreezeMarker {
+        frozen_at: chrono::Utc::now().to_rfc3339(),
+        frozen_by: get_commit_default_author(repo, &[]),
+        reason,
+    };
+    let marker = serde_json::to_string(&marker)?;
+
+    // Re-freezing keeps the original marker
+    let entries: Vec<(String, String)> = commits
+        .iter()
+        .filter(|commit| !frozen.contains_key(*commit))
+        .map(|commit| (commit.clone(), marker.clone()))
+        .collect();
+    notes_add_batch_to_ref(repo, &frozen_notes_ref(), &entries)?;
+
+    Ok(FreezeSummary {
+        frozen: entries.len(),
+        already_frozen: commits.len() - entries.len(),
+    })
+}
+
+fn unfreeze_commits(repo: &Repository, revs: &[String]) -> Result<usize, GitAiError> {
+    let frozen = frozen_commits(repo)?;
+    let commits: Vec<String> = rev_list(repo, revs)?
+        .into_iter()
+        .filter(|commit| frozen.contains_key(commit))
+        .collect();
+    if commits.is_empty() {
+        return Ok(0);
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", frozen_notes_ref()));
+    args.push("remove".to_string());
+    args.push("--stdin".to_string());
+    exec_git_stdin(&args, format!("{}\n", commits.join("\n")).as_bytes())?;
+    Ok(commits.len())
+}
+
+fn print_frozen(repo: &Repository) -> Result<(), GitAiError> {
+    let frozen = frozen_commits(repo)?;
+    let mut marker_oids: Vec<String> = frozen.values().cloned().collect();
+    marker_oids.sort();
+    marker_oids.dedup();
+    let markers = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &marker_oids)?;
+
+    let mut commits: Vec<(&String, &String)> = frozen.iter().collect();
+    commits.sort();
+    for (commit, oid) in commits {
+        match markers
+            .get(oid)
+            .and_then(|content| serde_json::from_str::<FreezeMarker>(content).ok())
+        {
+            Some(marker) => {
+                print!(
+                    "{}  frozen {} by {}",
+                    commit, marker.frozen_at, marker.frozen_by
+                );
+                match marker.reason {
+                    Some(reason) => println!(": {}", reason),
+                    None => println!(),
+                }
+            }
+            None => println!("{}  frozen", commit),
+        }
+    }
+    Ok(())
+}
+
+fn print_freeze_help() {
+    eprintln!("Usage: git-ai freeze <rev-range>... [--reason <text>]");
+    eprintln!("       git-ai freeze --list");
+    eprintln!();
+    eprintln!("Protect the authorship notes of released history. git-ai refuses to");
+    eprintln!("rewrite the note of a frozen commit unless the rewriting command is");
+    eprintln!("run with --force.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --reason <text>   Record why the commits were frozen");
+    eprintln!("  --list            Show frozen commits, when and by whom they were frozen");
+}
+
+fn print_unfreeze_help() {
+    eprintln!("Usage: git-ai unfreeze <rev-range>...");
+    eprintln!();
+    eprintln!("Remove the freeze markers written by `git-ai freeze`.");
+}
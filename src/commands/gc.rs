@@ -0,0 +1,325 @@
+//! `git-ai gc --prune-notes-older-than <duration>`: shrink the authorship notes of
+//! old commits. Their line attestations and prompt records are replaced with the
+//! per-tool totals stats reports, so `git-ai stats` on a pruned commit gives the
+//! same numbers while blame no longer attributes its lines. The notes ref's history
+//! is then squashed to one commit, so the replaced notes can be collected. The old
+//! tip is remembered so notes sync replaces the remote's history instead of merging
+//! it back in.
+
+use crate::authorship::authorship_log_serialization::{
+    AuthorshipLog, PrunedAttribution, PrunedToolTotals,
+};
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::commands::export::existing_commits;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::find_repository;
+use crate::git::refs::{all_note_oids, authorship_notes_ref, frozen_commits, notes_add_batch};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Default)]
+struct GcSummary {
+    pruned: usize,
+    frozen: usize,
+    /// Notes on tag objects, which hold tag attribution rather than a log
+    tags: usize,
+    bytes_before: usize,
+    bytes_after: usize,
+}
+
+pub fn handle_gc(args: &[String]) {
+    let mut older_than: Option<Duration> = None;
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--prune-notes-older-than" => {
+                i += 1;
+                let Some(value) = args.get(i) else {
+                    eprintln!("Error: --prune-notes-older-than requires a duration (e.g. 2y)");
+                    std::process::exit(1);
+                };
+                match humantime::parse_duration(value) {
+                    Ok(duration) => older_than = Some(duration),
+                    Err(e) => {
+                        eprintln!("Invalid --prune-notes-older-than '{}': {}", value, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                print_gc_help();
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown option: {}", other);
+                print_gc_help();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let Some(older_than) = older_than else {
+        print_gc_help();
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = now.saturating_sub(older_than.as_secs());
+
+    match prune_notes(&repo, cutoff, dry_run) {
+        Ok(summary) => {
+            println!(
+                "{} {} authorship notes ({} KB -> {} KB)",
+                if dry_run { "Would prune" } else { "Pruned" },
+                summary.pruned,
+                summary.bytes_before / 1024,
+                summary.bytes_after / 1024
+            );
+            if summary.frozen > 0 {
+                println!("  {} frozen commits left untouched", summary.frozen);
+            }
+            if summary.tags > 0 {
+                println!("  {} tag notes left untouched", summary.tags);
+            }
+            if !dry_run && summary.pruned > 0 {
+                let notes_ref = authorship_notes_ref();
+                eprintln!(
+                    "Note: {} was squashed to a single commit. The next push replaces the remote's",
+                    notes_ref
+                );
+                eprintln!(
+                    "      notes history; clones that already fetched it keep the old notes until they prune too."
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Pruning notes failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Replace the attestations of notes on commits committed before `cutoff` (Unix
+/// seconds) with aggregate totals
+fn prune_notes(repo: &Repository, cutoff: u64, dry_run: bool) -> Result<GcSummary, GitAiError> {
+    let note_oids = all_note_oids(repo)?;
+    let targets: Vec<String> = note_oids.keys().cloned().collect();
+    let commit_targets = existing_commits(repo, &targets)?;
+    let frozen = frozen_commits(repo)?;
+    let mut summary = GcSummary::default();
+
+    // Tag attribution notes are JSON, not authorship logs; leave them as they are
+    let mut commits = Vec::new();
+    for target in targets {
+        if commit_targets.contains(&target) {
+            commits.push(target);
+        } else if object_is_tag(repo, &target) {
+            summary.tags += 1;
+        }
+    }
+
+    let mut old_commits = Vec::new();
+    for commit in commits_committed_before(repo, &commits, cutoff)? {
+        if frozen.contains_key(&commit) {
+            summary.frozen += 1;
+        } else {
+            old_commits.push(commit);
+        }
+    }
+
+    let blob_oids: Vec<String> = old_commits
+        .iter()
+        .filter_map(|commit| note_oids.get(commit).cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let blobs = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &blob_oids)?;
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+
+    let mut entries = Vec::new();
+    for commit in old_commits {
+        let Some(content) = note_oids.get(&commit).and_then(|oid| blobs.get(oid)) else {
+            continue;
+        };
+        let Ok(log) = AuthorshipLog::deserialize_from_string(content) else {
+            continue;
+        };
+        // Already pruned, or nothing to prune
+        if log.attestations.is_empty() && log.metadata.prompts.is_empty() {
+            continue;
+        }
+
+        let pruned = pruned_log(repo, &commit, log, &ignore_patterns)?;
+        let pruned_content = pruned
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        summary.pruned += 1;
+        summary.bytes_before += content.len();
+        summary.bytes_after += pruned_content.len();
+        entries.push((commit, pruned_content));
+    }
+
+    if !dry_run && !entries.is_empty() {
+        notes_add_batch(repo, &entries)?;
+        squash_notes_history(repo)?;
+    }
+    Ok(summary)
+}
+
+fn object_is_tag(repo: &Repository, oid: &str) -> bool {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file", "-t", oid].map(String::from));
+    exec_git(&args)
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .is_some_and(|kind| kind.trim() == "tag")
+}
+
+/// Point the notes ref at one parentless commit holding its current notes, so
+/// the notes pruning replaced are no longer reachable and `git gc` can drop them
+fn squash_notes_history(repo: &Repository) -> Result<(), GitAiError> {
+    let notes_ref = authorship_notes_ref();
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["rev-parse", "--verify", &notes_ref].map(String::from));
+    let old_tip = String::from_utf8(exec_git(&args)?.stdout)?
+        .trim()
+        .to_string();
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "commit-tree",
+            &format!("{}^{{tree}}", old_tip),
+            "-m",
+            "Notes squashed by git-ai gc",
+        ]
+        .map(String::from),
+    );
+    let new_tip = String::from_utf8(exec_git(&args)?.stdout)?
+        .trim()
+        .to_string();
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "update-ref",
+            "-m",
+            "git-ai gc: squash pruned notes",
+            &notes_ref,
+            &new_tip,
+            &old_tip,
+        ]
+        .map(String::from),
+    );
+    exec_git(&args)?;
+    repo.storage.add_notes_squashed_tip(&old_tip)?;
+    Ok(())
+}
+
+/// `log` with its attestations and prompts folded into the totals stats reads
+fn pruned_log(
+    repo: &Repository,
+    commit: &str,
+    mut log: AuthorshipLog,
+    ignore_patterns: &[String],
+) -> Result<AuthorshipLog, GitAiError> {
+    let stats = stats_for_commit_stats(repo, commit, ignore_patterns)?;
+
+    let mut tools: BTreeMap<String, PrunedToolTotals> = stats
+        .tool_model_breakdown
+        .into_iter()
+        .map(|(tool_model, tool_stats)| {
+            (
+                tool_model,
+                PrunedToolTotals {
+                    prompts: 0,
+                    ai_accepted: tool_stats.ai_accepted,
+                    total_additions: tool_stats.total_ai_additions,
+                    total_deletions: tool_stats.total_ai_deletions,
+                    overriden_lines: tool_stats.mixed_additions,
+                    time_waiting_for_ai: tool_stats.time_waiting_for_ai,
+                },
+            )
+        })
+        .collect();
+    for prompt in log.metadata.prompts.values() {
+        let tool_model = format!("{}::{}", prompt.agent_id.tool, prompt.agent_id.model);
+        tools.entry(tool_model).or_default().prompts += 1;
+    }
+
+    log.attestations.clear();
+    log.metadata.prompts.clear();
+    log.metadata.pruned = Some(Box::new(PrunedAttribution {
+        ai_accepted: stats.ai_accepted,
+        tools,
+    }));
+    Ok(log)
+}
+
+/// The commits among `commits` whose committer date is before `cutoff`. Notes on
+/// objects that are gone or aren't commits are left alone.
+fn commits_committed_before(
+    repo: &Repository,
+    commits: &[String],
+    cutoff: u64,
+) -> Result<Vec<String>, GitAiError> {
+    if commits.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--no-walk=unsorted".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push("--format=%H %ct".to_string());
+    args.push("--stdin".to_string());
+    let output = exec_git_stdin(&args, format!("{}\n", commits.join("\n")).as_bytes())?;
+
+    let requested: BTreeSet<&str> = commits.iter().map(String::as_str).collect();
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (commit, time) = line.split_once(' ')?;
+            let time: u64 = time.trim().parse().ok()?;
+            (time < cutoff && requested.contains(commit)).then(|| commit.to_string())
+        })
+        .collect())
+}
+
+fn print_gc_help() {
+    eprintln!("Usage: git-ai gc --prune-notes-older-than <duration> [--dry-run]");
+    eprintln!();
+    eprintln!("Replace the line-level attribution in authorship notes of commits older");
+    eprintln!("than <duration> (e.g. 2y, 18months) with per-tool totals. Stats for those");
+    eprintln!("commits stay the same; blame stops attributing their lines to AI, and");
+    eprintln!("their prompt transcripts are dropped from the notes. Frozen commits and");
+    eprintln!("notes on tags are skipped.");
+    eprintln!();
+    eprintln!("The notes ref is then squashed to a single commit so the old notes can be");
+    eprintln!("garbage collected. The next push replaces the remote's notes history (notes");
+    eprintln!("pushed there since are kept), and other clones keep the old notes until");
+    eprintln!("they prune as well.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --prune-notes-older-than <duration>  Age by committer date");
+    eprintln!("  --dry-run                            Report what would be pruned");
+}
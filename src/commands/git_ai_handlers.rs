@@ -1,37 +1,51 @@
 use crate::authorship::ignore::effective_ignore_patterns;
 use crate::authorship::internal_db::InternalDatabase;
 use crate::authorship::range_authorship;
-use crate::authorship::stats::stats_command;
+use crate::authorship::stats::{resolve_stats_target, stats_command, stats_for_commit_stats};
+use crate::authorship::transcript::NO_REDACT_METADATA_KEY;
 use crate::authorship::working_log::{AgentId, CheckpointKind};
 use crate::commands;
+use crate::commands::checkpoint_agent::agent_detection::{infer_agent, inferred_run_result};
 use crate::commands::checkpoint_agent::agent_presets::{
-    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, AiTabPreset, ClaudePreset,
-    CodexPreset, ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset, GithubCopilotPreset,
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, AiTabPreset, AiderPreset,
+    ClaudePreset, CodexPreset, ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset,
+    GithubCopilotPreset, WindsurfPreset, ZedPreset,
 };
 use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Preset;
+use crate::commands::checkpoint_agent::copilot_preset::CopilotPreset;
 use crate::commands::checkpoint_agent::opencode_preset::OpenCodePreset;
+use crate::commands::checkpoint_agent::plugin_source_preset::PluginSourcePreset;
 use crate::config;
 use crate::git::find_repository;
 use crate::git::find_repository_in_path;
-use crate::git::repository::{CommitRange, group_files_by_repository};
+use crate::git::repository::{
+    CommitRange, Repository, find_repository_for_file, group_files_by_repository,
+};
 use crate::observability::wrapper_performance_targets::log_performance_for_checkpoint;
 use crate::observability::{self, log_message};
+use crate::output;
+use crate::reporting::filters::AuthorFilter;
+use crate::reporting::templates::{StatsTemplateContext, render_template};
 use crate::utils::is_interactive_terminal;
 use std::env;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn handle_git_ai(args: &[String]) {
+    let args = &output::init_from_args(args);
     if args.is_empty() {
         print_help();
         return;
     }
+    let _span = crate::observability::log::span(&args[0]);
 
     // Start DB warmup early for commands that need database access
     match args[0].as_str() {
-        "checkpoint" | "show-prompt" | "share" | "sync-prompts" | "flush-cas" | "search"
-        | "continue" => {
+        "checkpoint" | "show-prompt" | "share" | "sync-prompts" | "sync" | "flush-cas"
+        | "search" | "continue" | "explain" | "context" | "export" | "import" | "archive"
+        | "unbundle" => {
             InternalDatabase::warmup();
         }
         _ => {}
@@ -70,12 +84,69 @@ pub fn handle_git_ai(args: &[String]) {
         "checkpoint" => {
             handle_checkpoint(&args[1..]);
         }
+        "run" => {
+            commands::run::handle_run(&args[1..]);
+        }
         "blame" => {
             handle_ai_blame(&args[1..]);
             if is_interactive_terminal() {
                 log_message("blame", "info", None)
             }
         }
+        "explain" => {
+            commands::explain::handle_explain(&args[1..]);
+        }
+        "context" => {
+            commands::context::handle_context(&args[1..]);
+        }
+        "log" => {
+            commands::log::handle_log(&args[1..]);
+        }
+        "squash" => {
+            commands::squash::handle_squash(&args[1..]);
+        }
+        "split" => {
+            commands::split::handle_split(&args[1..]);
+        }
+        "suggest-cherry-picks" => {
+            commands::suggest_cherry_picks::handle_suggest_cherry_picks(&args[1..]);
+        }
+        "export" => {
+            commands::export::handle_export(&args[1..]);
+        }
+        "import" => {
+            commands::export::handle_import(&args[1..]);
+        }
+        "archive" => {
+            commands::archive::handle_archive(&args[1..]);
+        }
+        "unbundle" => {
+            commands::archive::handle_unbundle(&args[1..]);
+        }
+        "intern-prompts" => {
+            commands::intern_prompts::handle_intern_prompts(&args[1..]);
+        }
+        "freeze" => {
+            commands::freeze::handle_freeze(&args[1..]);
+        }
+        "unfreeze" => {
+            commands::freeze::handle_unfreeze(&args[1..]);
+        }
+        "pr" => {
+            commands::pr::handle_pr(&args[1..]);
+        }
+        "watch" => {
+            commands::watch::handle_watch(&args[1..]);
+        }
+        "ownership" => {
+            commands::ownership::handle_ownership(&args[1..]);
+        }
+        "report" => {
+            commands::report::handle_report(&args[1..]);
+        }
+        "correlate" => {
+            commands::correlate::handle_correlate(&args[1..]);
+        }
         "diff" => {
             handle_ai_diff(&args[1..]);
             if is_interactive_terminal() {
@@ -83,9 +154,13 @@ pub fn handle_git_ai(args: &[String]) {
             }
         }
         "git-path" => {
-            let config = config::Config::get();
-            println!("{}", config.git_cmd());
-            std::process::exit(0);
+            commands::git_path::handle_git_path(&args[1..]);
+        }
+        "doctor" => {
+            commands::doctor::handle_doctor(&args[1..]);
+        }
+        "mcp" => {
+            commands::mcp::handle_mcp(&args[1..]);
         }
         "install-hooks" | "install" => match commands::install_hooks::run(&args[1..]) {
             Ok(statuses) => {
@@ -98,6 +173,24 @@ pub fn handle_git_ai(args: &[String]) {
                 std::process::exit(1);
             }
         },
+        "maintenance" => {
+            commands::maintenance::handle_maintenance(&args[1..]);
+        }
+        "cache" => {
+            commands::cache::handle_cache(&args[1..]);
+        }
+        "gc" => {
+            commands::gc::handle_gc(&args[1..]);
+        }
+        "db" => {
+            commands::db::handle_db(&args[1..]);
+        }
+        "setup-container" => {
+            commands::setup_container::handle_setup_container(&args[1..]);
+        }
+        "mdm" => {
+            commands::mdm::handle_mdm(&args[1..]);
+        }
         "uninstall-hooks" => match commands::install_hooks::run_uninstall(&args[1..]) {
             Ok(statuses) => {
                 if let Ok(statuses_value) = serde_json::to_value(&statuses) {
@@ -109,6 +202,12 @@ pub fn handle_git_ai(args: &[String]) {
                 std::process::exit(1);
             }
         },
+        "uninstall" => {
+            commands::uninstall::handle_uninstall(&args[1..]);
+        }
+        "apply" => {
+            commands::apply::handle_apply(&args[1..]);
+        }
         "git-hooks" => {
             handle_git_hooks(&args[1..]);
         }
@@ -121,6 +220,12 @@ pub fn handle_git_ai(args: &[String]) {
         "upgrade" => {
             commands::upgrade::run_with_args(&args[1..]);
         }
+        "verify-push" => {
+            commands::verify_push::handle_verify_push(&args[1..]);
+        }
+        "sync" => {
+            commands::sync::handle_sync(&args[1..]);
+        }
         "flush-logs" => {
             commands::flush_logs::handle_flush_logs(&args[1..]);
         }
@@ -130,6 +235,12 @@ pub fn handle_git_ai(args: &[String]) {
         "flush-metrics-db" => {
             commands::flush_metrics_db::handle_flush_metrics_db(&args[1..]);
         }
+        "warm-blame-cache" => {
+            handle_warm_blame_cache(&args[1..]);
+        }
+        "flush-webhooks" => {
+            commands::flush_webhooks::handle_flush_webhooks(&args[1..]);
+        }
         "login" => {
             commands::login::handle_login(&args[1..]);
         }
@@ -157,9 +268,15 @@ pub fn handle_git_ai(args: &[String]) {
         "search" => {
             commands::search::handle_search(&args[1..]);
         }
+        "sessions" => {
+            commands::sessions::handle_sessions(&args[1..]);
+        }
         "continue" => {
             commands::continue_session::handle_continue(&args[1..]);
         }
+        "schema" => {
+            commands::schema::handle_schema(&args[1..]);
+        }
         #[cfg(debug_assertions)]
         "show-transcript" => {
             handle_show_transcript(&args[1..]);
@@ -174,33 +291,99 @@ pub fn handle_git_ai(args: &[String]) {
 fn print_help() {
     eprintln!("git-ai - git proxy with AI authorship tracking");
     eprintln!();
-    eprintln!("Usage: git-ai <command> [args...]");
+    eprintln!("Usage: git-ai [--no-color] [--ascii] [--plain] <command> [args...]");
+    eprintln!();
+    eprintln!("Output options (also NO_COLOR, GIT_AI_ASCII=1, GIT_AI_PLAIN=1):");
+    eprintln!("  --no-color         Don't use color");
+    eprintln!("  --ascii            Use ASCII instead of unicode symbols, bars and spinners");
+    eprintln!("  --plain            No color or animation; linear `label: value` output");
+    eprintln!("Messages and numbers follow GIT_AI_LOCALE, then LC_ALL/LC_MESSAGES/LANG (en, de)");
     eprintln!();
     eprintln!("Commands:");
     eprintln!("  checkpoint         Checkpoint working changes and attribute author");
     eprintln!(
-        "    Presets: claude, codex, continue-cli, cursor, gemini, github-copilot, ai_tab, mock_ai"
+        "    Presets: aider, claude, codex, continue-cli, copilot, cursor, gemini, github-copilot, windsurf, zed, ai_tab, mock_ai"
     );
     eprintln!(
         "    --hook-input <json|stdin>   JSON payload required by presets, or 'stdin' to read from stdin"
     );
     eprintln!("    --show-working-log          Display current working log");
     eprintln!("    --reset                     Reset working log");
+    eprintln!(
+        "    --with <user>               Credit a pairing partner for the changes (repeatable)"
+    );
+    eprintln!(
+        "    --no-redact                 Store the transcript without redaction (see redaction.*)"
+    );
     eprintln!("    mock_ai [pathspecs...]      Test preset accepting optional file pathspecs");
+    eprintln!("    <name>                      Runs the git-ai-source-<name> plugin on PATH");
+    eprintln!("  watch              Checkpoint manual edits automatically as files change");
+    eprintln!(
+        "    --debounce <ms>             Quiet period before checkpointing (watch_debounce_ms)"
+    );
+    eprintln!("  run -- <command>   Run a CLI agent and checkpoint its edits as AI");
+    eprintln!("    --tool <name>               Tool to record (default: the command's name)");
+    eprintln!("    --model <name>              Model to record (default: unknown)");
+    eprintln!("    --interval <secs>           How often to check for edits (default: 2)");
     eprintln!("  blame <file>       Git blame with AI authorship overlay");
+    eprintln!("  blame <dir>        Per-file AI/human percentages under a directory, rolled up");
+    eprintln!(
+        "  explain <file>:<line>  Explain who wrote a line, from which prompt, and its history"
+    );
+    eprintln!(
+        "  context --file <path> --lines <a>,<b>  Sessions, prompts and commits behind a span"
+    );
+    eprintln!("    --json                 Output in JSON format (to feed back to an agent)");
+    eprintln!("  ownership --file <path>  AI/human share of a file at HEAD and its last editors");
+    eprintln!(
+        "    --json                 Output in JSON format (cached per HEAD, for review bots)"
+    );
+    eprintln!(
+        "  correlate --sarif <file>  Static analysis findings per kLOC, AI vs human, per rule"
+    );
+    eprintln!("    --json | --csv         Output in JSON or CSV format");
+    eprintln!("  log [<git log args>]  git log --oneline with each commit's AI share");
+    eprintln!("    --min-ai <pct>         Only commits with at least this AI share");
+    eprintln!(
+        "  suggest-cherry-picks --from <branch>  Commits on another branch written mostly by an agent"
+    );
+    eprintln!("    --agent <tool>         Only count lines from this agent (e.g. claude)");
+    eprintln!("    --session <id>         Only count lines from this session or prompt id");
+    eprintln!("    --min-share <pct>      Share of added lines required (default: 50)");
     eprintln!("  diff <commit|range>  Show diff with AI authorship annotations");
     eprintln!("    <commit>              Diff from commit's parent to commit");
     eprintln!("    <commit1>..<commit2>  Diff between two commits");
     eprintln!("  stats [commit]     Show AI authorship statistics for a commit");
+    eprintln!("    <commit1>..<commit2>   Statistics for a range of commits");
+    eprintln!(
+        "    --since <rev> [--until <rev>]  Range from <rev> to --until (default HEAD), with --breakdown"
+    );
+    eprintln!("    --breakdown            Totals per author, tool and model in ranges");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!(
+        "    --exclude-bots         Leave out commits by bots (dependabot, renovate, ...) in ranges"
+    );
+    eprintln!(
+        "    --author <pattern>     Only count commits by matching authors in ranges (repeatable)"
+    );
+    eprintln!("    --all-authors          Ignore report.bot_authors / report.authors config");
+    eprintln!(
+        "    --template <name|path> Render with a template (markdown, digest, release-notes, pr-comment, or ~/.git-ai/templates/<name>.md.j2)"
+    );
     eprintln!("  status             Show uncommitted AI authorship status (debug)");
     eprintln!("    --json                 Output in JSON format");
     eprintln!("  show <rev|range>   Display authorship logs for a revision or range");
+    eprintln!("    --tag <tag>        Display the attribution note on an annotated tag");
+    eprintln!("    --json             With --tag, print the note as JSON");
     eprintln!("  show-prompt <id>   Display a prompt record by its ID");
     eprintln!("    --commit <rev>        Look in a specific commit only");
     eprintln!(
         "    --offset <n>          Skip n occurrences (0 = most recent, mutually exclusive with --commit)"
     );
+    eprintln!(
+        "  prompts show <rev> [<id>]  List the prompts in a commit's note, or print one's transcript"
+    );
+    eprintln!("    --json                Output in JSON format");
     eprintln!("  share <id>         Share a prompt by creating a bundle");
     eprintln!("    --title <title>       Custom title for the bundle (default: auto-generated)");
     eprintln!("  sync-prompts       Update prompts in database to latest versions");
@@ -209,6 +392,32 @@ fn print_help() {
         "                          Formats: '1d', '2h', '1w', Unix timestamp, ISO8601, YYYY-MM-DD"
     );
     eprintln!("    --workdir <path>      Only sync prompts from specific repository");
+    eprintln!("  sync --retry-pending  Resend telemetry, metrics and prompts queued while offline");
+    eprintln!(
+        "  export [<range>]   Bundle notes, prompts and working logs into a .tar.zst archive"
+    );
+    eprintln!("    -o, --output <path>   Archive to write (default: git-ai-export.tar.zst)");
+    eprintln!("  import <archive>   Load an archive from `git-ai export` into this clone");
+    eprintln!(
+        "    --force               Replace notes, prompts and working logs that already exist,"
+    );
+    eprintln!("                          including notes of frozen commits");
+    eprintln!("  archive [<rev>]    Write a git bundle that carries the authorship notes");
+    eprintln!("    -o, --out <path>      Bundle to write");
+    eprintln!("    --json <path>         Also write the notes and their prompts as JSON");
+    eprintln!("  unbundle <bundle>  Restore refs and notes from a `git-ai archive` bundle");
+    eprintln!("    --json <path>         Import prompts from the archive's JSON sidecar");
+    eprintln!("  intern-prompts [<rev>]  Store each prompt transcript once, referenced from notes");
+    eprintln!("    --dry-run             Report what would be interned");
+    eprintln!("    --force               Also rewrite notes of frozen commits");
+    eprintln!("  freeze <range>     Refuse later rewrites of these commits' authorship notes");
+    eprintln!("    --reason <text>       Record why the commits were frozen");
+    eprintln!("    --list                Show frozen commits");
+    eprintln!("  unfreeze <range>   Remove freeze markers");
+    eprintln!("  pr view <number>   Show a pull request's diff with AI attribution per hunk");
+    eprintln!("    --remote <name>       Remote to fetch the PR head and notes from");
+    eprintln!("    --repo <owner/name>   GitHub repository the PR belongs to");
+    eprintln!("    --no-fetch            Use only commits and notes already local");
     eprintln!("  config             View and manage git-ai configuration");
     eprintln!("                        Show all config as formatted JSON");
     eprintln!("    <key>                 Show specific config value (supports dot notation)");
@@ -217,17 +426,63 @@ fn print_help() {
     eprintln!("    unset <key>           Remove config value (reverts to default)");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
     eprintln!("  uninstall-hooks    Remove git-ai hooks from all detected tools");
+    eprintln!("  uninstall          Remove hooks, git shims, global git config and internal state");
+    eprintln!("    --dry-run             Show what would be removed");
+    eprintln!("    --keep-data           Keep ~/.git-ai/internal");
+    eprintln!("  apply <manifest>   Onboard this repository from a YAML manifest");
+    eprintln!("    --dry-run             Show what would change");
+    eprintln!("  mdm jetbrains      Pin, roll back or update the JetBrains IDE plugin");
+    eprintln!("    --pin <version>       Install exactly this plugin version (--unpin to clear)");
+    eprintln!("    --channel <name>      Marketplace channel when unpinned: stable or beta");
+    eprintln!("    --repo                Record the pin in this repository's git config");
+    eprintln!("    --check               Report installed and target versions only");
+    eprintln!("    --dry-run             Show what would be installed without installing");
+    eprintln!(
+        "  setup-container    Non-interactive setup for devcontainers and Codespaces prebuilds"
+    );
+    eprintln!("    --check               Exit 3 if setup would change anything");
+    eprintln!("    --skip-hooks          Don't install hooks into preinstalled agents");
     eprintln!("  git-hooks ensure   Ensure repo-local git-ai hooks are installed/healed");
+    eprintln!("  maintenance        Scheduled background maintenance (like git maintenance)");
+    eprintln!("    register              Schedule nightly maintenance for this repository");
+    eprintln!("    unregister            Remove this repository from scheduled maintenance");
+    eprintln!(
+        "    run [--task <name>]   Run maintenance tasks now (--schedule: all registered repos)"
+    );
+    eprintln!("  db                 Maintain the local metrics database");
+    eprintln!("    upgrade               Migrate to the current schema (backs up first)");
+    eprintln!("    vacuum                Reclaim unused space");
+    eprintln!("    prune --older-than <dur>  Delete data older than <dur> (e.g. 30d)");
+    eprintln!("  cache clear        Remove this repository's cached notes, ownership and blame");
+    eprintln!("  gc                 Shrink authorship notes of old commits");
+    eprintln!("    --prune-notes-older-than <dur>  Keep only per-tool totals (e.g. 2y)");
+    eprintln!("    --dry-run             Report what would be pruned");
     eprintln!("  ci                 Continuous integration utilities");
     eprintln!("    github                 GitHub CI helpers");
+    eprintln!("    annotate-pr            Comment AI authorship stats on a GitHub PR");
+    eprintln!("    gitlab annotate-mr     Comment AI authorship stats on a GitLab MR");
     eprintln!("  squash-authorship  Generate authorship log for squashed commits");
     eprintln!(
         "    <base_branch> <new_sha> <old_sha>  Required: base branch, new commit SHA, old commit SHA"
     );
     eprintln!("    --dry-run             Show what would be done without making changes");
     eprintln!("  git-path           Print the path to the underlying git executable");
+    eprintln!("    list                  List detected git binaries, best first");
+    eprintln!("    pin [<path>]          Pin the git binary git-ai runs (default: best detected)");
+    eprintln!("    unpin                 Go back to auto-detecting git");
+    eprintln!("    check                 Warn if the pinned git binary has changed");
+    eprintln!("  doctor             Check git-ai's setup and suggest fixes for problems");
+    eprintln!("  mcp zed            Serve the checkpoint tool to Zed's agent (MCP over stdio)");
+    eprintln!("  dash               Open your personal dashboard in the browser");
+    eprintln!(
+        "    --tz <zone>           Time zone for daily/weekly rollups (default: report.timezone or UTC)"
+    );
     eprintln!("  upgrade            Check for updates and install if available");
     eprintln!("    --force               Reinstall latest version even if already up to date");
+    eprintln!("  verify-push        Check that commits about to be pushed have authorship notes");
+    eprintln!("    [<remote>]            Remote to compare against (default: upstream)");
+    eprintln!("    --warn                Report missing or stale notes but exit 0");
+    eprintln!("    --json                Output as JSON");
     eprintln!("  prompts            Create local SQLite database for prompt analysis");
     eprintln!("    --since <time>        Only include prompts after this time (default: 30d)");
     eprintln!("    --author <name>       Filter by human author (default: current git user)");
@@ -251,6 +506,10 @@ fn print_help() {
     eprintln!("    --verbose             Include full transcripts");
     eprintln!("    --porcelain           Stable machine-parseable format");
     eprintln!("    --count               Just show result count");
+    eprintln!("  sessions           Agent sessions across repositories");
+    eprintln!(
+        "    show <id> [--json]    Repositories and commits of a session (prompt or session id)"
+    );
     eprintln!("  continue           Restore AI session context and launch agent");
     eprintln!("    --commit <rev>        Continue from a specific commit");
     eprintln!("    --file <path>         Continue from a specific file");
@@ -260,7 +519,9 @@ fn print_help() {
     eprintln!("    --launch              Launch agent CLI with restored context");
     eprintln!("    --clipboard           Copy context to system clipboard");
     eprintln!("    --json                Output context as structured JSON");
+    eprintln!("  schema [<name>]    Print the JSON Schema of a --json output, hook input or note");
     eprintln!("  login              Authenticate with Git AI");
+    eprintln!("    --device              Print a code to approve from another device (SSH, CI)");
     eprintln!("  logout             Clear stored credentials");
     eprintln!("  version, -v, --version     Print the git-ai version");
     eprintln!("  help, -h, --help           Show this help message");
@@ -277,7 +538,9 @@ fn handle_checkpoint(args: &[String]) {
     // Parse checkpoint-specific arguments
     let mut show_working_log = false;
     let mut reset = false;
+    let mut redact = true;
     let mut hook_input = None;
+    let mut co_authors: Vec<String> = Vec::new();
 
     let mut i = 0;
     while i < args.len() {
@@ -290,6 +553,22 @@ fn handle_checkpoint(args: &[String]) {
                 reset = true;
                 i += 1;
             }
+            "--no-redact" => {
+                redact = false;
+                i += 1;
+            }
+            "--with" => {
+                match args.get(i + 1).map(|v| v.trim()) {
+                    Some(co_author) if !co_author.is_empty() => {
+                        co_authors.push(co_author.to_string());
+                    }
+                    _ => {
+                        eprintln!("Error: --with requires a user (name, email or 'Name <email>')");
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
             "--hook-input" => {
                 if i + 1 < args.len() {
                     hook_input = Some(args[i + 1].clone());
@@ -324,6 +603,8 @@ fn handle_checkpoint(args: &[String]) {
     }
 
     let mut agent_run_result = None;
+    // Human baseline recorded just before the main checkpoint (inline completion events)
+    let mut baseline_run_result = None;
     // Handle preset arguments after parsing all flags
     if !args.is_empty() {
         match args[0].as_str() {
@@ -425,6 +706,9 @@ fn handle_checkpoint(args: &[String]) {
                     hook_input: hook_input.clone(),
                 }) {
                     Ok(agent_run) => {
+                        baseline_run_result = hook_input
+                            .as_deref()
+                            .and_then(AiTabPreset::completion_baseline);
                         if agent_run.repo_working_dir.is_some() {
                             repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
                         }
@@ -465,6 +749,35 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 }
             }
+            "copilot" => {
+                match CopilotPreset.run(AgentCheckpointFlags {
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        if agent_run.repo_working_dir.is_some() {
+                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
+                        }
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Copilot preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
+            "windsurf" => {
+                match WindsurfPreset.run(AgentCheckpointFlags {
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Windsurf preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
             "opencode" => {
                 match OpenCodePreset.run(AgentCheckpointFlags {
                     hook_input: hook_input.clone(),
@@ -481,6 +794,32 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 }
             }
+            "zed" => {
+                match ZedPreset.run(AgentCheckpointFlags {
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        if agent_run.repo_working_dir.is_some() {
+                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
+                        }
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Zed preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
+            "aider" => {
+                // Aider's lint command: the edited files follow the preset name
+                let edited_filepaths: Vec<String> = args[1..]
+                    .iter()
+                    .filter(|arg| !arg.starts_with("--"))
+                    .cloned()
+                    .collect();
+                agent_run_result =
+                    Some(AiderPreset.run_for_files(&repository_working_dir, edited_filepaths));
+            }
             "mock_ai" => {
                 let mock_agent_id = format!(
                     "ai-thread-{}",
@@ -524,10 +863,46 @@ fn handle_checkpoint(args: &[String]) {
                     dirty_files: None,
                 });
             }
-            _ => {}
+            name => {
+                // Not a built-in preset: defer to a git-ai-source-<name> plugin if one exists
+                if let Some(preset) = PluginSourcePreset::find(name) {
+                    match preset.run(AgentCheckpointFlags {
+                        hook_input: hook_input.clone(),
+                    }) {
+                        Ok(agent_run) => {
+                            if agent_run.repo_working_dir.is_some() {
+                                repository_working_dir =
+                                    agent_run.repo_working_dir.clone().unwrap();
+                            }
+                            agent_run_result = Some(agent_run);
+                        }
+                        Err(e) => {
+                            eprintln!("{} plugin error: {}", name, e);
+                            std::process::exit(0);
+                        }
+                    }
+                }
+            }
         }
     }
 
+    // Presets run from agent hooks; a bare `git-ai checkpoint` gets its human run below
+    let from_agent_hook = agent_run_result.is_some();
+
+    // Transcripts can carry secrets pasted into the chat; scrub them before the
+    // checkpoint writes the transcript to the working log and prompt database
+    if redact
+        && let Some(transcript) = agent_run_result
+            .as_mut()
+            .and_then(|run| run.transcript.as_mut())
+    {
+        transcript.redact_for_storage();
+    } else if !redact && let Some(run) = agent_run_result.as_mut() {
+        run.agent_metadata
+            .get_or_insert_default()
+            .insert(NO_REDACT_METADATA_KEY.to_string(), "true".to_string());
+    }
+
     let final_working_dir = agent_run_result
         .as_ref()
         .and_then(|r| r.repo_working_dir.clone())
@@ -547,20 +922,30 @@ fn handle_checkpoint(args: &[String]) {
         std::process::exit(0);
     }
 
+    let files_to_check = agent_run_result.as_ref().and_then(|r| {
+        if r.checkpoint_kind == CheckpointKind::Human {
+            r.will_edit_filepaths.as_ref()
+        } else {
+            r.edited_filepaths.as_ref()
+        }
+    });
+
+    // An agent running from one checkout can edit files in a linked worktree of the
+    // same repository, or in one of its submodules. Those edits belong to that
+    // checkout's working log.
+    let edits_in_other_checkout = match (&repo_result, files_to_check) {
+        (Ok(repo), Some(files)) => {
+            has_edits_in_other_checkout(repo, files, &repository_working_dir)
+        }
+        _ => false,
+    };
+
     // If the working directory is not a git repository, we need to detect repos from file paths
     // This happens in multi-repo workspaces where the workspace root contains multiple git repos
-    let needs_file_based_repo_detection = repo_result.is_err();
+    let needs_file_based_repo_detection = repo_result.is_err() || edits_in_other_checkout;
 
     if needs_file_based_repo_detection {
         // Workspace root is not a git repo - try to detect repositories from edited files
-        let files_to_check = agent_run_result.as_ref().and_then(|r| {
-            if r.checkpoint_kind == CheckpointKind::Human {
-                r.will_edit_filepaths.as_ref()
-            } else {
-                r.edited_filepaths.as_ref()
-            }
-        });
-
         if let Some(files) = files_to_check
             && !files.is_empty()
         {
@@ -580,9 +965,12 @@ fn handle_checkpoint(args: &[String]) {
                 })
                 .collect();
 
-            // Group files by their containing repository
+            // Group files by their containing repository. Worktrees can live outside
+            // the workspace root, so don't bound the search in that case.
+            let workspace_boundary =
+                (!edits_in_other_checkout).then_some(repository_working_dir.as_str());
             let (repo_files, orphan_files) =
-                group_files_by_repository(&absolute_files, Some(&repository_working_dir));
+                group_files_by_repository(&absolute_files, workspace_boundary);
 
             if repo_files.is_empty() {
                 eprintln!(
@@ -609,6 +997,8 @@ fn handle_checkpoint(args: &[String]) {
                     "Multi-repo workspace detected. Found {} repositories with edits.",
                     repo_files.len()
                 );
+            } else if edits_in_other_checkout {
+                eprintln!("Edited files are in another worktree or a submodule. Checkpointing it.");
             } else {
                 eprintln!(
                     "Workspace root is not a git repository. Detected repository from edited files."
@@ -669,9 +1059,13 @@ fn handle_checkpoint(args: &[String]) {
                 });
 
                 commands::git_hook_handlers::ensure_repo_level_hooks_for_checkpoint(&repo);
+                if from_agent_hook {
+                    record_agent_edit_state(&repo, checkpoint_kind);
+                }
                 let checkpoint_result = commands::checkpoint::run(
                     &repo,
                     &default_user_name,
+                    &co_authors,
                     checkpoint_kind,
                     show_working_log,
                     reset,
@@ -731,7 +1125,7 @@ fn handle_checkpoint(args: &[String]) {
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|_| final_working_dir.clone());
 
-    let checkpoint_kind = agent_run_result
+    let mut checkpoint_kind = agent_run_result
         .as_ref()
         .map(|r| r.checkpoint_kind)
         .unwrap_or(CheckpointKind::Human);
@@ -749,26 +1143,42 @@ fn handle_checkpoint(args: &[String]) {
             Some(get_all_files_for_mock_ai(&effective_working_dir))
         };
 
-        agent_run_result = Some(AgentRunResult {
-            agent_id: AgentId {
-                tool: "mock_ai".to_string(),
-                id: format!(
-                    "ai-thread-{}",
-                    SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .map(|d| d.as_nanos())
-                        .unwrap_or_else(|_| 0)
-                ),
-                model: "unknown".to_string(),
-            },
-            agent_metadata: None,
-            checkpoint_kind: CheckpointKind::Human,
-            transcript: None,
-            will_edit_filepaths: Some(will_edit_filepaths.unwrap_or_default()),
-            edited_filepaths: None,
-            repo_working_dir: Some(effective_working_dir),
-            dirty_files: None,
-        });
+        // No agent reported itself; one may still be running without its hooks
+        if config.feature_flags().infer_agent
+            && let Some(agent) = infer_agent(Path::new(&effective_working_dir))
+        {
+            eprintln!(
+                "Inferred agent {} from {}; checkpointing as AI",
+                agent.tool, agent.evidence
+            );
+            checkpoint_kind = CheckpointKind::AiAgent;
+            agent_run_result = Some(inferred_run_result(
+                agent,
+                effective_working_dir.clone(),
+                will_edit_filepaths.unwrap_or_default(),
+            ));
+        } else {
+            agent_run_result = Some(AgentRunResult {
+                agent_id: AgentId {
+                    tool: "mock_ai".to_string(),
+                    id: format!(
+                        "ai-thread-{}",
+                        SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|d| d.as_nanos())
+                            .unwrap_or_else(|_| 0)
+                    ),
+                    model: "unknown".to_string(),
+                },
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::Human,
+                transcript: None,
+                will_edit_filepaths: Some(will_edit_filepaths.unwrap_or_default()),
+                edited_filepaths: None,
+                repo_working_dir: Some(effective_working_dir),
+                dirty_files: None,
+            });
+        }
     }
 
     // Get the current user name from git config
@@ -783,9 +1193,28 @@ fn handle_checkpoint(args: &[String]) {
     let checkpoint_start = std::time::Instant::now();
     let agent_tool = agent_run_result.as_ref().map(|r| r.agent_id.tool.clone());
     commands::git_hook_handlers::ensure_repo_level_hooks_for_checkpoint(&repo);
+    if from_agent_hook {
+        record_agent_edit_state(&repo, checkpoint_kind);
+    }
+    if let Some(baseline) = baseline_run_result
+        && let Err(e) = commands::checkpoint::run(
+            &repo,
+            &default_user_name,
+            &co_authors,
+            CheckpointKind::Human,
+            false,
+            false,
+            true,
+            Some(baseline),
+            false,
+        )
+    {
+        eprintln!("Completion baseline checkpoint failed: {}", e);
+    }
     let checkpoint_result = commands::checkpoint::run(
         &repo,
         &default_user_name,
+        &co_authors,
         checkpoint_kind,
         show_working_log,
         reset,
@@ -819,6 +1248,17 @@ fn handle_checkpoint(args: &[String]) {
     }
 }
 
+/// Agent hooks bracket each edit with a human checkpoint before it and an AI one
+/// after; `git-ai watch` holds off while an edit is in flight
+fn record_agent_edit_state(repo: &Repository, kind: CheckpointKind) {
+    if let Err(e) = repo
+        .storage
+        .set_agent_edit_pending(kind == CheckpointKind::Human)
+    {
+        crate::utils::debug_log(&format!("Failed to record agent edit state: {}", e));
+    }
+}
+
 fn handle_ai_blame(args: &[String]) {
     if args.is_empty() {
         eprintln!("Error: blame requires a file argument");
@@ -847,6 +1287,15 @@ fn handle_ai_blame(args: &[String]) {
         }
     };
 
+    // A directory gets a per-file summary instead of line-by-line blame
+    if std::path::Path::new(&current_dir).join(&file_path).is_dir() {
+        if let Err(e) = commands::ownership::blame_directory(&repo, &file_path, options.json) {
+            eprintln!("Blame failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Auto-detect ignore-revs-file if not explicitly provided, not disabled via --no-ignore-revs-file,
     // and git version supports --ignore-revs-file (git >= 2.23)
     if options.ignore_revs_file.is_none()
@@ -940,14 +1389,71 @@ fn handle_stats(args: &[String]) {
     let mut commit_sha = None;
     let mut commit_range: Option<CommitRange> = None;
     let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut exclude_bots = false;
+    let mut authors: Vec<String> = Vec::new();
+    let mut all_authors = false;
+    let mut template: Option<String> = None;
+    let mut since: Option<String> = None;
+    let mut until: Option<String> = None;
+    let mut breakdown = false;
 
     let mut i = 0;
     while i < args.len() {
+        if let Some(value) = args[i].strip_prefix("--since=") {
+            since = Some(value.to_string());
+            i += 1;
+            continue;
+        }
+        if let Some(value) = args[i].strip_prefix("--until=") {
+            until = Some(value.to_string());
+            i += 1;
+            continue;
+        }
         match args[i].as_str() {
             "--json" => {
                 json_output = true;
                 i += 1;
             }
+            "--since" | "--until" => {
+                if i + 1 >= args.len() {
+                    eprintln!("{} requires a revision", args[i]);
+                    std::process::exit(1);
+                }
+                if args[i] == "--since" {
+                    since = Some(args[i + 1].clone());
+                } else {
+                    until = Some(args[i + 1].clone());
+                }
+                i += 2;
+            }
+            "--breakdown" => {
+                breakdown = true;
+                i += 1;
+            }
+            "--template" => {
+                if i + 1 >= args.len() {
+                    eprintln!("--template requires a template name or path");
+                    std::process::exit(1);
+                }
+                template = Some(args[i + 1].clone());
+                i += 2;
+            }
+            "--exclude-bots" => {
+                exclude_bots = true;
+                i += 1;
+            }
+            "--all-authors" => {
+                all_authors = true;
+                i += 1;
+            }
+            "--author" => {
+                if i + 1 >= args.len() {
+                    eprintln!("--author requires a value");
+                    std::process::exit(1);
+                }
+                authors.push(args[i + 1].clone());
+                i += 2;
+            }
             "--ignore" => {
                 // Collect all arguments after --ignore until we hit another flag or commit SHA
                 // This supports shell glob expansion: `--ignore *.lock` expands to `--ignore Cargo.lock package.lock`
@@ -983,21 +1489,7 @@ fn handle_stats(args: &[String]) {
                     if arg.contains("..") {
                         let parts: Vec<&str> = arg.split("..").collect();
                         if parts.len() == 2 {
-                            match CommitRange::new_infer_refname(
-                                &repo,
-                                parts[0].to_string(),
-                                parts[1].to_string(),
-                                // @todo this is probably fine, but we might want to give users an option to override from this command.
-                                None,
-                            ) {
-                                Ok(range) => {
-                                    commit_range = Some(range);
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to create commit range: {}", e);
-                                    std::process::exit(1);
-                                }
-                            }
+                            commit_range = Some(stats_commit_range(&repo, parts[0], parts[1]));
                         } else {
                             eprintln!("Invalid commit range format. Expected: <commit>..<commit>");
                             std::process::exit(1);
@@ -1014,13 +1506,47 @@ fn handle_stats(args: &[String]) {
         }
     }
 
+    // --since/--until is another way to spell a range, and always reports the breakdown
+    if since.is_some() || until.is_some() {
+        if commit_range.is_some() || commit_sha.is_some() {
+            eprintln!("--since/--until cannot be combined with a commit or range argument");
+            std::process::exit(1);
+        }
+        let Some(since) = since else {
+            eprintln!("--until requires --since");
+            std::process::exit(1);
+        };
+        let until = until.unwrap_or_else(|| "HEAD".to_string());
+        commit_range = Some(stats_commit_range(&repo, &since, &until));
+        breakdown = true;
+    }
+
     let effective_patterns = effective_ignore_patterns(&repo, &ignore_patterns, &[]);
 
     // Handle commit range if detected
     if let Some(range) = commit_range {
-        match range_authorship::range_authorship(range, false, &effective_patterns) {
+        let range_title = (
+            short_sha(&range.start_oid).to_string(),
+            short_sha(&range.end_oid).to_string(),
+        );
+        let author_filter = AuthorFilter::for_report(exclude_bots, &authors, all_authors);
+        match range_authorship::range_authorship_filtered(
+            range,
+            false,
+            &effective_patterns,
+            &author_filter,
+            breakdown,
+        ) {
             Ok(stats) => {
-                if json_output {
+                if let Some(template) = &template {
+                    let title = format!("{}..{}", range_title.0, range_title.1);
+                    let context = StatsTemplateContext::new(
+                        &title,
+                        &stats.range_stats,
+                        Some(&stats.authorship_stats),
+                    );
+                    print_rendered_template(template, &context);
+                } else if json_output {
                     let json_str = serde_json::to_string(&stats).unwrap();
                     println!("{}", json_str);
                 } else {
@@ -1035,6 +1561,23 @@ fn handle_stats(args: &[String]) {
         return;
     }
 
+    if let Some(template) = &template {
+        let result = resolve_stats_target(&repo, commit_sha.as_deref()).and_then(|(sha, _)| {
+            stats_for_commit_stats(&repo, &sha, &effective_patterns).map(|stats| (sha, stats))
+        });
+        match result {
+            Ok((sha, stats)) => {
+                let context = StatsTemplateContext::new(short_sha(&sha), &stats, None);
+                print_rendered_template(template, &context);
+            }
+            Err(e) => {
+                eprintln!("Stats failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     if let Err(e) = stats_command(
         &repo,
         commit_sha.as_deref(),
@@ -1053,6 +1596,36 @@ fn handle_stats(args: &[String]) {
     }
 }
 
+fn stats_commit_range<'a>(repo: &'a Repository, start: &str, end: &str) -> CommitRange<'a> {
+    match CommitRange::new_infer_refname(
+        repo,
+        start.to_string(),
+        end.to_string(),
+        // @todo this is probably fine, but we might want to give users an option to override from this command.
+        None,
+    ) {
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("Failed to create commit range: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn print_rendered_template(template: &str, context: &StatsTemplateContext) {
+    match render_template(template, context) {
+        Ok(output) => print!("{}", output),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn handle_git_hooks(args: &[String]) {
     match args.first().map(String::as_str) {
         Some("ensure") => {
@@ -1091,6 +1664,59 @@ fn handle_git_hooks(args: &[String]) {
     }
 }
 
+fn handle_warm_blame_cache(args: &[String]) {
+    let [workdir, from, to] = args else {
+        eprintln!("Usage: git-ai warm-blame-cache <repo-path> <from> <to>");
+        std::process::exit(1);
+    };
+    let repo = match find_repository_in_path(workdir) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+    match crate::authorship::attribution_cache::warm_for_range(&repo, from, to) {
+        Ok(warmed) => eprintln!("Warmed blame cache with {} authorship notes", warmed),
+        Err(e) => {
+            eprintln!("Failed to warm blame cache: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Whether any of `files` belongs to a different worktree of `repo`'s repository,
+/// or to a submodule checked out inside it
+fn has_edits_in_other_checkout(repo: &Repository, files: &[String], base_dir: &str) -> bool {
+    let Ok(workdir) = repo.workdir() else {
+        return false;
+    };
+    files.iter().any(|file| {
+        let path = std::path::Path::new(base_dir).join(file);
+        // Only files outside this checkout or below a `.git` file (a worktree or
+        // submodule root) can belong elsewhere; skip the git lookup for the rest
+        let maybe_elsewhere = !path.starts_with(&workdir)
+            || path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| *dir != workdir)
+                .any(|dir| dir.join(".git").is_file());
+        if !maybe_elsewhere {
+            return false;
+        }
+        match find_repository_for_file(&path.to_string_lossy(), None) {
+            Ok(file_repo) => {
+                file_repo.storage.repo_path != repo.storage.repo_path
+                    && (file_repo.storage.common_dir == repo.storage.common_dir
+                        || file_repo
+                            .workdir()
+                            .is_ok_and(|dir| dir.starts_with(&workdir)))
+            }
+            Err(_) => false,
+        }
+    })
+}
+
 fn get_all_files_for_mock_ai(working_dir: &str) -> Vec<String> {
     // Find the git repository
     let repo = match find_repository_in_path(working_dir) {
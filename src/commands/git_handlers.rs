@@ -13,14 +13,19 @@ use crate::commands::hooks::merge_hooks;
 use crate::commands::hooks::push_hooks;
 use crate::commands::hooks::rebase_hooks;
 use crate::commands::hooks::reset_hooks;
+use crate::commands::hooks::restore_hooks;
+use crate::commands::hooks::revert_hooks;
 use crate::commands::hooks::stash_hooks;
 use crate::commands::hooks::switch_hooks;
+use crate::commands::hooks::tag_hooks;
 use crate::config;
 use crate::git::cli_parser::{ParsedGitInvocation, parse_git_cli_args};
 use crate::git::find_repository;
 use crate::git::repository::{Repository, disable_internal_git_hooks};
+use crate::git::self_invocation;
 use crate::observability;
-
+use crate::observability::budgets::{check_budget, hook_operation};
+use crate::observability::profile;
 use crate::observability::wrapper_performance_targets::log_performance_target_if_violated;
 #[cfg(windows)]
 use crate::utils::CREATE_NO_WINDOW;
@@ -102,6 +107,13 @@ pub struct CommandHooksContext {
 }
 
 pub fn handle_git(args: &[String]) {
+    // A misconfigured git path can make the "real git" git-ai itself
+    self_invocation::exit_if_recursive(
+        args,
+        config::Config::get().git_cmd(),
+        &config::real_git_candidates_from_config_file(),
+    );
+
     // If we're being invoked from a shell completion context, bypass git-ai logic
     // and delegate directly to the real git so existing completion scripts work.
     if in_shell_completion_context() {
@@ -157,33 +169,45 @@ pub fn handle_git(args: &[String]) {
         }
 
         let pre_command_start = Instant::now();
-        run_pre_command_hooks(&mut command_hooks_context, &mut parsed_args, repository);
+        {
+            let _scope = profile::scope("pre_command_hooks");
+            run_pre_command_hooks(&mut command_hooks_context, &mut parsed_args, repository);
+        }
         let pre_command_duration = pre_command_start.elapsed();
+        let command_name = parsed_args.command.as_deref().unwrap_or("unknown");
+        check_budget(&hook_operation("pre", command_name), pre_command_duration);
 
         let child_hooks_path_override =
             resolve_child_git_hooks_path_override(&parsed_args, Some(repository));
         let git_start = Instant::now();
-        let exit_status = proxy_to_git(
-            &parsed_args.to_invocation_vec(),
-            false,
-            child_hooks_path_override.as_deref(),
-        );
+        let exit_status = {
+            let _scope = profile::scope("git");
+            proxy_to_git(
+                &parsed_args.to_invocation_vec(),
+                false,
+                child_hooks_path_override.as_deref(),
+            )
+        };
         if exit_status_was_interrupted(&exit_status) {
             exit_with_status(exit_status);
         }
         let git_duration = git_start.elapsed();
 
         let post_command_start = Instant::now();
-        run_post_command_hooks(
-            &mut command_hooks_context,
-            &parsed_args,
-            exit_status,
-            repository,
-        );
+        {
+            let _scope = profile::scope("post_command_hooks");
+            run_post_command_hooks(
+                &mut command_hooks_context,
+                &parsed_args,
+                exit_status,
+                repository,
+            );
+        }
         let post_command_duration = post_command_start.elapsed();
+        check_budget(&hook_operation("post", command_name), post_command_duration);
 
         log_performance_target_if_violated(
-            parsed_args.command.as_deref().unwrap_or("unknown"),
+            command_name,
             pre_command_duration,
             git_duration,
             post_command_duration,
@@ -348,6 +372,9 @@ fn run_pre_command_hooks(
             Some("reset") => {
                 reset_hooks::pre_reset_hook(parsed_args, repository);
             }
+            Some("revert") => {
+                revert_hooks::pre_revert_hook(repository);
+            }
             Some("cherry-pick") => {
                 cherry_pick_hooks::pre_cherry_pick_hook(
                     parsed_args,
@@ -379,6 +406,9 @@ fn run_pre_command_hooks(
             Some("switch") => {
                 switch_hooks::pre_switch_hook(parsed_args, repository, command_hooks_context);
             }
+            Some("restore") => {
+                restore_hooks::pre_restore_hook(repository);
+            }
             _ => {}
         }
     }));
@@ -446,6 +476,7 @@ fn run_post_command_hooks(
                 exit_status,
                 repository,
             ),
+            Some("revert") => revert_hooks::post_revert_hook(parsed_args, exit_status, repository),
             Some("cherry-pick") => cherry_pick_hooks::post_cherry_pick_hook(
                 command_hooks_context,
                 parsed_args,
@@ -480,6 +511,10 @@ fn run_post_command_hooks(
                     command_hooks_context,
                 );
             }
+            Some("restore") => {
+                restore_hooks::post_restore_hook(parsed_args, repository, exit_status);
+            }
+            Some("tag") => tag_hooks::post_tag_hook(parsed_args, exit_status, repository),
             _ => {}
         }
     }));
@@ -587,6 +622,7 @@ fn proxy_to_git(
             }
             cmd.args(args);
             cmd.env(ENV_SKIP_MANAGED_HOOKS, "1");
+            self_invocation::mark_git_child(&mut cmd);
             unsafe {
                 let setpgid_flag = should_setpgid;
                 cmd.pre_exec(move || {
@@ -613,6 +649,7 @@ fn proxy_to_git(
             }
             cmd.args(args);
             cmd.env(ENV_SKIP_MANAGED_HOOKS, "1");
+            self_invocation::mark_git_child(&mut cmd);
 
             #[cfg(windows)]
             {
@@ -697,6 +734,7 @@ fn proxy_to_git(
 
 // Exit mirroring the child's termination: same signal if signaled, else exit code
 fn exit_with_status(status: std::process::ExitStatus) -> ! {
+    profile::finish();
     #[cfg(unix)]
     {
         if let Some(sig) = status.signal() {
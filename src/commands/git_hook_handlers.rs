@@ -10,6 +10,7 @@ use crate::error::GitAiError;
 use crate::git::cli_parser::ParsedGitInvocation;
 use crate::git::repository::{Repository, disable_internal_git_hooks};
 use crate::git::sync_authorship::fetch_authorship_notes;
+use crate::observability::budgets::check_budget;
 use crate::utils::{debug_log, debug_performance_log_structured};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -747,6 +748,30 @@ pub fn mark_repo_hooks_enabled(repo: &Repository) -> Result<bool, GitAiError> {
     Ok(true)
 }
 
+/// Unsets a global `core.hooksPath` that points into a git-ai directory, which would
+/// leave every repository running missing hooks once git-ai is gone. Returns the
+/// value that was (or, with `dry_run`, would be) removed.
+pub fn restore_global_hooks_path(dry_run: bool) -> Result<Option<String>, GitAiError> {
+    let global_config_path = global_git_config_path();
+    let Some(hooks_path) =
+        read_hooks_path_from_config(&global_config_path, gix_config::Source::User)
+    else {
+        return Ok(None);
+    };
+    if !is_disallowed_forward_hooks_path(Path::new(hooks_path.trim()), None, None) {
+        return Ok(None);
+    }
+
+    if !dry_run {
+        let mut cfg = load_config(&global_config_path, gix_config::Source::User)?;
+        if let Ok(mut core) = cfg.section_mut("core", None) {
+            core.remove("hooksPath");
+        }
+        write_config(&global_config_path, &cfg)?;
+    }
+    Ok(Some(hooks_path))
+}
+
 fn is_repo_hooks_enabled(repo: &Repository) -> bool {
     let path = repo_enablement_path(repo);
     path.exists() || path.symlink_metadata().is_ok()
@@ -2148,7 +2173,7 @@ fn run_managed_hook(
         }
         "pre-push" => {
             let parsed = parsed_invocation("push", hook_args.to_vec());
-            push_hooks::run_pre_push_hook_managed(&parsed, &repo);
+            push_hooks::run_pre_push_hook_managed(&parsed, &repo, stdin);
             0
         }
         "reference-transaction" => {
@@ -2257,6 +2282,7 @@ pub fn handle_git_hook_invocation(hook_name: &str, hook_args: &[String]) -> i32
     if std::env::var(ENV_SKIP_ALL_HOOKS).as_deref() == Ok("1") {
         return 0;
     }
+    let _span = crate::observability::log::span(hook_name);
 
     let skip_managed_hooks = std::env::var(ENV_SKIP_MANAGED_HOOKS).as_deref() == Ok("1")
         || std::env::var(ENV_SKIP_MANAGED_HOOKS_LEGACY).as_deref() == Ok("1");
@@ -2292,6 +2318,8 @@ pub fn handle_git_hook_invocation(hook_name: &str, hook_args: &[String]) -> i32
             let managed_start = Instant::now();
             let managed_status = run_managed_hook(hook_name, hook_args, &stdin_data, repo.as_ref());
             managed_ms = managed_start.elapsed().as_millis();
+            // Same budget names as the wrapper, e.g. `post-commit` -> `post_commit`
+            check_budget(&hook_name.replace('-', "_"), managed_start.elapsed());
             if managed_status != 0 {
                 if perf_enabled {
                     debug_performance_log_structured(serde_json::json!({
@@ -0,0 +1,130 @@
+//! `git-ai git-path`: show, list, pin and check the real git binary git-ai runs.
+
+use crate::config::{self, Config};
+use crate::git::real_git::{self, PinStatus};
+use std::path::{Path, PathBuf};
+
+pub fn handle_git_path(args: &[String]) {
+    let result = match args.first().map(String::as_str) {
+        None => {
+            println!("{}", Config::get().git_cmd());
+            Ok(())
+        }
+        Some("list") | Some("--candidates") => list_candidates(),
+        Some("pin") => pin(args.get(1).map(PathBuf::from)),
+        Some("unpin") => unpin(),
+        Some("check") => check(),
+        Some("help") | Some("--help") | Some("-h") => {
+            print_help();
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown git-path subcommand: {}", other)),
+    };
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_help() {
+    eprintln!("git-ai git-path - The real git binary git-ai runs");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai git-path              Print the path to the git binary in use");
+    eprintln!("  git-ai git-path list         List detected git binaries, best first");
+    eprintln!("  git-ai git-path pin [<path>] Pin a git binary (default: the best detected one)");
+    eprintln!("  git-ai git-path unpin        Go back to auto-detection");
+    eprintln!("  git-ai git-path check        Check that the pinned binary hasn't changed");
+}
+
+fn list_candidates() -> Result<(), String> {
+    let file_config = config::load_file_config_public()?;
+    let in_use = Path::new(Config::get().git_cmd());
+    let candidates = real_git::discover_candidates(Some(&file_config));
+    if candidates.is_empty() {
+        return Err("No git binaries found".to_string());
+    }
+    for candidate in candidates {
+        let marker = if candidate.path == in_use { "*" } else { " " };
+        println!(
+            "{} {}  {}  ({})",
+            marker,
+            candidate.path.display(),
+            candidate.version,
+            candidate.source.as_str()
+        );
+    }
+    Ok(())
+}
+
+fn pin(path: Option<PathBuf>) -> Result<(), String> {
+    let mut file_config = config::load_file_config_public()?;
+    let path = match path {
+        Some(path) => std::path::absolute(&path).unwrap_or(path),
+        None => {
+            // Re-pinning picks afresh rather than keeping the old pin
+            file_config.git = None;
+            real_git::discover_candidates(Some(&file_config))
+                .into_iter()
+                .next()
+                .map(|candidate| candidate.path)
+                .ok_or_else(|| "No git binaries found to pin".to_string())?
+        }
+    };
+    let pinned = real_git::pin(&path)?;
+    eprintln!(
+        "Pinned git: {} (git version {})",
+        path.display(),
+        pinned.version.as_deref().unwrap_or("unknown")
+    );
+    file_config.git = Some(pinned);
+    config::save_file_config(&file_config)
+}
+
+fn unpin() -> Result<(), String> {
+    let mut file_config = config::load_file_config_public()?;
+    if file_config.git.take().is_none() {
+        eprintln!("No git binary is pinned");
+        return Ok(());
+    }
+    config::save_file_config(&file_config)?;
+    eprintln!("Unpinned git; auto-detecting it again");
+    Ok(())
+}
+
+fn check() -> Result<(), String> {
+    let file_config = config::load_file_config_public()?;
+    let status = real_git::check_pin(file_config.git.as_ref());
+    match &status {
+        PinStatus::NotPinned => {
+            println!("No git binary is pinned; using {}", Config::get().git_cmd())
+        }
+        PinStatus::Unchanged { path, version } => {
+            println!("Pinned git {} (git version {}) is unchanged", path, version)
+        }
+        PinStatus::Missing { path } => eprintln!(
+            "Warning: pinned git {} no longer exists. Run `git-ai git-path pin` to pin another.",
+            path
+        ),
+        PinStatus::Changed {
+            path,
+            pinned_version,
+            version,
+        } => eprintln!(
+            "Warning: pinned git {} has changed since it was pinned (git version {} -> {}). \
+             Run `git-ai git-path pin {}` if the new binary is expected.",
+            path,
+            pinned_version.as_deref().unwrap_or("unknown"),
+            version.as_deref().unwrap_or("unknown"),
+            path
+        ),
+    }
+    if status.is_ok() {
+        Ok(())
+    } else {
+        std::process::exit(1);
+    }
+}
@@ -150,7 +150,11 @@ pub fn post_checkout_hook(
 }
 
 /// Remove attributions for specific files from working log (pathspec checkout case).
-fn remove_attributions_for_pathspecs(repository: &Repository, head: &str, pathspecs: &[String]) {
+pub(crate) fn remove_attributions_for_pathspecs(
+    repository: &Repository,
+    head: &str,
+    pathspecs: &[String],
+) {
     let working_log = repository.storage.working_log_for_base_commit(head);
 
     // Filter INITIAL attributions
@@ -181,7 +185,9 @@ fn remove_attributions_for_pathspecs(repository: &Repository, head: &str, pathsp
 
 fn matches_any_pathspec(file: &str, pathspecs: &[String]) -> bool {
     pathspecs.iter().any(|p| {
-        file == p
+        // An empty pathspec is the repository root
+        p.is_empty()
+            || file == p
             || (p.ends_with('/') && file.starts_with(p))
             || file.starts_with(&format!("{}/", p))
     })
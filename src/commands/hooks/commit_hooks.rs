@@ -1,8 +1,10 @@
 use crate::authorship::pre_commit;
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::Repository;
 use crate::git::rewrite_log::RewriteLogEvent;
+use crate::policy::{AttributionSummary, PolicyStage, enforce_policies};
 use crate::utils::debug_log;
 
 pub fn commit_pre_command_hook(
@@ -31,6 +33,31 @@ pub fn commit_pre_command_hook(
         eprintln!("Pre-commit failed: {}", e);
         std::process::exit(1);
     }
+
+    let no_verify =
+        parsed_args.has_command_flag("--no-verify") || parsed_args.has_command_flag("-n");
+    if !enforce_policies(repository, PolicyStage::PreCommit, no_verify, || {
+        let base_commit = repository
+            .pre_command_base_commit
+            .clone()
+            .unwrap_or_else(|| "initial".to_string());
+        let checkpoints = repository
+            .storage
+            .working_log_for_base_commit(&base_commit)
+            .read_all_checkpoints()?;
+        let mut summary = AttributionSummary::from_checkpoints(&base_commit, &checkpoints);
+        let diff_base = if base_commit == "initial" {
+            EMPTY_TREE_HASH
+        } else {
+            base_commit.as_str()
+        };
+        let staged = repository.get_staged_filenames()?;
+        let added_lines = repository.diff_workdir_added_lines(diff_base, Some(&staged))?;
+        summary.add_file_lines(&checkpoints, &added_lines);
+        Ok(summary)
+    }) {
+        std::process::exit(1);
+    }
     true
 }
 
@@ -1,8 +1,10 @@
+use crate::authorship::attribution_cache;
 use crate::authorship::virtual_attribution::{VirtualAttributions, restore_stashed_va};
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
 use crate::commands::hooks::rebase_hooks::build_rebase_commit_mappings;
 use crate::commands::upgrade;
+use crate::config::Config;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::{Repository, exec_git, find_repository};
 use crate::git::rewrite_log::RewriteLogEvent;
@@ -116,9 +118,9 @@ pub fn pull_pre_command_hook(
 }
 
 pub fn fetch_pull_post_command_hook(
-    _repository: &Repository,
-    _parsed_args: &ParsedGitInvocation,
-    _exit_status: std::process::ExitStatus,
+    repository: &Repository,
+    parsed_args: &ParsedGitInvocation,
+    exit_status: std::process::ExitStatus,
     command_hooks_context: &mut CommandHooksContext,
 ) {
     // Always wait for the authorship fetch thread to complete if it was started,
@@ -127,6 +129,32 @@ pub fn fetch_pull_post_command_hook(
     if let Some(handle) = command_hooks_context.fetch_authorship_handle.take() {
         let _ = handle.join();
     }
+
+    if !exit_status.success() || is_dry_run(&parsed_args.command_args) {
+        return;
+    }
+
+    // Warm the cache for what the current branch would pick up from its upstream
+    if Config::get().feature_flags().warm_blame_cache
+        && let (Some(head), Some(upstream)) = (
+            rev_parse(repository, "HEAD"),
+            rev_parse(repository, "@{upstream}"),
+        )
+    {
+        attribution_cache::spawn_background_warm(repository, &head, &upstream);
+    }
+}
+
+fn rev_parse(repository: &Repository, rev: &str) -> Option<String> {
+    let mut args = repository.global_args_for_exec();
+    args.extend(
+        ["rev-parse", "--verify", "--quiet", rev]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args).ok()?;
+    let sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!sha.is_empty()).then_some(sha)
 }
 
 /// Post-command hook for git pull.
@@ -166,6 +194,10 @@ pub fn pull_post_command_hook(
         return;
     }
 
+    if Config::get().feature_flags().warm_blame_cache {
+        attribution_cache::spawn_background_warm(repository, &old_head, &new_head);
+    }
+
     // Check if we have a stashed VA to restore (from pull --rebase --autostash)
     if let Some(stashed_va) = command_hooks_context.stashed_va.take() {
         restore_stashed_va(repository, &old_head, &new_head, stashed_va);
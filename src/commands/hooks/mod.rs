@@ -7,5 +7,8 @@ pub mod merge_hooks;
 pub mod push_hooks;
 pub mod rebase_hooks;
 pub mod reset_hooks;
+pub mod restore_hooks;
+pub mod revert_hooks;
 pub mod stash_hooks;
 pub mod switch_hooks;
+pub mod tag_hooks;
@@ -1,8 +1,10 @@
 use crate::commands::git_handlers::CommandHooksContext;
-use crate::commands::upgrade;
+use crate::commands::{upgrade, verify_push};
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
+use crate::git::push_updates::PushUpdate;
 use crate::git::repository::{Repository, find_repository};
 use crate::git::sync_authorship::push_authorship_notes;
+use crate::policy::{AttributionSummary, PolicyStage, enforce_policies};
 use crate::utils::debug_log;
 
 pub fn push_pre_command_hook(
@@ -16,6 +18,7 @@ pub fn push_pre_command_hook(
         return None;
     }
     let remote = resolve_push_remote(parsed_args, repository);
+    enforce_push_policies(parsed_args, repository, remote.as_deref(), None);
 
     if let Some(remote) = remote {
         debug_log(&format!(
@@ -50,14 +53,26 @@ pub fn push_pre_command_hook(
     }
 }
 
-pub fn run_pre_push_hook_managed(parsed_args: &ParsedGitInvocation, repository: &Repository) {
+/// `pre_push_input` is the hook's stdin, listing the refs being pushed
+pub fn run_pre_push_hook_managed(
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+    pre_push_input: &[u8],
+) {
     upgrade::maybe_schedule_background_update_check();
 
     if should_skip_authorship_push(&parsed_args.command_args) {
         return;
     }
 
-    let Some(remote) = resolve_push_remote(parsed_args, repository) else {
+    let remote = resolve_push_remote(parsed_args, repository);
+    enforce_push_policies(
+        parsed_args,
+        repository,
+        remote.as_deref(),
+        Some(pre_push_input),
+    );
+    let Some(remote) = remote else {
         debug_log("no remotes found for authorship push; skipping");
         return;
     };
@@ -93,6 +108,92 @@ pub fn push_post_command_hook(
     }
 }
 
+/// Run pre-push policies and the `verify_push` note check, exiting (and so aborting
+/// the push) if any of them fails. The pushed refs come from `pre_push_input`
+/// (pre-push hook stdin) when given, and from the push's refspecs otherwise.
+fn enforce_push_policies(
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+    remote: Option<&str>,
+    pre_push_input: Option<&[u8]>,
+) {
+    if parsed_args.has_command_flag("--no-verify") {
+        return;
+    }
+    let Some(remote) = remote else {
+        return;
+    };
+    let updates = match pre_push_input {
+        Some(input) => PushUpdate::parse_pre_push_input(&String::from_utf8_lossy(input)),
+        None => {
+            match PushUpdate::from_refspecs(repository, &push_refspecs(&parsed_args.command_args)) {
+                Ok(updates) => updates,
+                Err(e) => {
+                    debug_log(&format!("could not resolve pushed refs: {}", e));
+                    return;
+                }
+            }
+        }
+    };
+    if !enforce_policies(repository, PolicyStage::PrePush, false, || {
+        AttributionSummary::for_push(repository, remote, &updates)
+    }) {
+        std::process::exit(1);
+    }
+    if !verify_push::verify_before_push(repository, remote, &updates) {
+        std::process::exit(1);
+    }
+}
+
+/// The refspecs a `git push` names: its positional arguments after the
+/// repository, or every branch (and tag) for `--all`/`--branches` (`--tags`)
+fn push_refspecs(command_args: &[String]) -> Vec<String> {
+    let mut refspecs = Vec::new();
+    if command_args
+        .iter()
+        .any(|arg| arg == "--all" || arg == "--branches")
+    {
+        refspecs.push("refs/heads/*".to_string());
+    }
+    if command_args.iter().any(|arg| arg == "--tags") {
+        refspecs.push("refs/tags/*".to_string());
+    }
+    if !refspecs.is_empty() {
+        return refspecs;
+    }
+
+    let mut repository_named = command_args
+        .iter()
+        .any(|arg| arg == "--repo" || arg.starts_with("--repo="));
+    let mut i = 0;
+    while i < command_args.len() {
+        let arg = &command_args[i];
+        if arg == "--" {
+            let rest = &command_args[i + 1..];
+            let rest = if repository_named {
+                rest
+            } else {
+                rest.get(1..).unwrap_or_default()
+            };
+            refspecs.extend(rest.iter().cloned());
+            break;
+        }
+        if arg.starts_with('-') {
+            let consumes_next = is_push_option_with_inline_value(arg).is_none()
+                && option_consumes_separate_value(arg);
+            i += if consumes_next { 2 } else { 1 };
+            continue;
+        }
+        if repository_named {
+            refspecs.push(arg.clone());
+        } else {
+            repository_named = true;
+        }
+        i += 1;
+    }
+    refspecs
+}
+
 fn should_skip_authorship_push(command_args: &[String]) -> bool {
     is_dry_run(command_args)
         || command_args.iter().any(|a| a == "-d" || a == "--delete")
@@ -221,6 +322,34 @@ mod tests {
         assert!(should_skip_authorship_push(&strings(&["--mirror"])));
     }
 
+    #[test]
+    fn push_refspecs_follow_the_repository_argument() {
+        assert!(push_refspecs(&strings(&[])).is_empty());
+        assert!(push_refspecs(&strings(&["origin"])).is_empty());
+        assert_eq!(
+            push_refspecs(&strings(&[
+                "-o",
+                "ci.skip",
+                "origin",
+                "main",
+                "+dev:release"
+            ])),
+            strings(&["main", "+dev:release"])
+        );
+        assert_eq!(
+            push_refspecs(&strings(&["--repo", "origin", "main"])),
+            strings(&["main"])
+        );
+        assert_eq!(
+            push_refspecs(&strings(&["origin", "--", "feature"])),
+            strings(&["feature"])
+        );
+        assert_eq!(
+            push_refspecs(&strings(&["--all", "origin"])),
+            strings(&["refs/heads/*"])
+        );
+    }
+
     #[test]
     fn resolve_push_remote_prefers_positional_remote() {
         let args = strings(&["origin", "main"]);
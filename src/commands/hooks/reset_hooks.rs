@@ -14,6 +14,7 @@ pub fn pre_reset_hook(parsed_args: &ParsedGitInvocation, repository: &mut Reposi
     let _result = crate::commands::checkpoint::run(
         repository,
         &human_author,
+        &[],
         CheckpointKind::Human,
         false,
         false,
@@ -0,0 +1,88 @@
+use crate::commands::hooks::checkout_hooks::remove_attributions_for_pathspecs;
+use crate::git::cli_parser::{ParsedGitInvocation, parse_restore_args};
+use crate::git::repository::{Repository, resolve_command_base_dir};
+use crate::utils::debug_log;
+use std::path::{Component, Path};
+
+pub fn pre_restore_hook(repository: &mut Repository) {
+    repository.require_pre_command_head();
+}
+
+/// `git restore` never moves HEAD; like a pathspec checkout it overwrites files in
+/// the working tree, so their attributions no longer describe what's on disk.
+pub fn post_restore_hook(
+    parsed_args: &ParsedGitInvocation,
+    repository: &mut Repository,
+    exit_status: std::process::ExitStatus,
+) {
+    if !exit_status.success() {
+        debug_log("Restore failed, skipping working log handling");
+        return;
+    }
+
+    let restore = parse_restore_args(&parsed_args.command_args);
+    // --staged alone only touches the index; the working tree keeps its edits
+    if !restore.worktree {
+        debug_log("Restore of the index only, no working log handling needed");
+        return;
+    }
+    // Which hunks were restored isn't known; leave it to the next checkpoint
+    if restore.patch || restore.pathspec_from_file {
+        debug_log("Restore --patch or --pathspec-from-file, keeping attributions");
+        return;
+    }
+
+    let head = match &repository.pre_command_base_commit {
+        Some(sha) => sha.clone(),
+        None => return,
+    };
+
+    let pathspecs = repo_relative_pathspecs(parsed_args, repository, &restore.pathspecs);
+    debug_log(&format!(
+        "Restore detected, removing attributions for: {:?}",
+        pathspecs
+    ));
+    remove_attributions_for_pathspecs(repository, &head, &pathspecs);
+}
+
+/// Pathspecs are relative to the directory git ran in; the working log is keyed by
+/// paths relative to the repository root. `:/` pathspecs are already root-relative.
+fn repo_relative_pathspecs(
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+    pathspecs: &[String],
+) -> Vec<String> {
+    let prefix = resolve_command_base_dir(&parsed_args.global_args)
+        .ok()
+        .and_then(|dir| dir.canonicalize().ok())
+        .and_then(|dir| {
+            dir.strip_prefix(repository.canonical_workdir())
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_default();
+
+    pathspecs
+        .iter()
+        .filter_map(|spec| match spec.strip_prefix(":/") {
+            Some(root_relative) => normalize(Path::new(root_relative)),
+            None => normalize(&prefix.join(spec)),
+        })
+        .collect()
+}
+
+/// Collapse `.` and `..` into a `/`-separated path; `None` if it leaves the repository
+fn normalize(path: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
@@ -0,0 +1,122 @@
+use crate::authorship::rebase_authorship::walk_commits_to_base;
+use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
+use crate::git::repository::Repository;
+use crate::git::rewrite_log::{RevertEvent, RewriteLogEvent};
+use crate::utils::debug_log;
+
+pub fn pre_revert_hook(repository: &mut Repository) {
+    // Capture HEAD so the post-hook can find the commits this revert created
+    repository.require_pre_command_head();
+}
+
+pub fn post_revert_hook(
+    parsed_args: &ParsedGitInvocation,
+    exit_status: std::process::ExitStatus,
+    repository: &mut Repository,
+) {
+    if is_dry_run(&parsed_args.command_args)
+        || parsed_args.has_command_flag("--no-commit")
+        || parsed_args.has_command_flag("-n")
+    {
+        debug_log("Revert did not create commits, skipping authorship rewrite");
+        return;
+    }
+
+    let Some(original_head) = repository.pre_command_base_commit.clone() else {
+        return;
+    };
+    let Some(new_head) = repository.head().ok().and_then(|h| h.target().ok()) else {
+        return;
+    };
+    if original_head == new_head {
+        debug_log("Revert resulted in no new commits");
+        return;
+    }
+
+    // A multi-commit revert that stops on a conflict has still created commits
+    // for everything before it. Those are complete, so handle them now; the rest
+    // are picked up by `git revert --continue`.
+    if !exit_status.success() {
+        debug_log("Revert stopped early, rewriting authorship for commits created so far");
+    }
+
+    let mut new_commits = match walk_commits_to_base(repository, &new_head, &original_head) {
+        Ok(commits) => commits,
+        Err(e) => {
+            debug_log(&format!("Failed to find revert commits: {}", e));
+            return;
+        }
+    };
+    new_commits.reverse();
+
+    let mut reverted_commits = Vec::new();
+    let mut revert_commits = Vec::new();
+    for new_commit in new_commits {
+        let Some(reverted_commit) = repository
+            .find_commit(new_commit.clone())
+            .and_then(|commit| commit.body())
+            .ok()
+            .and_then(|body| parse_reverted_commit(&body))
+        else {
+            continue;
+        };
+        reverted_commits.push(reverted_commit);
+        revert_commits.push(new_commit);
+    }
+
+    if revert_commits.is_empty() {
+        debug_log("No revert commits found to rewrite authorship for");
+        return;
+    }
+
+    let revert_event = RewriteLogEvent::revert(RevertEvent::new(
+        original_head,
+        new_head,
+        reverted_commits,
+        revert_commits,
+    ));
+    let commit_author = get_commit_default_author(repository, &parsed_args.command_args);
+    repository.handle_rewrite_log_event(
+        revert_event,
+        commit_author,
+        false, // don't suppress output
+        true,  // save to log
+    );
+}
+
+/// Find the reverted commit in git's default revert message
+/// ("This reverts commit <sha>.")
+fn parse_reverted_commit(body: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let sha = line
+            .trim()
+            .strip_prefix("This reverts commit ")?
+            .trim_end_matches('.')
+            .split_whitespace()
+            .next()?;
+        (sha.len() >= 40 && sha.chars().all(|c| c.is_ascii_hexdigit())).then(|| sha.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reverted_commit() {
+        let sha = "1f9a5dc45612afcbef17e9d07441d9b57c7bb5d0";
+        assert_eq!(
+            parse_reverted_commit(&format!("This reverts commit {}.\n", sha)).as_deref(),
+            Some(sha)
+        );
+        assert_eq!(
+            parse_reverted_commit(&format!(
+                "Explain why\n\nThis reverts commit {}, reversing\nchanges made to abc.\n",
+                sha
+            )),
+            None
+        );
+        assert_eq!(parse_reverted_commit("Revert \"thing\"\n"), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::authorship::attribution_tracker::LineAttribution;
+use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
 use crate::authorship::virtual_attribution::VirtualAttributions;
 use crate::authorship::working_log::CheckpointKind;
 use crate::commands::git_handlers::CommandHooksContext;
@@ -5,6 +7,7 @@ use crate::commands::hooks::commit_hooks::get_commit_default_author;
 use crate::error::GitAiError;
 use crate::git::cli_parser::ParsedGitInvocation;
 use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::git::rewrite_log::{RewriteLogEvent, StashEvent, StashOperation};
 use crate::utils::debug_log;
 
 pub fn pre_stash_hook(
@@ -32,6 +35,7 @@ pub fn pre_stash_hook(
         let _ = match crate::commands::checkpoint::run(
             repository,
             &get_commit_default_author(repository, &parsed_args.command_args),
+            &[],
             CheckpointKind::Human,
             false,
             false,
@@ -97,12 +101,37 @@ pub fn post_stash_hook(
 
         let human_author = get_commit_default_author(repository, &parsed_args.command_args);
 
-        if let Err(e) = restore_stash_attributions(repository, &stash_sha, &human_author) {
-            debug_log(&format!("Failed to restore stash attributions: {}", e));
+        let operation = if subcommand == "pop" {
+            StashOperation::Pop
+        } else {
+            StashOperation::Apply
+        };
+        match restore_stash_attributions(repository, &stash_sha, &human_author) {
+            Ok(files) => record_stash_event(repository, operation, &stash_sha, files),
+            Err(e) => debug_log(&format!("Failed to restore stash attributions: {}", e)),
         }
     }
 }
 
+/// Record the stash in the rewrite log so the attribution trail shows where
+/// working-log state went and came back from
+fn record_stash_event(
+    repo: &Repository,
+    operation: StashOperation,
+    stash_sha: &str,
+    affected_files: Vec<String>,
+) {
+    let event = RewriteLogEvent::stash(StashEvent::new(
+        operation,
+        Some(stash_sha.to_string()),
+        true,
+        affected_files,
+    ));
+    if let Err(e) = repo.storage.append_rewrite_event(event) {
+        debug_log(&format!("Failed to record stash event: {}", e));
+    }
+}
+
 /// Save the current working log as an authorship log in git notes (refs/notes/ai-stash)
 fn save_stash_authorship_log(repo: &Repository, pathspecs: &[String]) -> Result<(), GitAiError> {
     let head_sha = repo.head()?.target()?.to_string();
@@ -169,15 +198,20 @@ fn save_stash_authorship_log(repo: &Repository, pathspecs: &[String]) -> Result<
         filtered_files.len()
     ));
 
+    record_stash_event(repo, StashOperation::Create, &stash_sha, filtered_files);
+
     Ok(())
 }
 
-/// Restore attributions from a stash by reading the git note and converting to INITIAL attributions
+/// Restore attributions from a stash by reading the git note and converting to INITIAL attributions.
+/// Line numbers are remapped from the stashed content to the content after the stash was applied,
+/// since HEAD may have moved (or the stash merged with other edits) in between.
+/// Returns the files attributions were restored for.
 fn restore_stash_attributions(
     repo: &Repository,
     stash_sha: &str,
     _human_author: &str,
-) -> Result<(), GitAiError> {
+) -> Result<Vec<String>, GitAiError> {
     debug_log(&format!(
         "Restoring stash attributions from SHA: {}",
         stash_sha
@@ -190,7 +224,7 @@ fn restore_stash_attributions(
         Ok(content) => content,
         Err(_) => {
             debug_log("No authorship log found in refs/notes/ai-stash for this stash");
-            return Ok(());
+            return Ok(Vec::new());
         }
     };
 
@@ -199,7 +233,7 @@ fn restore_stash_attributions(
         Ok(log) => log,
         Err(e) => {
             debug_log(&format!("Failed to parse stash authorship log: {}", e));
-            return Ok(());
+            return Ok(Vec::new());
         }
     };
 
@@ -221,7 +255,7 @@ fn restore_stash_attributions(
                         (*start, *end)
                     }
                 };
-                line_attrs.push(crate::authorship::attribution_tracker::LineAttribution {
+                line_attrs.push(LineAttribution {
                     start_line: start,
                     end_line: end,
                     author_id: entry.hash.clone(),
@@ -229,6 +263,15 @@ fn restore_stash_attributions(
                 });
             }
         }
+        let line_attrs = match (
+            read_stashed_file(repo, stash_sha, &attestation.file_path),
+            std::fs::read_to_string(repo.workdir()?.join(&attestation.file_path)),
+        ) {
+            (Some(stashed), Ok(current)) if stashed != current => {
+                remap_line_attributions(&line_attrs, &stashed, &current)
+            }
+            _ => line_attrs,
+        };
         if !line_attrs.is_empty() {
             initial_files.insert(attestation.file_path.clone(), line_attrs);
         }
@@ -241,10 +284,15 @@ fn restore_stash_attributions(
         .into_iter()
         .collect();
 
-    // Write INITIAL attributions to working log
+    let restored_files: Vec<String> = initial_files.keys().cloned().collect();
+
+    // Write INITIAL attributions to working log, keeping any already there for other files
     if !initial_files.is_empty() || !initial_prompts.is_empty() {
         let working_log = repo.storage.working_log_for_base_commit(&head_sha);
-        working_log.write_initial_attributions(initial_files.clone(), initial_prompts.clone())?;
+        let mut existing = working_log.read_initial_attributions();
+        existing.files.extend(initial_files);
+        existing.prompts.extend(initial_prompts);
+        working_log.write_initial_attributions(existing.files, existing.prompts)?;
 
         debug_log(&format!(
             "✓ Wrote INITIAL attributions to working log for {}",
@@ -252,7 +300,73 @@ fn restore_stash_attributions(
         ));
     }
 
-    Ok(())
+    Ok(restored_files)
+}
+
+/// Content of `path` as it was stashed: from the stash's worktree tree, or from its
+/// untracked-files commit (`stash^3`, created by `stash -u`)
+fn read_stashed_file(repo: &Repository, stash_sha: &str, path: &str) -> Option<String> {
+    let stash_commit = repo.find_commit(stash_sha.to_string()).ok()?;
+    let mut trees = vec![stash_commit.tree().ok()?];
+    if stash_commit.parent_count().ok()? > 2
+        && let Ok(untracked) = stash_commit.parent(2)
+        && let Ok(tree) = untracked.tree()
+    {
+        trees.push(tree);
+    }
+    trees.iter().find_map(|tree| {
+        let entry = tree.get_path(std::path::Path::new(path)).ok()?;
+        let content = repo.find_blob(entry.id()).ok()?.content().ok()?;
+        Some(String::from_utf8_lossy(&content).to_string())
+    })
+}
+
+/// Move line attributions from `old` content to `new` content. Attributed lines that
+/// no longer exist are dropped.
+fn remap_line_attributions(
+    attrs: &[LineAttribution],
+    old: &str,
+    new: &str,
+) -> Vec<LineAttribution> {
+    // 1-based old line -> 1-based new line, for unchanged lines
+    let mut line_map = std::collections::HashMap::new();
+    let (mut old_line, mut new_line) = (0u32, 0u32);
+    for change in compute_line_changes(old, new) {
+        match change.tag() {
+            LineChangeTag::Equal => {
+                old_line += 1;
+                new_line += 1;
+                line_map.insert(old_line, new_line);
+            }
+            LineChangeTag::Delete => old_line += 1,
+            LineChangeTag::Insert => new_line += 1,
+        }
+    }
+
+    let mut remapped: Vec<LineAttribution> = Vec::new();
+    for attr in attrs {
+        for line in attr.start_line..=attr.end_line {
+            let Some(&mapped) = line_map.get(&line) else {
+                continue;
+            };
+            // Extend the previous range when this line follows it for the same author
+            if let Some(last) = remapped.last_mut()
+                && last.author_id == attr.author_id
+                && last.overrode == attr.overrode
+                && last.end_line + 1 == mapped
+            {
+                last.end_line = mapped;
+                continue;
+            }
+            remapped.push(LineAttribution {
+                start_line: mapped,
+                end_line: mapped,
+                author_id: attr.author_id.clone(),
+                overrode: attr.overrode.clone(),
+            });
+        }
+    }
+    remapped
 }
 
 /// Save a note to refs/notes/ai-stash
@@ -441,6 +555,25 @@ mod tests {
     use super::*;
     use crate::git::test_utils::TmpRepo;
 
+    #[test]
+    fn test_remap_line_attributions_follows_moved_lines() {
+        let attrs = vec![LineAttribution {
+            start_line: 2,
+            end_line: 3,
+            author_id: "prompt".to_string(),
+            overrode: None,
+        }];
+
+        let remapped = remap_line_attributions(&attrs, "a\nb\nc\n", "new\na\nb\nc\n");
+        assert_eq!(remapped.len(), 1);
+        assert_eq!((remapped[0].start_line, remapped[0].end_line), (3, 4));
+
+        // A deleted line drops out and splits nothing else
+        let remapped = remap_line_attributions(&attrs, "a\nb\nc\n", "a\nc\n");
+        assert_eq!(remapped.len(), 1);
+        assert_eq!((remapped[0].start_line, remapped[0].end_line), (2, 2));
+    }
+
     #[test]
     fn test_save_stash_note_roundtrip() {
         let repo = TmpRepo::new().unwrap();
@@ -0,0 +1,93 @@
+use crate::authorship::tag_attribution::annotate_tag;
+use crate::config;
+use crate::git::cli_parser::ParsedGitInvocation;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+
+pub fn post_tag_hook(
+    parsed_args: &ParsedGitInvocation,
+    exit_status: std::process::ExitStatus,
+    repository: &mut Repository,
+) {
+    if !exit_status.success() || !config::Config::get().annotate_tags() {
+        return;
+    }
+    let Some(tag) = created_tag_name(&parsed_args.command_args) else {
+        return;
+    };
+
+    // Lightweight tags have no tag object to annotate; annotate_tag skips them
+    match annotate_tag(repository, &tag) {
+        Ok(attribution) => debug_log(&format!(
+            "Annotated tag {}: {}% AI across {} commits",
+            tag, attribution.ai_percent, attribution.commits
+        )),
+        Err(e) => debug_log(&format!("Skipping attribution note for tag {}: {}", tag, e)),
+    }
+}
+
+/// The tag `git tag <args>` created, or `None` for listing, deleting and verifying
+fn created_tag_name(args: &[String]) -> Option<String> {
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        match arg.as_str() {
+            "-d" | "--delete" | "-l" | "--list" | "-v" | "--verify" => return None,
+            // Only valid when listing
+            _ if arg.starts_with("-n")
+                || [
+                    "--contains",
+                    "--no-contains",
+                    "--merged",
+                    "--no-merged",
+                    "--points-at",
+                    "--sort",
+                    "--format",
+                ]
+                .iter()
+                .any(|flag| arg.starts_with(flag)) =>
+            {
+                return None;
+            }
+            "-m" | "--message" | "-F" | "--file" | "-u" | "--local-user" | "--trailer" => {
+                skip_next = true
+            }
+            "--" => {}
+            _ if arg.starts_with('-') => {}
+            _ => return Some(arg.clone()),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_created_tag_name() {
+        assert_eq!(
+            created_tag_name(&args(&["-a", "v1.0", "-m", "Release"])),
+            Some("v1.0".to_string())
+        );
+        assert_eq!(
+            created_tag_name(&args(&["-m", "v2 notes", "-s", "v2.0", "HEAD~1"])),
+            Some("v2.0".to_string())
+        );
+        assert_eq!(
+            created_tag_name(&args(&["-u", "ABCD", "--force", "v3"])),
+            Some("v3".to_string())
+        );
+        assert_eq!(created_tag_name(&args(&["-d", "v1.0"])), None);
+        assert_eq!(created_tag_name(&args(&["--list", "v*"])), None);
+        assert_eq!(created_tag_name(&args(&["--contains", "HEAD"])), None);
+        assert_eq!(created_tag_name(&args(&[])), None);
+    }
+}
@@ -7,6 +7,7 @@ use crate::mdm::hook_installer::HookInstallerParams;
 use crate::mdm::skills_installer;
 use crate::mdm::spinner::{Spinner, print_diff};
 use crate::mdm::utils::{get_current_binary_path, git_shim_path};
+use crate::output::{self, Status};
 use std::collections::HashMap;
 
 /// Installation status for a tool
@@ -177,7 +178,7 @@ async fn async_run_install(
     }
 
     // === Coding Agents ===
-    println!("\n\x1b[1mCoding Agents\x1b[0m");
+    println!("\n{}", output::mode().paint("1", "Coding Agents"));
 
     let installers = get_all_installers();
 
@@ -309,7 +310,7 @@ async fn async_run_install(
     // === Git Clients ===
     let git_client_installers = get_all_git_client_installers();
     if !git_client_installers.is_empty() {
-        println!("\n\x1b[1mGit Clients\x1b[0m");
+        println!("\n{}", output::mode().paint("1", "Git Clients"));
 
         let git_client_params = GitClientInstallerParams {
             git_shim_path: git_shim_path(),
@@ -380,9 +381,18 @@ async fn async_run_install(
     if !any_checked {
         println!("No compatible IDEs or agent configurations detected. Nothing to install.");
     } else if has_changes && dry_run {
-        println!("\n\x1b[33m⚠ Dry-run mode (default). No changes were made.\x1b[0m");
+        println!(
+            "\n{}",
+            output::mode().status(
+                Status::Warning,
+                "Dry-run mode (default). No changes were made."
+            )
+        );
         println!("To apply these changes, run:");
-        println!("\x1b[1m  git-ai install-hooks --dry-run=false\x1b[0m");
+        println!(
+            "{}",
+            output::mode().paint("1", "  git-ai install-hooks --dry-run=false")
+        );
     }
 
     // Emit metrics for each agent/git_client result (only if not dry-run)
@@ -434,7 +444,7 @@ async fn async_run_uninstall(
     }
 
     // === Coding Agents ===
-    println!("\n\x1b[1mCoding Agents\x1b[0m");
+    println!("\n{}", output::mode().paint("1", "Coding Agents"));
 
     let installers = get_all_installers();
 
@@ -523,7 +533,7 @@ async fn async_run_uninstall(
     // === Git Clients ===
     let git_client_installers = get_all_git_client_installers();
     if !git_client_installers.is_empty() {
-        println!("\n\x1b[1mGit Clients\x1b[0m");
+        println!("\n{}", output::mode().paint("1", "Git Clients"));
 
         let git_client_params = GitClientInstallerParams {
             git_shim_path: git_shim_path(),
@@ -590,9 +600,18 @@ async fn async_run_uninstall(
     if !any_checked {
         println!("No git-ai hooks found to uninstall.");
     } else if has_changes && dry_run {
-        println!("\n\x1b[33m⚠ Dry-run mode (default). No changes were made.\x1b[0m");
+        println!(
+            "\n{}",
+            output::mode().status(
+                Status::Warning,
+                "Dry-run mode (default). No changes were made."
+            )
+        );
         println!("To apply these changes, run:");
-        println!("\x1b[1m  git-ai uninstall-hooks --dry-run=false\x1b[0m");
+        println!(
+            "{}",
+            output::mode().paint("1", "  git-ai uninstall-hooks --dry-run=false")
+        );
     } else if !has_changes {
         println!("All git-ai hooks have been removed.");
     }
@@ -0,0 +1,152 @@
+//! `git-ai intern-prompts`: rewrite existing authorship notes so their prompt
+//! messages live once under the prompts notes ref instead of in every note, the
+//! form post-commit writes when the `intern_prompts` feature flag is on.
+
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::prompt_interning::intern_prompt_messages;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
+use crate::git::find_repository;
+use crate::git::refs::{
+    FrozenNotes, all_note_oids, note_blob_oids_for_commits, notes_add_batch_with,
+};
+use crate::git::repository::{Repository, exec_git};
+use std::collections::BTreeSet;
+
+struct InternSummary {
+    notes: usize,
+    prompts: usize,
+}
+
+pub fn handle_intern_prompts(args: &[String]) {
+    let mut revs: Vec<String> = Vec::new();
+    let mut dry_run = false;
+    let mut frozen = FrozenNotes::Refuse;
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--force" => frozen = FrozenNotes::Overwrite,
+            "-h" | "--help" => {
+                print_intern_prompts_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_intern_prompts_help();
+                std::process::exit(1);
+            }
+            other => revs.push(other.to_string()),
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match intern_notes(&repo, &revs, dry_run, frozen) {
+        Ok(summary) if dry_run => println!(
+            "Would intern {} prompts in {} authorship notes",
+            summary.prompts, summary.notes
+        ),
+        Ok(summary) => println!(
+            "Interned {} prompts in {} authorship notes",
+            summary.prompts, summary.notes
+        ),
+        Err(e) => {
+            eprintln!("Interning prompts failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn intern_notes(
+    repo: &Repository,
+    revs: &[String],
+    dry_run: bool,
+    frozen: FrozenNotes,
+) -> Result<InternSummary, GitAiError> {
+    let note_oids = if revs.is_empty() {
+        all_note_oids(repo)?
+    } else {
+        let mut args = repo.global_args_for_exec();
+        args.push("rev-list".to_string());
+        args.extend(revs.iter().cloned());
+        let commits: Vec<String> = String::from_utf8(exec_git(&args)?.stdout)?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        note_blob_oids_for_commits(repo, &commits)?
+    };
+
+    let blob_oids: Vec<String> = note_oids
+        .values()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let blobs = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &blob_oids)?;
+
+    // Only notes that still carry messages inline need rewriting
+    let mut logs: Vec<(String, AuthorshipLog)> = note_oids
+        .iter()
+        .filter_map(|(commit, oid)| {
+            let log = AuthorshipLog::deserialize_from_string(blobs.get(oid)?).ok()?;
+            Some((commit.clone(), log))
+        })
+        .filter(|(_, log)| {
+            log.metadata
+                .prompts
+                .values()
+                .any(|prompt| !prompt.messages.is_empty())
+        })
+        .collect();
+    logs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let prompts = logs
+        .iter()
+        .flat_map(|(_, log)| log.metadata.prompts.values())
+        .filter(|prompt| !prompt.messages.is_empty())
+        .count();
+    if dry_run || logs.is_empty() {
+        return Ok(InternSummary {
+            notes: logs.len(),
+            prompts,
+        });
+    }
+
+    intern_prompt_messages(
+        repo,
+        logs.iter_mut()
+            .flat_map(|(_, log)| log.metadata.prompts.values_mut()),
+    )?;
+    let mut entries = Vec::with_capacity(logs.len());
+    for (commit, log) in &logs {
+        let content = log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        entries.push((commit.clone(), content));
+    }
+    notes_add_batch_with(repo, &entries, frozen)?;
+
+    Ok(InternSummary {
+        notes: logs.len(),
+        prompts,
+    })
+}
+
+fn print_intern_prompts_help() {
+    eprintln!("Usage: git-ai intern-prompts [<rev>...] [--dry-run] [--force]");
+    eprintln!();
+    eprintln!("Move prompt messages out of authorship notes into the prompts notes ref,");
+    eprintln!("storing each transcript once however many commits reference it.");
+    eprintln!("Without revisions, every authorship note is rewritten.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --dry-run    Report what would be interned without rewriting notes");
+    eprintln!("  --force      Also rewrite notes of commits frozen with `git-ai freeze`");
+}
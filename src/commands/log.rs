@@ -0,0 +1,175 @@
+//! `git-ai log`: `git log --oneline` with the AI share of each commit's added
+//! lines, read from its authorship note. `--min-ai <pct>` keeps only commits at
+//! or above that share, to find heavily AI-generated changes quickly.
+
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::note_blob_oids_for_commits;
+use crate::git::repository::{Repository, exec_git};
+
+/// A commit listed by `git-ai log`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub sha: String,
+    pub short_sha: String,
+    /// `git log`'s ` (HEAD -> main, tag: v1)`, empty when undecorated
+    pub decorations: String,
+    pub subject: String,
+    /// AI share of the commit's added lines, 0-100; `None` without a note
+    pub ai_percent: Option<u32>,
+}
+
+pub fn handle_log(args: &[String]) {
+    let (min_ai, log_args) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai log [--min-ai <pct>] [<git log options>] [<revision range>]");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let entries = match log_entries(&repo, &log_args) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    for entry in entries {
+        if min_ai.is_some_and(|min| entry.ai_percent.is_none_or(|pct| pct < min)) {
+            continue;
+        }
+        println!("{}", render_entry(&entry));
+    }
+}
+
+/// `--min-ai` and the arguments passed through to `git log`
+fn parse_args(args: &[String]) -> Result<(Option<u32>, Vec<String>), String> {
+    let mut min_ai = None;
+    let mut log_args = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        let value = if let Some(value) = arg.strip_prefix("--min-ai=") {
+            Some(value.to_string())
+        } else if arg == "--min-ai" {
+            i += 1;
+            Some(args.get(i).cloned().ok_or("missing value for --min-ai")?)
+        } else {
+            None
+        };
+        match value {
+            Some(value) => {
+                let pct = value
+                    .trim()
+                    .trim_end_matches('%')
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|pct| *pct <= 100)
+                    .ok_or_else(|| format!("invalid --min-ai '{}': expected 0-100", value))?;
+                min_ai = Some(pct);
+            }
+            None => log_args.push(arg.clone()),
+        }
+        i += 1;
+    }
+    Ok((min_ai, log_args))
+}
+
+/// The commits `git log <log_args>` lists, newest first, with their AI share
+pub fn log_entries(repo: &Repository, log_args: &[String]) -> Result<Vec<LogEntry>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "log",
+            "--no-color",
+            "--decorate=short",
+            "--format=%H%x09%h%x09%d%x09%s",
+        ]
+        .map(String::from),
+    );
+    args.extend(log_args.iter().cloned());
+    let output = exec_git(&args)?;
+
+    let mut entries: Vec<LogEntry> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            Some(LogEntry {
+                sha: fields.next()?.to_string(),
+                short_sha: fields.next()?.to_string(),
+                decorations: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+                ai_percent: None,
+            })
+        })
+        .collect();
+
+    // Only commits with a note have anything to compute
+    let shas: Vec<String> = entries.iter().map(|entry| entry.sha.clone()).collect();
+    let with_notes = note_blob_oids_for_commits(repo, &shas)?;
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| with_notes.contains_key(&entry.sha))
+    {
+        let stats = stats_for_commit_stats(repo, &entry.sha, &ignore_patterns)?;
+        let total = stats.ai_additions + stats.human_additions;
+        entry.ai_percent = Some(if total > 0 {
+            ((stats.ai_additions as f64 / total as f64) * 100.0).round() as u32
+        } else {
+            0
+        });
+    }
+    Ok(entries)
+}
+
+fn render_entry(entry: &LogEntry) -> String {
+    let ai = match entry.ai_percent {
+        Some(pct) => format!("{:>3}% AI", pct),
+        None => "      -".to_string(),
+    };
+    format!(
+        "{} {}{} {}",
+        entry.short_sha, ai, entry.decorations, entry.subject
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args(&["--min-ai", "50", "-n", "10", "main..topic"])).unwrap(),
+            (Some(50), args(&["-n", "10", "main..topic"]))
+        );
+        assert_eq!(
+            parse_args(&args(&["--min-ai=80%"])).unwrap(),
+            (Some(80), vec![])
+        );
+        assert_eq!(
+            parse_args(&args(&["--all"])).unwrap(),
+            (None, args(&["--all"]))
+        );
+        assert!(parse_args(&args(&["--min-ai", "120"])).is_err());
+        assert!(parse_args(&args(&["--min-ai"])).is_err());
+    }
+}
@@ -3,7 +3,26 @@ use crate::commands::flush_metrics_db::spawn_background_metrics_db_flush;
 use crate::metrics::db::MetricsDatabase;
 
 /// Handle the `git-ai login` command
-pub fn handle_login(_args: &[String]) {
+///
+/// Uses the OAuth device authorization grant (RFC 8628): the user approves a
+/// short code in a browser on any device while this process polls for the
+/// token. With `--device`, or on a machine with no browser to open (an SSH
+/// session, a CI runner, Linux without a display), only the URL and code are
+/// printed.
+pub fn handle_login(args: &[String]) {
+    let mut device = false;
+    for arg in args {
+        match arg.as_str() {
+            "--device" => device = true,
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                eprintln!("Usage: git-ai login [--device]");
+                std::process::exit(1);
+            }
+        }
+    }
+    let open_browser_here = !device && !is_headless();
+
     let store = CredentialStore::new();
 
     // Check if already logged in
@@ -35,15 +54,20 @@ pub fn handle_login(_args: &[String]) {
 
     // Display instructions
     eprintln!("To authorize this device:");
-    eprintln!("  1. Open this URL in your browser:");
-    eprintln!("     {}", display_url);
+    if open_browser_here {
+        eprintln!("  1. Open this URL in your browser:");
+        eprintln!("     {}", display_url);
+    } else {
+        eprintln!("  1. Open this URL in a browser on any device:");
+        eprintln!("     {}", auth_response.verification_uri);
+    }
     eprintln!();
     eprintln!("  2. Enter this code when prompted:");
     eprintln!("     {}", auth_response.user_code);
     eprintln!();
 
     // Try to open browser automatically
-    if open_browser(display_url).is_err() {
+    if open_browser_here && open_browser(display_url).is_err() {
         eprintln!("  (Could not open browser automatically)");
         eprintln!();
     }
@@ -84,6 +108,16 @@ pub fn handle_login(_args: &[String]) {
     }
 }
 
+/// Whether there's no local browser worth launching: an SSH session, a CI
+/// runner, or Linux without a graphical display
+fn is_headless() -> bool {
+    let is_set = |name: &str| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+    is_set("SSH_CONNECTION")
+        || is_set("SSH_TTY")
+        || is_set("CI")
+        || (cfg!(target_os = "linux") && !is_set("DISPLAY") && !is_set("WAYLAND_DISPLAY"))
+}
+
 /// Attempt to open a URL in the system's default browser
 fn open_browser(url: &str) -> Result<(), String> {
     #[cfg(target_os = "macos")]
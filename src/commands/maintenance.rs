@@ -0,0 +1,621 @@
+//! `git-ai maintenance register|unregister|run`
+//!
+//! Mirrors `git maintenance`: `register` adds the current repository to the
+//! maintenance list and installs a nightly schedule (launchd on macOS, a systemd
+//! user timer on Linux, Task Scheduler on Windows) that runs
+//! `git-ai maintenance run --schedule`. Background work that would otherwise be
+//! piggybacked on user commands runs there instead.
+
+use crate::config::{self, Config};
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::{LockFile, run_internal_git_ai_subcommand};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Observability logs older than this are deleted
+const LOG_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Working logs for commits other than HEAD are deleted after this long untouched
+const WORKING_LOG_MAX_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTask {
+    /// Upload queued telemetry and metrics
+    FlushLogs,
+    /// Delete old observability log files
+    PruneLogs,
+    /// Refresh the cached release info (and auto-update, if enabled)
+    UpdateCheck,
+    /// Delete stale working logs (per repository)
+    CompactWorkingLogs,
+    /// Write a commit-graph with changed-path filters to speed up blame (per repository)
+    CommitGraph,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 5] = [
+        MaintenanceTask::FlushLogs,
+        MaintenanceTask::PruneLogs,
+        MaintenanceTask::UpdateCheck,
+        MaintenanceTask::CompactWorkingLogs,
+        MaintenanceTask::CommitGraph,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceTask::FlushLogs => "flush-logs",
+            MaintenanceTask::PruneLogs => "prune-logs",
+            MaintenanceTask::UpdateCheck => "update-check",
+            MaintenanceTask::CompactWorkingLogs => "compact-working-logs",
+            MaintenanceTask::CommitGraph => "commit-graph",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == name)
+    }
+
+    pub fn is_per_repo(&self) -> bool {
+        matches!(
+            self,
+            MaintenanceTask::CompactWorkingLogs | MaintenanceTask::CommitGraph
+        )
+    }
+}
+
+/// Repositories registered for scheduled maintenance
+/// (~/.git-ai/internal/maintenance.json)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceRegistry {
+    #[serde(default)]
+    pub repos: Vec<String>,
+}
+
+impl MaintenanceRegistry {
+    pub fn load() -> Self {
+        registry_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), GitAiError> {
+        let path = registry_path()
+            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".into()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returns false if the repository was already registered
+    pub fn add(&mut self, repo: &str) -> bool {
+        if self.repos.iter().any(|r| r == repo) {
+            return false;
+        }
+        self.repos.push(repo.to_string());
+        true
+    }
+
+    /// Returns false if the repository wasn't registered
+    pub fn remove(&mut self, repo: &str) -> bool {
+        let before = self.repos.len();
+        self.repos.retain(|r| r != repo);
+        self.repos.len() != before
+    }
+}
+
+fn registry_path() -> Option<PathBuf> {
+    config::internal_dir_path().map(|dir| dir.join("maintenance.json"))
+}
+
+pub fn handle_maintenance(args: &[String]) {
+    let result = match args.first().map(String::as_str) {
+        Some("register") => register(),
+        Some("unregister") => unregister(),
+        Some("run") => run(&args[1..]),
+        _ => {
+            print_maintenance_help();
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn print_maintenance_help() {
+    eprintln!("Usage: git-ai maintenance <command>");
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  register              Schedule nightly maintenance for the current repository");
+    eprintln!("  unregister            Stop scheduled maintenance for the current repository");
+    eprintln!("  run                   Run maintenance tasks now");
+    eprintln!("    --task <name>         Only run this task (repeatable)");
+    eprintln!("    --schedule            Run for every registered repository");
+    eprintln!();
+    eprintln!(
+        "Tasks: {}",
+        MaintenanceTask::ALL
+            .iter()
+            .map(|t| t.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+fn current_repo_workdir() -> Result<String, GitAiError> {
+    let cwd = std::env::current_dir()?;
+    let repo = find_repository_in_path(&cwd.to_string_lossy())?;
+    let workdir = repo.workdir()?;
+    Ok(workdir
+        .canonicalize()
+        .unwrap_or(workdir)
+        .to_string_lossy()
+        .to_string())
+}
+
+fn register() -> Result<(), GitAiError> {
+    let workdir = current_repo_workdir()?;
+    let mut registry = MaintenanceRegistry::load();
+    if registry.add(&workdir) {
+        registry.save()?;
+        eprintln!("Registered {} for maintenance", workdir);
+    } else {
+        eprintln!("{} is already registered", workdir);
+    }
+
+    let exe = std::env::current_exe()?;
+    let scheduler = schedule::install(&exe)?;
+    eprintln!("Nightly maintenance scheduled with {}", scheduler);
+    Ok(())
+}
+
+fn unregister() -> Result<(), GitAiError> {
+    let workdir = current_repo_workdir()?;
+    let mut registry = MaintenanceRegistry::load();
+    if registry.remove(&workdir) {
+        registry.save()?;
+        eprintln!("Unregistered {}", workdir);
+    } else {
+        eprintln!("{} was not registered", workdir);
+    }
+
+    if registry.repos.is_empty() {
+        schedule::uninstall()?;
+        eprintln!("No repositories left; removed the maintenance schedule");
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), GitAiError> {
+    let mut tasks = Vec::new();
+    let mut scheduled = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--task" => {
+                let name = args
+                    .get(i + 1)
+                    .ok_or_else(|| GitAiError::Generic("--task requires a value".into()))?;
+                let task = MaintenanceTask::from_name(name)
+                    .ok_or_else(|| GitAiError::Generic(format!("unknown task: {}", name)))?;
+                tasks.push(task);
+                i += 2;
+            }
+            "--schedule" => {
+                scheduled = true;
+                i += 1;
+            }
+            other => {
+                return Err(GitAiError::Generic(format!("unknown argument: {}", other)));
+            }
+        }
+    }
+    if tasks.is_empty() {
+        tasks = MaintenanceTask::ALL.to_vec();
+    }
+
+    // Overlapping runs (e.g. a manual run during the nightly one) would race on the same files
+    let _lock = {
+        let lock_path = config::internal_dir_path().map(|dir| dir.join("maintenance.lock"));
+        if let Some(dir) = lock_path.as_ref().and_then(|p| p.parent()) {
+            let _ = fs::create_dir_all(dir);
+        }
+        match lock_path.and_then(|p| LockFile::try_acquire(&p)) {
+            Some(lock) => lock,
+            None => {
+                eprintln!("Maintenance is already running");
+                return Ok(());
+            }
+        }
+    };
+
+    let repos: Vec<String> = if scheduled {
+        MaintenanceRegistry::load().repos
+    } else {
+        current_repo_workdir().into_iter().collect()
+    };
+
+    let mut failures = 0;
+    for task in tasks.iter().filter(|t| !t.is_per_repo()) {
+        report(task.as_str(), run_global_task(*task), &mut failures);
+    }
+    for repo_path in &repos {
+        let repo = match find_repository_in_path(repo_path) {
+            Ok(repo) => repo,
+            Err(e) => {
+                report(repo_path, Err(e), &mut failures);
+                continue;
+            }
+        };
+        for task in tasks.iter().filter(|t| t.is_per_repo()) {
+            let label = format!("{} ({})", task.as_str(), repo_path);
+            report(&label, run_repo_task(*task, &repo), &mut failures);
+        }
+    }
+
+    if failures > 0 {
+        return Err(GitAiError::Generic(format!(
+            "{} maintenance task(s) failed",
+            failures
+        )));
+    }
+    Ok(())
+}
+
+fn report(label: &str, result: Result<String, GitAiError>, failures: &mut usize) {
+    match result {
+        Ok(summary) => eprintln!("{}: {}", label, summary),
+        Err(e) => {
+            *failures += 1;
+            eprintln!("{}: failed: {}", label, e);
+        }
+    }
+}
+
+fn run_global_task(task: MaintenanceTask) -> Result<String, GitAiError> {
+    match task {
+        MaintenanceTask::FlushLogs => {
+            for subcommand in ["flush-logs", "flush-metrics-db"] {
+                let status = run_internal_git_ai_subcommand(subcommand, &[])?;
+                if !status.success() {
+                    return Err(GitAiError::Generic(format!(
+                        "{} exited with {}",
+                        subcommand, status
+                    )));
+                }
+            }
+            Ok("done".to_string())
+        }
+        MaintenanceTask::PruneLogs => {
+            let logs_dir = config::internal_dir_path()
+                .map(|dir| dir.join("logs"))
+                .ok_or_else(|| GitAiError::Generic("Could not determine home directory".into()))?;
+            let removed = prune_old_files(&logs_dir, "log", LOG_MAX_AGE)?;
+            Ok(format!("removed {} log file(s)", removed))
+        }
+        MaintenanceTask::UpdateCheck => {
+            if Config::get().version_checks_disabled() {
+                return Ok("skipped (version checks disabled)".to_string());
+            }
+            let status = run_internal_git_ai_subcommand("upgrade", &["--background"])?;
+            if !status.success() {
+                return Err(GitAiError::Generic(format!(
+                    "upgrade exited with {}",
+                    status
+                )));
+            }
+            Ok("done".to_string())
+        }
+        MaintenanceTask::CompactWorkingLogs | MaintenanceTask::CommitGraph => {
+            unreachable!("per-repository task")
+        }
+    }
+}
+
+fn run_repo_task(task: MaintenanceTask, repo: &Repository) -> Result<String, GitAiError> {
+    match task {
+        MaintenanceTask::CompactWorkingLogs => {
+            let head = repo.head().and_then(|h| h.target()).unwrap_or_default();
+            let removed = repo
+                .storage
+                .prune_stale_working_logs(&head, WORKING_LOG_MAX_AGE)?;
+            Ok(format!("removed {} stale working log(s)", removed))
+        }
+        MaintenanceTask::CommitGraph => {
+            let mut args = repo.global_args_for_exec();
+            args.extend(
+                ["commit-graph", "write", "--reachable", "--changed-paths"]
+                    .iter()
+                    .map(|s| s.to_string()),
+            );
+            exec_git(&args)?;
+            Ok("done".to_string())
+        }
+        _ => unreachable!("global task"),
+    }
+}
+
+/// Delete files with `extension` in `dir` older than `max_age`
+fn prune_old_files(dir: &Path, extension: &str, max_age: Duration) -> Result<usize, GitAiError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(0);
+    };
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some(extension) {
+            continue;
+        }
+        let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+            continue;
+        };
+        if now.duration_since(modified).unwrap_or_default() >= max_age {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// OS scheduler integration. Every platform runs
+/// `git-ai maintenance run --schedule` once a night.
+mod schedule {
+    use crate::error::GitAiError;
+    use std::path::Path;
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub const LAUNCHD_LABEL: &str = "com.git-ai.maintenance";
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub const SYSTEMD_UNIT: &str = "git-ai-maintenance";
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const WINDOWS_TASK: &str = "git-ai maintenance";
+    /// Local time the nightly run starts
+    const HOUR: u32 = 3;
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn launchd_plist(exe: &Path) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{label}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{exe}</string>
+    <string>maintenance</string>
+    <string>run</string>
+    <string>--schedule</string>
+  </array>
+  <key>StartCalendarInterval</key>
+  <dict>
+    <key>Hour</key>
+    <integer>{hour}</integer>
+    <key>Minute</key>
+    <integer>0</integer>
+  </dict>
+  <key>StandardOutPath</key>
+  <string>/dev/null</string>
+  <key>StandardErrorPath</key>
+  <string>/dev/null</string>
+</dict>
+</plist>
+"#,
+            label = LAUNCHD_LABEL,
+            exe = exe.display(),
+            hour = HOUR,
+        )
+    }
+
+    /// `(service, timer)` unit files
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn systemd_units(exe: &Path) -> (String, String) {
+        let service = format!(
+            "[Unit]\nDescription=git-ai maintenance\n\n[Service]\nType=oneshot\nExecStart=\"{}\" maintenance run --schedule\n",
+            exe.display()
+        );
+        let timer = format!(
+            "[Unit]\nDescription=Nightly git-ai maintenance\n\n[Timer]\nOnCalendar=*-*-* {:02}:00:00\nRandomizedDelaySec=1h\nPersistent=true\n\n[Install]\nWantedBy=timers.target\n",
+            HOUR
+        );
+        (service, timer)
+    }
+
+    /// Install (or refresh) the schedule. Returns the scheduler's name.
+    pub fn install(exe: &Path) -> Result<&'static str, GitAiError> {
+        #[cfg(target_os = "macos")]
+        {
+            let path = launch_agent_path()?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, launchd_plist(exe))?;
+            let path = path.to_string_lossy().to_string();
+            // Reload so an updated binary path takes effect
+            let _ = run_command("launchctl", &["unload", &path]);
+            run_command("launchctl", &["load", "-w", &path])?;
+            Ok("launchd")
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let dir = systemd_user_dir()?;
+            std::fs::create_dir_all(&dir)?;
+            let (service, timer) = systemd_units(exe);
+            std::fs::write(dir.join(format!("{}.service", SYSTEMD_UNIT)), service)?;
+            std::fs::write(dir.join(format!("{}.timer", SYSTEMD_UNIT)), timer)?;
+            run_command("systemctl", &["--user", "daemon-reload"])?;
+            run_command(
+                "systemctl",
+                &[
+                    "--user",
+                    "enable",
+                    "--now",
+                    &format!("{}.timer", SYSTEMD_UNIT),
+                ],
+            )?;
+            Ok("systemd")
+        }
+        #[cfg(windows)]
+        {
+            let command = format!("\"{}\" maintenance run --schedule", exe.display());
+            let start = format!("{:02}:00", HOUR);
+            run_command(
+                "schtasks",
+                &[
+                    "/create",
+                    "/tn",
+                    WINDOWS_TASK,
+                    "/tr",
+                    &command,
+                    "/sc",
+                    "daily",
+                    "/st",
+                    &start,
+                    "/f",
+                ],
+            )?;
+            Ok("Task Scheduler")
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+        {
+            let _ = exe;
+            Err(GitAiError::Generic(
+                "No supported scheduler on this platform; run `git-ai maintenance run --schedule` from cron".into(),
+            ))
+        }
+    }
+
+    pub fn uninstall() -> Result<(), GitAiError> {
+        #[cfg(target_os = "macos")]
+        {
+            let path = launch_agent_path()?;
+            if path.exists() {
+                let _ = run_command("launchctl", &["unload", &path.to_string_lossy()]);
+                std::fs::remove_file(&path)?;
+            }
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let dir = systemd_user_dir()?;
+            let timer = dir.join(format!("{}.timer", SYSTEMD_UNIT));
+            if timer.exists() {
+                let _ = run_command(
+                    "systemctl",
+                    &[
+                        "--user",
+                        "disable",
+                        "--now",
+                        &format!("{}.timer", SYSTEMD_UNIT),
+                    ],
+                );
+                std::fs::remove_file(&timer)?;
+                let _ = std::fs::remove_file(dir.join(format!("{}.service", SYSTEMD_UNIT)));
+                let _ = run_command("systemctl", &["--user", "daemon-reload"]);
+            }
+        }
+        #[cfg(windows)]
+        {
+            let _ = run_command("schtasks", &["/delete", "/tn", WINDOWS_TASK, "/f"]);
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn launch_agent_path() -> Result<std::path::PathBuf, GitAiError> {
+        dirs::home_dir()
+            .map(|home| {
+                home.join("Library")
+                    .join("LaunchAgents")
+                    .join(format!("{}.plist", LAUNCHD_LABEL))
+            })
+            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".into()))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn systemd_user_dir() -> Result<std::path::PathBuf, GitAiError> {
+        dirs::config_dir()
+            .map(|dir| dir.join("systemd").join("user"))
+            .ok_or_else(|| GitAiError::Generic("Could not determine config directory".into()))
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux", windows))]
+    fn run_command(program: &str, args: &[&str]) -> Result<(), GitAiError> {
+        let output = std::process::Command::new(program).args(args).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(GitAiError::Generic(format!(
+                "{} {} failed: {}",
+                program,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_names_round_trip() {
+        for task in MaintenanceTask::ALL {
+            assert_eq!(MaintenanceTask::from_name(task.as_str()), Some(task));
+        }
+        assert_eq!(MaintenanceTask::from_name("gc"), None);
+    }
+
+    #[test]
+    fn test_registry_add_and_remove() {
+        let mut registry = MaintenanceRegistry::default();
+        assert!(registry.add("/src/a"));
+        assert!(!registry.add("/src/a"));
+        assert!(registry.add("/src/b"));
+        assert!(registry.remove("/src/a"));
+        assert!(!registry.remove("/src/a"));
+        assert_eq!(registry.repos, vec!["/src/b"]);
+    }
+
+    #[test]
+    fn test_schedule_definitions_run_scheduled_maintenance() {
+        let exe = Path::new("/usr/local/bin/git-ai");
+        let plist = schedule::launchd_plist(exe);
+        assert!(plist.contains("<string>/usr/local/bin/git-ai</string>"));
+        assert!(plist.contains("<string>--schedule</string>"));
+
+        let (service, timer) = schedule::systemd_units(exe);
+        assert!(service.contains("ExecStart=\"/usr/local/bin/git-ai\" maintenance run --schedule"));
+        assert!(timer.contains("OnCalendar=*-*-* 03:00:00"));
+    }
+
+    #[test]
+    fn test_prune_old_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("1.log");
+        let new = dir.path().join("2.log");
+        let other = dir.path().join("keep.txt");
+        for path in [&old, &new, &other] {
+            fs::write(path, "").unwrap();
+        }
+        let month_ago = filetime::FileTime::from_system_time(
+            SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60),
+        );
+        filetime::set_file_mtime(&old, month_ago).unwrap();
+        filetime::set_file_mtime(&other, month_ago).unwrap();
+
+        assert_eq!(prune_old_files(dir.path(), "log", LOG_MAX_AGE).unwrap(), 1);
+        assert!(!old.exists());
+        assert!(new.exists());
+        assert!(other.exists());
+    }
+}
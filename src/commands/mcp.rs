@@ -0,0 +1,161 @@
+//! `git-ai mcp zed`: a stdio MCP server for agents without edit hooks.
+//!
+//! Zed launches it as a context server (see `mdm::agents::ZedInstaller`). Its one
+//! tool, `checkpoint`, is what the agent calls before and after editing files;
+//! each call runs `git-ai checkpoint zed` with the call's arguments as hook input.
+
+use crate::commands::checkpoint_agent::agent_presets::{
+    AgentCheckpointFlags, AgentCheckpointPreset, ZedPreset,
+};
+use crate::utils::debug_log;
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+use std::process::Command;
+
+const DEFAULT_PROTOCOL_VERSION: &str = "2024-11-05";
+
+const CHECKPOINT_TOOL: &str = "checkpoint";
+
+const INSTRUCTIONS: &str = "git-ai records which code in this repository was written by AI. \
+Call the `checkpoint` tool with phase \"before_edit\" and the files you are about to change \
+before every edit, and with phase \"after_edit\" and the files you changed right after it.";
+
+pub fn handle_mcp(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("zed") => {}
+        _ => {
+            eprintln!("Usage: git-ai mcp zed");
+            eprintln!("  Serve the checkpoint tool to Zed's agent over stdio (MCP)");
+            std::process::exit(1);
+        }
+    }
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(response) = handle_message(&line) else {
+            continue;
+        };
+        if writeln!(stdout, "{}", response).is_err() || stdout.flush().is_err() {
+            break;
+        }
+    }
+    std::process::exit(0);
+}
+
+/// The response to one JSON-RPC message, or `None` for notifications
+fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                -32700,
+                &format!("Parse error: {}", e),
+            ));
+        }
+    };
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_PROTOCOL_VERSION),
+            "capabilities": {"tools": {}},
+            "serverInfo": {"name": "git-ai", "version": env!("CARGO_PKG_VERSION")},
+            "instructions": INSTRUCTIONS,
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({"tools": [checkpoint_tool()]}),
+        "tools/call" => call_tool(&params),
+        _ => {
+            return Some(error_response(
+                id,
+                -32601,
+                &format!("Method not found: {}", method),
+            ));
+        }
+    };
+    Some(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+fn checkpoint_tool() -> Value {
+    json!({
+        "name": CHECKPOINT_TOOL,
+        "description": "Record a git-ai checkpoint. Call with phase \"before_edit\" before editing files and \"after_edit\" right after, listing the files.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "phase": {"type": "string", "enum": ["before_edit", "after_edit"]},
+                "edited_filepaths": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Files about to be edited (before_edit) or just edited (after_edit)"
+                },
+                "model": {"type": "string", "description": "The model making the edits"},
+                "thread_id": {"type": "string", "description": "The agent thread making the edits"},
+                "repo_working_dir": {"type": "string", "description": "Repository root (default: the project root)"}
+            },
+            "required": ["phase", "edited_filepaths"]
+        }
+    })
+}
+
+fn call_tool(params: &Value) -> Value {
+    let name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
+    if name != CHECKPOINT_TOOL {
+        return tool_result(&format!("Unknown tool: {}", name), true);
+    }
+    let mut hook_input = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    // Validate up front so the agent sees a bad call, rather than a silent no-op
+    if let Err(e) = ZedPreset.run(AgentCheckpointFlags {
+        hook_input: Some(hook_input.to_string()),
+    }) {
+        return tool_result(&e.to_string(), true);
+    }
+
+    // Zed starts context servers in the project root
+    if hook_input.get("repo_working_dir").is_none()
+        && let (Some(obj), Ok(cwd)) = (hook_input.as_object_mut(), std::env::current_dir())
+    {
+        obj.insert(
+            "repo_working_dir".to_string(),
+            Value::String(cwd.to_string_lossy().to_string()),
+        );
+    }
+
+    let output = std::env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(["checkpoint", "zed", "--hook-input", &hook_input.to_string()])
+            .output()
+    });
+    match output {
+        Ok(output) if output.status.success() => tool_result("Checkpoint recorded", false),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            debug_log(&format!("Zed checkpoint failed: {}", stderr));
+            tool_result(&format!("Checkpoint failed: {}", stderr.trim()), true)
+        }
+        Err(e) => tool_result(&format!("Failed to run git-ai checkpoint: {}", e), true),
+    }
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({"content": [{"type": "text", "text": text}], "isError": is_error})
+}
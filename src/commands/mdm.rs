@@ -0,0 +1,278 @@
+//! `git-ai mdm jetbrains`: pin, roll back or update the JetBrains plugin.
+//!
+//! The pin (`--pin <version>`) and channel (`--channel stable|beta`) are recorded in
+//! the global config (`jetbrains_plugin.*`), or with `--repo` in the repository's
+//! git config (`git-ai.jetbrainsPluginVersion`, `git-ai.jetbrainsPluginChannel`),
+//! which overrides the global values key by key. Every detected IDE is then brought
+//! to the pinned version, or to the newest build on the channel.
+
+use crate::config::{self, Config, JetBrainsPluginSettings, PluginChannel};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git};
+use crate::mdm::jetbrains::{
+    DetectedIde, MIN_INTELLIJ_BUILD, PLUGIN_ID, download_plugin_from_marketplace,
+    download_plugin_version, find_jetbrains_installations, installed_plugin_version,
+    is_valid_plugin_version, latest_plugin_version, replace_installed_plugin,
+};
+
+const REPO_VERSION_KEY: &str = "git-ai.jetbrainsPluginVersion";
+const REPO_CHANNEL_KEY: &str = "git-ai.jetbrainsPluginChannel";
+
+const USAGE: &str = "Usage: git-ai mdm jetbrains [--pin <version> | --unpin] [--channel stable|beta] [--repo] [--check] [--dry-run]";
+
+pub fn handle_mdm(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("jetbrains") => handle_jetbrains(&args[1..]),
+        Some(other) => {
+            eprintln!("Unknown mdm target: {}", other);
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[derive(Default)]
+struct JetBrainsArgs {
+    /// `Some(None)` is `--unpin`
+    pin: Option<Option<String>>,
+    channel: Option<PluginChannel>,
+    repo: bool,
+    check: bool,
+    dry_run: bool,
+}
+
+fn parse_jetbrains_args(args: &[String]) -> Result<JetBrainsArgs, String> {
+    let mut parsed = JetBrainsArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--pin" => {
+                let version = args.get(i + 1).ok_or("--pin requires a version")?;
+                if !is_valid_plugin_version(version) {
+                    return Err(format!("Invalid plugin version '{}'", version));
+                }
+                parsed.pin = Some(Some(version.clone()));
+                i += 2;
+            }
+            "--unpin" => {
+                parsed.pin = Some(None);
+                i += 1;
+            }
+            "--channel" => {
+                let channel = args.get(i + 1).ok_or("--channel requires stable or beta")?;
+                parsed.channel = Some(PluginChannel::parse(channel).ok_or_else(|| {
+                    format!("Invalid channel '{}'. Expected 'stable' or 'beta'", channel)
+                })?);
+                i += 2;
+            }
+            "--repo" => {
+                parsed.repo = true;
+                i += 1;
+            }
+            "--check" => {
+                parsed.check = true;
+                i += 1;
+            }
+            "--dry-run" => {
+                parsed.dry_run = true;
+                i += 1;
+            }
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn handle_jetbrains(args: &[String]) {
+    let args = match parse_jetbrains_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = find_repository(&Vec::<String>::new()).ok();
+    if args.repo && repo.is_none() {
+        eprintln!("Error: --repo must be run inside a git repository");
+        std::process::exit(1);
+    }
+
+    let mut global = Config::get().jetbrains_plugin().clone();
+    let recording = args.pin.is_some() || args.channel.is_some();
+    if recording && !args.check {
+        let recorded = match repo.as_ref().filter(|_| args.repo) {
+            Some(repo) => record_in_repo(repo, &args),
+            None => record_globally(&args).map(|settings| global = settings),
+        };
+        if let Err(e) = recorded {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let mut settings = match repo.as_ref() {
+        Some(repo) => with_repo_overrides(global, repo),
+        None => global,
+    };
+    if args.check {
+        // Preview the given pin or channel without recording it
+        if let Some(pin) = &args.pin {
+            settings.version = pin.clone();
+        }
+        if let Some(channel) = args.channel {
+            settings.channel = channel;
+        }
+    }
+    match &settings.version {
+        Some(version) => println!("JetBrains plugin pinned to {}", version),
+        None => println!(
+            "JetBrains plugin follows the {} channel",
+            settings.channel.as_str()
+        ),
+    }
+
+    let installations = find_jetbrains_installations();
+    if installations.is_empty() {
+        println!("No JetBrains IDEs detected");
+        return;
+    }
+    for detected in &installations {
+        println!(
+            "{}",
+            sync_ide(detected, &settings, args.check || args.dry_run)
+        );
+    }
+}
+
+fn record_globally(args: &JetBrainsArgs) -> Result<JetBrainsPluginSettings, String> {
+    let mut file_config = config::load_file_config_public()?;
+    let plugin = file_config
+        .jetbrains_plugin
+        .get_or_insert_with(Default::default);
+    if let Some(pin) = &args.pin {
+        plugin.version = pin.clone();
+    }
+    if let Some(channel) = args.channel {
+        plugin.channel = Some(channel.as_str().to_string());
+    }
+    let settings = JetBrainsPluginSettings::from_file_config(Some(plugin));
+    config::save_file_config(&file_config)?;
+    Ok(settings)
+}
+
+fn record_in_repo(repo: &Repository, args: &JetBrainsArgs) -> Result<(), String> {
+    let git_config = |config_args: &[&str]| -> Result<(), GitAiError> {
+        let mut git_args = repo.global_args_for_exec();
+        git_args.push("config".to_string());
+        git_args.push("--local".to_string());
+        git_args.extend(config_args.iter().map(|arg| arg.to_string()));
+        exec_git(&git_args).map(|_| ())
+    };
+    match &args.pin {
+        Some(Some(version)) => git_config(&[REPO_VERSION_KEY, version]),
+        // Unsetting a key that isn't there fails; the pin is gone either way
+        Some(None) => git_config(&["--unset", REPO_VERSION_KEY]).or(Ok(())),
+        None => Ok(()),
+    }
+    .and_then(|_| match args.channel {
+        Some(channel) => git_config(&[REPO_CHANNEL_KEY, channel.as_str()]),
+        None => Ok(()),
+    })
+    .map_err(|e| format!("Failed to update repository config: {}", e))
+}
+
+fn with_repo_overrides(
+    mut settings: JetBrainsPluginSettings,
+    repo: &Repository,
+) -> JetBrainsPluginSettings {
+    if let Ok(Some(version)) = repo.config_get_str(REPO_VERSION_KEY)
+        && is_valid_plugin_version(version.trim())
+    {
+        settings.version = Some(version.trim().to_string());
+    }
+    if let Some(channel) = repo
+        .config_get_str(REPO_CHANNEL_KEY)
+        .ok()
+        .flatten()
+        .and_then(|channel| PluginChannel::parse(&channel))
+    {
+        settings.channel = channel;
+    }
+    settings
+}
+
+/// Bring one IDE to the target plugin version, or with `report_only` just say
+/// what would change
+fn sync_ide(
+    detected: &DetectedIde,
+    settings: &JetBrainsPluginSettings,
+    report_only: bool,
+) -> String {
+    let ide_name = detected.ide.name;
+    let Some(build_number) = detected.build_number.as_deref() else {
+        return format!("{}: Skipped (unknown IDE build)", ide_name);
+    };
+    if !detected.is_compatible() {
+        return format!(
+            "{}: Skipped (build {} is older than minimum required build {})",
+            ide_name, build_number, MIN_INTELLIJ_BUILD
+        );
+    }
+
+    let target = match &settings.version {
+        Some(version) => version.clone(),
+        None => match latest_plugin_version(
+            PLUGIN_ID,
+            detected.ide.product_code,
+            build_number,
+            settings.channel,
+        ) {
+            Ok(Some(version)) => version,
+            Ok(None) => {
+                return format!(
+                    "{}: No {} build of the plugin for build {}",
+                    ide_name,
+                    settings.channel.as_str(),
+                    build_number
+                );
+            }
+            Err(e) => return format!("{}: Update check failed: {}", ide_name, e),
+        },
+    };
+
+    let installed = installed_plugin_version(detected);
+    let installed_label = installed.as_deref().unwrap_or("not installed");
+    if installed.as_deref() == Some(target.as_str()) {
+        return format!("{}: Plugin {} is up to date", ide_name, target);
+    }
+    if report_only {
+        return format!(
+            "{}: Would install plugin {} (installed: {})",
+            ide_name, target, installed_label
+        );
+    }
+
+    let download = match &settings.version {
+        Some(version) => download_plugin_version(PLUGIN_ID, version, settings.channel),
+        None => download_plugin_from_marketplace(
+            PLUGIN_ID,
+            detected.ide.product_code,
+            build_number,
+            settings.channel,
+        ),
+    };
+    match download.and_then(|zip_data| replace_installed_plugin(&zip_data, detected)) {
+        Ok(()) => format!(
+            "{}: Installed plugin {} (was: {}); restart the IDE to load it",
+            ide_name, target, installed_label
+        ),
+        Err(e) => format!("{}: Failed to install plugin {}: {}", ide_name, target, e),
+    }
+}
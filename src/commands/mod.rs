@@ -1,30 +1,63 @@
+pub mod apply;
+pub mod archive;
 pub mod blame;
+pub mod cache;
 pub mod checkpoint;
 pub mod checkpoint_agent;
 pub mod ci_handlers;
 pub mod config;
+pub mod context;
 pub mod continue_session;
+pub mod correlate;
+pub mod db;
 pub mod diff;
+pub mod doctor;
 pub mod exchange_nonce;
+pub mod explain;
+pub mod export;
 pub mod flush_cas;
 pub mod flush_logs;
 pub mod flush_metrics_db;
+pub mod flush_webhooks;
+pub mod freeze;
+pub mod gc;
 pub mod git_ai_handlers;
 pub mod git_handlers;
 pub mod git_hook_handlers;
+pub mod git_path;
 pub mod hooks;
 pub mod install_hooks;
+pub mod intern_prompts;
+pub mod log;
 pub mod login;
 pub mod logout;
+pub mod maintenance;
+pub mod mcp;
+pub mod mdm;
+pub mod ownership;
 pub mod personal_dashboard;
+pub mod pr;
 pub mod prompt_picker;
 pub mod prompts_db;
+pub mod prompts_show;
+pub mod report;
+pub mod run;
+pub mod schema;
 pub mod search;
+pub mod sessions;
+pub mod setup_container;
 pub mod share;
 pub mod share_tui;
 pub mod show;
 pub mod show_prompt;
+pub mod split;
+pub mod squash;
 pub mod squash_authorship;
 pub mod status;
+pub mod suggest_cherry_picks;
+pub mod sync;
 pub mod sync_prompts;
+pub mod uninstall;
 pub mod upgrade;
+pub mod verify_push;
+pub mod watch;
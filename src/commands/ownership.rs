@@ -0,0 +1,535 @@
+//! `git-ai ownership --file <path>`: how much of a file at HEAD is AI-written, and
+//! who touched it last, for review bots that ask once per changed file.
+//!
+//! Results are cached under `.git/ai/cache/ownership`, keyed by HEAD, the notes
+//! ref and the path. Moving HEAD or rewriting notes changes the key, so an entry
+//! is never stale and repeat calls skip blame entirely.
+//!
+//! `git-ai blame <directory>` reuses the same per-file results to summarize every
+//! file under a directory, with totals rolled up into each subdirectory.
+
+use crate::authorship::attribution_cache::{AttributionCache, notes_ref_oid};
+use crate::commands::blame::{BlameHunk, GitAiBlameOptions, overlay_ai_authorship};
+use crate::config::{Config, TrustTier};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use chrono::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Oldest entries are evicted once the ownership cache grows past this
+const MAX_CACHE_ENTRIES: usize = 5_000;
+
+/// Output of `git-ai ownership --json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct FileOwnership {
+    /// Path relative to the repository root
+    file: String,
+    /// The HEAD commit the file was read from
+    commit: String,
+    total_lines: u32,
+    ai_lines: u32,
+    human_lines: u32,
+    /// `ai_lines / total_lines`, 0 for an empty file
+    ai_fraction: f64,
+    /// `human_lines / total_lines`, 0 for an empty file
+    human_fraction: f64,
+    /// The agent behind the most recently committed AI line
+    last_ai_contributor: Option<AiContributor>,
+    /// The author of the most recently committed human line
+    last_human_editor: Option<HumanEditor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct AiContributor {
+    tool: String,
+    model: String,
+    /// Trust tier of the agent under the current `trust.*` config, so review bots
+    /// can route untrusted output to stricter review
+    #[serde(default)]
+    trust: TrustTier,
+    prompt_id: String,
+    commit: String,
+    /// Author time of `commit`, in Unix seconds
+    timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct HumanEditor {
+    name: String,
+    email: String,
+    commit: String,
+    /// Author time of `commit`, in Unix seconds
+    timestamp: i64,
+}
+
+/// Output of `git-ai blame <directory> --json`
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub(crate) struct DirectoryOwnership {
+    /// Path relative to the repository root, `.` for the root
+    directory: String,
+    /// The HEAD commit the files were read from
+    commit: String,
+    /// Every file under the directory, sorted by path
+    files: Vec<OwnershipSummary>,
+    /// The directory and each subdirectory, with totals over all files below
+    /// them, sorted by path
+    directories: Vec<OwnershipSummary>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub(crate) struct OwnershipSummary {
+    path: String,
+    total_lines: u32,
+    ai_lines: u32,
+    human_lines: u32,
+    /// `ai_lines / total_lines`, 0 when there are no lines
+    ai_fraction: f64,
+    /// `human_lines / total_lines`, 0 when there are no lines
+    human_fraction: f64,
+    /// Author time of the most recently committed AI line, in Unix seconds
+    last_ai_timestamp: Option<i64>,
+}
+
+pub fn handle_ownership(args: &[String]) {
+    let mut file = None;
+    let mut json = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--file" if i + 1 < args.len() => {
+                file = Some(args[i + 1].clone());
+                i += 2;
+            }
+            "--json" => {
+                json = true;
+                i += 1;
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                eprintln!("Usage: git-ai ownership --file <path> [--json]");
+                std::process::exit(1);
+            }
+        }
+    }
+    let Some(file) = file else {
+        eprintln!("Usage: git-ai ownership --file <path> [--json]");
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let ownership = match file_ownership(&repo, &file) {
+        Ok(ownership) => ownership,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&ownership) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize ownership: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", ownership.render());
+    }
+}
+
+/// Ownership of `file` at HEAD, from the cache when possible. The trust tier is
+/// filled in afterwards since it depends on config rather than history.
+fn file_ownership(repo: &Repository, file: &str) -> Result<FileOwnership, GitAiError> {
+    let mut ownership = cached_file_ownership(repo, &repo_relative_path(repo, file)?)?;
+    if let Some(ai) = ownership.last_ai_contributor.as_mut() {
+        ai.trust = Config::get().trust().tier_for(&ai.tool, &ai.model);
+    }
+    Ok(ownership)
+}
+
+/// Ownership of the repository-relative `file` at HEAD
+fn cached_file_ownership(repo: &Repository, file: &str) -> Result<FileOwnership, GitAiError> {
+    let head = repo.head()?.target()?;
+    let cache = AttributionCache::for_ownership(repo);
+    let key = cache_key(&head, &notes_ref_oid(repo)?, file);
+
+    if let Some(ownership) = cache
+        .get(&key)
+        .and_then(|content| serde_json::from_str(&content).ok())
+    {
+        return Ok(ownership);
+    }
+
+    let ownership = compute_ownership(repo, file, &head)?;
+    let stored = serde_json::to_string(&ownership)
+        .map_err(GitAiError::from)
+        .and_then(|content| cache.put(&key, &content));
+    if let Err(e) = stored {
+        debug_log(&format!("failed to cache ownership of {}: {}", file, e));
+    }
+    let _ = cache.prune(MAX_CACHE_ENTRIES);
+    Ok(ownership)
+}
+
+fn compute_ownership(
+    repo: &Repository,
+    file: &str,
+    head: &str,
+) -> Result<FileOwnership, GitAiError> {
+    let tree = repo.find_commit(head.to_string())?.tree()?;
+    let entry = tree
+        .get_path(std::path::Path::new(file))
+        .map_err(|_| GitAiError::Generic(format!("File '{}' not found at HEAD", file)))?;
+    let content = repo.find_blob(entry.id())?.content().unwrap_or_default();
+    let total_lines = String::from_utf8_lossy(&content).lines().count() as u32;
+
+    let mut ownership = FileOwnership {
+        file: file.to_string(),
+        commit: head.to_string(),
+        total_lines,
+        ai_lines: 0,
+        human_lines: 0,
+        ai_fraction: 0.0,
+        human_fraction: 0.0,
+        last_ai_contributor: None,
+        last_human_editor: None,
+    };
+    if total_lines == 0 {
+        return Ok(ownership);
+    }
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(head.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let hunks = repo.blame_hunks(file, 1, total_lines, &options)?;
+    let (line_authors, prompt_records, _, _) =
+        overlay_ai_authorship(repo, &hunks, file, None, &options)?;
+
+    let mut last_ai: Option<&BlameHunk> = None;
+    let mut last_human: Option<&BlameHunk> = None;
+    for hunk in &hunks {
+        for line in hunk.range.0..=hunk.range.1 {
+            let prompt = line_authors
+                .get(&line)
+                .and_then(|author| prompt_records.get_key_value(author));
+            match prompt {
+                Some((prompt_id, record)) => {
+                    ownership.ai_lines += 1;
+                    if last_ai.is_none_or(|last| hunk.author_time > last.author_time) {
+                        last_ai = Some(hunk);
+                        ownership.last_ai_contributor = Some(AiContributor {
+                            tool: record.agent_id.tool.clone(),
+                            model: record.agent_id.model.clone(),
+                            trust: TrustTier::default(),
+                            prompt_id: prompt_id.clone(),
+                            commit: hunk.commit_sha.clone(),
+                            timestamp: hunk.author_time,
+                        });
+                    }
+                }
+                None => {
+                    ownership.human_lines += 1;
+                    if last_human.is_none_or(|last| hunk.author_time > last.author_time) {
+                        last_human = Some(hunk);
+                    }
+                }
+            }
+        }
+    }
+    ownership.last_human_editor = last_human.map(|hunk| HumanEditor {
+        name: hunk.original_author.clone(),
+        email: hunk.author_email.clone(),
+        commit: hunk.commit_sha.clone(),
+        timestamp: hunk.author_time,
+    });
+    ownership.ai_fraction = ownership.ai_lines as f64 / total_lines as f64;
+    ownership.human_fraction = ownership.human_lines as f64 / total_lines as f64;
+    Ok(ownership)
+}
+
+/// Summarize ownership of every file under `dir` at HEAD, for `git-ai blame`
+/// given a directory
+pub fn blame_directory(repo: &Repository, dir: &str, json: bool) -> Result<(), GitAiError> {
+    let ownership = directory_ownership(repo, dir)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&ownership)?);
+    } else {
+        print!("{}", ownership.render());
+    }
+    Ok(())
+}
+
+fn directory_ownership(repo: &Repository, dir: &str) -> Result<DirectoryOwnership, GitAiError> {
+    let dir = repo_relative_path(repo, dir)?;
+    let head = repo.head()?.target()?;
+
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "-c".to_string(),
+        "core.quotepath=false".to_string(),
+        "ls-tree".to_string(),
+        "-r".to_string(),
+        "--name-only".to_string(),
+        head.clone(),
+    ]);
+    if !dir.is_empty() {
+        args.extend(["--".to_string(), format!("{}/", dir)]);
+    }
+    let output = exec_git(&args)?;
+    let paths: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    if paths.is_empty() {
+        return Err(GitAiError::Generic(format!(
+            "No files under '{}' at HEAD",
+            display_dir(&dir)
+        )));
+    }
+
+    let mut files = Vec::with_capacity(paths.len());
+    let mut directories: BTreeMap<String, OwnershipSummary> = BTreeMap::new();
+    for path in paths {
+        let ownership = cached_file_ownership(repo, &path)?;
+        let summary = OwnershipSummary::from(&ownership);
+
+        // Every directory between the file and the one asked about, inclusive
+        let mut parent = path.as_str();
+        while let Some((up, _)) = parent.rsplit_once('/') {
+            if up.len() < dir.len() {
+                break;
+            }
+            directories
+                .entry(up.to_string())
+                .or_insert_with(|| OwnershipSummary::empty(up))
+                .add(&summary);
+            parent = up;
+        }
+        if dir.is_empty() {
+            directories
+                .entry(String::new())
+                .or_insert_with(|| OwnershipSummary::empty(""))
+                .add(&summary);
+        }
+        files.push(summary);
+    }
+
+    Ok(DirectoryOwnership {
+        directory: display_dir(&dir).to_string(),
+        commit: head,
+        files,
+        directories: directories
+            .into_values()
+            .map(|mut summary| {
+                summary.path = display_dir(&summary.path).to_string();
+                summary
+            })
+            .collect(),
+    })
+}
+
+fn display_dir(dir: &str) -> &str {
+    if dir.is_empty() { "." } else { dir }
+}
+
+impl OwnershipSummary {
+    fn empty(path: &str) -> Self {
+        OwnershipSummary {
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, other: &OwnershipSummary) {
+        self.total_lines += other.total_lines;
+        self.ai_lines += other.ai_lines;
+        self.human_lines += other.human_lines;
+        self.last_ai_timestamp = self.last_ai_timestamp.max(other.last_ai_timestamp);
+        if self.total_lines > 0 {
+            self.ai_fraction = self.ai_lines as f64 / self.total_lines as f64;
+            self.human_fraction = self.human_lines as f64 / self.total_lines as f64;
+        }
+    }
+
+    fn render_row(&self, path: &str) -> String {
+        let last_ai = self
+            .last_ai_timestamp
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        format!(
+            "{:>6.1}%  {:>6.1}%  {:>6}  {:<10}  {}\n",
+            self.ai_fraction * 100.0,
+            self.human_fraction * 100.0,
+            self.total_lines,
+            last_ai,
+            path
+        )
+    }
+}
+
+impl From<&FileOwnership> for OwnershipSummary {
+    fn from(ownership: &FileOwnership) -> Self {
+        OwnershipSummary {
+            path: ownership.file.clone(),
+            total_lines: ownership.total_lines,
+            ai_lines: ownership.ai_lines,
+            human_lines: ownership.human_lines,
+            ai_fraction: ownership.ai_fraction,
+            human_fraction: ownership.human_fraction,
+            last_ai_timestamp: ownership
+                .last_ai_contributor
+                .as_ref()
+                .map(|ai| ai.timestamp),
+        }
+    }
+}
+
+impl DirectoryOwnership {
+    fn render(&self) -> String {
+        let header = format!(
+            "{:>7}  {:>7}  {:>6}  {:<10}  {}\n",
+            "AI", "Human", "Lines", "Last AI", "Path"
+        );
+        let mut out = format!(
+            "{} at {}\n\n",
+            self.directory,
+            &self.commit[..self.commit.len().min(7)]
+        );
+        out.push_str(&header);
+        for file in &self.files {
+            out.push_str(&file.render_row(&file.path));
+        }
+        out.push_str("\nDirectories (recursive):\n");
+        out.push_str(&header);
+        for dir in &self.directories {
+            let path = if dir.path == "." {
+                "./".to_string()
+            } else {
+                format!("{}/", dir.path)
+            };
+            out.push_str(&dir.render_row(&path));
+        }
+        out
+    }
+}
+
+/// Resolve `file` against the current directory to a repository-relative path,
+/// the way git resolves pathspecs. The file only has to exist at HEAD.
+fn repo_relative_path(repo: &Repository, file: &str) -> Result<String, GitAiError> {
+    let root = repo.workdir()?.canonicalize()?;
+    if std::path::Path::new(file).is_absolute() {
+        let path = std::path::Path::new(file).canonicalize()?;
+        return match path.strip_prefix(&root) {
+            Ok(relative) => Ok(crate::utils::normalize_to_posix(
+                &relative.to_string_lossy(),
+            )),
+            Err(_) => Err(GitAiError::Generic(format!(
+                "'{}' is not inside the repository",
+                file
+            ))),
+        };
+    }
+    let cwd = std::env::current_dir()?.canonicalize()?;
+    let prefix = cwd.strip_prefix(&root).unwrap_or(std::path::Path::new(""));
+
+    let mut components: Vec<String> = Vec::new();
+    for component in prefix.join(file).components() {
+        match component {
+            std::path::Component::Normal(part) => {
+                components.push(part.to_string_lossy().to_string())
+            }
+            std::path::Component::ParentDir => {
+                if components.pop().is_none() {
+                    return Err(GitAiError::Generic(format!(
+                        "'{}' is not inside the repository",
+                        file
+                    )));
+                }
+            }
+            std::path::Component::CurDir => {}
+            _ => {
+                return Err(GitAiError::Generic(format!(
+                    "'{}' must be a path relative to the repository",
+                    file
+                )));
+            }
+        }
+    }
+    Ok(components.join("/"))
+}
+
+fn cache_key(head: &str, notes_oid: &str, file: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\0{}\0{}", head, notes_oid, file).as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+impl FileOwnership {
+    fn render(&self) -> String {
+        let percent = |fraction: f64| format!("{:.1}%", fraction * 100.0);
+        let short = |sha: &str| sha[..sha.len().min(7)].to_string();
+        let mut out = format!("{} at {}\n", self.file, short(&self.commit));
+        out.push_str(&format!(
+            "  AI:    {} ({} of {} lines)\n",
+            percent(self.ai_fraction),
+            self.ai_lines,
+            self.total_lines
+        ));
+        out.push_str(&format!(
+            "  Human: {} ({} of {} lines)\n",
+            percent(self.human_fraction),
+            self.human_lines,
+            self.total_lines
+        ));
+        if let Some(ai) = &self.last_ai_contributor {
+            out.push_str(&format!(
+                "  Last AI contributor: {} ({}, {}) in {}\n",
+                ai.tool,
+                ai.model,
+                ai.trust.as_str(),
+                short(&ai.commit)
+            ));
+        }
+        if let Some(human) = &self.last_human_editor {
+            out.push_str(&format!(
+                "  Last human editor: {} <{}> in {}\n",
+                human.name,
+                human.email,
+                short(&human.commit)
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_changes_with_head_notes_and_path() {
+        let key = cache_key("aaa", "bbb", "src/lib.rs");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache_key("aaa", "bbb", "src/lib.rs"));
+        assert_ne!(key, cache_key("aab", "bbb", "src/lib.rs"));
+        assert_ne!(key, cache_key("aaa", "", "src/lib.rs"));
+        assert_ne!(key, cache_key("aaa", "bbb", "src/main.rs"));
+    }
+}
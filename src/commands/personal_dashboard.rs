@@ -1,11 +1,39 @@
 use crate::config;
+use crate::reporting::buckets::{ReportTimezone, resolve_report_timezone};
 
 /// Handle the `git-ai personal-dashboard` command
-pub fn handle_personal_dashboard(_args: &[String]) {
+pub fn handle_personal_dashboard(args: &[String]) {
+    let mut tz_arg: Option<&str> = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--tz" if i + 1 < args.len() => {
+                tz_arg = Some(&args[i + 1]);
+                i += 2;
+            }
+            "--tz" => {
+                eprintln!("--tz requires a value (e.g. America/New_York, +02:00, local)");
+                std::process::exit(1);
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let tz = match resolve_report_timezone(tz_arg) {
+        Ok(tz) => tz,
+        Err(e) => {
+            eprintln!("Invalid time zone: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     let config = config::Config::get();
     let api_base_url = config.api_base_url();
 
-    let dashboard_url = format!("{}/me", api_base_url);
+    let dashboard_url = dashboard_url(api_base_url, &tz);
 
     eprintln!("Opening dashboard: {}", dashboard_url);
 
@@ -16,6 +44,21 @@ pub fn handle_personal_dashboard(_args: &[String]) {
     }
 }
 
+/// Dashboard URL. Daily/weekly rollups are computed server-side, so the report
+/// time zone is passed along for the buckets to align with local days.
+fn dashboard_url(api_base_url: &str, tz: &ReportTimezone) -> String {
+    let tz_name = match tz {
+        // The server has no idea what "local" means; send the resolved offset
+        ReportTimezone::Local => chrono::Local::now().offset().to_string(),
+        other => other.to_string(),
+    };
+    if tz_name == "UTC" {
+        return format!("{}/me", api_base_url);
+    }
+    let encoded: String = url::form_urlencoded::byte_serialize(tz_name.as_bytes()).collect();
+    format!("{}/me?tz={}", api_base_url, encoded)
+}
+
 /// Attempt to open a URL in the system's default browser
 fn open_browser(url: &str) -> Result<(), String> {
     #[cfg(target_os = "macos")]
@@ -44,3 +87,26 @@ fn open_browser(url: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_url_includes_timezone() {
+        assert_eq!(
+            dashboard_url("https://example.com", &ReportTimezone::Utc),
+            "https://example.com/me"
+        );
+        let tz: ReportTimezone = "America/New_York".parse().unwrap();
+        assert_eq!(
+            dashboard_url("https://example.com", &tz),
+            "https://example.com/me?tz=America%2FNew_York"
+        );
+        let tz: ReportTimezone = "+05:30".parse().unwrap();
+        assert_eq!(
+            dashboard_url("https://example.com", &tz),
+            "https://example.com/me?tz=%2B05%3A30"
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! `git-ai pr view <number>`: a pull request's diff in the terminal, annotated
+//! the way the web view is. The PR and its diff come from `gh` when it is
+//! installed, and from the GitHub REST API otherwise. The PR head and the
+//! authorship notes are fetched first, so each added line shows who wrote it and
+//! each hunk ends with a summary of the prompts behind its AI lines.
+//!
+//! Notes on a fork are not trusted like the remote's own: they go to
+//! `refs/notes/ai-pr/<number>` and are only read while rendering, never merged
+//! into `refs/notes/ai`.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::prompt_utils::enrich_prompt_messages;
+use crate::authorship::transcript::Message;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::diff::{
+    Attribution, DiffLineKey, LineSide, annotate_diff_text, lines_to_ranges,
+};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::authorship_notes_ref;
+use crate::git::repository::{Repository, exec_git};
+use crate::git::sync_authorship::{fetch_authorship_notes, fetch_authorship_notes_into};
+use crate::output;
+use crate::utils::debug_log;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
+use std::process::Command;
+
+/// Longest excerpt of a prompt shown in a hunk summary
+const PROMPT_EXCERPT_CHARS: usize = 60;
+
+struct PullRequest {
+    number: u64,
+    title: String,
+    url: String,
+    base_sha: String,
+    head_sha: String,
+    /// Clone URL of the fork the PR comes from, when it isn't the base repository
+    fork_url: Option<String>,
+    diff: String,
+}
+
+/// Added lines of one hunk, on the new side
+struct PrHunk {
+    file: String,
+    new_start: u32,
+    added: Vec<u32>,
+}
+
+pub fn handle_pr(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("view") => handle_pr_view(&args[1..]),
+        Some("-h") | Some("--help") | None => {
+            print_pr_help();
+            std::process::exit(if args.is_empty() { 1 } else { 0 });
+        }
+        Some(other) => {
+            eprintln!("Unknown pr subcommand: {}", other);
+            print_pr_help();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_pr_view(args: &[String]) {
+    let mut number: Option<u64> = None;
+    let mut remote: Option<String> = None;
+    let mut repo_slug: Option<String> = None;
+    let mut fetch = true;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--remote" | "--repo" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a value", args[i]);
+                    std::process::exit(1);
+                };
+                if args[i] == "--remote" {
+                    remote = Some(value.clone());
+                } else {
+                    repo_slug = Some(value.clone());
+                }
+                i += 1;
+            }
+            "--no-fetch" => fetch = false,
+            "-h" | "--help" => {
+                print_pr_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_pr_help();
+                std::process::exit(1);
+            }
+            other => match other.trim_start_matches('#').parse::<u64>() {
+                Ok(n) => number = Some(n),
+                Err(_) => {
+                    eprintln!("Error: '{}' is not a pull request number", other);
+                    std::process::exit(1);
+                }
+            },
+        }
+        i += 1;
+    }
+
+    let Some(number) = number else {
+        eprintln!("Error: pr view requires a pull request number");
+        print_pr_help();
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let remote = remote
+        .or_else(|| repo.upstream_remote().ok().flatten())
+        .or_else(|| repo.get_default_remote().ok().flatten())
+        .unwrap_or_else(|| "origin".to_string());
+
+    let pr = match load_pull_request(&repo, number, repo_slug.as_deref(), &remote) {
+        Ok(pr) => pr,
+        Err(e) => {
+            eprintln!("Failed to load pull request #{}: {}", number, e);
+            std::process::exit(1);
+        }
+    };
+
+    if fetch {
+        fetch_pull_request(&repo, &pr, &remote);
+    }
+
+    let use_color = std::io::stdout().is_terminal() && output::mode().color;
+    match render_pull_request(&repo, &pr, use_color) {
+        Ok(rendered) => print!("{}", rendered),
+        Err(e) => {
+            eprintln!("Failed to annotate pull request #{}: {}", number, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// ============================================================================
+// Loading the PR
+// ============================================================================
+
+fn load_pull_request(
+    repo: &Repository,
+    number: u64,
+    repo_slug: Option<&str>,
+    remote: &str,
+) -> Result<PullRequest, GitAiError> {
+    let gh_error = match pull_request_from_gh(repo, number, repo_slug) {
+        Ok(pr) => return Ok(pr),
+        Err(e) => e,
+    };
+    debug_log(&format!(
+        "gh unavailable, using the GitHub API: {}",
+        gh_error
+    ));
+
+    let slug = repo_slug
+        .map(str::to_string)
+        .or_else(|| std::env::var("GITHUB_REPOSITORY").ok())
+        .or_else(|| slug_from_remote(repo, remote))
+        .ok_or_else(|| {
+            GitAiError::Generic(format!(
+                "{} (and no --repo <owner/name> for the GitHub API)",
+                gh_error
+            ))
+        })?;
+    pull_request_from_api(number, &slug)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhPullRequest {
+    title: String,
+    url: String,
+    base_ref_oid: String,
+    head_ref_oid: String,
+    #[serde(default)]
+    is_cross_repository: bool,
+    head_repository: Option<GhRepository>,
+    head_repository_owner: Option<GhOwner>,
+}
+
+#[derive(Deserialize)]
+struct GhRepository {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GhOwner {
+    login: String,
+}
+
+fn pull_request_from_gh(
+    repo: &Repository,
+    number: u64,
+    repo_slug: Option<&str>,
+) -> Result<PullRequest, GitAiError> {
+    let number_arg = number.to_string();
+    let view = run_gh(
+        repo,
+        &[
+            "pr",
+            "view",
+            &number_arg,
+            "--json",
+            "title,url,baseRefOid,headRefOid,isCrossRepository,headRepository,headRepositoryOwner",
+        ],
+        repo_slug,
+    )?;
+    let view: GhPullRequest = serde_json::from_str(&view)?;
+    let diff = run_gh(
+        repo,
+        &["pr", "diff", &number_arg, "--color=never"],
+        repo_slug,
+    )?;
+
+    let fork_url = match (
+        view.is_cross_repository,
+        &view.head_repository,
+        &view.head_repository_owner,
+    ) {
+        (true, Some(head_repo), Some(owner)) => url::Url::parse(&view.url).ok().and_then(|url| {
+            Some(format!(
+                "{}://{}/{}/{}.git",
+                url.scheme(),
+                url.host_str()?,
+                owner.login,
+                head_repo.name
+            ))
+        }),
+        _ => None,
+    };
+
+    Ok(PullRequest {
+        number,
+        title: view.title,
+        url: view.url,
+        base_sha: view.base_ref_oid,
+        head_sha: view.head_ref_oid,
+        fork_url,
+        diff,
+    })
+}
+
+fn run_gh(repo: &Repository, args: &[&str], repo_slug: Option<&str>) -> Result<String, GitAiError> {
+    let mut command = Command::new("gh");
+    command.args(args);
+    if let Some(slug) = repo_slug {
+        command.args(["--repo", slug]);
+    }
+    if let Ok(workdir) = repo.workdir() {
+        command.current_dir(workdir);
+    }
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(GitAiError::Generic(format!(
+            "gh {} failed: {}",
+            args[..2].join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[derive(Deserialize)]
+struct ApiPullRequest {
+    title: String,
+    html_url: String,
+    base: ApiBranch,
+    head: ApiBranch,
+}
+
+#[derive(Deserialize)]
+struct ApiBranch {
+    sha: String,
+    repo: Option<ApiRepository>,
+}
+
+#[derive(Deserialize)]
+struct ApiRepository {
+    clone_url: String,
+}
+
+fn pull_request_from_api(number: u64, slug: &str) -> Result<PullRequest, GitAiError> {
+    let api_url =
+        std::env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".to_string());
+    let url = format!(
+        "{}/repos/{}/pulls/{}",
+        api_url.trim_end_matches('/'),
+        slug,
+        number
+    );
+
+    let pull: ApiPullRequest =
+        serde_json::from_str(&github_get(&url, "application/vnd.github+json")?)?;
+    let diff = github_get(&url, "application/vnd.github.v3.diff")?;
+
+    let base_clone_url = pull.base.repo.map(|repo| repo.clone_url);
+    let fork_url = pull
+        .head
+        .repo
+        .map(|repo| repo.clone_url)
+        .filter(|url| Some(url) != base_clone_url.as_ref());
+
+    Ok(PullRequest {
+        number,
+        title: pull.title,
+        url: pull.html_url,
+        base_sha: pull.base.sha,
+        head_sha: pull.head.sha,
+        fork_url,
+        diff,
+    })
+}
+
+fn github_get(url: &str, accept: &str) -> Result<String, GitAiError> {
+    let mut request = minreq::get(url)
+        .with_header("Accept", accept)
+        .with_header("X-GitHub-Api-Version", "2022-11-28")
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_timeout(30);
+    // Public repositories work without a token, at a lower rate limit
+    if let Some(token) = ["GH_TOKEN", "GITHUB_TOKEN"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|token| !token.is_empty()))
+    {
+        request = request.with_header("Authorization", format!("Bearer {}", token));
+    }
+    let response = request
+        .send()
+        .map_err(|e| GitAiError::Generic(format!("GitHub API request failed: {}", e)))?;
+    if !(200..300).contains(&response.status_code) {
+        return Err(GitAiError::Generic(format!(
+            "GitHub API returned status {}: {}",
+            response.status_code,
+            response.as_str().unwrap_or("unknown error")
+        )));
+    }
+    Ok(response.as_str().unwrap_or_default().to_string())
+}
+
+/// `owner/name` of a GitHub remote
+fn slug_from_remote(repo: &Repository, remote: &str) -> Option<String> {
+    let url = repo
+        .remotes_with_urls()
+        .ok()?
+        .into_iter()
+        .find(|(name, _)| name == remote)?
+        .1;
+    let normalized = crate::repo_url::normalize_repo_url(&url).ok()?;
+    let path = url::Url::parse(&normalized)
+        .ok()?
+        .path()
+        .trim_matches('/')
+        .to_string();
+    (path.split('/').count() == 2).then_some(path)
+}
+
+/// Bring the PR's commits and the authorship notes on them into this clone.
+/// Best-effort: rendering works with whatever is already local.
+fn fetch_pull_request(repo: &Repository, pr: &PullRequest, remote: &str) {
+    let pull_ref = format!("refs/pull/{}/head", pr.number);
+    for (sha, refspec) in [(&pr.head_sha, &pull_ref), (&pr.base_sha, &pr.base_sha)] {
+        if has_commit(repo, sha) {
+            continue;
+        }
+        let mut args = repo.global_args_for_exec();
+        args.extend(["fetch", "--quiet", "--no-tags", remote, refspec].map(String::from));
+        if let Err(e) = exec_git(&args) {
+            debug_log(&format!(
+                "Failed to fetch {} from {}: {}",
+                refspec, remote, e
+            ));
+        }
+    }
+
+    if let Err(e) = fetch_authorship_notes(repo, remote) {
+        debug_log(&format!("Failed to fetch authorship notes: {}", e));
+    }
+    // Commits made on a fork are noted on the fork
+    if let Some(fork_url) = &pr.fork_url
+        && let Err(e) = fetch_authorship_notes_into(repo, fork_url, &fork_notes_ref(pr.number))
+    {
+        debug_log(&format!(
+            "Failed to fetch authorship notes from the fork: {}",
+            e
+        ));
+    }
+}
+
+/// Where a PR's fork notes are kept, apart from the authorship notes
+fn fork_notes_ref(number: u64) -> String {
+    format!("{}-pr/{}", authorship_notes_ref(), number)
+}
+
+fn has_commit(repo: &Repository, sha: &str) -> bool {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file", "-e", &format!("{}^{{commit}}", sha)].map(String::from));
+    exec_git(&args).is_ok()
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+fn render_pull_request(
+    repo: &Repository,
+    pr: &PullRequest,
+    use_color: bool,
+) -> Result<String, GitAiError> {
+    if !has_commit(repo, &pr.head_sha) {
+        return Err(GitAiError::Generic(format!(
+            "PR head {} isn't available locally; fetch it or drop --no-fetch",
+            pr.head_sha
+        )));
+    }
+    // GitHub diffs a PR against the merge base, not the base branch tip
+    let mut args = repo.global_args_for_exec();
+    args.extend(["merge-base", &pr.base_sha, &pr.head_sha].map(String::from));
+    let merge_base = String::from_utf8(exec_git(&args)?.stdout)?
+        .trim()
+        .to_string();
+
+    let hunks = added_lines_by_hunk(&pr.diff);
+    let mut lines_by_file: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+    for hunk in &hunks {
+        lines_by_file
+            .entry(&hunk.file)
+            .or_default()
+            .extend(&hunk.added);
+    }
+
+    let mut attributions: HashMap<DiffLineKey, Attribution> = HashMap::new();
+    let mut line_prompts: HashMap<(String, u32), String> = HashMap::new();
+    let mut prompts: HashMap<String, PromptRecord> = HashMap::new();
+    for (file, lines) in lines_by_file {
+        let options = GitAiBlameOptions {
+            oldest_commit: Some(merge_base.clone()),
+            newest_commit: Some(pr.head_sha.clone()),
+            line_ranges: lines_to_ranges(&lines),
+            no_output: true,
+            use_prompt_hashes_as_names: true,
+            fallback_notes_ref: pr.fork_url.as_ref().map(|_| fork_notes_ref(pr.number)),
+            ..Default::default()
+        };
+        let blamed = repo.blame(file, &options);
+        for line in &lines {
+            let key = DiffLineKey {
+                file: file.to_string(),
+                line: *line,
+                side: LineSide::New,
+            };
+            let author = blamed
+                .as_ref()
+                .ok()
+                .and_then(|(line_authors, _)| line_authors.get(line));
+            let attribution = match (author, &blamed) {
+                (Some(author), Ok((_, records))) => match records.get(author) {
+                    Some(record) => {
+                        line_prompts.insert((file.to_string(), *line), author.clone());
+                        prompts
+                            .entry(author.clone())
+                            .or_insert_with(|| record.clone());
+                        Attribution::Ai(record.agent_id.tool.clone())
+                    }
+                    None => Attribution::Human(author.clone()),
+                },
+                _ => Attribution::NoData,
+            };
+            attributions.insert(key, attribution);
+        }
+    }
+
+    let prompt_ids: Vec<String> = prompts.keys().cloned().collect();
+    let referenced: HashSet<&String> = prompt_ids.iter().collect();
+    enrich_prompt_messages(repo, &mut prompts, &referenced);
+
+    let mut footers = HashMap::new();
+    for hunk in &hunks {
+        let mut lines_per_prompt: BTreeMap<&str, usize> = BTreeMap::new();
+        for line in &hunk.added {
+            if let Some(prompt_id) = line_prompts.get(&(hunk.file.clone(), *line)) {
+                *lines_per_prompt.entry(prompt_id).or_default() += 1;
+            }
+        }
+        if lines_per_prompt.is_empty() {
+            continue;
+        }
+        footers.insert(
+            (hunk.file.clone(), hunk.new_start),
+            hunk_summary(hunk.added.len(), &lines_per_prompt, &prompts),
+        );
+    }
+
+    let added: usize = hunks.iter().map(|hunk| hunk.added.len()).sum();
+    let mut rendered = format!("#{} {}\n{}\n", pr.number, pr.title, pr.url);
+    if added > 0 {
+        rendered.push_str(&format!(
+            "AI wrote {} of {} added lines ({}%) across {} {}\n",
+            line_prompts.len(),
+            added,
+            line_prompts.len() * 100 / added,
+            prompts.len(),
+            if prompts.len() == 1 {
+                "session"
+            } else {
+                "sessions"
+            }
+        ));
+    }
+    rendered.push('\n');
+    rendered.push_str(&annotate_diff_text(
+        &pr.diff,
+        &attributions,
+        use_color,
+        &footers,
+    ));
+    Ok(rendered)
+}
+
+/// The added lines of every hunk in a unified diff
+fn added_lines_by_hunk(diff_text: &str) -> Vec<PrHunk> {
+    let mut hunks: Vec<PrHunk> = Vec::new();
+    let mut current_file = String::new();
+    let mut new_line = 0u32;
+    for line in diff_text.lines() {
+        if line.starts_with("diff --git") {
+            current_file.clear();
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let path = crate::utils::unescape_git_path(path.trim_end());
+            current_file = path.strip_prefix("b/").unwrap_or(&path).to_string();
+        } else if line.starts_with("@@ ") {
+            new_line = line
+                .split_whitespace()
+                .nth(2)
+                .and_then(|part| part.strip_prefix('+'))
+                .and_then(|part| part.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+            hunks.push(PrHunk {
+                file: current_file.clone(),
+                new_start: new_line,
+                added: Vec::new(),
+            });
+        } else if line.starts_with('+') {
+            if let Some(hunk) = hunks.last_mut() {
+                hunk.added.push(new_line);
+            }
+            new_line += 1;
+        } else if line.starts_with(' ') {
+            new_line += 1;
+        }
+    }
+    hunks.retain(|hunk| !hunk.added.is_empty() && hunk.file != "/dev/null");
+    hunks
+}
+
+fn hunk_summary(
+    added: usize,
+    lines_per_prompt: &BTreeMap<&str, usize>,
+    prompts: &HashMap<String, PromptRecord>,
+) -> String {
+    let ai_lines: usize = lines_per_prompt.values().sum();
+    let sessions: Vec<String> = lines_per_prompt
+        .keys()
+        .filter_map(|prompt_id| prompts.get(*prompt_id))
+        .map(|prompt| {
+            let agent = format!("{} ({})", prompt.agent_id.tool, prompt.agent_id.model);
+            match first_prompt(prompt) {
+                Some(excerpt) => format!("{}: \"{}\"", agent, excerpt),
+                None => agent,
+            }
+        })
+        .collect();
+    format!(
+        "{} {} of {} added lines by AI {} {}",
+        output::mode().pick("🤖", "ai:"),
+        ai_lines,
+        added,
+        output::mode().pick("·", "-"),
+        sessions.join("; ")
+    )
+}
+
+/// First line of the first user message, shortened for a one-line summary
+fn first_prompt(prompt: &PromptRecord) -> Option<String> {
+    let text = prompt.messages.iter().find_map(|message| match message {
+        Message::User { text, .. } if !text.trim().is_empty() => Some(text),
+        _ => None,
+    })?;
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() > PROMPT_EXCERPT_CHARS {
+        let shortened: String = line.chars().take(PROMPT_EXCERPT_CHARS - 3).collect();
+        Some(format!("{}...", shortened.trim_end()))
+    } else {
+        Some(line.to_string())
+    }
+}
+
+fn print_pr_help() {
+    eprintln!(
+        "Usage: git-ai pr view <number> [--remote <name>] [--repo <owner/name>] [--no-fetch]"
+    );
+    eprintln!();
+    eprintln!("Show a GitHub pull request's diff with AI attribution on each added line and");
+    eprintln!("a summary of the prompts behind each hunk. Uses gh when installed, otherwise");
+    eprintln!("the GitHub API (set GH_TOKEN or GITHUB_TOKEN for private repositories).");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!(
+        "  --remote <name>       Remote to fetch the PR head and notes from (default: upstream)"
+    );
+    eprintln!("  --repo <owner/name>   Repository the PR belongs to (default: from the remote)");
+    eprintln!("  --no-fetch            Use only commits and notes already in this clone");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_lines_by_hunk_tracks_new_side_line_numbers() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ fn a() {}
+-fn b() {}
++fn b() { 1 }
++fn c() {}
+ fn d() {}
+@@ -10,2 +11,3 @@ fn e() {}
+ fn f() {}
++fn g() {}
+ fn h() {}
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+        let hunks = added_lines_by_hunk(diff);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].file, "src/lib.rs");
+        assert_eq!(hunks[0].new_start, 1);
+        assert_eq!(hunks[0].added, vec![2, 3]);
+        assert_eq!(hunks[1].new_start, 11);
+        assert_eq!(hunks[1].added, vec![12]);
+    }
+}
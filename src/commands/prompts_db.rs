@@ -4,9 +4,11 @@
 //! Designed for Claude Code skills and other terminal-based analysis tools.
 
 use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::prompt_interning::resolve_interned_messages;
 use crate::authorship::transcript::AiTranscript;
 use crate::error::GitAiError;
 use crate::git::find_repository_in_path;
+use crate::git::refs::authorship_notes_ref_arg;
 use crate::git::repository::{Repository, exec_git, exec_git_stdin};
 use chrono::{Local, TimeZone};
 use rusqlite::{Connection, params};
@@ -87,10 +89,11 @@ pub fn handle_prompts(args: &[String]) {
         "next" => handle_next(&args[1..]),
         "reset" => handle_reset(&args[1..]),
         "count" => handle_count(&args[1..]),
+        "show" => crate::commands::prompts_show::handle_prompts_show(&args[1..]),
         arg if arg.starts_with('-') => handle_populate(args), // flags for populate
         _ => {
             eprintln!("Unknown subcommand: {}", args[0]);
-            eprintln!("Usage: git-ai prompts [exec|list|next|count|reset] [options]");
+            eprintln!("Usage: git-ai prompts [exec|list|next|count|reset|show] [options]");
             std::process::exit(1);
         }
     }
@@ -837,9 +840,10 @@ fn fetch_from_git_notes(
 
         for (commit_sha, note_content) in commits_with_notes {
             // Parse the note content as AuthorshipLog
-            if let Ok(authorship_log) =
+            if let Ok(mut authorship_log) =
                 crate::authorship::authorship_log_serialization::AuthorshipLog::deserialize_from_string(&note_content)
             {
+                resolve_interned_messages(&repo, authorship_log.metadata.prompts.values_mut());
                 for (prompt_hash, prompt_record) in &authorship_log.metadata.prompts {
                     // Apply author filter
                     if let Some(auth_filter) = author {
@@ -970,7 +974,7 @@ fn get_commits_since(global_args: &[String], since_timestamp: i64) -> Vec<String
 fn get_notes_list(global_args: &[String]) -> Vec<(String, String)> {
     let mut args = global_args.to_vec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(authorship_notes_ref_arg());
     args.push("list".to_string());
 
     let output = match exec_git(&args) {
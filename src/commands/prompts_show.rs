@@ -0,0 +1,273 @@
+//! `git-ai prompts show <commit> [<prompt_id>]`: the prompts recorded in a
+//! commit's authorship note, and the transcript behind any one of them.
+//!
+//! Without a prompt id this lists every prompt in the note with the files it
+//! wrote; with one (or a unique prefix of one) it prints that session's
+//! messages. Transcripts are resolved the same way as `show-prompt`, so notes
+//! that only reference CAS or interned messages still show them.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::{AuthorshipLog, find_prompt_by_id};
+use crate::authorship::transcript::Message;
+use crate::commands::show_prompt::resolve_prompt_messages;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::Repository;
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PromptsShowArgs {
+    pub commit: String,
+    pub prompt_id: Option<String>,
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct PromptSummary<'a> {
+    id: &'a str,
+    tool: &'a str,
+    model: &'a str,
+    human_author: Option<&'a str>,
+    accepted_lines: u32,
+    overridden_lines: u32,
+    files: Vec<&'a str>,
+    messages: usize,
+    first_message_at: Option<&'a str>,
+    last_message_at: Option<&'a str>,
+}
+
+pub fn parse_prompts_show_args(args: &[String]) -> Result<PromptsShowArgs, String> {
+    let mut positional = Vec::new();
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            other if other.starts_with('-') => return Err(format!("Unknown option: {}", other)),
+            other => positional.push(other.to_string()),
+        }
+    }
+    if positional.len() > 2 {
+        return Err("prompts show takes a commit and at most one prompt ID".to_string());
+    }
+    let mut positional = positional.into_iter();
+    let commit = positional.next().ok_or("prompts show requires a commit")?;
+    Ok(PromptsShowArgs {
+        commit,
+        prompt_id: positional.next(),
+        json,
+    })
+}
+
+pub fn handle_prompts_show(args: &[String]) {
+    let parsed = match parse_prompts_show_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai prompts show <commit> [<prompt_id>] [--json]");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = show_prompts(&repo, &parsed) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn show_prompts(repo: &Repository, args: &PromptsShowArgs) -> Result<(), GitAiError> {
+    let commit_sha = repo.revparse_single(&args.commit)?.peel_to_commit()?.id();
+    let log = get_authorship(repo, &commit_sha).ok_or_else(|| {
+        GitAiError::Generic(format!("No authorship data found for {}", args.commit))
+    })?;
+
+    let Some(prefix) = &args.prompt_id else {
+        let mut prompts: Vec<(String, PromptRecord)> = log
+            .metadata
+            .prompts
+            .iter()
+            .map(|(id, prompt)| (id.clone(), prompt.clone()))
+            .collect();
+        for (id, prompt) in &mut prompts {
+            resolve_prompt_messages(repo, id, prompt);
+        }
+        prompts.sort_by_key(|(_, prompt)| first_timestamp(prompt));
+        let summaries: Vec<PromptSummary> = prompts
+            .iter()
+            .map(|(id, prompt)| summarize(&log, id, prompt))
+            .collect();
+        if args.json {
+            let output = serde_json::json!({
+                "commit": commit_sha,
+                "prompts": summaries,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            print!("{}", render_summaries(&commit_sha, &summaries));
+        }
+        return Ok(());
+    };
+
+    let (prompt_id, prompt) = find_by_prefix(&log, prefix)?;
+    let mut prompt = prompt.clone();
+    resolve_prompt_messages(repo, &prompt_id, &mut prompt);
+    if args.json {
+        let output = serde_json::json!({
+            "commit": commit_sha,
+            "prompt_id": prompt_id,
+            "prompt": prompt,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print!("{}", render_transcript(&prompt_id, &prompt));
+    }
+    Ok(())
+}
+
+fn find_by_prefix<'a>(
+    log: &'a AuthorshipLog,
+    prefix: &str,
+) -> Result<(String, &'a PromptRecord), GitAiError> {
+    if let Some((id, prompt)) = find_prompt_by_id(&log.metadata.prompts, prefix) {
+        return Ok((id.clone(), prompt));
+    }
+    let matches: Vec<_> = log
+        .metadata
+        .prompts
+        .iter()
+        .filter(|(id, _)| id.starts_with(prefix))
+        .collect();
+    match matches.as_slice() {
+        [(id, prompt)] => Ok(((*id).clone(), prompt)),
+        [] => Err(GitAiError::Generic(format!(
+            "No prompt {} in this commit's authorship note",
+            prefix
+        ))),
+        _ => Err(GitAiError::Generic(format!(
+            "Prompt id {} is ambiguous in this commit ({} matches)",
+            prefix,
+            matches.len()
+        ))),
+    }
+}
+
+fn first_timestamp(prompt: &PromptRecord) -> Option<String> {
+    prompt.messages.iter().find_map(|m| m.timestamp().cloned())
+}
+
+fn summarize<'a>(
+    log: &'a AuthorshipLog,
+    id: &'a str,
+    prompt: &'a PromptRecord,
+) -> PromptSummary<'a> {
+    let files = log
+        .attestations
+        .iter()
+        .filter(|file| file.entries.iter().any(|entry| entry.hash == id))
+        .map(|file| file.file_path.as_str())
+        .collect();
+    PromptSummary {
+        id,
+        tool: &prompt.agent_id.tool,
+        model: &prompt.agent_id.model,
+        human_author: prompt.human_author.as_deref(),
+        accepted_lines: prompt.accepted_lines,
+        overridden_lines: prompt.overriden_lines,
+        files,
+        messages: prompt.messages.len(),
+        first_message_at: prompt
+            .messages
+            .iter()
+            .find_map(|m| m.timestamp())
+            .map(String::as_str),
+        last_message_at: prompt
+            .messages
+            .iter()
+            .rev()
+            .find_map(|m| m.timestamp())
+            .map(String::as_str),
+    }
+}
+
+fn render_summaries(commit_sha: &str, summaries: &[PromptSummary]) -> String {
+    if summaries.is_empty() {
+        return format!("No prompts recorded for {}\n", commit_sha);
+    }
+    let mut out = format!(
+        "{} prompt{} in {}\n",
+        summaries.len(),
+        if summaries.len() == 1 { "" } else { "s" },
+        &commit_sha[..commit_sha.len().min(7)]
+    );
+    for summary in summaries {
+        out.push_str(&format!(
+            "\n{}  {} ({})  {} accepted, {} overridden\n",
+            summary.id,
+            summary.tool,
+            summary.model,
+            summary.accepted_lines,
+            summary.overridden_lines
+        ));
+        if let Some(author) = summary.human_author {
+            out.push_str(&format!("  by {}\n", author));
+        }
+        match (summary.first_message_at, summary.last_message_at) {
+            (Some(first), Some(last)) if first != last => {
+                out.push_str(&format!("  {} .. {}\n", first, last))
+            }
+            (Some(first), _) => out.push_str(&format!("  {}\n", first)),
+            _ => {}
+        }
+        for file in &summary.files {
+            out.push_str(&format!("  {}\n", file));
+        }
+    }
+    out
+}
+
+fn render_transcript(prompt_id: &str, prompt: &PromptRecord) -> String {
+    let mut out = format!(
+        "Prompt {} ({}, {})\n",
+        prompt_id, prompt.agent_id.tool, prompt.agent_id.model
+    );
+    if let Some(author) = &prompt.human_author {
+        out.push_str(&format!("Author: {}\n", author));
+    }
+    if prompt.messages.is_empty() {
+        out.push_str("\n(transcript not available)\n");
+        return out;
+    }
+    for message in &prompt.messages {
+        let (role, text) = match message {
+            Message::User { text, .. } => ("user", text.clone()),
+            Message::Assistant { text, .. } => ("assistant", text.clone()),
+            Message::Thinking { text, .. } => ("thinking", text.clone()),
+            Message::Plan { text, .. } => ("plan", text.clone()),
+            Message::ToolUse { name, input, .. } => (
+                "tool",
+                format!(
+                    "{} {}",
+                    name,
+                    serde_json::to_string(input).unwrap_or_default()
+                ),
+            ),
+        };
+        out.push('\n');
+        match message.timestamp() {
+            Some(timestamp) => out.push_str(&format!("[{}] {}\n", role, timestamp)),
+            None => out.push_str(&format!("[{}]\n", role)),
+        }
+        for line in text.lines() {
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+    out
+}
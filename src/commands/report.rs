@@ -0,0 +1,703 @@
+//! `git-ai report <start>..<end>`: a standalone HTML page summarizing AI
+//! authorship over a range, for people who want the numbers without the
+//! hosted dashboard.
+//!
+//! The page shows AI vs human additions per day or week, a heatmap of the AI
+//! share per directory and bucket, the prompts whose lines still survive at the
+//! end of the range, and totals per agent. Everything is computed locally from
+//! authorship notes and blame.
+
+use crate::api::object_storage::{is_object_url, upload_object};
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::identity::IdentityResolver;
+use crate::authorship::ignore::{
+    build_ignore_matcher, effective_ignore_patterns, should_ignore_file_with_matcher,
+};
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
+use crate::authorship::stats::{CommitStats, line_range_overlap_len, stats_for_commit_stats};
+use crate::authorship::transcript::Message;
+use crate::commands::blame::{GitAiBlameOptions, overlay_ai_authorship};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
+use crate::git::repository::{CommitRange, Repository};
+use crate::reporting::buckets::{BucketSize, ReportTimezone, resolve_report_timezone, rollup};
+use crate::reporting::filters::AuthorFilter;
+use crate::reporting::templates::render_html_report;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+/// Prompts listed in "top prompts" unless `--top` says otherwise
+const DEFAULT_TOP_PROMPTS: usize = 20;
+/// Path components that make up a heatmap directory unless `--depth` says otherwise
+const DEFAULT_DIRECTORY_DEPTH: usize = 2;
+/// Characters of a prompt's first message shown in the report
+const PROMPT_SUMMARY_LENGTH: usize = 160;
+
+#[derive(Debug, Clone)]
+struct ReportOptions {
+    bucket: BucketSize,
+    tz: ReportTimezone,
+    top: usize,
+    depth: usize,
+    ignore_patterns: Vec<String>,
+    author_filter: AuthorFilter,
+}
+
+/// Everything the HTML template renders
+#[derive(Debug, Serialize)]
+struct ReportContext {
+    title: String,
+    /// Short SHA of the range end, where surviving lines are counted
+    end: String,
+    generated_at: String,
+    version: &'static str,
+    timezone: String,
+    bucket: &'static str,
+    totals: ReportTotals,
+    trend: Vec<TrendPoint>,
+    heatmap: Heatmap,
+    prompts: Vec<PromptSummary>,
+    agents: Vec<AgentSummary>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ReportTotals {
+    commits: usize,
+    commits_with_authorship: usize,
+    ai_additions: u32,
+    human_additions: u32,
+    ai_percent: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct TrendPoint {
+    start: String,
+    commits: usize,
+    ai_additions: u32,
+    human_additions: u32,
+    ai_percent: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Heatmap {
+    /// Bucket start dates, oldest first
+    columns: Vec<String>,
+    /// Directories, most added lines first
+    rows: Vec<HeatmapRow>,
+}
+
+#[derive(Debug, Serialize)]
+struct HeatmapRow {
+    directory: String,
+    ai_additions: u32,
+    human_additions: u32,
+    ai_percent: u32,
+    /// One per column; `None` where the directory had no added lines
+    cells: Vec<Option<HeatmapCell>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+struct HeatmapCell {
+    added_lines: u32,
+    ai_additions: u32,
+    ai_percent: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct PromptSummary {
+    id: String,
+    tool: String,
+    model: String,
+    author: Option<String>,
+    /// The first user message, when the note carries the transcript
+    summary: String,
+    /// Short SHA of the commit that introduced the prompt's lines
+    commit: String,
+    surviving_lines: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct AgentSummary {
+    tool: String,
+    model: String,
+    ai_additions: u32,
+    ai_accepted: u32,
+    surviving_lines: u32,
+    share_percent: u32,
+}
+
+/// Per-commit inputs to the report
+struct CommitData {
+    timestamp: i64,
+    stats: CommitStats,
+    /// Added lines per file: (added, AI-attributed)
+    files: HashMap<String, (u32, u32)>,
+}
+
+pub fn handle_report(args: &[String]) {
+    let mut range_arg: Option<String> = None;
+    let mut output: Option<String> = None;
+    let mut bucket = BucketSize::Week;
+    let mut tz_arg: Option<String> = None;
+    let mut top = DEFAULT_TOP_PROMPTS;
+    let mut depth = DEFAULT_DIRECTORY_DEPTH;
+    let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut exclude_bots = false;
+    let mut authors: Vec<String> = Vec::new();
+    let mut all_authors = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let takes_value = matches!(
+            args[i].as_str(),
+            "-o" | "--output" | "--bucket" | "--tz" | "--top" | "--depth" | "--ignore" | "--author"
+        );
+        if takes_value && i + 1 >= args.len() {
+            eprintln!("{} requires a value", args[i]);
+            std::process::exit(1);
+        }
+        match args[i].as_str() {
+            "-h" | "--help" => {
+                print_report_help();
+                return;
+            }
+            "-o" | "--output" => output = Some(args[i + 1].clone()),
+            "--bucket" => {
+                bucket = match args[i + 1].parse() {
+                    Ok(bucket) => bucket,
+                    Err(e) => {
+                        eprintln!("{} (expected day or week)", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--tz" => tz_arg = Some(args[i + 1].clone()),
+            "--top" | "--depth" => {
+                let Some(value) = args[i + 1].parse::<usize>().ok().filter(|n| *n > 0) else {
+                    eprintln!("{} requires a positive number", args[i]);
+                    std::process::exit(1);
+                };
+                if args[i] == "--top" {
+                    top = value;
+                } else {
+                    depth = value;
+                }
+            }
+            "--ignore" => ignore_patterns.push(args[i + 1].clone()),
+            "--author" => authors.push(args[i + 1].clone()),
+            "--exclude-bots" => exclude_bots = true,
+            "--all-authors" => all_authors = true,
+            other if !other.starts_with('-') && range_arg.is_none() => {
+                range_arg = Some(other.to_string())
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                print_report_help();
+                std::process::exit(1);
+            }
+        }
+        i += if takes_value { 2 } else { 1 };
+    }
+
+    let Some((start, end)) = range_arg
+        .as_deref()
+        .and_then(|range| range.split_once(".."))
+    else {
+        print_report_help();
+        std::process::exit(1);
+    };
+    let end = if end.is_empty() { "HEAD" } else { end };
+
+    let tz = match resolve_report_timezone(tz_arg.as_deref()) {
+        Ok(tz) => tz,
+        Err(e) => {
+            eprintln!("Invalid time zone: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let range =
+        match CommitRange::new_infer_refname(&repo, start.to_string(), end.to_string(), None) {
+            Ok(range) => range,
+            Err(e) => {
+                eprintln!("Failed to create commit range: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+    let options = ReportOptions {
+        bucket,
+        tz,
+        top,
+        depth,
+        ignore_patterns: effective_ignore_patterns(&repo, &ignore_patterns, &[]),
+        author_filter: AuthorFilter::for_report(exclude_bots, &authors, all_authors),
+    };
+    let html = match build_report(&repo, range, &options).and_then(|ctx| render_html_report(&ctx)) {
+        Ok(html) => html,
+        Err(e) => {
+            eprintln!("Report failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match output {
+        Some(path) => {
+            let written = if is_object_url(&path) {
+                upload_object(&path, html.as_bytes())
+            } else {
+                fs::write(&path, html).map_err(GitAiError::from)
+            };
+            if let Err(e) = written {
+                eprintln!("Failed to write {}: {}", path, e);
+                std::process::exit(1);
+            }
+            eprintln!("Wrote report to {}", path);
+        }
+        None => print!("{}", html),
+    }
+}
+
+fn build_report(
+    repo: &Repository,
+    range: CommitRange,
+    options: &ReportOptions,
+) -> Result<ReportContext, GitAiError> {
+    range.is_valid()?;
+    let title = format!(
+        "{}..{}",
+        short_sha(&range.start_oid),
+        short_sha(&range.end_oid)
+    );
+    let start_oid = range.start_oid.clone();
+    let end_oid = range.end_oid.clone();
+
+    let commit_shas: Vec<String> = range.into_iter().map(|c| c.id().to_string()).collect();
+    let identities = IdentityResolver::from_config(true);
+    let commits: Vec<CommitAuthorship> = get_commits_with_notes_from_list(repo, &commit_shas)?
+        .into_iter()
+        .filter(|ca| {
+            let author = commit_git_author(ca);
+            options
+                .author_filter
+                .allows_identity(author, &identities.resolve(author))
+        })
+        .collect();
+
+    let ignore_matcher = build_ignore_matcher(&options.ignore_patterns);
+    let mut data = Vec::with_capacity(commits.len());
+    for ca in &commits {
+        let (sha, log) = match ca {
+            CommitAuthorship::Log {
+                sha,
+                authorship_log,
+                ..
+            } => (sha, Some(authorship_log.as_ref())),
+            CommitAuthorship::NoLog { sha, .. } => (sha, None),
+        };
+        let commit = repo.find_commit(sha.clone())?;
+        let mut files = HashMap::new();
+        // Merges bring in lines their parents already added
+        if commit.parent_count()? <= 1 {
+            let parent = if commit.parent_count()? == 0 {
+                EMPTY_TREE_HASH.to_string()
+            } else {
+                commit.parent(0)?.id()
+            };
+            let mut added = repo.diff_added_lines(&parent, sha, None)?;
+            added.retain(|file, _| !should_ignore_file_with_matcher(file, &ignore_matcher));
+            files = attribute_added_lines(added, log);
+        }
+        data.push(CommitData {
+            timestamp: commit.time()?.seconds(),
+            stats: stats_for_commit_stats(repo, sha, &options.ignore_patterns)?,
+            files,
+        });
+    }
+
+    let included: HashSet<&str> = commits.iter().map(commit_sha).collect();
+    let (prompts, surviving_by_agent) =
+        surviving_prompts(repo, &start_oid, &end_oid, &included, &ignore_matcher)?;
+
+    let mut totals = ReportTotals {
+        commits: commits.len(),
+        commits_with_authorship: commits
+            .iter()
+            .filter(|ca| matches!(ca, CommitAuthorship::Log { .. }))
+            .count(),
+        ..Default::default()
+    };
+    for commit in &data {
+        totals.ai_additions += commit.stats.ai_additions;
+        totals.human_additions += commit.stats.human_additions;
+    }
+    totals.ai_percent = percent(
+        totals.ai_additions,
+        totals.ai_additions + totals.human_additions,
+    );
+
+    Ok(ReportContext {
+        title,
+        end: short_sha(&end_oid).to_string(),
+        generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        version: env!("CARGO_PKG_VERSION"),
+        timezone: options.tz.to_string(),
+        bucket: match options.bucket {
+            BucketSize::Day => "day",
+            BucketSize::Week => "week",
+        },
+        totals,
+        trend: trend(&data, options),
+        heatmap: heatmap(&data, options),
+        prompts: prompts.into_iter().take(options.top).collect(),
+        agents: agents(&data, &surviving_by_agent),
+    })
+}
+
+/// Split each file's added lines into (added, AI-attributed) using the
+/// commit's attestations
+fn attribute_added_lines(
+    mut added: HashMap<String, Vec<u32>>,
+    log: Option<&AuthorshipLog>,
+) -> HashMap<String, (u32, u32)> {
+    for lines in added.values_mut() {
+        lines.sort_unstable();
+        lines.dedup();
+    }
+    let mut ai_by_file: HashMap<&str, u32> = HashMap::new();
+    for attestation in log.iter().flat_map(|log| &log.attestations) {
+        let Some(lines) = added.get(&attestation.file_path) else {
+            continue;
+        };
+        let ai: u32 = attestation
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.line_ranges)
+            .map(|range| line_range_overlap_len(range, lines))
+            .sum();
+        *ai_by_file
+            .entry(attestation.file_path.as_str())
+            .or_default() += ai;
+    }
+    added
+        .iter()
+        .map(|(file, lines)| {
+            let added_lines = lines.len() as u32;
+            let ai = ai_by_file.get(file.as_str()).copied().unwrap_or(0);
+            (file.clone(), (added_lines, ai.min(added_lines)))
+        })
+        .collect()
+}
+
+fn trend(data: &[CommitData], options: &ReportOptions) -> Vec<TrendPoint> {
+    let commits = data.iter().map(|commit| (commit.timestamp, commit));
+    rollup(commits, options.bucket, &options.tz)
+        .into_iter()
+        .map(|(start, commits)| {
+            let ai_additions: u32 = commits.iter().map(|c| c.stats.ai_additions).sum();
+            let human_additions: u32 = commits.iter().map(|c| c.stats.human_additions).sum();
+            TrendPoint {
+                start: start.to_string(),
+                commits: commits.len(),
+                ai_additions,
+                human_additions,
+                ai_percent: percent(ai_additions, ai_additions + human_additions),
+            }
+        })
+        .collect()
+}
+
+fn heatmap(data: &[CommitData], options: &ReportOptions) -> Heatmap {
+    let buckets = rollup(
+        data.iter().map(|commit| (commit.timestamp, commit)),
+        options.bucket,
+        &options.tz,
+    );
+    let columns: Vec<NaiveDate> = buckets.keys().copied().collect();
+    let mut cells: BTreeMap<String, BTreeMap<NaiveDate, HeatmapCell>> = BTreeMap::new();
+    for (start, commits) in buckets {
+        for (file, (added, ai)) in commits.iter().flat_map(|commit| &commit.files) {
+            let cell = cells
+                .entry(directory_of(file, options.depth))
+                .or_default()
+                .entry(start)
+                .or_default();
+            cell.added_lines += added;
+            cell.ai_additions += ai;
+        }
+    }
+
+    let mut rows: Vec<HeatmapRow> = cells
+        .into_iter()
+        .map(|(directory, by_bucket)| {
+            let added: u32 = by_bucket.values().map(|cell| cell.added_lines).sum();
+            let ai: u32 = by_bucket.values().map(|cell| cell.ai_additions).sum();
+            HeatmapRow {
+                directory,
+                ai_additions: ai,
+                human_additions: added - ai,
+                ai_percent: percent(ai, added),
+                cells: columns
+                    .iter()
+                    .map(|column| {
+                        by_bucket.get(column).map(|cell| HeatmapCell {
+                            ai_percent: percent(cell.ai_additions, cell.added_lines),
+                            ..*cell
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(row.ai_additions + row.human_additions));
+
+    Heatmap {
+        columns: columns.iter().map(|date| date.to_string()).collect(),
+        rows,
+    }
+}
+
+/// AI lines written in the range that are still present at `end`, per prompt
+/// (most lines first) and per `tool::model`
+fn surviving_prompts(
+    repo: &Repository,
+    start: &str,
+    end: &str,
+    included: &HashSet<&str>,
+    ignore_matcher: &crate::authorship::ignore::IgnoreMatcher,
+) -> Result<(Vec<PromptSummary>, HashMap<String, u32>), GitAiError> {
+    let tree = repo.find_commit(end.to_string())?.tree()?;
+    let options = GitAiBlameOptions {
+        newest_commit: Some(end.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+
+    let mut prompts: HashMap<String, PromptSummary> = HashMap::new();
+    for file in repo.diff_changed_files(start, end)? {
+        if should_ignore_file_with_matcher(&file, ignore_matcher) {
+            continue;
+        }
+        // Deleted by the end of the range
+        let Ok(entry) = tree.get_path(std::path::Path::new(&file)) else {
+            continue;
+        };
+        let content = repo.find_blob(entry.id())?.content().unwrap_or_default();
+        let total_lines = String::from_utf8_lossy(&content).lines().count() as u32;
+        if total_lines == 0 {
+            continue;
+        }
+
+        let hunks = repo.blame_hunks(&file, 1, total_lines, &options)?;
+        let (line_authors, prompt_records, _, _) =
+            overlay_ai_authorship(repo, &hunks, &file, None, &options)?;
+        for hunk in hunks
+            .iter()
+            .filter(|hunk| included.contains(hunk.commit_sha.as_str()))
+        {
+            for line in hunk.range.0..=hunk.range.1 {
+                let Some((id, record)) = line_authors
+                    .get(&line)
+                    .and_then(|author| prompt_records.get_key_value(author))
+                else {
+                    continue;
+                };
+                prompts
+                    .entry(id.clone())
+                    .or_insert_with(|| PromptSummary {
+                        id: id.clone(),
+                        tool: record.agent_id.tool.clone(),
+                        model: record.agent_id.model.clone(),
+                        author: record.human_author.clone(),
+                        summary: first_user_message(&record.messages),
+                        commit: short_sha(&hunk.commit_sha).to_string(),
+                        surviving_lines: 0,
+                    })
+                    .surviving_lines += 1;
+            }
+        }
+    }
+
+    let mut by_agent: HashMap<String, u32> = HashMap::new();
+    for prompt in prompts.values() {
+        *by_agent
+            .entry(format!("{}::{}", prompt.tool, prompt.model))
+            .or_default() += prompt.surviving_lines;
+    }
+    let mut prompts: Vec<PromptSummary> = prompts.into_values().collect();
+    prompts.sort_by(|a, b| {
+        b.surviving_lines
+            .cmp(&a.surviving_lines)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok((prompts, by_agent))
+}
+
+fn agents(data: &[CommitData], surviving_by_agent: &HashMap<String, u32>) -> Vec<AgentSummary> {
+    let mut agents: BTreeMap<String, AgentSummary> = BTreeMap::new();
+    for commit in data {
+        for (tool_model, stats) in &commit.stats.tool_model_breakdown {
+            let agent = agent_entry(&mut agents, tool_model);
+            agent.ai_additions += stats.ai_additions;
+            agent.ai_accepted += stats.ai_accepted;
+        }
+    }
+    for (tool_model, lines) in surviving_by_agent {
+        agent_entry(&mut agents, tool_model).surviving_lines += lines;
+    }
+
+    let total: u32 = agents.values().map(|agent| agent.ai_additions).sum();
+    let mut agents: Vec<AgentSummary> = agents
+        .into_values()
+        .map(|mut agent| {
+            agent.share_percent = percent(agent.ai_additions, total);
+            agent
+        })
+        .collect();
+    agents.sort_by_key(|agent| std::cmp::Reverse(agent.ai_additions));
+    agents
+}
+
+fn agent_entry<'a>(
+    agents: &'a mut BTreeMap<String, AgentSummary>,
+    tool_model: &str,
+) -> &'a mut AgentSummary {
+    agents.entry(tool_model.to_string()).or_insert_with(|| {
+        let (tool, model) = tool_model
+            .split_once("::")
+            .unwrap_or((tool_model, "unknown"));
+        AgentSummary {
+            tool: tool.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    })
+}
+
+/// The first `depth` directories of `path`, or `.` for files at the root
+fn directory_of(path: &str, depth: usize) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    let directories = &parts[..parts.len() - 1];
+    if directories.is_empty() {
+        return ".".to_string();
+    }
+    directories[..directories.len().min(depth)].join("/")
+}
+
+fn first_user_message(messages: &[Message]) -> String {
+    let Some(text) = messages.iter().find_map(|message| match message {
+        Message::User { text, .. } => Some(text.trim()),
+        _ => None,
+    }) else {
+        return String::new();
+    };
+    if text.chars().count() <= PROMPT_SUMMARY_LENGTH {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(PROMPT_SUMMARY_LENGTH).collect();
+    format!("{}...", truncated)
+}
+
+fn percent(part: u32, total: u32) -> u32 {
+    if total == 0 {
+        return 0;
+    }
+    ((part as f64 / total as f64) * 100.0).round() as u32
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn commit_sha(ca: &CommitAuthorship) -> &str {
+    match ca {
+        CommitAuthorship::NoLog { sha, .. } | CommitAuthorship::Log { sha, .. } => sha,
+    }
+}
+
+fn commit_git_author(ca: &CommitAuthorship) -> &str {
+    match ca {
+        CommitAuthorship::NoLog { git_author, .. } | CommitAuthorship::Log { git_author, .. } => {
+            git_author
+        }
+    }
+}
+
+fn print_report_help() {
+    eprintln!("Usage: git-ai report <start>..<end> [options]");
+    eprintln!();
+    eprintln!("Write a standalone HTML report on AI authorship in a range: AI vs human");
+    eprintln!("additions over time, a per-directory heatmap, the prompts with the most");
+    eprintln!("lines surviving at <end>, and totals per agent. Prints to stdout unless");
+    eprintln!("--output is given.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  -o, --output <file>   Write the report to <file>, or upload it to an");
+    eprintln!("                        s3://bucket/key or gs://bucket/key URL");
+    eprintln!("  --bucket day|week     Trend and heatmap granularity (default: week)");
+    eprintln!("  --tz <zone>           Time zone for buckets (default: report.timezone, UTC)");
+    eprintln!(
+        "  --top <n>             Prompts to list (default: {})",
+        DEFAULT_TOP_PROMPTS
+    );
+    eprintln!(
+        "  --depth <n>           Directory levels in the heatmap (default: {})",
+        DEFAULT_DIRECTORY_DEPTH
+    );
+    eprintln!("  --ignore <pattern>    Leave matching files out (repeatable)");
+    eprintln!("  --exclude-bots        Leave out commits by common bots");
+    eprintln!("  --author <pattern>    Only count matching authors (repeatable)");
+    eprintln!("  --all-authors         Ignore report.authors and report.bot_authors");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::LineRange;
+    use crate::authorship::authorship_log_serialization::{AttestationEntry, FileAttestation};
+
+    #[test]
+    fn test_directory_of_truncates_to_depth() {
+        assert_eq!(directory_of("README.md", 2), ".");
+        assert_eq!(directory_of("src/lib.rs", 2), "src");
+        assert_eq!(directory_of("src/commands/report.rs", 2), "src/commands");
+        assert_eq!(
+            directory_of("src/commands/hooks/commit.rs", 2),
+            "src/commands"
+        );
+        assert_eq!(directory_of("src/commands/hooks/commit.rs", 1), "src");
+    }
+
+    #[test]
+    fn test_attribute_added_lines_counts_only_added_ai_lines() {
+        let mut log = AuthorshipLog::default();
+        let mut attestation = FileAttestation::new("src/a.rs".to_string());
+        attestation.entries.push(AttestationEntry::new(
+            "abc1234".to_string(),
+            vec![LineRange::Range(1, 4), LineRange::Single(10)],
+        ));
+        log.attestations.push(attestation);
+
+        let added = HashMap::from([
+            ("src/a.rs".to_string(), vec![3, 4, 5, 6, 10]),
+            ("src/b.rs".to_string(), vec![1, 2]),
+        ]);
+        let files = attribute_added_lines(added, Some(&log));
+        assert_eq!(files["src/a.rs"], (5, 3));
+        assert_eq!(files["src/b.rs"], (2, 0));
+
+        let added = HashMap::from([("src/a.rs".to_string(), vec![1, 2])]);
+        assert_eq!(attribute_added_lines(added, None)["src/a.rs"], (2, 0));
+    }
+}
@@ -0,0 +1,397 @@
+//! `git-ai run -- <agent command>`
+//!
+//! Wraps any CLI agent we have no installer or hook preset for. The working
+//! tree is snapshotted with a human checkpoint before the agent starts, then
+//! polled while it runs; every batch of edits becomes an AI checkpoint with the
+//! wrapped command recorded as the tool. A final checkpoint is taken when the
+//! agent exits, and `git-ai run` exits with the agent's status.
+
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands;
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use crate::git::repository::Repository;
+use crate::observability;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+/// How often the child is checked for exit between polls
+const WAIT_TICK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunArgs {
+    /// Tool name recorded on checkpoints, derived from the command if not given
+    pub tool: String,
+    pub model: String,
+    pub poll_interval: Duration,
+    pub command: Vec<String>,
+}
+
+/// Parse `[--tool <name>] [--model <name>] [--interval <secs>] -- <command> [args...]`.
+/// The `--` is optional when the command doesn't start with a dash.
+pub fn parse_run_args(args: &[String]) -> Result<RunArgs, String> {
+    let mut tool = None;
+    let mut model = None;
+    let mut poll_interval = Duration::from_millis(DEFAULT_POLL_INTERVAL_MS);
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--" => {
+                i += 1;
+                break;
+            }
+            "--tool" | "--model" | "--interval" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", arg))?;
+                match arg {
+                    "--tool" => tool = Some(value.clone()),
+                    "--model" => model = Some(value.clone()),
+                    _ => {
+                        poll_interval = value
+                            .parse()
+                            .ok()
+                            .filter(|s: &f64| *s > 0.0)
+                            .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                            .ok_or_else(|| format!("invalid --interval: {}", value))?;
+                    }
+                }
+                i += 2;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ => break,
+        }
+    }
+
+    let command: Vec<String> = args[i..].to_vec();
+    let Some(program) = command.first() else {
+        return Err("missing command to run".to_string());
+    };
+    Ok(RunArgs {
+        tool: tool.unwrap_or_else(|| tool_name_for_command(program)),
+        model: model.unwrap_or_else(|| "unknown".to_string()),
+        poll_interval,
+        command,
+    })
+}
+
+/// Tool name for a wrapped program: its file name without directory or extension
+pub fn tool_name_for_command(program: &str) -> String {
+    let name = Path::new(program)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if name.is_empty() {
+        "unknown".to_string()
+    } else {
+        name
+    }
+}
+
+pub fn handle_run(args: &[String]) {
+    let run_args = match parse_run_args(args) {
+        Ok(run_args) => run_args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!(
+                "Usage: git-ai run [--tool <name>] [--model <name>] [--interval <secs>] -- <command> [args...]"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let cwd = std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| ".".to_string());
+    let repo = match find_repository_in_path(&cwd) {
+        Ok(repo) => Some(repo),
+        Err(e) => {
+            eprintln!("[git-ai] Not tracking edits: {}", e);
+            None
+        }
+    };
+    let repo = if Config::get().is_allowed_repository(&repo) {
+        repo
+    } else {
+        None
+    };
+
+    let exit_code = match repo {
+        Some(repo) => run_tracked(&repo, &run_args),
+        None => spawn_command(&run_args.command).and_then(|mut child| {
+            child
+                .wait()
+                .map(|status| exit_code_for(&status))
+                .map_err(GitAiError::from)
+        }),
+    };
+    match exit_code {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("[git-ai] Failed to run {}: {}", run_args.command[0], e);
+            std::process::exit(127);
+        }
+    }
+}
+
+/// Run the command with checkpointing. Returns the child's exit code.
+fn run_tracked(repo: &Repository, run_args: &RunArgs) -> Result<i32, GitAiError> {
+    let author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+    let workdir = repo.workdir()?;
+    let session = RunSession {
+        repo,
+        author: &author,
+        agent_id: AgentId {
+            tool: run_args.tool.clone(),
+            id: format!(
+                "run-{}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0)
+            ),
+            model: run_args.model.clone(),
+        },
+        command: run_args.command.join(" "),
+        workdir: workdir.to_string_lossy().to_string(),
+    };
+
+    commands::git_hook_handlers::ensure_repo_level_hooks_for_checkpoint(repo);
+    // Attribute anything already pending to the human before the agent starts
+    if let Err(e) = commands::checkpoint::run(
+        repo,
+        &author,
+        &[],
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        None,
+        false,
+    ) {
+        eprintln!("[git-ai] Pre-run checkpoint failed: {}", e);
+    }
+    let mut snapshot = snapshot_dirty_files(repo, &workdir).unwrap_or_default();
+
+    let mut child = spawn_command(&run_args.command)?;
+    let _signals = IgnoreInterrupts::install();
+
+    let mut last_poll = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        std::thread::sleep(WAIT_TICK);
+        if last_poll.elapsed() >= run_args.poll_interval {
+            last_poll = Instant::now();
+            session.checkpoint_changes(&mut snapshot);
+        }
+    };
+    session.checkpoint_changes(&mut snapshot);
+    observability::spawn_background_flush();
+
+    Ok(exit_code_for(&status))
+}
+
+struct RunSession<'a> {
+    repo: &'a Repository,
+    author: &'a str,
+    agent_id: AgentId,
+    command: String,
+    workdir: String,
+}
+
+impl RunSession<'_> {
+    /// Emit an AI checkpoint for files that changed since `snapshot`, then update it
+    fn checkpoint_changes(&self, snapshot: &mut HashMap<String, FileFingerprint>) {
+        let Ok(current) = snapshot_dirty_files(self.repo, Path::new(&self.workdir)) else {
+            return;
+        };
+        let changed = changed_files(snapshot, &current);
+        *snapshot = current;
+        if changed.is_empty() {
+            return;
+        }
+
+        let result = AgentRunResult {
+            agent_id: self.agent_id.clone(),
+            agent_metadata: Some(HashMap::from([(
+                "command".to_string(),
+                self.command.clone(),
+            )])),
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: Some(self.workdir.clone()),
+            edited_filepaths: Some(changed),
+            will_edit_filepaths: None,
+            dirty_files: None,
+        };
+        if let Err(e) = commands::checkpoint::run(
+            self.repo,
+            self.author,
+            &[],
+            CheckpointKind::AiAgent,
+            false,
+            false,
+            true,
+            Some(result),
+            false,
+        ) {
+            eprintln!(
+                "[git-ai] Checkpoint for {} failed: {}",
+                self.agent_id.tool, e
+            );
+        }
+    }
+}
+
+/// Size and modification time of a dirty file, `None` once it's deleted
+type FileFingerprint = Option<(u64, SystemTime)>;
+
+fn snapshot_dirty_files(
+    repo: &Repository,
+    workdir: &Path,
+) -> Result<HashMap<String, FileFingerprint>, GitAiError> {
+    Ok(repo
+        .status(None, false)?
+        .into_iter()
+        .map(|entry| {
+            let fingerprint = std::fs::metadata(workdir.join(&entry.path))
+                .ok()
+                .and_then(|m| Some((m.len(), m.modified().ok()?)));
+            (entry.path, fingerprint)
+        })
+        .collect())
+}
+
+/// Files whose fingerprint differs between snapshots, including files that
+/// became clean again (e.g. an edit the agent reverted)
+fn changed_files(
+    before: &HashMap<String, FileFingerprint>,
+    after: &HashMap<String, FileFingerprint>,
+) -> Vec<String> {
+    let paths: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    paths
+        .into_iter()
+        .filter(|path| before.get(*path) != after.get(*path))
+        .cloned()
+        .collect()
+}
+
+fn spawn_command(command: &[String]) -> Result<std::process::Child, GitAiError> {
+    Ok(Command::new(&command[0]).args(&command[1..]).spawn()?)
+}
+
+fn exit_code_for(status: &std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// Ctrl-C goes to the whole foreground process group. While the agent runs we
+/// ignore it so the final checkpoint still happens after the agent exits.
+struct IgnoreInterrupts;
+
+impl IgnoreInterrupts {
+    fn install() -> Self {
+        #[cfg(unix)]
+        unsafe {
+            let _ = libc::signal(libc::SIGINT, libc::SIG_IGN);
+            let _ = libc::signal(libc::SIGQUIT, libc::SIG_IGN);
+        }
+        IgnoreInterrupts
+    }
+}
+
+impl Drop for IgnoreInterrupts {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            let _ = libc::signal(libc::SIGINT, libc::SIG_DFL);
+            let _ = libc::signal(libc::SIGQUIT, libc::SIG_DFL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_run_args() {
+        let parsed = parse_run_args(&args(&[
+            "--model",
+            "gpt-5",
+            "--interval",
+            "0.5",
+            "--",
+            "/usr/local/bin/my-agent",
+            "--yes",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.tool, "my-agent");
+        assert_eq!(parsed.model, "gpt-5");
+        assert_eq!(parsed.poll_interval, Duration::from_millis(500));
+        assert_eq!(parsed.command, args(&["/usr/local/bin/my-agent", "--yes"]));
+
+        let parsed = parse_run_args(&args(&["--tool", "amp", "agent", "-p", "fix"])).unwrap();
+        assert_eq!(parsed.tool, "amp");
+        assert_eq!(parsed.model, "unknown");
+        assert_eq!(parsed.command, args(&["agent", "-p", "fix"]));
+
+        assert!(parse_run_args(&args(&["--"])).is_err());
+        assert!(parse_run_args(&args(&["--interval", "0", "--", "x"])).is_err());
+        assert_eq!(
+            parse_run_args(&args(&["--interval", "1e20", "--", "x"])).unwrap_err(),
+            "invalid --interval: 1e20"
+        );
+        assert!(parse_run_args(&args(&["--interval", "inf", "--", "x"])).is_err());
+        assert!(parse_run_args(&args(&["--bogus", "x"])).is_err());
+    }
+
+    #[test]
+    fn test_tool_name_for_command() {
+        assert_eq!(tool_name_for_command("aider"), "aider");
+        assert_eq!(tool_name_for_command("./bin/agent.sh"), "agent");
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let t = SystemTime::UNIX_EPOCH;
+        let before = HashMap::from([
+            ("a.rs".to_string(), Some((1, t))),
+            ("b.rs".to_string(), Some((1, t))),
+        ]);
+        let after = HashMap::from([
+            ("a.rs".to_string(), Some((1, t))),
+            ("b.rs".to_string(), Some((2, t))),
+            ("c.rs".to_string(), None),
+        ]);
+        assert_eq!(changed_files(&before, &after), args(&["b.rs", "c.rs"]));
+        assert_eq!(
+            changed_files(&after, &HashMap::new()),
+            args(&["a.rs", "b.rs", "c.rs"])
+        );
+    }
+}
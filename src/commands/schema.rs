@@ -0,0 +1,213 @@
+//! `git-ai schema`: print the JSON Schema of a machine-readable format.
+//!
+//! Schemas are generated from the same Rust types that produce (or, for hook
+//! input, parse) the JSON, so they can't drift from what the binary does.
+
+use crate::authorship::authorship_log_serialization::AuthorshipMetadata;
+use crate::authorship::range_authorship::RangeAuthorshipStats;
+use crate::authorship::stats::CommitStats;
+use crate::authorship::tag_attribution::TagAttribution;
+use crate::ci::changed_ai_files::ChangedAiFiles;
+use crate::ci::note_coverage::RangeNoteCoverage;
+use crate::commands::blame::{BlameLineRecord, JsonBlameOutput};
+use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Input;
+use crate::commands::context::ContextOutput;
+use crate::commands::continue_session::ContinueJsonOutput;
+use crate::commands::correlate::CorrelationReport;
+use crate::commands::diff::DiffJson;
+use crate::commands::ownership::{DirectoryOwnership, FileOwnership};
+use crate::commands::search::SearchJsonOutput;
+use crate::commands::status::StatusOutput;
+use crate::commands::verify_push::PushNoteCoverage;
+use schemars::{JsonSchema, Schema};
+
+/// A published schema: its name on the command line and the type it describes
+pub struct SchemaDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    generate: fn() -> Schema,
+}
+
+impl SchemaDefinition {
+    /// The schema, titled with its published name so renaming a Rust type
+    /// doesn't change the output
+    pub fn schema(&self) -> Schema {
+        let mut schema = (self.generate)();
+        schema.insert("title".to_string(), self.name.into());
+        schema.insert("description".to_string(), self.description.into());
+        schema
+    }
+}
+
+fn schema_of<T: JsonSchema>() -> Schema {
+    schemars::schema_for!(T)
+}
+
+pub const SCHEMAS: &[SchemaDefinition] = &[
+    SchemaDefinition {
+        name: "blame-json",
+        description: "Output of `git-ai blame --json`",
+        generate: schema_of::<JsonBlameOutput>,
+    },
+    SchemaDefinition {
+        name: "blame-json-lines",
+        description: "One line of `git-ai blame --json-lines` output",
+        generate: schema_of::<BlameLineRecord>,
+    },
+    SchemaDefinition {
+        name: "blame-directory-json",
+        description: "Output of `git-ai blame <directory> --json`",
+        generate: schema_of::<DirectoryOwnership>,
+    },
+    SchemaDefinition {
+        name: "stats-json",
+        description: "Output of `git-ai stats --json` for a single commit",
+        generate: schema_of::<CommitStats>,
+    },
+    SchemaDefinition {
+        name: "stats-range-json",
+        description: "Output of `git-ai stats --json` for a commit range",
+        generate: schema_of::<RangeAuthorshipStats>,
+    },
+    SchemaDefinition {
+        name: "status-json",
+        description: "Output of `git-ai status --json`",
+        generate: schema_of::<StatusOutput>,
+    },
+    SchemaDefinition {
+        name: "diff-json",
+        description: "Output of `git-ai diff --json`",
+        generate: schema_of::<DiffJson>,
+    },
+    SchemaDefinition {
+        name: "verify-push-json",
+        description: "Output of `git-ai verify-push --json`",
+        generate: schema_of::<PushNoteCoverage>,
+    },
+    SchemaDefinition {
+        name: "require-notes-json",
+        description: "Output of `git-ai ci require-notes`",
+        generate: schema_of::<RangeNoteCoverage>,
+    },
+    SchemaDefinition {
+        name: "changed-ai-files-json",
+        description: "Output of `git-ai ci changed-ai-files --json`",
+        generate: schema_of::<ChangedAiFiles>,
+    },
+    SchemaDefinition {
+        name: "context-json",
+        description: "Output of `git-ai context --json`",
+        generate: schema_of::<ContextOutput>,
+    },
+    SchemaDefinition {
+        name: "search-json",
+        description: "Output of `git-ai search --json`",
+        generate: schema_of::<SearchJsonOutput>,
+    },
+    SchemaDefinition {
+        name: "ownership-json",
+        description: "Output of `git-ai ownership --json`",
+        generate: schema_of::<FileOwnership>,
+    },
+    SchemaDefinition {
+        name: "correlate-json",
+        description: "Output of `git-ai correlate --json`",
+        generate: schema_of::<CorrelationReport>,
+    },
+    SchemaDefinition {
+        name: "continue-json",
+        description: "Output of `git-ai continue --json`",
+        generate: schema_of::<ContinueJsonOutput>,
+    },
+    SchemaDefinition {
+        name: "checkpoint-agent-v1",
+        description: "Hook input read by `git-ai checkpoint agent-v1 --hook-input`",
+        generate: schema_of::<AgentV1Input>,
+    },
+    SchemaDefinition {
+        name: "authorship-note-v3",
+        description: "JSON metadata section of an authorship/3.0.0 note, below the `---` divider",
+        generate: schema_of::<AuthorshipMetadata>,
+    },
+    SchemaDefinition {
+        name: "authorship-note-v4",
+        description: "JSON metadata section of an authorship/4.0.0 note (sub-line ranges), below the `---` divider",
+        generate: schema_of::<AuthorshipMetadata>,
+    },
+    SchemaDefinition {
+        name: "tag-attribution-note",
+        description: "Attribution note on an annotated tag, shown by `git-ai show --tag --json`",
+        generate: schema_of::<TagAttribution>,
+    },
+];
+
+pub fn find_schema(name: &str) -> Option<&'static SchemaDefinition> {
+    SCHEMAS.iter().find(|definition| definition.name == name)
+}
+
+pub fn handle_schema(args: &[String]) {
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            for definition in SCHEMAS {
+                println!("{:<22} {}", definition.name, definition.description);
+            }
+        }
+        Some("-h") | Some("--help") => print_help(),
+        Some(name) => {
+            let Some(definition) = find_schema(name) else {
+                eprintln!("Unknown schema: {}", name);
+                eprintln!("Run `git-ai schema` to list the available schemas");
+                std::process::exit(1);
+            };
+            match serde_json::to_string_pretty(&definition.schema()) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize schema: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+fn print_help() {
+    eprintln!("git-ai schema - Print JSON Schemas for git-ai's machine-readable formats");
+    eprintln!();
+    eprintln!("Usage: git-ai schema [<name>]");
+    eprintln!();
+    eprintln!("Without a name, lists the available schemas.");
+    eprintln!("With a name, prints that schema (JSON Schema draft 2020-12).");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_every_schema_generates_with_its_name_as_title() {
+        let mut names = HashSet::new();
+        for definition in SCHEMAS {
+            assert!(
+                names.insert(definition.name),
+                "duplicate {}",
+                definition.name
+            );
+            let schema = definition.schema();
+            assert_eq!(
+                schema.get("title").and_then(|t| t.as_str()),
+                Some(definition.name)
+            );
+            assert!(schema.get("$schema").is_some());
+        }
+    }
+
+    #[test]
+    fn test_diff_annotations_schema_matches_serialized_ranges() {
+        let schema = serde_json::to_value(find_schema("diff-json").unwrap().schema()).unwrap();
+        let range = &schema["$defs"]["AnnotationRange"];
+        let variants = range["anyOf"].as_array().expect("untagged enum");
+        assert_eq!(variants[0]["type"], "integer");
+        assert_eq!(variants[1]["type"], "array");
+    }
+}
@@ -5,13 +5,18 @@
 
 use crate::authorship::authorship_log::{LineRange, PromptRecord};
 use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::prompt_interning::resolve_interned_messages;
 use crate::authorship::prompt_utils::find_prompt_with_db_fallback;
+use crate::authorship::transcript::Message;
+use crate::authorship::working_log::AgentId;
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
 use crate::git::find_repository_in_path;
 use crate::git::refs::get_authorship;
 use crate::git::repository::{Repository, exec_git};
-use std::collections::HashMap;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::path::Path;
 
@@ -114,25 +119,29 @@ pub fn search_by_commit(repo: &Repository, commit_rev: &str) -> Result<SearchRes
                 // Note: DB records don't have file/line location data
             }
         }
-    } else if let Ok(db) = InternalDatabase::global()
-        && let Ok(db_guard) = db.lock()
-    {
-        // Git notes were found but messages may have been stripped
-        // (e.g., PromptStorageMode::Local or CAS upload). Try to
-        // supplement empty messages from the internal database.
-        let ids_needing_messages: Vec<String> = result
-            .prompts
-            .iter()
-            .filter(|(_, prompt)| prompt.messages.is_empty())
-            .map(|(id, _)| id.clone())
-            .collect();
-
-        for id in ids_needing_messages {
-            if let Ok(Some(db_record)) = db_guard.get_prompt(&id)
-                && !db_record.messages.messages.is_empty()
-                && let Some(prompt) = result.prompts.get_mut(&id)
-            {
-                prompt.messages = db_record.messages.messages;
+    } else {
+        // Git notes were found but messages may have been interned or stripped
+        // (e.g., PromptStorageMode::Local or CAS upload). Try to resolve
+        // interned messages, then supplement from the internal database.
+        resolve_interned_messages(repo, result.prompts.values_mut());
+
+        if let Ok(db) = InternalDatabase::global()
+            && let Ok(db_guard) = db.lock()
+        {
+            let ids_needing_messages: Vec<String> = result
+                .prompts
+                .iter()
+                .filter(|(_, prompt)| prompt.messages.is_empty())
+                .map(|(id, _)| id.clone())
+                .collect();
+
+            for id in ids_needing_messages {
+                if let Ok(Some(db_record)) = db_guard.get_prompt(&id)
+                    && !db_record.messages.messages.is_empty()
+                    && let Some(prompt) = result.prompts.get_mut(&id)
+                {
+                    prompt.messages = db_record.messages.messages;
+                }
             }
         }
     }
@@ -202,6 +211,7 @@ pub fn search_by_file(
     for (hash, prompt) in &blame_prompt_records {
         result.prompts.insert(hash.clone(), prompt.clone());
     }
+    resolve_interned_messages(repo, result.prompts.values_mut());
 
     // Group lines by prompt hash, filtering out human-authored lines
     let mut lines_by_hash: HashMap<String, Vec<u32>> = HashMap::new();
@@ -294,7 +304,8 @@ pub fn search_by_pattern(query: &str) -> Result<SearchResult, GitAiError> {
 ///
 /// Looks up the prompt in the database first, then falls back to searching git notes.
 pub fn search_by_prompt_id(repo: &Repository, prompt_id: &str) -> Result<SearchResult, GitAiError> {
-    let (commit_sha, prompt) = find_prompt_with_db_fallback(prompt_id, Some(repo))?;
+    let (commit_sha, mut prompt) = find_prompt_with_db_fallback(prompt_id, Some(repo))?;
+    resolve_interned_messages(repo, std::iter::once(&mut prompt));
 
     let mut result = SearchResult::new();
 
@@ -599,32 +610,82 @@ fn format_default(result: &SearchResult, mode: &SearchMode) -> String {
     output.trim_end().to_string()
 }
 
+/// `git-ai search --json` output
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct SearchJsonOutput {
+    query: SearchJsonQuery,
+    result_count: usize,
+    /// Matching prompts keyed by prompt hash
+    prompts: BTreeMap<String, SearchJsonPrompt>,
+}
+
+/// The search that produced the results
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum SearchJsonQuery {
+    Commit {
+        commit: String,
+    },
+    CommitRange {
+        start: String,
+        end: String,
+    },
+    File {
+        file: String,
+        line_ranges: Vec<(u32, u32)>,
+    },
+    Pattern {
+        query: String,
+    },
+    PromptId {
+        prompt_id: String,
+    },
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct SearchJsonPrompt {
+    agent_id: AgentId,
+    human_author: Option<String>,
+    messages: Vec<Message>,
+    total_additions: u32,
+    total_deletions: u32,
+    locations: Vec<SearchJsonLocation>,
+    commits: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct SearchJsonLocation {
+    file: String,
+    /// Line ranges as `"12"` or `"[12, 20]"`
+    lines: Vec<String>,
+}
+
 /// Format search results as JSON
 fn format_json(result: &SearchResult, mode: &SearchMode) -> String {
-    use serde_json::json;
-
     let query = match mode {
-        SearchMode::Commit { commit_rev } => {
-            json!({ "mode": "commit", "commit": commit_rev })
-        }
-        SearchMode::CommitRange { start, end } => {
-            json!({ "mode": "commit_range", "start": start, "end": end })
-        }
+        SearchMode::Commit { commit_rev } => SearchJsonQuery::Commit {
+            commit: commit_rev.clone(),
+        },
+        SearchMode::CommitRange { start, end } => SearchJsonQuery::CommitRange {
+            start: start.clone(),
+            end: end.clone(),
+        },
         SearchMode::File {
             file_path,
             line_ranges,
-        } => {
-            json!({ "mode": "file", "file": file_path, "line_ranges": line_ranges })
-        }
-        SearchMode::Pattern { query } => {
-            json!({ "mode": "pattern", "query": query })
-        }
-        SearchMode::PromptId { prompt_id } => {
-            json!({ "mode": "prompt_id", "prompt_id": prompt_id })
-        }
+        } => SearchJsonQuery::File {
+            file: file_path.clone(),
+            line_ranges: line_ranges.clone(),
+        },
+        SearchMode::Pattern { query } => SearchJsonQuery::Pattern {
+            query: query.clone(),
+        },
+        SearchMode::PromptId { prompt_id } => SearchJsonQuery::PromptId {
+            prompt_id: prompt_id.clone(),
+        },
     };
 
-    let prompts: serde_json::Map<String, serde_json::Value> = result
+    let prompts = result
         .prompts
         .iter()
         .map(|(hash, prompt)| {
@@ -633,13 +694,11 @@ fn format_json(result: &SearchResult, mode: &SearchMode) -> String {
                 .get(hash)
                 .map(|locs| {
                     locs.iter()
-                        .map(|(path, ranges)| {
-                            json!({
-                                "file": path,
-                                "lines": ranges.iter().map(|r| format!("{}", r)).collect::<Vec<_>>()
-                            })
+                        .map(|(path, ranges)| SearchJsonLocation {
+                            file: path.clone(),
+                            lines: ranges.iter().map(|r| format!("{}", r)).collect(),
                         })
-                        .collect::<Vec<_>>()
+                        .collect()
                 })
                 .unwrap_or_default();
 
@@ -647,30 +706,29 @@ fn format_json(result: &SearchResult, mode: &SearchMode) -> String {
 
             (
                 hash.clone(),
-                json!({
-                    "agent_id": {
-                        "tool": prompt.agent_id.tool,
-                        "id": prompt.agent_id.id,
-                        "model": prompt.agent_id.model
-                    },
-                    "human_author": prompt.human_author,
-                    "messages": prompt.messages,
-                    "total_additions": prompt.total_additions,
-                    "total_deletions": prompt.total_deletions,
-                    "locations": locations,
-                    "commits": commits
-                }),
+                SearchJsonPrompt {
+                    agent_id: prompt.agent_id.clone(),
+                    human_author: prompt.human_author.clone(),
+                    messages: prompt.messages.clone(),
+                    total_additions: prompt.total_additions,
+                    total_deletions: prompt.total_deletions,
+                    locations,
+                    commits,
+                },
             )
         })
         .collect();
 
-    let output = json!({
-        "query": query,
-        "result_count": result.len(),
-        "prompts": prompts
-    });
+    let output = SearchJsonOutput {
+        query,
+        result_count: result.len(),
+        prompts,
+    };
 
-    serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+    // Going through `Value` keeps object keys sorted, as they always have been
+    serde_json::to_value(&output)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| "{}".to_string())
 }
 
 /// Format search results with full transcripts
@@ -1440,6 +1498,8 @@ mod tests {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         }
     }
 
@@ -0,0 +1,214 @@
+//! `git-ai sessions show <id>`: one agent session across every repository it
+//! edited, with the commits it produced in each.
+//!
+//! Agents working across split repositories report the same session id in each
+//! of them, so the local prompt database links checkpoints by that id (see
+//! [`InternalDatabase::session_repo_links`]). `<id>` is either a prompt id or the
+//! agent's own session id.
+
+use crate::authorship::internal_db::{InternalDatabase, SessionRepoLink};
+use crate::error::GitAiError;
+use crate::git::repository::exec_git;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+struct SessionView {
+    prompt_id: String,
+    session_id: String,
+    tool: String,
+    model: Option<String>,
+    /// First user message, truncated
+    summary: Option<String>,
+    repositories: Vec<SessionRepository>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionRepository {
+    workdir: String,
+    commits: Vec<SessionCommit>,
+    /// The session has checkpoints here that aren't committed yet
+    uncommitted: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionCommit {
+    sha: String,
+    /// `None` when the repository or commit is gone
+    subject: Option<String>,
+}
+
+pub fn handle_sessions(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("show") => handle_sessions_show(&args[1..]),
+        Some("-h") | Some("--help") | Some("help") => {
+            print_sessions_help();
+            std::process::exit(0);
+        }
+        Some(other) => {
+            eprintln!("Unknown sessions subcommand: {}", other);
+            print_sessions_help();
+            std::process::exit(1);
+        }
+        None => {
+            print_sessions_help();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_sessions_show(args: &[String]) {
+    let mut id = None;
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option for sessions show: {}", other);
+                std::process::exit(1);
+            }
+            other if id.is_none() => id = Some(other.to_string()),
+            other => {
+                eprintln!("Unexpected argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+    }
+    let Some(id) = id else {
+        eprintln!("Error: sessions show requires a prompt id or agent session id");
+        print_sessions_help();
+        std::process::exit(1);
+    };
+
+    let view = match load_session(&id) {
+        Ok(Some(view)) => view,
+        Ok(None) => {
+            eprintln!("No session found for '{}'", id);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&view) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize session: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", view.render());
+    }
+}
+
+fn load_session(id: &str) -> Result<Option<SessionView>, GitAiError> {
+    let db = InternalDatabase::global()?;
+    let db = db
+        .lock()
+        .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?;
+
+    let links = db.session_repo_links(id)?;
+    let Some(first) = links.first() else {
+        return Ok(None);
+    };
+    let prompt = db.get_prompt(&first.prompt_id)?;
+
+    Ok(Some(SessionView {
+        prompt_id: first.prompt_id.clone(),
+        session_id: first.external_thread_id.clone(),
+        tool: first.tool.clone(),
+        model: prompt.as_ref().map(|p| p.model.clone()),
+        summary: prompt
+            .as_ref()
+            .map(|p| p.first_message_snippet(80))
+            .filter(|s| !s.is_empty()),
+        repositories: group_by_repository(&links),
+    }))
+}
+
+/// Repositories in the order the session first touched them
+fn group_by_repository(links: &[SessionRepoLink]) -> Vec<SessionRepository> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut by_workdir: BTreeMap<&str, SessionRepository> = BTreeMap::new();
+    for link in links {
+        let repo = by_workdir.entry(&link.workdir).or_insert_with(|| {
+            order.push(&link.workdir);
+            SessionRepository {
+                workdir: link.workdir.clone(),
+                commits: Vec::new(),
+                uncommitted: false,
+            }
+        });
+        match &link.commit_sha {
+            Some(sha) => repo.commits.push(SessionCommit {
+                sha: sha.clone(),
+                subject: commit_subject(&link.workdir, sha),
+            }),
+            None => repo.uncommitted = true,
+        }
+    }
+    order
+        .into_iter()
+        .filter_map(|workdir| by_workdir.remove(workdir))
+        .collect()
+}
+
+fn commit_subject(workdir: &str, sha: &str) -> Option<String> {
+    let args: Vec<String> = ["-C", workdir, "show", "-s", "--format=%s", sha]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    let output = exec_git(&args).ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl SessionView {
+    fn render(&self) -> String {
+        let commits: usize = self.repositories.iter().map(|r| r.commits.len()).sum();
+        let mut out = format!("Session {} ({}", self.prompt_id, self.tool);
+        if let Some(model) = &self.model {
+            out.push_str(&format!(", {}", model));
+        }
+        out.push_str(")\n");
+        if let Some(summary) = &self.summary {
+            out.push_str(&format!("  \"{}\"\n", summary));
+        }
+        out.push_str(&format!(
+            "  {} repositor{}, {} commit{}\n",
+            self.repositories.len(),
+            if self.repositories.len() == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+            commits,
+            if commits == 1 { "" } else { "s" }
+        ));
+
+        for repo in &self.repositories {
+            out.push_str(&format!("\n{}\n", repo.workdir));
+            for commit in &repo.commits {
+                let short = &commit.sha[..commit.sha.len().min(7)];
+                match &commit.subject {
+                    Some(subject) => out.push_str(&format!("  {}  {}\n", short, subject)),
+                    None => out.push_str(&format!("  {}  (not found)\n", short)),
+                }
+            }
+            if repo.uncommitted {
+                out.push_str("  (uncommitted checkpoints)\n");
+            }
+        }
+        out
+    }
+}
+
+fn print_sessions_help() {
+    eprintln!("Usage: git-ai sessions show <id> [--json]");
+    eprintln!();
+    eprintln!("Show an agent session across every repository it edited, with the");
+    eprintln!("commits it produced in each. <id> is a prompt id or the agent's session id.");
+}
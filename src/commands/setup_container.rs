@@ -0,0 +1,204 @@
+//! `git-ai setup-container`
+//!
+//! Non-interactive setup for devcontainer features and Codespaces prebuilds.
+//! Containers have no system keyring and no browser for `git-ai login`, and
+//! their images are rebuilt rather than updated, so this:
+//!
+//! - pins config to container-friendly defaults (file credentials, no auto-updates)
+//! - reports how the container will authenticate (`GIT_AI_API_KEY` or stored credentials)
+//! - installs hooks into whichever agents are preinstalled in the image
+//!
+//! Exit contract, safe to run on every container create:
+//!
+//! - `0` the container is set up, whether or not this run changed anything
+//! - `1` setup failed (config not writable, hook installation error)
+//! - `2` invalid usage
+//! - `3` with `--check`, changes would be made
+//!
+//! The last line on stdout is `git-ai setup-container: changed` or
+//! `git-ai setup-container: unchanged` when setup succeeds.
+
+use crate::auth::CredentialStore;
+use crate::commands::install_hooks::{self, InstallStatus};
+use crate::config::{self, Config, FileConfig};
+use serde_json::Value;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_CHANGES_NEEDED: i32 = 3;
+
+/// How the container will authenticate to the git-ai API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    /// `GIT_AI_API_KEY` or `api_key` in config
+    ApiKey,
+    /// Credentials from a previous `git-ai login`
+    Stored,
+    None,
+}
+
+impl AuthMode {
+    pub fn detect() -> Self {
+        if Config::get().api_key().is_some() {
+            AuthMode::ApiKey
+        } else if CredentialStore::new().has_credentials() {
+            AuthMode::Stored
+        } else {
+            AuthMode::None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMode::ApiKey => "api-key",
+            AuthMode::Stored => "stored",
+            AuthMode::None => "none",
+        }
+    }
+}
+
+/// Apply container defaults to `file_config`. Returns the keys that changed.
+pub fn apply_container_defaults(file_config: &mut FileConfig) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+
+    // Containers have no keyring daemon; credentials must live in a file
+    let mut flags = match file_config.feature_flags.take() {
+        Some(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    if flags.get("auth_keyring") != Some(&Value::Bool(false)) {
+        flags.insert("auth_keyring".to_string(), Value::Bool(false));
+        changed.push("feature_flags.auth_keyring");
+    }
+    file_config.feature_flags = Some(Value::Object(flags));
+
+    // Images are rebuilt to pick up new versions
+    if file_config.disable_auto_updates != Some(true) {
+        file_config.disable_auto_updates = Some(true);
+        changed.push("disable_auto_updates");
+    }
+
+    changed
+}
+
+pub fn handle_setup_container(args: &[String]) {
+    let mut check = false;
+    let mut skip_hooks = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--skip-hooks" => skip_hooks = true,
+            _ => {
+                eprintln!("Unknown argument: {}", arg);
+                eprintln!("Usage: git-ai setup-container [--check] [--skip-hooks]");
+                std::process::exit(EXIT_USAGE);
+            }
+        }
+    }
+    std::process::exit(run(check, skip_hooks));
+}
+
+fn run(check: bool, skip_hooks: bool) -> i32 {
+    let mut any_changes = false;
+
+    let mut file_config = match config::load_file_config_public() {
+        Ok(file_config) => file_config,
+        Err(e) => {
+            eprintln!("[git-ai] Failed to read config: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    let changed_keys = apply_container_defaults(&mut file_config);
+    if !changed_keys.is_empty() {
+        any_changes = true;
+        if check {
+            eprintln!("config: would set {}", changed_keys.join(", "));
+        } else if let Err(e) = config::save_file_config(&file_config) {
+            eprintln!("[git-ai] Failed to write config: {}", e);
+            return EXIT_FAILED;
+        } else {
+            eprintln!("config: set {}", changed_keys.join(", "));
+        }
+    } else {
+        eprintln!("config: up to date");
+    }
+
+    let auth = AuthMode::detect();
+    match auth {
+        AuthMode::None => eprintln!(
+            "auth: none (set GIT_AI_API_KEY in the container environment to enable uploads)"
+        ),
+        _ => eprintln!("auth: {}", auth.as_str()),
+    }
+
+    if skip_hooks {
+        eprintln!("hooks: skipped");
+    } else {
+        let hook_args = if check {
+            vec!["--dry-run".to_string()]
+        } else {
+            Vec::new()
+        };
+        match install_hooks::run(&hook_args) {
+            Ok(statuses) => {
+                if statuses
+                    .values()
+                    .any(|s| s == InstallStatus::Failed.as_str())
+                {
+                    eprintln!("[git-ai] Hook installation failed for some agents");
+                    return EXIT_FAILED;
+                }
+                if statuses
+                    .values()
+                    .any(|s| s == InstallStatus::Installed.as_str())
+                {
+                    any_changes = true;
+                }
+            }
+            Err(e) => {
+                eprintln!("[git-ai] Hook installation failed: {}", e);
+                return EXIT_FAILED;
+            }
+        }
+    }
+
+    if check {
+        return if any_changes {
+            EXIT_CHANGES_NEEDED
+        } else {
+            EXIT_OK
+        };
+    }
+    println!(
+        "git-ai setup-container: {}",
+        if any_changes { "changed" } else { "unchanged" }
+    );
+    EXIT_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_container_defaults_is_idempotent() {
+        let mut file_config = FileConfig {
+            feature_flags: Some(serde_json::json!({"auth_keyring": true, "rewrite_stash": true})),
+            ..Default::default()
+        };
+
+        let changed = apply_container_defaults(&mut file_config);
+        assert_eq!(
+            changed,
+            vec!["feature_flags.auth_keyring", "disable_auto_updates"]
+        );
+        assert_eq!(
+            file_config.feature_flags,
+            Some(serde_json::json!({"auth_keyring": false, "rewrite_stash": true}))
+        );
+        assert_eq!(file_config.disable_auto_updates, Some(true));
+
+        assert!(apply_container_defaults(&mut file_config).is_empty());
+    }
+}
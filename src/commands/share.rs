@@ -1,3 +1,4 @@
+use crate::api::sink::dual_write_bundle;
 use crate::api::{ApiClient, ApiContext, ApiFileRecord};
 use crate::api::{BundleData, CreateBundleRequest};
 use crate::authorship::prompt_utils::find_prompt_with_db_fallback;
@@ -201,5 +202,7 @@ pub fn create_bundle(
 
     let context = ApiContext::new(None);
     let client = ApiClient::new(context);
-    client.create_bundle(bundle_request)
+    let response = client.create_bundle_resumable(bundle_request.clone())?;
+    dual_write_bundle(&bundle_request, &response);
+    Ok(response)
 }
@@ -1,3 +1,4 @@
+use crate::authorship::tag_attribution::{TagAttribution, read_tag_attribution};
 use crate::error::GitAiError;
 use crate::git::find_repository;
 use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
@@ -11,7 +12,9 @@ pub fn handle_show(args: &[String]) {
         std::process::exit(1);
     }
 
-    if args.len() > 1 {
+    let tag = args.iter().position(|arg| arg == "--tag");
+    let json = args.iter().any(|arg| arg == "--json");
+    if tag.is_none() && args.len() > 1 {
         eprintln!("Error: show accepts exactly one revision or range");
         std::process::exit(1);
     }
@@ -24,6 +27,18 @@ pub fn handle_show(args: &[String]) {
         }
     };
 
+    if let Some(index) = tag {
+        let Some(tag) = args.get(index + 1) else {
+            eprintln!("Error: --tag requires a tag name");
+            std::process::exit(1);
+        };
+        if let Err(e) = show_tag_attribution(&repo, tag, json) {
+            eprintln!("Failed to show tag attribution: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     if let Err(e) = show_authorship(&repo, &args[0]) {
         eprintln!("Failed to show authorship: {}", e);
         std::process::exit(1);
@@ -71,6 +86,49 @@ fn show_authorship(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
     Ok(())
 }
 
+fn show_tag_attribution(repo: &Repository, tag: &str, json: bool) -> Result<(), GitAiError> {
+    let Some(attribution) = read_tag_attribution(repo, tag)? else {
+        println!("No attribution recorded for tag {}", tag);
+        return Ok(());
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&attribution)?);
+    } else {
+        print!("{}", format_tag_attribution(&attribution));
+    }
+    Ok(())
+}
+
+fn format_tag_attribution(attribution: &TagAttribution) -> String {
+    let since = match &attribution.previous_tag {
+        Some(previous) => format!("since {}", previous),
+        None => "all history".to_string(),
+    };
+    let mut out = format!(
+        "Tag {} ({}), {}\n",
+        attribution.tag,
+        &attribution.commit[..attribution.commit.len().min(7)],
+        since
+    );
+    out.push_str(&format!(
+        "Commits:  {} ({} with authorship logs)\n",
+        attribution.commits, attribution.commits_with_authorship
+    ));
+    out.push_str(&format!(
+        "AI share: {}% ({} AI, {} human lines added)\n",
+        attribution.ai_percent, attribution.ai_additions, attribution.human_additions
+    ));
+    if !attribution.tools.is_empty() {
+        let tools: Vec<String> = attribution
+            .tools
+            .iter()
+            .map(|(tool, lines)| format!("{} ({})", tool, lines))
+            .collect();
+        out.push_str(&format!("Tools:    {}\n", tools.join(", ")));
+    }
+    out
+}
+
 fn resolve_commits(repo: &Repository, spec: &str) -> Result<Vec<String>, GitAiError> {
     if let Some((start, end)) = spec.split_once("..") {
         if start.is_empty() || end.is_empty() {
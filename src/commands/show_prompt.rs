@@ -1,8 +1,11 @@
 use crate::api::client::{ApiClient, ApiContext};
 use crate::api::types::CasMessagesObject;
+use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::prompt_interning::resolve_interned_messages;
 use crate::authorship::prompt_utils::find_prompt;
 use crate::git::find_repository;
+use crate::git::repository::Repository;
 use crate::utils::debug_log;
 
 /// Handle the `show-prompt` command
@@ -35,83 +38,7 @@ pub fn handle_show_prompt(args: &[String]) {
         parsed.offset,
     ) {
         Ok((commit_sha, mut prompt_record)) => {
-            // If messages are empty, resolve from the best available source.
-            // Priority: CAS cache → CAS API (if messages_url) → local SQLite
-            if prompt_record.messages.is_empty() {
-                if let Some(url) = &prompt_record.messages_url
-                    && let Some(hash) = url.rsplit('/').next().filter(|h| !h.is_empty())
-                {
-                    // 1. Check cas_cache (instant, local)
-                    if let Ok(db_mutex) = InternalDatabase::global()
-                        && let Ok(db_guard) = db_mutex.lock()
-                        && let Ok(Some(cached_json)) = db_guard.get_cas_cache(hash)
-                        && let Ok(cas_obj) = serde_json::from_str::<CasMessagesObject>(&cached_json)
-                    {
-                        prompt_record.messages = cas_obj.messages;
-                        debug_log("show-prompt: resolved from cas_cache");
-                    }
-
-                    // 2. If cache miss, fetch from CAS API (network)
-                    if prompt_record.messages.is_empty() {
-                        let context = ApiContext::new(None);
-                        if context.auth_token.is_some() {
-                            debug_log(&format!(
-                                "show-prompt: trying CAS API for hash {}",
-                                &hash[..8.min(hash.len())]
-                            ));
-                            let client = ApiClient::new(context);
-                            match client.read_ca_prompt_store(&[hash]) {
-                                Ok(response) => {
-                                    for result in &response.results {
-                                        if result.status == "ok"
-                                            && let Some(content) = &result.content
-                                        {
-                                            let json_str =
-                                                serde_json::to_string(content).unwrap_or_default();
-                                            if let Ok(cas_obj) =
-                                                serde_json::from_value::<CasMessagesObject>(
-                                                    content.clone(),
-                                                )
-                                            {
-                                                prompt_record.messages = cas_obj.messages;
-                                                debug_log(&format!(
-                                                    "show-prompt: resolved {} messages from CAS API",
-                                                    prompt_record.messages.len()
-                                                ));
-                                                // Cache for next time
-                                                if let Ok(db_mutex) = InternalDatabase::global()
-                                                    && let Ok(mut db_guard) = db_mutex.lock()
-                                                {
-                                                    let _ = db_guard.set_cas_cache(hash, &json_str);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    debug_log(&format!("show-prompt: CAS API error: {}", e));
-                                }
-                            }
-                        } else {
-                            debug_log("show-prompt: no auth token, skipping CAS API");
-                        }
-                    }
-                }
-
-                // 3. Last resort: local SQLite (for prompts without a CAS URL)
-                if prompt_record.messages.is_empty()
-                    && let Ok(db_mutex) = InternalDatabase::global()
-                    && let Ok(db_guard) = db_mutex.lock()
-                    && let Ok(Some(db_record)) = db_guard.get_prompt(&parsed.prompt_id)
-                    && !db_record.messages.messages.is_empty()
-                {
-                    prompt_record.messages = db_record.messages.messages;
-                    debug_log(&format!(
-                        "show-prompt: resolved {} messages from local SQLite",
-                        prompt_record.messages.len()
-                    ));
-                }
-            }
+            resolve_prompt_messages(&repo, &parsed.prompt_id, &mut prompt_record);
 
             // Output the prompt as JSON, including the commit SHA for context
             let output = serde_json::json!({
@@ -131,6 +58,90 @@ pub fn handle_show_prompt(args: &[String]) {
     }
 }
 
+/// Fill in `prompt_record.messages` when the note doesn't carry them inline.
+///
+/// Priority: interned messages → CAS cache → CAS API (if messages_url) → local SQLite
+pub fn resolve_prompt_messages(
+    repo: &Repository,
+    prompt_id: &str,
+    prompt_record: &mut PromptRecord,
+) {
+    resolve_interned_messages(repo, std::iter::once(&mut *prompt_record));
+    if prompt_record.messages.is_empty() {
+        if let Some(url) = &prompt_record.messages_url
+            && let Some(hash) = url.rsplit('/').next().filter(|h| !h.is_empty())
+        {
+            // 1. Check cas_cache (instant, local)
+            if let Ok(db_mutex) = InternalDatabase::global()
+                && let Ok(db_guard) = db_mutex.lock()
+                && let Ok(Some(cached_json)) = db_guard.get_cas_cache(hash)
+                && let Ok(cas_obj) = serde_json::from_str::<CasMessagesObject>(&cached_json)
+            {
+                prompt_record.messages = cas_obj.messages;
+                debug_log("show-prompt: resolved from cas_cache");
+            }
+
+            // 2. If cache miss, fetch from CAS API (network)
+            if prompt_record.messages.is_empty() {
+                let context = ApiContext::new(None);
+                if context.auth_token.is_some() {
+                    debug_log(&format!(
+                        "show-prompt: trying CAS API for hash {}",
+                        &hash[..8.min(hash.len())]
+                    ));
+                    let client = ApiClient::new(context);
+                    match client.read_ca_prompt_store(&[hash]) {
+                        Ok(response) => {
+                            for result in &response.results {
+                                if result.status == "ok"
+                                    && let Some(content) = &result.content
+                                {
+                                    let json_str =
+                                        serde_json::to_string(content).unwrap_or_default();
+                                    if let Ok(cas_obj) =
+                                        serde_json::from_value::<CasMessagesObject>(content.clone())
+                                    {
+                                        prompt_record.messages = cas_obj.messages;
+                                        debug_log(&format!(
+                                            "show-prompt: resolved {} messages from CAS API",
+                                            prompt_record.messages.len()
+                                        ));
+                                        // Cache for next time
+                                        if let Ok(db_mutex) = InternalDatabase::global()
+                                            && let Ok(mut db_guard) = db_mutex.lock()
+                                        {
+                                            let _ = db_guard.set_cas_cache(hash, &json_str);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            debug_log(&format!("show-prompt: CAS API error: {}", e));
+                        }
+                    }
+                } else {
+                    debug_log("show-prompt: no auth token, skipping CAS API");
+                }
+            }
+        }
+
+        // 3. Last resort: local SQLite (for prompts without a CAS URL)
+        if prompt_record.messages.is_empty()
+            && let Ok(db_mutex) = InternalDatabase::global()
+            && let Ok(db_guard) = db_mutex.lock()
+            && let Ok(Some(db_record)) = db_guard.get_prompt(prompt_id)
+            && !db_record.messages.messages.is_empty()
+        {
+            prompt_record.messages = db_record.messages.messages;
+            debug_log(&format!(
+                "show-prompt: resolved {} messages from local SQLite",
+                prompt_record.messages.len()
+            ));
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ParsedArgs {
     pub prompt_id: String,
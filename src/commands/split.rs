@@ -0,0 +1,720 @@
+//! `git-ai split HEAD --by-author`: rewrite HEAD as one commit per attribution
+//! origin, the human changes first and then each AI session's, for teams whose
+//! policy wants AI changes in commits of their own. Every new commit gets a note
+//! attesting only its own session's lines, and the final tree is HEAD's, so the
+//! working log just moves over to the new HEAD.
+//!
+//! Deleted lines go with the lines that replaced them, or with the human commit
+//! when nothing did. Binary files, symlinks and deleted files go to the human commit.
+
+use crate::authorship::authorship_log::{CharRange, LineRange};
+use crate::authorship::authorship_log_serialization::{
+    AUTHORSHIP_LOG_VERSION, AUTHORSHIP_LOG_VERSION_V4, AttestationEntry, AuthorshipLog,
+    FileAttestation,
+};
+use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, notes_add};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin_with_env};
+use crate::utils::is_interactive_terminal;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+/// Who a split commit belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Origin {
+    Human,
+    /// An AI session, by prompt id
+    Prompt(String),
+}
+
+/// A file's mode and content at some commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileState {
+    mode: String,
+    content: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+enum SplitLineKind {
+    Equal,
+    Deleted,
+    /// Inserted at this line of HEAD's version of the file
+    Inserted(u32),
+}
+
+#[derive(Debug, Clone)]
+struct SplitLine {
+    text: String,
+    kind: SplitLineKind,
+    /// Index into the origins of the commit that applies the change
+    owner: usize,
+}
+
+#[derive(Debug, Clone)]
+enum FileChange {
+    /// The whole change lands in one commit
+    Whole { owner: usize },
+    /// Line by line, each with its own owner
+    Lines(Vec<SplitLine>),
+}
+
+#[derive(Debug, Clone)]
+struct FileSplit {
+    path: String,
+    old: Option<FileState>,
+    new: Option<FileState>,
+    change: FileChange,
+}
+
+/// A file as of one split commit, with the lines that commit inserted as
+/// (line in this version, line in HEAD)
+struct FileVersion {
+    state: Option<FileState>,
+    own_lines: Vec<(u32, u32)>,
+}
+
+impl FileSplit {
+    fn first_owner(&self) -> usize {
+        match &self.change {
+            FileChange::Whole { owner } => *owner,
+            FileChange::Lines(lines) => lines
+                .iter()
+                .filter(|line| !matches!(line.kind, SplitLineKind::Equal))
+                .map(|line| line.owner)
+                .min()
+                .unwrap_or(0),
+        }
+    }
+
+    /// The file once origins `0..=k` have been applied
+    fn version(&self, k: usize) -> FileVersion {
+        if k < self.first_owner() {
+            return FileVersion {
+                state: self.old.clone(),
+                own_lines: Vec::new(),
+            };
+        }
+        let lines = match &self.change {
+            FileChange::Whole { .. } => {
+                return FileVersion {
+                    state: self.new.clone(),
+                    own_lines: Vec::new(),
+                };
+            }
+            FileChange::Lines(lines) => lines,
+        };
+
+        let mut content = String::new();
+        let mut own_lines = Vec::new();
+        let mut line_no = 0;
+        for line in lines {
+            let keep = match line.kind {
+                SplitLineKind::Equal => true,
+                SplitLineKind::Deleted => line.owner > k,
+                SplitLineKind::Inserted(_) => line.owner <= k,
+            };
+            if !keep {
+                continue;
+            }
+            content.push_str(&line.text);
+            line_no += 1;
+            if let SplitLineKind::Inserted(head_line) = line.kind
+                && line.owner == k
+            {
+                own_lines.push((line_no, head_line));
+            }
+        }
+        let mode = self
+            .new
+            .as_ref()
+            .or(self.old.as_ref())
+            .map(|state| state.mode.clone())
+            .unwrap_or_else(|| "100644".to_string());
+        FileVersion {
+            state: Some(FileState {
+                mode,
+                content: content.into_bytes(),
+            }),
+            own_lines,
+        }
+    }
+}
+
+/// HEAD's message, reused for every split commit
+struct CommitMessage {
+    subject: String,
+    body: String,
+}
+
+/// How HEAD would be split, before anything is written
+struct SplitPlan {
+    origins: Vec<Origin>,
+    files: Vec<FileSplit>,
+    log: AuthorshipLog,
+}
+
+pub fn handle_split(args: &[String]) {
+    let (dry_run, yes) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai split [HEAD] --by-author [--dry-run] [--yes]");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let head = match repo.head().and_then(|head| head.target()) {
+        Ok(head) => head,
+        Err(e) => {
+            eprintln!("Error: could not resolve HEAD: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let plan = match plan_split(&repo, &head) {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let steps = plan.steps();
+    if steps.len() < 2 {
+        println!("HEAD has changes from a single origin; nothing to split");
+        return;
+    }
+    println!("HEAD would be split into {} commits:", steps.len());
+    for &k in &steps {
+        println!("  {}", plan.label(k));
+    }
+    if dry_run {
+        return;
+    }
+    if is_interactive_terminal() && !yes && !confirm("Split HEAD?") {
+        println!("Aborted");
+        return;
+    }
+
+    match apply_split(&repo, &head, &plan) {
+        Ok(new_commits) => {
+            println!("Rewrote {} as:", &head[..head.len().min(7)]);
+            for (sha, subject) in new_commits {
+                println!("  {} {}", &sha[..sha.len().min(7)], subject);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `--dry-run` and `--yes`
+fn parse_args(args: &[String]) -> Result<(bool, bool), String> {
+    let mut by_author = false;
+    let mut dry_run = false;
+    let mut yes = false;
+    for arg in args {
+        match arg.as_str() {
+            "HEAD" => {}
+            "--by-author" => by_author = true,
+            "--dry-run" => dry_run = true,
+            "--yes" | "-y" => yes = true,
+            other if !other.starts_with('-') => {
+                return Err(format!("only HEAD can be split, got '{}'", other));
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    if !by_author {
+        return Err("missing --by-author (the only split mode)".to_string());
+    }
+    Ok((dry_run, yes))
+}
+
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+impl SplitPlan {
+    /// Origins that change something, in commit order
+    fn steps(&self) -> Vec<usize> {
+        (0..self.origins.len())
+            .filter(|&k| {
+                self.files.iter().any(|file| {
+                    let before = if k == 0 {
+                        file.old.clone()
+                    } else {
+                        file.version(k - 1).state
+                    };
+                    file.version(k).state != before
+                })
+            })
+            .collect()
+    }
+
+    fn label(&self, k: usize) -> String {
+        match &self.origins[k] {
+            Origin::Human => "human".to_string(),
+            Origin::Prompt(prompt_id) => match self.log.metadata.prompts.get(prompt_id) {
+                Some(record) => format!("{} {}", record.agent_id.tool, prompt_id),
+                None => format!("ai {}", prompt_id),
+            },
+        }
+    }
+
+    /// The note for the commit that applies origin `k`
+    fn authorship_for(&self, k: usize, versions: &[(String, FileVersion)]) -> AuthorshipLog {
+        let mut log = AuthorshipLog::new();
+        log.metadata.git_ai_version = self.log.metadata.git_ai_version.clone();
+        log.metadata.humans = self.log.metadata.humans.clone();
+        log.metadata.environment = self.log.metadata.environment.clone();
+
+        let mut has_char_ranges = false;
+        for (path, version) in versions {
+            if version.own_lines.is_empty() {
+                continue;
+            }
+            let Some(original) = self.log.attestations.iter().find(|f| f.file_path == *path) else {
+                continue;
+            };
+            let head_to_version: HashMap<u32, u32> = version
+                .own_lines
+                .iter()
+                .map(|(line, head_line)| (*head_line, *line))
+                .collect();
+
+            let mut file = FileAttestation::new(path.clone());
+            for entry in &original.entries {
+                let mut lines = Vec::new();
+                if self.origins[k] == Origin::Prompt(entry.hash.clone()) {
+                    for range in &entry.line_ranges {
+                        lines.extend(
+                            range
+                                .expand()
+                                .iter()
+                                .filter_map(|head_line| head_to_version.get(head_line)),
+                        );
+                    }
+                }
+                lines.sort_unstable();
+                lines.dedup();
+                let char_ranges: Vec<CharRange> = entry
+                    .char_ranges
+                    .iter()
+                    .filter_map(|range| {
+                        head_to_version
+                            .get(&range.line)
+                            .map(|line| CharRange::new(*line, range.start, range.end))
+                    })
+                    .collect();
+                if lines.is_empty() && char_ranges.is_empty() {
+                    continue;
+                }
+                has_char_ranges |= !char_ranges.is_empty();
+                if let Some(record) = self.log.metadata.prompts.get(&entry.hash) {
+                    log.metadata
+                        .prompts
+                        .insert(entry.hash.clone(), record.clone());
+                }
+                let mut split_entry =
+                    AttestationEntry::new(entry.hash.clone(), LineRange::compress_lines(&lines));
+                split_entry.char_ranges = char_ranges;
+                file.add_entry(split_entry);
+            }
+            if !file.entries.is_empty() {
+                log.attestations.push(file);
+            }
+        }
+        log.metadata.schema_version = if has_char_ranges {
+            AUTHORSHIP_LOG_VERSION_V4
+        } else {
+            AUTHORSHIP_LOG_VERSION
+        }
+        .to_string();
+        log
+    }
+}
+
+/// Work out each changed file's owners from HEAD's note
+fn plan_split(repo: &Repository, head: &str) -> Result<SplitPlan, GitAiError> {
+    let commit = repo.find_commit(head.to_string())?;
+    let parent = match commit.parent_count()? {
+        0 => EMPTY_TREE_HASH.to_string(),
+        1 => commit.parent(0)?.id(),
+        _ => {
+            return Err(GitAiError::Generic(
+                "merge commits can't be split".to_string(),
+            ));
+        }
+    };
+    let log = get_authorship(repo, head).ok_or_else(|| {
+        GitAiError::Generic("HEAD has no authorship note to split by".to_string())
+    })?;
+
+    // HEAD line -> prompt id, per file; later entries win, as in blame
+    let mut line_owners: HashMap<&str, HashMap<u32, &str>> = HashMap::new();
+    for file in &log.attestations {
+        let owners = line_owners.entry(file.file_path.as_str()).or_default();
+        for entry in &file.entries {
+            for range in &entry.line_ranges {
+                for line in range.expand() {
+                    owners.insert(line, entry.hash.as_str());
+                }
+            }
+        }
+    }
+
+    let mut origins = vec![Origin::Human];
+    let mut origin_index = |prompt_id: &str| -> usize {
+        let origin = Origin::Prompt(prompt_id.to_string());
+        match origins.iter().position(|o| *o == origin) {
+            Some(index) => index,
+            None => {
+                origins.push(origin);
+                origins.len() - 1
+            }
+        }
+    };
+
+    let mut files = Vec::new();
+    for (path, old_mode, new_mode) in changed_files(repo, &parent, head)? {
+        let old = match old_mode {
+            Some(mode) => Some(FileState {
+                mode,
+                content: repo.get_file_content(&path, &parent)?,
+            }),
+            None => None,
+        };
+        let new = match new_mode {
+            Some(mode) => Some(FileState {
+                mode,
+                content: repo.get_file_content(&path, head)?,
+            }),
+            None => None,
+        };
+
+        let texts = match (&old, &new) {
+            (_, Some(new_state)) if is_splittable(old.as_ref(), new_state) => Some((
+                old.as_ref()
+                    .map(|state| String::from_utf8_lossy(&state.content).into_owned())
+                    .unwrap_or_default(),
+                String::from_utf8_lossy(&new_state.content).into_owned(),
+            )),
+            _ => None,
+        };
+        let Some((old_text, new_text)) = texts else {
+            files.push(FileSplit {
+                path,
+                old,
+                new,
+                change: FileChange::Whole { owner: 0 },
+            });
+            continue;
+        };
+
+        let owners = line_owners.get(path.as_str());
+        let mut lines = Vec::new();
+        let mut pending_deletes = Vec::new();
+        let mut new_line = 0;
+        for change in compute_line_changes(&old_text, &new_text) {
+            let text = change.value().to_string();
+            match change.tag() {
+                LineChangeTag::Equal => {
+                    new_line += 1;
+                    // Deletions nothing replaced belong to the human commit
+                    lines.append(&mut pending_deletes);
+                    lines.push(SplitLine {
+                        text,
+                        kind: SplitLineKind::Equal,
+                        owner: 0,
+                    });
+                }
+                LineChangeTag::Delete => pending_deletes.push(SplitLine {
+                    text,
+                    kind: SplitLineKind::Deleted,
+                    owner: 0,
+                }),
+                LineChangeTag::Insert => {
+                    new_line += 1;
+                    let owner = owners
+                        .and_then(|owners| owners.get(&new_line))
+                        .map(|prompt_id| origin_index(prompt_id))
+                        .unwrap_or(0);
+                    // Replaced lines go with whoever replaced them
+                    for mut deleted in pending_deletes.drain(..) {
+                        deleted.owner = owner;
+                        lines.push(deleted);
+                    }
+                    lines.push(SplitLine {
+                        text,
+                        kind: SplitLineKind::Inserted(new_line),
+                        owner,
+                    });
+                }
+            }
+        }
+        lines.append(&mut pending_deletes);
+        files.push(FileSplit {
+            path,
+            old,
+            new,
+            change: FileChange::Lines(lines),
+        });
+    }
+
+    Ok(SplitPlan {
+        origins,
+        files,
+        log,
+    })
+}
+
+/// Regular text files can be split line by line; anything else moves whole
+fn is_splittable(old: Option<&FileState>, new: &FileState) -> bool {
+    let is_text = |state: &FileState| {
+        state.mode.starts_with("100")
+            && !state.content.contains(&0)
+            && std::str::from_utf8(&state.content).is_ok()
+    };
+    is_text(new) && old.is_none_or(is_text) && !new.content.is_empty()
+}
+
+/// A file that differs between two commits, with its mode on each side (`None`
+/// where it doesn't exist)
+type ChangedFile = (String, Option<String>, Option<String>);
+
+/// Every file that differs between `from` and `to`
+fn changed_files(repo: &Repository, from: &str, to: &str) -> Result<Vec<ChangedFile>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["diff-tree", "-r", "-z", "--no-renames", from, to].map(String::from));
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Raw format: ":<old mode> <new mode> <old oid> <new oid> <status>\0<path>\0"
+    let mut files = Vec::new();
+    let mut fields = stdout.split('\0');
+    while let (Some(meta), Some(path)) = (fields.next(), fields.next()) {
+        let mut meta = meta.trim_start_matches(':').split(' ');
+        let mode = |mode: Option<&str>| {
+            mode.filter(|mode| *mode != "000000")
+                .map(|mode| mode.to_string())
+        };
+        let old_mode = mode(meta.next());
+        let new_mode = mode(meta.next());
+        files.push((path.to_string(), old_mode, new_mode));
+    }
+    Ok(files)
+}
+
+/// Write the split commits and their notes, move HEAD and the working log over,
+/// and return the new commits with their subjects
+fn apply_split(
+    repo: &Repository,
+    head: &str,
+    plan: &SplitPlan,
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let commit = repo.find_commit(head.to_string())?;
+    let parent = match commit.parent_count()? {
+        0 => None,
+        _ => Some(commit.parent(0)?.id()),
+    };
+    let message = CommitMessage {
+        subject: commit.summary()?,
+        body: commit.body()?,
+    };
+
+    let author_env = commit.author_env()?;
+
+    let index_path = repo
+        .path()
+        .join(format!("git-ai-split-{}.index", std::process::id()));
+    let result = write_split_commits(
+        repo,
+        plan,
+        parent.as_deref(),
+        &message,
+        &author_env,
+        &index_path,
+    );
+    let _ = std::fs::remove_file(&index_path);
+    let new_commits = result?;
+    let Some((new_head, _)) = new_commits.last() else {
+        return Ok(new_commits);
+    };
+
+    let mut update_ref = repo.global_args_for_exec();
+    update_ref.extend(
+        [
+            "update-ref",
+            "-m",
+            "git-ai split --by-author",
+            "HEAD",
+            new_head,
+            head,
+        ]
+        .map(String::from),
+    );
+    exec_git(&update_ref)?;
+
+    // The last commit's tree is HEAD's, so uncommitted work carries over as is
+    repo.storage.rename_working_log(head, new_head)?;
+    Ok(new_commits)
+}
+
+/// One commit (and note) per step of `plan` on top of `parent` (none for a root
+/// commit), building each tree in a scratch index so the real index and
+/// worktree are left alone
+fn write_split_commits(
+    repo: &Repository,
+    plan: &SplitPlan,
+    parent: Option<&str>,
+    message: &CommitMessage,
+    author_env: &[(String, String)],
+    index_path: &Path,
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let index_env = vec![(
+        "GIT_INDEX_FILE".to_string(),
+        index_path.to_string_lossy().to_string(),
+    )];
+    let mut read_tree = repo.global_args_for_exec();
+    read_tree.push("read-tree".to_string());
+    read_tree.push(parent.unwrap_or("--empty").to_string());
+    exec_git_stdin_with_env(&read_tree, &index_env, b"")?;
+
+    let mut parent = parent.map(str::to_string);
+    let mut new_commits = Vec::new();
+    let mut written: HashMap<&str, Option<FileState>> = HashMap::new();
+    for k in plan.steps() {
+        let versions: Vec<(String, FileVersion)> = plan
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.version(k)))
+            .collect();
+
+        for (file, (path, version)) in plan.files.iter().zip(&versions) {
+            let before = written.get(path.as_str()).unwrap_or(&file.old);
+            if *before == version.state {
+                continue;
+            }
+            let mut update = repo.global_args_for_exec();
+            update.push("update-index".to_string());
+            match &version.state {
+                Some(state) => {
+                    let blob = repo.blob(&state.content)?;
+                    update.push("--add".to_string());
+                    update.push("--cacheinfo".to_string());
+                    update.push(format!("{},{},{}", state.mode, blob, path));
+                }
+                None => {
+                    update.push("--force-remove".to_string());
+                    update.push(path.clone());
+                }
+            }
+            exec_git_stdin_with_env(&update, &index_env, b"")?;
+            written.insert(file.path.as_str(), version.state.clone());
+        }
+
+        let mut write_tree = repo.global_args_for_exec();
+        write_tree.push("write-tree".to_string());
+        let output = exec_git_stdin_with_env(&write_tree, &index_env, b"")?;
+        let tree = String::from_utf8(output.stdout)?.trim().to_string();
+
+        let subject = format!("{} ({})", message.subject, plan.label(k));
+        let full_message = if message.body.is_empty() {
+            subject.clone()
+        } else {
+            format!("{}\n\n{}", subject, message.body)
+        };
+        let mut commit_tree = repo.global_args_for_exec();
+        commit_tree.extend(["commit-tree".to_string(), tree]);
+        if let Some(parent) = parent {
+            commit_tree.extend(["-p".to_string(), parent]);
+        }
+        let output = exec_git_stdin_with_env(&commit_tree, author_env, full_message.as_bytes())?;
+        let sha = String::from_utf8(output.stdout)?.trim().to_string();
+
+        let mut log = plan.authorship_for(k, &versions);
+        log.metadata.base_commit_sha = sha.clone();
+        let note = log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        notes_add(repo, &sha, &note)?;
+
+        new_commits.push((sha.clone(), subject));
+        parent = Some(sha);
+    }
+    Ok(new_commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str, kind: SplitLineKind, owner: usize) -> SplitLine {
+        SplitLine {
+            text: text.to_string(),
+            kind,
+            owner,
+        }
+    }
+
+    #[test]
+    fn test_file_versions_apply_owners_in_order() {
+        let file = FileSplit {
+            path: "lib.rs".to_string(),
+            old: Some(FileState {
+                mode: "100644".to_string(),
+                content: b"keep\nold\n".to_vec(),
+            }),
+            new: None,
+            change: FileChange::Lines(vec![
+                line("human\n", SplitLineKind::Inserted(1), 0),
+                line("keep\n", SplitLineKind::Equal, 0),
+                line("old\n", SplitLineKind::Deleted, 1),
+                line("ai\n", SplitLineKind::Inserted(3), 1),
+            ]),
+        };
+
+        let human = file.version(0);
+        assert_eq!(human.state.unwrap().content, b"human\nkeep\nold\n");
+        assert_eq!(human.own_lines, vec![(1, 1)]);
+
+        let ai = file.version(1);
+        assert_eq!(ai.state.unwrap().content, b"human\nkeep\nai\n");
+        assert_eq!(ai.own_lines, vec![(3, 3)]);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_args(&args(&["HEAD", "--by-author"])),
+            Ok((false, false))
+        );
+        assert_eq!(
+            parse_args(&args(&["--by-author", "--dry-run", "-y"])),
+            Ok((true, true))
+        );
+        assert!(parse_args(&args(&["HEAD"])).is_err());
+        assert!(parse_args(&args(&["HEAD~1", "--by-author"])).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! `git-ai squash <range>`: squash the commits in `<range>` (ending at HEAD) into
+//! one and write its note in the same step, as the union of the constituent
+//! notes' prompts with their attestations carried forward to the squashed file
+//! contents. Local squashes don't need the CI reconciliation path this way.
+
+use crate::authorship::note_composition::compose_authorship_for_commits;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::notes_add;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin_with_env};
+
+struct SquashArgs {
+    range: String,
+    message: Option<String>,
+}
+
+pub fn handle_squash(args: &[String]) {
+    let parsed = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai squash <base>..HEAD | <base> [-m <message>]");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match squash(&repo, &parsed.range, parsed.message.as_deref()) {
+        Ok((sha, count)) => {
+            println!(
+                "Squashed {} commits into {}",
+                count,
+                &sha[..sha.len().min(7)]
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<SquashArgs, String> {
+    let mut range = None;
+    let mut message = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-m" | "--message" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("missing value for {}", args[i]))?;
+                message = Some(value.clone());
+                i += 2;
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("unexpected argument: {}", other));
+            }
+            other => {
+                if range.is_some() {
+                    return Err(format!("unexpected argument: {}", other));
+                }
+                range = Some(other.to_string());
+                i += 1;
+            }
+        }
+    }
+
+    Ok(SquashArgs {
+        range: range.ok_or("missing range")?,
+        message,
+    })
+}
+
+/// Squash `range` into one commit on top of its base, move HEAD there and
+/// note it. Returns the new commit and how many commits went into it.
+pub fn squash(
+    repo: &Repository,
+    range: &str,
+    message: Option<&str>,
+) -> Result<(String, usize), GitAiError> {
+    let head = repo.head()?.target()?;
+    let (base, tip) = match range.split_once("..") {
+        Some((base, tip)) => (base, if tip.is_empty() { "HEAD" } else { tip }),
+        None => (range, "HEAD"),
+    };
+    let base = repo.revparse_single(base)?.peel_to_commit()?.id();
+    let tip = repo.revparse_single(tip)?.peel_to_commit()?.id();
+    if tip != head {
+        return Err(GitAiError::Generic(
+            "the range must end at HEAD".to_string(),
+        ));
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "rev-list",
+            "--reverse",
+            "--parents",
+            &format!("{}..{}", base, tip),
+        ]
+        .map(String::from),
+    );
+    let output = exec_git(&args)?;
+    let mut commits = Vec::new();
+    let mut expected_parent = base.clone();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut shas = line.split_whitespace();
+        let Some(sha) = shas.next() else {
+            continue;
+        };
+        let parents: Vec<&str> = shas.collect();
+        if parents != [expected_parent.as_str()] {
+            return Err(GitAiError::Generic(format!(
+                "{} is not a linear descendant of {}; only linear history can be squashed",
+                &tip[..tip.len().min(7)],
+                &base[..base.len().min(7)]
+            )));
+        }
+        commits.push(sha.to_string());
+        expected_parent = sha.to_string();
+    }
+    if commits.len() < 2 {
+        return Err(GitAiError::Generic(format!(
+            "{} contains {} commit(s); nothing to squash",
+            range,
+            commits.len()
+        )));
+    }
+
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => squash_message(repo, &commits)?,
+    };
+
+    // Author of the first commit, like an interactive rebase squash
+    let author_env = repo.find_commit(commits[0].clone())?.author_env()?;
+
+    let mut commit_tree = repo.global_args_for_exec();
+    commit_tree
+        .extend(["commit-tree", &format!("{}^{{tree}}", tip), "-p", &base].map(String::from));
+    let output = exec_git_stdin_with_env(&commit_tree, &author_env, message.as_bytes())?;
+    let squashed = String::from_utf8(output.stdout)?.trim().to_string();
+
+    if let Some(log) = compose_authorship_for_commits(repo, Some(&base), &commits, &squashed)? {
+        let note = log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        notes_add(repo, &squashed, &note)?;
+    }
+
+    let mut update_ref = repo.global_args_for_exec();
+    update_ref.extend(
+        [
+            "update-ref",
+            "-m",
+            &format!("git-ai squash {}", range),
+            "HEAD",
+            &squashed,
+            &head,
+        ]
+        .map(String::from),
+    );
+    exec_git(&update_ref)?;
+
+    // Same tree as the old HEAD, so uncommitted work carries over as is
+    repo.storage.rename_working_log(&head, &squashed)?;
+    Ok((squashed, commits.len()))
+}
+
+/// Every constituent commit's message, oldest first, like `git rebase -i` offers
+fn squash_message(repo: &Repository, commits: &[String]) -> Result<String, GitAiError> {
+    let mut messages = Vec::new();
+    for sha in commits {
+        let mut args = repo.global_args_for_exec();
+        args.extend(["show", "-s", "--no-notes", "--format=%B", sha].map(String::from));
+        let output = exec_git(&args)?;
+        messages.push(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+    Ok(messages.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        let parsed = parse_args(&args(&["HEAD~3..HEAD", "-m", "One change"])).unwrap();
+        assert_eq!(parsed.range, "HEAD~3..HEAD");
+        assert_eq!(parsed.message.as_deref(), Some("One change"));
+
+        let parsed = parse_args(&args(&["main"])).unwrap();
+        assert_eq!(parsed.range, "main");
+        assert!(parsed.message.is_none());
+
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["a", "b"])).is_err());
+        assert!(parse_args(&args(&["main", "-m"])).is_err());
+    }
+}
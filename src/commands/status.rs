@@ -10,12 +10,14 @@ use crate::git::find_repository;
 use crate::git::repo_storage::InitialAttributions;
 use crate::git::repository::Repository;
 use crate::git::status::MAX_PATHSPEC_ARGS;
+use crate::output;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Serialize)]
-struct CheckpointInfo {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CheckpointInfo {
     time_ago: String,
     additions: u32,
     deletions: u32,
@@ -23,8 +25,8 @@ struct CheckpointInfo {
     is_human: bool,
 }
 
-#[derive(Serialize)]
-struct StatusOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct StatusOutput {
     stats: CommitStats,
     checkpoints: Vec<CheckpointInfo>,
 }
@@ -59,6 +61,7 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
     let _ = checkpoint::run(
         &repo,
         &default_user_name,
+        &[],
         CheckpointKind::Human,
         false,
         false,
@@ -170,6 +173,7 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
 
     write_stats_to_terminal(&stats, true);
 
+    let mode = output::mode();
     println!();
     for cp in &checkpoint_infos {
         let add_str = if cp.additions > 0 {
@@ -188,8 +192,13 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
             cp.time_ago, add_str, del_str, cp.tool_model
         );
 
-        if cp.is_human {
-            println!("\x1b[90m{}\x1b[0m", line);
+        if mode.plain {
+            println!(
+                "checkpoint: {}, {} added, {} deleted, {}",
+                cp.time_ago, cp.additions, cp.deletions, cp.tool_model
+            );
+        } else if cp.is_human {
+            println!("{}", mode.paint("90", &line));
         } else {
             println!("{}", line);
         }
@@ -516,6 +525,8 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                messages_ref: None,
+                classification: None,
             },
         );
 
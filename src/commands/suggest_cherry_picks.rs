@@ -0,0 +1,294 @@
+//! `git-ai suggest-cherry-picks --from <branch>`: the commits on another branch
+//! that aren't in HEAD and were mostly written by a given agent or session, e.g.
+//! to port only the AI-generated fix. Shares come from each commit's authorship
+//! note, counted over the lines the commit adds.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::range_authorship::EMPTY_TREE_HASH;
+use crate::authorship::stats::line_range_overlap_len;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, note_blob_oids_for_commits};
+use crate::git::repository::{CommitRange, Repository};
+
+/// Share of added lines a commit needs from the matching sessions unless
+/// `--min-share` says otherwise
+const DEFAULT_MIN_SHARE: u32 = 50;
+
+/// Which sessions count towards a commit's share. Empty matches every AI session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionFilter {
+    /// Agent tool, e.g. "claude" or "cursor"
+    pub agent: Option<String>,
+    /// Prompt id or the agent's own session id; a prefix is enough
+    pub session: Option<String>,
+}
+
+impl SessionFilter {
+    fn matches(&self, prompt_id: &str, record: &PromptRecord) -> bool {
+        let agent_matches = self
+            .agent
+            .as_ref()
+            .is_none_or(|agent| record.agent_id.tool.eq_ignore_ascii_case(agent));
+        let session_matches = self.session.as_ref().is_none_or(|session| {
+            prompt_id.starts_with(session.as_str())
+                || record.agent_id.id.starts_with(session.as_str())
+        });
+        agent_matches && session_matches
+    }
+
+    fn describe(&self) -> String {
+        match (&self.agent, &self.session) {
+            (Some(agent), Some(session)) => format!("{} session {}", agent, session),
+            (Some(agent), None) => agent.clone(),
+            (None, Some(session)) => format!("session {}", session),
+            (None, None) => "AI".to_string(),
+        }
+    }
+}
+
+/// A commit worth cherry-picking
+#[derive(Debug, Clone, PartialEq)]
+pub struct CherryPickSuggestion {
+    pub sha: String,
+    pub subject: String,
+    /// Added lines the matching sessions wrote
+    pub matched_lines: u32,
+    pub added_lines: u32,
+}
+
+impl CherryPickSuggestion {
+    pub fn share(&self) -> u32 {
+        if self.added_lines == 0 {
+            return 0;
+        }
+        ((self.matched_lines as f64 / self.added_lines as f64) * 100.0).round() as u32
+    }
+}
+
+struct SuggestArgs {
+    from: String,
+    filter: SessionFilter,
+    min_share: u32,
+}
+
+pub fn handle_suggest_cherry_picks(args: &[String]) {
+    let parsed = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!(
+                "Usage: git-ai suggest-cherry-picks --from <branch> [--agent <tool>] [--session <id>] [--min-share <pct>]"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let suggestions =
+        match suggest_cherry_picks(&repo, &parsed.from, &parsed.filter, parsed.min_share) {
+            Ok(suggestions) => suggestions,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+    let who = parsed.filter.describe();
+    if suggestions.is_empty() {
+        println!(
+            "No commits on {} outside HEAD are at least {}% {}",
+            parsed.from, parsed.min_share, who
+        );
+        return;
+    }
+    println!(
+        "Commits on {} outside HEAD that are at least {}% {}:",
+        parsed.from, parsed.min_share, who
+    );
+    for suggestion in &suggestions {
+        println!(
+            "  {} {:>3}% ({}/{} lines) {}",
+            &suggestion.sha[..suggestion.sha.len().min(7)],
+            suggestion.share(),
+            suggestion.matched_lines,
+            suggestion.added_lines,
+            suggestion.subject
+        );
+    }
+    let shas: Vec<&str> = suggestions.iter().map(|s| s.sha.as_str()).collect();
+    println!();
+    println!("To port them:");
+    println!("  git cherry-pick {}", shas.join(" "));
+}
+
+fn parse_args(args: &[String]) -> Result<SuggestArgs, String> {
+    let mut from = None;
+    let mut filter = SessionFilter::default();
+    let mut min_share = DEFAULT_MIN_SHARE;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag @ ("--from" | "--agent" | "--session" | "--min-share") => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("missing value for {}", flag))?
+                    .clone();
+                match flag {
+                    "--from" => from = Some(value),
+                    "--agent" => filter.agent = Some(value),
+                    "--session" => filter.session = Some(value),
+                    _ => {
+                        min_share = value
+                            .trim()
+                            .trim_end_matches('%')
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|pct| *pct <= 100)
+                            .ok_or_else(|| {
+                                format!("invalid --min-share '{}': expected 0-100", value)
+                            })?;
+                    }
+                }
+                i += 2;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(SuggestArgs {
+        from: from.ok_or("missing --from")?,
+        filter,
+        min_share,
+    })
+}
+
+/// Non-merge commits on `from` that HEAD doesn't have, oldest first, where the
+/// sessions `filter` matches wrote at least `min_share` percent of the added lines
+pub fn suggest_cherry_picks(
+    repo: &Repository,
+    from: &str,
+    filter: &SessionFilter,
+    min_share: u32,
+) -> Result<Vec<CherryPickSuggestion>, GitAiError> {
+    let range = CommitRange::new(repo, "HEAD".to_string(), from.to_string(), from.to_string())?;
+    // The range iterator treats equal ends as a one-commit range
+    if range.start_oid == range.end_oid {
+        return Ok(Vec::new());
+    }
+    let mut shas = range.all_commits();
+    shas.reverse();
+
+    // A commit without a note has no AI lines to match
+    let with_notes = note_blob_oids_for_commits(repo, &shas)?;
+
+    let mut suggestions = Vec::new();
+    for sha in shas.iter().filter(|sha| with_notes.contains_key(*sha)) {
+        let commit = repo.find_commit(sha.clone())?;
+        let parent = match commit.parent_count()? {
+            0 => EMPTY_TREE_HASH.to_string(),
+            1 => commit.parent(0)?.id(),
+            _ => continue,
+        };
+        let Some(log) = get_authorship(repo, sha) else {
+            continue;
+        };
+
+        let mut added_lines_by_file = repo.diff_added_lines(&parent, sha, None)?;
+        for lines in added_lines_by_file.values_mut() {
+            lines.sort_unstable();
+            lines.dedup();
+        }
+        let added_lines: u32 = added_lines_by_file.values().map(|l| l.len() as u32).sum();
+
+        let mut matched_lines = 0;
+        for file in &log.attestations {
+            let Some(added) = added_lines_by_file.get(&file.file_path) else {
+                continue;
+            };
+            for entry in &file.entries {
+                let matches = log
+                    .metadata
+                    .prompts
+                    .get(&entry.hash)
+                    .is_some_and(|record| filter.matches(&entry.hash, record));
+                if matches {
+                    matched_lines += entry
+                        .line_ranges
+                        .iter()
+                        .map(|range| line_range_overlap_len(range, added))
+                        .sum::<u32>();
+                }
+            }
+        }
+
+        let suggestion = CherryPickSuggestion {
+            sha: sha.clone(),
+            subject: commit.summary()?,
+            matched_lines,
+            added_lines,
+        };
+        if matched_lines > 0 && suggestion.share() >= min_share {
+            suggestions.push(suggestion);
+        }
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::AgentId;
+
+    fn record(tool: &str, id: &str) -> PromptRecord {
+        PromptRecord {
+            agent_id: AgentId {
+                tool: tool.to_string(),
+                id: id.to_string(),
+                model: "model".to_string(),
+            },
+            human_author: None,
+            messages: Vec::new(),
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            messages_url: None,
+            messages_ref: None,
+            classification: None,
+        }
+    }
+
+    #[test]
+    fn test_session_filter_matches() {
+        let claude = record("claude", "session-abc");
+        assert!(SessionFilter::default().matches("1234567", &claude));
+
+        let by_agent = SessionFilter {
+            agent: Some("Claude".to_string()),
+            session: None,
+        };
+        assert!(by_agent.matches("1234567", &claude));
+        assert!(!by_agent.matches("1234567", &record("cursor", "session-abc")));
+
+        let by_session = SessionFilter {
+            agent: None,
+            session: Some("session-a".to_string()),
+        };
+        assert!(by_session.matches("1234567", &claude));
+        let by_prompt = SessionFilter {
+            agent: None,
+            session: Some("1234".to_string()),
+        };
+        assert!(by_prompt.matches("1234567", &claude));
+        assert!(!by_prompt.matches("7654321", &claude));
+    }
+}
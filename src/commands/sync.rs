@@ -0,0 +1,68 @@
+//! `git-ai sync --retry-pending`: resend everything that was queued because the
+//! API or telemetry endpoints were unreachable.
+
+use crate::commands::flush_cas::handle_flush_cas;
+use crate::commands::flush_metrics_db::upload_queued_metrics;
+use crate::config::Config;
+use crate::metrics::db::MetricsDatabase;
+use crate::observability::flush::{TelemetryClients, retry_pending_envelopes};
+
+pub fn handle_sync(args: &[String]) {
+    let mut retry_pending = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--retry-pending" => retry_pending = true,
+            "-h" | "--help" => {
+                print_help();
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown option: {}", other);
+                print_help();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if !retry_pending {
+        print_help();
+        std::process::exit(1);
+    }
+
+    // Queued envelopes are resent regardless of their backoff schedule: asking
+    // for a retry means the network is expected to be back
+    let clients = TelemetryClients::from_config(Config::get());
+    let envelopes = retry_pending_envelopes(&clients, u64::MAX);
+    println!("Telemetry events: {}", envelopes);
+
+    let metrics_remaining = || {
+        MetricsDatabase::global()
+            .ok()
+            .and_then(|db| db.lock().ok()?.count().ok())
+            .unwrap_or(0)
+    };
+    match upload_queued_metrics() {
+        Some(uploaded) => println!(
+            "Metrics: {} uploaded, {} still queued",
+            uploaded,
+            metrics_remaining()
+        ),
+        None => println!(
+            "Metrics: not uploaded (log in with `git-ai login`), {} queued",
+            metrics_remaining()
+        ),
+    }
+
+    // Prompt uploads keep their own queue and backoff in the internal database
+    handle_flush_cas(&[]);
+}
+
+fn print_help() {
+    eprintln!("git-ai sync - Resend data that was queued while offline");
+    eprintln!();
+    eprintln!("Usage: git-ai sync --retry-pending");
+    eprintln!();
+    eprintln!("Resends telemetry events that `flush-logs` couldn't deliver, uploads queued");
+    eprintln!("metrics, and syncs queued prompt uploads.");
+}
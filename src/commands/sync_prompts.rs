@@ -2,6 +2,7 @@ use crate::authorship::internal_db::{InternalDatabase, PromptDbRecord};
 use crate::authorship::prompt_utils::{PromptUpdateResult, update_prompt_from_tool};
 use crate::error::GitAiError;
 use crate::observability::log_error;
+use crate::output::{self, Status};
 use chrono::{DateTime, NaiveDate};
 use std::cmp::min;
 use std::collections::HashMap;
@@ -130,7 +131,8 @@ fn sync_prompts(since_timestamp: Option<i64>, workdir: Option<&str>) -> Result<(
         match update_prompt_record(&record) {
             Ok(Some(updated_record)) => {
                 eprintln!(
-                    "  ✓ Updated {} ({}/{})",
+                    "  {} Updated {} ({}/{})",
+                    output::mode().status_symbol(Status::Success),
                     &record.id[..8],
                     record.tool,
                     &record.external_thread_id[..min(16, record.external_thread_id.len())]
@@ -142,7 +144,13 @@ fn sync_prompts(since_timestamp: Option<i64>, workdir: Option<&str>) -> Result<(
                 skip_count += 1;
             }
             Err(e) => {
-                eprintln!("  ✗ Failed {} ({}): {}", &record.id[..8], record.tool, e);
+                eprintln!(
+                    "  {} Failed {} ({}): {}",
+                    output::mode().status_symbol(Status::Error),
+                    &record.id[..8],
+                    record.tool,
+                    e
+                );
                 log_error(
                     &e,
                     Some(serde_json::json!({
@@ -166,8 +174,11 @@ fn sync_prompts(since_timestamp: Option<i64>, workdir: Option<&str>) -> Result<(
     }
 
     eprintln!(
-        "\n✓ Sync complete: {} updated, {} skipped, {} failed",
-        success_count, skip_count, error_count
+        "\n{} Sync complete: {} updated, {} skipped, {} failed",
+        output::mode().status_symbol(Status::Success),
+        success_count,
+        skip_count,
+        error_count
     );
 
     Ok(())
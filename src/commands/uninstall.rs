@@ -0,0 +1,123 @@
+//! `git-ai uninstall`: undo an install in one step. Removes the hooks of every
+//! coding agent and git client through their installers, the `git`/`git-og` shims
+//! and `libexec` link, a global `core.hooksPath` pointing into git-ai, and the
+//! internal state under `~/.git-ai/internal` unless `--keep-data` is given.
+
+use crate::commands::git_hook_handlers::restore_global_hooks_path;
+use crate::commands::install_hooks;
+use crate::config::internal_dir_path;
+use crate::mdm::remove_git_symlinks;
+use crate::output::{self, Status};
+
+pub fn handle_uninstall(args: &[String]) {
+    let mut dry_run = false;
+    let mut keep_data = false;
+    let mut hook_args = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" | "--dry-run=true" => {
+                dry_run = true;
+                hook_args.push(arg.clone());
+            }
+            "--dry-run=false" => {}
+            "--keep-data" => keep_data = true,
+            "-v" | "--verbose" => hook_args.push(arg.clone()),
+            "-h" | "--help" => {
+                print_uninstall_help();
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown option: {}", other);
+                print_uninstall_help();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut failed = false;
+
+    if let Err(e) = install_hooks::run_uninstall(&hook_args) {
+        eprintln!("Failed to remove hooks: {}", e);
+        failed = true;
+    }
+
+    println!("\n{}", output::mode().paint("1", "Git Configuration"));
+    match restore_global_hooks_path(dry_run) {
+        Ok(Some(hooks_path)) => println!(
+            "  {} global core.hooksPath ({})",
+            if dry_run { "Would unset" } else { "Unset" },
+            hooks_path
+        ),
+        Ok(None) => println!("  No global git config entries to restore"),
+        Err(e) => {
+            eprintln!("  Failed to restore global git config: {}", e);
+            failed = true;
+        }
+    }
+
+    println!("\n{}", output::mode().paint("1", "Files"));
+    match remove_git_symlinks(dry_run) {
+        Ok(removed) if removed.is_empty() => println!("  No git symlinks to remove"),
+        Ok(removed) => {
+            for path in removed {
+                println!(
+                    "  {} {}",
+                    if dry_run { "Would remove" } else { "Removed" },
+                    path.display()
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("  Failed to remove git symlinks: {}", e);
+            failed = true;
+        }
+    }
+
+    match internal_dir_path() {
+        Some(_) if keep_data => println!("  Kept internal state (--keep-data)"),
+        Some(internal_dir) if internal_dir.exists() => {
+            if dry_run {
+                println!("  Would remove {}", internal_dir.display());
+            } else if let Err(e) = std::fs::remove_dir_all(&internal_dir) {
+                eprintln!("  Failed to remove {}: {}", internal_dir.display(), e);
+                failed = true;
+            } else {
+                println!("  Removed {}", internal_dir.display());
+            }
+        }
+        _ => println!("  No internal state to remove"),
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    println!();
+    if dry_run {
+        println!(
+            "{}",
+            output::mode().status(Status::Warning, "Dry run. No changes were made.")
+        );
+    } else {
+        println!(
+            "{}",
+            output::mode().status(Status::Success, "git-ai has been uninstalled.")
+        );
+        println!(
+            "Remove the git-ai bin directory from PATH in your shell profile, then delete the git-ai binary."
+        );
+    }
+}
+
+fn print_uninstall_help() {
+    eprintln!("Usage: git-ai uninstall [--dry-run] [--keep-data] [--verbose]");
+    eprintln!();
+    eprintln!("Removes git-ai from this machine: agent and git client hooks, the git shims,");
+    eprintln!("global git config pointing at git-ai, and internal state.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --dry-run     Show what would be removed without changing anything");
+    eprintln!("  --keep-data   Keep ~/.git-ai/internal (databases, logs, credentials)");
+    eprintln!("  -v, --verbose Show the diff of each settings file changed");
+}
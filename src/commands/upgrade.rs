@@ -1,6 +1,7 @@
 use crate::api::client::ApiContext;
 use crate::config::{self, UpdateChannel};
 use crate::observability::log_message;
+use crate::output::{self, Status};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -421,7 +422,11 @@ fn run_install_script(script_content: &str, tag: &str, silent: bool) -> Result<(
             Ok(_) => {
                 if !silent {
                     println!(
-                        "\x1b[1;33mNote: The installation is running in the background on Windows.\x1b[0m"
+                        "{}",
+                        output::mode().paint(
+                            "1;33",
+                            "Note: The installation is running in the background on Windows."
+                        )
                     );
                     println!(
                         "This allows the current git-ai process to exit and release file locks."
@@ -559,7 +564,10 @@ fn run_impl_with_url(
             println!("You are already on the latest version!");
             println!();
             println!("To reinstall anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!(
+                "  {}",
+                output::mode().paint("1;36", "git-ai upgrade --force")
+            );
             return action;
         }
         UpgradeAction::RunningNewerVersion => {
@@ -567,17 +575,26 @@ fn run_impl_with_url(
             println!("(This usually means you're running a development build)");
             println!();
             println!("To reinstall the selected release anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!(
+                "  {}",
+                output::mode().paint("1;36", "git-ai upgrade --force")
+            );
             return action;
         }
         UpgradeAction::ForceReinstall => {
             println!(
-                "\x1b[1;33mForce mode enabled - reinstalling {}\x1b[0m",
-                release.tag
+                "{}",
+                output::mode().paint(
+                    "1;33",
+                    &format!("Force mode enabled - reinstalling {}", release.tag)
+                )
             );
         }
         UpgradeAction::UpgradeAvailable => {
-            println!("\x1b[1;33mA new version is available!\x1b[0m");
+            println!(
+                "{}",
+                output::mode().paint("1;33", "A new version is available!")
+            );
         }
     }
     println!();
@@ -592,7 +609,10 @@ fn run_impl_with_url(
     let checksums =
         match fetch_and_verify_checksums(api_base_url, channel.as_str(), &release.checksum) {
             Ok(checksums) => {
-                println!("\x1b[1;32m✓\x1b[0m SHA256SUMS verified");
+                println!(
+                    "{}",
+                    output::mode().status(Status::Success, "SHA256SUMS verified")
+                );
                 checksums
             }
             Err(err) => {
@@ -606,9 +626,15 @@ fn run_impl_with_url(
         match fetch_and_verify_install_script(api_base_url, channel.as_str(), &checksums) {
             Ok(content) => {
                 #[cfg(windows)]
-                println!("\x1b[1;32m✓\x1b[0m install.ps1 verified");
+                println!(
+                    "{}",
+                    output::mode().status(Status::Success, "install.ps1 verified")
+                );
                 #[cfg(not(windows))]
-                println!("\x1b[1;32m✓\x1b[0m install.sh verified");
+                println!(
+                    "{}",
+                    output::mode().status(Status::Success, "install.sh verified")
+                );
                 content
             }
             Err(err) => {
@@ -626,7 +652,13 @@ fn run_impl_with_url(
             // On Windows, we spawn the installer in the background and can't verify success
             #[cfg(not(windows))]
             {
-                println!("\x1b[1;32m✓\x1b[0m Successfully installed {}!", release.tag);
+                println!(
+                    "{}",
+                    output::mode().status(
+                        Status::Success,
+                        &format!("Successfully installed {}!", release.tag)
+                    )
+                );
             }
 
             log_message(
@@ -669,13 +701,20 @@ fn print_cached_notice(cache: &UpdateCache) {
     let current_version = env!("CARGO_PKG_VERSION");
     let available_version = cache.available_semver.as_deref().unwrap_or("");
 
+    let mode = output::mode();
     eprintln!();
     eprintln!(
-        "\x1b[1;33mA new version of git-ai is available: \x1b[1;32mv{}\x1b[0m → \x1b[1;32mv{}\x1b[0m",
-        current_version, available_version
+        "{} {} {} {}",
+        mode.paint("1;33", "A new version of git-ai is available:"),
+        mode.paint("1;32", &format!("v{}", current_version)),
+        mode.pick("→", "->"),
+        mode.paint("1;32", &format!("v{}", available_version))
     );
     eprintln!(
-        "\x1b[1;33mRun \x1b[1;36mgit-ai upgrade\x1b[0m \x1b[1;33mto upgrade to the latest version.\x1b[0m"
+        "{} {} {}",
+        mode.paint("1;33", "Run"),
+        mode.paint("1;36", "git-ai upgrade"),
+        mode.paint("1;33", "to upgrade to the latest version.")
     );
     eprintln!();
 }
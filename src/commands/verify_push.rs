@@ -0,0 +1,310 @@
+//! `git-ai verify-push`: check that every commit about to be pushed has an
+//! authorship note, and the `verify_push` check the wrapper runs before `push`.
+
+use crate::config::{Config, VerifyPushMode};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::push_updates::{PushUpdate, pushed_revision_args};
+use crate::git::refs::{get_reference_as_authorship_log_v3, note_blob_oids_for_commits};
+use crate::git::repository::{Repository, exec_git};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::io::Read;
+
+/// A pushed commit whose authorship note is missing or can't be used
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+pub struct UnverifiedCommit {
+    pub sha: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Authorship note coverage of the commits a push would send
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct PushNoteCoverage {
+    pub remote: Option<String>,
+    pub commits: usize,
+    /// Commits without any note
+    pub missing: Vec<UnverifiedCommit>,
+    /// Commits whose note can't be read by this version (unparsable or an
+    /// unsupported schema), so blame and stats treat them as unattributed
+    pub stale: Vec<UnverifiedCommit>,
+}
+
+impl PushNoteCoverage {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty()
+    }
+
+    /// Check the non-merge commits `updates` would send that `remote` (or, with
+    /// no remote, any remote) doesn't have yet
+    pub fn for_push(
+        repo: &Repository,
+        remote: Option<&str>,
+        updates: &[PushUpdate],
+    ) -> Result<Self, GitAiError> {
+        let Some(revisions) = pushed_revision_args(repo, remote, updates) else {
+            return Ok(PushNoteCoverage {
+                remote: remote.map(str::to_string),
+                ..Default::default()
+            });
+        };
+        let mut args = repo.global_args_for_exec();
+        args.extend([
+            "log".to_string(),
+            "--no-merges".to_string(),
+            "--format=%H%x09%s".to_string(),
+        ]);
+        args.extend(revisions);
+        let output = exec_git(&args)?;
+        let stdout = String::from_utf8(output.stdout)?;
+        let outgoing: Vec<(String, String)> = stdout
+            .lines()
+            .filter_map(|line| {
+                let (sha, summary) = line.split_once('\t').unwrap_or((line, ""));
+                let sha = sha.trim();
+                (!sha.is_empty()).then(|| (sha.to_string(), summary.to_string()))
+            })
+            .collect();
+
+        let shas: Vec<String> = outgoing.iter().map(|(sha, _)| sha.clone()).collect();
+        let with_notes = note_blob_oids_for_commits(repo, &shas)?;
+
+        let mut coverage = PushNoteCoverage {
+            remote: remote.map(str::to_string),
+            commits: outgoing.len(),
+            ..Default::default()
+        };
+        for (sha, summary) in outgoing {
+            if !with_notes.contains_key(&sha) {
+                coverage.missing.push(UnverifiedCommit {
+                    sha,
+                    summary,
+                    reason: None,
+                });
+            } else if let Err(e) = get_reference_as_authorship_log_v3(repo, &sha) {
+                coverage.stale.push(UnverifiedCommit {
+                    sha,
+                    summary,
+                    reason: Some(e.to_string()),
+                });
+            }
+        }
+        Ok(coverage)
+    }
+
+    fn report(&self) -> String {
+        let destination = match &self.remote {
+            Some(remote) => format!("being pushed to {}", remote),
+            None => "not on any remote".to_string(),
+        };
+        if self.is_complete() {
+            return format!(
+                "[git-ai] Every commit {} has an authorship note ({} checked)",
+                destination, self.commits
+            );
+        }
+
+        let mut lines = Vec::new();
+        if !self.missing.is_empty() {
+            lines.push(format!(
+                "[git-ai] {} of {} {} {} {} no authorship note:",
+                self.missing.len(),
+                self.commits,
+                plural(self.commits, "commit", "commits"),
+                destination,
+                plural(self.missing.len(), "has", "have")
+            ));
+            lines.extend(self.missing.iter().map(format_commit));
+        }
+        if !self.stale.is_empty() {
+            lines.push(format!(
+                "[git-ai] {} {} {} a stale authorship note:",
+                self.stale.len(),
+                plural(self.stale.len(), "commit", "commits"),
+                plural(self.stale.len(), "has", "have")
+            ));
+            lines.extend(self.stale.iter().map(format_commit));
+        }
+        lines.join("\n")
+    }
+}
+
+fn format_commit(commit: &UnverifiedCommit) -> String {
+    let short_sha = &commit.sha[..commit.sha.len().min(7)];
+    match &commit.reason {
+        Some(reason) => format!("  {} {} ({})", short_sha, commit.summary, reason),
+        None => format!("  {} {}", short_sha, commit.summary),
+    }
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 { one } else { many }
+}
+
+/// Run the `verify_push` check before a push of `updates`. Returns false if the
+/// push should be blocked.
+pub fn verify_before_push(repo: &Repository, remote: &str, updates: &[PushUpdate]) -> bool {
+    let mode = Config::get().verify_push();
+    if mode == VerifyPushMode::Off {
+        return true;
+    }
+
+    let coverage = match PushNoteCoverage::for_push(repo, Some(remote), updates) {
+        Ok(coverage) => coverage,
+        Err(e) => {
+            eprintln!("[git-ai] Skipping push verification: {}", e);
+            return true;
+        }
+    };
+    if coverage.is_complete() {
+        return true;
+    }
+
+    eprintln!("{}", coverage.report());
+    if mode == VerifyPushMode::Block {
+        eprintln!(
+            "[git-ai] Push blocked (verify_push = block). Use --no-verify to push anyway, or `git-ai config set verify_push warn`."
+        );
+        return false;
+    }
+    true
+}
+
+pub fn handle_verify_push(args: &[String]) {
+    let mut remote: Option<String> = None;
+    let mut refspecs: Vec<String> = Vec::new();
+    let mut json = false;
+    let mut stdin = false;
+    let mut mode: Option<VerifyPushMode> = None;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--stdin" => stdin = true,
+            "--warn" => mode = Some(VerifyPushMode::Warn),
+            "--block" => mode = Some(VerifyPushMode::Block),
+            "-h" | "--help" => {
+                print_help();
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}", other);
+                print_help();
+                std::process::exit(1);
+            }
+            other if remote.is_none() => remote = Some(other.to_string()),
+            other => refspecs.push(other.to_string()),
+        }
+    }
+
+    // Warn-only if asked to, or if that's what pushes are configured to do
+    let mode = mode.unwrap_or(match Config::get().verify_push() {
+        VerifyPushMode::Warn => VerifyPushMode::Warn,
+        VerifyPushMode::Off | VerifyPushMode::Block => VerifyPushMode::Block,
+    });
+
+    let coverage = find_repository(&[]).and_then(|repo| {
+        let remote = remote.or_else(|| {
+            repo.upstream_remote()
+                .ok()
+                .flatten()
+                .or_else(|| repo.get_default_remote().ok().flatten())
+        });
+        let updates = if stdin {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            PushUpdate::parse_pre_push_input(&input)
+        } else {
+            PushUpdate::from_refspecs(&repo, &refspecs)?
+        };
+        PushNoteCoverage::for_push(&repo, remote.as_deref(), &updates)
+    });
+    let coverage = match coverage {
+        Ok(coverage) => coverage,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&coverage) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!("{}", coverage.report());
+    }
+
+    if !coverage.is_complete() && mode == VerifyPushMode::Block {
+        std::process::exit(1);
+    }
+}
+
+fn print_help() {
+    eprintln!("git-ai verify-push - Check that commits about to be pushed have authorship notes");
+    eprintln!();
+    eprintln!(
+        "Usage: git-ai verify-push [<remote> [<refspec>...]] [--stdin] [--warn|--block] [--json]"
+    );
+    eprintln!();
+    eprintln!("Checks the commits the refspecs (default: HEAD) would push that <remote>");
+    eprintln!("(default: upstream, then the default remote) doesn't have yet. Exits 1 if any");
+    eprintln!("has a missing or stale note, unless --warn is given or verify_push is set to warn.");
+    eprintln!();
+    eprintln!("  --stdin   Read the pushed refs from pre-push hook input instead of refspecs");
+    eprintln!("  --warn    Report problems but exit 0");
+    eprintln!("  --block   Exit 1 on problems (default)");
+    eprintln!("  --json    Print the result as JSON");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(sha: &str, summary: &str, reason: Option<&str>) -> UnverifiedCommit {
+        UnverifiedCommit {
+            sha: sha.to_string(),
+            summary: summary.to_string(),
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_report_lists_missing_and_stale_commits() {
+        let coverage = PushNoteCoverage {
+            remote: Some("origin".to_string()),
+            commits: 3,
+            missing: vec![commit("1234567890abcdef", "Add parser", None)],
+            stale: vec![commit(
+                "abcdef1234567890",
+                "Fix lexer",
+                Some("Failed to parse authorship log"),
+            )],
+        };
+        assert!(!coverage.is_complete());
+        assert_eq!(
+            coverage.report(),
+            "[git-ai] 1 of 3 commits being pushed to origin has no authorship note:\n  1234567 Add parser\n[git-ai] 1 commit has a stale authorship note:\n  abcdef1 Fix lexer (Failed to parse authorship log)"
+        );
+    }
+
+    #[test]
+    fn test_report_when_complete() {
+        let coverage = PushNoteCoverage {
+            remote: Some("origin".to_string()),
+            commits: 1,
+            ..Default::default()
+        };
+        assert!(coverage.is_complete());
+        assert_eq!(
+            coverage.report(),
+            "[git-ai] Every commit being pushed to origin has an authorship note (1 checked)"
+        );
+    }
+}
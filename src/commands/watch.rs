@@ -0,0 +1,262 @@
+//! `git-ai watch`: checkpoint manual edits as they happen, for editors without a
+//! git-ai integration. File changes are collected until the tree has been quiet for
+//! `watch_debounce_ms`, then the changed files get a human checkpoint so edits made
+//! between AI sessions are attributed to the human rather than to the next agent.
+//!
+//! Agent hooks write their own checkpoints around each edit. While an agent's
+//! pre-edit checkpoint is waiting for its post-edit one, the changed files are the
+//! agent's and the watcher holds off.
+
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint;
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git_stdin};
+use crate::utils::{debug_log, normalize_to_posix};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::time::{Duration, Instant, SystemTime};
+
+/// An agent edit that hasn't finished after this long was abandoned (the agent
+/// crashed or its post-edit hook never ran); stop waiting for it
+const AGENT_EDIT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub fn handle_watch(args: &[String]) {
+    let mut debounce = Config::get().watch_debounce();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--debounce" => {
+                let Some(ms) = args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) else {
+                    eprintln!("Error: --debounce requires a number of milliseconds");
+                    std::process::exit(1);
+                };
+                debounce = Duration::from_millis(ms);
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_watch_help();
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown option: {}", other);
+                print_watch_help();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = watch(&repo, debounce) {
+        eprintln!("Watch failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn watch(repo: &Repository, debounce: Duration) -> Result<(), GitAiError> {
+    let workdir = repo.workdir()?;
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| GitAiError::Generic(format!("Failed to start file watcher: {}", e)))?;
+    watcher
+        .watch(&workdir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            GitAiError::Generic(format!("Failed to watch {}: {}", workdir.display(), e))
+        })?;
+    eprintln!(
+        "Watching {} for edits (checkpointing {}ms after they settle, Ctrl-C to stop)",
+        workdir.display(),
+        debounce.as_millis()
+    );
+
+    let mut changed: BTreeSet<String> = BTreeSet::new();
+    let mut last_change: Option<Instant> = None;
+    loop {
+        let timeout = match last_change {
+            Some(at) => debounce.saturating_sub(at.elapsed()),
+            None => Duration::from_secs(60 * 60),
+        };
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Access(_) | EventKind::Other) {
+                    continue;
+                }
+                for path in &event.paths {
+                    if let Some(relative) = watched_path(&workdir, repo.path(), path) {
+                        changed.insert(relative);
+                        last_change = Some(Instant::now());
+                    }
+                }
+            }
+            Ok(Err(e)) => debug_log(&format!("File watcher error: {}", e)),
+            Err(RecvTimeoutError::Timeout) => {
+                if changed.is_empty() {
+                    last_change = None;
+                    continue;
+                }
+                if agent_edit_in_flight(repo.storage.agent_edit_pending_since()) {
+                    // Check again once the agent has had another quiet period to finish
+                    last_change = Some(Instant::now());
+                    continue;
+                }
+                let files = not_ignored(repo, std::mem::take(&mut changed))?;
+                last_change = None;
+                if !files.is_empty() {
+                    checkpoint_files(repo, files);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// `path` relative to the working tree, or None for paths git-ai shouldn't react to:
+/// anything in the git directory (our own working logs live there) or outside the tree
+fn watched_path(workdir: &Path, git_dir: &Path, path: &Path) -> Option<String> {
+    if path.starts_with(git_dir) {
+        return None;
+    }
+    let relative = path.strip_prefix(workdir).ok()?;
+    if relative.as_os_str().is_empty()
+        || relative
+            .components()
+            .any(|component| component.as_os_str() == ".git")
+    {
+        return None;
+    }
+    Some(normalize_to_posix(&relative.to_string_lossy()))
+}
+
+fn agent_edit_in_flight(pending_since: Option<SystemTime>) -> bool {
+    pending_since
+        .and_then(|since| since.elapsed().ok())
+        .is_some_and(|elapsed| elapsed < AGENT_EDIT_TIMEOUT)
+}
+
+/// Drop paths matched by .gitignore so build output doesn't trigger checkpoints
+fn not_ignored(repo: &Repository, paths: BTreeSet<String>) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["check-ignore", "--stdin"].map(String::from));
+    let stdin: String = paths.iter().map(|path| format!("{}\n", path)).collect();
+    let ignored: BTreeSet<String> = match exec_git_stdin(&args, stdin.as_bytes()) {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        // Exit code 1: none of the paths are ignored
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => BTreeSet::new(),
+        Err(e) => return Err(e),
+    };
+    Ok(paths
+        .into_iter()
+        .filter(|path| !ignored.contains(path))
+        .collect())
+}
+
+fn checkpoint_files(repo: &Repository, files: Vec<String>) {
+    let author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+    let file_count = files.len();
+    // Human checkpoints only look at `will_edit_filepaths`; the agent id is unused
+    let run = AgentRunResult {
+        agent_id: AgentId {
+            tool: "git-ai-watch".to_string(),
+            id: "watch".to_string(),
+            model: "unknown".to_string(),
+        },
+        agent_metadata: None,
+        checkpoint_kind: CheckpointKind::Human,
+        transcript: None,
+        repo_working_dir: None,
+        edited_filepaths: None,
+        will_edit_filepaths: Some(files),
+        dirty_files: None,
+    };
+    match checkpoint::run(
+        repo,
+        &author,
+        &[],
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        Some(run),
+        false,
+    ) {
+        Ok(_) => eprintln!(
+            "Checkpointed {} changed {}",
+            file_count,
+            if file_count == 1 { "file" } else { "files" }
+        ),
+        Err(e) => eprintln!("Checkpoint failed: {}", e),
+    }
+}
+
+fn print_watch_help() {
+    eprintln!("Usage: git-ai watch [--debounce <ms>]");
+    eprintln!();
+    eprintln!("Watch the working tree and record a human checkpoint once edits settle, so");
+    eprintln!("changes made outside agent sessions are attributed without an editor plugin.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!(
+        "  --debounce <ms>    Quiet period before checkpointing (default: watch_debounce_ms)"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_watched_path_skips_git_dir_and_outside_paths() {
+        let workdir = PathBuf::from("/repo");
+        let git_dir = PathBuf::from("/repo/.git");
+        assert_eq!(
+            watched_path(&workdir, &git_dir, Path::new("/repo/src/lib.rs")),
+            Some("src/lib.rs".to_string())
+        );
+        assert_eq!(
+            watched_path(
+                &workdir,
+                &git_dir,
+                Path::new("/repo/.git/ai/working_logs/x")
+            ),
+            None
+        );
+        assert_eq!(
+            watched_path(&workdir, &git_dir, Path::new("/repo/vendor/dep/.git/HEAD")),
+            None
+        );
+        assert_eq!(
+            watched_path(&workdir, &git_dir, Path::new("/elsewhere/a.rs")),
+            None
+        );
+        assert_eq!(watched_path(&workdir, &git_dir, Path::new("/repo")), None);
+    }
+
+    #[test]
+    fn test_agent_edit_in_flight_expires() {
+        assert!(!agent_edit_in_flight(None));
+        assert!(agent_edit_in_flight(Some(SystemTime::now())));
+        assert!(!agent_edit_in_flight(Some(
+            SystemTime::now() - AGENT_EDIT_TIMEOUT - Duration::from_secs(1)
+        )));
+    }
+}
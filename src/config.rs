@@ -1,15 +1,21 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
+use std::time::Duration;
 use uuid::Uuid;
 
 use glob::Pattern;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::feature_flags::FeatureFlags;
+use crate::git::refs::AI_AUTHORSHIP_REFNAME;
 use crate::git::repository::Repository;
 use crate::mdm::utils::home_dir;
+use crate::repo_config::find_repo_config_layers_from_cwd;
+use crate::reporting::buckets::ReportTimezone;
 
 #[cfg(any(test, feature = "test-support"))]
 use std::sync::RwLock;
@@ -17,6 +23,9 @@ use std::sync::RwLock;
 /// Default API base URL for comparison
 pub const DEFAULT_API_BASE_URL: &str = "https://usegitai.com";
 
+/// Default client-side API rate limit (requests per second per process)
+pub const DEFAULT_API_MAX_RPS: f64 = 10.0;
+
 /// Prompt storage mode enum for type-safe handling
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PromptStorageMode {
@@ -73,7 +82,37 @@ pub struct Config {
     prompt_storage: String,
     default_prompt_storage: Option<String>,
     api_key: Option<String>,
+    api_max_rps: f64,
     quiet: bool,
+    report_timezone: Option<String>,
+    report_bot_authors: Vec<String>,
+    report_authors: Vec<String>,
+    events_webhook_url: Option<String>,
+    events_webhook_events: Vec<String>,
+    dual_write_destination: Option<String>,
+    dual_write_headers: BTreeMap<String, String>,
+    identity_aliases: BTreeMap<String, Vec<String>>,
+    identity_lookup_url: Option<String>,
+    context_capture: ContextCaptureSettings,
+    redaction: RedactionSettings,
+    prompt_hashing: PromptHashingSettings,
+    performance_budgets: BTreeMap<String, Duration>,
+    verify_push: VerifyPushMode,
+    blame_parallelism: usize,
+    attribution_granularity: AttributionGranularity,
+    ai_assisted_threshold: f64,
+    capture_environment: bool,
+    annotate_tags: bool,
+    exclude_paths: Vec<String>,
+    tracking: TrackingSettings,
+    trust: TrustSettings,
+    policy: PolicySettings,
+    notes_ref: String,
+    credential_backend: Option<CredentialBackendKind>,
+    credential_file_fallback: bool,
+    watch_debounce: Duration,
+    jetbrains_plugin: JetBrainsPluginSettings,
+    history: HistorySettings,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -106,11 +145,136 @@ impl UpdateChannel {
     }
 }
 
+/// Files blamed at once when `blame_parallelism` isn't set
+pub const DEFAULT_BLAME_PARALLELISM: usize = 30;
+
+/// Share of a mixed line's characters AI must have written for blame to call the
+/// line AI-assisted, when `ai_assisted_threshold` isn't set
+pub const DEFAULT_AI_ASSISTED_THRESHOLD: f64 = 0.5;
+
+/// Quiet period after the last file change before `git-ai watch` checkpoints, when
+/// `watch_debounce_ms` isn't set
+pub const DEFAULT_WATCH_DEBOUNCE: Duration = Duration::from_millis(2000);
+
+/// How finely commits record AI authorship (`attribution_granularity`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AttributionGranularity {
+    /// Whole lines only; a human edit to an AI line makes it human
+    #[default]
+    Line,
+    /// Also record the AI-written characters of lines a human edited (authorship/4.0.0)
+    Char,
+}
+
+impl AttributionGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributionGranularity::Line => "line",
+            AttributionGranularity::Char => "char",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "line" => Some(AttributionGranularity::Line),
+            "char" => Some(AttributionGranularity::Char),
+            _ => None,
+        }
+    }
+}
+
+/// Parse an `ai_assisted_threshold`: a fraction in (0, 1]
+pub fn parse_ai_assisted_threshold(value: f64) -> Option<f64> {
+    (value > 0.0 && value <= 1.0).then_some(value)
+}
+
+/// Parse a `notes_ref`: a short name under `refs/notes/`, such as `ai` or `team/ai`
+pub fn parse_notes_ref(value: &str) -> Option<String> {
+    let value = value.trim().trim_start_matches("refs/notes/");
+    let valid = !value.is_empty()
+        && !value.starts_with(['-', '/', '.'])
+        && !value.ends_with(['/', '.'])
+        && !value.contains("..")
+        && !value.contains("//")
+        && !value.ends_with(".lock")
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    valid.then(|| value.to_string())
+}
+
+/// Where `git-ai login` keeps its tokens (`credential_backend`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialBackendKind {
+    /// The platform's native store: Keychain, Secret Service or Credential Manager
+    Auto,
+    Keychain,
+    SecretService,
+    WindowsCredentialManager,
+    /// Plaintext file under `~/.git-ai/internal`
+    File,
+}
+
+impl CredentialBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CredentialBackendKind::Auto => "auto",
+            CredentialBackendKind::Keychain => "keychain",
+            CredentialBackendKind::SecretService => "secret-service",
+            CredentialBackendKind::WindowsCredentialManager => "windows-credential-manager",
+            CredentialBackendKind::File => "file",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "auto" => Some(CredentialBackendKind::Auto),
+            "keychain" => Some(CredentialBackendKind::Keychain),
+            "secret-service" | "libsecret" => Some(CredentialBackendKind::SecretService),
+            "windows-credential-manager" | "wincred" => {
+                Some(CredentialBackendKind::WindowsCredentialManager)
+            }
+            "file" => Some(CredentialBackendKind::File),
+            _ => None,
+        }
+    }
+}
+
+/// What the wrapper's `push` does about commits without authorship notes (`verify_push`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum VerifyPushMode {
+    #[default]
+    Off,
+    Warn,
+    Block,
+}
+
+impl VerifyPushMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerifyPushMode::Off => "off",
+            VerifyPushMode::Warn => "warn",
+            VerifyPushMode::Block => "block",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "off" => Some(VerifyPushMode::Off),
+            "warn" => Some(VerifyPushMode::Warn),
+            "block" => Some(VerifyPushMode::Block),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Default)]
 pub struct FileConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub git_path: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitPinConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub exclude_prompts_in_repositories: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub include_prompts_in_repositories: Option<Vec<String>>,
@@ -127,19 +291,761 @@ pub struct FileConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disable_auto_updates: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub update_channel: Option<String>,
+    pub update_channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature_flags: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_storage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_prompt_storage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_max_rps: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<ReportConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<EventsConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual_write: Option<DualWriteConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identities: Option<IdentitiesConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_capture: Option<ContextCaptureConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hashing: Option<PromptHashingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance_budgets: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_push: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame_parallelism: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_granularity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_assisted_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_environment: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotate_tags: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_paths: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking: Option<TrackingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_file_fallback: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_debounce_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jetbrains_plugin: Option<JetBrainsPluginConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<OrgConfigSource>,
+}
+
+/// The real git binary pinned by `git-ai git-path pin` (`git.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct GitPinConfig {
+    /// Binary git-ai runs; takes precedence over `git_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// `--version` of the binary when it was pinned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// SHA-256 of the binary when it was pinned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Settings shared by all reports (`report.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct ReportConfig {
+    /// Time zone that daily/weekly buckets are aligned to (IANA name, offset, "local" or "UTC")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Author patterns (e.g. "renovate", "[bot]") whose commits are left out of reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot_authors: Option<Vec<String>>,
+    /// When set, reports only count commits by authors matching one of these patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<String>>,
+}
+
+/// Local event delivery settings (`events.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct EventsConfig {
+    /// Endpoint that selected local events are POSTed to as JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// Event names to deliver (e.g. "commit_processed"); all events when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_events: Option<Vec<String>>,
+}
+
+/// Copies of metrics and bundles kept outside the hosted API (`dual_write.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct DualWriteConfig {
+    /// Local directory, http(s) URL of an endpoint accepting PUT requests, or
+    /// an `s3://` / `gs://` bucket URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// Extra headers sent with each PUT to an http(s) destination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+}
+
+/// Author identity mapping (`identities.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct IdentitiesConfig {
+    /// Canonical `Name <email>` -> other emails, names or `Name <email>` strings used by that person
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<BTreeMap<String, Vec<String>>>,
+    /// Org API queried (and cached) for authors without an alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookup_url: Option<String>,
+}
+
+/// Checkpoint context bundles (`context_capture.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct ContextCaptureConfig {
+    /// Capture a bundle of edited regions with each AI checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Unchanged lines kept around each edited region
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<u32>,
+    /// Largest bundle size in bytes; files past the cap are left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+    /// Path globs that are never captured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+}
+
+/// Effective `context_capture.*` settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContextCaptureSettings {
+    pub enabled: bool,
+    pub context_lines: u32,
+    pub max_bytes: usize,
+    pub exclude: Vec<String>,
+}
+
+impl Default for ContextCaptureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            context_lines: 3,
+            max_bytes: 64 * 1024,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl ContextCaptureSettings {
+    fn from_file_config(config: Option<&ContextCaptureConfig>) -> Self {
+        let defaults = Self::default();
+        let Some(config) = config else {
+            return defaults;
+        };
+        Self {
+            enabled: config.enabled.unwrap_or(defaults.enabled),
+            context_lines: config.context_lines.unwrap_or(defaults.context_lines),
+            max_bytes: config.max_bytes.unwrap_or(defaults.max_bytes),
+            exclude: config.exclude.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Transcript redaction before storage (`redaction.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct RedactionConfig {
+    /// Redact transcripts before they are written to working logs and the prompt database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Also redact high-entropy strings that look like keys but match no rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<bool>,
+    /// Also redact email addresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emails: Option<bool>,
+    /// Extra rules: name -> regex; matches are replaced with `[REDACTED:<name>]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<BTreeMap<String, String>>,
+}
+
+/// Effective `redaction.*` settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedactionSettings {
+    pub enabled: bool,
+    pub entropy: bool,
+    pub emails: bool,
+    pub rules: BTreeMap<String, String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            entropy: true,
+            emails: true,
+            rules: BTreeMap::new(),
+        }
+    }
+}
+
+impl RedactionSettings {
+    fn from_file_config(config: Option<&RedactionConfig>) -> Self {
+        let defaults = Self::default();
+        let Some(config) = config else {
+            return defaults;
+        };
+        Self {
+            enabled: config.enabled.unwrap_or(defaults.enabled),
+            entropy: config.entropy.unwrap_or(defaults.entropy),
+            emails: config.emails.unwrap_or(defaults.emails),
+            rules: config.rules.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Path rules for files that don't get line attribution (`tracking.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct TrackingConfig {
+    /// Path globs that aren't tracked at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+    /// Path globs for generated or binary files (lockfiles, `dist/**`, `*.min.js`),
+    /// summarized per rule in authorship logs with a byte count instead of lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated: Option<Vec<String>>,
+}
+
+/// Effective `tracking.*` settings
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrackingSettings {
+    pub exclude: Vec<String>,
+    pub generated: Vec<String>,
+}
+
+impl TrackingSettings {
+    fn from_file_config(config: Option<&TrackingConfig>) -> Self {
+        let Some(config) = config else {
+            return Self::default();
+        };
+        Self {
+            exclude: config.exclude.clone().unwrap_or_default(),
+            generated: config.generated.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// How much AI output from an agent is trusted (`trust.*` keys)
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustTier {
+    Trusted,
+    #[default]
+    Standard,
+    Untrusted,
+}
+
+impl TrustTier {
+    pub const ALL: [TrustTier; 3] = [
+        TrustTier::Trusted,
+        TrustTier::Standard,
+        TrustTier::Untrusted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrustTier::Trusted => "trusted",
+            TrustTier::Standard => "standard",
+            TrustTier::Untrusted => "untrusted",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "trusted" => Some(TrustTier::Trusted),
+            "standard" => Some(TrustTier::Standard),
+            "untrusted" => Some(TrustTier::Untrusted),
+            _ => None,
+        }
+    }
+
+    /// Weight of one AI line of this tier when nothing is configured
+    fn default_weight(&self) -> f64 {
+        match self {
+            TrustTier::Trusted => 0.5,
+            TrustTier::Standard => 1.0,
+            TrustTier::Untrusted => 2.0,
+        }
+    }
+}
+
+/// Trust tiers for agents (`trust.*` keys). Rules are globs over the tool name
+/// (`claude`) or `tool/model` (`cursor/acme-*`).
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct TrustConfig {
+    /// Agents whose output is trusted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusted: Option<Vec<String>>,
+    /// Agents whose output is untrusted; wins over `trusted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub untrusted: Option<Vec<String>>,
+    /// Tier for agents matching no rule (default standard)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Tier -> weight of one AI line in weighted policy totals
+    /// (defaults: trusted 0.5, standard 1, untrusted 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<BTreeMap<String, f64>>,
+}
+
+/// Effective `trust.*` settings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrustSettings {
+    pub trusted: Vec<String>,
+    pub untrusted: Vec<String>,
+    pub default: TrustTier,
+    pub weights: BTreeMap<TrustTier, f64>,
+}
+
+impl TrustSettings {
+    fn from_file_config(config: Option<&TrustConfig>) -> Self {
+        let Some(config) = config else {
+            return Self::default();
+        };
+        let default = match config.default.as_deref() {
+            Some(value) => TrustTier::parse(value).unwrap_or_else(|| {
+                eprintln!(
+                    "Warning: Invalid trust.default value '{}', using 'standard'",
+                    value
+                );
+                TrustTier::Standard
+            }),
+            None => TrustTier::Standard,
+        };
+        let mut weights = BTreeMap::new();
+        for (tier, weight) in config.weights.iter().flatten() {
+            match TrustTier::parse(tier) {
+                Some(tier) if *weight >= 0.0 => {
+                    weights.insert(tier, *weight);
+                }
+                _ => eprintln!("Warning: Ignoring invalid trust.weights entry '{}'", tier),
+            }
+        }
+        Self {
+            trusted: config.trusted.clone().unwrap_or_default(),
+            untrusted: config.untrusted.clone().unwrap_or_default(),
+            default,
+            weights,
+        }
+    }
+
+    /// Tier of an agent: `untrusted` rules first, then `trusted`, then the default
+    pub fn tier_for(&self, tool: &str, model: &str) -> TrustTier {
+        let tool_model = format!("{}/{}", tool, model);
+        let matches = |rules: &[String]| {
+            rules.iter().any(|rule| {
+                let subject = if rule.contains('/') {
+                    &tool_model
+                } else {
+                    tool
+                };
+                Pattern::new(rule)
+                    .map(|pattern| pattern.matches(subject))
+                    .unwrap_or(false)
+            })
+        };
+        if matches(&self.untrusted) {
+            TrustTier::Untrusted
+        } else if matches(&self.trusted) {
+            TrustTier::Trusted
+        } else {
+            self.default
+        }
+    }
+
+    pub fn weight(&self, tier: TrustTier) -> f64 {
+        self.weights
+            .get(&tier)
+            .copied()
+            .unwrap_or_else(|| tier.default_weight())
+    }
+}
+
+/// Built-in commit policies (`policy.*`)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct PolicyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<PolicyRuleConfig>>,
+}
+
+/// One `[[policy.rules]]` entry
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct PolicyRuleConfig {
+    /// Shown in the policy report (default `rule-<n>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Globs limiting the rule to some files (default all files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<String>>,
+    /// Fail if more than this percentage of the added lines in matching files
+    /// are AI-authored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ai_percent: Option<f64>,
+    /// Fail if an AI checkpoint touching a matching file recorded no transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_transcript: Option<bool>,
+    /// Code that lets a commit through this rule when passed in
+    /// `GIT_AI_POLICY_OVERRIDE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_code: Option<String>,
+}
+
+/// A validated `policy.rules` entry
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PolicyRule {
+    pub name: String,
+    pub paths: Vec<String>,
+    pub max_ai_percent: Option<f64>,
+    pub require_transcript: bool,
+    pub override_code: Option<String>,
+}
+
+impl PolicyRule {
+    /// Whether the rule covers `path`; rules without `paths` cover every file
+    pub fn matches_path(&self, path: &str) -> bool {
+        self.paths.is_empty()
+            || self.paths.iter().any(|glob| {
+                Pattern::new(glob)
+                    .map(|pattern| pattern.matches(path))
+                    .unwrap_or(false)
+            })
+    }
+}
+
+/// Effective `policy.*` settings
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PolicySettings {
+    pub rules: Vec<PolicyRule>,
+}
+
+impl PolicySettings {
+    fn from_file_config(config: Option<&PolicyConfig>) -> Self {
+        let mut rules = Vec::new();
+        let configured = config.and_then(|c| c.rules.as_ref());
+        for (index, rule) in configured.into_iter().flatten().enumerate() {
+            let name = rule
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("rule-{}", index + 1));
+            if let Some(glob) = rule
+                .paths
+                .iter()
+                .flatten()
+                .find(|glob| Pattern::new(glob).is_err())
+            {
+                eprintln!(
+                    "Warning: Ignoring policy rule '{}': invalid path glob '{}'",
+                    name, glob
+                );
+                continue;
+            }
+            let max_ai_percent = match rule.max_ai_percent {
+                Some(percent) if !(0.0..=100.0).contains(&percent) => {
+                    eprintln!(
+                        "Warning: Ignoring policy rule '{}': max_ai_percent must be between 0 and 100",
+                        name
+                    );
+                    continue;
+                }
+                percent => percent,
+            };
+            let require_transcript = rule.require_transcript.unwrap_or(false);
+            if max_ai_percent.is_none() && !require_transcript {
+                eprintln!(
+                    "Warning: Ignoring policy rule '{}': it sets neither max_ai_percent nor require_transcript",
+                    name
+                );
+                continue;
+            }
+            rules.push(PolicyRule {
+                name,
+                paths: rule.paths.clone().unwrap_or_default(),
+                max_ai_percent,
+                require_transcript,
+                override_code: rule.override_code.clone().filter(|code| !code.is_empty()),
+            });
+        }
+        Self { rules }
+    }
+}
+
+/// Hash function behind prompt ids (`prompt_hashing.algorithm`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PromptHashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Sha3_256,
+}
+
+impl PromptHashAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptHashAlgorithm::Sha256 => "sha256",
+            PromptHashAlgorithm::Sha512 => "sha512",
+            PromptHashAlgorithm::Sha3_256 => "sha3-256",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "sha256" => Some(PromptHashAlgorithm::Sha256),
+            "sha512" => Some(PromptHashAlgorithm::Sha512),
+            "sha3-256" => Some(PromptHashAlgorithm::Sha3_256),
+            _ => None,
+        }
+    }
+}
+
+/// A prompt id scheme retired by a rotation (`prompt_hashing.previous[]`)
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PromptHashSchemeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+}
+
+/// How prompt ids are derived from agent sessions (`prompt_hashing.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct PromptHashingConfig {
+    /// Hash algorithm for new prompt ids (default sha256)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    /// Organization salt; when set, prompt ids are HMACs keyed with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    /// Schemes in use before the last rotations. Working logs written under them
+    /// are still read, and their prompt ids mapped to the current scheme.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Vec<PromptHashSchemeConfig>>,
+}
+
+/// One resolved way of deriving prompt ids
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct PromptHashScheme {
+    pub algorithm: PromptHashAlgorithm,
+    pub salt: Option<String>,
+}
+
+impl PromptHashScheme {
+    /// An unknown algorithm is reported and falls back to sha256, so a typo in a
+    /// mandated algorithm doesn't go unnoticed
+    fn from_parts(algorithm: Option<&str>, salt: Option<&str>) -> Self {
+        Self {
+            algorithm: algorithm
+                .map(|name| {
+                    PromptHashAlgorithm::parse(name).unwrap_or_else(|| {
+                        eprintln!(
+                            "Warning: Unknown prompt_hashing.algorithm '{}', using sha256. Expected 'sha256', 'sha512', or 'sha3-256'",
+                            name
+                        );
+                        PromptHashAlgorithm::default()
+                    })
+                })
+                .unwrap_or_default(),
+            salt: salt.filter(|salt| !salt.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+/// Effective `prompt_hashing.*` settings
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct PromptHashingSettings {
+    pub current: PromptHashScheme,
+    pub previous: Vec<PromptHashScheme>,
+}
+
+impl PromptHashingSettings {
+    /// `GIT_AI_PROMPT_HASH_SALT` replaces the configured salt, so it can come from a
+    /// secret store instead of the config file
+    fn from_file_config(config: Option<&PromptHashingConfig>) -> Self {
+        let env_salt = env::var("GIT_AI_PROMPT_HASH_SALT").ok();
+        let config = config.cloned().unwrap_or_default();
+        let current = PromptHashScheme::from_parts(
+            config.algorithm.as_deref(),
+            env_salt.as_deref().or(config.salt.as_deref()),
+        );
+        let previous = config
+            .previous
+            .unwrap_or_default()
+            .iter()
+            .map(|scheme| {
+                PromptHashScheme::from_parts(scheme.algorithm.as_deref(), scheme.salt.as_deref())
+            })
+            .filter(|scheme| *scheme != current)
+            .collect();
+        Self { current, previous }
+    }
+}
+
+/// JetBrains Marketplace channel the IDE plugin is installed from (`jetbrains_plugin.channel`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PluginChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl PluginChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginChannel::Stable => "stable",
+            PluginChannel::Beta => "beta",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "stable" => Some(PluginChannel::Stable),
+            "beta" => Some(PluginChannel::Beta),
+            _ => None,
+        }
+    }
+}
+
+/// Which build of the JetBrains plugin `git-ai mdm jetbrains` and `install-hooks`
+/// install (`jetbrains_plugin.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct JetBrainsPluginConfig {
+    /// Exact plugin version to install; updates are held back until it changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Marketplace channel for the latest version when none is pinned (stable or beta)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub feature_flags: Option<serde_json::Value>,
+    pub channel: Option<String>,
+}
+
+/// Effective `jetbrains_plugin.*` settings
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct JetBrainsPluginSettings {
+    pub version: Option<String>,
+    pub channel: PluginChannel,
+}
+
+impl JetBrainsPluginSettings {
+    pub fn from_file_config(config: Option<&JetBrainsPluginConfig>) -> Self {
+        let Some(config) = config else {
+            return Self::default();
+        };
+        Self {
+            version: config
+                .version
+                .as_deref()
+                .map(str::trim)
+                .filter(|version| !version.is_empty())
+                .map(str::to_string),
+            channel: config
+                .channel
+                .as_deref()
+                .and_then(PluginChannel::parse)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// History traversal in shallow and partial clones (`history.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct HistoryConfig {
+    /// Fetch more history when a shallow clone cuts off a commit range being
+    /// rewritten. Defaults to on in CI only, since it changes the clone.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub api_base_url: Option<String>,
+    pub deepen: Option<bool>,
+    /// Most commits fetched on demand before attribution is limited to what is present
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub prompt_storage: Option<String>,
+    pub deepen_limit: Option<u32>,
+}
+
+/// Effective `history.*` settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistorySettings {
+    pub deepen: bool,
+    pub deepen_limit: u32,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            deepen: false,
+            deepen_limit: 1000,
+        }
+    }
+}
+
+impl HistorySettings {
+    fn from_file_config(config: Option<&HistoryConfig>) -> Self {
+        let defaults = Self::default();
+        Self {
+            deepen: config
+                .and_then(|config| config.deepen)
+                .unwrap_or_else(running_in_ci),
+            deepen_limit: config
+                .and_then(|config| config.deepen_limit)
+                .unwrap_or(defaults.deepen_limit),
+        }
+    }
+}
+
+/// CI runners set `CI`; their clones are throwaway, unlike a developer's
+fn running_in_ci() -> bool {
+    env::var_os("CI").is_some_and(|value| !value.is_empty())
+}
+
+/// Org-hosted config document layered beneath this file (`config.*` keys)
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct OrgConfigSource {
+    /// URL of a JSON document with the same keys as `config.json`
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub default_prompt_storage: Option<String>,
+    pub remote_url: Option<String>,
+    /// Seconds a fetched document is used before checking for a new version
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub api_key: Option<String>,
+    pub ttl_secs: Option<u64>,
+    /// Whether the document may also set API endpoints (off by default)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub quiet: Option<bool>,
+    pub allow_endpoints: Option<bool>,
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
@@ -162,6 +1068,38 @@ pub struct ConfigPatch {
     pub disable_auto_updates: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub prompt_storage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance_budgets: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_push: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame_parallelism: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_granularity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_assisted_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_environment: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotate_tags: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_paths: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking: Option<TrackingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual_write: Option<DualWriteConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction_rules: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hashing: Option<PromptHashingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryConfig>,
 }
 
 impl Config {
@@ -375,6 +1313,164 @@ impl Config {
         self.api_key.as_deref()
     }
 
+    /// Time zone configured for report buckets, if any
+    pub fn report_timezone(&self) -> Option<&str> {
+        self.report_timezone.as_deref()
+    }
+
+    /// Author patterns excluded from reports (`report.bot_authors`)
+    pub fn report_bot_authors(&self) -> &[String] {
+        &self.report_bot_authors
+    }
+
+    /// Author patterns reports are restricted to (`report.authors`); empty means everyone
+    pub fn report_authors(&self) -> &[String] {
+        &self.report_authors
+    }
+
+    /// Endpoint local events are delivered to (`events.webhook_url`), if any
+    pub fn events_webhook_url(&self) -> Option<&str> {
+        self.events_webhook_url.as_deref()
+    }
+
+    /// Event names delivered to the webhook (`events.webhook_events`); empty means all
+    pub fn events_webhook_events(&self) -> &[String] {
+        &self.events_webhook_events
+    }
+
+    /// Where copies of metrics and bundles are written (`dual_write.destination`), if anywhere
+    pub fn dual_write_destination(&self) -> Option<&str> {
+        self.dual_write_destination.as_deref()
+    }
+
+    /// Headers sent with dual-write PUT requests (`dual_write.headers`)
+    pub fn dual_write_headers(&self) -> &BTreeMap<String, String> {
+        &self.dual_write_headers
+    }
+
+    /// Canonical identity -> aliases (`identities.aliases`)
+    pub fn identity_aliases(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.identity_aliases
+    }
+
+    /// Org API used to resolve unknown authors (`identities.lookup_url`), if any
+    pub fn identity_lookup_url(&self) -> Option<&str> {
+        self.identity_lookup_url.as_deref()
+    }
+
+    /// Checkpoint context bundle settings (`context_capture.*`)
+    pub fn context_capture(&self) -> &ContextCaptureSettings {
+        &self.context_capture
+    }
+
+    /// Transcript redaction settings (`redaction.*`)
+    pub fn redaction(&self) -> &RedactionSettings {
+        &self.redaction
+    }
+
+    /// Prompt id hashing scheme and the schemes it replaced (`prompt_hashing.*`)
+    pub fn prompt_hashing(&self) -> &PromptHashingSettings {
+        &self.prompt_hashing
+    }
+
+    /// Latency budget for `operation` (`performance_budgets`), if one is set
+    pub fn performance_budget(&self, operation: &str) -> Option<Duration> {
+        self.performance_budgets.get(operation).copied()
+    }
+
+    /// Whether pushes check that every pushed commit has an authorship note
+    pub fn verify_push(&self) -> VerifyPushMode {
+        self.verify_push
+    }
+
+    /// Pinned version and channel of the JetBrains plugin (`jetbrains_plugin.*`)
+    pub fn jetbrains_plugin(&self) -> &JetBrainsPluginSettings {
+        &self.jetbrains_plugin
+    }
+
+    /// Deepening of shallow clones during authorship rewrites (`history.*`)
+    pub fn history(&self) -> &HistorySettings {
+        &self.history
+    }
+
+    /// How many files are blamed at once when rebuilding attributions (`blame_parallelism`)
+    pub fn blame_parallelism(&self) -> usize {
+        self.blame_parallelism
+    }
+
+    /// Whether commits also record sub-line AI ranges (`attribution_granularity`)
+    pub fn attribution_granularity(&self) -> AttributionGranularity {
+        self.attribution_granularity
+    }
+
+    /// Minimum AI share for blame to show a mixed line as AI-assisted (`ai_assisted_threshold`)
+    pub fn ai_assisted_threshold(&self) -> f64 {
+        self.ai_assisted_threshold
+    }
+
+    /// Whether authorship logs record the OS, git and agent versions behind each
+    /// commit (`capture_environment`)
+    pub fn capture_environment(&self) -> bool {
+        self.capture_environment
+    }
+
+    /// Whether annotated tags created through git-ai get an aggregate attribution
+    /// note (`annotate_tags`)
+    pub fn annotate_tags(&self) -> bool {
+        self.annotate_tags
+    }
+
+    /// Path globs left out of attribution stats on top of the built-in lockfile
+    /// and generated-file patterns (`exclude_paths`)
+    pub fn exclude_paths(&self) -> &[String] {
+        &self.exclude_paths
+    }
+
+    /// Paths excluded from tracking and paths tracked as generated files
+    /// (`tracking.*`)
+    pub fn tracking(&self) -> &TrackingSettings {
+        &self.tracking
+    }
+
+    /// Trust tiers of agents and the weight of their lines in policy totals
+    /// (`trust.*`)
+    pub fn trust(&self) -> &TrustSettings {
+        &self.trust
+    }
+
+    /// Built-in commit policy rules (`policy.rules`)
+    pub fn policy(&self) -> &PolicySettings {
+        &self.policy
+    }
+
+    /// Credential store chosen in config (`credential_backend`); `None` leaves it
+    /// to the `auth_keyring` feature flag
+    pub fn credential_backend(&self) -> Option<CredentialBackendKind> {
+        self.credential_backend
+    }
+
+    /// Whether credentials may go to a plaintext file when the native store is
+    /// unavailable, e.g. on headless CI machines (`credential_file_fallback`)
+    pub fn credential_file_fallback(&self) -> bool {
+        self.credential_file_fallback
+    }
+
+    /// How long `git-ai watch` waits for edits to settle before checkpointing
+    /// (`watch_debounce_ms`)
+    pub fn watch_debounce(&self) -> Duration {
+        self.watch_debounce
+    }
+
+    /// Short name of the notes ref authorship logs are stored in (`notes_ref`)
+    pub fn notes_ref(&self) -> &str {
+        &self.notes_ref
+    }
+
+    /// Maximum API requests per second from one process (`api_max_rps`)
+    pub fn api_max_rps(&self) -> f64 {
+        self.api_max_rps
+    }
+
     /// Returns true if quiet mode is enabled (suppresses chart output after commits)
     pub fn is_quiet(&self) -> bool {
         self.quiet
@@ -424,7 +1520,18 @@ impl Config {
 }
 
 fn build_config() -> Config {
-    let file_cfg = load_file_config();
+    let mut file_cfg = load_file_config();
+    // `.git-ai.toml` and `.git/ai/config.toml` override repository settings
+    let (repo_layers, repo_warnings) = find_repo_config_layers_from_cwd();
+    for warning in &repo_warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if !repo_layers.is_empty() {
+        let cfg = file_cfg.get_or_insert_with(FileConfig::default);
+        for layer in &repo_layers {
+            layer.apply(cfg);
+        }
+    }
     let exclude_prompts_in_repositories = file_cfg
         .as_ref()
         .and_then(|c| c.exclude_prompts_in_repositories.clone())
@@ -574,9 +1681,202 @@ fn build_config() -> Config {
                 .filter(|s| !s.is_empty())
         });
 
+    let api_max_rps = match file_cfg.as_ref().and_then(|c| c.api_max_rps) {
+        Some(rps) if rps.is_finite() && rps > 0.0 => rps,
+        Some(rps) => {
+            eprintln!(
+                "Warning: Invalid api_max_rps value '{}', using {}",
+                rps, DEFAULT_API_MAX_RPS
+            );
+            DEFAULT_API_MAX_RPS
+        }
+        None => DEFAULT_API_MAX_RPS,
+    };
+
     // Get quiet setting (defaults to false)
     let quiet = file_cfg.as_ref().and_then(|c| c.quiet).unwrap_or(false);
 
+    // Get report time zone (warn on invalid values rather than silently using UTC)
+    let report_timezone = file_cfg
+        .as_ref()
+        .and_then(|c| c.report.as_ref())
+        .and_then(|r| r.timezone.clone())
+        .filter(|tz| {
+            let valid = tz.parse::<ReportTimezone>().is_ok();
+            if !valid {
+                eprintln!("Warning: Invalid report.timezone value '{}', using UTC", tz);
+            }
+            valid
+        });
+    let report_bot_authors = file_cfg
+        .as_ref()
+        .and_then(|c| c.report.as_ref())
+        .and_then(|r| r.bot_authors.clone())
+        .unwrap_or_default();
+    let report_authors = file_cfg
+        .as_ref()
+        .and_then(|c| c.report.as_ref())
+        .and_then(|r| r.authors.clone())
+        .unwrap_or_default();
+
+    // Get webhook settings; only http(s) endpoints are accepted
+    let events_webhook_url = file_cfg
+        .as_ref()
+        .and_then(|c| c.events.as_ref())
+        .and_then(|e| e.webhook_url.clone())
+        .filter(|url| {
+            let valid = url.starts_with("http://") || url.starts_with("https://");
+            if !valid {
+                eprintln!(
+                    "Warning: Invalid events.webhook_url value '{}', webhook disabled",
+                    url
+                );
+            }
+            valid
+        });
+    let events_webhook_events = file_cfg
+        .as_ref()
+        .and_then(|c| c.events.as_ref())
+        .and_then(|e| e.webhook_events.clone())
+        .unwrap_or_default();
+
+    // Dual-write destination: an absolute local directory, an http(s) endpoint or a bucket
+    let dual_write = file_cfg.as_ref().and_then(|c| c.dual_write.as_ref());
+    let dual_write_destination =
+        dual_write
+            .and_then(|d| d.destination.clone())
+            .filter(|destination| {
+                let valid = crate::api::sink::is_valid_destination(destination);
+                if !valid {
+                    eprintln!(
+                        "Warning: Invalid dual_write.destination value '{}', dual-write disabled",
+                        destination
+                    );
+                }
+                valid
+            });
+    let dual_write_headers = dual_write
+        .and_then(|d| d.headers.clone())
+        .unwrap_or_default();
+
+    let identity_aliases = file_cfg
+        .as_ref()
+        .and_then(|c| c.identities.as_ref())
+        .and_then(|i| i.aliases.clone())
+        .unwrap_or_default();
+    let identity_lookup_url = file_cfg
+        .as_ref()
+        .and_then(|c| c.identities.as_ref())
+        .and_then(|i| i.lookup_url.clone())
+        .filter(|url| !url.is_empty());
+
+    let context_capture = ContextCaptureSettings::from_file_config(
+        file_cfg.as_ref().and_then(|c| c.context_capture.as_ref()),
+    );
+
+    let redaction =
+        RedactionSettings::from_file_config(file_cfg.as_ref().and_then(|c| c.redaction.as_ref()));
+
+    let prompt_hashing = PromptHashingSettings::from_file_config(
+        file_cfg.as_ref().and_then(|c| c.prompt_hashing.as_ref()),
+    );
+
+    let performance_budgets = parse_performance_budgets(
+        file_cfg
+            .as_ref()
+            .and_then(|c| c.performance_budgets.as_deref())
+            .unwrap_or_default(),
+    );
+
+    let verify_push = file_cfg
+        .as_ref()
+        .and_then(|c| c.verify_push.as_deref())
+        .and_then(VerifyPushMode::parse)
+        .unwrap_or_default();
+
+    let blame_parallelism = file_cfg
+        .as_ref()
+        .and_then(|c| c.blame_parallelism)
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_BLAME_PARALLELISM);
+
+    let attribution_granularity = file_cfg
+        .as_ref()
+        .and_then(|c| c.attribution_granularity.as_deref())
+        .and_then(AttributionGranularity::parse)
+        .unwrap_or_default();
+
+    let ai_assisted_threshold = file_cfg
+        .as_ref()
+        .and_then(|c| c.ai_assisted_threshold)
+        .and_then(parse_ai_assisted_threshold)
+        .unwrap_or(DEFAULT_AI_ASSISTED_THRESHOLD);
+
+    let capture_environment = file_cfg
+        .as_ref()
+        .and_then(|c| c.capture_environment)
+        .unwrap_or(false);
+
+    let annotate_tags = file_cfg
+        .as_ref()
+        .and_then(|c| c.annotate_tags)
+        .unwrap_or(false);
+
+    let exclude_paths = file_cfg
+        .as_ref()
+        .and_then(|c| c.exclude_paths.clone())
+        .unwrap_or_default();
+
+    let tracking =
+        TrackingSettings::from_file_config(file_cfg.as_ref().and_then(|c| c.tracking.as_ref()));
+
+    let trust = TrustSettings::from_file_config(file_cfg.as_ref().and_then(|c| c.trust.as_ref()));
+
+    let policy =
+        PolicySettings::from_file_config(file_cfg.as_ref().and_then(|c| c.policy.as_ref()));
+
+    let notes_ref = match file_cfg.as_ref().and_then(|c| c.notes_ref.as_deref()) {
+        Some(value) => parse_notes_ref(value).unwrap_or_else(|| {
+            eprintln!(
+                "Warning: Invalid notes_ref value '{}', using '{}'",
+                value, AI_AUTHORSHIP_REFNAME
+            );
+            AI_AUTHORSHIP_REFNAME.to_string()
+        }),
+        None => AI_AUTHORSHIP_REFNAME.to_string(),
+    };
+
+    let credential_backend = match file_cfg
+        .as_ref()
+        .and_then(|c| c.credential_backend.as_deref())
+    {
+        Some(value) => CredentialBackendKind::parse(value).or_else(|| {
+            eprintln!(
+                "Warning: Invalid credential_backend value '{}', ignoring",
+                value
+            );
+            None
+        }),
+        None => None,
+    };
+
+    let credential_file_fallback = file_cfg
+        .as_ref()
+        .and_then(|c| c.credential_file_fallback)
+        .unwrap_or(false);
+
+    let watch_debounce = file_cfg
+        .as_ref()
+        .and_then(|c| c.watch_debounce_ms)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_WATCH_DEBOUNCE);
+
+    let jetbrains_plugin = JetBrainsPluginSettings::from_file_config(
+        file_cfg.as_ref().and_then(|c| c.jetbrains_plugin.as_ref()),
+    );
+    let history =
+        HistorySettings::from_file_config(file_cfg.as_ref().and_then(|c| c.history.as_ref()));
+
     #[cfg(any(test, feature = "test-support"))]
     {
         let mut config = Config {
@@ -595,7 +1895,37 @@ fn build_config() -> Config {
             prompt_storage,
             default_prompt_storage,
             api_key,
+            api_max_rps,
             quiet,
+            report_timezone,
+            report_bot_authors,
+            report_authors,
+            events_webhook_url,
+            events_webhook_events,
+            dual_write_destination,
+            dual_write_headers,
+            identity_aliases,
+            identity_lookup_url,
+            context_capture,
+            redaction,
+            prompt_hashing,
+            performance_budgets,
+            verify_push,
+            blame_parallelism,
+            attribution_granularity,
+            ai_assisted_threshold,
+            capture_environment,
+            annotate_tags,
+            exclude_paths,
+            tracking,
+            trust,
+            policy,
+            notes_ref,
+            credential_backend,
+            credential_file_fallback,
+            watch_debounce,
+            jetbrains_plugin,
+            history,
         };
         apply_test_config_patch(&mut config);
         config
@@ -618,10 +1948,75 @@ fn build_config() -> Config {
         prompt_storage,
         default_prompt_storage,
         api_key,
+        api_max_rps,
         quiet,
+        report_timezone,
+        report_bot_authors,
+        report_authors,
+        events_webhook_url,
+        events_webhook_events,
+        dual_write_destination,
+        dual_write_headers,
+        identity_aliases,
+        identity_lookup_url,
+        context_capture,
+        redaction,
+        prompt_hashing,
+        performance_budgets,
+        verify_push,
+        blame_parallelism,
+        attribution_granularity,
+        ai_assisted_threshold,
+        capture_environment,
+        annotate_tags,
+        exclude_paths,
+        tracking,
+        trust,
+        policy,
+        notes_ref,
+        credential_backend,
+        credential_file_fallback,
+        watch_debounce,
+        jetbrains_plugin,
+        history,
     }
 }
 
+/// Parse `performance_budgets` entries like `checkpoint<150ms` or `post_commit<2s`,
+/// warning about and skipping malformed ones
+fn parse_performance_budgets(specs: &[String]) -> BTreeMap<String, Duration> {
+    specs
+        .iter()
+        .filter_map(|spec| {
+            let parsed = parse_performance_budget(spec);
+            if parsed.is_none() {
+                eprintln!(
+                    "Warning: Invalid performance_budgets entry '{}', expected e.g. 'checkpoint<150ms'",
+                    spec
+                );
+            }
+            parsed
+        })
+        .collect()
+}
+
+fn parse_performance_budget(spec: &str) -> Option<(String, Duration)> {
+    let (operation, limit) = spec.split_once('<')?;
+    let operation = operation.trim();
+    if operation.is_empty() {
+        return None;
+    }
+    let limit = limit.trim();
+    let duration = if let Some(ms) = limit.strip_suffix("ms") {
+        Duration::from_millis(ms.trim().parse().ok()?)
+    } else if let Some(secs) = limit.strip_suffix('s') {
+        Duration::try_from_secs_f64(secs.trim().parse().ok()?).ok()?
+    } else {
+        Duration::from_millis(limit.parse().ok()?)
+    };
+    Some((operation.to_string(), duration))
+}
+
 fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
     let mut file_flags_value = file_cfg
         .as_ref()
@@ -646,39 +2041,27 @@ fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
 }
 
 fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
-    // 1) From config file
-    if let Some(cfg) = file_cfg
-        && let Some(path) = cfg.git_path.as_ref()
+    // 0) Test harness override, so the suite can run against a specific git build
+    #[cfg(any(test, feature = "test-support"))]
+    if let Ok(path) = env::var("GIT_AI_TEST_GIT_PATH")
+        && !path.trim().is_empty()
     {
-        let trimmed = path.trim();
-        if !trimmed.is_empty() {
-            let p = Path::new(trimmed);
-            if is_executable(p) {
-                return trimmed.to_string();
-            }
-        }
+        return path.trim().to_string();
     }
 
-    // 2) Probe common locations across platforms
-    let candidates: &[&str] = &[
-        // macOS Homebrew (ARM and Intel)
-        "/opt/homebrew/bin/git",
-        "/usr/local/bin/git",
-        // Common Unix paths
-        "/usr/bin/git",
-        "/bin/git",
-        "/usr/local/sbin/git",
-        "/usr/sbin/git",
-        // Windows Git for Windows
-        r"C:\\Program Files\\Git\\bin\\git.exe",
-        r"C:\\Program Files (x86)\\Git\\bin\\git.exe",
-    ];
+    // 1) The pinned binary. It is trusted as-is here (hashing it on every run
+    //    would be too slow); `git-ai git-path check` re-verifies it.
+    if let Some(pinned) = pinned_git_path(file_cfg.as_ref()) {
+        return pinned;
+    }
 
-    if let Some(found) = candidates.iter().map(Path::new).find(|p| is_executable(p)) {
+    // 2) From config file, then 3) common locations, skipping any that are git-ai
+    //    itself so a misconfigured system can't make git-ai run itself as git
+    if let Some(found) = real_git_candidates(file_cfg.as_ref()).into_iter().next() {
         return found.to_string_lossy().to_string();
     }
 
-    // 3) Fatal error: no real git found
+    // 4) Fatal error: no real git found
     eprintln!(
         "Fatal: Could not locate a real 'git' binary.\n\
          Expected a valid 'git_path' in {cfg_path} or in standard locations.\n\
@@ -690,10 +2073,89 @@ fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
     std::process::exit(1);
 }
 
+/// `git.path` from the config file, if it is still usable
+fn pinned_git_path(file_cfg: Option<&FileConfig>) -> Option<String> {
+    let path = file_cfg?
+        .git
+        .as_ref()?
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())?;
+    let binary = Path::new(path);
+    if is_executable(binary) && !crate::git::self_invocation::is_self(binary) {
+        return Some(path.to_string());
+    }
+    eprintln!(
+        "Warning: pinned git {} is missing or is git-ai itself; falling back to auto-detection. \
+         Run `git-ai git-path pin` to pin a new one.",
+        path
+    );
+    None
+}
+
+/// Real git binaries to use, best first: `git_path` from the config file, then
+/// common install locations. Paths that are the git-ai executable are left out.
+pub fn real_git_candidates(file_cfg: Option<&FileConfig>) -> Vec<PathBuf> {
+    let configured = file_cfg
+        .and_then(|cfg| cfg.git_path.as_deref())
+        .map(str::trim)
+        .filter(|path| !path.is_empty());
+    if let Some(path) = configured
+        && crate::git::self_invocation::is_self(Path::new(path))
+    {
+        eprintln!(
+            "Warning: git_path {} in {} is git-ai itself; ignoring it",
+            path,
+            config_file_path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| "~/.git-ai/config.json".to_string()),
+        );
+    }
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for path in configured
+        .into_iter()
+        .chain(crate::git::real_git::COMMON_GIT_LOCATIONS.iter().copied())
+    {
+        let path = PathBuf::from(path);
+        if is_executable(&path)
+            && !crate::git::self_invocation::is_self(&path)
+            && !candidates.contains(&path)
+        {
+            candidates.push(path);
+        }
+    }
+    candidates
+}
+
+/// [`real_git_candidates`] for the config file on disk
+pub fn real_git_candidates_from_config_file() -> Vec<PathBuf> {
+    real_git_candidates(load_file_config().as_ref())
+}
+
 fn load_file_config() -> Option<FileConfig> {
     let path = config_file_path()?;
     let data = fs::read(&path).ok()?;
-    serde_json::from_slice::<FileConfig>(&data).ok()
+    let local = serde_json::from_slice::<serde_json::Value>(&data).ok()?;
+    let file_cfg = serde_json::from_value::<FileConfig>(local.clone()).ok()?;
+
+    // Settings rolled out through `config.remote_url` sit beneath the local file
+    let Some(mut layered) = file_cfg
+        .config
+        .as_ref()
+        .and_then(crate::org_config::org_config_document)
+    else {
+        return Some(file_cfg);
+    };
+    crate::org_config::merge_over(&mut layered, local);
+    match serde_json::from_value::<FileConfig>(layered) {
+        Ok(layered) => Some(layered),
+        Err(e) => {
+            eprintln!("Warning: Ignoring org config from config.remote_url: {}", e);
+            Some(file_cfg)
+        }
+    }
 }
 
 fn config_file_path() -> Option<PathBuf> {
@@ -806,7 +2268,7 @@ pub fn save_file_config(config: &FileConfig) -> Result<(), String> {
     fs::write(&path, json).map_err(|e| format!("Failed to write config file: {}", e))
 }
 
-fn is_executable(path: &Path) -> bool {
+pub(crate) fn is_executable(path: &Path) -> bool {
     if !path.exists() || !path.is_file() {
         return false;
     }
@@ -846,6 +2308,62 @@ fn apply_test_config_patch(config: &mut Config) {
         if let Some(disable_auto_updates) = patch.disable_auto_updates {
             config.disable_auto_updates = disable_auto_updates;
         }
+        if let Some(budgets) = patch.performance_budgets {
+            config.performance_budgets = parse_performance_budgets(&budgets);
+        }
+        if let Some(verify_push) = patch.verify_push.as_deref().and_then(VerifyPushMode::parse) {
+            config.verify_push = verify_push;
+        }
+        if let Some(parallelism) = patch.blame_parallelism.filter(|n| *n > 0) {
+            config.blame_parallelism = parallelism;
+        }
+        if let Some(granularity) = patch
+            .attribution_granularity
+            .as_deref()
+            .and_then(AttributionGranularity::parse)
+        {
+            config.attribution_granularity = granularity;
+        }
+        if let Some(threshold) = patch
+            .ai_assisted_threshold
+            .and_then(parse_ai_assisted_threshold)
+        {
+            config.ai_assisted_threshold = threshold;
+        }
+        if let Some(capture) = patch.capture_environment {
+            config.capture_environment = capture;
+        }
+        if let Some(annotate) = patch.annotate_tags {
+            config.annotate_tags = annotate;
+        }
+        if let Some(exclude_paths) = patch.exclude_paths {
+            config.exclude_paths = exclude_paths;
+        }
+        if let Some(tracking) = patch.tracking {
+            config.tracking = TrackingSettings::from_file_config(Some(&tracking));
+        }
+        if let Some(trust) = patch.trust {
+            config.trust = TrustSettings::from_file_config(Some(&trust));
+        }
+        if let Some(policy) = patch.policy {
+            config.policy = PolicySettings::from_file_config(Some(&policy));
+        }
+        if let Some(dual_write) = patch.dual_write {
+            config.dual_write_destination = dual_write.destination;
+            config.dual_write_headers = dual_write.headers.unwrap_or_default();
+        }
+        if let Some(notes_ref) = patch.notes_ref.as_deref().and_then(parse_notes_ref) {
+            config.notes_ref = notes_ref;
+        }
+        if let Some(rules) = patch.redaction_rules {
+            config.redaction.rules = rules;
+        }
+        if let Some(prompt_hashing) = patch.prompt_hashing {
+            config.prompt_hashing = PromptHashingSettings::from_file_config(Some(&prompt_hashing));
+        }
+        if let Some(history) = patch.history {
+            config.history = HistorySettings::from_file_config(Some(&history));
+        }
         if let Some(prompt_storage) = patch.prompt_storage {
             // Validate the value
             if matches!(prompt_storage.as_str(), "default" | "notes" | "local") {
@@ -890,7 +2408,37 @@ mod tests {
             prompt_storage: "default".to_string(),
             default_prompt_storage: None,
             api_key: None,
+            api_max_rps: DEFAULT_API_MAX_RPS,
             quiet: false,
+            report_timezone: None,
+            report_bot_authors: Vec::new(),
+            report_authors: Vec::new(),
+            events_webhook_url: None,
+            events_webhook_events: Vec::new(),
+            dual_write_destination: None,
+            dual_write_headers: BTreeMap::new(),
+            identity_aliases: BTreeMap::new(),
+            identity_lookup_url: None,
+            context_capture: ContextCaptureSettings::default(),
+            redaction: RedactionSettings::default(),
+            prompt_hashing: PromptHashingSettings::default(),
+            performance_budgets: BTreeMap::new(),
+            verify_push: VerifyPushMode::Off,
+            blame_parallelism: DEFAULT_BLAME_PARALLELISM,
+            attribution_granularity: AttributionGranularity::Line,
+            ai_assisted_threshold: DEFAULT_AI_ASSISTED_THRESHOLD,
+            capture_environment: false,
+            annotate_tags: false,
+            exclude_paths: vec![],
+            tracking: TrackingSettings::default(),
+            trust: TrustSettings::default(),
+            policy: PolicySettings::default(),
+            notes_ref: AI_AUTHORSHIP_REFNAME.to_string(),
+            credential_backend: None,
+            credential_file_fallback: false,
+            watch_debounce: DEFAULT_WATCH_DEBOUNCE,
+            jetbrains_plugin: JetBrainsPluginSettings::default(),
+            history: HistorySettings::default(),
         }
     }
 
@@ -997,7 +2545,37 @@ mod tests {
             prompt_storage: "default".to_string(),
             default_prompt_storage: None,
             api_key: None,
+            api_max_rps: DEFAULT_API_MAX_RPS,
             quiet: false,
+            report_timezone: None,
+            report_bot_authors: Vec::new(),
+            report_authors: Vec::new(),
+            events_webhook_url: None,
+            events_webhook_events: Vec::new(),
+            dual_write_destination: None,
+            dual_write_headers: BTreeMap::new(),
+            identity_aliases: BTreeMap::new(),
+            identity_lookup_url: None,
+            context_capture: ContextCaptureSettings::default(),
+            redaction: RedactionSettings::default(),
+            prompt_hashing: PromptHashingSettings::default(),
+            performance_budgets: BTreeMap::new(),
+            verify_push: VerifyPushMode::Off,
+            blame_parallelism: DEFAULT_BLAME_PARALLELISM,
+            attribution_granularity: AttributionGranularity::Line,
+            ai_assisted_threshold: DEFAULT_AI_ASSISTED_THRESHOLD,
+            capture_environment: false,
+            annotate_tags: false,
+            exclude_paths: vec![],
+            tracking: TrackingSettings::default(),
+            trust: TrustSettings::default(),
+            policy: PolicySettings::default(),
+            notes_ref: AI_AUTHORSHIP_REFNAME.to_string(),
+            credential_backend: None,
+            credential_file_fallback: false,
+            watch_debounce: DEFAULT_WATCH_DEBOUNCE,
+            jetbrains_plugin: JetBrainsPluginSettings::default(),
+            history: HistorySettings::default(),
         }
     }
 
@@ -1113,7 +2691,37 @@ mod tests {
             prompt_storage: prompt_storage.to_string(),
             default_prompt_storage: default_prompt_storage.map(|s| s.to_string()),
             api_key: None,
+            api_max_rps: DEFAULT_API_MAX_RPS,
             quiet: false,
+            report_timezone: None,
+            report_bot_authors: Vec::new(),
+            report_authors: Vec::new(),
+            events_webhook_url: None,
+            events_webhook_events: Vec::new(),
+            dual_write_destination: None,
+            dual_write_headers: BTreeMap::new(),
+            identity_aliases: BTreeMap::new(),
+            identity_lookup_url: None,
+            context_capture: ContextCaptureSettings::default(),
+            redaction: RedactionSettings::default(),
+            prompt_hashing: PromptHashingSettings::default(),
+            performance_budgets: BTreeMap::new(),
+            verify_push: VerifyPushMode::Off,
+            blame_parallelism: DEFAULT_BLAME_PARALLELISM,
+            attribution_granularity: AttributionGranularity::Line,
+            ai_assisted_threshold: DEFAULT_AI_ASSISTED_THRESHOLD,
+            capture_environment: false,
+            annotate_tags: false,
+            exclude_paths: vec![],
+            tracking: TrackingSettings::default(),
+            trust: TrustSettings::default(),
+            policy: PolicySettings::default(),
+            notes_ref: AI_AUTHORSHIP_REFNAME.to_string(),
+            credential_backend: None,
+            credential_file_fallback: false,
+            watch_debounce: DEFAULT_WATCH_DEBOUNCE,
+            jetbrains_plugin: JetBrainsPluginSettings::default(),
+            history: HistorySettings::default(),
         }
     }
 
@@ -1398,4 +3006,86 @@ mod tests {
         ];
         assert!(!config.is_allowed_repository_with_remotes(Some(&remotes)));
     }
+
+    #[test]
+    fn test_parse_performance_budgets() {
+        let budgets = parse_performance_budgets(&[
+            "checkpoint<150ms".to_string(),
+            "post_commit < 2s".to_string(),
+            "pre_rebase<500".to_string(),
+            "bogus".to_string(),
+            "<10ms".to_string(),
+            "fetch<-1s".to_string(),
+        ]);
+        assert_eq!(
+            budgets,
+            BTreeMap::from([
+                ("checkpoint".to_string(), Duration::from_millis(150)),
+                ("post_commit".to_string(), Duration::from_secs(2)),
+                ("pre_rebase".to_string(), Duration::from_millis(500)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_trust_tier_for_agents() {
+        let trust = TrustSettings::from_file_config(Some(&TrustConfig {
+            trusted: Some(vec!["claude".to_string(), "cursor/acme-*".to_string()]),
+            untrusted: Some(vec!["claude/*-preview".to_string()]),
+            default: Some("untrusted".to_string()),
+            weights: Some(BTreeMap::from([
+                ("trusted".to_string(), 0.25),
+                ("bogus".to_string(), 3.0),
+            ])),
+        }));
+
+        assert_eq!(trust.tier_for("claude", "sonnet-4"), TrustTier::Trusted);
+        assert_eq!(
+            trust.tier_for("claude", "opus-preview"),
+            TrustTier::Untrusted
+        );
+        assert_eq!(trust.tier_for("cursor", "acme-coder"), TrustTier::Trusted);
+        assert_eq!(trust.tier_for("cursor", "gpt-5"), TrustTier::Untrusted);
+        assert_eq!(trust.weight(TrustTier::Trusted), 0.25);
+        assert_eq!(trust.weight(TrustTier::Untrusted), 2.0);
+
+        let defaults = TrustSettings::default();
+        assert_eq!(defaults.tier_for("anything", "at-all"), TrustTier::Standard);
+        assert_eq!(defaults.weight(TrustTier::Standard), 1.0);
+    }
+
+    #[test]
+    fn test_policy_rules_are_validated() {
+        let policy = PolicySettings::from_file_config(Some(&PolicyConfig {
+            rules: Some(vec![
+                PolicyRuleConfig {
+                    paths: Some(vec!["src/crypto/**".to_string()]),
+                    max_ai_percent: Some(80.0),
+                    override_code: Some("SEC-42".to_string()),
+                    ..Default::default()
+                },
+                PolicyRuleConfig {
+                    name: Some("too-high".to_string()),
+                    max_ai_percent: Some(150.0),
+                    ..Default::default()
+                },
+                PolicyRuleConfig {
+                    name: Some("no-checks".to_string()),
+                    ..Default::default()
+                },
+                PolicyRuleConfig {
+                    name: Some("transcripts".to_string()),
+                    require_transcript: Some(true),
+                    ..Default::default()
+                },
+            ]),
+        }));
+
+        assert_eq!(policy.rules.len(), 2);
+        assert_eq!(policy.rules[0].name, "rule-1");
+        assert!(policy.rules[0].matches_path("src/crypto/aes/gcm.rs"));
+        assert!(!policy.rules[0].matches_path("src/lib.rs"));
+        assert_eq!(policy.rules[1].name, "transcripts");
+        assert!(policy.rules[1].matches_path("anything.rs"));
+    }
 }
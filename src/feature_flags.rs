@@ -52,9 +52,12 @@ macro_rules! define_feature_flags {
 // Define all feature flags in one place
 // Format: struct_field: file_and_env_name, debug = <bool>, release = <bool>
 define_feature_flags!(
-    rewrite_stash: rewrite_stash, debug = true, release = false,
+    rewrite_stash: rewrite_stash, debug = true, release = true,
     inter_commit_move: checkpoint_inter_commit_move, debug = false, release = false,
     auth_keyring: auth_keyring, debug = false, release = false,
+    warm_blame_cache: warm_blame_cache_on_fetch, debug = false, release = false,
+    intern_prompts: intern_prompts, debug = false, release = false,
+    infer_agent: checkpoint_infer_agent, debug = false, release = false,
 );
 
 impl FeatureFlags {
@@ -121,12 +124,18 @@ mod tests {
             assert!(flags.rewrite_stash);
             assert!(!flags.inter_commit_move);
             assert!(!flags.auth_keyring);
+            assert!(!flags.warm_blame_cache);
+            assert!(!flags.intern_prompts);
+            assert!(!flags.infer_agent);
         }
         #[cfg(not(debug_assertions))]
         {
-            assert!(!flags.rewrite_stash);
+            assert!(flags.rewrite_stash);
             assert!(!flags.inter_commit_move);
             assert!(!flags.auth_keyring);
+            assert!(!flags.warm_blame_cache);
+            assert!(!flags.intern_prompts);
+            assert!(!flags.infer_agent);
         }
     }
 
@@ -221,6 +230,9 @@ mod tests {
             rewrite_stash: true,
             inter_commit_move: false,
             auth_keyring: true,
+            warm_blame_cache: false,
+            intern_prompts: false,
+            infer_agent: false,
         };
 
         let serialized = serde_json::to_string(&flags).unwrap();
@@ -235,6 +247,9 @@ mod tests {
             rewrite_stash: true,
             inter_commit_move: false,
             auth_keyring: true,
+            warm_blame_cache: false,
+            intern_prompts: false,
+            infer_agent: false,
         };
         let cloned = flags.clone();
         assert_eq!(cloned.rewrite_stash, flags.rewrite_stash);
@@ -2,10 +2,15 @@ use std::collections::HashSet;
 
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::error::GitAiError;
+#[cfg(test)]
+use crate::git::refs::authorship_notes_ref_arg;
 use crate::git::refs::{commits_with_authorship_notes, note_blob_oids_for_commits};
 #[cfg(test)]
 use crate::git::repository::exec_git;
-use crate::git::repository::{Repository, exec_git_stdin};
+use crate::git::repository::{
+    Repository, exec_git_stdin, exec_git_stdin_with_env, promisor_remote,
+};
+use crate::utils::debug_log;
 
 pub async fn load_ai_touched_files_for_commits(
     repo: &Repository,
@@ -61,7 +66,7 @@ pub fn commits_have_authorship_notes(
 fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAiError> {
     let mut args = global_args.to_vec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(authorship_notes_ref_arg());
     args.push("list".to_string());
 
     let output = match exec_git(&args) {
@@ -90,7 +95,7 @@ fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAi
     Ok(mappings)
 }
 
-fn batch_read_blobs_with_oids(
+pub(crate) fn batch_read_blobs_with_oids(
     global_args: &[String],
     blob_oids: &[String],
 ) -> Result<std::collections::HashMap<String, String>, GitAiError> {
@@ -98,20 +103,107 @@ fn batch_read_blobs_with_oids(
         return Ok(std::collections::HashMap::new());
     }
 
+    let output = cat_file_batch(global_args, blob_oids)?;
+    parse_cat_file_batch_output_with_oids(&output)
+}
+
+/// Runs `cat-file --batch` over `oids`. A partial clone would otherwise fetch each blob
+/// it left out in its own round trip, so the first pass doesn't fetch at all; the oids
+/// it reports as `<oid> missing` are fetched from the promisor remote in one batch and
+/// read again. Git versions that die on an unfetched promisor object instead of
+/// reporting it don't say which ones are missing, so all of `oids` are fetched.
+pub(crate) fn cat_file_batch(
+    global_args: &[String],
+    oids: &[String],
+) -> Result<Vec<u8>, GitAiError> {
     let mut args = global_args.to_vec();
     args.push("cat-file".to_string());
     args.push("--batch".to_string());
+    let stdin_data = oids.join("\n") + "\n";
+
+    let no_lazy_fetch = [("GIT_NO_LAZY_FETCH".to_string(), "1".to_string())];
+    let (mut output, missing) =
+        match exec_git_stdin_with_env(&args, &no_lazy_fetch, stdin_data.as_bytes()) {
+            Ok(output) => {
+                let missing = missing_oids_in_batch_output(&output.stdout)?;
+                if missing.is_empty() {
+                    return Ok(output.stdout);
+                }
+                (output.stdout, missing)
+            }
+            Err(err) if promisor_remote(global_args).is_none() => return Err(err),
+            Err(_) => (Vec::new(), oids.to_vec()),
+        };
+    let Some(remote) = promisor_remote(global_args) else {
+        return Ok(output);
+    };
+    fetch_promised_objects(global_args, &remote, &missing);
+    // The first pass's missing entries stay in the output; parsers skip them
+    let stdin_data = missing.join("\n") + "\n";
+    output.extend(exec_git_stdin(&args, stdin_data.as_bytes())?.stdout);
+    Ok(output)
+}
 
-    let stdin_data = blob_oids.join("\n") + "\n";
-    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
+/// Fetches `oids` from a partial clone's promisor remote in one request. Failures are
+/// only logged: reading the objects afterwards falls back to fetching them one by one.
+fn fetch_promised_objects(global_args: &[String], remote: &str, oids: &[String]) {
+    let mut wanted = oids.to_vec();
+    wanted.sort();
+    wanted.dedup();
 
-    parse_cat_file_batch_output_with_oids(&output.stdout)
+    let mut args = global_args.to_vec();
+    args.push("-c".to_string());
+    args.push("fetch.negotiationAlgorithm=noop".to_string());
+    args.push("fetch".to_string());
+    args.push(remote.to_string());
+    args.push("--no-tags".to_string());
+    args.push("--no-write-fetch-head".to_string());
+    args.push("--recurse-submodules=no".to_string());
+    args.push("--filter=blob:none".to_string());
+    args.push("--stdin".to_string());
+    let stdin_data = wanted.join("\n") + "\n";
+    if let Err(e) = exec_git_stdin(&args, stdin_data.as_bytes()) {
+        debug_log(&format!(
+            "Failed to fetch {} objects from promisor remote {}: {}",
+            wanted.len(),
+            remote,
+            e
+        ));
+    }
 }
 
 fn parse_cat_file_batch_output_with_oids(
     data: &[u8],
 ) -> Result<std::collections::HashMap<String, String>, GitAiError> {
     let mut results = std::collections::HashMap::new();
+    for_each_batch_entry(data, |oid, content| {
+        if let Some(content) = content {
+            results.insert(
+                oid.to_string(),
+                String::from_utf8_lossy(content).to_string(),
+            );
+        }
+    })?;
+    Ok(results)
+}
+
+/// The oids `cat-file --batch` output reports as `<oid> missing`
+fn missing_oids_in_batch_output(data: &[u8]) -> Result<Vec<String>, GitAiError> {
+    let mut missing = Vec::new();
+    for_each_batch_entry(data, |oid, content| {
+        if content.is_none() {
+            missing.push(oid.to_string());
+        }
+    })?;
+    Ok(missing)
+}
+
+/// Walk `cat-file --batch` output, calling `f` with each oid and its content (`None`
+/// for a missing object). Malformed headers are skipped.
+fn for_each_batch_entry(
+    data: &[u8],
+    mut f: impl FnMut(&str, Option<&[u8]>),
+) -> Result<(), GitAiError> {
     let mut pos = 0usize;
 
     while pos < data.len() {
@@ -127,8 +219,8 @@ fn parse_cat_file_batch_output_with_oids(
             continue;
         }
 
-        let oid = parts[0].to_string();
         if parts[1] == "missing" {
+            f(parts[0], None);
             pos = header_end + 1;
             continue;
         }
@@ -150,8 +242,7 @@ fn parse_cat_file_batch_output_with_oids(
             ));
         }
 
-        let content = String::from_utf8_lossy(&data[content_start..content_end]).to_string();
-        results.insert(oid, content);
+        f(parts[0], Some(&data[content_start..content_end]));
 
         pos = content_end;
         if pos < data.len() && data[pos] == b'\n' {
@@ -159,7 +250,7 @@ fn parse_cat_file_batch_output_with_oids(
         }
     }
 
-    Ok(results)
+    Ok(())
 }
 
 /// Extract file paths from a note blob content
@@ -318,6 +409,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_missing_oids_in_batch_output() {
+        // A blob whose content looks like a missing line isn't mistaken for one
+        let data = b"abc123 missing\ndef456 blob 15\nfff999 missing\n\n789abc missing\n";
+        assert_eq!(
+            missing_oids_in_batch_output(data).unwrap(),
+            vec!["abc123".to_string(), "789abc".to_string()]
+        );
+        assert!(
+            missing_oids_in_batch_output(b"abc123 blob 5\nhello\n")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_batch_read_blobs_with_oids_without_promisor_skips_missing() {
+        let repo = crate::git::test_utils::TmpRepo::new().unwrap();
+        let missing = "1111111111111111111111111111111111111111".to_string();
+        let blobs = batch_read_blobs_with_oids(
+            &repo.gitai_repo().global_args_for_exec(),
+            std::slice::from_ref(&missing),
+        )
+        .unwrap();
+        assert!(blobs.is_empty());
+    }
+
     #[test]
     fn test_parse_cat_file_batch_output_single_blob() {
         let data = b"abc123 blob 11\nhello world\n";
@@ -364,6 +482,73 @@ mod tests {
         assert!(result.is_empty(), "Empty OID list should return empty map");
     }
 
+    #[test]
+    fn test_batch_read_blobs_with_oids_fetches_blobs_left_out_of_partial_clone() {
+        let source = crate::git::test_utils::TmpRepo::new().unwrap();
+        source.write_file("f.txt", "first\n", true).unwrap();
+        source.commit_with_message("first").unwrap();
+        source.write_file("f.txt", "second\n", true).unwrap();
+        source.commit_with_message("second").unwrap();
+        source
+            .git_command(&["config", "uploadpack.allowFilter", "true"])
+            .unwrap();
+        source
+            .git_command(&["config", "uploadpack.allowAnySHA1InWant", "true"])
+            .unwrap();
+
+        let mut rev_parse = source.gitai_repo().global_args_for_exec();
+        rev_parse.push("rev-parse".to_string());
+        rev_parse.push("HEAD~1:f.txt".to_string());
+        let old_blob = String::from_utf8(exec_git(&rev_parse).unwrap().stdout)
+            .unwrap()
+            .trim()
+            .to_string();
+
+        let clone = source.clone_with_args(&["--filter=blob:none"]).unwrap();
+        let global_args = clone.gitai_repo().global_args_for_exec();
+        assert_eq!(promisor_remote(&global_args).as_deref(), Some("origin"));
+        assert_eq!(
+            promisor_remote(&source.gitai_repo().global_args_for_exec()),
+            None
+        );
+
+        let blobs =
+            batch_read_blobs_with_oids(&global_args, std::slice::from_ref(&old_blob)).unwrap();
+        assert_eq!(blobs.get(&old_blob).map(String::as_str), Some("first\n"));
+    }
+
+    #[test]
+    fn test_batch_read_blobs_with_oids_refetches_only_missing_oids_in_partial_clone() {
+        let source = crate::git::test_utils::TmpRepo::new().unwrap();
+        source.write_file("f.txt", "present\n", true).unwrap();
+        source.commit_with_message("first").unwrap();
+        source
+            .git_command(&["config", "uploadpack.allowFilter", "true"])
+            .unwrap();
+        let clone = source.clone_with_args(&["--filter=blob:none"]).unwrap();
+        let global_args = clone.gitai_repo().global_args_for_exec();
+
+        // Checked out, so local; the other oid exists nowhere
+        let mut rev_parse = global_args.clone();
+        rev_parse.push("rev-parse".to_string());
+        rev_parse.push("HEAD:f.txt".to_string());
+        let present = String::from_utf8(exec_git(&rev_parse).unwrap().stdout)
+            .unwrap()
+            .trim()
+            .to_string();
+        let unknown = "1111111111111111111111111111111111111111".to_string();
+
+        let output = cat_file_batch(&global_args, &[present.clone(), unknown.clone()]).unwrap();
+        // The first pass reports it missing, the refetch can't find it either
+        assert_eq!(
+            missing_oids_in_batch_output(&output).unwrap(),
+            vec![unknown.clone(), unknown]
+        );
+        let blobs = parse_cat_file_batch_output_with_oids(&output).unwrap();
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs.get(&present).map(String::as_str), Some("present\n"));
+    }
+
     #[test]
     fn test_extract_file_paths_from_note_empty() {
         let mut files = HashSet::new();
@@ -537,6 +537,81 @@ pub fn extract_clone_target_directory(args: &[String]) -> Option<String> {
     derive_directory_from_url(repo_url)
 }
 
+/// What a `git restore` invocation overwrites, from its command args.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RestoreArgs {
+    /// `-W`/`--worktree`, or implied when neither it nor `--staged` is given
+    pub worktree: bool,
+    /// `-S`/`--staged`
+    pub staged: bool,
+    /// `-s`/`--source`; the index (or HEAD with only `--staged`) when absent
+    pub source: Option<String>,
+    /// `-p`/`--patch`: only some hunks of the pathspecs are restored
+    pub patch: bool,
+    /// `--pathspec-from-file` was given, so `pathspecs` is incomplete
+    pub pathspec_from_file: bool,
+    pub pathspecs: Vec<String>,
+}
+
+/// Parse the arguments after `git restore`. Unlike checkout, restore takes
+/// pathspecs without a `--` separator.
+pub fn parse_restore_args(args: &[String]) -> RestoreArgs {
+    let mut restore = RestoreArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        i += 1;
+        match arg {
+            "--" => {
+                restore.pathspecs.extend(args[i..].iter().cloned());
+                break;
+            }
+            "-W" | "--worktree" => restore.worktree = true,
+            "-S" | "--staged" => restore.staged = true,
+            "-p" | "--patch" => restore.patch = true,
+            "-s" | "--source" => {
+                restore.source = args.get(i).cloned();
+                i += 1;
+            }
+            "--pathspec-from-file" => {
+                restore.pathspec_from_file = true;
+                i += 1;
+            }
+            _ if arg.starts_with("--source=") => {
+                restore.source = Some(arg["--source=".len()..].to_string());
+            }
+            _ if arg.starts_with("--pathspec-from-file=") => restore.pathspec_from_file = true,
+            _ if arg.starts_with("--") => {}
+            // Clustered short flags such as -SW, or -s<tree>
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                for (pos, flag) in arg.char_indices().skip(1) {
+                    match flag {
+                        'W' => restore.worktree = true,
+                        'S' => restore.staged = true,
+                        'p' => restore.patch = true,
+                        's' => {
+                            let rest = &arg[pos + 1..];
+                            if rest.is_empty() {
+                                restore.source = args.get(i).cloned();
+                                i += 1;
+                            } else {
+                                restore.source = Some(rest.to_string());
+                            }
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => restore.pathspecs.push(arg.to_string()),
+        }
+    }
+    if !restore.staged {
+        restore.worktree = true;
+    }
+    restore
+}
+
 /// Derive the target directory name from a repository URL.
 /// Mimics git's behavior of using the last path component, stripping .git suffix.
 fn derive_directory_from_url(url: &str) -> Option<String> {
@@ -641,6 +716,45 @@ mod tests {
         assert_eq!(parsed.pos_command(0), Some("abc".to_string()));
     }
 
+    fn restore(args: &[&str]) -> RestoreArgs {
+        parse_restore_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_restore_args_targets() {
+        let parsed = restore(&["a.txt", "src/"]);
+        assert!(parsed.worktree && !parsed.staged);
+        assert_eq!(parsed.pathspecs, vec!["a.txt", "src/"]);
+
+        let parsed = restore(&["--staged", "a.txt"]);
+        assert!(parsed.staged && !parsed.worktree);
+
+        let parsed = restore(&["-SW", "--", "-odd-name"]);
+        assert!(parsed.staged && parsed.worktree);
+        assert_eq!(parsed.pathspecs, vec!["-odd-name"]);
+    }
+
+    #[test]
+    fn test_parse_restore_args_source_forms() {
+        assert_eq!(
+            restore(&["--source", "main", "a.txt"]).source.as_deref(),
+            Some("main")
+        );
+        assert_eq!(
+            restore(&["--source=HEAD~2", "a.txt"]).source.as_deref(),
+            Some("HEAD~2")
+        );
+        let parsed = restore(&["-Ws", "v1.0", "a.txt"]);
+        assert_eq!(parsed.source.as_deref(), Some("v1.0"));
+        assert_eq!(parsed.pathspecs, vec!["a.txt"]);
+        assert_eq!(
+            restore(&["-sabc123", "a.txt"]).source.as_deref(),
+            Some("abc123")
+        );
+        assert!(restore(&["-p", "a.txt"]).patch);
+        assert!(restore(&["--pathspec-from-file=list"]).pathspec_from_file);
+    }
+
     #[test]
     fn test_derive_directory_from_url() {
         assert_eq!(
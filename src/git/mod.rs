@@ -1,5 +1,6 @@
 pub mod cli_parser;
 pub mod diff_tree_to_tree;
+pub mod push_updates;
 pub mod refs;
 pub mod repository;
 
@@ -10,8 +11,10 @@ pub use repository::{
     find_repository, find_repository_for_file, find_repository_in_path, from_bare_repository,
     group_files_by_repository,
 };
+pub mod real_git;
 pub mod repo_storage;
 pub mod rewrite_log;
+pub mod self_invocation;
 pub mod status;
 pub mod sync_authorship;
 
@@ -0,0 +1,174 @@
+//! The ref updates a push makes, taken from the refspecs given to `git push` or
+//! from the lines git passes a pre-push hook on stdin, and the commits they send.
+
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+
+const ZERO_OID: &str = "0000000000000000000000000000000000000000";
+
+/// One ref a push updates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushUpdate {
+    /// Commit the remote ref will point at
+    pub local_sha: String,
+    /// Commit the remote ref points at now, when known
+    pub remote_sha: Option<String>,
+}
+
+impl PushUpdate {
+    /// Parse pre-push hook input: `<local ref> <local sha> <remote ref> <remote sha>`
+    /// per line. Deletions (an all-zero local sha) push no commits and are dropped.
+    pub fn parse_pre_push_input(input: &str) -> Vec<PushUpdate> {
+        input
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [_, local_sha, _, remote_sha] = fields[..] else {
+                    return None;
+                };
+                (local_sha != ZERO_OID).then(|| PushUpdate {
+                    local_sha: local_sha.to_string(),
+                    remote_sha: (remote_sha != ZERO_OID).then(|| remote_sha.to_string()),
+                })
+            })
+            .collect()
+    }
+
+    /// Resolve the source side of `refspecs` (`HEAD` when there are none) to the
+    /// commits they push. Deletions (`:dst`) push nothing; sources with a `*`
+    /// expand to every matching local ref.
+    pub fn from_refspecs(
+        repo: &Repository,
+        refspecs: &[String],
+    ) -> Result<Vec<PushUpdate>, GitAiError> {
+        let default = ["HEAD".to_string()];
+        let refspecs = if refspecs.is_empty() {
+            &default[..]
+        } else {
+            refspecs
+        };
+
+        let mut updates = Vec::new();
+        for refspec in refspecs {
+            let refspec = refspec.strip_prefix('+').unwrap_or(refspec);
+            let source = refspec.split_once(':').map_or(refspec, |(src, _)| src);
+            if source.is_empty() {
+                continue;
+            }
+            let commits = if source.contains('*') {
+                matching_ref_commits(repo, source)?
+            } else {
+                vec![resolve_commit(repo, source)?]
+            };
+            updates.extend(commits.into_iter().map(|local_sha| PushUpdate {
+                local_sha,
+                remote_sha: None,
+            }));
+        }
+        updates.dedup();
+        Ok(updates)
+    }
+}
+
+/// `git log`/`rev-list` revisions selecting the commits `updates` send to
+/// `remote`: those reachable from the pushed commits that neither the remote
+/// refs being replaced nor `remote`'s tracking refs already have. `None` when
+/// the push sends no commits.
+pub fn pushed_revision_args(
+    repo: &Repository,
+    remote: Option<&str>,
+    updates: &[PushUpdate],
+) -> Option<Vec<String>> {
+    if updates.is_empty() {
+        return None;
+    }
+    let mut args: Vec<String> = updates.iter().map(|u| u.local_sha.clone()).collect();
+    args.push("--not".to_string());
+    // A remote tip this clone hasn't fetched can't be named in the range
+    args.extend(
+        updates
+            .iter()
+            .filter_map(|u| u.remote_sha.clone())
+            .filter(|sha| resolve_commit(repo, sha).is_ok()),
+    );
+    args.push(match remote {
+        Some(remote) => format!("--remotes={}", remote),
+        None => "--remotes".to_string(),
+    });
+    Some(args)
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ]
+        .map(String::from),
+    );
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Commits the local refs matching `pattern` point at, peeling annotated tags
+fn matching_ref_commits(repo: &Repository, pattern: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "for-each-ref",
+            "--format=%(objecttype) %(objectname) %(*objecttype) %(*objectname)",
+            pattern,
+        ]
+        .map(String::from),
+    );
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout
+        .lines()
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["commit", sha, ..] | [_, _, "commit", sha] => Some(sha.to_string()),
+                _ => None,
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pre_push_input() {
+        let input = format!(
+            "refs/heads/main {} refs/heads/main {}\nrefs/heads/new {} refs/heads/new {}\n(delete) {} refs/heads/old {}\n",
+            "a".repeat(40),
+            "b".repeat(40),
+            "c".repeat(40),
+            ZERO_OID,
+            ZERO_OID,
+            "d".repeat(40),
+        );
+        assert_eq!(
+            PushUpdate::parse_pre_push_input(&input),
+            vec![
+                PushUpdate {
+                    local_sha: "a".repeat(40),
+                    remote_sha: Some("b".repeat(40)),
+                },
+                PushUpdate {
+                    local_sha: "c".repeat(40),
+                    remote_sha: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pre_push_input_skips_malformed_lines() {
+        assert!(PushUpdate::parse_pre_push_input("\nrefs/heads/main abc\n").is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! Discovery and pinning of the real git binary that git-ai runs.
+//!
+//! Unpinned, git-ai runs `git_path` or the first common install location that
+//! exists (see `Config::git_cmd`), which silently changes when PATH or an install
+//! location does. `git-ai git-path pin` records a verified binary's path, version
+//! and SHA-256 under `git` in the config file. git-ai then always runs that
+//! binary, and [`check_pin`] reports when it has been replaced or removed (an OS
+//! or Homebrew upgrade, say) so it can be re-pinned deliberately.
+
+use crate::config::{FileConfig, GitPinConfig, is_executable};
+use crate::git::self_invocation::{is_self, mark_git_child};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Install locations probed for git, after `$PATH`
+pub const COMMON_GIT_LOCATIONS: &[&str] = &[
+    // macOS Homebrew (ARM and Intel)
+    "/opt/homebrew/bin/git",
+    "/usr/local/bin/git",
+    // Common Unix paths
+    "/usr/bin/git",
+    "/bin/git",
+    "/usr/local/sbin/git",
+    "/usr/sbin/git",
+    // Windows Git for Windows
+    r"C:\\Program Files\\Git\\bin\\git.exe",
+    r"C:\\Program Files (x86)\\Git\\bin\\git.exe",
+];
+
+/// Where a candidate git was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CandidateSource {
+    /// `git.path`
+    Pinned,
+    /// `git_path`
+    Configured,
+    /// A `$PATH` entry
+    Path,
+    /// One of [`COMMON_GIT_LOCATIONS`]
+    Common,
+}
+
+impl CandidateSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandidateSource::Pinned => "pinned",
+            CandidateSource::Configured => "git_path",
+            CandidateSource::Path => "PATH",
+            CandidateSource::Common => "common location",
+        }
+    }
+}
+
+/// A git binary that answered `--version`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitCandidate {
+    pub path: PathBuf,
+    pub source: CandidateSource,
+    /// e.g. "2.43.0"
+    pub version: String,
+}
+
+/// Every usable git binary, best first: the pinned one, then `git_path`, then
+/// auto-detected ones by newest version (PATH order breaking ties). Binaries that
+/// are git-ai itself, or that don't report a git version, are left out.
+pub fn discover_candidates(file_cfg: Option<&FileConfig>) -> Vec<GitCandidate> {
+    let pinned = file_cfg
+        .and_then(|cfg| cfg.git.as_ref())
+        .and_then(|git| git.path.as_deref());
+    let configured = file_cfg.and_then(|cfg| cfg.git_path.as_deref());
+
+    let mut found: Vec<(PathBuf, CandidateSource)> = Vec::new();
+    found.extend(pinned.map(|p| (PathBuf::from(p.trim()), CandidateSource::Pinned)));
+    found.extend(configured.map(|p| (PathBuf::from(p.trim()), CandidateSource::Configured)));
+    found.extend(
+        path_entries()
+            .into_iter()
+            .map(|p| (p, CandidateSource::Path)),
+    );
+    found.extend(
+        COMMON_GIT_LOCATIONS
+            .iter()
+            .map(|p| (PathBuf::from(p), CandidateSource::Common)),
+    );
+
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut candidates: Vec<GitCandidate> = Vec::new();
+    for (path, source) in found {
+        if path.as_os_str().is_empty() || !is_executable(&path) || is_self(&path) {
+            continue;
+        }
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+        if let Some(version) = git_version(&path) {
+            candidates.push(GitCandidate {
+                path,
+                source,
+                version,
+            });
+        }
+    }
+
+    // Stable, so PATH order survives among equal versions
+    candidates.sort_by(|a, b| {
+        let explicit = |c: &GitCandidate| {
+            matches!(
+                c.source,
+                CandidateSource::Pinned | CandidateSource::Configured
+            )
+        };
+        match (explicit(a), explicit(b)) {
+            (true, true) => a.source.cmp(&b.source),
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            (false, false) => version_key(&b.version)
+                .cmp(&version_key(&a.version))
+                .then(a.source.cmp(&b.source)),
+        }
+    });
+    candidates
+}
+
+fn path_entries() -> Vec<PathBuf> {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let name = if cfg!(windows) { "git.exe" } else { "git" };
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(name))
+        .collect()
+}
+
+/// The version `path --version` reports, if it is git
+pub fn git_version(path: &Path) -> Option<String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    mark_git_child(&mut cmd);
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_git_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_git_version(output: &str) -> Option<String> {
+    output
+        .trim()
+        .strip_prefix("git version ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+}
+
+/// Leading numeric components, so "2.43.0.windows.1" sorts as 2.43.0
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Hex SHA-256 of the file at `path`
+pub fn file_sha256(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Verify `path` as the real git and describe it for the `git` config section
+pub fn pin(path: &Path) -> Result<GitPinConfig, String> {
+    if !is_executable(path) {
+        return Err(format!("{} is not an executable file", path.display()));
+    }
+    if is_self(path) {
+        return Err(format!(
+            "{} is git-ai itself, not the real git",
+            path.display()
+        ));
+    }
+    let version = git_version(path)
+        .ok_or_else(|| format!("{} did not report a git version", path.display()))?;
+    let sha256 = file_sha256(path).ok_or_else(|| format!("Failed to read {}", path.display()))?;
+    Ok(GitPinConfig {
+        path: Some(path.to_string_lossy().to_string()),
+        version: Some(version),
+        sha256: Some(sha256),
+    })
+}
+
+/// How the pinned git compares with what was recorded when it was pinned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PinStatus {
+    NotPinned,
+    Unchanged {
+        path: String,
+        version: String,
+    },
+    Missing {
+        path: String,
+    },
+    /// Replaced in place; `version` is what it reports now, if anything
+    Changed {
+        path: String,
+        pinned_version: Option<String>,
+        version: Option<String>,
+    },
+}
+
+impl PinStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, PinStatus::NotPinned | PinStatus::Unchanged { .. })
+    }
+}
+
+/// Re-verify the pinned binary against its recorded version and hash
+pub fn check_pin(pin: Option<&GitPinConfig>) -> PinStatus {
+    let Some(pin) = pin else {
+        return PinStatus::NotPinned;
+    };
+    let Some(path) = pin
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    else {
+        return PinStatus::NotPinned;
+    };
+    let binary = Path::new(path);
+    if !is_executable(binary) {
+        return PinStatus::Missing {
+            path: path.to_string(),
+        };
+    }
+
+    let version = git_version(binary);
+    let sha256 = file_sha256(binary);
+    let version_matches = pin.version.is_none() || version == pin.version;
+    let hash_matches = pin.sha256.is_none() || sha256 == pin.sha256;
+    match version {
+        Some(version) if version_matches && hash_matches => PinStatus::Unchanged {
+            path: path.to_string(),
+            version,
+        },
+        version => PinStatus::Changed {
+            path: path.to_string(),
+            pinned_version: pin.version.clone(),
+            version,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_orders_git_versions() {
+        assert_eq!(
+            parse_git_version("git version 2.43.0\n").as_deref(),
+            Some("2.43.0")
+        );
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)").as_deref(),
+            Some("2.39.3")
+        );
+        assert_eq!(parse_git_version("git-ai 1.0.0"), None);
+
+        assert!(version_key("2.43.0.windows.1") > version_key("2.9.5"));
+        assert_eq!(version_key("2.43.0.windows.1"), vec![2, 43, 0]);
+    }
+
+    #[test]
+    fn check_pin_without_a_path_is_not_pinned() {
+        assert_eq!(check_pin(None), PinStatus::NotPinned);
+        assert_eq!(
+            check_pin(Some(&GitPinConfig::default())),
+            PinStatus::NotPinned
+        );
+        let missing = GitPinConfig {
+            path: Some("/nonexistent/git".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            check_pin(Some(&missing)),
+            PinStatus::Missing {
+                path: "/nonexistent/git".to_string()
+            }
+        );
+    }
+}
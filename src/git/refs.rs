@@ -1,23 +1,150 @@
-use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, AuthorshipLog};
+use crate::authorship::authorship_log_serialization::{
+    AUTHORSHIP_LOG_VERSION, AUTHORSHIP_LOG_VERSION_V4, AuthorshipLog, is_supported_schema_version,
+};
 use crate::authorship::working_log::Checkpoint;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, exec_git, exec_git_stdin};
 use crate::utils::debug_log;
 use serde_json;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
-// Modern refspecs without force to enable proper merging
+/// Default short name of the authorship notes ref (`refs/notes/ai`)
 pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
-pub const AI_AUTHORSHIP_PUSH_REFSPEC: &str = "refs/notes/ai:refs/notes/ai";
+
+/// Short name of the notes ref holding authorship logs (`notes_ref`, default `ai`)
+pub fn authorship_notes_ref_name() -> &'static str {
+    Config::get().notes_ref()
+}
+
+/// Full name of the authorship notes ref, e.g. `refs/notes/ai`
+pub fn authorship_notes_ref() -> String {
+    format!("refs/notes/{}", authorship_notes_ref_name())
+}
+
+/// `--ref=<name>` argument selecting the authorship notes ref for `git notes`
+pub fn authorship_notes_ref_arg() -> String {
+    format!("--ref={}", authorship_notes_ref_name())
+}
+
+/// Full name of the notes ref holding interned prompt messages, e.g. `refs/notes/ai-prompts`
+pub fn interned_prompts_ref() -> String {
+    format!("refs/notes/{}-prompts", authorship_notes_ref_name())
+}
+
+/// Full name of the notes ref holding tag attribution notes, e.g. `refs/notes/ai-tags`
+pub fn tag_notes_ref() -> String {
+    format!("refs/notes/{}-tags", authorship_notes_ref_name())
+}
+
+/// Full name of the notes ref marking commits frozen by `git-ai freeze`, e.g. `refs/notes/ai-frozen`
+pub fn frozen_notes_ref() -> String {
+    format!("refs/notes/{}-frozen", authorship_notes_ref_name())
+}
+
+/// Whether a notes write may replace the authorship note of a frozen commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrozenNotes {
+    /// Fail the whole write if any target commit is frozen
+    Refuse,
+    /// Write anyway (`--force`)
+    Overwrite,
+}
+
+/// Git dir, frozen ref tip and the commits frozen at that tip
+type FrozenCacheEntry = (PathBuf, String, Arc<HashSet<String>>);
+
+/// Frozen commits of the last frozen ref tip read by this process
+static FROZEN_CACHE: Mutex<Option<FrozenCacheEntry>> = Mutex::new(None);
+
+/// Frozen commit -> blob of its freeze marker
+pub fn frozen_commits(repo: &Repository) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", frozen_notes_ref()));
+    args.push("list".to_string());
+    let output = match exec_git(&args) {
+        Ok(output) => output,
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => return Ok(HashMap::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (marker, commit) = line.split_once(' ')?;
+            Some((commit.to_string(), marker.to_string()))
+        })
+        .collect())
+}
+
+/// Tip of the frozen notes ref, or `None` when nothing was ever frozen
+fn frozen_notes_tip(repo: &Repository) -> Result<Option<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-parse".to_string());
+    args.push("--verify".to_string());
+    args.push("--quiet".to_string());
+    args.push(frozen_notes_ref());
+    match exec_git(&args) {
+        Ok(output) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Frozen commits, listed once per frozen ref tip. Freezing or unfreezing moves the tip,
+/// so the cache never outlives the ref it was read from.
+fn frozen_commit_set(repo: &Repository) -> Result<Option<Arc<HashSet<String>>>, GitAiError> {
+    let Some(tip) = frozen_notes_tip(repo)? else {
+        return Ok(None);
+    };
+    let mut cache = FROZEN_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((path, cached_tip, commits)) = cache.as_ref()
+        && path == repo.path()
+        && *cached_tip == tip
+    {
+        return Ok(Some(commits.clone()));
+    }
+    let commits: Arc<HashSet<String>> = Arc::new(frozen_commits(repo)?.into_keys().collect());
+    *cache = Some((repo.path().to_path_buf(), tip, commits.clone()));
+    Ok(Some(commits))
+}
+
+/// Refuse to write authorship notes for frozen commits unless `frozen` is `Overwrite`
+fn ensure_not_frozen<'a>(
+    repo: &Repository,
+    commit_shas: impl IntoIterator<Item = &'a str>,
+    frozen: FrozenNotes,
+) -> Result<(), GitAiError> {
+    if frozen == FrozenNotes::Overwrite {
+        return Ok(());
+    }
+    let Some(frozen) = frozen_commit_set(repo)? else {
+        return Ok(());
+    };
+    match commit_shas
+        .into_iter()
+        .find(|commit_sha| frozen.contains(*commit_sha))
+    {
+        Some(commit_sha) => Err(GitAiError::Generic(format!(
+            "Refusing to rewrite the authorship note of frozen commit {}; pass --force to override",
+            commit_sha
+        ))),
+        None => Ok(()),
+    }
+}
 
 pub fn notes_add(
     repo: &Repository,
     commit_sha: &str,
     note_content: &str,
 ) -> Result<(), GitAiError> {
+    ensure_not_frozen(repo, [commit_sha], FrozenNotes::Refuse)?;
+
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(authorship_notes_ref_arg());
     args.push("add".to_string());
     args.push("-f".to_string()); // Always force overwrite
     args.push("-F".to_string());
@@ -38,11 +165,15 @@ fn notes_path_for_object(oid: &str) -> String {
 }
 
 fn flat_note_pathspec_for_commit(commit_sha: &str) -> String {
-    format!("refs/notes/ai:{}", commit_sha)
+    format!("{}:{}", authorship_notes_ref(), commit_sha)
 }
 
 fn fanout_note_pathspec_for_commit(commit_sha: &str) -> String {
-    format!("refs/notes/ai:{}", notes_path_for_object(commit_sha))
+    format!(
+        "{}:{}",
+        authorship_notes_ref(),
+        notes_path_for_object(commit_sha)
+    )
 }
 
 fn parse_batch_check_blob_oid(line: &str) -> Option<String> {
@@ -107,6 +238,32 @@ pub fn note_blob_oids_for_commits(
 }
 
 pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Result<(), GitAiError> {
+    notes_add_batch_with(repo, entries, FrozenNotes::Refuse)
+}
+
+/// Like `notes_add_batch`, choosing whether frozen commits may be rewritten
+pub fn notes_add_batch_with(
+    repo: &Repository,
+    entries: &[(String, String)],
+    frozen: FrozenNotes,
+) -> Result<(), GitAiError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    ensure_not_frozen(
+        repo,
+        entries.iter().map(|(commit_sha, _)| commit_sha.as_str()),
+        frozen,
+    )?;
+    notes_add_batch_to_ref(repo, &authorship_notes_ref(), entries)
+}
+
+/// Like `notes_add_batch`, for any notes ref
+pub fn notes_add_batch_to_ref(
+    repo: &Repository,
+    notes_ref: &str,
+    entries: &[(String, String)],
+) -> Result<(), GitAiError> {
     if entries.is_empty() {
         return Ok(());
     }
@@ -114,7 +271,7 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
-    args.push("refs/notes/ai".to_string());
+    args.push(notes_ref.to_string());
     let existing_notes_tip = match exec_git(&args) {
         Ok(output) => Some(String::from_utf8(output.stdout)?.trim().to_string()),
         Err(GitAiError::GitCliError {
@@ -148,7 +305,7 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
         script.extend_from_slice(b"\n");
     }
 
-    script.extend_from_slice(b"commit refs/notes/ai\n");
+    script.extend_from_slice(format!("commit {}\n", notes_ref).as_bytes());
     script.extend_from_slice(format!("committer git-ai <git-ai@local> {} +0000\n", now).as_bytes());
     script.extend_from_slice(b"data 0\n");
     if let Some(existing_tip) = existing_notes_tip {
@@ -181,6 +338,20 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
 pub fn notes_add_blob_batch(
     repo: &Repository,
     entries: &[(String, String)],
+) -> Result<(), GitAiError> {
+    ensure_not_frozen(
+        repo,
+        entries.iter().map(|(commit_sha, _)| commit_sha.as_str()),
+        FrozenNotes::Refuse,
+    )?;
+    notes_add_blob_batch_to_ref(repo, &authorship_notes_ref(), entries)
+}
+
+/// Like `notes_add_blob_batch`, for any notes ref
+pub fn notes_add_blob_batch_to_ref(
+    repo: &Repository,
+    notes_ref: &str,
+    entries: &[(String, String)],
 ) -> Result<(), GitAiError> {
     if entries.is_empty() {
         return Ok(());
@@ -189,7 +360,7 @@ pub fn notes_add_blob_batch(
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
-    args.push("refs/notes/ai".to_string());
+    args.push(notes_ref.to_string());
     let existing_notes_tip = match exec_git(&args) {
         Ok(output) => Some(String::from_utf8(output.stdout)?.trim().to_string()),
         Err(GitAiError::GitCliError {
@@ -214,7 +385,7 @@ pub fn notes_add_blob_batch(
         .as_secs();
 
     let mut script = Vec::<u8>::new();
-    script.extend_from_slice(b"commit refs/notes/ai\n");
+    script.extend_from_slice(format!("commit {}\n", notes_ref).as_bytes());
     script.extend_from_slice(format!("committer git-ai <git-ai@local> {} +0000\n", now).as_bytes());
     script.extend_from_slice(b"data 0\n");
     if let Some(existing_tip) = existing_notes_tip {
@@ -240,6 +411,27 @@ pub fn notes_add_blob_batch(
     Ok(())
 }
 
+/// Commit -> note blob for every authorship note
+pub fn all_note_oids(repo: &Repository) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(authorship_notes_ref_arg());
+    args.push("list".to_string());
+    let output = match exec_git(&args) {
+        Ok(output) => output,
+        // No notes ref yet
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => return Ok(HashMap::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (note, commit) = line.split_once(' ')?;
+            Some((commit.to_string(), note.to_string()))
+        })
+        .collect())
+}
+
 // Check which commits from the given list have authorship notes.
 // Uses git cat-file --batch-check to efficiently check multiple commits in one invocation.
 // Returns a Vec of CommitAuthorship for each commit.
@@ -253,7 +445,8 @@ pub enum CommitAuthorship {
     Log {
         sha: String,
         git_author: String,
-        authorship_log: AuthorshipLog,
+        /// Boxed: a log is much larger than the `NoLog` variant
+        authorship_log: Box<AuthorshipLog>,
     },
 }
 pub fn get_commits_with_notes_from_list(
@@ -314,7 +507,7 @@ pub fn get_commits_with_notes_from_list(
             result.push(CommitAuthorship::Log {
                 sha: sha.clone(),
                 git_author,
-                authorship_log,
+                authorship_log: Box::new(authorship_log),
             });
         } else {
             result.push(CommitAuthorship::NoLog {
@@ -329,9 +522,14 @@ pub fn get_commits_with_notes_from_list(
 
 // Show an authorship note and return its JSON content if found, or None if it doesn't exist.
 pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<String> {
+    show_note_in_ref(repo, &authorship_notes_ref(), commit_sha)
+}
+
+/// The note `notes_ref` (a full `refs/notes/...` name) holds for `commit_sha`
+pub fn show_note_in_ref(repo: &Repository, notes_ref: &str, commit_sha: &str) -> Option<String> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(format!("--ref={}", notes_ref));
     args.push("show".to_string());
     args.push(commit_sha.to_string());
 
@@ -367,6 +565,22 @@ pub fn get_authorship(repo: &Repository, commit_sha: &str) -> Option<AuthorshipL
     Some(authorship_log)
 }
 
+/// An authorship log read from a notes ref other than the authorship ref, such
+/// as notes fetched from a fork for `git-ai pr view`
+pub fn get_authorship_from_notes_ref(
+    repo: &Repository,
+    notes_ref: &str,
+    commit_sha: &str,
+) -> Option<AuthorshipLog> {
+    let content = show_note_in_ref(repo, notes_ref, commit_sha)?;
+    let mut authorship_log = AuthorshipLog::deserialize_from_string(&content).ok()?;
+    if !is_supported_schema_version(&authorship_log.metadata.schema_version) {
+        return None;
+    }
+    authorship_log.metadata.base_commit_sha = commit_sha.to_string();
+    Some(authorship_log)
+}
+
 #[allow(dead_code)]
 pub fn get_reference_as_working_log(
     repo: &Repository,
@@ -396,10 +610,12 @@ pub fn get_reference_as_authorship_log_v3(
     };
 
     // Check version compatibility
-    if authorship_log.metadata.schema_version != AUTHORSHIP_LOG_VERSION {
+    if !is_supported_schema_version(&authorship_log.metadata.schema_version) {
         return Err(GitAiError::Generic(format!(
-            "Unsupported authorship log version: {} (expected: {})",
-            authorship_log.metadata.schema_version, AUTHORSHIP_LOG_VERSION
+            "Unsupported authorship log version: {} (expected: {} or {})",
+            authorship_log.metadata.schema_version,
+            AUTHORSHIP_LOG_VERSION,
+            AUTHORSHIP_LOG_VERSION_V4
         )));
     }
 
@@ -425,7 +641,7 @@ fn sanitize_remote_name(remote: &str) -> String {
 }
 
 /// Generate a tracking ref name for notes from a specific remote
-/// Returns a ref like "refs/notes/ai-remote/origin"
+/// Returns a ref like "refs/notes/ai-remote/origin" (named after `notes_ref`)
 ///
 /// SAFETY: These tracking refs are stored under refs/notes/ai-remote/* which:
 /// - Won't be pushed by `git push` (only pushes refs/heads/* by default)
@@ -434,7 +650,29 @@ fn sanitize_remote_name(remote: &str) -> String {
 /// - **WILL** be pushed by `git push --mirror` (usually only used for backups, etc.)
 /// - **WILL** be pushed if user explicitly specifies refs/notes/ai-remote/* (extremely rare)
 pub fn tracking_ref_for_remote(remote_name: &str) -> String {
-    format!("refs/notes/ai-remote/{}", sanitize_remote_name(remote_name))
+    format!(
+        "refs/notes/{}-remote/{}",
+        authorship_notes_ref_name(),
+        sanitize_remote_name(remote_name)
+    )
+}
+
+/// Tracking ref for a remote's interned prompts, e.g. "refs/notes/ai-prompts-remote/origin"
+pub fn interned_prompts_tracking_ref(remote_name: &str) -> String {
+    format!(
+        "refs/notes/{}-prompts-remote/{}",
+        authorship_notes_ref_name(),
+        sanitize_remote_name(remote_name)
+    )
+}
+
+/// Tracking ref for a remote's tag notes, e.g. "refs/notes/ai-tags-remote/origin"
+pub fn tag_notes_tracking_ref(remote_name: &str) -> String {
+    format!(
+        "refs/notes/{}-tags-remote/{}",
+        authorship_notes_ref_name(),
+        sanitize_remote_name(remote_name)
+    )
 }
 
 /// Check if a ref exists in the repository
@@ -451,9 +689,18 @@ pub fn ref_exists(repo: &Repository, ref_name: &str) -> bool {
 /// Merge notes from a source ref into refs/notes/ai
 /// Uses the 'ours' strategy to combine notes without data loss
 pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), GitAiError> {
+    merge_notes_into_ref(repo, &authorship_notes_ref(), source_ref)
+}
+
+/// Like `merge_notes_from_ref`, for any notes ref
+pub fn merge_notes_into_ref(
+    repo: &Repository,
+    notes_ref: &str,
+    source_ref: &str,
+) -> Result<(), GitAiError> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+    args.push(format!("--ref={}", notes_ref));
     args.push("merge".to_string());
     args.push("-s".to_string());
     args.push("ours".to_string());
@@ -461,8 +708,8 @@ pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), G
     args.push(source_ref.to_string());
 
     debug_log(&format!(
-        "Merging notes from {} into refs/notes/ai",
-        source_ref
+        "Merging notes from {} into {}",
+        source_ref, notes_ref
     ));
     exec_git(&args)?;
     Ok(())
@@ -488,7 +735,7 @@ pub fn grep_ai_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, Gi
     args.push("grep".to_string());
     args.push("-nI".to_string());
     args.push(pattern.to_string());
-    args.push("refs/notes/ai".to_string());
+    args.push(authorship_notes_ref());
 
     let output = exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)
@@ -497,8 +744,9 @@ pub fn grep_ai_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, Gi
     // Parse output format: refs/notes/ai:ab/cdef123...:line_number:matched_content
     // Extract the commit SHA from the path
     let mut shas = HashSet::new();
+    let prefix = format!("{}:", authorship_notes_ref());
     for line in stdout.lines() {
-        if let Some(path_and_rest) = line.strip_prefix("refs/notes/ai:")
+        if let Some(path_and_rest) = line.strip_prefix(prefix.as_str())
             && let Some(path_end) = path_and_rest.find(':')
         {
             let path = &path_and_rest[..path_end];
@@ -614,6 +862,44 @@ mod tests {
         assert!(note_b.contains("\"note\":\"b\""));
     }
 
+    #[test]
+    fn test_notes_add_refuses_frozen_commits() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+
+        tmp_repo.write_file("a.txt", "a\n", true).expect("write a");
+        tmp_repo.commit_with_message("Commit A").expect("commit A");
+        let commit_a = tmp_repo.get_head_commit_sha().expect("head A");
+        let original = show_authorship_note(tmp_repo.gitai_repo(), &commit_a).expect("note A");
+
+        notes_add_batch_to_ref(
+            tmp_repo.gitai_repo(),
+            &frozen_notes_ref(),
+            &[(commit_a.clone(), "{}".to_string())],
+        )
+        .expect("freeze commit A");
+
+        let err = notes_add(tmp_repo.gitai_repo(), &commit_a, "rewritten").unwrap_err();
+        assert!(err.to_string().contains("frozen commit"), "{}", err);
+        let err = notes_add_batch(
+            tmp_repo.gitai_repo(),
+            &[(commit_a.clone(), "rewritten".to_string())],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("frozen commit"), "{}", err);
+
+        let note_a = show_authorship_note(tmp_repo.gitai_repo(), &commit_a).expect("note A");
+        assert_eq!(note_a, original);
+
+        notes_add_batch_with(
+            tmp_repo.gitai_repo(),
+            &[(commit_a.clone(), "forced".to_string())],
+            FrozenNotes::Overwrite,
+        )
+        .expect("forced rewrite of frozen commit");
+        let note_a = show_authorship_note(tmp_repo.gitai_repo(), &commit_a).expect("note A");
+        assert_eq!(note_a, "forced");
+    }
+
     #[test]
     fn test_notes_add_blob_batch_reuses_existing_note_blob() {
         let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
@@ -1,7 +1,9 @@
 use crate::authorship::attribution_tracker::LineAttribution;
 use crate::authorship::authorship_log::PromptRecord;
-use crate::authorship::authorship_log_serialization::generate_short_hash;
-use crate::authorship::working_log::{CHECKPOINT_API_VERSION, Checkpoint, CheckpointKind};
+use crate::authorship::authorship_log_serialization::{generate_short_hash, previous_short_hashes};
+use crate::authorship::working_log::{
+    CHECKPOINT_API_VERSION, Checkpoint, CheckpointKind, next_checkpoint_seq,
+};
 use crate::error::GitAiError;
 use crate::git::rewrite_log::{RewriteLogEvent, append_event_to_file};
 use crate::utils::{debug_log, normalize_to_posix};
@@ -9,7 +11,9 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// Initial attributions data structure stored in the INITIAL file
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -20,13 +24,25 @@ pub struct InitialAttributions {
     pub prompts: HashMap<String, PromptRecord>,
 }
 
+/// Per-repository git-ai state under `<git dir>/ai`.
+///
+/// Each linked worktree has its own git dir (`.git/worktrees/<name>`), so working
+/// logs and the rewrite log are namespaced per worktree and parallel sessions in
+/// different worktrees never share a working log. State that is the same for
+/// every worktree (the authorship note and file ownership caches) lives under the
+/// common `.git` dir.
 #[derive(Debug, Clone)]
 pub struct RepoStorage {
     pub repo_path: PathBuf,
     pub repo_workdir: PathBuf,
+    /// Shared `.git` dir; same as `repo_path` outside linked worktrees
+    pub common_dir: PathBuf,
     pub working_logs: PathBuf,
     pub rewrite_log: PathBuf,
     pub logs: PathBuf,
+    pub attribution_cache: PathBuf,
+    pub ownership_cache: PathBuf,
+    pub blame_cache: PathBuf,
 }
 
 impl RepoStorage {
@@ -35,13 +51,21 @@ impl RepoStorage {
         let working_logs_dir = ai_dir.join("working_logs");
         let rewrite_log_file = ai_dir.join("rewrite_log");
         let logs_dir = ai_dir.join("logs");
+        let common_dir = common_git_dir(repo_path);
+        let attribution_cache_dir = common_dir.join("ai").join("cache").join("notes");
+        let ownership_cache_dir = common_dir.join("ai").join("cache").join("ownership");
+        let blame_cache_dir = common_dir.join("ai").join("cache").join("blame");
 
         let config = RepoStorage {
             repo_path: repo_path.to_path_buf(),
             repo_workdir: repo_workdir.to_path_buf(),
+            common_dir,
             working_logs: working_logs_dir,
             rewrite_log: rewrite_log_file,
             logs: logs_dir,
+            attribution_cache: attribution_cache_dir,
+            ownership_cache: ownership_cache_dir,
+            blame_cache: blame_cache_dir,
         };
 
         config.ensure_config_directory().unwrap();
@@ -127,6 +151,108 @@ impl RepoStorage {
         Ok(())
     }
 
+    /// Delete working logs that haven't been written to in `max_age`, except the
+    /// one for `keep_sha` (normally HEAD). These are left behind by branches that
+    /// were abandoned or rewritten outside of git-ai. Returns how many were removed.
+    pub fn prune_stale_working_logs(
+        &self,
+        keep_sha: &str,
+        max_age: std::time::Duration,
+    ) -> Result<usize, GitAiError> {
+        let now = std::time::SystemTime::now();
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.working_logs)?.flatten() {
+            let path = entry.path();
+            if !path.is_dir() || entry.file_name().to_string_lossy() == keep_sha {
+                continue;
+            }
+            let checkpoints = path.join("checkpoints.jsonl");
+            let modified = fs::metadata(&checkpoints)
+                .or_else(|_| fs::metadata(&path))
+                .and_then(|m| m.modified());
+            let Ok(modified) = modified else {
+                continue;
+            };
+            if now.duration_since(modified).unwrap_or_default() >= max_age {
+                fs::remove_dir_all(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Base commit the last checkpoint was written against, if any
+    pub fn last_checkpoint_base(&self) -> Option<String> {
+        fs::read_to_string(self.checkpoint_base_file())
+            .ok()
+            .map(|sha| sha.trim().to_string())
+            .filter(|sha| !sha.is_empty())
+    }
+
+    /// Remember the base commit a checkpoint was just written against, so the next
+    /// one can tell when HEAD moved without our hooks
+    pub fn set_last_checkpoint_base(&self, sha: &str) -> Result<(), GitAiError> {
+        fs::write(self.checkpoint_base_file(), sha)?;
+        Ok(())
+    }
+
+    fn checkpoint_base_file(&self) -> PathBuf {
+        self.repo_path.join("ai").join("checkpoint_base")
+    }
+
+    /// When an agent's pre-edit checkpoint was written, if its post-edit checkpoint
+    /// hasn't followed yet. Edits in between are the agent's, not the human's.
+    pub fn agent_edit_pending_since(&self) -> Option<SystemTime> {
+        fs::metadata(self.agent_edit_pending_file())
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Mark an agent edit as started (pre-edit checkpoint) or finished (post-edit)
+    pub fn set_agent_edit_pending(&self, pending: bool) -> Result<(), GitAiError> {
+        let path = self.agent_edit_pending_file();
+        if pending {
+            fs::write(path, "")?;
+        } else if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn agent_edit_pending_file(&self) -> PathBuf {
+        self.repo_path.join("ai").join("agent_edit_pending")
+    }
+
+    /// Notes ref tips `git-ai gc` squashed away. Remote notes still built on one of
+    /// them are replaced rather than merged, so the pruned notes stay unreachable.
+    pub fn notes_squashed_tips(&self) -> Vec<String> {
+        fs::read_to_string(self.notes_squashed_file())
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    /// Remember a notes ref tip `git-ai gc` just squashed away
+    pub fn add_notes_squashed_tip(&self, sha: &str) -> Result<(), GitAiError> {
+        let path = self.notes_squashed_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", sha)?;
+        Ok(())
+    }
+
+    // Notes are shared by every worktree
+    fn notes_squashed_file(&self) -> PathBuf {
+        self.common_dir.join("ai").join("notes_squashed")
+    }
+
     /* Rewrite Log Persistance */
 
     /// Append a rewrite event to the rewrite log file and return the full log
@@ -149,6 +275,21 @@ impl RepoStorage {
     }
 }
 
+/// The common `.git` dir for `git_dir`. Linked worktrees point at it through a
+/// `commondir` file; any other git dir is its own common dir.
+fn common_git_dir(git_dir: &Path) -> PathBuf {
+    let Ok(content) = fs::read_to_string(git_dir.join("commondir")) else {
+        return git_dir.to_path_buf();
+    };
+    let common = Path::new(content.trim());
+    let common = if common.is_absolute() {
+        common.to_path_buf()
+    } else {
+        git_dir.join(common)
+    };
+    common.canonicalize().unwrap_or(common)
+}
+
 #[derive(Clone)]
 pub struct PersistedWorkingLog {
     pub dir: PathBuf,
@@ -327,45 +468,14 @@ impl PersistedWorkingLog {
         // Create a copy, potentially without transcript to reduce storage size.
         // Transcripts are refetched in update_prompts_to_latest() before post-commit
         // using tool-specific sources (transcript_path for Claude, cursor_db_path for Cursor, etc.)
-        //
-        // Tools that DON'T support refetch (transcript must be kept):
-        // - "opencode" - uses agent-v1 format, transcript provided inline
-        // - "mock_ai" - test preset, transcript not stored externally
-        // - Any other agent-v1 custom tools (detected by lack of tool-specific metadata)
         let mut storage_checkpoint = checkpoint.clone();
-        let tool = checkpoint
-            .agent_id
-            .as_ref()
-            .map(|a| a.tool.as_str())
-            .unwrap_or("");
-        let metadata = &checkpoint.agent_metadata;
-
-        // Blacklist: tools that cannot refetch transcripts
-        let cannot_refetch = match tool {
-            "opencode" | "mock_ai" => true,
-            // human checkpoints have no transcript anyway
-            "human" => false,
-            // For other tools, check if they have the necessary metadata for refetching
-            // cursor can always refetch from its database
-            "cursor" => false,
-            // claude, codex, gemini, continue-cli need transcript_path
-            "claude" | "codex" | "gemini" | "continue-cli" => metadata
-                .as_ref()
-                .and_then(|m| m.get("transcript_path"))
-                .is_none(),
-            // github-copilot needs chat_session_path
-            "github-copilot" => metadata
-                .as_ref()
-                .and_then(|m| m.get("chat_session_path"))
-                .is_none(),
-            // Unknown tools (like custom agent-v1 tools) can't refetch
-            _ => true,
-        };
-
-        if !cannot_refetch {
+        if checkpoint.can_refetch_transcript() {
             storage_checkpoint.transcript = None;
         }
 
+        // Assign a logical sequence so ordering doesn't depend on the wall clock
+        storage_checkpoint.seq = next_checkpoint_seq(&checkpoints);
+
         // Add the new checkpoint
         checkpoints.push(storage_checkpoint);
 
@@ -407,14 +517,23 @@ impl PersistedWorkingLog {
             checkpoints.push(checkpoint);
         }
 
-        // Migrate 7-char prompt hashes to 16-char hashes
-        // Step 1: Build mapping from old 7-char hash to new 16-char hash
+        // Later checkpoints override earlier ones and the last one per agent has its
+        // newest prompt, so they're returned in `seq` order rather than by the clock.
+        // Logs from before `seq` (all zeros) keep their file order.
+        checkpoints.sort_by_key(|checkpoint| checkpoint.seq);
+
+        // Migrate 7-char prompt hashes to 16-char hashes, and ids from hashing schemes
+        // retired by a `prompt_hashing` rotation to the current scheme
+        // Step 1: Build mapping from old hash to current 16-char hash
         let mut old_to_new_hash: HashMap<String, String> = HashMap::new();
 
         for checkpoint in &checkpoints {
             if let Some(agent_id) = &checkpoint.agent_id {
                 let new_hash = generate_short_hash(&agent_id.id, &agent_id.tool);
                 let old_hash = new_hash[..7].to_string();
+                for previous_hash in previous_short_hashes(&agent_id.id, &agent_id.tool) {
+                    old_to_new_hash.insert(previous_hash, new_hash.clone());
+                }
                 old_to_new_hash.insert(old_hash, new_hash);
             }
         }
@@ -425,23 +544,18 @@ impl PersistedWorkingLog {
             for entry in &mut checkpoint.entries {
                 // Replace author_ids in attributions
                 for attr in &mut entry.attributions {
-                    if attr.author_id.len() == 7
-                        && let Some(new_hash) = old_to_new_hash.get(&attr.author_id)
-                    {
+                    if let Some(new_hash) = old_to_new_hash.get(&attr.author_id) {
                         attr.author_id = new_hash.clone();
                     }
                 }
 
                 // Replace author_ids in line_attributions
                 for line_attr in &mut entry.line_attributions {
-                    if line_attr.author_id.len() == 7
-                        && let Some(new_hash) = old_to_new_hash.get(&line_attr.author_id)
-                    {
+                    if let Some(new_hash) = old_to_new_hash.get(&line_attr.author_id) {
                         line_attr.author_id = new_hash.clone();
                     }
-                    // Also migrate the overrode field if it contains a 7-char hash
+                    // Also migrate the overrode field if it contains an old hash
                     if let Some(ref overrode_id) = line_attr.overrode
-                        && overrode_id.len() == 7
                         && let Some(new_hash) = old_to_new_hash.get(overrode_id)
                     {
                         line_attr.overrode = Some(new_hash.clone());
@@ -775,6 +889,44 @@ mod tests {
         assert_eq!(checkpoints[1].author, "test-author-2");
     }
 
+    #[test]
+    fn test_read_all_checkpoints_orders_by_seq_not_timestamp() {
+        use crate::authorship::working_log::CheckpointKind;
+
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let working_log = repo_storage.working_log_for_base_commit("test-commit-sha");
+
+        let mut first = Checkpoint::new(
+            CheckpointKind::AiAgent,
+            String::new(),
+            "first".to_string(),
+            vec![],
+        );
+        first.seq = 1;
+        first.timestamp = 2_000_000_000;
+        let mut second = Checkpoint::new(
+            CheckpointKind::AiAgent,
+            String::new(),
+            "second".to_string(),
+            vec![],
+        );
+        second.seq = 2;
+        second.timestamp = 1_000_000_000;
+        working_log
+            .write_all_checkpoints(&[second, first])
+            .expect("Failed to write checkpoints");
+
+        let authors: Vec<String> = working_log
+            .read_all_checkpoints()
+            .expect("Failed to read checkpoints")
+            .into_iter()
+            .map(|checkpoint| checkpoint.author)
+            .collect();
+        assert_eq!(authors, vec!["first", "second"]);
+    }
+
     #[test]
     fn test_read_all_checkpoints_filters_incompatible_versions() {
         use crate::authorship::working_log::CheckpointKind;
@@ -934,4 +1086,32 @@ mod tests {
             "Working log directory should be in correct location"
         );
     }
+
+    #[test]
+    fn test_prune_stale_working_logs_keeps_head_and_recent_logs() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+
+        let month_ago = filetime::FileTime::from_system_time(
+            std::time::SystemTime::now() - std::time::Duration::from_secs(30 * 24 * 60 * 60),
+        );
+        for sha in ["head", "stale", "recent"] {
+            let working_log = repo_storage.working_log_for_base_commit(sha);
+            fs::write(working_log.dir.join("checkpoints.jsonl"), "").unwrap();
+            if sha != "recent" {
+                filetime::set_file_mtime(working_log.dir.join("checkpoints.jsonl"), month_ago)
+                    .unwrap();
+            }
+        }
+
+        let removed = repo_storage
+            .prune_stale_working_logs("head", std::time::Duration::from_secs(14 * 24 * 60 * 60))
+            .unwrap();
+
+        assert_eq!(removed, 1);
+        assert!(repo_storage.has_working_log("head"));
+        assert!(repo_storage.has_working_log("recent"));
+        assert!(!repo_storage.has_working_log("stale"));
+    }
 }
@@ -7,8 +7,10 @@ use crate::error::GitAiError;
 use crate::git::refs::get_authorship;
 use crate::git::repo_storage::RepoStorage;
 use crate::git::rewrite_log::RewriteLogEvent;
+use crate::git::self_invocation::mark_git_child;
 use crate::git::status::MAX_PATHSPEC_ARGS;
 use crate::git::sync_authorship::{fetch_authorship_notes, push_authorship_notes};
+use crate::utils::debug_log;
 #[cfg(windows)]
 use crate::utils::is_interactive_terminal;
 
@@ -567,6 +569,17 @@ impl<'a> Commit<'a> {
         })
     }
 
+    /// `GIT_AUTHOR_*` variables that keep this commit's author on a commit
+    /// written to replace it with `commit-tree`
+    pub fn author_env(&self) -> Result<Vec<(String, String)>, GitAiError> {
+        let author = self.author()?;
+        Ok(vec![
+            ("GIT_AUTHOR_NAME".to_string(), author.name),
+            ("GIT_AUTHOR_EMAIL".to_string(), author.email),
+            ("GIT_AUTHOR_DATE".to_string(), author.time_iso8601),
+        ])
+    }
+
     // Get the committer of this commit.
     #[allow(dead_code)]
     pub fn committer(&self) -> Result<Signature<'a>, GitAiError> {
@@ -1050,6 +1063,67 @@ impl Repository {
         Ok(value.trim() == "true")
     }
 
+    /// Returns true when history is cut off by a shallow clone (`git clone --depth`).
+    pub fn is_shallow(&self) -> bool {
+        let mut args = self.global_args_for_exec();
+        args.push("rev-parse".to_string());
+        args.push("--is-shallow-repository".to_string());
+        exec_git(&args)
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Fetches more history into a shallow clone until `reached` holds, doubling the
+    /// depth each round and stopping after `settings.deepen_limit` commits. Returns
+    /// false when deepening is disabled, there is no remote, or history runs out first.
+    pub fn deepen_until(
+        &self,
+        settings: &config::HistorySettings,
+        mut reached: impl FnMut() -> bool,
+    ) -> bool {
+        if !settings.deepen || !self.is_shallow() {
+            return false;
+        }
+        let Some(remote) = self
+            .upstream_remote()
+            .ok()
+            .flatten()
+            .or_else(|| self.get_default_remote().ok().flatten())
+            .filter(|remote| !remote.is_empty())
+        else {
+            return false;
+        };
+
+        let mut fetched = 0u32;
+        let mut step = 32u32;
+        while fetched < settings.deepen_limit {
+            let depth = step.min(settings.deepen_limit - fetched);
+            let mut args = self.global_args_for_exec();
+            args.push("fetch".to_string());
+            args.push("--no-tags".to_string());
+            args.push("--recurse-submodules=no".to_string());
+            args.push(format!("--deepen={}", depth));
+            args.push(remote.clone());
+            if let Err(e) = exec_git(&args) {
+                debug_log(&format!(
+                    "Failed to deepen shallow clone from {}: {}",
+                    remote, e
+                ));
+                return false;
+            }
+            fetched += depth;
+            if reached() {
+                debug_log(&format!("Deepened shallow clone by {} commits", fetched));
+                return true;
+            }
+            if !self.is_shallow() {
+                return false;
+            }
+            step = step.saturating_mul(2);
+        }
+        false
+    }
+
     /// Get the canonical (absolute, resolved) path of the working directory
     /// On Windows, this uses the \\?\ UNC prefix format for reliable path comparisons
     #[allow(dead_code)]
@@ -1194,14 +1268,7 @@ impl Repository {
     /// Check if the current git version supports --ignore-revs-file flag for blame.
     /// This flag was added in git 2.23.0.
     pub fn git_supports_ignore_revs_file(&self) -> bool {
-        if let Some((major, minor, _)) = self.git_version() {
-            // --ignore-revs-file was added in git 2.23.0
-            major > 2 || (major == 2 && minor >= 23)
-        } else {
-            // If we can't determine the version, assume it's supported
-            // to avoid breaking existing functionality
-            true
-        }
+        supports_ignore_revs_file(self.git_version())
     }
 
     // Write an in-memory buffer to the ODB as a blob.
@@ -1484,10 +1551,10 @@ impl Repository {
             rp_args.push("--verify".to_string());
             rp_args.push(target_ref.clone());
 
-            let old_tip: Option<String> = match Command::new(config::Config::get().git_cmd())
-                .args(args_with_disabled_hooks_if_needed(&rp_args))
-                .output()
-            {
+            let mut rp_cmd = Command::new(config::Config::get().git_cmd());
+            rp_cmd.args(args_with_disabled_hooks_if_needed(&rp_args));
+            mark_git_child(&mut rp_cmd);
+            let old_tip: Option<String> = match rp_cmd.output() {
                 Ok(output) if output.status.success() => {
                     Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
                 }
@@ -2104,7 +2171,7 @@ pub fn find_repository(global_args: &[String]) -> Result<Repository, GitAiError>
     })
 }
 
-fn resolve_command_base_dir(global_args: &[String]) -> Result<PathBuf, GitAiError> {
+pub(crate) fn resolve_command_base_dir(global_args: &[String]) -> Result<PathBuf, GitAiError> {
     let mut base = std::env::current_dir().map_err(GitAiError::IoError)?;
     let mut idx = 0usize;
 
@@ -2156,6 +2223,58 @@ pub fn find_repository_in_path(path: &str) -> Result<Repository, GitAiError> {
     find_repository(&global_args)
 }
 
+/// What a directory's `.git` entry turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitMarker {
+    /// A repository or linked worktree rooted at this directory
+    Repository,
+    /// A submodule checkout (gitfile pointing into a superproject's `.git/modules`)
+    Submodule,
+    /// A `.git` that isn't usable: an empty or partial directory left by
+    /// vendoring, or a gitfile whose target is gone
+    Broken,
+}
+
+/// Classify `dir/.git` without running git. `None` when there is no `.git`.
+pub fn git_marker(dir: &Path) -> Option<GitMarker> {
+    let git_path = dir.join(".git");
+    let metadata = std::fs::symlink_metadata(&git_path).ok()?;
+
+    if metadata.is_file() {
+        let Some(target) = std::fs::read_to_string(&git_path).ok().and_then(|content| {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix("gitdir:"))
+                .map(|target| dir.join(target.trim()))
+        }) else {
+            return Some(GitMarker::Broken);
+        };
+        if !target.join("HEAD").exists() {
+            return Some(GitMarker::Broken);
+        }
+        // `<superproject>/.git/modules/<name>`, possibly nested for submodules
+        // of submodules
+        let components: Vec<_> = target.components().map(|c| c.as_os_str()).collect();
+        let is_submodule = components
+            .windows(2)
+            .any(|pair| pair[0] == ".git" && pair[1] == "modules");
+        return Some(if is_submodule {
+            GitMarker::Submodule
+        } else {
+            GitMarker::Repository
+        });
+    }
+
+    // Follow a symlinked `.git` to whatever it points at
+    if git_path.is_dir() {
+        if git_path.join("HEAD").is_file() && git_path.join("objects").is_dir() {
+            return Some(GitMarker::Repository);
+        }
+        return Some(GitMarker::Broken);
+    }
+    Some(GitMarker::Broken)
+}
+
 /// Find the git repository that contains the given file path by walking up the directory tree.
 ///
 /// This function is useful when working with multi-repository workspaces where the workspace
@@ -2208,24 +2327,14 @@ pub fn find_repository_for_file(
             }
         }
 
-        // Check for .git directory or file (file for submodules/worktrees)
-        let git_path = dir.join(".git");
-        if git_path.exists() {
-            // Found a .git - but we need to check if this is a submodule
-            // Submodules have a .git file (not directory) that points to the parent's .git/modules
-            if git_path.is_file() {
-                // This is a submodule - read the file to check if it points to modules/
-                if let Ok(content) = std::fs::read_to_string(&git_path)
-                    && content.contains("gitdir:")
-                    && content.contains("/modules/")
-                {
-                    // This is a submodule, skip it and continue searching up
-                    current_dir = dir.parent();
-                    continue;
-                }
-            }
-
-            // Found a real git repository, use find_repository_in_path
+        // Innermost real repository wins, even when an outer repository also
+        // tracks the path (vendored checkouts, meta-repos). Submodule edits go
+        // to the submodule's own working log, and leftover `.git` entries that
+        // aren't repositories don't claim anything.
+        if matches!(
+            git_marker(dir),
+            Some(GitMarker::Repository | GitMarker::Submodule)
+        ) {
             return find_repository_in_path(&dir.to_string_lossy());
         }
 
@@ -2241,7 +2350,8 @@ pub fn find_repository_for_file(
 /// Group edited file paths by their containing git repository.
 ///
 /// This function takes a list of file paths and groups them by the git repository
-/// they belong to. Files that don't belong to any repository are collected separately.
+/// they belong to, treating checked-out submodules as repositories of their own.
+/// Files that don't belong to any repository are collected separately.
 ///
 /// # Arguments
 /// * `file_paths` - List of absolute file paths to group
@@ -2291,6 +2401,7 @@ pub fn exec_git(args: &[String]) -> Result<Output, GitAiError> {
     let effective_args = args_with_disabled_hooks_if_needed(args);
     let mut cmd = Command::new(config::Config::get().git_cmd());
     cmd.args(&effective_args);
+    mark_git_child(&mut cmd);
 
     #[cfg(windows)]
     {
@@ -2323,6 +2434,7 @@ pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitA
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
+    mark_git_child(&mut cmd);
 
     #[cfg(windows)]
     {
@@ -2355,6 +2467,36 @@ pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitA
     Ok(output)
 }
 
+/// Remote that promises the objects a partial clone left out: `extensions.partialClone`,
+/// or the first remote with `remote.<name>.promisor` set.
+pub fn promisor_remote(global_args: &[String]) -> Option<String> {
+    let mut args = global_args.to_vec();
+    args.push("config".to_string());
+    args.push("--get-regexp".to_string());
+    args.push(r"^(extensions\.partialclone|remote\..*\.promisor)$".to_string());
+    let output = exec_git(&args).ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut promisor = None;
+    for line in stdout.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        if key.eq_ignore_ascii_case("extensions.partialclone") && !value.is_empty() {
+            return Some(value.to_string());
+        }
+        if promisor.is_none()
+            && value == "true"
+            && let Some(name) = key
+                .strip_prefix("remote.")
+                .and_then(|rest| rest.strip_suffix(".promisor"))
+        {
+            promisor = Some(name.to_string());
+        }
+    }
+    promisor
+}
+
 /// Helper to execute a git command with data provided on stdin and additional environment variables
 #[allow(dead_code)]
 pub fn exec_git_stdin_with_env(
@@ -2369,6 +2511,7 @@ pub fn exec_git_stdin_with_env(
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
+    mark_git_child(&mut cmd);
 
     // Apply env overrides
     for (k, v) in env.iter() {
@@ -2432,6 +2575,17 @@ fn parse_git_version(version_str: &str) -> Option<(u32, u32, u32)> {
     Some((major, minor, patch))
 }
 
+fn supports_ignore_revs_file(version: Option<(u32, u32, u32)>) -> bool {
+    if let Some((major, minor, _)) = version {
+        // --ignore-revs-file was added in git 2.23.0
+        major > 2 || (major == 2 && minor >= 23)
+    } else {
+        // If we can't determine the version, assume it's supported
+        // to avoid breaking existing functionality
+        true
+    }
+}
+
 /// Parse git diff output to extract added line numbers per file
 ///
 /// Parses unified diff format hunk headers like:
@@ -2694,6 +2848,18 @@ mod tests {
         assert_eq!(parse_git_version("git version 2.39.3\n"), Some((2, 39, 3)));
     }
 
+    #[test]
+    fn test_supports_ignore_revs_file() {
+        assert!(!supports_ignore_revs_file(Some((2, 22, 5))));
+        assert!(supports_ignore_revs_file(Some((2, 23, 0))));
+        assert!(supports_ignore_revs_file(parse_git_version(
+            "git version 2.39.5 (Apple Git-154)"
+        )));
+        assert!(supports_ignore_revs_file(Some((3, 0, 0))));
+        // Unknown versions fall back to passing the flag
+        assert!(supports_ignore_revs_file(None));
+    }
+
     #[test]
     fn test_parse_git_version_invalid() {
         // Invalid formats should return None
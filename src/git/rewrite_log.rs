@@ -32,6 +32,9 @@ pub enum RewriteLogEvent {
     RevertMixed {
         revert_mixed: RevertMixedEvent,
     },
+    Revert {
+        revert: RevertEvent,
+    },
     Reset {
         reset: ResetEvent,
     },
@@ -118,6 +121,10 @@ impl RewriteLogEvent {
         }
     }
 
+    pub fn revert(event: RevertEvent) -> Self {
+        Self::Revert { revert: event }
+    }
+
     #[allow(dead_code)]
     pub fn reset(event: ResetEvent) -> Self {
         Self::Reset { reset: event }
@@ -338,6 +345,31 @@ impl RevertMixedEvent {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevertEvent {
+    pub original_head: String,
+    pub new_head: String,
+    /// `reverted_commits[i]` is undone by `new_commits[i]`
+    pub reverted_commits: Vec<String>,
+    pub new_commits: Vec<String>,
+}
+
+impl RevertEvent {
+    pub fn new(
+        original_head: String,
+        new_head: String,
+        reverted_commits: Vec<String>,
+        new_commits: Vec<String>,
+    ) -> Self {
+        Self {
+            original_head,
+            new_head,
+            reverted_commits,
+            new_commits,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ResetKind {
@@ -415,7 +447,6 @@ pub struct StashEvent {
 }
 
 impl StashEvent {
-    #[allow(dead_code)]
     pub fn new(
         operation: StashOperation,
         stash_ref: Option<String>,
@@ -0,0 +1,140 @@
+//! Guard against git-ai invoking itself as git.
+//!
+//! On a misconfigured system the "real git" that git-ai runs can resolve back to
+//! git-ai: `git_path` or a probed location is a symlink or copy of git-ai, or a
+//! script that execs it. Two checks keep that from recursing:
+//!
+//! - Binary identity: git paths that are the running executable (same canonical
+//!   path, or same file on unix) are never chosen as the real git.
+//! - Env marker: every git child gets `GIT_AI_PROXY_PID` (the spawning git-ai's
+//!   pid) and `GIT_AI_PROXY_DEPTH`. A wrapper whose parent is that pid was started
+//!   as the real git, and one nested deeper than any hook or alias chain is looping.
+//!
+//! A wrapper that detects recursion says so and runs a different real git instead.
+
+use crate::utils::debug_log;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Pid of the git-ai process that spawned this git
+pub const ENV_PROXY_PID: &str = "GIT_AI_PROXY_PID";
+/// How many git-ai processes are above this one, counting through hooks and aliases
+pub const ENV_PROXY_DEPTH: &str = "GIT_AI_PROXY_DEPTH";
+
+/// Far beyond legitimate nesting (a hook running git, an alias calling another)
+const MAX_PROXY_DEPTH: u32 = 16;
+
+/// Mark a git child so that it can tell it was started by git-ai
+pub fn mark_git_child(cmd: &mut Command) {
+    cmd.env(ENV_PROXY_PID, std::process::id().to_string());
+    cmd.env(ENV_PROXY_DEPTH, (proxy_depth() + 1).to_string());
+}
+
+fn proxy_depth() -> u32 {
+    std::env::var(ENV_PROXY_DEPTH)
+        .ok()
+        .and_then(|depth| depth.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Why this process looks like git-ai running itself as git
+pub fn detect_recursion() -> Option<String> {
+    #[cfg(unix)]
+    {
+        let parent = std::os::unix::process::parent_id().to_string();
+        if std::env::var(ENV_PROXY_PID).is_ok_and(|pid| pid.trim() == parent) {
+            return Some("git-ai was started as git by git-ai".to_string());
+        }
+    }
+    let depth = proxy_depth();
+    (depth > MAX_PROXY_DEPTH).then(|| format!("git-ai is nested {} levels deep", depth))
+}
+
+/// Whether `path` is the running git-ai executable
+pub fn is_self(path: &Path) -> bool {
+    let Ok(current) = std::env::current_exe() else {
+        return false;
+    };
+    is_same_file(path, &current)
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    let (Ok(a), Ok(b)) = (a.canonicalize(), b.canonicalize()) else {
+        return false;
+    };
+    if a == b {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let (Ok(a), Ok(b)) = (a.metadata(), b.metadata()) {
+            return a.dev() == b.dev() && a.ino() == b.ino();
+        }
+    }
+    false
+}
+
+/// When recursion is detected, explain it and run `args` with a real git other than
+/// `looping_git` (the git path that led back here), exiting with its status
+pub fn exit_if_recursive(args: &[String], looping_git: &str, fallbacks: &[PathBuf]) {
+    let Some(reason) = detect_recursion() else {
+        return;
+    };
+    let fallback = fallbacks
+        .iter()
+        .find(|path| path.as_os_str() != looping_git && !is_self(path));
+
+    eprintln!(
+        "git-ai: {} ('{}' resolves back to git-ai). Set git_path in ~/.git-ai/config.json to the real git binary.",
+        reason, looping_git
+    );
+    let Some(fallback) = fallback else {
+        eprintln!("git-ai: no other git binary found; aborting");
+        std::process::exit(1);
+    };
+    eprintln!("git-ai: falling back to {}", fallback.display());
+    debug_log(&format!("recursion guard: running {:?} directly", fallback));
+
+    match Command::new(fallback)
+        .args(args)
+        .env_remove(ENV_PROXY_PID)
+        .env_remove(ENV_PROXY_DEPTH)
+        .status()
+    {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("git-ai: failed to run {}: {}", fallback.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_same_file_follows_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("git-ai");
+        std::fs::write(&target, "binary").unwrap();
+        let other = dir.path().join("git");
+        std::fs::write(&other, "binary").unwrap();
+
+        assert!(is_same_file(&target, &target));
+        assert!(!is_same_file(&target, &other));
+        assert!(!is_same_file(&target, &dir.path().join("missing")));
+
+        #[cfg(unix)]
+        {
+            let symlink = dir.path().join("git-link");
+            std::os::unix::fs::symlink(&target, &symlink).unwrap();
+            assert!(is_same_file(&symlink, &target));
+
+            let hardlink = dir.path().join("git-hard");
+            std::fs::hard_link(&target, &hardlink).unwrap();
+            assert!(is_same_file(&hardlink, &target));
+        }
+    }
+}
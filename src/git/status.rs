@@ -175,6 +175,19 @@ impl Repository {
 
         Ok(entries)
     }
+
+    /// Every changed or untracked file in the working tree, listing the files
+    /// inside untracked directories rather than the directories
+    pub fn changed_files_including_untracked(&self) -> Result<Vec<String>, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.extend(["status", "--porcelain=v2", "-z", "--untracked-files=all"].map(String::from));
+        let output = exec_git(&args)?;
+        Ok(parse_porcelain_v2(&output.stdout)?
+            .into_iter()
+            .filter(|entry| entry.kind != EntryKind::Ignored && entry.kind != EntryKind::Unmerged)
+            .map(|entry| entry.path)
+            .collect())
+    }
 }
 
 fn parse_porcelain_v2(data: &[u8]) -> Result<Vec<StatusEntry>, GitAiError> {
@@ -1,14 +1,31 @@
+use crate::git::authorship_traversal::batch_read_blobs_with_oids;
 use crate::git::refs::{
-    AI_AUTHORSHIP_PUSH_REFSPEC, copy_ref, merge_notes_from_ref, ref_exists, tracking_ref_for_remote,
+    FrozenNotes, all_note_oids, authorship_notes_ref, copy_ref, interned_prompts_ref,
+    interned_prompts_tracking_ref, merge_notes_from_ref, merge_notes_into_ref,
+    notes_add_batch_with, ref_exists, tag_notes_ref, tag_notes_tracking_ref,
+    tracking_ref_for_remote,
 };
 use crate::{
     error::GitAiError,
     git::{cli_parser::ParsedGitInvocation, repository::exec_git},
     utils::debug_log,
 };
+use std::io::IsTerminal;
+use std::process::Output;
+use std::time::Duration;
 
 use super::repository::Repository;
 
+/// Notes commits sent per push. Each chunk is a fast-forward of the one before,
+/// so a failure part-way keeps everything already pushed.
+const NOTES_PUSH_CHUNK_SIZE: usize = 500;
+
+/// Attempts per git network operation before giving up on a transient failure
+const NOTES_SYNC_MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubles on each one after
+const NOTES_SYNC_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
 #[cfg(windows)]
 fn disabled_hooks_config() -> &'static str {
     "core.hooksPath=NUL"
@@ -83,16 +100,18 @@ pub fn fetch_authorship_notes(
         remote_name, tracking_ref
     ));
 
-    // First, check if the remote has refs/notes/ai using ls-remote
+    // First, check if the remote has the notes ref using ls-remote
     // This is important for bare repos where the refmap might not be configured
     let mut ls_remote_args = repository.global_args_for_exec();
     ls_remote_args.push("ls-remote".to_string());
     ls_remote_args.push(remote_name.to_string());
-    ls_remote_args.push("refs/notes/ai".to_string());
+    ls_remote_args.push(authorship_notes_ref());
+    let side_refs = side_notes_refs(remote_name);
+    ls_remote_args.extend(side_refs.iter().map(|side| side.local_ref.clone()));
 
     debug_log(&format!("ls-remote command: {:?}", ls_remote_args));
 
-    match exec_git(&ls_remote_args) {
+    let remote_tip = match exec_git_with_retry(&ls_remote_args) {
         Ok(output) => {
             let result = String::from_utf8_lossy(&output.stdout).to_string();
             debug_log(&format!("ls-remote stdout: '{}'", result));
@@ -101,17 +120,23 @@ pub fn fetch_authorship_notes(
                 String::from_utf8_lossy(&output.stderr)
             ));
 
-            if result.trim().is_empty() {
+            let Some(remote_tip) = ls_remote_tip(&result, &authorship_notes_ref()) else {
                 debug_log(&format!(
                     "no authorship notes found on remote '{}', nothing to sync",
                     remote_name
                 ));
                 return Ok(NotesExistence::NotFound);
-            }
+            };
             debug_log(&format!(
-                "found authorship notes on remote '{}'",
-                remote_name
+                "found authorship notes on remote '{}' at {}",
+                remote_name, remote_tip
             ));
+            for side in &side_refs {
+                if let Some(side_tip) = ls_remote_tip(&result, &side.local_ref) {
+                    fetch_side_notes(repository, remote_name, side, &side_tip);
+                }
+            }
+            remote_tip
         }
         Err(e) => {
             debug_log(&format!(
@@ -121,10 +146,24 @@ pub fn fetch_authorship_notes(
             // Return error instead of assuming no notes - we don't know the state
             return Err(e);
         }
+    };
+
+    // Already have the remote's notes commit (and so everything under it): point
+    // the tracking ref at it and skip the fetch entirely
+    if has_commit(repository, &remote_tip) {
+        debug_log(&format!(
+            "remote notes tip {} already present locally, skipping fetch",
+            remote_tip
+        ));
+        if let Err(e) = copy_ref(repository, &remote_tip, &tracking_ref) {
+            debug_log(&format!("tracking ref update failed: {}", e));
+        }
+        merge_tracking_ref(repository, &tracking_ref);
+        return Ok(NotesExistence::Found);
     }
 
     // Now fetch the notes to the tracking ref with explicit refspec
-    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+    let fetch_refspec = format!("+{}:{}", authorship_notes_ref(), tracking_ref);
 
     // Build the internal authorship fetch with explicit flags and disabled hooks.
     // IMPORTANT: use repository.global_args_for_exec() to ensure -C flag is present for bare repos.
@@ -136,7 +175,7 @@ pub fn fetch_authorship_notes(
 
     debug_log(&format!("fetch command: {:?}", fetch_authorship));
 
-    match exec_git(&fetch_authorship) {
+    match exec_git_with_retry(&fetch_authorship) {
         Ok(output) => {
             debug_log(&format!(
                 "fetch stdout: '{}'",
@@ -153,46 +192,287 @@ pub fn fetch_authorship_notes(
         }
     }
 
-    // After successful fetch, merge the tracking ref into refs/notes/ai
-    let local_notes_ref = "refs/notes/ai";
+    // After successful fetch, merge the tracking ref into the local notes ref
+    if ref_exists(repository, &tracking_ref) {
+        merge_tracking_ref(repository, &tracking_ref);
+    } else {
+        debug_log(&format!(
+            "tracking ref {} was not created after fetch",
+            tracking_ref
+        ));
+    }
 
-    if crate::git::refs::ref_exists(repository, &tracking_ref) {
-        if crate::git::refs::ref_exists(repository, local_notes_ref) {
-            // Both exist - merge them
-            debug_log(&format!(
-                "merging authorship notes from {} into {}",
-                tracking_ref, local_notes_ref
-            ));
-            if let Err(e) = merge_notes_from_ref(repository, &tracking_ref) {
-                debug_log(&format!("notes merge failed: {}", e));
-                // Don't fail on merge errors, just log and continue
-            }
-        } else {
-            // Only tracking ref exists - copy it to local
+    Ok(NotesExistence::Found)
+}
+
+/// Fetch a remote's authorship notes into `target_ref` without merging them
+/// into the local notes ref, for notes that are only read (a PR's fork, say).
+pub fn fetch_authorship_notes_into(
+    repository: &Repository,
+    remote_name: &str,
+    target_ref: &str,
+) -> Result<NotesExistence, GitAiError> {
+    let mut ls_remote_args = repository.global_args_for_exec();
+    ls_remote_args.extend(["ls-remote".to_string(), remote_name.to_string()]);
+    ls_remote_args.push(authorship_notes_ref());
+    let output = exec_git_with_retry(&ls_remote_args)?;
+    let Some(remote_tip) = ls_remote_tip(
+        &String::from_utf8_lossy(&output.stdout),
+        &authorship_notes_ref(),
+    ) else {
+        return Ok(NotesExistence::NotFound);
+    };
+
+    if has_commit(repository, &remote_tip) {
+        copy_ref(repository, &remote_tip, target_ref)?;
+    } else {
+        let fetch_refspec = format!("+{}:{}", authorship_notes_ref(), target_ref);
+        let fetch_args = build_authorship_fetch_args(
+            repository.global_args_for_exec(),
+            remote_name,
+            &fetch_refspec,
+        );
+        exec_git_with_retry(&fetch_args)?;
+    }
+    Ok(NotesExistence::Found)
+}
+
+/// Fold a remote's notes (fetched into `tracking_ref`) into the local notes ref.
+/// Best-effort: failures are logged, not returned.
+fn merge_tracking_ref(repository: &Repository, tracking_ref: &str) {
+    let local_notes_ref = authorship_notes_ref();
+    let local_notes_ref = local_notes_ref.as_str();
+
+    if ref_exists(repository, local_notes_ref) {
+        // Merging history `git-ai gc` squashed would make its pruned notes
+        // reachable again, so only the notes written on top of it are taken
+        if let Some(squashed_tip) = squashed_history_tip(repository, tracking_ref) {
             debug_log(&format!(
-                "initializing {} from tracking ref {}",
-                local_notes_ref, tracking_ref
+                "{} still holds notes history squashed at {}, not merging it",
+                tracking_ref, squashed_tip
             ));
-            if let Err(e) = copy_ref(repository, &tracking_ref, local_notes_ref) {
-                debug_log(&format!("notes copy failed: {}", e));
-                // Don't fail on copy errors, just log and continue
+            if let Err(e) = carry_notes_past_squash(repository, &squashed_tip, tracking_ref) {
+                debug_log(&format!("carrying notes past squash failed: {}", e));
             }
+            return;
+        }
+        // Both exist - merge them
+        debug_log(&format!(
+            "merging authorship notes from {} into {}",
+            tracking_ref, local_notes_ref
+        ));
+        if let Err(e) = merge_notes_from_ref(repository, tracking_ref) {
+            debug_log(&format!("notes merge failed: {}", e));
         }
     } else {
+        // Only tracking ref exists - copy it to local
         debug_log(&format!(
-            "tracking ref {} was not created after fetch",
-            tracking_ref
+            "initializing {} from tracking ref {}",
+            local_notes_ref, tracking_ref
         ));
+        if let Err(e) = copy_ref(repository, tracking_ref, local_notes_ref) {
+            debug_log(&format!("notes copy failed: {}", e));
+        }
     }
+}
 
-    Ok(NotesExistence::Found)
+/// The notes ref tip `git-ai gc` squashed away that `tracking_ref` is, builds
+/// on, or is behind, if any
+fn squashed_history_tip(repository: &Repository, tracking_ref: &str) -> Option<String> {
+    repository
+        .storage
+        .notes_squashed_tips()
+        .into_iter()
+        .find(|tip| {
+            is_ancestor(repository, tip, tracking_ref) || is_ancestor(repository, tracking_ref, tip)
+        })
+}
+
+/// Add the notes `tracking_ref` wrote on top of `squashed_tip` to the local notes
+/// ref without its history. As with `-s ours`, a note changed on both sides keeps
+/// the local version.
+fn carry_notes_past_squash(
+    repository: &Repository,
+    squashed_tip: &str,
+    tracking_ref: &str,
+) -> Result<(), GitAiError> {
+    if is_ancestor(repository, tracking_ref, squashed_tip) {
+        return Ok(());
+    }
+
+    let mut args = repository.global_args_for_exec();
+    args.extend(
+        [
+            "diff-tree",
+            "-r",
+            "--no-renames",
+            "--no-commit-id",
+            squashed_tip,
+            tracking_ref,
+        ]
+        .map(String::from),
+    );
+    let output = exec_git(&args)?;
+    let local_oids = all_note_oids(repository)?;
+
+    // `:<mode> <mode> <old oid> <new oid> <status>\t<path>`, paths fanned out by oid
+    let mut carried = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((fields, path)) = line.split_once('\t') else {
+            continue;
+        };
+        let [_, _, old_oid, new_oid, status] = fields.split_whitespace().collect::<Vec<_>>()[..]
+        else {
+            continue;
+        };
+        if status == "D" {
+            continue;
+        }
+        let commit = path.replace('/', "");
+        let unchanged_locally = local_oids
+            .get(&commit)
+            .is_none_or(|local_oid| local_oid == old_oid);
+        if unchanged_locally {
+            carried.push((commit, new_oid.to_string()));
+        }
+    }
+    if carried.is_empty() {
+        return Ok(());
+    }
+
+    let blob_oids: Vec<String> = carried.iter().map(|(_, oid)| oid.clone()).collect();
+    let blobs = batch_read_blobs_with_oids(&repository.global_args_for_exec(), &blob_oids)?;
+    let entries: Vec<(String, String)> = carried
+        .into_iter()
+        .filter_map(|(commit, oid)| Some((commit, blobs.get(&oid)?.clone())))
+        .collect();
+    debug_log(&format!(
+        "carrying {} notes written past squashed notes history",
+        entries.len()
+    ));
+    notes_add_batch_with(repository, &entries, FrozenNotes::Overwrite)
+}
+
+/// The oid `ls-remote` output lists for `ref_name`
+fn ls_remote_tip(ls_remote_output: &str, ref_name: &str) -> Option<String> {
+    ls_remote_output.lines().find_map(|line| {
+        let (oid, name) = line.split_once(char::is_whitespace)?;
+        (name.trim() == ref_name).then(|| oid.to_string())
+    })
+}
+
+/// A notes ref that travels with the authorship notes: interned prompt messages
+/// or tag attribution notes. Entries are keyed by content or by tag object, so
+/// merging them never conflicts.
+struct SideNotesRef {
+    local_ref: String,
+    tracking_ref: String,
+    label: &'static str,
+}
+
+fn side_notes_refs(remote_name: &str) -> [SideNotesRef; 2] {
+    [
+        SideNotesRef {
+            local_ref: interned_prompts_ref(),
+            tracking_ref: interned_prompts_tracking_ref(remote_name),
+            label: "interned prompts",
+        },
+        SideNotesRef {
+            local_ref: tag_notes_ref(),
+            tracking_ref: tag_notes_tracking_ref(remote_name),
+            label: "tag notes",
+        },
+    ]
+}
+
+/// Bring a remote's side notes ref (at `remote_tip`) into the local one.
+/// Best-effort: failures are logged, not returned.
+fn fetch_side_notes(
+    repository: &Repository,
+    remote_name: &str,
+    side: &SideNotesRef,
+    remote_tip: &str,
+) {
+    let updated = if has_commit(repository, remote_tip) {
+        copy_ref(repository, remote_tip, &side.tracking_ref)
+    } else {
+        let fetch_refspec = format!("+{}:{}", side.local_ref, side.tracking_ref);
+        let fetch_args = build_authorship_fetch_args(
+            repository.global_args_for_exec(),
+            remote_name,
+            &fetch_refspec,
+        );
+        exec_git_with_retry(&fetch_args).map(|_| ())
+    };
+    if let Err(e) = updated {
+        debug_log(&format!("{} fetch failed: {}", side.label, e));
+        return;
+    }
+    merge_side_notes(repository, side);
+}
+
+fn merge_side_notes(repository: &Repository, side: &SideNotesRef) {
+    let merged = if ref_exists(repository, &side.local_ref) {
+        merge_notes_into_ref(repository, &side.local_ref, &side.tracking_ref)
+    } else {
+        copy_ref(repository, &side.tracking_ref, &side.local_ref)
+    };
+    if let Err(e) = merged {
+        debug_log(&format!("{} merge failed: {}", side.label, e));
+    }
+}
+
+/// Push a local side notes ref ahead of the authorship notes. Best-effort: a
+/// remote without it still gets the notes, and readers fall back to the local
+/// database (prompts) or report no attribution (tags).
+fn push_side_notes(repository: &Repository, remote_name: &str, side: &SideNotesRef) {
+    if !ref_exists(repository, &side.local_ref) {
+        return;
+    }
+    let fetch_refspec = format!("+{}:{}", side.local_ref, side.tracking_ref);
+    let fetch_args = build_authorship_fetch_args(
+        repository.global_args_for_exec(),
+        remote_name,
+        &fetch_refspec,
+    );
+    // Fails when the remote doesn't have the ref yet
+    if exec_git(&fetch_args).is_ok() && ref_exists(repository, &side.tracking_ref) {
+        if is_ancestor(repository, &side.local_ref, &side.tracking_ref) {
+            debug_log(&format!(
+                "remote already has every local {}, skipping push",
+                side.label
+            ));
+            return;
+        }
+        merge_side_notes(repository, side);
+    }
+
+    let push_args = build_notes_push_args(
+        repository.global_args_for_exec(),
+        remote_name,
+        &side.local_ref,
+        &side.local_ref,
+    );
+    match exec_git_with_retry(&push_args) {
+        Ok(_) => {
+            if let Err(e) = copy_ref(repository, &side.local_ref, &side.tracking_ref) {
+                debug_log(&format!("tracking ref update failed: {}", e));
+            }
+        }
+        Err(e) => debug_log(&format!("{} push failed: {}", side.label, e)),
+    }
 }
+
 // for use with post-push hook
 pub fn push_authorship_notes(repository: &Repository, remote_name: &str) -> Result<(), GitAiError> {
+    for side in side_notes_refs(remote_name) {
+        push_side_notes(repository, remote_name, &side);
+    }
+
     // STEP 1: Fetch remote notes into tracking ref and merge before pushing
     // This ensures we don't lose notes from other branches/clones
     let tracking_ref = tracking_ref_for_remote(remote_name);
-    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+    let fetch_refspec = format!("+{}:{}", authorship_notes_ref(), tracking_ref);
 
     let fetch_before_push = build_authorship_fetch_args(
         repository.global_args_for_exec(),
@@ -206,48 +486,205 @@ pub fn push_authorship_notes(repository: &Repository, remote_name: &str) -> Resu
     ));
 
     // Fetch is best-effort; if it fails (e.g., no remote notes yet), continue
-    if exec_git(&fetch_before_push).is_ok() {
-        // Merge fetched notes into local refs/notes/ai
-        let local_notes_ref = "refs/notes/ai";
+    let fetched = exec_git_with_retry(&fetch_before_push).is_ok();
+    if fetched && ref_exists(repository, &tracking_ref) {
+        merge_tracking_ref(repository, &tracking_ref);
+    }
 
-        if ref_exists(repository, &tracking_ref) {
-            if ref_exists(repository, local_notes_ref) {
-                // Both exist - merge them
-                debug_log(&format!(
-                    "pre-push: merging {} into {}",
-                    tracking_ref, local_notes_ref
-                ));
-                if let Err(e) = merge_notes_from_ref(repository, &tracking_ref) {
-                    debug_log(&format!("pre-push notes merge failed: {}", e));
-                }
-            } else {
-                // Only tracking ref exists - copy it to local
-                debug_log(&format!(
-                    "pre-push: initializing {} from {}",
-                    local_notes_ref, tracking_ref
-                ));
-                if let Err(e) = copy_ref(repository, &tracking_ref, local_notes_ref) {
-                    debug_log(&format!("pre-push notes copy failed: {}", e));
-                }
+    // STEP 2: Work out which notes commits the remote is missing
+    let local_notes_ref = authorship_notes_ref();
+    if !ref_exists(repository, &local_notes_ref) {
+        debug_log("no local authorship notes, nothing to push");
+        return Ok(());
+    }
+
+    // The remote still has history `git-ai gc` squashed locally; its newer notes
+    // were carried over above, so replace it unless someone pushed since the fetch
+    if fetched && squashed_history_tip(repository, &tracking_ref).is_some() {
+        let mut args = repository.global_args_for_exec();
+        args.extend(["rev-parse", "--verify", &tracking_ref].map(String::from));
+        let remote_tip = String::from_utf8(exec_git(&args)?.stdout)?
+            .trim()
+            .to_string();
+        let replace_args = build_authorship_replace_args(
+            repository.global_args_for_exec(),
+            remote_name,
+            &local_notes_ref,
+            &remote_tip,
+        );
+        debug_log(&format!(
+            "replacing squashed authorship notes history: {:?}",
+            &replace_args
+        ));
+        exec_git_with_retry(&replace_args)?;
+        if let Err(e) = copy_ref(repository, &local_notes_ref, &tracking_ref) {
+            debug_log(&format!("tracking ref update failed: {}", e));
+        }
+        return Ok(());
+    }
+
+    let known_remote_tip = ref_exists(repository, &tracking_ref).then_some(tracking_ref.as_str());
+    let missing = missing_notes_commits(repository, &local_notes_ref, known_remote_tip)?;
+    if missing.is_empty() {
+        if fetched && is_ancestor(repository, &local_notes_ref, &tracking_ref) {
+            debug_log("remote already has every local notes commit, skipping push");
+            return Ok(());
+        }
+        // The tracking ref may be stale, or local notes diverged from it (e.g. the
+        // merge failed): push the whole ref and let the remote decide
+        debug_log("could not confirm remote notes state, pushing notes ref");
+        return push_notes_chunk(repository, remote_name, &local_notes_ref);
+    }
+
+    // STEP 3: Push without force (requires fast-forward), oldest chunk first.
+    // The tracking ref follows each pushed chunk, so a sync that fails part-way
+    // resumes from there next time.
+    let tips = chunk_tips(&missing, NOTES_PUSH_CHUNK_SIZE);
+    let show_progress = tips.len() > 1 && std::io::stderr().is_terminal();
+    for (i, tip) in tips.iter().enumerate() {
+        if let Err(e) = push_notes_chunk(repository, remote_name, tip) {
+            // Best-effort; don't fail user operation due to authorship sync issues
+            debug_log(&format!("authorship push skipped due to error: {}", e));
+            if i == 0 {
+                return Err(e);
             }
+            return Err(GitAiError::Generic(format!(
+                "pushed {} of {} authorship notes chunks to {} before failing: {}",
+                i,
+                tips.len(),
+                remote_name,
+                e
+            )));
+        }
+        if let Err(e) = copy_ref(repository, tip, &tracking_ref) {
+            debug_log(&format!("tracking ref update failed: {}", e));
+        }
+        if show_progress {
+            eprintln!(
+                "git-ai: pushed authorship notes {}/{}",
+                ((i + 1) * NOTES_PUSH_CHUNK_SIZE).min(missing.len()),
+                missing.len()
+            );
         }
     }
 
-    // STEP 2: Push notes without force (requires fast-forward)
+    Ok(())
+}
+
+/// Push `source` (the notes ref or one of its commits) to the remote notes ref
+fn push_notes_chunk(
+    repository: &Repository,
+    remote_name: &str,
+    source: &str,
+) -> Result<(), GitAiError> {
     let push_authorship =
-        build_authorship_push_args(repository.global_args_for_exec(), remote_name);
+        build_authorship_push_args(repository.global_args_for_exec(), remote_name, source);
 
     debug_log(&format!(
         "pushing authorship refs (no force): {:?}",
         &push_authorship
     ));
-    if let Err(e) = exec_git(&push_authorship) {
-        // Best-effort; don't fail user operation due to authorship sync issues
-        debug_log(&format!("authorship push skipped due to error: {}", e));
-        return Err(e);
+    exec_git_with_retry(&push_authorship).map(|_| ())
+}
+
+/// Notes commits reachable from `local_ref` but not from `remote_tip`, oldest
+/// first, along the first-parent chain. With a known remote tip only commits
+/// descending from it are listed, so pushing any of them is a fast-forward.
+fn missing_notes_commits(
+    repository: &Repository,
+    local_ref: &str,
+    remote_tip: Option<&str>,
+) -> Result<Vec<String>, GitAiError> {
+    let mut args = repository.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push("--reverse".to_string());
+    args.push("--first-parent".to_string());
+    match remote_tip {
+        Some(remote_tip) => {
+            args.push("--ancestry-path".to_string());
+            args.push(format!("{}..{}", remote_tip, local_ref));
+        }
+        None => args.push(local_ref.to_string()),
     }
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
 
-    Ok(())
+/// The last commit of every `chunk_size` run of `commits`, ending with the final one
+fn chunk_tips(commits: &[String], chunk_size: usize) -> Vec<String> {
+    commits
+        .chunks(chunk_size.max(1))
+        .filter_map(|chunk| chunk.last().cloned())
+        .collect()
+}
+
+fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
+    let mut args = repository.global_args_for_exec();
+    args.push("merge-base".to_string());
+    args.push("--is-ancestor".to_string());
+    args.push(ancestor.to_string());
+    args.push(descendant.to_string());
+    exec_git(&args).is_ok()
+}
+
+fn has_commit(repository: &Repository, oid: &str) -> bool {
+    let mut args = repository.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("-e".to_string());
+    args.push(format!("{}^{{commit}}", oid));
+    exec_git(&args).is_ok()
+}
+
+/// Run a git network operation, retrying failures that look transient
+fn exec_git_with_retry(args: &[String]) -> Result<Output, GitAiError> {
+    let mut attempt = 1;
+    loop {
+        match exec_git(args) {
+            Ok(output) => return Ok(output),
+            Err(e) if attempt < NOTES_SYNC_MAX_ATTEMPTS && is_transient_git_error(&e) => {
+                debug_log(&format!(
+                    "transient authorship sync failure (attempt {}/{}), retrying: {}",
+                    attempt, NOTES_SYNC_MAX_ATTEMPTS, e
+                ));
+                std::thread::sleep(NOTES_SYNC_RETRY_BACKOFF * 2u32.pow(attempt - 1));
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether a failed git command looks like a network hiccup worth retrying,
+/// as opposed to a rejection (non-fast-forward, auth) that will fail again
+fn is_transient_git_error(error: &GitAiError) -> bool {
+    const TRANSIENT_MARKERS: &[&str] = &[
+        "could not resolve host",
+        "connection timed out",
+        "connection reset",
+        "connection refused",
+        "operation timed out",
+        "the remote end hung up unexpectedly",
+        "early eof",
+        "rpc failed",
+        "temporary failure",
+        "http 502",
+        "http 503",
+        "http 504",
+    ];
+    match error {
+        GitAiError::GitCliError { stderr, .. } => {
+            let stderr = stderr.to_lowercase();
+            TRANSIENT_MARKERS
+                .iter()
+                .any(|marker| stderr.contains(marker))
+        }
+        GitAiError::IoError(_) => true,
+        _ => false,
+    }
 }
 
 fn extract_remote_from_fetch_args(args: &[String]) -> Option<String> {
@@ -324,7 +761,42 @@ fn build_authorship_fetch_args(
     args
 }
 
-fn build_authorship_push_args(global_args: Vec<String>, remote_name: &str) -> Vec<String> {
+fn build_authorship_push_args(
+    global_args: Vec<String>,
+    remote_name: &str,
+    source: &str,
+) -> Vec<String> {
+    build_notes_push_args(global_args, remote_name, source, &authorship_notes_ref())
+}
+
+/// Push `source` over the remote notes ref, as long as the remote is still at
+/// `expected_remote_tip`
+fn build_authorship_replace_args(
+    global_args: Vec<String>,
+    remote_name: &str,
+    source: &str,
+    expected_remote_tip: &str,
+) -> Vec<String> {
+    let mut args = build_authorship_push_args(global_args, remote_name, source);
+    // Before the remote and refspec at the end
+    let options_end = args.len() - 2;
+    args.insert(
+        options_end,
+        format!(
+            "--force-with-lease={}:{}",
+            authorship_notes_ref(),
+            expected_remote_tip
+        ),
+    );
+    args
+}
+
+fn build_notes_push_args(
+    global_args: Vec<String>,
+    remote_name: &str,
+    source: &str,
+    destination: &str,
+) -> Vec<String> {
     let mut args = with_disabled_hooks(global_args);
     args.push("push".to_string());
     args.push("--quiet".to_string());
@@ -332,7 +804,7 @@ fn build_authorship_push_args(global_args: Vec<String>, remote_name: &str) -> Ve
     args.push("--no-verify".to_string());
     args.push("--no-signed".to_string());
     args.push(remote_name.to_string());
-    args.push(AI_AUTHORSHIP_PUSH_REFSPEC.to_string());
+    args.push(format!("{}:{}", source, destination));
     args
 }
 
@@ -359,13 +831,46 @@ mod tests {
     #[test]
     fn authorship_push_args_always_disable_hooks() {
         let disabled_hooks = disabled_hooks_config();
-        let args =
-            build_authorship_push_args(vec!["-C".to_string(), "/tmp/repo".to_string()], "origin");
+        let args = build_authorship_push_args(
+            vec!["-C".to_string(), "/tmp/repo".to_string()],
+            "origin",
+            "refs/notes/ai",
+        );
 
         assert!(
             args.windows(2)
                 .any(|pair| pair[0] == "-c" && pair[1] == disabled_hooks)
         );
         assert!(args.contains(&"push".to_string()));
+        assert!(args.contains(&"refs/notes/ai:refs/notes/ai".to_string()));
+    }
+
+    #[test]
+    fn chunk_tips_end_each_chunk_and_the_range() {
+        let commits: Vec<String> = (1..=5).map(|i| format!("c{}", i)).collect();
+        assert_eq!(chunk_tips(&commits, 2), vec!["c2", "c4", "c5"]);
+        assert_eq!(chunk_tips(&commits, 10), vec!["c5"]);
+        assert!(chunk_tips(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn only_network_failures_are_transient() {
+        let cli_error = |stderr: &str| GitAiError::GitCliError {
+            code: Some(128),
+            stderr: stderr.to_string(),
+            args: vec!["push".to_string()],
+        };
+        assert!(is_transient_git_error(&cli_error(
+            "fatal: unable to access 'https://example.com/': Could not resolve host: example.com"
+        )));
+        assert!(is_transient_git_error(&cli_error(
+            "fatal: the remote end hung up unexpectedly"
+        )));
+        assert!(!is_transient_git_error(&cli_error(
+            " ! [rejected] refs/notes/ai -> refs/notes/ai (non-fast-forward)"
+        )));
+        assert!(!is_transient_git_error(&GitAiError::Generic(
+            "oops".to_string()
+        )));
     }
 }
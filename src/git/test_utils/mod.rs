@@ -308,6 +308,39 @@ impl TmpRepo {
         })
     }
 
+    /// Clones this repository over `file://` into a new temporary repository, passing
+    /// extra `git clone` arguments such as `--depth=1` or `--filter=blob:none`
+    pub fn clone_with_args(&self, args: &[&str]) -> Result<TmpRepo, GitAiError> {
+        let tmp_dir = create_unique_tmp_dir("git-ai-tmp-clone")?;
+        let output = Command::new(crate::config::Config::get().git_cmd())
+            .arg("clone")
+            .arg("-q")
+            .args(args)
+            .arg(format!("file://{}", self.path.display()))
+            .arg(&tmp_dir)
+            .output()
+            .map_err(|e| GitAiError::Generic(format!("Failed to run git clone: {}", e)))?;
+        if !output.status.success() {
+            return Err(GitAiError::Generic(format!(
+                "git clone failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let repo_git2 = Repository::open(&tmp_dir)?;
+        let repo_gitai =
+            crate::git::repository::find_repository_in_path(tmp_dir.to_str().unwrap())?;
+        let mut config = repo_git2.config()?;
+        config.set_str("user.name", "Test User")?;
+        config.set_str("user.email", "test@example.com")?;
+
+        Ok(TmpRepo {
+            path: tmp_dir,
+            repo_git2,
+            repo_gitai,
+        })
+    }
+
     pub fn new_with_base_commit() -> Result<(Self, TmpFile, TmpFile), GitAiError> {
         let repo = TmpRepo::new()?;
         let lines_file = repo.write_file("lines.md", LINES, true)?;
@@ -361,6 +394,7 @@ impl TmpRepo {
         checkpoint(
             &self.repo_gitai,
             author,
+            &[],
             CheckpointKind::Human,
             false, // show_working_log
             false, // reset
@@ -418,6 +452,7 @@ impl TmpRepo {
         checkpoint(
             &self.repo_gitai,
             agent_name,
+            &[],
             CheckpointKind::AiAgent,
             false, // show_working_log
             false, // reset
@@ -440,6 +475,7 @@ impl TmpRepo {
         checkpoint(
             &self.repo_gitai,
             author,
+            &[],
             checkpoint_kind,
             false, // show_working_log
             false, // reset
@@ -0,0 +1,66 @@
+# German
+
+percent = { $value } %
+
+## git-ai stats
+
+stats-no-additions = (keine Hinzufügungen)
+stats-mixed = gemischt { $percent }
+stats-ai-accepted = { $percent } KI-Code übernommen
+stats-waited-minutes = { $minutes } Min. auf KI gewartet
+stats-waited-seconds = { $seconds } s auf KI gewartet
+
+# Plain mode (--plain): one `label: value` line each
+stats-plain-additions = hinzugefügt: { $count }
+stats-plain-deletions = entfernt: { $count }
+stats-plain-human = Mensch: { $percent } ({ $lines ->
+        [one] { $lines } Zeile
+       *[other] { $lines } Zeilen
+    })
+stats-plain-mixed = gemischt: { $percent } ({ $lines ->
+        [one] { $lines } Zeile
+       *[other] { $lines } Zeilen
+    })
+stats-plain-ai = KI: { $percent } ({ $lines ->
+        [one] { $lines } Zeile
+       *[other] { $lines } Zeilen
+    })
+stats-plain-accepted = KI-Code übernommen: { $percent }
+stats-plain-waited = auf KI gewartet: { $duration }
+
+duration-minutes-seconds = { $minutes } Min. { $seconds } s
+duration-seconds = { $seconds } s
+
+## git-ai export / import
+
+export-done = { $notes ->
+        [one] { $notes } Notiz
+       *[other] { $notes } Notizen
+    }, { $prompts ->
+        [one] { $prompts } Prompt
+       *[other] { $prompts } Prompts
+    } und { $working_logs ->
+        [one] { $working_logs } Arbeitsprotokoll
+       *[other] { $working_logs } Arbeitsprotokolle
+    } aus { $commits ->
+        [one] { $commits } Commit
+       *[other] { $commits } Commits
+    } nach { $path } exportiert
+import-done = { $notes ->
+        [one] { $notes } Notiz
+       *[other] { $notes } Notizen
+    }, { $prompts ->
+        [one] { $prompts } Prompt
+       *[other] { $prompts } Prompts
+    } und { $working_logs ->
+        [one] { $working_logs } Arbeitsprotokoll
+       *[other] { $working_logs } Arbeitsprotokolle
+    } importiert
+import-skipped-existing = { $count ->
+        [one] { $count } Notiz übersprungen, deren Commit bereits eine hat
+       *[other] { $count } Notizen übersprungen, deren Commits bereits eine haben
+    } (--force ersetzt sie)
+import-skipped-missing = { $count ->
+        [one] { $count } Eintrag übersprungen, dessen Commit in diesem Klon fehlt
+       *[other] { $count } Einträge übersprungen, deren Commits in diesem Klon fehlen
+    }
@@ -0,0 +1,66 @@
+# English, the source catalog. Every other catalog translates these messages.
+
+percent = { $value }%
+
+## git-ai stats
+
+stats-no-additions = (no additions)
+stats-mixed = mixed { $percent }
+stats-ai-accepted = { $percent } AI code accepted
+stats-waited-minutes = waited { $minutes }m for ai
+stats-waited-seconds = waited { $seconds }s for ai
+
+# Plain mode (--plain): one `label: value` line each
+stats-plain-additions = additions: { $count }
+stats-plain-deletions = deletions: { $count }
+stats-plain-human = human: { $percent } ({ $lines ->
+        [one] { $lines } line
+       *[other] { $lines } lines
+    })
+stats-plain-mixed = mixed: { $percent } ({ $lines ->
+        [one] { $lines } line
+       *[other] { $lines } lines
+    })
+stats-plain-ai = ai: { $percent } ({ $lines ->
+        [one] { $lines } line
+       *[other] { $lines } lines
+    })
+stats-plain-accepted = ai code accepted: { $percent }
+stats-plain-waited = waited for ai: { $duration }
+
+duration-minutes-seconds = { $minutes }m { $seconds }s
+duration-seconds = { $seconds }s
+
+## git-ai export / import
+
+export-done = Exported { $notes ->
+        [one] { $notes } note
+       *[other] { $notes } notes
+    }, { $prompts ->
+        [one] { $prompts } prompt
+       *[other] { $prompts } prompts
+    } and { $working_logs ->
+        [one] { $working_logs } working log
+       *[other] { $working_logs } working logs
+    } from { $commits ->
+        [one] { $commits } commit
+       *[other] { $commits } commits
+    } to { $path }
+import-done = Imported { $notes ->
+        [one] { $notes } note
+       *[other] { $notes } notes
+    }, { $prompts ->
+        [one] { $prompts } prompt
+       *[other] { $prompts } prompts
+    } and { $working_logs ->
+        [one] { $working_logs } working log
+       *[other] { $working_logs } working logs
+    }
+import-skipped-existing = Skipped { $count ->
+        [one] { $count } note for a commit that already has one
+       *[other] { $count } notes for commits that already have one
+    } (use --force to replace them)
+import-skipped-missing = Skipped { $count ->
+        [one] { $count } entry for a commit that doesn't exist in this clone
+       *[other] { $count } entries for commits that don't exist in this clone
+    }
@@ -0,0 +1,205 @@
+//! Translated user-facing messages and locale-aware number formatting.
+//!
+//! Messages live in Fluent (https://projectfluent.org) catalogs next to this file,
+//! one `<language>.ftl` per locale, and are looked up by id with [`tr`]. English is
+//! the source catalog: a message missing from another catalog falls back to it.
+//!
+//! The locale is taken from `GIT_AI_LOCALE`, then the POSIX `LC_ALL`, `LC_MESSAGES`
+//! and `LANG` variables, first non-empty wins. Unknown languages get English.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    De,
+}
+
+impl Locale {
+    /// Parse a POSIX locale (`de_DE.UTF-8`, `C`) or a BCP 47 tag (`de-AT`)
+    pub fn parse(value: &str) -> Option<Locale> {
+        let language = value.split(['_', '-', '.', '@']).next()?;
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+
+    fn from_env() -> Locale {
+        ["GIT_AI_LOCALE", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.trim().is_empty())
+            .and_then(|value| Locale::parse(value.trim()))
+            .unwrap_or(Locale::En)
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("en.ftl"),
+            Locale::De => include_str!("de.ftl"),
+        }
+    }
+
+    /// Format a count with this locale's digit grouping: `12,345` or `12.345`
+    pub fn format_number(self, value: u64) -> String {
+        let separator = match self {
+            Locale::En => ',',
+            Locale::De => '.',
+        };
+        let digits = value.to_string();
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
+/// Whole non-negative numbers in messages get digit grouping; anything else keeps
+/// Fluent's default formatting
+fn format_value(locale: Locale, value: &FluentValue) -> Option<String> {
+    match value {
+        FluentValue::Number(number) if number.value >= 0.0 && number.value.fract() == 0.0 => {
+            Some(locale.format_number(number.value as u64))
+        }
+        _ => None,
+    }
+}
+
+pub struct Catalog {
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Self {
+        let language: LanguageIdentifier = locale.tag().parse().unwrap_or_default();
+        let mut bundle = FluentBundle::new_concurrent(vec![language]);
+        // Terminal output doesn't need bidi isolation marks around arguments
+        bundle.set_use_isolating(false);
+        bundle.set_formatter(Some(match locale {
+            Locale::En => |value, _| format_value(Locale::En, value),
+            Locale::De => |value, _| format_value(Locale::De, value),
+        }));
+
+        for source in [locale.source(), Locale::En.source()] {
+            let resource = FluentResource::try_new(source.to_string())
+                .unwrap_or_else(|(resource, _)| resource);
+            // English goes in last; its duplicates of translated messages are
+            // rejected, which leaves it filling only the gaps
+            let _ = bundle.add_resource(resource);
+        }
+        Catalog { bundle }
+    }
+
+    /// The message `id` with `args` substituted, or `id` itself if no catalog has it
+    pub fn message(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let Some(pattern) = self.bundle.get_message(id).and_then(|m| m.value()) else {
+            return id.to_string();
+        };
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        self.bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned()
+    }
+
+    /// A percentage in this locale's style: `42%` or `42 %`
+    pub fn percent(&self, value: impl ToString) -> String {
+        self.message("percent", &[("value", value.to_string().into())])
+    }
+}
+
+/// The process-wide catalog for the detected locale
+pub fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::new(Locale::from_env()))
+}
+
+/// Look up a message in the process-wide catalog
+pub fn tr(id: &str, args: &[(&str, FluentValue)]) -> String {
+    catalog().message(id, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The ids of the catalog's messages; fails on syntax errors
+    fn message_ids(locale: Locale) -> Vec<&'static str> {
+        if let Err((_, errors)) = FluentResource::try_new(locale.source().to_string()) {
+            panic!("{:?} catalog has errors: {:?}", locale, errors);
+        }
+        locale
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_catalog_translates_every_english_message() {
+        let english = message_ids(Locale::En);
+        for locale in [Locale::De] {
+            let ids = message_ids(locale);
+            for id in &english {
+                assert!(ids.contains(id), "{:?} catalog is missing {}", locale, id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_posix_and_bcp47_locales() {
+        assert_eq!(Locale::parse("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::parse("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::parse("C"), Some(Locale::En));
+        assert_eq!(Locale::parse("en_US"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_numbers_and_plurals_follow_the_locale() {
+        assert_eq!(Locale::En.format_number(1234567), "1,234,567");
+        assert_eq!(Locale::De.format_number(1234567), "1.234.567");
+        assert_eq!(Locale::De.format_number(999), "999");
+
+        let en = Catalog::new(Locale::En);
+        let de = Catalog::new(Locale::De);
+        assert_eq!(en.percent(42), "42%");
+        assert_eq!(de.percent(42), "42 %");
+        assert_eq!(
+            en.message(
+                "stats-plain-ai",
+                &[("percent", en.percent(80).into()), ("lines", 1.into())]
+            ),
+            "ai: 80% (1 line)"
+        );
+        assert_eq!(
+            de.message(
+                "stats-plain-ai",
+                &[("percent", de.percent(80).into()), ("lines", 2500.into())]
+            ),
+            "KI: 80 % (2.500 Zeilen)"
+        );
+        assert_eq!(en.message("no-such-message", &[]), "no-such-message");
+    }
+}
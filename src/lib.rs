@@ -7,8 +7,15 @@ pub mod config;
 pub mod error;
 pub mod feature_flags;
 pub mod git;
+pub mod i18n;
 pub mod mdm;
 pub mod metrics;
 pub mod observability;
+pub mod org_config;
+pub mod output;
+pub mod plugins;
+pub mod policy;
+pub mod repo_config;
 pub mod repo_url;
+pub mod reporting;
 pub mod utils;
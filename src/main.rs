@@ -7,10 +7,17 @@ mod config;
 mod error;
 mod feature_flags;
 mod git;
+mod i18n;
 mod mdm;
 mod metrics;
 mod observability;
+mod org_config;
+mod output;
+mod plugins;
+mod policy;
+mod repo_config;
 mod repo_url;
+mod reporting;
 mod utils;
 
 use clap::Parser;
@@ -42,6 +49,7 @@ fn main() {
         let hook_args: Vec<String> = std::env::args().skip(1).collect();
         let exit_code =
             commands::git_hook_handlers::handle_git_hook_invocation(&binary_name, &hook_args);
+        observability::profile::finish();
         std::process::exit(exit_code);
     }
 
@@ -57,6 +65,7 @@ fn main() {
 
     if binary_name == "git-ai" || binary_name == "git-ai.exe" {
         commands::git_ai_handlers::handle_git_ai(&cli.args);
+        observability::profile::finish();
         std::process::exit(0);
     }
 
@@ -0,0 +1,374 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::utils::{binary_exists, generate_diff, home_dir, write_atomic};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Aider has no edit hooks, but it runs `lint-cmd` with the edited file names
+// appended after every edit it makes. The checkpoint runs there.
+const AIDER_LINT_CMD: &str = "checkpoint aider";
+
+// Trailing comment on the settings git-ai owns, so uninstall only removes those
+const MANAGED_MARKER: &str = "# managed by git-ai";
+
+pub struct AiderInstaller;
+
+/// A top-level key in `.aider.conf.yml`: the line it starts on and how many lines
+/// it spans (block lists continue on indented lines)
+struct YamlKey {
+    start: usize,
+    len: usize,
+    inline_value: String,
+}
+
+impl AiderInstaller {
+    fn config_path() -> PathBuf {
+        home_dir().join(".aider.conf.yml")
+    }
+
+    fn desired_lint_cmd(binary_path: &Path) -> String {
+        format!("{} {}", binary_path.display(), AIDER_LINT_CMD)
+    }
+
+    fn is_aider_checkpoint_command(cmd: &str) -> bool {
+        cmd.contains("git-ai") && cmd.contains("checkpoint") && cmd.contains("aider")
+    }
+
+    /// Aider lint commands are `lang: cmd` or, for every language, just `cmd`
+    fn is_language_specific(cmd: &str) -> bool {
+        cmd.split_once(':')
+            .map(|(lang, _)| !lang.is_empty() && lang.chars().all(|c| c.is_ascii_lowercase()))
+            .unwrap_or(false)
+    }
+
+    fn find_key(lines: &[&str], key: &str) -> Option<YamlKey> {
+        let prefix = format!("{}:", key);
+        let start = lines.iter().position(|line| line.starts_with(&prefix))?;
+        let inline_value = strip_comment(&lines[start][prefix.len()..])
+            .trim()
+            .to_string();
+        let len = 1 + lines[start + 1..]
+            .iter()
+            .take_while(|line| line.starts_with(' ') || line.starts_with('\t'))
+            .count();
+        Some(YamlKey {
+            start,
+            len,
+            inline_value,
+        })
+    }
+
+    /// Entries of the `lint-cmd` key, whether written as a scalar, a flow list or
+    /// a block list
+    fn lint_cmds(lines: &[&str]) -> Vec<String> {
+        let Some(key) = Self::find_key(lines, "lint-cmd") else {
+            return Vec::new();
+        };
+        if let Some(flow) = key
+            .inline_value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+        {
+            return flow
+                .split(',')
+                .map(unquote)
+                .filter(|cmd| !cmd.is_empty())
+                .collect();
+        }
+        if !key.inline_value.is_empty() {
+            return vec![unquote(&key.inline_value)];
+        }
+        lines[key.start + 1..key.start + key.len]
+            .iter()
+            .filter_map(|line| line.trim().strip_prefix('-'))
+            .map(|item| unquote(strip_comment(item)))
+            .filter(|cmd| !cmd.is_empty())
+            .collect()
+    }
+
+    /// Replace `key` (or append it) with `replacement` lines
+    fn set_key(lines: &mut Vec<String>, key: &str, replacement: Vec<String>) {
+        let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+        match Self::find_key(&borrowed, key) {
+            Some(found) => {
+                lines.splice(found.start..found.start + found.len, replacement);
+            }
+            None => lines.extend(replacement),
+        }
+    }
+
+    fn remove_key(lines: &mut Vec<String>, key: &str) {
+        Self::set_key(lines, key, Vec::new());
+    }
+
+    /// Add the checkpoint to `lint-cmd`, and turn on auto-lint and off auto-commits
+    /// (Aider commits before linting, which would leave nothing to checkpoint)
+    fn apply_config(content: &str, binary_path: &Path) -> Result<String, GitAiError> {
+        let desired_cmd = Self::desired_lint_cmd(binary_path);
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+        let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let mut lint_cmds: Vec<String> = Self::lint_cmds(&borrowed)
+            .into_iter()
+            .filter(|cmd| !Self::is_aider_checkpoint_command(cmd))
+            .collect();
+        if let Some(conflict) = lint_cmds
+            .iter()
+            .find(|cmd| !Self::is_language_specific(cmd))
+        {
+            return Err(GitAiError::Generic(format!(
+                "Aider already runs '{}' as the lint command for all languages; scope it to a language (e.g. 'python: {}') so git-ai can add its checkpoint",
+                conflict, conflict
+            )));
+        }
+        lint_cmds.push(desired_cmd);
+
+        let mut lint_block = vec!["lint-cmd:".to_string()];
+        lint_block.extend(lint_cmds.iter().map(|cmd| format!("  - {}", quote(cmd))));
+        Self::set_key(&mut lines, "lint-cmd", lint_block);
+        Self::set_key(
+            &mut lines,
+            "auto-lint",
+            vec![format!("auto-lint: true  {}", MANAGED_MARKER)],
+        );
+        Self::set_key(
+            &mut lines,
+            "auto-commits",
+            vec![format!("auto-commits: false  {}", MANAGED_MARKER)],
+        );
+
+        Ok(join_lines(&lines))
+    }
+
+    /// Undo [`Self::apply_config`]. Returns `None` if git-ai wasn't installed.
+    fn remove_config(content: &str) -> Option<String> {
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+        let lint_cmds = Self::lint_cmds(&borrowed);
+        let remaining: Vec<String> = lint_cmds
+            .iter()
+            .filter(|cmd| !Self::is_aider_checkpoint_command(cmd))
+            .cloned()
+            .collect();
+        let managed_keys: Vec<&str> = ["auto-lint", "auto-commits"]
+            .into_iter()
+            .filter(|key| {
+                Self::find_key(&borrowed, key)
+                    .map(|found| borrowed[found.start].contains(MANAGED_MARKER))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        if remaining.len() == lint_cmds.len() && managed_keys.is_empty() {
+            return None;
+        }
+
+        if remaining.is_empty() {
+            Self::remove_key(&mut lines, "lint-cmd");
+        } else if remaining.len() != lint_cmds.len() {
+            let mut lint_block = vec!["lint-cmd:".to_string()];
+            lint_block.extend(remaining.iter().map(|cmd| format!("  - {}", quote(cmd))));
+            Self::set_key(&mut lines, "lint-cmd", lint_block);
+        }
+        for key in managed_keys {
+            Self::remove_key(&mut lines, key);
+        }
+
+        Some(join_lines(&lines))
+    }
+}
+
+fn strip_comment(value: &str) -> &str {
+    match value.find(" #") {
+        Some(idx) => &value[..idx],
+        None => value,
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+    {
+        let inner = &value[1..value.len() - 1];
+        if value.starts_with('"') {
+            return inner.replace("\\\"", "\"").replace("\\\\", "\\");
+        }
+        return inner.replace("''", "'");
+    }
+    value.to_string()
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn join_lines(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", lines.join("\n"))
+    }
+}
+
+impl HookInstaller for AiderInstaller {
+    fn name(&self) -> &str {
+        "Aider"
+    }
+
+    fn id(&self) -> &str {
+        "aider"
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let config_path = Self::config_path();
+        if !binary_exists("aider") && !config_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = if config_path.exists() {
+            fs::read_to_string(&config_path)?
+        } else {
+            String::new()
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        let installed_cmd = Self::lint_cmds(&lines)
+            .into_iter()
+            .find(|cmd| Self::is_aider_checkpoint_command(cmd));
+
+        let hooks_up_to_date = installed_cmd.as_deref()
+            == Some(Self::desired_lint_cmd(&params.binary_path).as_str())
+            && Self::find_key(&lines, "auto-commits")
+                .map(|key| key.inline_value == "false")
+                .unwrap_or(false);
+
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: installed_cmd.is_some(),
+            hooks_up_to_date,
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+
+        let existing_content = if config_path.exists() {
+            fs::read_to_string(&config_path)?
+        } else {
+            String::new()
+        };
+
+        let new_content = Self::apply_config(&existing_content, &params.binary_path)?;
+        if new_content == existing_content {
+            return Ok(None);
+        }
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+
+        if !config_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(&config_path)?;
+        let Some(new_content) = Self::remove_config(&existing_content) else {
+            return Ok(None);
+        };
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_path() -> PathBuf {
+        PathBuf::from("/usr/local/bin/git-ai")
+    }
+
+    #[test]
+    fn test_apply_config_from_scratch() {
+        let config = AiderInstaller::apply_config("", &binary_path()).unwrap();
+        assert_eq!(
+            config,
+            "lint-cmd:\n  - \"/usr/local/bin/git-ai checkpoint aider\"\nauto-lint: true  # managed by git-ai\nauto-commits: false  # managed by git-ai\n"
+        );
+
+        // Installing again is a no-op
+        assert_eq!(
+            AiderInstaller::apply_config(&config, &binary_path()).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn test_apply_config_keeps_language_linters_and_other_settings() {
+        let existing = "model: sonnet\nlint-cmd: \"python: flake8 --select=E9\"\nauto-commits: true\ndark-mode: true\n";
+        let config = AiderInstaller::apply_config(existing, &binary_path()).unwrap();
+
+        assert_eq!(
+            config,
+            "model: sonnet\nlint-cmd:\n  - \"python: flake8 --select=E9\"\n  - \"/usr/local/bin/git-ai checkpoint aider\"\nauto-commits: false  # managed by git-ai\ndark-mode: true\nauto-lint: true  # managed by git-ai\n"
+        );
+
+        let lines: Vec<&str> = config.lines().collect();
+        assert_eq!(
+            AiderInstaller::lint_cmds(&lines),
+            vec![
+                "python: flake8 --select=E9".to_string(),
+                "/usr/local/bin/git-ai checkpoint aider".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_config_refuses_to_replace_catch_all_linter() {
+        let existing = "lint-cmd: [\"./lint.sh\"]\n";
+        assert!(AiderInstaller::apply_config(existing, &binary_path()).is_err());
+    }
+
+    #[test]
+    fn test_remove_config_restores_original_settings() {
+        let existing = "model: sonnet\nlint-cmd:\n  - 'python: flake8'\n";
+        let installed = AiderInstaller::apply_config(existing, &binary_path()).unwrap();
+
+        assert_eq!(
+            AiderInstaller::remove_config(&installed).unwrap(),
+            "model: sonnet\nlint-cmd:\n  - \"python: flake8\"\n"
+        );
+        assert!(AiderInstaller::remove_config(existing).is_none());
+
+        let from_scratch = AiderInstaller::apply_config("", &binary_path()).unwrap();
+        assert_eq!(AiderInstaller::remove_config(&from_scratch).unwrap(), "");
+    }
+}
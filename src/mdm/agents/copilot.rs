@@ -0,0 +1,171 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::utils::{
+    binary_exists, generate_diff, home_dir, is_vsc_editor_extension_installed, resolve_editor_cli,
+    write_atomic,
+};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Command pattern for hooks
+const COPILOT_TOOL_USE_CMD: &str = "checkpoint copilot --hook-input stdin";
+
+// Copilot hook events git-ai registers for
+const COPILOT_HOOK_EVENTS: [&str; 2] = ["preToolUse", "postToolUse"];
+
+// VS Code extension that runs Copilot's agent mode
+const COPILOT_CHAT_EXTENSION: &str = "github.copilot-chat";
+
+pub struct CopilotInstaller;
+
+impl CopilotInstaller {
+    fn config_dir() -> PathBuf {
+        home_dir().join(".copilot")
+    }
+
+    /// git-ai's own hooks file; Copilot loads every file in the hooks directory
+    fn hooks_path() -> PathBuf {
+        Self::config_dir().join("hooks").join("git-ai.json")
+    }
+
+    fn desired_hooks(binary_path: &Path) -> Value {
+        let hook = json!({
+            "type": "command",
+            "bash": format!("'{}' {}", binary_path.display(), COPILOT_TOOL_USE_CMD),
+            "powershell": format!("& '{}' {}", binary_path.display(), COPILOT_TOOL_USE_CMD),
+            "timeoutSec": 30
+        });
+        let mut hooks = serde_json::Map::new();
+        for event in COPILOT_HOOK_EVENTS {
+            hooks.insert(event.to_string(), json!([hook.clone()]));
+        }
+        json!({
+            "version": 1,
+            "hooks": hooks
+        })
+    }
+
+    /// Whether the Copilot CLI is installed, or VS Code has the Copilot Chat
+    /// extension that runs agent mode
+    fn copilot_installed() -> bool {
+        if binary_exists("copilot") || Self::config_dir().exists() {
+            return true;
+        }
+        resolve_editor_cli("code")
+            .and_then(|cli| is_vsc_editor_extension_installed(&cli, COPILOT_CHAT_EXTENSION).ok())
+            .unwrap_or(false)
+    }
+}
+
+impl HookInstaller for CopilotInstaller {
+    fn name(&self) -> &str {
+        "GitHub Copilot CLI"
+    }
+
+    fn id(&self) -> &str {
+        "copilot"
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        if !Self::copilot_installed() {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let hooks_path = Self::hooks_path();
+        if !hooks_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&hooks_path)?;
+        let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: true,
+            hooks_up_to_date: existing == Self::desired_hooks(&params.binary_path),
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let hooks_path = Self::hooks_path();
+
+        let existing_content = if hooks_path.exists() {
+            fs::read_to_string(&hooks_path)?
+        } else {
+            String::new()
+        };
+        let existing: Value = serde_json::from_str(&existing_content).unwrap_or(Value::Null);
+
+        let desired = Self::desired_hooks(&params.binary_path);
+        if existing == desired {
+            return Ok(None);
+        }
+
+        let new_content = serde_json::to_string_pretty(&desired)?;
+        let diff_output = generate_diff(&hooks_path, &existing_content, &new_content);
+
+        if !dry_run {
+            if let Some(dir) = hooks_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            write_atomic(&hooks_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let hooks_path = Self::hooks_path();
+
+        if !hooks_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(&hooks_path)?;
+        let diff_output = generate_diff(&hooks_path, &existing_content, "");
+
+        if !dry_run {
+            fs::remove_file(&hooks_path)?;
+        }
+
+        Ok(Some(diff_output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desired_hooks_run_checkpoint_for_each_tool_use_event() {
+        let hooks = CopilotInstaller::desired_hooks(&PathBuf::from("/usr/local/bin/git-ai"));
+
+        assert_eq!(hooks["version"], 1);
+        for event in COPILOT_HOOK_EVENTS {
+            let event_hooks = hooks["hooks"][event].as_array().unwrap();
+            assert_eq!(event_hooks.len(), 1);
+            assert_eq!(event_hooks[0]["type"], "command");
+            assert_eq!(
+                event_hooks[0]["bash"],
+                "'/usr/local/bin/git-ai' checkpoint copilot --hook-input stdin"
+            );
+        }
+    }
+}
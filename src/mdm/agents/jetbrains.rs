@@ -1,11 +1,12 @@
+use crate::config::{Config, PluginChannel};
 use crate::error::GitAiError;
 use crate::mdm::hook_installer::{
     HookCheckResult, HookInstaller, HookInstallerParams, InstallResult, UninstallResult,
 };
 use crate::mdm::jetbrains::{
     DetectedIde, MARKETPLACE_URL, MIN_INTELLIJ_BUILD, PLUGIN_ID, download_plugin_from_marketplace,
-    find_jetbrains_installations, install_plugin_to_directory, install_plugin_via_cli,
-    is_plugin_installed,
+    download_plugin_version, find_jetbrains_installations, install_plugin_to_directory,
+    install_plugin_via_cli, is_plugin_installed,
 };
 use crate::utils::debug_log;
 
@@ -54,36 +55,48 @@ impl JetBrainsInstaller {
             };
         }
 
+        // The IDE CLI always installs the latest stable build, so a pinned version or
+        // another channel (`git-ai mdm jetbrains`) goes straight to the Marketplace
+        let plugin = Config::get().jetbrains_plugin();
+        let pinned = plugin.version.is_some() || plugin.channel != PluginChannel::Stable;
+
         // Try CLI installation first
-        match install_plugin_via_cli(&detected.binary_path, PLUGIN_ID) {
-            Ok(true) => {
-                return InstallResult {
-                    changed: true,
-                    diff: None,
-                    message: format!("{}: Plugin installed via CLI", ide_name),
-                };
-            }
-            Ok(false) => {
-                debug_log(&format!(
-                    "JetBrains: CLI install failed for {}, trying direct download",
-                    ide_name
-                ));
-            }
-            Err(e) => {
-                debug_log(&format!(
-                    "JetBrains: CLI install error for {}: {}",
-                    ide_name, e
-                ));
+        if !pinned {
+            match install_plugin_via_cli(&detected.binary_path, PLUGIN_ID) {
+                Ok(true) => {
+                    return InstallResult {
+                        changed: true,
+                        diff: None,
+                        message: format!("{}: Plugin installed via CLI", ide_name),
+                    };
+                }
+                Ok(false) => {
+                    debug_log(&format!(
+                        "JetBrains: CLI install failed for {}, trying direct download",
+                        ide_name
+                    ));
+                }
+                Err(e) => {
+                    debug_log(&format!(
+                        "JetBrains: CLI install error for {}: {}",
+                        ide_name, e
+                    ));
+                }
             }
         }
 
         // Try direct download from Marketplace
         if let Some(build_number) = &detected.build_number {
-            match download_plugin_from_marketplace(
-                PLUGIN_ID,
-                detected.ide.product_code,
-                build_number,
-            ) {
+            let download = match &plugin.version {
+                Some(version) => download_plugin_version(PLUGIN_ID, version, plugin.channel),
+                None => download_plugin_from_marketplace(
+                    PLUGIN_ID,
+                    detected.ide.product_code,
+                    build_number,
+                    plugin.channel,
+                ),
+            };
+            match download {
                 Ok(zip_data) => {
                     match install_plugin_to_directory(&zip_data, &detected.plugins_dir) {
                         Ok(()) => {
@@ -1,33 +1,56 @@
+mod aider;
 mod claude_code;
 mod codex;
+mod copilot;
 mod cursor;
 mod droid;
 mod gemini;
 mod jetbrains;
 mod opencode;
+mod plugin_source;
 mod vscode;
+mod windsurf;
+mod zed;
 
+pub use aider::AiderInstaller;
 pub use claude_code::ClaudeCodeInstaller;
 pub use codex::CodexInstaller;
+pub use copilot::CopilotInstaller;
 pub use cursor::CursorInstaller;
 pub use droid::DroidInstaller;
 pub use gemini::GeminiInstaller;
 pub use jetbrains::JetBrainsInstaller;
 pub use opencode::OpenCodeInstaller;
+pub use plugin_source::PluginSourceInstaller;
 pub use vscode::VSCodeInstaller;
+pub use windsurf::WindsurfInstaller;
+pub use zed::ZedInstaller;
 
 use super::hook_installer::HookInstaller;
 
-/// Get all available hook installers
+/// Get all available hook installers, including `git-ai-source-*` plugins on PATH
 pub fn get_all_installers() -> Vec<Box<dyn HookInstaller>> {
-    vec![
+    let mut installers: Vec<Box<dyn HookInstaller>> = vec![
         Box::new(ClaudeCodeInstaller),
         Box::new(CodexInstaller),
         Box::new(CursorInstaller),
         Box::new(VSCodeInstaller),
+        Box::new(CopilotInstaller),
+        Box::new(WindsurfInstaller),
         Box::new(OpenCodeInstaller),
         Box::new(GeminiInstaller),
         Box::new(DroidInstaller),
+        Box::new(AiderInstaller),
         Box::new(JetBrainsInstaller),
-    ]
+        Box::new(ZedInstaller),
+    ];
+
+    // Built-in integrations take precedence over plugins with the same id
+    for plugin in PluginSourceInstaller::discover() {
+        if !installers.iter().any(|i| i.id() == plugin.id()) {
+            installers.push(Box::new(plugin));
+        }
+    }
+
+    installers
 }
@@ -0,0 +1,138 @@
+use crate::commands::checkpoint_agent::plugin_source_preset::SOURCE_PLUGIN_PREFIX;
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::plugins::{Plugin, discover_plugins, run_plugin};
+use serde::{Deserialize, Serialize};
+
+/// `git-ai-source-<name> info` response
+#[derive(Debug, Deserialize)]
+struct PluginInfo {
+    /// Display name; defaults to the plugin name
+    #[serde(default)]
+    name: Option<String>,
+    tool_installed: bool,
+    #[serde(default)]
+    hooks_installed: bool,
+    #[serde(default)]
+    hooks_up_to_date: Option<bool>,
+}
+
+/// stdin for `install-hooks` / `uninstall-hooks`
+#[derive(Debug, Serialize)]
+struct PluginHookRequest<'a> {
+    binary_path: &'a str,
+    dry_run: bool,
+}
+
+/// `install-hooks` / `uninstall-hooks` response
+#[derive(Debug, Deserialize)]
+struct PluginHookResponse {
+    changed: bool,
+    #[serde(default)]
+    diff: Option<String>,
+}
+
+/// Hook installer for an agent integration provided by a `git-ai-source-*` plugin
+pub struct PluginSourceInstaller {
+    plugin: Plugin,
+    display_name: String,
+}
+
+impl PluginSourceInstaller {
+    /// Installer for `plugin`, using the display name it reports from `info`
+    pub fn new(plugin: Plugin) -> Self {
+        let mut installer = Self {
+            display_name: plugin.name.clone(),
+            plugin,
+        };
+        if let Ok(PluginInfo {
+            name: Some(name), ..
+        }) = installer.call::<PluginInfo>("info", "{}")
+        {
+            installer.display_name = name;
+        }
+        installer
+    }
+
+    /// Installers for every source plugin on PATH
+    pub fn discover() -> Vec<Self> {
+        discover_plugins(SOURCE_PLUGIN_PREFIX)
+            .into_iter()
+            .map(Self::new)
+            .collect()
+    }
+
+    fn call<T: for<'de> Deserialize<'de>>(
+        &self,
+        subcommand: &str,
+        stdin: &str,
+    ) -> Result<T, GitAiError> {
+        let output = run_plugin(&self.plugin, subcommand, stdin)?;
+        if output.exit_code != Some(0) {
+            return Err(GitAiError::Generic(format!(
+                "{} {} failed: {}",
+                self.plugin.path.display(),
+                subcommand,
+                output.stderr.trim()
+            )));
+        }
+        serde_json::from_str(&output.stdout).map_err(|e| {
+            GitAiError::Generic(format!(
+                "{} {} returned invalid JSON: {}",
+                self.plugin.path.display(),
+                subcommand,
+                e
+            ))
+        })
+    }
+
+    fn change_hooks(
+        &self,
+        subcommand: &str,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let binary_path = params.binary_path.to_string_lossy();
+        let request = serde_json::to_string(&PluginHookRequest {
+            binary_path: &binary_path,
+            dry_run,
+        })?;
+        let response: PluginHookResponse = self.call(subcommand, &request)?;
+        Ok(response.changed.then(|| response.diff.unwrap_or_default()))
+    }
+}
+
+impl HookInstaller for PluginSourceInstaller {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn id(&self) -> &str {
+        &self.plugin.name
+    }
+
+    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let info: PluginInfo = self.call("info", "{}")?;
+        Ok(HookCheckResult {
+            tool_installed: info.tool_installed,
+            hooks_installed: info.hooks_installed,
+            hooks_up_to_date: info.hooks_up_to_date.unwrap_or(info.hooks_installed),
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        self.change_hooks("install-hooks", params, dry_run)
+    }
+
+    fn uninstall_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        self.change_hooks("uninstall-hooks", params, dry_run)
+    }
+}
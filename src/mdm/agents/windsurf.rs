@@ -0,0 +1,437 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookInstaller, HookInstallerParams, InstallResult,
+};
+use crate::mdm::utils::{
+    generate_diff, home_dir, install_vsc_editor_extension, is_vsc_editor_extension_installed,
+    resolve_editor_cli, settings_paths_for_products, should_process_settings_target, write_atomic,
+};
+use crate::utils::debug_log;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Command patterns for hooks
+const WINDSURF_PRE_WRITE_CMD: &str = "checkpoint windsurf --hook-input stdin";
+const WINDSURF_POST_WRITE_CMD: &str = "checkpoint windsurf --hook-input stdin";
+
+// Cascade hook events git-ai registers for
+const WINDSURF_HOOK_EVENTS: [&str; 2] = ["pre_write_code", "post_write_code"];
+
+pub struct WindsurfInstaller;
+
+impl WindsurfInstaller {
+    fn config_dir() -> PathBuf {
+        home_dir().join(".codeium").join("windsurf")
+    }
+
+    fn hooks_path() -> PathBuf {
+        Self::config_dir().join("hooks.json")
+    }
+
+    fn settings_targets() -> Vec<PathBuf> {
+        settings_paths_for_products(&["Windsurf"])
+    }
+
+    fn is_windsurf_checkpoint_command(cmd: &str) -> bool {
+        cmd.contains("git-ai") && cmd.contains("checkpoint") && cmd.contains("windsurf")
+    }
+
+    fn desired_commands(binary_path: &Path) -> [(&'static str, String); 2] {
+        [
+            (
+                WINDSURF_HOOK_EVENTS[0],
+                format!("{} {}", binary_path.display(), WINDSURF_PRE_WRITE_CMD),
+            ),
+            (
+                WINDSURF_HOOK_EVENTS[1],
+                format!("{} {}", binary_path.display(), WINDSURF_POST_WRITE_CMD),
+            ),
+        ]
+    }
+
+    /// The git-ai checkpoint command registered for `event`, if any
+    fn installed_command<'a>(hooks: &'a Value, event: &str) -> Option<&'a str> {
+        hooks
+            .get("hooks")
+            .and_then(|h| h.get(event))
+            .and_then(|v| v.as_array())?
+            .iter()
+            .filter_map(|hook| hook.get("command").and_then(|c| c.as_str()))
+            .find(|cmd| Self::is_windsurf_checkpoint_command(cmd))
+    }
+
+    /// Add or update the git-ai hooks, leaving any other hooks untouched
+    fn merge_hooks(existing: &Value, binary_path: &Path) -> Value {
+        let mut merged = existing.clone();
+        if !merged.is_object() {
+            merged = json!({});
+        }
+        let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
+
+        for (event, desired_cmd) in Self::desired_commands(binary_path) {
+            let mut event_hooks = hooks_obj
+                .get(event)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+
+            let desired_hook = json!({
+                "command": desired_cmd,
+                "show_output": false
+            });
+
+            let existing_idx = event_hooks.iter().position(|hook| {
+                hook.get("command")
+                    .and_then(|c| c.as_str())
+                    .map(Self::is_windsurf_checkpoint_command)
+                    .unwrap_or(false)
+            });
+
+            match existing_idx {
+                Some(idx) => {
+                    if event_hooks[idx].get("command").and_then(|c| c.as_str())
+                        != Some(desired_cmd.as_str())
+                    {
+                        event_hooks[idx] = desired_hook;
+                    }
+                }
+                None => event_hooks.push(desired_hook),
+            }
+
+            if let Some(obj) = hooks_obj.as_object_mut() {
+                obj.insert(event.to_string(), Value::Array(event_hooks));
+            }
+        }
+
+        if let Some(root) = merged.as_object_mut() {
+            root.insert("hooks".to_string(), hooks_obj);
+        }
+        merged
+    }
+
+    /// Remove the git-ai hooks. Returns `None` if there were none.
+    fn remove_hooks(existing: &Value) -> Option<Value> {
+        let mut merged = existing.clone();
+        let hooks_obj = merged.get_mut("hooks")?;
+
+        let mut changed = false;
+        for event in WINDSURF_HOOK_EVENTS {
+            if let Some(event_hooks) = hooks_obj.get_mut(event).and_then(|v| v.as_array_mut()) {
+                let original_len = event_hooks.len();
+                event_hooks.retain(|hook| {
+                    !hook
+                        .get("command")
+                        .and_then(|c| c.as_str())
+                        .map(Self::is_windsurf_checkpoint_command)
+                        .unwrap_or(false)
+                });
+                changed |= event_hooks.len() != original_len;
+            }
+        }
+
+        changed.then_some(merged)
+    }
+}
+
+impl HookInstaller for WindsurfInstaller {
+    fn name(&self) -> &str {
+        "Windsurf"
+    }
+
+    fn id(&self) -> &str {
+        "windsurf"
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let has_cli = resolve_editor_cli("windsurf").is_some();
+        let has_dotfiles = Self::config_dir().exists();
+        let has_settings_targets = Self::settings_targets()
+            .iter()
+            .any(|path| should_process_settings_target(path));
+
+        if !has_cli && !has_dotfiles && !has_settings_targets {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let hooks_path = Self::hooks_path();
+        if !hooks_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&hooks_path)?;
+        let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+
+        let desired = Self::desired_commands(&params.binary_path);
+        let hooks_installed = desired
+            .iter()
+            .any(|(event, _)| Self::installed_command(&existing, event).is_some());
+        let hooks_up_to_date = desired
+            .iter()
+            .all(|(event, cmd)| Self::installed_command(&existing, event) == Some(cmd.as_str()));
+
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed,
+            hooks_up_to_date,
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let hooks_path = Self::hooks_path();
+
+        if let Some(dir) = hooks_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let existing_content = if hooks_path.exists() {
+            fs::read_to_string(&hooks_path)?
+        } else {
+            String::new()
+        };
+
+        let existing: Value = if existing_content.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(&existing_content)?
+        };
+
+        let merged = Self::merge_hooks(&existing, &params.binary_path);
+        if existing == merged {
+            return Ok(None);
+        }
+
+        let new_content = serde_json::to_string_pretty(&merged)?;
+        let diff_output = generate_diff(&hooks_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&hooks_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let hooks_path = Self::hooks_path();
+
+        if !hooks_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(&hooks_path)?;
+        let existing: Value = serde_json::from_str(&existing_content)?;
+
+        let Some(merged) = Self::remove_hooks(&existing) else {
+            return Ok(None);
+        };
+
+        let new_content = serde_json::to_string_pretty(&merged)?;
+        let diff_output = generate_diff(&hooks_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&hooks_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn install_extras(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Vec<InstallResult>, GitAiError> {
+        let mut results = Vec::new();
+        let manual_install_message = "Windsurf: Unable to automatically install extension. Please search for 'git-ai-vscode' in the Windsurf extensions tab".to_string();
+
+        // Install VS Code extension
+        if let Some(cli) = resolve_editor_cli("windsurf") {
+            match is_vsc_editor_extension_installed(&cli, "git-ai.git-ai-vscode") {
+                Ok(true) => {
+                    results.push(InstallResult {
+                        changed: false,
+                        diff: None,
+                        message: "Windsurf: Extension already installed".to_string(),
+                    });
+                }
+                Ok(false) => {
+                    if dry_run {
+                        results.push(InstallResult {
+                            changed: true,
+                            diff: None,
+                            message: "Windsurf: Pending extension install".to_string(),
+                        });
+                    } else {
+                        println!("Installing extensions...");
+                        println!("\tInstalling extension 'git-ai.git-ai-vscode'...");
+                        match install_vsc_editor_extension(&cli, "git-ai.git-ai-vscode") {
+                            Ok(()) => {
+                                results.push(InstallResult {
+                                    changed: true,
+                                    diff: None,
+                                    message: "\tExtension 'git-ai.git-ai-vscode' was successfully installed.".to_string(),
+                                });
+                            }
+                            Err(e) => {
+                                debug_log(&format!(
+                                    "Windsurf: Error automatically installing extension: {}",
+                                    e
+                                ));
+                                results.push(InstallResult {
+                                    changed: false,
+                                    diff: None,
+                                    message: manual_install_message,
+                                });
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    results.push(InstallResult {
+                        changed: false,
+                        diff: None,
+                        message: format!("Windsurf: Failed to check extension: {}", e),
+                    });
+                }
+            }
+        } else {
+            results.push(InstallResult {
+                changed: false,
+                diff: None,
+                message: manual_install_message,
+            });
+        }
+
+        // Configure git.path
+        {
+            use crate::mdm::utils::{git_shim_path_string, update_git_path_setting};
+
+            let git_path = git_shim_path_string();
+            for settings_path in Self::settings_targets() {
+                if !should_process_settings_target(&settings_path) {
+                    continue;
+                }
+
+                match update_git_path_setting(&settings_path, &git_path, dry_run) {
+                    Ok(Some(diff)) => {
+                        results.push(InstallResult {
+                            changed: true,
+                            diff: Some(diff),
+                            message: format!(
+                                "Windsurf: git.path updated in {}",
+                                settings_path.display()
+                            ),
+                        });
+                    }
+                    Ok(None) => {
+                        results.push(InstallResult {
+                            changed: false,
+                            diff: None,
+                            message: format!(
+                                "Windsurf: git.path already configured in {}",
+                                settings_path.display()
+                            ),
+                        });
+                    }
+                    Err(e) => {
+                        results.push(InstallResult {
+                            changed: false,
+                            diff: None,
+                            message: format!("Windsurf: Failed to configure git.path: {}", e),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_path() -> PathBuf {
+        PathBuf::from("/usr/local/bin/git-ai")
+    }
+
+    #[test]
+    fn test_merge_hooks_from_scratch() {
+        let merged = WindsurfInstaller::merge_hooks(&json!({}), &binary_path());
+
+        for event in WINDSURF_HOOK_EVENTS {
+            let hooks = merged["hooks"][event].as_array().unwrap();
+            assert_eq!(hooks.len(), 1);
+            assert_eq!(
+                hooks[0]["command"],
+                "/usr/local/bin/git-ai checkpoint windsurf --hook-input stdin"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_hooks_preserves_other_hooks_and_updates_stale_command() {
+        let existing = json!({
+            "hooks": {
+                "pre_user_prompt": [{"command": "echo prompt"}],
+                "post_write_code": [
+                    {"command": "echo 'after'"},
+                    {"command": "/old/path/git-ai checkpoint windsurf --hook-input stdin"}
+                ]
+            }
+        });
+
+        let merged = WindsurfInstaller::merge_hooks(&existing, &binary_path());
+
+        assert_eq!(
+            merged["hooks"]["pre_user_prompt"],
+            existing["hooks"]["pre_user_prompt"]
+        );
+        let post_write = merged["hooks"]["post_write_code"].as_array().unwrap();
+        assert_eq!(post_write.len(), 2);
+        assert_eq!(post_write[0]["command"], "echo 'after'");
+        assert_eq!(
+            post_write[1]["command"],
+            "/usr/local/bin/git-ai checkpoint windsurf --hook-input stdin"
+        );
+
+        // Merging again is a no-op
+        assert_eq!(
+            WindsurfInstaller::merge_hooks(&merged, &binary_path()),
+            merged
+        );
+    }
+
+    #[test]
+    fn test_remove_hooks() {
+        let installed = WindsurfInstaller::merge_hooks(
+            &json!({"hooks": {"pre_write_code": [{"command": "echo keep"}]}}),
+            &binary_path(),
+        );
+
+        let removed = WindsurfInstaller::remove_hooks(&installed).unwrap();
+        assert_eq!(
+            removed["hooks"]["pre_write_code"],
+            json!([{"command": "echo keep"}])
+        );
+        assert_eq!(removed["hooks"]["post_write_code"], json!([]));
+
+        assert!(WindsurfInstaller::remove_hooks(&removed).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::utils::{binary_exists, generate_diff, home_dir, write_atomic};
+use jsonc_parser::cst::CstRootNode;
+use jsonc_parser::{ParseOptions, json};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Zed's agent has no edit hooks. git-ai registers as a context server instead,
+// whose checkpoint tool the agent calls around its edits (see `git-ai mcp zed`).
+const ZED_CONTEXT_SERVER: &str = "git-ai";
+const ZED_MCP_ARGS: [&str; 2] = ["mcp", "zed"];
+
+pub struct ZedInstaller;
+
+impl ZedInstaller {
+    fn config_dir() -> PathBuf {
+        #[cfg(windows)]
+        {
+            if let Ok(appdata) = std::env::var("APPDATA")
+                && !appdata.is_empty()
+            {
+                return PathBuf::from(appdata).join("Zed");
+            }
+        }
+        // Zed uses ~/.config/zed on macOS too
+        match std::env::var("XDG_CONFIG_HOME") {
+            Ok(xdg) if !xdg.is_empty() => PathBuf::from(xdg).join("zed"),
+            _ => home_dir().join(".config").join("zed"),
+        }
+    }
+
+    fn settings_path() -> PathBuf {
+        Self::config_dir().join("settings.json")
+    }
+
+    fn desired_server(binary_path: &Path) -> Value {
+        serde_json::json!({
+            "source": "custom",
+            "command": binary_path.to_string_lossy(),
+            "args": ZED_MCP_ARGS,
+        })
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<CstRootNode, GitAiError> {
+        let input = if content.trim().is_empty() {
+            "{}"
+        } else {
+            content
+        };
+        CstRootNode::parse(input, &ParseOptions::default()).map_err(|err| {
+            GitAiError::Generic(format!("Failed to parse {}: {}", path.display(), err))
+        })
+    }
+
+    /// The git-ai context server entry in Zed's settings, if any
+    fn installed_server(root: &CstRootNode) -> Option<Value> {
+        root.object_value()?
+            .object_value("context_servers")?
+            .get(ZED_CONTEXT_SERVER)?
+            .value()?
+            .to_serde_value()
+    }
+
+    /// Settings with the git-ai context server added or updated, keeping comments
+    /// and every other setting. `None` if it is already up to date.
+    fn apply_settings(
+        content: &str,
+        path: &Path,
+        binary_path: &Path,
+    ) -> Result<Option<String>, GitAiError> {
+        let root = Self::parse(content, path)?;
+        let desired = Self::desired_server(binary_path);
+        if Self::installed_server(&root).as_ref() == Some(&desired) {
+            return Ok(None);
+        }
+
+        let servers = root
+            .object_value_or_set()
+            .object_value_or_set("context_servers");
+        let command = binary_path.to_string_lossy().replace('\\', "\\\\");
+        let value = json!({
+            "source": "custom",
+            "command": (command.as_str()),
+            "args": ["mcp", "zed"],
+        });
+        match servers.get(ZED_CONTEXT_SERVER) {
+            Some(prop) => prop.set_value(value),
+            None => {
+                servers.append(ZED_CONTEXT_SERVER, value);
+            }
+        }
+        Ok(Some(root.to_string()))
+    }
+
+    /// Settings without the git-ai context server. `None` if there was none.
+    fn remove_settings(content: &str, path: &Path) -> Result<Option<String>, GitAiError> {
+        let root = Self::parse(content, path)?;
+        let Some(prop) = root
+            .object_value()
+            .and_then(|obj| obj.object_value("context_servers"))
+            .and_then(|servers| servers.get(ZED_CONTEXT_SERVER))
+        else {
+            return Ok(None);
+        };
+        prop.remove();
+        Ok(Some(root.to_string()))
+    }
+}
+
+impl HookInstaller for ZedInstaller {
+    fn name(&self) -> &str {
+        "Zed"
+    }
+
+    fn id(&self) -> &str {
+        "zed"
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let has_binary = binary_exists("zed") || binary_exists("zeditor");
+        let has_app = cfg!(target_os = "macos") && Path::new("/Applications/Zed.app").exists();
+        if !has_binary && !has_app && !Self::config_dir().exists() {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let settings_path = Self::settings_path();
+        if !settings_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&settings_path)?;
+        let installed = Self::parse(&content, &settings_path)
+            .ok()
+            .and_then(|root| Self::installed_server(&root));
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: installed.is_some(),
+            hooks_up_to_date: installed == Some(Self::desired_server(&params.binary_path)),
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let settings_path = Self::settings_path();
+        let existing_content = if settings_path.exists() {
+            fs::read_to_string(&settings_path)?
+        } else {
+            String::new()
+        };
+
+        let Some(new_content) =
+            Self::apply_settings(&existing_content, &settings_path, &params.binary_path)?
+        else {
+            return Ok(None);
+        };
+        let diff_output = generate_diff(&settings_path, &existing_content, &new_content);
+
+        if !dry_run {
+            if let Some(dir) = settings_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            write_atomic(&settings_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let settings_path = Self::settings_path();
+        if !settings_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(&settings_path)?;
+        let Some(new_content) = Self::remove_settings(&existing_content, &settings_path)? else {
+            return Ok(None);
+        };
+        let diff_output = generate_diff(&settings_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&settings_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"// Zed settings
+{
+  "theme": "One Dark", // keep me
+  "context_servers": {
+    "other": {"source": "custom", "command": "other-server", "args": []}
+  }
+}
+"#;
+
+    fn binary_path() -> PathBuf {
+        PathBuf::from("/usr/local/bin/git-ai")
+    }
+
+    fn parsed(content: &str) -> Value {
+        ZedInstaller::parse(content, Path::new("settings.json"))
+            .unwrap()
+            .object_value()
+            .unwrap()
+            .to_serde_value()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply_settings_keeps_comments_and_other_servers() {
+        let path = Path::new("settings.json");
+        let updated = ZedInstaller::apply_settings(SETTINGS, path, &binary_path())
+            .unwrap()
+            .unwrap();
+
+        assert!(updated.contains("// keep me"), "{updated}");
+        let settings = parsed(&updated);
+        assert_eq!(settings["theme"], "One Dark");
+        assert_eq!(
+            settings["context_servers"]["other"]["command"],
+            "other-server"
+        );
+        assert_eq!(
+            settings["context_servers"]["git-ai"],
+            serde_json::json!({
+                "source": "custom",
+                "command": "/usr/local/bin/git-ai",
+                "args": ["mcp", "zed"]
+            })
+        );
+
+        // Applying again is a no-op
+        assert!(
+            ZedInstaller::apply_settings(&updated, path, &binary_path())
+                .unwrap()
+                .is_none()
+        );
+
+        // A moved binary updates the entry in place
+        let moved = ZedInstaller::apply_settings(&updated, path, Path::new("/opt/git-ai"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            parsed(&moved)["context_servers"]["git-ai"]["command"],
+            "/opt/git-ai"
+        );
+    }
+
+    #[test]
+    fn test_apply_settings_from_scratch() {
+        let updated = ZedInstaller::apply_settings("", Path::new("settings.json"), &binary_path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            parsed(&updated)["context_servers"]["git-ai"]["args"],
+            serde_json::json!(["mcp", "zed"])
+        );
+    }
+
+    #[test]
+    fn test_remove_settings() {
+        let path = Path::new("settings.json");
+        let installed = ZedInstaller::apply_settings(SETTINGS, path, &binary_path())
+            .unwrap()
+            .unwrap();
+
+        let removed = ZedInstaller::remove_settings(&installed, path)
+            .unwrap()
+            .unwrap();
+        let settings = parsed(&removed);
+        assert!(settings["context_servers"].get("git-ai").is_none());
+        assert_eq!(
+            settings["context_servers"]["other"]["command"],
+            "other-server"
+        );
+        assert!(removed.contains("// keep me"));
+
+        assert!(
+            ZedInstaller::remove_settings(&removed, path)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::error::GitAiError;
 use crate::git::repository::exec_git;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Ensures the libexec symlink exists for Fork compatibility.
 /// Creates a symlink from <binary_parent>/../libexec to the real git's libexec.
@@ -89,3 +89,132 @@ fn create_junction(
 
     Ok(())
 }
+
+/// Removes the links an install puts next to the binary: the `git` shim and `git-og`
+/// in the binary's directory and the `libexec` symlink (or junction) beside it. A link
+/// is only removed once its target shows git-ai put it there, so a binary installed
+/// into a shared directory like `/usr/local/bin` leaves another `git` alone. Returns
+/// the removed paths.
+pub fn remove_git_symlinks(dry_run: bool) -> Result<Vec<PathBuf>, GitAiError> {
+    let exe_path = std::env::current_exe()?;
+    if exe_path.to_string_lossy().contains("/nix/store") {
+        return Ok(Vec::new());
+    }
+
+    let output = exec_git(&["--exec-path".to_string()])?;
+    let exec_path = PathBuf::from(String::from_utf8(output.stdout)?.trim());
+    remove_install_links(&exe_path, exec_path.parent(), dry_run)
+}
+
+fn remove_install_links(
+    exe_path: &Path,
+    libexec_target: Option<&Path>,
+    dry_run: bool,
+) -> Result<Vec<PathBuf>, GitAiError> {
+    let binary_dir = exe_path
+        .parent()
+        .ok_or_else(|| GitAiError::Generic("Cannot get binary directory".to_string()))?;
+
+    // `git-og` points at the real git, so it is only ours when the `git` shim beside
+    // it resolves to this binary
+    let git_shim = binary_dir.join("git");
+    let mut candidates = Vec::new();
+    if resolves_to(&git_shim, exe_path) {
+        candidates.push(git_shim);
+        candidates.push(binary_dir.join("git-og"));
+    }
+    if let (Some(base_dir), Some(libexec_target)) = (binary_dir.parent(), libexec_target) {
+        let libexec = base_dir.join("libexec");
+        if std::fs::read_link(&libexec).is_ok_and(|target| target == libexec_target) {
+            candidates.push(libexec);
+        }
+    }
+
+    let mut removed = Vec::new();
+    for path in candidates {
+        let Ok(metadata) = path.symlink_metadata() else {
+            continue;
+        };
+        #[cfg(windows)]
+        let is_link =
+            metadata.file_type().is_symlink() || (metadata.is_dir() && path.ends_with("libexec"));
+        #[cfg(not(windows))]
+        let is_link = metadata.file_type().is_symlink();
+        if !is_link {
+            continue;
+        }
+
+        if !dry_run {
+            // Junctions are directories on Windows; a real (non-empty) libexec isn't
+            #[cfg(windows)]
+            if std::fs::remove_dir(&path).is_err() && std::fs::remove_file(&path).is_err() {
+                continue;
+            }
+            #[cfg(not(windows))]
+            std::fs::remove_file(&path)?;
+        }
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+/// Whether `link` is a symlink that ends up at `exe_path`
+fn resolves_to(link: &Path, exe_path: &Path) -> bool {
+    let is_symlink = link
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+    is_symlink
+        && match (link.canonicalize(), exe_path.canonicalize()) {
+            (Ok(target), Ok(exe)) => target == exe,
+            _ => false,
+        }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_remove_install_links_removes_links_to_this_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin_dir = dir.path().join("bin");
+        let real_libexec = dir.path().join("real").join("libexec");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        std::fs::create_dir_all(&real_libexec).unwrap();
+        let exe = bin_dir.join("git-ai");
+        std::fs::write(&exe, "").unwrap();
+        symlink(&exe, bin_dir.join("git")).unwrap();
+        symlink("/usr/bin/git", bin_dir.join("git-og")).unwrap();
+        symlink(&real_libexec, dir.path().join("libexec")).unwrap();
+
+        let removed = remove_install_links(&exe, Some(&real_libexec), false).unwrap();
+        assert_eq!(removed.len(), 3, "{:?}", removed);
+        assert!(bin_dir.join("git").symlink_metadata().is_err());
+        assert!(bin_dir.join("git-og").symlink_metadata().is_err());
+        assert!(dir.path().join("libexec").symlink_metadata().is_err());
+        assert!(exe.exists());
+    }
+
+    #[test]
+    fn test_remove_install_links_keeps_foreign_git_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin_dir = dir.path().join("bin");
+        let cellar_git = dir.path().join("Cellar").join("git");
+        let other_libexec = dir.path().join("Cellar").join("libexec");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        std::fs::create_dir_all(&other_libexec).unwrap();
+        std::fs::write(&cellar_git, "").unwrap();
+        let exe = bin_dir.join("git-ai");
+        std::fs::write(&exe, "").unwrap();
+        symlink(&cellar_git, bin_dir.join("git")).unwrap();
+        symlink(&cellar_git, bin_dir.join("git-og")).unwrap();
+        symlink(&other_libexec, dir.path().join("libexec")).unwrap();
+
+        let removed = remove_install_links(&exe, Some(Path::new("/usr/libexec")), false).unwrap();
+        assert!(removed.is_empty(), "{:?}", removed);
+        assert!(bin_dir.join("git").symlink_metadata().is_ok());
+        assert!(bin_dir.join("git-og").symlink_metadata().is_ok());
+        assert!(dir.path().join("libexec").symlink_metadata().is_ok());
+    }
+}
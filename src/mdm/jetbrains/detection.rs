@@ -488,6 +488,6 @@ fn get_plugins_dir(product_code: &str, build_number: Option<&str>) -> PathBuf {
 
 /// Check if the Git AI plugin is installed for a detected IDE
 pub fn is_plugin_installed(detected: &DetectedIde) -> bool {
-    let plugin_dir = detected.plugins_dir.join("git-ai-intellij");
+    let plugin_dir = detected.plugins_dir.join(super::PLUGIN_DIR_NAME);
     plugin_dir.exists()
 }
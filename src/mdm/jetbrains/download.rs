@@ -1,3 +1,4 @@
+use crate::config::PluginChannel;
 use crate::error::GitAiError;
 use crate::utils::debug_log;
 use std::io::{Cursor, Read};
@@ -5,20 +6,52 @@ use std::path::Path;
 
 /// Download plugin from JetBrains Marketplace
 ///
+/// Fetches the newest build on `channel` that is compatible with the IDE build.
 /// Returns the ZIP file contents as bytes
 pub fn download_plugin_from_marketplace(
     plugin_id: &str,
     product_code: &str,
     build_number: &str,
+    channel: PluginChannel,
 ) -> Result<Vec<u8>, GitAiError> {
     let url = format!(
-        "https://plugins.jetbrains.com/pluginManager?action=download&id={}&build={}-{}",
-        plugin_id, product_code, build_number
+        "https://plugins.jetbrains.com/pluginManager?action=download&id={}&build={}-{}{}",
+        plugin_id,
+        product_code,
+        build_number,
+        channel_query(channel)
     );
+    fetch_plugin_zip(&url)
+}
+
+/// Download one specific plugin version from JetBrains Marketplace, for pinning
+/// and rolling back. Returns the ZIP file contents as bytes
+pub fn download_plugin_version(
+    plugin_id: &str,
+    version: &str,
+    channel: PluginChannel,
+) -> Result<Vec<u8>, GitAiError> {
+    let url = format!(
+        "https://plugins.jetbrains.com/plugin/download?pluginId={}&version={}{}",
+        plugin_id,
+        version,
+        channel_query(channel)
+    );
+    fetch_plugin_zip(&url)
+}
+
+/// Marketplace serves the stable channel when no channel is given
+pub(crate) fn channel_query(channel: PluginChannel) -> String {
+    match channel {
+        PluginChannel::Stable => String::new(),
+        other => format!("&channel={}", other.as_str()),
+    }
+}
 
+fn fetch_plugin_zip(url: &str) -> Result<Vec<u8>, GitAiError> {
     debug_log(&format!("JetBrains: Downloading plugin from {}", url));
 
-    let response = minreq::get(&url)
+    let response = minreq::get(url)
         .with_timeout(120) // 120 second timeout for plugin download
         .send()
         .map_err(|e| GitAiError::Generic(format!("Failed to download plugin: {}", e)))?;
@@ -1,9 +1,15 @@
 pub mod detection;
 pub mod download;
 pub mod ide_types;
+pub mod versions;
 
 pub use detection::{find_jetbrains_installations, is_plugin_installed};
 pub use download::{
-    download_plugin_from_marketplace, install_plugin_to_directory, install_plugin_via_cli,
+    download_plugin_from_marketplace, download_plugin_version, install_plugin_to_directory,
+    install_plugin_via_cli,
 };
 pub use ide_types::{DetectedIde, MARKETPLACE_URL, MIN_INTELLIJ_BUILD, PLUGIN_ID};
+pub use versions::{
+    PLUGIN_DIR_NAME, installed_plugin_version, is_valid_plugin_version, latest_plugin_version,
+    replace_installed_plugin,
+};
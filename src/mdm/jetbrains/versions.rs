@@ -0,0 +1,136 @@
+use crate::config::PluginChannel;
+use crate::error::GitAiError;
+use crate::mdm::jetbrains::download::channel_query;
+use crate::mdm::jetbrains::ide_types::DetectedIde;
+use crate::utils::debug_log;
+use std::io::Read;
+use std::path::Path;
+
+/// Directory the plugin ZIP extracts to inside an IDE's plugins directory
+pub const PLUGIN_DIR_NAME: &str = "git-ai-intellij";
+
+/// Ask JetBrains Marketplace for the newest plugin version on `channel` that is
+/// compatible with the given IDE build
+pub fn latest_plugin_version(
+    plugin_id: &str,
+    product_code: &str,
+    build_number: &str,
+    channel: PluginChannel,
+) -> Result<Option<String>, GitAiError> {
+    let url = format!(
+        "https://plugins.jetbrains.com/plugins/list?pluginId={}&build={}-{}{}",
+        plugin_id,
+        product_code,
+        build_number,
+        channel_query(channel)
+    );
+    debug_log(&format!("JetBrains: Checking for updates at {}", url));
+
+    let response = minreq::get(&url)
+        .with_timeout(30)
+        .send()
+        .map_err(|e| GitAiError::Generic(format!("Failed to check for plugin updates: {}", e)))?;
+
+    if response.status_code != 200 {
+        return Err(GitAiError::Generic(format!(
+            "JetBrains Marketplace returned status {}",
+            response.status_code
+        )));
+    }
+
+    let body = response
+        .as_str()
+        .map_err(|e| GitAiError::Generic(format!("Invalid Marketplace response: {}", e)))?;
+    Ok(xml_version(body))
+}
+
+/// Version of the plugin installed in an IDE, read from the `plugin.xml` in its jars
+pub fn installed_plugin_version(detected: &DetectedIde) -> Option<String> {
+    let lib_dir = detected.plugins_dir.join(PLUGIN_DIR_NAME).join("lib");
+    let entries = std::fs::read_dir(&lib_dir).ok()?;
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jar"))
+        .find_map(|jar| jar_plugin_version(&jar))
+}
+
+fn jar_plugin_version(jar: &Path) -> Option<String> {
+    let file = std::fs::File::open(jar).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut plugin_xml = archive.by_name("META-INF/plugin.xml").ok()?;
+    let mut content = String::new();
+    plugin_xml.read_to_string(&mut content).ok()?;
+    xml_version(&content)
+}
+
+/// The first `<version>` element of a `plugin.xml` or a Marketplace plugin list
+fn xml_version(xml: &str) -> Option<String> {
+    let start = xml.find("<version>")? + "<version>".len();
+    let end = start + xml[start..].find("</version>")?;
+    let version = xml[start..end].trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Replace whatever plugin version is installed with the given plugin ZIP, so
+/// pinning to an older version rolls back cleanly
+pub fn replace_installed_plugin(zip_data: &[u8], detected: &DetectedIde) -> Result<(), GitAiError> {
+    let plugin_dir = detected.plugins_dir.join(PLUGIN_DIR_NAME);
+    if plugin_dir.exists() {
+        std::fs::remove_dir_all(&plugin_dir).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Failed to remove old plugin at {}: {}",
+                plugin_dir.display(),
+                e
+            ))
+        })?;
+    }
+    super::install_plugin_to_directory(zip_data, &detected.plugins_dir)
+}
+
+/// Plugin versions are pinned by their Marketplace version string, e.g. `0.4.2` or
+/// `0.5.0-beta.1`
+pub fn is_valid_plugin_version(version: &str) -> bool {
+    !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_version_reads_plugin_xml_and_marketplace_list() {
+        let plugin_xml = r#"<idea-plugin>
+  <id>com.usegitai.plugins.jetbrains</id>
+  <version>0.4.2</version>
+</idea-plugin>"#;
+        assert_eq!(xml_version(plugin_xml), Some("0.4.2".to_string()));
+
+        let list = r#"<plugin-repository><category name="VCS">
+<idea-plugin downloads="10" size="1" date="1" updatedDate="1" url="">
+<name>Git AI</name><id>com.usegitai.plugins.jetbrains</id>
+<version> 0.5.0-beta.1 </version></idea-plugin></category></plugin-repository>"#;
+        assert_eq!(xml_version(list), Some("0.5.0-beta.1".to_string()));
+
+        assert_eq!(xml_version("<plugin-repository/>"), None);
+        assert_eq!(xml_version("<version></version>"), None);
+    }
+
+    #[test]
+    fn test_plugin_version_validation() {
+        assert!(is_valid_plugin_version("0.4.2"));
+        assert!(is_valid_plugin_version("0.5.0-beta.1"));
+        assert!(!is_valid_plugin_version(""));
+        assert!(!is_valid_plugin_version("1.0&channel=beta"));
+        assert!(!is_valid_plugin_version("1.0 2"));
+    }
+
+    #[test]
+    fn test_channel_query() {
+        assert_eq!(channel_query(PluginChannel::Stable), "");
+        assert_eq!(channel_query(PluginChannel::Beta), "&channel=beta");
+    }
+}
@@ -8,4 +8,4 @@ pub mod skills_installer;
 pub mod spinner;
 pub mod utils;
 
-pub use ensure_git_symlinks::ensure_git_symlinks;
+pub use ensure_git_symlinks::{ensure_git_symlinks, remove_git_symlinks};
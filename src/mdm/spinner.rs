@@ -1,3 +1,4 @@
+use crate::output::{self, Status};
 use indicatif::{ProgressBar, ProgressStyle};
 
 /// Spinner UI component for showing progress
@@ -7,12 +8,30 @@ pub struct Spinner {
 
 impl Spinner {
     pub fn new(message: &str) -> Self {
+        let mode = output::mode();
+        // Screen readers re-announce every redraw, so plain mode doesn't animate
+        if mode.plain {
+            return Self {
+                pb: ProgressBar::hidden(),
+            };
+        }
+
         let pb = ProgressBar::new_spinner();
+        let tick_strings: &[&str] = if mode.unicode {
+            &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]
+        } else {
+            &["-", "\\", "|", "/"]
+        };
+        let template = if mode.color {
+            "{spinner:.green} {msg}"
+        } else {
+            "{spinner} {msg}"
+        };
         pb.set_style(
             ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
+                .template(template)
                 .unwrap()
-                .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+                .tick_strings(tick_strings),
         );
         pb.set_message(message.to_string());
         pb.enable_steady_tick(std::time::Duration::from_millis(100));
@@ -37,45 +56,46 @@ impl Spinner {
     pub fn success(&self, message: &str) {
         // Clear spinner and show success with green checkmark and bold green text
         self.pb.finish_and_clear();
-        println!("\x1b[1;32m✓ {}\x1b[0m", message);
+        println!("{}", output::mode().status(Status::Success, message));
     }
 
     pub fn pending(&self, message: &str) {
         // Clear spinner and show pending with yellow warning triangle and bold yellow text
         self.pb.finish_and_clear();
-        println!("\x1b[1;33m⚠ {}\x1b[0m", message);
+        println!("{}", output::mode().status(Status::Warning, message));
     }
 
     pub fn error(&self, message: &str) {
         // Clear spinner and show error with red X and bold red text
         self.pb.finish_and_clear();
-        println!("\x1b[1;31m✗ {}\x1b[0m", message);
+        println!("{}", output::mode().status(Status::Error, message));
     }
 
     #[allow(dead_code)]
     pub fn skipped(&self, message: &str) {
         // Clear spinner and show skipped with gray circle and gray text
         self.pb.finish_and_clear();
-        println!("\x1b[90m○ {}\x1b[0m", message);
+        println!("{}", output::mode().status(Status::Skipped, message));
     }
 }
 
 /// Print a formatted diff using colors
 pub fn print_diff(diff_text: &str) {
     // Print a formatted diff using colors
+    let mode = output::mode();
     for line in diff_text.lines() {
         if line.starts_with("+++") || line.starts_with("---") {
             // File headers in bold
-            println!("\x1b[1m{}\x1b[0m", line);
+            println!("{}", mode.paint("1", line));
         } else if line.starts_with('+') {
             // Additions in green
-            println!("\x1b[32m{}\x1b[0m", line);
+            println!("{}", mode.paint("32", line));
         } else if line.starts_with('-') {
             // Deletions in red
-            println!("\x1b[31m{}\x1b[0m", line);
+            println!("{}", mode.paint("31", line));
         } else if line.starts_with("@@") {
             // Hunk headers in cyan
-            println!("\x1b[36m{}\x1b[0m", line);
+            println!("{}", mode.paint("36", line));
         } else {
             // Context lines normal
             println!("{}", line);
@@ -317,6 +317,55 @@ fn get_editor_cli_candidates(cli_name: &str) -> Vec<(PathBuf, PathBuf)> {
                 }
             }
         }
+        "windsurf" => {
+            #[cfg(target_os = "macos")]
+            {
+                for apps_dir in [PathBuf::from("/Applications"), home.join("Applications")] {
+                    let app = apps_dir.join("Windsurf.app");
+                    candidates.push((
+                        app.join("Contents").join("MacOS").join("Electron"),
+                        app.join("Contents")
+                            .join("Resources")
+                            .join("app")
+                            .join("out")
+                            .join("cli.js"),
+                    ));
+                }
+            }
+
+            #[cfg(all(unix, not(target_os = "macos")))]
+            {
+                for base in [
+                    PathBuf::from("/usr/share/windsurf"),
+                    PathBuf::from("/opt/Windsurf"),
+                    home.join(".local").join("share").join("windsurf"),
+                ] {
+                    candidates.push((
+                        base.join("windsurf"),
+                        base.join("resources")
+                            .join("app")
+                            .join("out")
+                            .join("cli.js"),
+                    ));
+                }
+            }
+
+            #[cfg(windows)]
+            {
+                if let Ok(localappdata) = std::env::var("LOCALAPPDATA") {
+                    let base = PathBuf::from(&localappdata)
+                        .join("Programs")
+                        .join("Windsurf");
+                    candidates.push((
+                        base.join("Windsurf.exe"),
+                        base.join("resources")
+                            .join("app")
+                            .join("out")
+                            .join("cli.js"),
+                    ));
+                }
+            }
+        }
         _ => {}
     }
 
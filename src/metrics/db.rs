@@ -1,15 +1,32 @@
 //! Simple metrics storage for offline buffering.
 //!
 //! Events are stored here when API conditions aren't met.
-//! Server handles idempotency - no retry/queue logic needed.
+//! Each event carries an idempotency key; the database refuses duplicate keys
+//! and remembers keys that were already uploaded so retries never resend them.
+//! Error, performance and message events that couldn't be delivered wait in
+//! `pending_envelopes` with a backoff schedule until they're resent.
+//!
+//! The schema is versioned: `MIGRATIONS` upgrades it one step at a time when the
+//! database is opened, after snapshotting the old file (`git-ai db upgrade` runs
+//! the same thing on demand).
 
+use crate::api::rate_limit::CircuitState;
 use crate::error::GitAiError;
+use crate::metrics::MetricEvent;
 use rusqlite::{Connection, OptionalExtension, params};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
 /// Current schema version (must match MIGRATIONS.len())
-const SCHEMA_VERSION: usize = 2;
+const SCHEMA_VERSION: usize = 5;
+
+/// How long uploaded idempotency keys are remembered
+const UPLOADED_KEY_RETENTION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// How long a pending envelope keeps being retried after it was first queued,
+/// so a long outage (or a laptop offline for the weekend) doesn't lose events
+pub const MAX_ENVELOPE_AGE_SECS: u64 = 7 * 24 * 60 * 60;
 
 /// Database migrations - each migration upgrades the schema by one version
 const MIGRATIONS: &[&str] = &[
@@ -27,6 +44,37 @@ const MIGRATIONS: &[&str] = &[
         last_sent_ts INTEGER NOT NULL
     );
     "#,
+    // Migration 2 -> 3: Idempotency keys for deduplicating queued and uploaded events
+    r#"
+    ALTER TABLE metrics ADD COLUMN dedup_key TEXT;
+    CREATE UNIQUE INDEX idx_metrics_dedup_key ON metrics(dedup_key);
+    CREATE TABLE uploaded_metrics (
+        dedup_key TEXT PRIMARY KEY,
+        uploaded_ts INTEGER NOT NULL
+    );
+    "#,
+    // Migration 3 -> 4: API circuit breaker state shared across processes
+    r#"
+    CREATE TABLE api_circuit_breaker (
+        host TEXT PRIMARY KEY,
+        consecutive_failures INTEGER NOT NULL,
+        open_until_ts INTEGER NOT NULL
+    );
+    "#,
+    // Migration 4 -> 5: Observability envelopes waiting to be resent
+    r#"
+    CREATE TABLE pending_envelopes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        destination TEXT NOT NULL,
+        payload_json TEXT NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        last_error TEXT,
+        next_attempt_ts INTEGER NOT NULL,
+        created_ts INTEGER NOT NULL
+    );
+    CREATE INDEX idx_pending_envelopes_next_attempt
+        ON pending_envelopes(next_attempt_ts);
+    "#,
 ];
 
 /// Global database singleton
@@ -39,6 +87,34 @@ pub struct MetricRecord {
     pub event_json: String,
 }
 
+/// An observability event that couldn't be delivered, waiting to be resent
+#[derive(Debug, Clone)]
+pub struct PendingEnvelope {
+    pub id: i64,
+    /// Where the payload goes (e.g. `sentry-oss`, `posthog`)
+    pub destination: String,
+    /// The event exactly as it would have been sent
+    pub payload_json: String,
+}
+
+/// Outcome of bringing the schema up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaUpgrade {
+    pub from: usize,
+    pub to: usize,
+    /// Snapshot taken before migrating, if any migration ran on existing data
+    pub backup: Option<PathBuf>,
+}
+
+/// Rows removed by [`MetricsDatabase::prune_older_than`], per table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrunedRows {
+    pub metrics: usize,
+    pub uploaded_keys: usize,
+    pub envelopes: usize,
+    pub throttle: usize,
+}
+
 /// Database wrapper for metrics storage
 pub struct MetricsDatabase {
     conn: Connection,
@@ -65,15 +141,21 @@ impl MetricsDatabase {
 
     /// Create a new database connection
     fn new() -> Result<Self, GitAiError> {
-        let db_path = Self::database_path()?;
+        let mut db = Self::open(&Self::database_path()?)?;
+        db.initialize_schema()?;
 
+        Ok(db)
+    }
+
+    /// Open the database file without touching its schema
+    fn open(db_path: &Path) -> Result<Self, GitAiError> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
         // Open with WAL mode and performance optimizations
-        let conn = Connection::open(&db_path)?;
+        let conn = Connection::open(db_path)?;
         conn.execute_batch(
             r#"
             PRAGMA journal_mode=WAL;
@@ -83,14 +165,19 @@ impl MetricsDatabase {
             "#,
         )?;
 
-        let mut db = Self { conn };
-        db.initialize_schema()?;
+        Ok(Self { conn })
+    }
 
-        Ok(db)
+    /// Bring the database up to the current schema (`git-ai db upgrade`).
+    /// Opening it through [`MetricsDatabase::global`] does the same implicitly;
+    /// this reports what changed.
+    pub fn upgrade() -> Result<SchemaUpgrade, GitAiError> {
+        let mut db = Self::open(&Self::database_path()?)?;
+        db.initialize_schema()
     }
 
     /// Get database path: ~/.git-ai/internal/metrics-db
-    fn database_path() -> Result<PathBuf, GitAiError> {
+    pub(crate) fn database_path() -> Result<PathBuf, GitAiError> {
         // Allow test override via environment variable
         #[cfg(any(test, feature = "test-support"))]
         if let Ok(test_path) = std::env::var("GIT_AI_TEST_METRICS_DB_PATH") {
@@ -102,8 +189,9 @@ impl MetricsDatabase {
         Ok(home.join(".git-ai").join("internal").join("metrics-db"))
     }
 
-    /// Initialize schema and handle migrations
-    fn initialize_schema(&mut self) -> Result<(), GitAiError> {
+    /// Initialize schema and handle migrations. A database that already holds
+    /// data is backed up before its first migration runs.
+    fn initialize_schema(&mut self) -> Result<SchemaUpgrade, GitAiError> {
         // FAST PATH: Check if database is already at current version
         let version_check: Result<usize, _> = self.conn.query_row(
             "SELECT value FROM schema_metadata WHERE key = 'version'",
@@ -118,7 +206,11 @@ impl MetricsDatabase {
 
         if let Ok(current_version) = version_check {
             if current_version == SCHEMA_VERSION {
-                return Ok(());
+                return Ok(SchemaUpgrade {
+                    from: current_version,
+                    to: SCHEMA_VERSION,
+                    backup: None,
+                });
             }
             if current_version > SCHEMA_VERSION {
                 return Err(GitAiError::Generic(format!(
@@ -154,27 +246,27 @@ impl MetricsDatabase {
             )
             .unwrap_or(0);
 
+        // A new database has nothing worth keeping
+        let backup = if current_version > 0 {
+            Some(self.backup(current_version)?)
+        } else {
+            None
+        };
+
         // Apply all missing migrations sequentially
         for target_version in current_version..SCHEMA_VERSION {
             self.apply_migration(target_version)?;
-
-            if target_version == 0 {
-                self.conn.execute(
-                    "INSERT INTO schema_metadata (key, value) VALUES ('version', ?1)",
-                    params![(target_version + 1).to_string()],
-                )?;
-            } else {
-                self.conn.execute(
-                    "UPDATE schema_metadata SET value = ?1 WHERE key = 'version'",
-                    params![(target_version + 1).to_string()],
-                )?;
-            }
         }
 
-        Ok(())
+        Ok(SchemaUpgrade {
+            from: current_version,
+            to: SCHEMA_VERSION,
+            backup,
+        })
     }
 
-    /// Apply a single migration
+    /// Apply a single migration, bumping the stored version in the same
+    /// transaction so a failed migration leaves the database as it was
     fn apply_migration(&mut self, from_version: usize) -> Result<(), GitAiError> {
         if from_version >= MIGRATIONS.len() {
             return Err(GitAiError::Generic(format!(
@@ -187,12 +279,75 @@ impl MetricsDatabase {
         let migration_sql = MIGRATIONS[from_version];
         let tx = self.conn.transaction()?;
         tx.execute_batch(migration_sql)?;
+        tx.execute(
+            r#"
+            INSERT INTO schema_metadata (key, value) VALUES ('version', ?1)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+            params![(from_version + 1).to_string()],
+        )?;
         tx.commit()?;
 
         Ok(())
     }
 
+    /// Snapshot the database next to itself as `<db>.v<version>.bak`,
+    /// replacing an older snapshot of the same version
+    fn backup(&self, version: usize) -> Result<PathBuf, GitAiError> {
+        let db_path = self
+            .conn
+            .path()
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| {
+                GitAiError::Generic("Can't back up an in-memory database".to_string())
+            })?;
+        let backup_path = PathBuf::from(format!("{}.v{}.bak", db_path, version));
+        if backup_path.exists() {
+            std::fs::remove_file(&backup_path)?;
+        }
+        self.conn
+            .execute("VACUUM INTO ?1", params![backup_path.to_string_lossy()])?;
+        Ok(backup_path)
+    }
+
+    /// Rebuild the database file to reclaim space left by deleted rows
+    pub fn vacuum(&mut self) -> Result<(), GitAiError> {
+        self.conn
+            .execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+        Ok(())
+    }
+
+    /// Delete queued events, uploaded keys, pending envelopes and throttle
+    /// entries from before `cutoff_ts`
+    pub fn prune_older_than(&mut self, cutoff_ts: u64) -> Result<PrunedRows, GitAiError> {
+        let cutoff = cutoff_ts.min(i64::MAX as u64) as i64;
+        let tx = self.conn.transaction()?;
+        let pruned = PrunedRows {
+            metrics: tx.execute(
+                "DELETE FROM metrics WHERE json_extract(event_json, '$.t') < ?1",
+                params![cutoff],
+            )?,
+            uploaded_keys: tx.execute(
+                "DELETE FROM uploaded_metrics WHERE uploaded_ts < ?1",
+                params![cutoff],
+            )?,
+            envelopes: tx.execute(
+                "DELETE FROM pending_envelopes WHERE created_ts < ?1",
+                params![cutoff],
+            )?,
+            throttle: tx.execute(
+                "DELETE FROM agent_usage_throttle WHERE last_sent_ts < ?1",
+                params![cutoff],
+            )?,
+        };
+        tx.commit()?;
+        Ok(pruned)
+    }
+
     /// Insert events as JSON strings
+    ///
+    /// Events whose idempotency key is already queued or was already uploaded
+    /// are skipped, so re-processing the same log file is harmless.
     pub fn insert_events(&mut self, events: &[String]) -> Result<(), GitAiError> {
         if events.is_empty() {
             return Ok(());
@@ -201,10 +356,19 @@ impl MetricsDatabase {
         let tx = self.conn.transaction()?;
 
         {
-            let mut stmt = tx.prepare_cached("INSERT INTO metrics (event_json) VALUES (?1)")?;
+            let mut stmt = tx.prepare_cached(
+                r#"
+                INSERT OR IGNORE INTO metrics (event_json, dedup_key)
+                SELECT ?1, ?2
+                WHERE NOT EXISTS (SELECT 1 FROM uploaded_metrics WHERE dedup_key = ?2)
+                "#,
+            )?;
 
             for event_json in events {
-                stmt.execute(params![event_json])?;
+                let dedup_key = serde_json::from_str::<MetricEvent>(event_json)
+                    .ok()
+                    .map(|event| event.dedup_key());
+                stmt.execute(params![event_json, dedup_key])?;
             }
         }
 
@@ -253,6 +417,54 @@ impl MetricsDatabase {
         Ok(())
     }
 
+    /// Returns the subset of `keys` that were already uploaded successfully.
+    pub fn uploaded_keys(&self, keys: &[String]) -> Result<HashSet<String>, GitAiError> {
+        let mut uploaded = HashSet::new();
+        if keys.is_empty() {
+            return Ok(uploaded);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT 1 FROM uploaded_metrics WHERE dedup_key = ?1")?;
+        for key in keys {
+            if stmt.exists(params![key])? {
+                uploaded.insert(key.clone());
+            }
+        }
+
+        Ok(uploaded)
+    }
+
+    /// Remember that events with these keys were accepted by the server.
+    ///
+    /// Also forgets keys older than the retention window to keep the table small.
+    pub fn mark_uploaded(&mut self, keys: &[String], now_ts: u64) -> Result<(), GitAiError> {
+        let tx = self.conn.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                r#"
+                INSERT INTO uploaded_metrics (dedup_key, uploaded_ts)
+                VALUES (?1, ?2)
+                ON CONFLICT(dedup_key) DO UPDATE SET uploaded_ts = excluded.uploaded_ts
+                "#,
+            )?;
+            for key in keys {
+                stmt.execute(params![key, now_ts as i64])?;
+            }
+        }
+
+        let cutoff = now_ts.saturating_sub(UPLOADED_KEY_RETENTION_SECS);
+        tx.execute(
+            "DELETE FROM uploaded_metrics WHERE uploaded_ts < ?1",
+            params![cutoff as i64],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
     /// Get count of pending metrics
     pub fn count(&self) -> Result<usize, GitAiError> {
         let count: i64 = self
@@ -261,6 +473,179 @@ impl MetricsDatabase {
         Ok(count as usize)
     }
 
+    /// Queue an undeliverable event for `destination`, due for a first retry
+    /// after the initial backoff
+    pub fn queue_envelope(
+        &mut self,
+        destination: &str,
+        payload_json: &str,
+        error: &str,
+        now_ts: u64,
+    ) -> Result<(), GitAiError> {
+        self.conn.execute(
+            r#"
+            INSERT INTO pending_envelopes (
+                destination, payload_json, attempts, last_error, next_attempt_ts, created_ts
+            ) VALUES (?1, ?2, 1, ?3, ?4, ?5)
+            "#,
+            params![
+                destination,
+                payload_json,
+                error,
+                next_envelope_attempt(1, now_ts) as i64,
+                now_ts as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Pending envelopes due at or before `due_ts` with an ID above `after_id`
+    /// (oldest first), so callers can page through the queue
+    pub fn due_envelopes(
+        &self,
+        due_ts: u64,
+        after_id: i64,
+        limit: usize,
+    ) -> Result<Vec<PendingEnvelope>, GitAiError> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, destination, payload_json FROM pending_envelopes
+            WHERE next_attempt_ts <= ?1 AND id > ?2
+            ORDER BY id ASC
+            LIMIT ?3
+            "#,
+        )?;
+
+        let due_ts = due_ts.min(i64::MAX as u64) as i64;
+        let rows = stmt.query_map(params![due_ts, after_id, limit], |row| {
+            Ok(PendingEnvelope {
+                id: row.get(0)?,
+                destination: row.get(1)?,
+                payload_json: row.get(2)?,
+            })
+        })?;
+
+        let mut envelopes = Vec::new();
+        for row in rows {
+            envelopes.push(row?);
+        }
+
+        Ok(envelopes)
+    }
+
+    /// Record another failed delivery: back off further, or drop the envelope
+    /// once it was queued more than `MAX_ENVELOPE_AGE_SECS` ago. Returns whether
+    /// it was kept.
+    pub fn record_envelope_failure(
+        &mut self,
+        id: i64,
+        error: &str,
+        now_ts: u64,
+    ) -> Result<bool, GitAiError> {
+        let row: Option<(i64, i64)> = self
+            .conn
+            .query_row(
+                "SELECT attempts, created_ts FROM pending_envelopes WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((attempts, created_ts)) = row else {
+            return Ok(false);
+        };
+
+        let attempts = attempts as u32 + 1;
+        if now_ts.saturating_sub(created_ts as u64) >= MAX_ENVELOPE_AGE_SECS {
+            self.delete_envelopes(&[id])?;
+            return Ok(false);
+        }
+
+        self.conn.execute(
+            r#"
+            UPDATE pending_envelopes
+            SET attempts = ?1, last_error = ?2, next_attempt_ts = ?3
+            WHERE id = ?4
+            "#,
+            params![
+                attempts as i64,
+                error,
+                next_envelope_attempt(attempts, now_ts) as i64,
+                id
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Delete pending envelopes by ID (after they were delivered or given up on)
+    pub fn delete_envelopes(&mut self, ids: &[i64]) -> Result<(), GitAiError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached("DELETE FROM pending_envelopes WHERE id = ?1")?;
+
+            for id in ids {
+                stmt.execute(params![id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get count of envelopes waiting to be resent
+    pub fn pending_envelope_count(&self) -> Result<usize, GitAiError> {
+        let count: i64 =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM pending_envelopes", [], |row| {
+                    row.get(0)
+                })?;
+        Ok(count as usize)
+    }
+
+    /// Circuit breaker state for an API host (closed with no failures if unknown)
+    pub fn api_circuit_state(&self, host: &str) -> Result<CircuitState, GitAiError> {
+        let state = self
+            .conn
+            .query_row(
+                "SELECT consecutive_failures, open_until_ts FROM api_circuit_breaker WHERE host = ?1",
+                params![host],
+                |row| {
+                    Ok(CircuitState {
+                        consecutive_failures: row.get::<_, i64>(0)? as u32,
+                        open_until_ts: row.get::<_, i64>(1)? as u64,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(state.unwrap_or_default())
+    }
+
+    pub fn set_api_circuit_state(
+        &mut self,
+        host: &str,
+        state: &CircuitState,
+    ) -> Result<(), GitAiError> {
+        self.conn.execute(
+            r#"
+            INSERT INTO api_circuit_breaker (host, consecutive_failures, open_until_ts)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(host) DO UPDATE SET
+                consecutive_failures = excluded.consecutive_failures,
+                open_until_ts = excluded.open_until_ts
+            "#,
+            params![
+                host,
+                state.consecutive_failures as i64,
+                state.open_until_ts as i64
+            ],
+        )?;
+        Ok(())
+    }
+
     /// Returns whether an `agent_usage` event should be emitted for this prompt_id.
     ///
     /// If emitted, this method also updates the prompt's last-sent timestamp.
@@ -303,6 +688,13 @@ impl MetricsDatabase {
     }
 }
 
+/// When an envelope that has failed `attempts` times is next due: 5 minutes
+/// after the first failure, doubling each time up to 6 hours
+fn next_envelope_attempt(attempts: u32, now_ts: u64) -> u64 {
+    let delay_secs = (5 * 60u64) << attempts.saturating_sub(1).min(16);
+    now_ts + delay_secs.min(6 * 60 * 60)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,7 +737,7 @@ mod tests {
                 |row| row.get(0),
             )
             .unwrap();
-        assert_eq!(version, "2");
+        assert_eq!(version, "5");
     }
 
     #[test]
@@ -461,4 +853,222 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_insert_events_skips_duplicate_keys() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        let event = r#"{"i":"key-1","t":1,"e":1,"v":{},"a":{}}"#.to_string();
+        db.insert_events(std::slice::from_ref(&event)).unwrap();
+        db.insert_events(&[event.clone(), event]).unwrap();
+
+        assert_eq!(db.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_insert_events_skips_uploaded_keys() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        db.mark_uploaded(&["key-1".to_string()], 1_700_000_000)
+            .unwrap();
+        db.insert_events(&[
+            r#"{"i":"key-1","t":1,"e":1,"v":{},"a":{}}"#.to_string(),
+            r#"{"i":"key-2","t":2,"e":1,"v":{},"a":{}}"#.to_string(),
+        ])
+        .unwrap();
+
+        let remaining = db.get_batch(10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(remaining[0].event_json.contains("key-2"));
+    }
+
+    #[test]
+    fn test_uploaded_keys_and_retention() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        db.mark_uploaded(&["old".to_string()], 1_000).unwrap();
+        let keys = vec!["old".to_string(), "new".to_string()];
+        assert_eq!(
+            db.uploaded_keys(&keys).unwrap(),
+            HashSet::from(["old".to_string()])
+        );
+
+        // Marking far in the future prunes keys outside the retention window.
+        db.mark_uploaded(
+            &["new".to_string()],
+            1_000 + UPLOADED_KEY_RETENTION_SECS + 1,
+        )
+        .unwrap();
+        assert_eq!(
+            db.uploaded_keys(&keys).unwrap(),
+            HashSet::from(["new".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_api_circuit_state_round_trip() {
+        let (mut db, _temp_dir) = create_test_db();
+        assert_eq!(
+            db.api_circuit_state("usegitai.com").unwrap(),
+            CircuitState::default()
+        );
+
+        let state = CircuitState {
+            consecutive_failures: 5,
+            open_until_ts: 1_700_000_300,
+        };
+        db.set_api_circuit_state("usegitai.com", &state).unwrap();
+        assert_eq!(db.api_circuit_state("usegitai.com").unwrap(), state);
+        assert_eq!(
+            db.api_circuit_state("other.example").unwrap(),
+            CircuitState::default()
+        );
+    }
+
+    #[test]
+    fn test_pending_envelopes_back_off_until_dropped() {
+        let (mut db, _temp_dir) = create_test_db();
+        let now = 1_700_000_000;
+
+        db.queue_envelope("sentry-oss", r#"{"message":"boom"}"#, "timed out", now)
+            .unwrap();
+        assert_eq!(db.pending_envelope_count().unwrap(), 1);
+
+        // Not due until the first backoff has passed
+        assert!(db.due_envelopes(now, 0, 10).unwrap().is_empty());
+        let due = db.due_envelopes(now + 5 * 60, 0, 10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].destination, "sentry-oss");
+        assert_eq!(due[0].payload_json, r#"{"message":"boom"}"#);
+        assert!(
+            db.due_envelopes(u64::MAX, due[0].id, 10)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Each failure pushes the next attempt further out
+        assert!(
+            db.record_envelope_failure(due[0].id, "timed out", now)
+                .unwrap()
+        );
+        assert!(db.due_envelopes(now + 5 * 60, 0, 10).unwrap().is_empty());
+        assert_eq!(db.due_envelopes(now + 10 * 60, 0, 10).unwrap().len(), 1);
+
+        // Retries continue well past the capped backoff, until the envelope ages out
+        for _ in 0..20 {
+            assert!(
+                db.record_envelope_failure(due[0].id, "timed out", now)
+                    .unwrap()
+            );
+        }
+        assert!(
+            db.record_envelope_failure(due[0].id, "timed out", now + MAX_ENVELOPE_AGE_SECS - 1)
+                .unwrap()
+        );
+        assert!(
+            !db.record_envelope_failure(due[0].id, "timed out", now + MAX_ENVELOPE_AGE_SECS)
+                .unwrap()
+        );
+        assert_eq!(db.pending_envelope_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_next_envelope_attempt_is_capped() {
+        assert_eq!(next_envelope_attempt(1, 0), 5 * 60);
+        assert_eq!(next_envelope_attempt(2, 0), 10 * 60);
+        assert_eq!(next_envelope_attempt(3, 0), 20 * 60);
+        assert_eq!(next_envelope_attempt(30, 0), 6 * 60 * 60);
+    }
+
+    #[test]
+    fn test_upgrade_backs_up_and_keeps_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test-metrics.db");
+
+        // A database left at schema version 3 by an older git-ai
+        let mut db = MetricsDatabase::open(&db_path).unwrap();
+        db.conn
+            .execute_batch(
+                "CREATE TABLE schema_metadata (key TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL);",
+            )
+            .unwrap();
+        for version in 0..3 {
+            db.apply_migration(version).unwrap();
+        }
+        db.insert_events(&[
+            r#"{"t":1234567890,"e":1,"v":{"0":"abc123"},"a":{"0":"1.0.0"}}"#.to_string(),
+        ])
+        .unwrap();
+
+        let upgrade = db.initialize_schema().unwrap();
+        assert_eq!(upgrade.from, 3);
+        assert_eq!(upgrade.to, SCHEMA_VERSION);
+        assert_eq!(db.count().unwrap(), 1);
+        assert_eq!(db.pending_envelope_count().unwrap(), 0);
+
+        let backup_path = upgrade.backup.expect("expected a backup");
+        assert_eq!(
+            backup_path,
+            PathBuf::from(format!("{}.v3.bak", db_path.display()))
+        );
+        let backup = Connection::open(&backup_path).unwrap();
+        let backup_version: String = backup
+            .query_row(
+                "SELECT value FROM schema_metadata WHERE key = 'version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(backup_version, "3");
+
+        // Already current: nothing to do, no new backup
+        let upgrade = db.initialize_schema().unwrap();
+        assert_eq!(upgrade.from, SCHEMA_VERSION);
+        assert!(upgrade.backup.is_none());
+    }
+
+    #[test]
+    fn test_new_database_is_not_backed_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = MetricsDatabase::open(&temp_dir.path().join("test-metrics.db")).unwrap();
+
+        let upgrade = db.initialize_schema().unwrap();
+        assert_eq!(upgrade.from, 0);
+        assert!(upgrade.backup.is_none());
+    }
+
+    #[test]
+    fn test_prune_older_than_and_vacuum() {
+        let (mut db, _temp_dir) = create_test_db();
+
+        db.insert_events(&[
+            r#"{"t":1000,"e":1,"v":{"0":"old"},"a":{"0":"1.0.0"}}"#.to_string(),
+            r#"{"t":5000,"e":1,"v":{"0":"new"},"a":{"0":"1.0.0"}}"#.to_string(),
+        ])
+        .unwrap();
+        db.mark_uploaded(&["old-key".to_string()], 1000).unwrap();
+        db.mark_uploaded(&["new-key".to_string()], 5000).unwrap();
+        db.queue_envelope("posthog", "{}", "offline", 1000).unwrap();
+        db.should_emit_agent_usage("prompt-1", 1000, 0).unwrap();
+
+        let pruned = db.prune_older_than(2000).unwrap();
+        assert_eq!(
+            pruned,
+            PrunedRows {
+                metrics: 1,
+                uploaded_keys: 1,
+                envelopes: 1,
+                throttle: 1,
+            }
+        );
+        assert_eq!(db.count().unwrap(), 1);
+        assert_eq!(
+            db.uploaded_keys(&["old-key".to_string(), "new-key".to_string()])
+                .unwrap(),
+            HashSet::from(["new-key".to_string()])
+        );
+
+        db.vacuum().unwrap();
+        assert_eq!(db.count().unwrap(), 1);
+    }
 }
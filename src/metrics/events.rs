@@ -27,6 +27,8 @@ pub mod committed_pos {
     pub const FIRST_CHECKPOINT_TS: usize = 10; // u64 (null if no checkpoints)
     pub const COMMIT_SUBJECT: usize = 11; // String
     pub const COMMIT_BODY: usize = 12; // String (null if empty)
+    pub const AI_ASSISTED_ADDITIONS: usize = 13; // u32 - committed lines from inline completions
+    pub const AI_GENERATED_ADDITIONS: usize = 14; // u32 - committed lines from agents
 }
 
 /// Values for Event ID 1: committed
@@ -53,6 +55,8 @@ pub mod committed_pos {
 /// | 10 | first_checkpoint_ts | u64 |
 /// | 11 | commit_subject | String |
 /// | 12 | commit_body | String |
+/// | 13 | ai_assisted_additions | u32 |
+/// | 14 | ai_generated_additions | u32 |
 #[derive(Debug, Clone, Default)]
 pub struct CommittedValues {
     // Scalar fields
@@ -73,6 +77,8 @@ pub struct CommittedValues {
     pub first_checkpoint_ts: PosField<u64>,
     pub commit_subject: PosField<String>,
     pub commit_body: PosField<String>,
+    pub ai_assisted_additions: PosField<u32>,
+    pub ai_generated_additions: PosField<u32>,
 }
 
 impl CommittedValues {
@@ -225,6 +231,28 @@ impl CommittedValues {
         self.commit_body = Some(None);
         self
     }
+
+    pub fn ai_assisted_additions(mut self, value: u32) -> Self {
+        self.ai_assisted_additions = Some(Some(value));
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn ai_assisted_additions_null(mut self) -> Self {
+        self.ai_assisted_additions = Some(None);
+        self
+    }
+
+    pub fn ai_generated_additions(mut self, value: u32) -> Self {
+        self.ai_generated_additions = Some(Some(value));
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn ai_generated_additions_null(mut self) -> Self {
+        self.ai_generated_additions = Some(None);
+        self
+    }
 }
 
 impl PosEncoded for CommittedValues {
@@ -301,6 +329,16 @@ impl PosEncoded for CommittedValues {
             committed_pos::COMMIT_BODY,
             string_to_json(&self.commit_body),
         );
+        sparse_set(
+            &mut map,
+            committed_pos::AI_ASSISTED_ADDITIONS,
+            u32_to_json(&self.ai_assisted_additions),
+        );
+        sparse_set(
+            &mut map,
+            committed_pos::AI_GENERATED_ADDITIONS,
+            u32_to_json(&self.ai_generated_additions),
+        );
 
         map
     }
@@ -325,6 +363,8 @@ impl PosEncoded for CommittedValues {
             first_checkpoint_ts: sparse_get_u64(arr, committed_pos::FIRST_CHECKPOINT_TS),
             commit_subject: sparse_get_string(arr, committed_pos::COMMIT_SUBJECT),
             commit_body: sparse_get_string(arr, committed_pos::COMMIT_BODY),
+            ai_assisted_additions: sparse_get_u32(arr, committed_pos::AI_ASSISTED_ADDITIONS),
+            ai_generated_additions: sparse_get_u32(arr, committed_pos::AI_GENERATED_ADDITIONS),
         }
     }
 }
@@ -488,6 +528,7 @@ pub mod checkpoint_pos {
     pub const LINES_DELETED: usize = 4; // u32 - for this file
     pub const LINES_ADDED_SLOC: usize = 5; // u32 - for this file
     pub const LINES_DELETED_SLOC: usize = 6; // u32 - for this file
+    pub const CLASSIFICATION: usize = 7; // String ("assisted", "generated"; null for human)
 }
 
 /// Values for Event ID 4: checkpoint
@@ -505,6 +546,7 @@ pub mod checkpoint_pos {
 /// | 4 | lines_deleted | u32 |
 /// | 5 | lines_added_sloc | u32 |
 /// | 6 | lines_deleted_sloc | u32 |
+/// | 7 | classification | String |
 #[derive(Debug, Clone, Default)]
 pub struct CheckpointValues {
     pub checkpoint_ts: PosField<u64>,
@@ -514,6 +556,7 @@ pub struct CheckpointValues {
     pub lines_deleted: PosField<u32>,
     pub lines_added_sloc: PosField<u32>,
     pub lines_deleted_sloc: PosField<u32>,
+    pub classification: PosField<String>,
 }
 
 impl CheckpointValues {
@@ -597,6 +640,16 @@ impl CheckpointValues {
         self.lines_deleted_sloc = Some(None);
         self
     }
+
+    pub fn classification(mut self, value: impl Into<String>) -> Self {
+        self.classification = Some(Some(value.into()));
+        self
+    }
+
+    pub fn classification_null(mut self) -> Self {
+        self.classification = Some(None);
+        self
+    }
 }
 
 impl PosEncoded for CheckpointValues {
@@ -634,6 +687,11 @@ impl PosEncoded for CheckpointValues {
             checkpoint_pos::LINES_DELETED_SLOC,
             u32_to_json(&self.lines_deleted_sloc),
         );
+        sparse_set(
+            &mut map,
+            checkpoint_pos::CLASSIFICATION,
+            string_to_json(&self.classification),
+        );
 
         map
     }
@@ -647,6 +705,7 @@ impl PosEncoded for CheckpointValues {
             lines_deleted: sparse_get_u32(arr, checkpoint_pos::LINES_DELETED),
             lines_added_sloc: sparse_get_u32(arr, checkpoint_pos::LINES_ADDED_SLOC),
             lines_deleted_sloc: sparse_get_u32(arr, checkpoint_pos::LINES_DELETED_SLOC),
+            classification: sparse_get_string(arr, checkpoint_pos::CLASSIFICATION),
         }
     }
 }
@@ -665,6 +724,113 @@ impl EventValues for CheckpointValues {
     }
 }
 
+/// Value positions for "policy_override" event.
+/// One event per failing policy rule bypassed with an override code.
+pub mod policy_override_pos {
+    pub const RULE: usize = 0; // String - name of the overridden rule
+    pub const OVERRIDE_CODE: usize = 1; // String - code from GIT_AI_POLICY_OVERRIDE
+    pub const STAGE: usize = 2; // String - "pre-commit" or "pre-push"
+    pub const MESSAGE: usize = 3; // Option<String> - why the rule failed
+}
+
+/// Values for Event ID 5: policy_override
+///
+/// Recorded when a commit goes ahead despite a failing policy rule because
+/// its override code was supplied.
+///
+/// **Fields:**
+/// | Position | Name | Type |
+/// |----------|------|------|
+/// | 0 | rule | String |
+/// | 1 | override_code | String |
+/// | 2 | stage | String |
+/// | 3 | message | `Option<String>` |
+#[derive(Debug, Clone, Default)]
+pub struct PolicyOverrideValues {
+    pub rule: PosField<String>,
+    pub override_code: PosField<String>,
+    pub stage: PosField<String>,
+    pub message: PosField<String>,
+}
+
+impl PolicyOverrideValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(mut self, value: String) -> Self {
+        self.rule = Some(Some(value));
+        self
+    }
+
+    pub fn override_code(mut self, value: String) -> Self {
+        self.override_code = Some(Some(value));
+        self
+    }
+
+    pub fn stage(mut self, value: String) -> Self {
+        self.stage = Some(Some(value));
+        self
+    }
+
+    pub fn message(mut self, value: String) -> Self {
+        self.message = Some(Some(value));
+        self
+    }
+}
+
+impl PosEncoded for PolicyOverrideValues {
+    fn to_sparse(&self) -> SparseArray {
+        let mut map = SparseArray::new();
+
+        sparse_set(
+            &mut map,
+            policy_override_pos::RULE,
+            string_to_json(&self.rule),
+        );
+        sparse_set(
+            &mut map,
+            policy_override_pos::OVERRIDE_CODE,
+            string_to_json(&self.override_code),
+        );
+        sparse_set(
+            &mut map,
+            policy_override_pos::STAGE,
+            string_to_json(&self.stage),
+        );
+        sparse_set(
+            &mut map,
+            policy_override_pos::MESSAGE,
+            string_to_json(&self.message),
+        );
+
+        map
+    }
+
+    fn from_sparse(arr: &SparseArray) -> Self {
+        Self {
+            rule: sparse_get_string(arr, policy_override_pos::RULE),
+            override_code: sparse_get_string(arr, policy_override_pos::OVERRIDE_CODE),
+            stage: sparse_get_string(arr, policy_override_pos::STAGE),
+            message: sparse_get_string(arr, policy_override_pos::MESSAGE),
+        }
+    }
+}
+
+impl EventValues for PolicyOverrideValues {
+    fn event_id() -> MetricEventId {
+        MetricEventId::PolicyOverride
+    }
+
+    fn to_sparse(&self) -> SparseArray {
+        PosEncoded::to_sparse(self)
+    }
+
+    fn from_sparse(arr: &SparseArray) -> Self {
+        PosEncoded::from_sparse(arr)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -909,6 +1075,30 @@ mod tests {
         assert_eq!(InstallHooksValues::event_id() as u16, 3);
     }
 
+    #[test]
+    fn test_policy_override_values_roundtrip() {
+        let values = PolicyOverrideValues::new()
+            .rule("crypto-ai-limit".to_string())
+            .override_code("SEC-42".to_string())
+            .stage("pre-commit".to_string())
+            .message("90% of 10 added lines are AI-authored".to_string());
+
+        let sparse = PosEncoded::to_sparse(&values);
+        assert_eq!(
+            sparse.get("0"),
+            Some(&Value::String("crypto-ai-limit".to_string()))
+        );
+
+        let restored = <PolicyOverrideValues as PosEncoded>::from_sparse(&sparse);
+        assert_eq!(restored.override_code, Some(Some("SEC-42".to_string())));
+        assert_eq!(restored.stage, Some(Some("pre-commit".to_string())));
+        assert_eq!(
+            restored.message,
+            Some(Some("90% of 10 added lines are AI-authored".to_string()))
+        );
+        assert_eq!(PolicyOverrideValues::event_id() as u16, 5);
+    }
+
     #[test]
     fn test_checkpoint_values_builder() {
         let values = CheckpointValues::new()
@@ -990,6 +1180,28 @@ mod tests {
         assert_eq!(values.lines_deleted_sloc, Some(Some(12)));
     }
 
+    #[test]
+    fn test_classification_fields_roundtrip() {
+        use super::PosEncoded;
+
+        let values = CheckpointValues::new().classification("assisted");
+        let sparse = PosEncoded::to_sparse(&values);
+        assert_eq!(
+            sparse.get("7"),
+            Some(&Value::String("assisted".to_string()))
+        );
+
+        let committed = CommittedValues::new()
+            .ai_assisted_additions(4)
+            .ai_generated_additions(9);
+        let sparse = PosEncoded::to_sparse(&committed);
+        assert_eq!(sparse.get("13"), Some(&Value::Number(4.into())));
+        assert_eq!(sparse.get("14"), Some(&Value::Number(9.into())));
+        let restored = <CommittedValues as PosEncoded>::from_sparse(&sparse);
+        assert_eq!(restored.ai_assisted_additions, Some(Some(4)));
+        assert_eq!(restored.ai_generated_additions, Some(Some(9)));
+    }
+
     #[test]
     fn test_checkpoint_event_id() {
         assert_eq!(CheckpointValues::event_id(), MetricEventId::Checkpoint);
@@ -13,7 +13,9 @@ pub mod types;
 
 // Re-export all public types for external crates
 pub use attrs::EventAttributes;
-pub use events::{AgentUsageValues, CheckpointValues, CommittedValues, InstallHooksValues};
+pub use events::{
+    AgentUsageValues, CheckpointValues, CommittedValues, InstallHooksValues, PolicyOverrideValues,
+};
 pub use pos_encoded::PosEncoded;
 pub use types::{EventValues, METRICS_API_VERSION, MetricEvent, MetricsBatch};
 
@@ -3,7 +3,9 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Current API version for metrics wire format.
 pub const METRICS_API_VERSION: u8 = 1;
@@ -20,6 +22,7 @@ pub enum MetricEventId {
     AgentUsage = 2,
     InstallHooks = 3,
     Checkpoint = 4,
+    PolicyOverride = 5,
 }
 
 /// Trait for event-specific values.
@@ -30,12 +33,30 @@ pub trait EventValues: Sized {
     fn from_sparse(arr: &SparseArray) -> Self;
 }
 
+static EVENT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Next value of the per-process logical event sequence.
+fn next_event_seq() -> u64 {
+    EVENT_SEQ.fetch_add(1, Ordering::Relaxed) + 1
+}
+
 /// Generic wrapper for any metric event.
-/// JSON keys: t=timestamp, e=event_id, v=values, a=attrs
+/// JSON keys: i=idempotency key, t=timestamp, s=sequence, e=event_id, v=values, a=attrs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricEvent {
+    /// UUID assigned at creation. Stays with the event through the
+    /// log file -> SQLite -> upload handoff so retries can be deduplicated.
+    /// Absent on events written by older versions.
+    #[serde(rename = "i", default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Wall-clock seconds since epoch. Subject to clock skew.
     #[serde(rename = "t")]
     pub timestamp: u32,
+    /// Logical sequence within the emitting process, restarting with each run.
+    /// Only a tie-break for events one process recorded in the same second or
+    /// across a clock jump; it doesn't order events from different processes.
+    #[serde(rename = "s", default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
     #[serde(rename = "e")]
     pub event_id: u16,
     #[serde(rename = "v")]
@@ -48,10 +69,12 @@ impl MetricEvent {
     /// Create a new metric event with current timestamp.
     pub fn new<V: EventValues>(values: &V, attrs: SparseArray) -> Self {
         Self {
+            idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs() as u32,
+            seq: Some(next_event_seq()),
             event_id: V::event_id() as u16,
             values: values.to_sparse(),
             attrs,
@@ -62,12 +85,35 @@ impl MetricEvent {
     #[allow(dead_code)]
     pub fn with_timestamp<V: EventValues>(timestamp: u32, values: &V, attrs: SparseArray) -> Self {
         Self {
+            idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
             timestamp,
+            seq: Some(next_event_seq()),
             event_id: V::event_id() as u16,
             values: values.to_sparse(),
             attrs,
         }
     }
+
+    /// Key used to deduplicate this event across retries and handoffs.
+    ///
+    /// Returns the idempotency key when present. Legacy events without one
+    /// fall back to a SHA256 of their canonicalized JSON, which is stable
+    /// across processes because the canonical form sorts object keys.
+    pub fn dedup_key(&self) -> String {
+        if let Some(key) = &self.idempotency_key
+            && !key.is_empty()
+        {
+            return key.clone();
+        }
+
+        let canonical = serde_json::to_value(self)
+            .ok()
+            .and_then(|value| serde_json_canonicalizer::to_string(&value).ok())
+            .unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(canonical.as_bytes());
+        format!("legacy-{:x}", hasher.finalize())
+    }
 }
 
 /// Metrics batch for wire format.
@@ -85,6 +131,21 @@ impl MetricsBatch {
             events,
         }
     }
+
+    /// Idempotency key for the whole batch, derived from the event keys.
+    ///
+    /// Retrying the same set of events yields the same key regardless of order,
+    /// so the server can recognize a replayed upload.
+    pub fn idempotency_key(&self) -> String {
+        let mut keys: Vec<String> = self.events.iter().map(|e| e.dedup_key()).collect();
+        keys.sort();
+        let mut hasher = Sha256::new();
+        for key in &keys {
+            hasher.update(key.as_bytes());
+            hasher.update(b"\n");
+        }
+        format!("{:x}", hasher.finalize())
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +169,8 @@ mod tests {
         attrs.insert("0".to_string(), Value::String("version".to_string()));
 
         let event = MetricEvent {
+            idempotency_key: None,
+            seq: None,
             timestamp: 1704067200,
             event_id: MetricEventId::Committed as u16,
             values,
@@ -159,6 +222,7 @@ mod tests {
         assert_eq!(MetricEventId::AgentUsage as u16, 2);
         assert_eq!(MetricEventId::InstallHooks as u16, 3);
         assert_eq!(MetricEventId::Checkpoint as u16, 4);
+        assert_eq!(MetricEventId::PolicyOverride as u16, 5);
     }
 
     #[test]
@@ -180,6 +244,8 @@ mod tests {
         attrs.insert("0".to_string(), Value::String("2.0.0".to_string()));
 
         let event1 = MetricEvent {
+            idempotency_key: None,
+            seq: None,
             timestamp: 1704067200,
             event_id: 1,
             values: values.clone(),
@@ -187,6 +253,8 @@ mod tests {
         };
 
         let event2 = MetricEvent {
+            idempotency_key: None,
+            seq: None,
             timestamp: 1704067300,
             event_id: 2,
             values,
@@ -268,4 +336,75 @@ mod tests {
         let id2 = id1.clone();
         assert_eq!(id1, id2);
     }
+
+    #[test]
+    fn test_metric_event_new_assigns_unique_idempotency_key() {
+        use crate::metrics::events::AgentUsageValues;
+
+        let values = AgentUsageValues::new();
+        let a = MetricEvent::new(&values, SparseArray::new());
+        let b = MetricEvent::new(&values, SparseArray::new());
+
+        assert!(a.idempotency_key.is_some());
+        assert_ne!(a.idempotency_key, b.idempotency_key);
+        assert_eq!(a.dedup_key(), a.idempotency_key.clone().unwrap());
+    }
+
+    #[test]
+    fn test_idempotency_key_survives_round_trip() {
+        use crate::metrics::events::AgentUsageValues;
+
+        let event = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"i\":"));
+
+        let parsed: MetricEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.idempotency_key, event.idempotency_key);
+    }
+
+    #[test]
+    fn test_metric_event_seq_increases_within_process() {
+        use crate::metrics::events::AgentUsageValues;
+
+        let first =
+            MetricEvent::with_timestamp(1700000000, &AgentUsageValues::new(), SparseArray::new());
+        // A clock that went backwards must not reorder the logical sequence
+        let second =
+            MetricEvent::with_timestamp(1600000000, &AgentUsageValues::new(), SparseArray::new());
+        assert!(second.seq.unwrap() > first.seq.unwrap());
+
+        let parsed: MetricEvent =
+            serde_json::from_str(&serde_json::to_string(&second).unwrap()).unwrap();
+        assert_eq!(parsed.seq, second.seq);
+    }
+
+    #[test]
+    fn test_legacy_event_dedup_key_is_stable() {
+        let json = r#"{"t":1704067200,"e":1,"v":{"0":"a","1":2,"2":null},"a":{"0":"1.0.0"}}"#;
+        let first: MetricEvent = serde_json::from_str(json).unwrap();
+        let second: MetricEvent = serde_json::from_str(json).unwrap();
+
+        assert!(first.idempotency_key.is_none());
+        assert!(first.dedup_key().starts_with("legacy-"));
+        assert_eq!(first.dedup_key(), second.dedup_key());
+
+        let other: MetricEvent =
+            serde_json::from_str(r#"{"t":1704067201,"e":1,"v":{},"a":{}}"#).unwrap();
+        assert_ne!(first.dedup_key(), other.dedup_key());
+    }
+
+    #[test]
+    fn test_batch_idempotency_key_is_order_independent() {
+        use crate::metrics::events::AgentUsageValues;
+
+        let a = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+        let b = MetricEvent::new(&AgentUsageValues::new(), SparseArray::new());
+
+        let forward = MetricsBatch::new(vec![a.clone(), b.clone()]);
+        let reverse = MetricsBatch::new(vec![b.clone(), a.clone()]);
+        let single = MetricsBatch::new(vec![a]);
+
+        assert_eq!(forward.idempotency_key(), reverse.idempotency_key());
+        assert_ne!(forward.idempotency_key(), single.idempotency_key());
+    }
 }
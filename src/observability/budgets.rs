@@ -0,0 +1,156 @@
+//! Per-operation latency budgets (`performance_budgets` config).
+//!
+//! Hot paths record how long their expensive phases took with [`record_phase`].
+//! When an operation finishes, [`check_budget`] compares its duration against the
+//! configured budget; if it ran over, a `performance_budget_exceeded` envelope is
+//! emitted with the phase breakdown and a one-line warning is printed, so slow
+//! repos can be diagnosed without turning on debug logging.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::config::Config;
+use crate::observability::log_performance;
+use crate::observability::profile;
+
+/// Time spent per phase since the last [`check_budget`]. Phases that run on
+/// several threads (e.g. per-file blame) add up, so they can exceed wall time.
+static PHASES: Mutex<BTreeMap<&'static str, Duration>> = Mutex::new(BTreeMap::new());
+
+/// Add `duration` to the running total for `phase`
+pub fn record_phase(phase: &'static str, duration: Duration) {
+    if let Ok(mut phases) = PHASES.lock() {
+        *phases.entry(phase).or_default() += duration;
+    }
+}
+
+/// Run `f`, recording its duration under `phase` (and as a profile scope when
+/// `GIT_AI_PROFILE` is on)
+pub fn time_phase<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    let _scope = profile::scope(phase);
+    let start = Instant::now();
+    let result = f();
+    record_phase(phase, start.elapsed());
+    result
+}
+
+fn take_phases() -> BTreeMap<&'static str, Duration> {
+    PHASES
+        .lock()
+        .map(|mut phases| std::mem::take(&mut *phases))
+        .unwrap_or_default()
+}
+
+/// Compare `duration` against the budget configured for `operation` (e.g.
+/// `checkpoint`, `post_commit`). Always clears the recorded phases, so the next
+/// operation starts a fresh breakdown. Returns true if the budget was exceeded.
+pub fn check_budget(operation: &str, duration: Duration) -> bool {
+    let phases = take_phases();
+    let Some(budget) = Config::get().performance_budget(operation) else {
+        return false;
+    };
+    if duration <= budget {
+        return false;
+    }
+
+    log_performance(
+        "performance_budget_exceeded",
+        duration,
+        Some(budget_exceeded_context(
+            operation, duration, budget, &phases,
+        )),
+        Some(HashMap::from([(
+            "operation".to_string(),
+            operation.to_string(),
+        )])),
+    );
+    eprintln!(
+        "{}",
+        budget_exceeded_message(operation, duration, budget, &phases)
+    );
+    true
+}
+
+/// Budget name for a wrapper hook stage, e.g. `post_commit` or `pre_cherry_pick`
+pub fn hook_operation(stage: &str, command: &str) -> String {
+    format!("{}_{}", stage, command.replace('-', "_"))
+}
+
+fn budget_exceeded_context(
+    operation: &str,
+    duration: Duration,
+    budget: Duration,
+    phases: &BTreeMap<&'static str, Duration>,
+) -> serde_json::Value {
+    let phases_ms: BTreeMap<&str, u128> = phases
+        .iter()
+        .map(|(phase, duration)| (*phase, duration.as_millis()))
+        .collect();
+    json!({
+        "operation": operation,
+        "duration_ms": duration.as_millis(),
+        "budget_ms": budget.as_millis(),
+        "phases_ms": phases_ms,
+    })
+}
+
+fn budget_exceeded_message(
+    operation: &str,
+    duration: Duration,
+    budget: Duration,
+    phases: &BTreeMap<&'static str, Duration>,
+) -> String {
+    let mut message = format!(
+        "[git-ai] {} took {}ms (budget {}ms)",
+        operation,
+        duration.as_millis(),
+        budget.as_millis()
+    );
+    if !phases.is_empty() {
+        let breakdown: Vec<String> = phases
+            .iter()
+            .map(|(phase, duration)| format!("{} {}ms", phase, duration.as_millis()))
+            .collect();
+        message.push_str(&format!(": {}", breakdown.join(", ")));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_exceeded_breakdown() {
+        let phases = BTreeMap::from([
+            ("status_scan", Duration::from_millis(120)),
+            ("blame", Duration::from_millis(300)),
+        ]);
+        let duration = Duration::from_millis(450);
+        let budget = Duration::from_millis(150);
+
+        let context = budget_exceeded_context("checkpoint", duration, budget, &phases);
+        assert_eq!(context["budget_ms"], 150);
+        assert_eq!(context["duration_ms"], 450);
+        assert_eq!(context["phases_ms"]["blame"], 300);
+        assert_eq!(context["phases_ms"]["status_scan"], 120);
+
+        assert_eq!(
+            budget_exceeded_message("checkpoint", duration, budget, &phases),
+            "[git-ai] checkpoint took 450ms (budget 150ms): blame 300ms, status_scan 120ms"
+        );
+        assert_eq!(
+            budget_exceeded_message("checkpoint", duration, budget, &BTreeMap::new()),
+            "[git-ai] checkpoint took 450ms (budget 150ms)"
+        );
+    }
+
+    #[test]
+    fn test_hook_operation() {
+        assert_eq!(hook_operation("post", "commit"), "post_commit");
+        assert_eq!(hook_operation("pre", "cherry-pick"), "pre_cherry_pick");
+    }
+}
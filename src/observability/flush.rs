@@ -1,8 +1,11 @@
+use crate::api::rate_limit::BACKGROUND_MAX_RETRIES;
+use crate::api::sink::dual_write_metrics;
 use crate::api::{ApiClient, ApiContext, upload_metrics_with_retry};
 use crate::config::{Config, get_or_create_distinct_id};
 use crate::git::find_repository_in_path;
 use crate::metrics::db::MetricsDatabase;
 use crate::metrics::{MetricEvent, MetricsBatch};
+use crate::output::{self, Status};
 use futures::stream::{self, StreamExt};
 use serde_json::{Value, json};
 use std::collections::BTreeMap;
@@ -33,49 +36,12 @@ pub fn handle_flush_logs(args: &[String]) {
     // In dev builds without --force, we only send metrics envelopes (skip error/performance/message)
     let skip_non_metrics = cfg!(debug_assertions) && !force;
 
-    let config = Config::get();
-
-    // Check for Enterprise DSN: config takes precedence over env var, which takes precedence over build-time value
-    let enterprise_dsn = config
-        .telemetry_enterprise_dsn()
-        .map(|s| s.to_string())
-        .or_else(|| {
-            std::env::var("SENTRY_ENTERPRISE")
-                .ok()
-                .or_else(|| option_env!("SENTRY_ENTERPRISE").map(|s| s.to_string()))
-                .filter(|s| !s.is_empty())
-        });
-
-    // Check for PostHog configuration: runtime env var takes precedence over build-time value
-    let posthog_api_key = std::env::var("POSTHOG_API_KEY")
-        .ok()
-        .or_else(|| option_env!("POSTHOG_API_KEY").map(|s| s.to_string()))
-        .filter(|s| !s.is_empty());
-
-    let posthog_host = std::env::var("POSTHOG_HOST")
-        .ok()
-        .or_else(|| option_env!("POSTHOG_HOST").map(|s| s.to_string()))
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "https://us.i.posthog.com".to_string());
-
     // Get the global logs directory
     let Some(logs_dir) = get_logs_directory() else {
         // No logs directory - nothing to do, exit successfully
         std::process::exit(0);
     };
 
-    // Check for OSS DSN: runtime env var takes precedence over build-time value
-    // Can be explicitly disabled with empty string
-    // Skip OSS DSN if OSS telemetry is disabled in config
-    let oss_dsn = if config.is_telemetry_oss_disabled() {
-        None
-    } else {
-        std::env::var("SENTRY_OSS")
-            .ok()
-            .or_else(|| option_env!("SENTRY_OSS").map(|s| s.to_string()))
-            .filter(|s| !s.is_empty())
-    };
-
     // Initialize metrics uploader (metrics can always be stored in local DB even if upload isn't possible)
     let metrics_uploader = MetricsUploader::new();
 
@@ -118,22 +84,11 @@ pub fn handle_flush_logs(args: &[String]) {
     // Get or create distinct_id from ~/.git-ai/internal/distinct_id
     let distinct_id = get_or_create_distinct_id();
 
-    // Initialize Sentry clients
-    let (oss_client, enterprise_client) = initialize_sentry_clients(oss_dsn, enterprise_dsn);
-
-    // Initialize PostHog client
-    let posthog_client = if config.is_telemetry_oss_disabled() {
-        None
-    } else {
-        posthog_api_key
-            .as_ref()
-            .map(|api_key| PostHogClient::new(api_key.clone(), posthog_host.clone()))
-    };
+    let clients = Arc::new(TelemetryClients::from_config(Config::get()));
 
     // Check if telemetry clients are present (needed for cleanup logic later)
     // Note: metrics are always processed (uploaded to API or stored in SQLite)
-    let has_telemetry_clients =
-        oss_client.is_some() || enterprise_client.is_some() || posthog_client.is_some();
+    let has_telemetry_clients = !clients.is_empty();
 
     eprintln!(
         "Processing {} log files (max 10 concurrent)...",
@@ -156,7 +111,8 @@ pub fn handle_flush_logs(args: &[String]) {
             match collect_metrics_from_file(log_file) {
                 Ok((metrics_envelopes, metrics_events)) if !metrics_events.is_empty() => {
                     eprintln!(
-                        "  ✓ {} - collected {} metrics event(s) from {} envelope(s)",
+                        "  {} {} - collected {} metrics event(s) from {} envelope(s)",
+                        output::mode().status_symbol(Status::Success),
                         file_name,
                         metrics_events.len(),
                         metrics_envelopes
@@ -165,10 +121,19 @@ pub fn handle_flush_logs(args: &[String]) {
                     all_metrics.extend(metrics_events);
                 }
                 Ok(_) => {
-                    eprintln!("  ○ {} - no metrics to send", file_name);
+                    eprintln!(
+                        "  {} {} - no metrics to send",
+                        output::mode().status_symbol(Status::Skipped),
+                        file_name
+                    );
                 }
                 Err(e) => {
-                    eprintln!("  ✗ {} - error: {}", file_name, e);
+                    eprintln!(
+                        "  {} {} - error: {}",
+                        output::mode().status_symbol(Status::Error),
+                        file_name,
+                        e
+                    );
                 }
             }
         }
@@ -199,18 +164,13 @@ pub fn handle_flush_logs(args: &[String]) {
 
     // Process log files in parallel (max 10 at a time)
     let results = smol::block_on(async {
-        let oss_client = Arc::new(oss_client);
-        let enterprise_client = Arc::new(enterprise_client);
-        let posthog_client = Arc::new(posthog_client);
         let metrics_uploader = Arc::new(metrics_uploader);
         let remotes_info = Arc::new(remotes_info);
         let distinct_id = Arc::new(distinct_id);
 
         stream::iter(log_files)
             .map(|log_file| {
-                let oss_client = Arc::clone(&oss_client);
-                let enterprise_client = Arc::clone(&enterprise_client);
-                let posthog_client = Arc::clone(&posthog_client);
+                let clients = Arc::clone(&clients);
                 let metrics_uploader = Arc::clone(&metrics_uploader);
                 let remotes_info = Arc::clone(&remotes_info);
                 let distinct_id = Arc::clone(&distinct_id);
@@ -223,24 +183,46 @@ pub fn handle_flush_logs(args: &[String]) {
 
                     match process_log_file(
                         &log_file,
-                        &oss_client,
-                        &enterprise_client,
-                        &posthog_client,
+                        &clients,
                         &metrics_uploader,
                         &remotes_info,
                         &distinct_id,
                         skip_non_metrics,
                     ) {
-                        Ok(count) if count > 0 => {
-                            eprintln!("  ✓ {} - sent {} events", file_name, count);
-                            Some((log_file, count))
+                        Ok((count, queued)) if count + queued > 0 => {
+                            if queued > 0 {
+                                eprintln!(
+                                    "  {} {} - sent {} events, queued {} for retry",
+                                    output::mode().status_symbol(Status::Success),
+                                    file_name,
+                                    count,
+                                    queued
+                                );
+                            } else {
+                                eprintln!(
+                                    "  {} {} - sent {} events",
+                                    output::mode().status_symbol(Status::Success),
+                                    file_name,
+                                    count
+                                );
+                            }
+                            Some((log_file, count, queued))
                         }
                         Ok(_) => {
-                            eprintln!("  ○ {} - no events to send", file_name);
+                            eprintln!(
+                                "  {} {} - no events to send",
+                                output::mode().status_symbol(Status::Skipped),
+                                file_name
+                            );
                             None
                         }
                         Err(e) => {
-                            eprintln!("  ✗ {} - error: {}", file_name, e);
+                            eprintln!(
+                                "  {} {} - error: {}",
+                                output::mode().status_symbol(Status::Error),
+                                file_name,
+                                e
+                            );
                             None
                         }
                     }
@@ -253,10 +235,12 @@ pub fn handle_flush_logs(args: &[String]) {
 
     // Collect results
     let mut events_sent = 0;
+    let mut events_queued = 0;
     let mut files_to_delete = Vec::new();
 
-    for (log_file, count) in results.into_iter().flatten() {
+    for (log_file, count, queued) in results.into_iter().flatten() {
         events_sent += count;
+        events_queued += queued;
         files_to_delete.push(log_file);
     }
 
@@ -265,6 +249,18 @@ pub fn handle_flush_logs(args: &[String]) {
         events_sent,
         files_to_delete.len()
     );
+    if events_queued > 0 {
+        eprintln!(
+            "{} events could not be delivered and were queued for retry",
+            events_queued
+        );
+    }
+
+    // Resend anything from earlier runs whose backoff has elapsed
+    let retried = retry_pending_envelopes(&clients, unix_now());
+    if retried.attempted() > 0 {
+        eprintln!("Retried queued events: {}", retried);
+    }
 
     // Clean up old logs if no clients configured
     if !has_telemetry_clients {
@@ -272,7 +268,7 @@ pub fn handle_flush_logs(args: &[String]) {
         cleanup_old_logs(&logs_dir);
     }
 
-    if events_sent > 0 {
+    if !files_to_delete.is_empty() {
         eprintln!("Deleting {} processed log files", files_to_delete.len());
         for file_path in files_to_delete {
             let _ = fs::remove_file(&file_path);
@@ -439,7 +435,7 @@ struct MetricsUploader {
 
 impl MetricsUploader {
     fn new() -> Self {
-        let context = ApiContext::new(None);
+        let context = ApiContext::new(None).with_retries(BACKGROUND_MAX_RETRIES);
         let api_base_url = context.base_url.clone();
         let client = ApiClient::new(context);
 
@@ -454,29 +450,234 @@ impl MetricsUploader {
     }
 }
 
-fn initialize_sentry_clients(
-    oss_dsn: Option<String>,
-    enterprise_dsn: Option<String>,
-) -> (Option<SentryClient>, Option<SentryClient>) {
-    let oss_client = oss_dsn.and_then(|dsn| SentryClient::from_dsn(&dsn));
-    let enterprise_client = enterprise_dsn.and_then(|dsn| SentryClient::from_dsn(&dsn));
+/// Queue destinations for undeliverable events
+const DESTINATION_SENTRY_OSS: &str = "sentry-oss";
+const DESTINATION_SENTRY_ENTERPRISE: &str = "sentry-enterprise";
+const DESTINATION_POSTHOG: &str = "posthog";
 
-    (oss_client, enterprise_client)
+/// Max pending envelopes resent per database read
+const PENDING_BATCH_SIZE: usize = 100;
+
+/// The Sentry and PostHog endpoints error, performance and message events go to
+pub struct TelemetryClients {
+    oss: Option<SentryClient>,
+    enterprise: Option<SentryClient>,
+    posthog: Option<PostHogClient>,
 }
 
-#[allow(clippy::too_many_arguments)]
+impl TelemetryClients {
+    pub fn from_config(config: &Config) -> Self {
+        // Check for Enterprise DSN: config takes precedence over env var, which takes precedence over build-time value
+        let enterprise_dsn = config
+            .telemetry_enterprise_dsn()
+            .map(|s| s.to_string())
+            .or_else(|| {
+                std::env::var("SENTRY_ENTERPRISE")
+                    .ok()
+                    .or_else(|| option_env!("SENTRY_ENTERPRISE").map(|s| s.to_string()))
+                    .filter(|s| !s.is_empty())
+            });
+
+        // Check for OSS DSN: runtime env var takes precedence over build-time value
+        // Can be explicitly disabled with empty string
+        // Skip OSS DSN if OSS telemetry is disabled in config
+        let oss_dsn = if config.is_telemetry_oss_disabled() {
+            None
+        } else {
+            std::env::var("SENTRY_OSS")
+                .ok()
+                .or_else(|| option_env!("SENTRY_OSS").map(|s| s.to_string()))
+                .filter(|s| !s.is_empty())
+        };
+
+        // Check for PostHog configuration: runtime env var takes precedence over build-time value
+        let posthog_api_key = std::env::var("POSTHOG_API_KEY")
+            .ok()
+            .or_else(|| option_env!("POSTHOG_API_KEY").map(|s| s.to_string()))
+            .filter(|s| !s.is_empty());
+
+        let posthog_host = std::env::var("POSTHOG_HOST")
+            .ok()
+            .or_else(|| option_env!("POSTHOG_HOST").map(|s| s.to_string()))
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "https://us.i.posthog.com".to_string());
+
+        let posthog = if config.is_telemetry_oss_disabled() {
+            None
+        } else {
+            posthog_api_key.map(|api_key| PostHogClient::new(api_key, posthog_host))
+        };
+
+        TelemetryClients {
+            oss: oss_dsn.and_then(|dsn| SentryClient::from_dsn(&dsn)),
+            enterprise: enterprise_dsn.and_then(|dsn| SentryClient::from_dsn(&dsn)),
+            posthog,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.oss.is_none() && self.enterprise.is_none() && self.posthog.is_none()
+    }
+
+    /// Send a built event to a queue destination. `None` if that destination
+    /// isn't configured (any more).
+    fn deliver(&self, destination: &str, event: Value) -> Option<Result<(), String>> {
+        let result = match destination {
+            DESTINATION_SENTRY_OSS => self.oss.as_ref()?.send_event(event).map(|_| ()),
+            DESTINATION_SENTRY_ENTERPRISE => {
+                self.enterprise.as_ref()?.send_event(event).map(|_| ())
+            }
+            DESTINATION_POSTHOG => self.posthog.as_ref()?.send_event(event),
+            _ => return None,
+        };
+        Some(result.map_err(|e| e.to_string()))
+    }
+
+    /// The events an envelope turns into, keyed by destination
+    fn events_for(
+        &self,
+        envelope: &Value,
+        remotes_info: &[(String, String)],
+        distinct_id: &str,
+    ) -> Vec<(&'static str, Value)> {
+        let mut events = Vec::new();
+        if (self.oss.is_some() || self.enterprise.is_some())
+            && let Some(event) = sentry_event(envelope, remotes_info, distinct_id)
+        {
+            if self.oss.is_some() {
+                events.push((DESTINATION_SENTRY_OSS, event.clone()));
+            }
+            if self.enterprise.is_some() {
+                events.push((DESTINATION_SENTRY_ENTERPRISE, event));
+            }
+        }
+        if let Some(client) = &self.posthog
+            && let Some(event) = posthog_event(envelope, client, remotes_info, distinct_id)
+        {
+            events.push((DESTINATION_POSTHOG, event));
+        }
+        events
+    }
+}
+
+/// Outcome of resending queued envelopes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PendingRetrySummary {
+    pub sent: usize,
+    /// Failed again and rescheduled
+    pub failed: usize,
+    /// Gave up on: out of attempts, or the destination is no longer configured
+    pub dropped: usize,
+    /// Still queued afterwards, including envelopes that weren't due yet
+    pub remaining: usize,
+}
+
+impl PendingRetrySummary {
+    pub fn attempted(&self) -> usize {
+        self.sent + self.failed + self.dropped
+    }
+}
+
+impl std::fmt::Display for PendingRetrySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} sent, {} failed, {} dropped, {} still queued",
+            self.sent, self.failed, self.dropped, self.remaining
+        )
+    }
+}
+
+/// Resend queued envelopes that are due at or before `due_ts`. Failures are
+/// rescheduled with exponential backoff.
+pub fn retry_pending_envelopes(clients: &TelemetryClients, due_ts: u64) -> PendingRetrySummary {
+    let mut summary = PendingRetrySummary::default();
+    let Ok(db) = MetricsDatabase::global() else {
+        return summary;
+    };
+
+    // Page by ID so each envelope is attempted at most once per call, even when
+    // its rescheduled attempt is still before `due_ts`
+    let now = unix_now();
+    let mut after_id = 0;
+    loop {
+        let batch = match db.lock() {
+            Ok(db_lock) => db_lock
+                .due_envelopes(due_ts, after_id, PENDING_BATCH_SIZE)
+                .unwrap_or_default(),
+            Err(_) => break,
+        };
+        if batch.is_empty() {
+            break;
+        }
+
+        for pending in batch {
+            after_id = pending.id;
+            let result = serde_json::from_str::<Value>(&pending.payload_json)
+                .ok()
+                .and_then(|event| clients.deliver(&pending.destination, event));
+            let Ok(mut db_lock) = db.lock() else {
+                return summary;
+            };
+            match result {
+                Some(Ok(())) => {
+                    let _ = db_lock.delete_envelopes(&[pending.id]);
+                    summary.sent += 1;
+                }
+                Some(Err(e)) => match db_lock.record_envelope_failure(pending.id, &e, now) {
+                    Ok(true) => summary.failed += 1,
+                    Ok(false) => summary.dropped += 1,
+                    Err(_) => return summary,
+                },
+                None => {
+                    let _ = db_lock.delete_envelopes(&[pending.id]);
+                    summary.dropped += 1;
+                }
+            }
+        }
+    }
+
+    summary.remaining = db
+        .lock()
+        .ok()
+        .and_then(|db_lock| db_lock.pending_envelope_count().ok())
+        .unwrap_or(0);
+    summary
+}
+
+/// Persist an event that couldn't be delivered so a later flush (or
+/// `git-ai sync --retry-pending`) can resend it
+fn queue_failed_envelope(destination: &str, event: &Value, error: &str) -> bool {
+    let Ok(db) = MetricsDatabase::global() else {
+        return false;
+    };
+    let Ok(mut db_lock) = db.lock() else {
+        return false;
+    };
+    db_lock
+        .queue_envelope(destination, &event.to_string(), error, unix_now())
+        .is_ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Returns (envelopes sent, envelopes queued for retry)
 fn process_log_file(
     path: &PathBuf,
-    oss_client: &Option<SentryClient>,
-    enterprise_client: &Option<SentryClient>,
-    posthog_client: &Option<PostHogClient>,
+    clients: &TelemetryClients,
     metrics_uploader: &MetricsUploader,
     remotes_info: &[(String, String)],
     distinct_id: &str,
     skip_non_metrics: bool,
-) -> Result<usize, Box<dyn std::error::Error>> {
+) -> Result<(usize, usize), Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
     let mut count = 0;
+    let mut queued = 0;
 
     for line in content.lines() {
         if line.trim().is_empty() {
@@ -486,6 +687,7 @@ fn process_log_file(
         if let Ok(envelope) = serde_json::from_str::<Value>(line) {
             let event_type = envelope.get("type").and_then(|t| t.as_str());
             let mut sent = false;
+            let mut deferred = false;
 
             // Handle metrics envelopes specially - send to API (always, even in dev builds)
             if event_type == Some("metrics") {
@@ -494,37 +696,29 @@ fn process_log_file(
                 }
             } else if !skip_non_metrics {
                 // Only send error/performance/message envelopes if not in dev mode
-                // (or if --force was passed)
-
-                // Send to OSS if configured
-                if let Some(client) = oss_client
-                    && send_envelope_to_sentry(&envelope, client, remotes_info, distinct_id)
+                // (or if --force was passed). Deliveries that fail are queued
+                // per destination, so a retry never resends to one that succeeded.
+                for (destination, event) in clients.events_for(&envelope, remotes_info, distinct_id)
                 {
-                    sent = true;
-                }
-
-                // Send to Enterprise if configured
-                if let Some(client) = enterprise_client
-                    && send_envelope_to_sentry(&envelope, client, remotes_info, distinct_id)
-                {
-                    sent = true;
-                }
-
-                // Send to PostHog if configured
-                if let Some(client) = posthog_client
-                    && send_envelope_to_posthog(&envelope, client, remotes_info, distinct_id)
-                {
-                    sent = true;
+                    match clients.deliver(destination, event.clone()) {
+                        Some(Ok(())) => sent = true,
+                        Some(Err(e)) if queue_failed_envelope(destination, &event, &e) => {
+                            deferred = true
+                        }
+                        Some(Err(_)) | None => {}
+                    }
                 }
             }
 
             if sent {
                 count += 1;
+            } else if deferred {
+                queued += 1;
             }
         }
     }
 
-    Ok(count)
+    Ok((count, queued))
 }
 
 fn collect_metrics_from_file(
@@ -552,12 +746,12 @@ fn collect_metrics_from_file(
     Ok((metrics_envelopes, metrics_events))
 }
 
-fn send_envelope_to_sentry(
+/// Build the Sentry event for an error, performance or message envelope
+fn sentry_event(
     envelope: &Value,
-    client: &SentryClient,
     remotes_info: &[(String, String)],
     distinct_id: &str,
-) -> bool {
+) -> Option<Value> {
     let event_type = envelope.get("type").and_then(|t| t.as_str());
     let timestamp = envelope
         .get("timestamp")
@@ -663,24 +857,25 @@ fn send_envelope_to_sentry(
             })
         }
         _ => {
-            return false;
+            return None;
         }
     };
 
-    client.send_event(event).is_ok()
+    Some(event)
 }
 
-fn send_envelope_to_posthog(
+/// Build the PostHog event for a message envelope
+fn posthog_event(
     envelope: &Value,
     client: &PostHogClient,
     remotes_info: &[(String, String)],
     distinct_id: &str,
-) -> bool {
+) -> Option<Value> {
     let event_type = envelope.get("type").and_then(|t| t.as_str());
 
     // Only send log messages to PostHog, not errors or performance
     if event_type != Some("message") {
-        return false;
+        return None;
     }
 
     let timestamp = envelope.get("timestamp").and_then(|t| t.as_str());
@@ -727,7 +922,7 @@ fn send_envelope_to_posthog(
         event["timestamp"] = json!(ts);
     }
 
-    client.send_event(event).is_ok()
+    Some(event)
 }
 
 /// Sanitize git URLs by replacing passwords with asterisks
@@ -779,6 +974,9 @@ fn send_metrics_events(events: &[MetricEvent], uploader: &MetricsUploader) -> bo
     }
 
     let batch = MetricsBatch::new(events.to_vec());
+    // Copied when the events leave the log, whether they're uploaded now or
+    // stored for a later upload
+    dual_write_metrics(&batch);
 
     if uploader.should_upload
         && let Some(client) = &uploader.client
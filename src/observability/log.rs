@@ -0,0 +1,208 @@
+//! Debug logging with levels, spans and an operation id.
+//!
+//! `GIT_AI_LOG_FORMAT` picks how records are written to stderr:
+//!
+//! - `text` (default): `[git-ai] message`, as `debug_log` always printed
+//! - `json`: one `debug` envelope per line, also appended to the observability
+//!   log so debug output lands next to the errors and performance events of
+//!   the same invocation
+//!
+//! Every record carries the invocation's operation id (`GIT_AI_OPERATION_ID`
+//! when set, so a parent process can tie its children's records to its own)
+//! and the names of the [`span`]s open on the current thread.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{LogEnvelope, append_envelope};
+use crate::output;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// A log record as written to the observability log
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(super) struct DebugEnvelope {
+    #[serde(rename = "type")]
+    pub(super) event_type: String,
+    pub(super) timestamp: String,
+    pub(super) level: Level,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) target: Option<String>,
+    pub(super) message: String,
+    pub(super) operation_id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) spans: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) fields: Option<Value>,
+}
+
+static FORMAT: OnceLock<LogFormat> = OnceLock::new();
+static OPERATION_ID: OnceLock<String> = OnceLock::new();
+
+thread_local! {
+    static SPANS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn format() -> LogFormat {
+    *FORMAT.get_or_init(|| {
+        match std::env::var("GIT_AI_LOG_FORMAT")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "json" => LogFormat::Json,
+            _ => LogFormat::Text,
+        }
+    })
+}
+
+/// Id shared by every record of this invocation
+pub fn operation_id() -> &'static str {
+    OPERATION_ID.get_or_init(|| {
+        std::env::var("GIT_AI_OPERATION_ID")
+            .ok()
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+    })
+}
+
+/// Records at this level are written; debug records only with debug logging on
+pub fn is_enabled(level: Level) -> bool {
+    level < Level::Debug || crate::utils::is_debug_enabled()
+}
+
+/// Log `message` if `level` is enabled
+pub fn event(level: Level, target: Option<&str>, message: &str, fields: Option<Value>) {
+    if is_enabled(level) {
+        write(level, target, message, fields);
+    }
+}
+
+/// Write a record without checking the level; for callers that gate on their
+/// own switches (`GIT_AI_DEBUG_PERFORMANCE`)
+pub fn write(level: Level, target: Option<&str>, message: &str, fields: Option<Value>) {
+    match format() {
+        LogFormat::Text => {
+            let prefix = match target {
+                Some(target) => format!("[git-ai ({})]", target),
+                None => "[git-ai]".to_string(),
+            };
+            match fields {
+                Some(fields) if message.is_empty() => {
+                    eprintln!("{} {}", output::mode().paint("1;33", &prefix), fields)
+                }
+                _ => eprintln!("{} {}", output::mode().paint("1;33", &prefix), message),
+            }
+        }
+        LogFormat::Json => {
+            let envelope = DebugEnvelope {
+                event_type: "debug".to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                level,
+                target: target.map(str::to_string),
+                message: message.to_string(),
+                operation_id: operation_id().to_string(),
+                spans: SPANS.with(|spans| spans.borrow().clone()),
+                fields,
+            };
+            if let Ok(json) = serde_json::to_string(&envelope) {
+                eprintln!("{}", json);
+            }
+            append_envelope(LogEnvelope::Debug(envelope));
+        }
+    }
+}
+
+/// Names the records logged until the guard drops. In JSON format, closing it
+/// logs the span's duration at debug level.
+pub fn span(name: &str) -> SpanGuard {
+    SPANS.with(|spans| spans.borrow_mut().push(name.to_string()));
+    SpanGuard {
+        name: name.to_string(),
+        start: Instant::now(),
+    }
+}
+
+pub struct SpanGuard {
+    name: String,
+    start: Instant,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        if format() == LogFormat::Json {
+            event(
+                Level::Debug,
+                None,
+                &format!("{} finished", self.name),
+                Some(serde_json::json!({ "duration_ms": self.start.elapsed().as_millis() })),
+            );
+        }
+        SPANS.with(|spans| {
+            let mut spans = spans.borrow_mut();
+            if let Some(index) = spans.iter().rposition(|span| *span == self.name) {
+                spans.remove(index);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_nest_and_close_in_order() {
+        let outer = span("outer");
+        {
+            let _inner = span("inner");
+            SPANS.with(|spans| assert_eq!(*spans.borrow(), vec!["outer", "inner"]));
+        }
+        SPANS.with(|spans| assert_eq!(*spans.borrow(), vec!["outer"]));
+        drop(outer);
+        SPANS.with(|spans| assert!(spans.borrow().is_empty()));
+    }
+
+    #[test]
+    fn test_debug_envelope_serializes_level_and_skips_empty_fields() {
+        let envelope = DebugEnvelope {
+            event_type: "debug".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            level: Level::Warn,
+            target: None,
+            message: "slow hook".to_string(),
+            operation_id: "abc".to_string(),
+            spans: vec![],
+            fields: None,
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["type"], "debug");
+        assert_eq!(json["level"], "warn");
+        assert_eq!(json["operation_id"], "abc");
+        assert!(json.get("spans").is_none());
+        assert!(json.get("target").is_none());
+    }
+
+    #[test]
+    fn test_errors_are_always_enabled() {
+        assert!(is_enabled(Level::Error));
+        assert!(is_enabled(Level::Info));
+    }
+}
@@ -8,7 +8,11 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::metrics::{METRICS_API_VERSION, MetricEvent};
 
+pub mod budgets;
 pub mod flush;
+pub mod log;
+pub mod profile;
+pub mod webhook;
 pub mod wrapper_performance_targets;
 
 /// Maximum events per metrics envelope
@@ -64,6 +68,7 @@ enum LogEnvelope {
     #[allow(dead_code)]
     Message(MessageEnvelope),
     Metrics(MetricsEnvelope),
+    Debug(log::DebugEnvelope),
 }
 
 impl LogEnvelope {
@@ -73,6 +78,7 @@ impl LogEnvelope {
             LogEnvelope::Performance(p) => serde_json::to_value(p).ok(),
             LogEnvelope::Message(m) => serde_json::to_value(m).ok(),
             LogEnvelope::Metrics(m) => serde_json::to_value(m).ok(),
+            LogEnvelope::Debug(d) => serde_json::to_value(d).ok(),
         }
     }
 }
@@ -0,0 +1,299 @@
+//! `GIT_AI_PROFILE=1` profiling.
+//!
+//! Major phases open a [`scope`]; nested scopes form a call stack per thread. At
+//! exit, [`finish`] writes two files per invocation to `GIT_AI_PROFILE_DIR`
+//! (default `~/.git-ai/profiles`):
+//!
+//! - `<name>.folded`: folded stacks with self time in microseconds, for
+//!   `flamegraph.pl` / inferno / speedscope
+//! - `<name>.trace.json`: Chrome trace events, for Perfetto or `chrome://tracing`
+//!
+//! With profiling off, a scope is a single cached flag check.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+/// One finished scope
+struct Span {
+    name: &'static str,
+    /// `outer;inner;name`
+    stack: String,
+    thread: u64,
+    start_us: u64,
+    duration_us: u64,
+    self_us: u64,
+}
+
+struct OpenScope {
+    name: &'static str,
+    child_us: u64,
+}
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+static SPANS: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static STACK: RefCell<Vec<OpenScope>> = const { RefCell::new(Vec::new()) };
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    *ENABLED.get_or_init(|| {
+        let enabled = matches!(
+            std::env::var("GIT_AI_PROFILE").as_deref(),
+            Ok("1") | Ok("true")
+        );
+        if enabled {
+            PROCESS_START.get_or_init(Instant::now);
+        }
+        enabled
+    })
+}
+
+/// Guard for a timed scope; the scope ends when it is dropped
+pub struct ProfileScope {
+    start: Option<Instant>,
+}
+
+/// Time everything until the returned guard is dropped under `name`
+pub fn scope(name: &'static str) -> ProfileScope {
+    if !is_enabled() {
+        return ProfileScope { start: None };
+    }
+    STACK.with(|stack| {
+        stack.borrow_mut().push(OpenScope { name, child_us: 0 });
+    });
+    ProfileScope {
+        start: Some(Instant::now()),
+    }
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let duration_us = start.elapsed().as_micros() as u64;
+        let process_start = *PROCESS_START.get_or_init(Instant::now);
+        let start_us = start.saturating_duration_since(process_start).as_micros() as u64;
+
+        let span = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let open = stack.pop()?;
+            let mut path: Vec<&str> = stack.iter().map(|s| s.name).collect();
+            path.push(open.name);
+            if let Some(parent) = stack.last_mut() {
+                parent.child_us += duration_us;
+            }
+            Some(Span {
+                name: open.name,
+                stack: path.join(";"),
+                thread: THREAD_ID.with(|id| *id),
+                start_us,
+                duration_us,
+                self_us: duration_us.saturating_sub(open.child_us),
+            })
+        });
+
+        if let Some(span) = span
+            && let Ok(mut spans) = SPANS.lock()
+        {
+            spans.push(span);
+        }
+    }
+}
+
+/// Write the profile for this invocation, if profiling is on and anything was
+/// recorded. Safe to call more than once; later calls write nothing new.
+pub fn finish() {
+    if !is_enabled() {
+        return;
+    }
+    let spans = match SPANS.lock() {
+        Ok(mut spans) => std::mem::take(&mut *spans),
+        Err(_) => return,
+    };
+    if spans.is_empty() {
+        return;
+    }
+
+    let label = invocation_label();
+    let total_us = PROCESS_START
+        .get()
+        .map(|start| start.elapsed().as_micros() as u64)
+        .unwrap_or(0);
+
+    let Some(dir) = profile_dir() else {
+        return;
+    };
+    if std::fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let base = dir.join(format!("{}-{}-{}", timestamp, std::process::id(), label));
+
+    let folded_path = base.with_extension("folded");
+    let trace_path = base.with_extension("trace.json");
+    let folded = render_folded(&label, total_us, &spans);
+    let trace = render_trace(&label, total_us, &spans);
+
+    if std::fs::write(&folded_path, folded).is_ok()
+        && std::fs::write(&trace_path, trace.to_string()).is_ok()
+    {
+        eprintln!(
+            "[git-ai] profile written to {} and {}",
+            folded_path.display(),
+            trace_path.display()
+        );
+    }
+}
+
+fn profile_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("GIT_AI_PROFILE_DIR")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::home_dir().map(|home| home.join(".git-ai").join("profiles"))
+}
+
+/// `git-ai-checkpoint`, `git-rebase`, ... from the process arguments
+fn invocation_label() -> String {
+    let mut args = std::env::args();
+    let binary = args
+        .next()
+        .and_then(|arg| {
+            std::path::Path::new(&arg)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "git-ai".to_string());
+    let command = args.find(|arg| !arg.starts_with('-'));
+    let label = match command {
+        Some(command) => format!("{}-{}", binary, command),
+        None => binary,
+    };
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Folded stacks rooted at `root`, self time in microseconds. Time outside any
+/// scope is charged to the root.
+fn render_folded(root: &str, total_us: u64, spans: &[Span]) -> String {
+    let mut self_times: BTreeMap<String, u64> = BTreeMap::new();
+    for span in spans {
+        *self_times
+            .entry(format!("{};{}", root, span.stack))
+            .or_default() += span.self_us;
+    }
+    // Only the main thread's top-level scopes count against wall time
+    let main_thread = spans.iter().map(|span| span.thread).min().unwrap_or(0);
+    let top_level_us: u64 = spans
+        .iter()
+        .filter(|span| span.thread == main_thread && !span.stack.contains(';'))
+        .map(|span| span.duration_us)
+        .sum();
+    let root_self_us = total_us.saturating_sub(top_level_us);
+    if root_self_us > 0 {
+        self_times.insert(root.to_string(), root_self_us);
+    }
+
+    self_times
+        .into_iter()
+        .filter(|(_, us)| *us > 0)
+        .map(|(stack, us)| format!("{} {}\n", stack, us))
+        .collect()
+}
+
+fn render_trace(root: &str, total_us: u64, spans: &[Span]) -> serde_json::Value {
+    let pid = std::process::id();
+    let mut events = vec![json!({
+        "name": root,
+        "ph": "X",
+        "ts": 0,
+        "dur": total_us,
+        "pid": pid,
+        "tid": spans.iter().map(|span| span.thread).min().unwrap_or(1),
+    })];
+    events.extend(spans.iter().map(|span| {
+        json!({
+            "name": span.name,
+            "ph": "X",
+            "ts": span.start_us,
+            "dur": span.duration_us,
+            "pid": pid,
+            "tid": span.thread,
+        })
+    }));
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(
+        stack: &'static str,
+        thread: u64,
+        start_us: u64,
+        duration_us: u64,
+        self_us: u64,
+    ) -> Span {
+        Span {
+            name: stack.rsplit(';').next().unwrap(),
+            stack: stack.to_string(),
+            thread,
+            start_us,
+            duration_us,
+            self_us,
+        }
+    }
+
+    #[test]
+    fn test_render_folded_charges_self_time() {
+        let spans = vec![
+            span("post_command_hooks;note_write", 1, 150, 50, 50),
+            span("post_command_hooks", 1, 100, 300, 250),
+            span("blame", 2, 120, 40, 40),
+        ];
+        assert_eq!(
+            render_folded("git-commit", 500, &spans),
+            "git-commit 200\ngit-commit;blame 40\ngit-commit;post_command_hooks 250\ngit-commit;post_command_hooks;note_write 50\n"
+        );
+    }
+
+    #[test]
+    fn test_render_trace_events() {
+        let spans = vec![span("rewrite_authorship", 1, 10, 90, 90)];
+        let trace = render_trace("git-rebase", 100, &spans);
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["name"], "git-rebase");
+        assert_eq!(events[1]["name"], "rewrite_authorship");
+        assert_eq!(events[1]["ph"], "X");
+        assert_eq!(events[1]["ts"], 10);
+        assert_eq!(events[1]["dur"], 90);
+    }
+}
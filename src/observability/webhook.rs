@@ -0,0 +1,294 @@
+//! Local event webhooks (`events.webhook_url`).
+//!
+//! Selected local events are written to a small on-disk queue under
+//! `~/.git-ai/internal/webhooks/` and delivered by the `flush-webhooks`
+//! background worker, so git hooks never wait on a user-supplied endpoint.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+
+/// Payload schema version sent in every webhook body
+pub const WEBHOOK_PAYLOAD_VERSION: u8 = 1;
+
+/// Undelivered events older than this are dropped instead of retried
+const MAX_PENDING_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static QUEUE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Local events that can be forwarded to a webhook
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalEventKind {
+    /// Authorship for a new commit was written
+    CommitProcessed,
+    /// Authorship was carried over after amend, rebase or cherry-pick
+    RewriteCompleted,
+    /// A policy check failed
+    PolicyViolation,
+}
+
+impl LocalEventKind {
+    pub const ALL_NAMES: [&'static str; 3] =
+        ["commit_processed", "rewrite_completed", "policy_violation"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalEventKind::CommitProcessed => "commit_processed",
+            LocalEventKind::RewriteCompleted => "rewrite_completed",
+            LocalEventKind::PolicyViolation => "policy_violation",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "commit_processed" => Some(LocalEventKind::CommitProcessed),
+            "rewrite_completed" => Some(LocalEventKind::RewriteCompleted),
+            "policy_violation" => Some(LocalEventKind::PolicyViolation),
+            _ => None,
+        }
+    }
+}
+
+/// JSON body POSTed to the webhook endpoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WebhookPayload {
+    pub version: u8,
+    pub event: String,
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    pub data: serde_json::Value,
+}
+
+impl WebhookPayload {
+    pub fn new(kind: LocalEventKind, repo: Option<String>, data: serde_json::Value) -> Self {
+        Self {
+            version: WEBHOOK_PAYLOAD_VERSION,
+            event: kind.as_str().to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            repo,
+            data,
+        }
+    }
+}
+
+/// Whether `kind` is selected by `events.webhook_events` (empty selects everything)
+pub fn is_event_selected(kind: LocalEventKind, selected: &[String]) -> bool {
+    selected.is_empty() || selected.iter().any(|name| name.trim() == kind.as_str())
+}
+
+/// Directory holding undelivered webhook payloads
+pub fn webhook_queue_dir() -> Option<PathBuf> {
+    crate::config::internal_dir_path().map(|dir| dir.join("webhooks"))
+}
+
+/// Queue a local event for delivery and kick off the background worker.
+///
+/// Does nothing unless `events.webhook_url` is configured and the event is selected.
+pub fn emit_local_event(kind: LocalEventKind, repo: Option<String>, data: serde_json::Value) {
+    let config = Config::get();
+    if config.events_webhook_url().is_none()
+        || !is_event_selected(kind, config.events_webhook_events())
+    {
+        return;
+    }
+    let Some(dir) = webhook_queue_dir() else {
+        return;
+    };
+
+    let payload = WebhookPayload::new(kind, repo, data);
+    match enqueue_payload(&dir, &payload) {
+        Ok(_) => crate::commands::flush_webhooks::spawn_background_webhook_flush(),
+        Err(e) => crate::utils::debug_log(&format!("Failed to queue webhook event: {}", e)),
+    }
+}
+
+/// Write a payload to the queue directory, returning the file it was stored in
+pub fn enqueue_payload(dir: &Path, payload: &WebhookPayload) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let name = format!(
+        "{:020}-{}-{}.json",
+        nanos,
+        std::process::id(),
+        QUEUE_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+    let path = dir.join(name);
+    let json = serde_json::to_vec(payload).map_err(std::io::Error::other)?;
+
+    // Write to a temp name first so the worker never reads a partial payload
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, json)?;
+    std::fs::rename(&tmp_path, &path)?;
+    Ok(path)
+}
+
+/// Queued payload files, oldest first
+pub fn pending_payloads(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Outcome of one `deliver_pending` pass
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub dropped: usize,
+    pub remaining: usize,
+}
+
+/// Deliver queued payloads in order using `post`, which returns the HTTP status code.
+///
+/// Delivered and unreadable payloads are removed. On the first failed delivery the
+/// pass stops so events stay ordered; payloads older than a day are dropped instead.
+pub fn deliver_pending<F>(dir: &Path, url: &str, mut post: F) -> DeliveryReport
+where
+    F: FnMut(&str, &str) -> Result<u16, String>,
+{
+    let mut report = DeliveryReport::default();
+    let pending = pending_payloads(dir);
+
+    for (index, path) in pending.iter().enumerate() {
+        let body = match std::fs::read_to_string(path) {
+            Ok(body) if serde_json::from_str::<WebhookPayload>(&body).is_ok() => body,
+            _ => {
+                let _ = std::fs::remove_file(path);
+                report.dropped += 1;
+                continue;
+            }
+        };
+
+        match post(url, &body) {
+            Ok(status) if (200..300).contains(&status) => {
+                let _ = std::fs::remove_file(path);
+                report.delivered += 1;
+            }
+            result => {
+                if is_expired(path) {
+                    let _ = std::fs::remove_file(path);
+                    report.dropped += 1;
+                    continue;
+                }
+                crate::utils::debug_log(&format!(
+                    "Webhook delivery to {} failed: {}",
+                    url,
+                    match result {
+                        Ok(status) => format!("HTTP {}", status),
+                        Err(e) => e,
+                    }
+                ));
+                report.remaining = pending.len() - index;
+                break;
+            }
+        }
+    }
+
+    report
+}
+
+fn is_expired(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > MAX_PENDING_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload(kind: LocalEventKind) -> WebhookPayload {
+        WebhookPayload::new(kind, Some("/tmp/repo".to_string()), json!({"sha": "abc"}))
+    }
+
+    #[test]
+    fn test_event_kind_names_round_trip() {
+        for name in LocalEventKind::ALL_NAMES {
+            let kind = LocalEventKind::from_name(name).unwrap();
+            assert_eq!(kind.as_str(), name);
+        }
+        assert_eq!(LocalEventKind::from_name("push"), None);
+    }
+
+    #[test]
+    fn test_is_event_selected() {
+        assert!(is_event_selected(LocalEventKind::CommitProcessed, &[]));
+        let selected = vec!["rewrite_completed".to_string()];
+        assert!(is_event_selected(
+            LocalEventKind::RewriteCompleted,
+            &selected
+        ));
+        assert!(!is_event_selected(
+            LocalEventKind::CommitProcessed,
+            &selected
+        ));
+    }
+
+    #[test]
+    fn test_payload_serialization() {
+        let value = serde_json::to_value(payload(LocalEventKind::CommitProcessed)).unwrap();
+        assert_eq!(value["version"], json!(WEBHOOK_PAYLOAD_VERSION));
+        assert_eq!(value["event"], json!("commit_processed"));
+        assert_eq!(value["repo"], json!("/tmp/repo"));
+        assert_eq!(value["data"]["sha"], json!("abc"));
+        assert!(value["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_deliver_pending_in_order_and_removes_delivered() {
+        let dir = tempfile::tempdir().unwrap();
+        enqueue_payload(dir.path(), &payload(LocalEventKind::CommitProcessed)).unwrap();
+        enqueue_payload(dir.path(), &payload(LocalEventKind::RewriteCompleted)).unwrap();
+        std::fs::write(dir.path().join("0-garbage.json"), "not json").unwrap();
+
+        let mut seen = Vec::new();
+        let report = deliver_pending(dir.path(), "https://example.com/hook", |_, body| {
+            let parsed: WebhookPayload = serde_json::from_str(body).unwrap();
+            seen.push(parsed.event);
+            Ok(204)
+        });
+
+        assert_eq!(seen, vec!["commit_processed", "rewrite_completed"]);
+        assert_eq!(
+            report,
+            DeliveryReport {
+                delivered: 2,
+                dropped: 1,
+                remaining: 0
+            }
+        );
+        assert!(pending_payloads(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_deliver_pending_keeps_events_after_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        enqueue_payload(dir.path(), &payload(LocalEventKind::CommitProcessed)).unwrap();
+        enqueue_payload(dir.path(), &payload(LocalEventKind::CommitProcessed)).unwrap();
+
+        let mut calls = 0;
+        let report = deliver_pending(dir.path(), "https://example.com/hook", |_, _| {
+            calls += 1;
+            Ok(503)
+        });
+
+        assert_eq!(calls, 1);
+        assert_eq!(report.remaining, 2);
+        assert_eq!(pending_payloads(dir.path()).len(), 2);
+    }
+}
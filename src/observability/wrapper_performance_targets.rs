@@ -4,7 +4,7 @@ use serde_json::json;
 
 use crate::{
     authorship::working_log::CheckpointKind,
-    observability::log_performance,
+    observability::{budgets::check_budget, log_performance},
     utils::{debug_performance_log, debug_performance_log_structured},
 };
 
@@ -103,6 +103,7 @@ pub fn log_performance_for_checkpoint(
     checkpoint_kind: CheckpointKind,
 ) {
     let within_target = Duration::from_millis(50 * files_edited as u64) >= duration;
+    check_budget("checkpoint", duration);
 
     // Output structured JSON for benchmarking (when GIT_AI_DEBUG_PERFORMANCE >= 2)
     // For git-ai commands like checkpoint, there's no pre/post/git breakdown - just total time
@@ -0,0 +1,391 @@
+//! Org-wide configuration.
+//!
+//! `config.remote_url` points at a JSON document, hosted by the org, with the
+//! same keys as `~/.git-ai/config.json`. It sits between the defaults and the
+//! local file: local keys win, and objects are merged key by key so a local
+//! `redaction.enabled` doesn't drop the org's `redaction.rules`. This lets
+//! admins roll out new exclusions or policy to every machine by editing one
+//! document.
+//!
+//! Like `.git-ai.toml`, the document only carries repo policy: exclusions,
+//! redaction, tracking, policy, granularity and the notes ref. Anything that
+//! could run a program or send data elsewhere (`git_path`, `api_key`,
+//! `dual_write`, telemetry, ...) is dropped. `api_base_url` is honoured only
+//! when the machine opts in with `config.allow_endpoints`. The URL itself must
+//! be https; plain http is accepted for localhost only.
+//!
+//! The document is cached in `~/.git-ai/internal/org_config.json` and checked
+//! again once `config.ttl_secs` (default one hour) has passed, sending the
+//! cached `ETag` so an unchanged document costs a 304. When the endpoint can't
+//! be reached the cached copy keeps being used, and the next attempt waits a
+//! few minutes so an outage doesn't slow down every git command.
+
+use crate::api::ApiContext;
+use crate::config::{FileConfig, OrgConfigSource, internal_dir_path};
+use crate::utils::debug_log;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_TTL_SECS: u64 = 60 * 60;
+/// Wait before retrying after a failed fetch, if shorter than the TTL
+const FAILED_FETCH_RETRY_SECS: u64 = 5 * 60;
+const FETCH_TIMEOUT_SECS: u64 = 3;
+
+/// Keys the org document may set, mirroring what `.git-ai.toml` accepts
+const POLICY_KEYS: &[&str] = &[
+    "attribution_granularity",
+    "capture_environment",
+    "context_capture",
+    "exclude_paths",
+    "tracking",
+    "redaction",
+    "policy",
+    "notes_ref",
+];
+/// Keys the org document may set once `config.allow_endpoints` is on
+const ENDPOINT_KEYS: &[&str] = &["api_base_url"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedOrgConfig {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    /// When the document was last fetched or confirmed unchanged (or, if
+    /// `failed`, when fetching it last failed)
+    checked_at: u64,
+    #[serde(default)]
+    failed: bool,
+    document: Value,
+}
+
+enum FetchedOrgConfig {
+    NotModified,
+    Document {
+        document: Value,
+        etag: Option<String>,
+    },
+}
+
+/// The org config document for `source`, from the cache while it is fresh and
+/// from `config.remote_url` otherwise. `None` when no URL is set or nothing
+/// could be fetched yet.
+pub fn org_config_document(source: &OrgConfigSource) -> Option<Value> {
+    let url = source.remote_url.as_deref().filter(|url| !url.is_empty())?;
+    if !is_allowed_url(url) {
+        eprintln!(
+            "Warning: Ignoring config.remote_url '{}': expected an https URL",
+            url
+        );
+        return None;
+    }
+    let ttl_secs = source.ttl_secs.unwrap_or(DEFAULT_TTL_SECS);
+    let mut document = load_document(url, ttl_secs, &cache_path()?, now_secs())?;
+    restrict_keys(&mut document, source.allow_endpoints.unwrap_or(false));
+    Some(document)
+}
+
+/// Whether `url` may serve an org config: https, or http to a loopback host
+pub fn is_allowed_url(url: &str) -> bool {
+    if url.starts_with("https://") {
+        return true;
+    }
+    let Some(rest) = url.strip_prefix("http://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Drop every key the org document isn't allowed to set
+fn restrict_keys(document: &mut Value, allow_endpoints: bool) {
+    let Some(keys) = document.as_object_mut() else {
+        return;
+    };
+    keys.retain(|key, _| {
+        let allowed = POLICY_KEYS.contains(&key.as_str())
+            || (allow_endpoints && ENDPOINT_KEYS.contains(&key.as_str()));
+        if !allowed {
+            debug_log(&format!("Ignoring org config key '{}'", key));
+        }
+        allowed
+    });
+}
+
+fn load_document(url: &str, ttl_secs: u64, cache_path: &Path, now: u64) -> Option<Value> {
+    let cached = std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<CachedOrgConfig>(&contents).ok())
+        .filter(|cached| cached.url == url);
+
+    if let Some(cached) = &cached {
+        let fresh_for = if cached.failed {
+            ttl_secs.min(FAILED_FETCH_RETRY_SECS)
+        } else {
+            ttl_secs
+        };
+        if now.saturating_sub(cached.checked_at) < fresh_for {
+            return Some(cached.document.clone());
+        }
+    }
+
+    let etag = cached.as_ref().and_then(|cached| cached.etag.clone());
+    let updated = match fetch_document(url, etag.as_deref()) {
+        Ok(FetchedOrgConfig::Document { document, etag }) => CachedOrgConfig {
+            url: url.to_string(),
+            etag,
+            checked_at: now,
+            failed: false,
+            document,
+        },
+        Ok(FetchedOrgConfig::NotModified) => match cached {
+            Some(cached) => CachedOrgConfig {
+                checked_at: now,
+                failed: false,
+                ..cached
+            },
+            None => return None,
+        },
+        Err(e) => {
+            debug_log(&format!("Failed to fetch org config from {}: {}", url, e));
+            CachedOrgConfig {
+                checked_at: now,
+                failed: true,
+                ..cached.unwrap_or_else(|| CachedOrgConfig {
+                    url: url.to_string(),
+                    etag: None,
+                    checked_at: now,
+                    failed: true,
+                    document: Value::Object(Default::default()),
+                })
+            }
+        }
+    };
+
+    if let Ok(json) = serde_json::to_string(&updated) {
+        if let Some(dir) = cache_path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = std::fs::write(cache_path, json);
+    }
+    Some(updated.document)
+}
+
+fn fetch_document(url: &str, etag: Option<&str>) -> Result<FetchedOrgConfig, String> {
+    let mut request = ApiContext::http_get(url).with_timeout(FETCH_TIMEOUT_SECS);
+    if let Some(etag) = etag {
+        request = request.with_header("If-None-Match", etag);
+    }
+    let response = request.send().map_err(|e| e.to_string())?;
+    match response.status_code {
+        304 => Ok(FetchedOrgConfig::NotModified),
+        200 => {
+            let mut document =
+                serde_json::from_str::<Value>(response.as_str().map_err(|e| e.to_string())?)
+                    .map_err(|e| format!("invalid JSON: {}", e))?;
+            if !document.is_object() {
+                return Err("document is not a JSON object".to_string());
+            }
+            // Endpoints are kept in the cache so opting in later takes effect
+            // without a refetch; they are filtered again on every load
+            restrict_keys(&mut document, true);
+            serde_json::from_value::<FileConfig>(document.clone())
+                .map_err(|e| format!("invalid config: {}", e))?;
+            Ok(FetchedOrgConfig::Document {
+                document,
+                etag: response.headers.get("etag").cloned(),
+            })
+        }
+        status => Err(format!("HTTP {}", status)),
+    }
+}
+
+/// Merge `overlay` over `base`: objects key by key, anything else replaced
+pub fn merge_over(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_over(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    internal_dir_path().map(|dir| dir.join("org_config.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one canned response per request, returning the requests received
+    fn serve(responses: Vec<String>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/config.json", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).unwrap();
+                requests.push(String::from_utf8_lossy(&buffer[..read]).to_string());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_merge_over_keeps_org_keys_local_does_not_set() {
+        let mut org = json!({
+            "exclude_paths": ["vendor/**"],
+            "redaction": {"enabled": true, "rules": {"token": "tok_[a-z]+"}},
+            "api_base_url": "https://ai.corp.example"
+        });
+        merge_over(
+            &mut org,
+            json!({"redaction": {"enabled": false}, "exclude_paths": ["dist/**"]}),
+        );
+        assert_eq!(
+            org,
+            json!({
+                "exclude_paths": ["dist/**"],
+                "redaction": {"enabled": false, "rules": {"token": "tok_[a-z]+"}},
+                "api_base_url": "https://ai.corp.example"
+            })
+        );
+    }
+
+    #[test]
+    fn test_document_is_cached_and_revalidated_with_etag() {
+        let body = r#"{"exclude_paths":["vendor/**"],"config":{"remote_url":"http://elsewhere"},"git_path":"/tmp/evil","api_key":"k","dual_write":{}}"#;
+        let ok = format!(
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (url, server) = serve(vec![
+            ok,
+            "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("org_config.json");
+        let expected = json!({"exclude_paths": ["vendor/**"]});
+
+        assert_eq!(
+            load_document(&url, 60, &cache, 1000),
+            Some(expected.clone())
+        );
+        // Within the TTL the cache answers without a request
+        assert_eq!(
+            load_document(&url, 60, &cache, 1030),
+            Some(expected.clone())
+        );
+        // After it, the document is revalidated and kept on 304
+        assert_eq!(load_document(&url, 60, &cache, 1100), Some(expected));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
+        assert!(
+            requests[1].to_lowercase().contains("if-none-match: \"v1\""),
+            "{}",
+            requests[1]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_endpoint_keeps_cached_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("org_config.json");
+        let url = "http://127.0.0.1:1/config.json";
+        let cached = CachedOrgConfig {
+            url: url.to_string(),
+            etag: None,
+            checked_at: 0,
+            failed: false,
+            document: json!({"notes_ref": "org"}),
+        };
+        std::fs::write(&cache, serde_json::to_string(&cached).unwrap()).unwrap();
+
+        assert_eq!(
+            load_document(url, 60, &cache, 1000),
+            Some(json!({"notes_ref": "org"}))
+        );
+        let saved: CachedOrgConfig =
+            serde_json::from_str(&std::fs::read_to_string(&cache).unwrap()).unwrap();
+        assert!(saved.failed);
+        assert_eq!(saved.checked_at, 1000);
+    }
+
+    #[test]
+    fn test_restrict_keys_allows_endpoints_only_when_opted_in() {
+        let document = json!({
+            "exclude_paths": ["vendor/**"],
+            "policy": {"rules": []},
+            "api_base_url": "https://ai.corp.example",
+            "git_path": "/tmp/git",
+            "api_key": "secret",
+            "dual_write": {"url": "https://sink.example"},
+            "telemetry_oss": "off"
+        });
+
+        let mut restricted = document.clone();
+        restrict_keys(&mut restricted, false);
+        assert_eq!(
+            restricted,
+            json!({"exclude_paths": ["vendor/**"], "policy": {"rules": []}})
+        );
+
+        let mut restricted = document;
+        restrict_keys(&mut restricted, true);
+        assert_eq!(
+            restricted,
+            json!({
+                "exclude_paths": ["vendor/**"],
+                "policy": {"rules": []},
+                "api_base_url": "https://ai.corp.example"
+            })
+        );
+    }
+
+    #[test]
+    fn test_plain_http_is_only_allowed_for_loopback() {
+        assert!(is_allowed_url("https://config.corp.example/git-ai.json"));
+        assert!(is_allowed_url("http://127.0.0.1:8080/config.json"));
+        assert!(is_allowed_url("http://localhost/config.json"));
+        assert!(is_allowed_url("http://[::1]:9000/config.json"));
+        assert!(!is_allowed_url("http://config.corp.example/git-ai.json"));
+        assert!(!is_allowed_url("http://127.0.0.1.evil.example/config.json"));
+        assert!(!is_allowed_url("http://localhost@evil.example/config.json"));
+        assert!(!is_allowed_url("ftp://localhost/config.json"));
+    }
+}
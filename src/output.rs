@@ -0,0 +1,172 @@
+//! Terminal output capabilities shared by every command.
+//!
+//! Three switches, each available as an environment variable (so they reach git-ai
+//! running inside git hooks and the git wrapper) and as a global `git-ai` flag:
+//!
+//! - color: off with `NO_COLOR` (https://no-color.org), `TERM=dumb` or `--no-color`
+//! - unicode: off with `GIT_AI_ASCII=1` or `--ascii`; symbols, bars and spinners
+//!   fall back to plain ASCII
+//! - plain: `GIT_AI_PLAIN=1` or `--plain`; no color, ASCII only, no animation, and
+//!   linear `label: value` lines instead of bars and aligned columns, for screen
+//!   readers and logs
+
+use std::sync::OnceLock;
+
+static MODE: OnceLock<OutputMode> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputMode {
+    pub color: bool,
+    pub unicode: bool,
+    pub plain: bool,
+}
+
+/// The kind of a one-line status message, such as the result of an install step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Warning,
+    Error,
+    Skipped,
+}
+
+impl Default for OutputMode {
+    fn default() -> Self {
+        Self {
+            color: true,
+            unicode: true,
+            plain: false,
+        }
+    }
+}
+
+impl OutputMode {
+    pub const PLAIN: OutputMode = OutputMode {
+        color: false,
+        unicode: false,
+        plain: true,
+    };
+
+    fn from_env() -> Self {
+        let flag = |name: &str| {
+            std::env::var(name)
+                .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+                .unwrap_or(false)
+        };
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+            || std::env::var("TERM").as_deref() == Ok("dumb");
+
+        if flag("GIT_AI_PLAIN") {
+            return Self::PLAIN;
+        }
+        Self {
+            color: !no_color,
+            unicode: !flag("GIT_AI_ASCII"),
+            plain: false,
+        }
+    }
+
+    /// Wrap `text` in an SGR escape sequence (e.g. `"1;32"` for bold green) when
+    /// color is enabled
+    pub fn paint(&self, sgr: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", sgr, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// `unicode` when unicode output is enabled, `ascii` otherwise
+    pub fn pick<'a>(&self, unicode: &'a str, ascii: &'a str) -> &'a str {
+        if self.unicode { unicode } else { ascii }
+    }
+
+    /// The marker for a status message: `✓`, `[ok]` in ASCII, `ok:` in plain mode
+    pub fn status_symbol(&self, status: Status) -> &'static str {
+        match (status, self.plain, self.unicode) {
+            (Status::Success, true, _) => "ok:",
+            (Status::Warning, true, _) => "warning:",
+            (Status::Error, true, _) => "error:",
+            (Status::Skipped, true, _) => "skipped:",
+            (Status::Success, false, true) => "✓",
+            (Status::Warning, false, true) => "⚠",
+            (Status::Error, false, true) => "✗",
+            (Status::Skipped, false, true) => "○",
+            (Status::Success, false, false) => "[ok]",
+            (Status::Warning, false, false) => "[!]",
+            (Status::Error, false, false) => "[x]",
+            (Status::Skipped, false, false) => "[-]",
+        }
+    }
+
+    /// A status message with its marker, colored to match: green for success,
+    /// yellow for warnings, red for errors and gray for skipped steps
+    pub fn status(&self, status: Status, message: &str) -> String {
+        let sgr = match status {
+            Status::Success => "1;32",
+            Status::Warning => "1;33",
+            Status::Error => "1;31",
+            Status::Skipped => "90",
+        };
+        self.paint(sgr, &format!("{} {}", self.status_symbol(status), message))
+    }
+}
+
+/// The process-wide output mode, read from the environment unless a global flag
+/// already set it
+pub fn mode() -> &'static OutputMode {
+    MODE.get_or_init(OutputMode::from_env)
+}
+
+/// Remove the global `--no-color`, `--ascii` and `--plain` flags from git-ai's own
+/// arguments and fix the output mode. Has no effect on the mode after the first call
+/// to [`mode`].
+pub fn init_from_args(args: &[String]) -> Vec<String> {
+    let mut mode = OutputMode::from_env();
+    let mut remaining = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.as_str() {
+            "--no-color" => mode.color = false,
+            "--ascii" => mode.unicode = false,
+            "--plain" => mode = OutputMode::PLAIN,
+            _ => remaining.push(arg.clone()),
+        }
+    }
+    let _ = MODE.set(mode);
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_only_colors_when_enabled() {
+        let mode = OutputMode::default();
+        assert_eq!(mode.paint("32", "hi"), "\x1b[32mhi\x1b[0m");
+        let mode = OutputMode {
+            color: false,
+            ..OutputMode::default()
+        };
+        assert_eq!(mode.paint("32", "hi"), "hi");
+    }
+
+    #[test]
+    fn test_status_falls_back_to_ascii_and_labels() {
+        let ascii = OutputMode {
+            color: false,
+            unicode: false,
+            plain: false,
+        };
+        assert_eq!(ascii.status(Status::Success, "Installed"), "[ok] Installed");
+        assert_eq!(
+            OutputMode::PLAIN.status(Status::Warning, "Dry run"),
+            "warning: Dry run"
+        );
+        assert_eq!(
+            OutputMode::default().status(Status::Error, "Failed"),
+            "\x1b[1;31m✗ Failed\x1b[0m"
+        );
+        assert!(OutputMode::PLAIN.status(Status::Skipped, "x").is_ascii());
+    }
+}
@@ -0,0 +1,252 @@
+//! Discovery and invocation of external plugin executables.
+//!
+//! Plugins are executables on `PATH` named `<prefix><name>` (for example
+//! `git-ai-source-aider`). They are invoked with a subcommand argument, receive
+//! JSON on stdin and answer with JSON on stdout. Every invocation sets
+//! `GIT_AI_PLUGIN_API_VERSION` so plugins can reject contracts they don't know.
+
+use crate::error::GitAiError;
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Version of the JSON contract spoken with plugins
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Plugins that don't answer within this window are killed
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An executable discovered on PATH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugin {
+    /// Name with the prefix stripped (e.g. "aider" for `git-ai-source-aider`)
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Result of running a plugin to completion
+#[derive(Debug)]
+pub struct PluginOutput {
+    /// Exit code, `None` if the plugin was killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Plugin names are restricted so they can double as ids and preset names
+pub fn is_valid_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Find all plugins with `prefix` on PATH. Earlier PATH entries win on name clashes.
+pub fn discover_plugins(prefix: &str) -> Vec<Plugin> {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    discover_plugins_in(std::env::split_paths(&path_var), prefix)
+}
+
+/// Find the plugin called `name` with `prefix` on PATH
+pub fn find_plugin(prefix: &str, name: &str) -> Option<Plugin> {
+    if !is_valid_plugin_name(name) {
+        return None;
+    }
+    discover_plugins(prefix)
+        .into_iter()
+        .find(|plugin| plugin.name == name)
+}
+
+fn discover_plugins_in(dirs: impl IntoIterator<Item = PathBuf>, prefix: &str) -> Vec<Plugin> {
+    let mut seen = HashSet::new();
+    let mut plugins = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut found: Vec<Plugin> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let file_name = path.file_name()?.to_str()?;
+                let name = plugin_name_from_file(file_name, prefix)?;
+                is_executable_file(&path).then_some(Plugin { name, path })
+            })
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for plugin in found {
+            if seen.insert(plugin.name.clone()) {
+                plugins.push(plugin);
+            }
+        }
+    }
+
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+fn plugin_name_from_file(file_name: &str, prefix: &str) -> Option<String> {
+    let rest = file_name.strip_prefix(prefix)?;
+    #[cfg(windows)]
+    let rest = {
+        let lower = rest.to_ascii_lowercase();
+        [".exe", ".cmd", ".bat"]
+            .iter()
+            .find(|ext| lower.ends_with(*ext))
+            .map(|ext| &rest[..rest.len() - ext.len()])
+            .unwrap_or(rest)
+    };
+    is_valid_plugin_name(rest).then(|| rest.to_string())
+}
+
+#[cfg(unix)]
+fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run `plugin <subcommand>` with `stdin` piped in, waiting up to the plugin timeout
+pub fn run_plugin(
+    plugin: &Plugin,
+    subcommand: &str,
+    stdin: &str,
+) -> Result<PluginOutput, GitAiError> {
+    let mut child = Command::new(&plugin.path)
+        .arg(subcommand)
+        .env("GIT_AI_PLUGIN_API_VERSION", PLUGIN_API_VERSION.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            GitAiError::Generic(format!(
+                "Failed to run plugin {}: {}",
+                plugin.path.display(),
+                e
+            ))
+        })?;
+
+    // Drain output before writing stdin so a chatty plugin can't fill a pipe and block us
+    let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+    let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+    if let Some(mut child_stdin) = child.stdin.take() {
+        // A plugin that ignores stdin may exit before we finish writing; that's fine.
+        let _ = child_stdin.write_all(stdin.as_bytes());
+    }
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > PLUGIN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(GitAiError::Generic(format!(
+                "Plugin {} timed out after {}s",
+                plugin.name,
+                PLUGIN_TIMEOUT.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    let join = |reader: Option<std::thread::JoinHandle<String>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+
+    Ok(PluginOutput {
+        exit_code: status.code(),
+        stdout: join(stdout_reader),
+        stderr: join(stderr_reader),
+    })
+}
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(mut pipe: R) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = pipe.read_to_string(&mut buf);
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, file_name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(file_name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_is_valid_plugin_name() {
+        assert!(is_valid_plugin_name("aider"));
+        assert!(is_valid_plugin_name("my_tool-2"));
+        assert!(!is_valid_plugin_name(""));
+        assert!(!is_valid_plugin_name("../evil"));
+        assert!(!is_valid_plugin_name("has space"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_plugins_first_path_entry_wins() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let winner = write_script(first.path(), "git-ai-source-foo", "exit 0");
+        write_script(second.path(), "git-ai-source-foo", "exit 0");
+        write_script(second.path(), "git-ai-source-bar", "exit 0");
+        write_script(second.path(), "git-ai-policy-baz", "exit 0");
+        // Not executable, so not a plugin
+        std::fs::write(second.path().join("git-ai-source-noexec"), "").unwrap();
+
+        let plugins = discover_plugins_in(
+            vec![first.path().to_path_buf(), second.path().to_path_buf()],
+            "git-ai-source-",
+        );
+
+        let names: Vec<&str> = plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["bar", "foo"]);
+        assert_eq!(plugins[1].path, winner);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_plugin_passes_stdin_and_subcommand() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_script(
+            dir.path(),
+            "git-ai-source-echo",
+            "echo \"$1 v$GIT_AI_PLUGIN_API_VERSION\"; cat; echo oops >&2; exit 3",
+        );
+        let plugin = Plugin {
+            name: "echo".to_string(),
+            path,
+        };
+
+        let output = run_plugin(&plugin, "info", "{\"hello\":1}").unwrap();
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(output.stdout, "info v1\n{\"hello\":1}");
+        assert_eq!(output.stderr.trim(), "oops");
+    }
+}
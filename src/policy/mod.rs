@@ -0,0 +1,524 @@
+//! Commit and push policy evaluation.
+//!
+//! Before a commit or push, git-ai summarises the pending attribution and hands
+//! it to every `git-ai-policy-*` plugin on PATH (see [`plugins`]). Commits are
+//! also checked against the built-in `policy.rules` from the config (see
+//! [`rules`]). The combined verdicts are printed as a policy report; any failing
+//! verdict blocks the operation. `--no-verify` skips the plugins like it does git
+//! hooks, but built-in rules are still checked: failures no longer block and are
+//! recorded as `policy_override` events with the code `--no-verify`.
+
+pub mod plugins;
+pub mod rules;
+
+use crate::authorship::stats::{CommitStats, stats_for_commit_stats};
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::config::{Config, TrustSettings, TrustTier};
+use crate::error::GitAiError;
+use crate::git::push_updates::{PushUpdate, pushed_revision_args};
+use crate::git::repository::{Repository, exec_git};
+use crate::observability::webhook::{LocalEventKind, emit_local_event};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Outgoing commits summarised for pre-push policies are capped at this many
+const MAX_PUSH_COMMITS: usize = 50;
+
+/// When policies are evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PolicyStage {
+    PreCommit,
+    PrePush,
+}
+
+impl PolicyStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyStage::PreCommit => "pre-commit",
+            PolicyStage::PrePush => "pre-push",
+        }
+    }
+}
+
+/// Outcome of a single policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Pass,
+    Warn,
+    Fail,
+    /// The policy could not be evaluated; reported but never blocks
+    Error,
+}
+
+/// A specific rule a policy found broken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyResult {
+    pub policy: String,
+    pub verdict: Verdict,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+    /// Override code that let a failing rule pass, logged to metrics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridden_with: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyReport {
+    pub stage: PolicyStage,
+    pub results: Vec<PolicyResult>,
+}
+
+impl PolicyReport {
+    pub fn has_failures(&self) -> bool {
+        self.results.iter().any(|r| r.verdict == Verdict::Fail)
+    }
+
+    /// Print the report to stderr; passing policies are only listed alongside problems
+    pub fn print(&self) {
+        if self.results.iter().all(|r| r.verdict == Verdict::Pass) {
+            return;
+        }
+        eprintln!("[git-ai] {} policy report:", self.stage.as_str());
+        for result in &self.results {
+            let label = match result.verdict {
+                Verdict::Pass => "pass",
+                Verdict::Warn => "warn",
+                Verdict::Fail => "FAIL",
+                Verdict::Error => "error",
+            };
+            match &result.message {
+                Some(message) => eprintln!("  {:<5} {}: {}", label, result.policy, message),
+                None => eprintln!("  {:<5} {}", label, result.policy),
+            }
+            match result.overridden_with.as_deref() {
+                Some(rules::NO_VERIFY_OVERRIDE) => eprintln!("        bypassed with --no-verify"),
+                Some(code) => eprintln!("        overridden with code {}", code),
+                None => {}
+            }
+            for violation in &result.violations {
+                let location = violation
+                    .file
+                    .as_ref()
+                    .map(|f| format!(" ({})", f))
+                    .unwrap_or_default();
+                match &violation.rule {
+                    Some(rule) => {
+                        eprintln!("        - [{}] {}{}", rule, violation.message, location)
+                    }
+                    None => eprintln!("        - {}{}", violation.message, location),
+                }
+            }
+        }
+    }
+}
+
+/// Lines added per agent in the pending changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AgentSummary {
+    pub tool: String,
+    pub model: String,
+    /// Trust tier from the `trust.*` config
+    pub tier: TrustTier,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// Pending changes to one file (pre-commit only)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FileSummary {
+    /// Staged lines added since the base commit
+    pub added_lines: u32,
+    /// Of those, lines the working log attributes to AI
+    pub ai_lines: u32,
+    /// AI checkpoints that edited the file without recording a transcript
+    pub untranscribed_ai_checkpoints: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitSummary {
+    pub sha: String,
+    pub stats: CommitStats,
+}
+
+/// Attribution summary handed to policies on stdin
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttributionSummary {
+    /// Commit the pending changes are based on (pre-commit only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_commit: Option<String>,
+    pub ai_additions: u32,
+    pub human_additions: u32,
+    pub deletions: u32,
+    pub agents: Vec<AgentSummary>,
+    /// AI additions per trust tier; lines from unknown agents count towards the
+    /// default tier
+    pub ai_additions_by_tier: BTreeMap<TrustTier, u32>,
+    /// AI additions scaled by the weight of their tier (`trust.weights`)
+    pub weighted_ai_additions: f64,
+    /// Files with AI edits
+    pub ai_files: Vec<String>,
+    /// Per-file breakdown of the pending changes (pre-commit only)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, FileSummary>,
+    /// Outgoing commits, newest first (pre-push only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<CommitSummary>,
+}
+
+impl AttributionSummary {
+    /// Summarise uncommitted work from working log checkpoints
+    pub fn from_checkpoints(base_commit: &str, checkpoints: &[Checkpoint]) -> Self {
+        let mut summary = AttributionSummary {
+            base_commit: Some(base_commit.to_string()),
+            ..Default::default()
+        };
+        let mut agents: BTreeMap<(String, String), AgentSummary> = BTreeMap::new();
+        let mut ai_files = BTreeSet::new();
+
+        for checkpoint in checkpoints {
+            let stats = &checkpoint.line_stats;
+            summary.deletions += stats.deletions;
+            if checkpoint.kind == CheckpointKind::Human {
+                summary.human_additions += stats.additions;
+                continue;
+            }
+
+            summary.ai_additions += stats.additions;
+            ai_files.extend(checkpoint.entries.iter().map(|e| e.file.clone()));
+            if checkpoint.lacks_transcript() {
+                for entry in &checkpoint.entries {
+                    summary
+                        .files
+                        .entry(entry.file.clone())
+                        .or_default()
+                        .untranscribed_ai_checkpoints += 1;
+                }
+            }
+            if let Some(agent_id) = &checkpoint.agent_id {
+                let agent = agents
+                    .entry((agent_id.tool.clone(), agent_id.model.clone()))
+                    .or_insert_with(|| AgentSummary {
+                        tool: agent_id.tool.clone(),
+                        model: agent_id.model.clone(),
+                        ..Default::default()
+                    });
+                agent.additions += stats.additions;
+                agent.deletions += stats.deletions;
+            }
+        }
+
+        summary.agents = agents.into_values().collect();
+        summary.ai_files = ai_files.into_iter().collect();
+        summary.apply_trust(Config::get().trust());
+        summary
+    }
+
+    /// Count the AI-authored lines among `added_lines` (staged lines added per
+    /// file, numbered as in the working tree) using each file's latest
+    /// checkpoint entry
+    pub fn add_file_lines(
+        &mut self,
+        checkpoints: &[Checkpoint],
+        added_lines: &HashMap<String, Vec<u32>>,
+    ) {
+        let mut latest_entries = HashMap::new();
+        for checkpoint in checkpoints {
+            for entry in &checkpoint.entries {
+                latest_entries.insert(entry.file.as_str(), entry);
+            }
+        }
+
+        let human = CheckpointKind::Human.to_str();
+        for (file, lines) in added_lines {
+            let ai_lines = latest_entries.get(file.as_str()).map_or(0, |entry| {
+                lines
+                    .iter()
+                    .filter(|line| {
+                        entry.line_attributions.iter().any(|attr| {
+                            attr.author_id != human
+                                && attr.start_line <= **line
+                                && **line <= attr.end_line
+                        })
+                    })
+                    .count() as u32
+            });
+            let file_summary = self.files.entry(file.clone()).or_default();
+            file_summary.added_lines = lines.len() as u32;
+            file_summary.ai_lines = ai_lines;
+        }
+    }
+
+    /// Summarise commits that `updates` would push to `remote`
+    pub fn for_push(
+        repo: &Repository,
+        remote: &str,
+        updates: &[PushUpdate],
+    ) -> Result<Self, GitAiError> {
+        let Some(revisions) = pushed_revision_args(repo, Some(remote), updates) else {
+            return Ok(AttributionSummary::default());
+        };
+        let mut args = repo.global_args_for_exec();
+        args.extend([
+            "rev-list".to_string(),
+            format!("--max-count={}", MAX_PUSH_COMMITS),
+        ]);
+        args.extend(revisions);
+        let output = exec_git(&args)?;
+        let stdout = String::from_utf8(output.stdout)?;
+
+        let mut summary = AttributionSummary::default();
+        let mut agents: BTreeMap<String, AgentSummary> = BTreeMap::new();
+        for sha in stdout.lines().map(str::trim).filter(|s| !s.is_empty()) {
+            let stats = stats_for_commit_stats(repo, sha, &[])?;
+            summary.ai_additions += stats.ai_additions;
+            summary.human_additions += stats.human_additions;
+            summary.deletions += stats.git_diff_deleted_lines;
+            for (tool_model, tool_stats) in &stats.tool_model_breakdown {
+                let agent = agents.entry(tool_model.clone()).or_insert_with(|| {
+                    let (tool, model) = tool_model.split_once("::").unwrap_or((tool_model, ""));
+                    AgentSummary {
+                        tool: tool.to_string(),
+                        model: model.to_string(),
+                        ..Default::default()
+                    }
+                });
+                agent.additions += tool_stats.ai_additions;
+                agent.deletions += tool_stats.total_ai_deletions;
+            }
+            summary.commits.push(CommitSummary {
+                sha: sha.to_string(),
+                stats,
+            });
+        }
+        summary.agents = agents.into_values().collect();
+        summary.apply_trust(Config::get().trust());
+        Ok(summary)
+    }
+
+    /// Assign each agent its trust tier and total the AI additions per tier
+    fn apply_trust(&mut self, trust: &TrustSettings) {
+        let mut by_tier: BTreeMap<TrustTier, u32> = BTreeMap::new();
+        let mut attributed = 0;
+        for agent in &mut self.agents {
+            agent.tier = trust.tier_for(&agent.tool, &agent.model);
+            *by_tier.entry(agent.tier).or_default() += agent.additions;
+            attributed += agent.additions;
+        }
+        let unattributed = self.ai_additions.saturating_sub(attributed);
+        if unattributed > 0 {
+            *by_tier.entry(trust.default).or_default() += unattributed;
+        }
+
+        self.weighted_ai_additions = by_tier
+            .iter()
+            .map(|(tier, additions)| *additions as f64 * trust.weight(*tier))
+            .sum();
+        self.ai_additions_by_tier = by_tier;
+    }
+}
+
+/// Run policies for `stage` and report the results. Returns false if the operation
+/// should be blocked. The summary is only built when at least one policy exists.
+/// With `no_verify`, plugins are skipped and failing rules are bypassed, not blocking.
+pub fn enforce_policies<F>(
+    repo: &Repository,
+    stage: PolicyStage,
+    no_verify: bool,
+    summarize: F,
+) -> bool
+where
+    F: FnOnce() -> Result<AttributionSummary, GitAiError>,
+{
+    let policies = if no_verify {
+        Vec::new()
+    } else {
+        plugins::discover_policy_plugins()
+    };
+    // Built-in rules only apply to commits
+    let rules = match stage {
+        PolicyStage::PreCommit => Config::get().policy().rules.as_slice(),
+        PolicyStage::PrePush => &[],
+    };
+    if policies.is_empty() && rules.is_empty() {
+        return true;
+    }
+
+    let summary = match summarize() {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("[git-ai] Skipping policy evaluation: {}", e);
+            return true;
+        }
+    };
+
+    let repo_path = repo.canonical_workdir().display().to_string();
+    let override_codes = rules::override_codes_from_env();
+    let mut results: Vec<PolicyResult> = policies
+        .iter()
+        .map(|policy| plugins::evaluate_policy_plugin(policy, stage, &repo_path, &summary))
+        .collect();
+    results.extend(
+        rules
+            .iter()
+            .map(|rule| rules::evaluate_rule(rule, &summary, &override_codes, no_verify)),
+    );
+    let report = PolicyReport { stage, results };
+    report.print();
+    rules::record_overrides(&report, &summary);
+
+    if report.has_failures() {
+        eprintln!("[git-ai] {}", rules::block_hint(rules, &report));
+        emit_local_event(
+            LocalEventKind::PolicyViolation,
+            Some(repo_path),
+            serde_json::to_value(&report).unwrap_or_default(),
+        );
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::attribution_tracker::LineAttribution;
+    use crate::authorship::working_log::{AgentId, CheckpointLineStats, WorkingLogEntry};
+
+    fn checkpoint(kind: CheckpointKind, tool: Option<&str>, additions: u32) -> Checkpoint {
+        let mut cp = Checkpoint::new(kind, String::new(), "Test User".to_string(), vec![]);
+        cp.agent_id = tool.map(|tool| AgentId {
+            tool: tool.to_string(),
+            id: "session".to_string(),
+            model: "model-a".to_string(),
+        });
+        cp.line_stats = CheckpointLineStats {
+            additions,
+            deletions: 1,
+            ..Default::default()
+        };
+        cp
+    }
+
+    #[test]
+    fn test_summary_from_checkpoints_groups_agents() {
+        let checkpoints = vec![
+            checkpoint(CheckpointKind::AiAgent, Some("claude"), 10),
+            checkpoint(CheckpointKind::Human, None, 3),
+            checkpoint(CheckpointKind::AiAgent, Some("claude"), 5),
+            checkpoint(CheckpointKind::AiTab, Some("cursor"), 2),
+        ];
+
+        let summary = AttributionSummary::from_checkpoints("abc", &checkpoints);
+
+        assert_eq!(summary.base_commit.as_deref(), Some("abc"));
+        assert_eq!(summary.ai_additions, 17);
+        assert_eq!(summary.human_additions, 3);
+        assert_eq!(summary.deletions, 4);
+        assert_eq!(summary.agents.len(), 2);
+        assert_eq!(summary.agents[0].tool, "claude");
+        assert_eq!(summary.agents[0].additions, 15);
+    }
+
+    #[test]
+    fn test_summary_weights_ai_additions_by_trust_tier() {
+        let checkpoints = vec![
+            checkpoint(CheckpointKind::AiAgent, Some("claude"), 10),
+            checkpoint(CheckpointKind::AiAgent, Some("mystery-bot"), 4),
+            checkpoint(CheckpointKind::AiAgent, None, 2),
+        ];
+        let mut summary = AttributionSummary::from_checkpoints("abc", &checkpoints);
+        summary.apply_trust(&TrustSettings {
+            trusted: vec!["claude/model-*".to_string()],
+            untrusted: vec!["mystery-*".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(summary.agents[0].tier, TrustTier::Trusted);
+        assert_eq!(summary.agents[1].tier, TrustTier::Untrusted);
+        assert_eq!(
+            summary.ai_additions_by_tier,
+            BTreeMap::from([
+                (TrustTier::Trusted, 10),
+                (TrustTier::Standard, 2),
+                (TrustTier::Untrusted, 4),
+            ])
+        );
+        assert_eq!(summary.weighted_ai_additions, 5.0 + 2.0 + 8.0);
+    }
+
+    #[test]
+    fn test_file_lines_use_latest_checkpoint_entry() {
+        let entry = |author: &str, end_line: u32| {
+            WorkingLogEntry::new(
+                "src/crypto/aes.rs".to_string(),
+                String::new(),
+                vec![],
+                vec![
+                    LineAttribution::new(1, end_line, author.to_string(), None),
+                    LineAttribution::new(end_line + 1, 10, "human".to_string(), None),
+                ],
+            )
+        };
+        let mut ai = checkpoint(CheckpointKind::AiAgent, Some("claude"), 4);
+        ai.entries = vec![entry("prompt-1", 2)];
+        let mut human = checkpoint(CheckpointKind::Human, None, 1);
+        human.entries = vec![entry("prompt-1", 4)];
+        let checkpoints = vec![ai, human];
+
+        let mut summary = AttributionSummary::from_checkpoints("abc", &checkpoints);
+        summary.add_file_lines(
+            &checkpoints,
+            &HashMap::from([("src/crypto/aes.rs".to_string(), vec![3, 4, 5, 6])]),
+        );
+
+        assert_eq!(
+            summary.files["src/crypto/aes.rs"],
+            FileSummary {
+                added_lines: 4,
+                ai_lines: 2,
+                // A claude checkpoint without transcript_path has nothing to refetch
+                untranscribed_ai_checkpoints: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_report_failures() {
+        let mut report = PolicyReport {
+            stage: PolicyStage::PreCommit,
+            results: vec![PolicyResult {
+                policy: "max-ai".to_string(),
+                verdict: Verdict::Warn,
+                message: None,
+                violations: vec![],
+                overridden_with: None,
+            }],
+        };
+        assert!(!report.has_failures());
+
+        report.results.push(PolicyResult {
+            policy: "broken".to_string(),
+            verdict: Verdict::Error,
+            message: Some("bad JSON".to_string()),
+            violations: vec![],
+            overridden_with: None,
+        });
+        assert!(!report.has_failures());
+
+        report.results[0].verdict = Verdict::Fail;
+        assert!(report.has_failures());
+    }
+}
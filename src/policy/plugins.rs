@@ -0,0 +1,195 @@
+//! External policy executables (`git-ai-policy-<name>` on PATH).
+//!
+//! Each policy is run as `git-ai-policy-<name> evaluate` with a JSON document
+//! on stdin:
+//!
+//! ```json
+//! {"version": 1, "stage": "pre-commit", "repo": "/path/to/repo", "summary": {...}}
+//! ```
+//!
+//! where `summary` is an [`AttributionSummary`]. The exit code decides the
+//! verdict: 0 passes, 1 fails, anything else is reported as an error and never
+//! blocks. A policy may also print a JSON verdict on stdout, which refines the
+//! exit code (e.g. a `warn` on exit 0) and adds details to the report:
+//!
+//! ```json
+//! {"verdict": "fail", "message": "too much AI code", "violations": [{"rule": "max-ai", "message": "...", "file": "src/lib.rs"}]}
+//! ```
+
+use super::{AttributionSummary, PolicyResult, PolicyStage, Verdict, Violation};
+use crate::plugins::{PLUGIN_API_VERSION, Plugin, PluginOutput, discover_plugins, run_plugin};
+use serde::{Deserialize, Serialize};
+
+/// Executable name prefix for policy plugins
+pub const POLICY_PLUGIN_PREFIX: &str = "git-ai-policy-";
+
+#[derive(Serialize)]
+struct PolicyInput<'a> {
+    version: u32,
+    stage: PolicyStage,
+    repo: &'a str,
+    summary: &'a AttributionSummary,
+}
+
+/// Optional JSON verdict printed by a policy
+#[derive(Debug, Deserialize)]
+struct PolicyOutput {
+    #[serde(default)]
+    verdict: Option<Verdict>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    violations: Vec<Violation>,
+}
+
+pub fn discover_policy_plugins() -> Vec<Plugin> {
+    discover_plugins(POLICY_PLUGIN_PREFIX)
+}
+
+/// Run one policy plugin and turn its exit code and output into a result
+pub fn evaluate_policy_plugin(
+    plugin: &Plugin,
+    stage: PolicyStage,
+    repo: &str,
+    summary: &AttributionSummary,
+) -> PolicyResult {
+    let input = PolicyInput {
+        version: PLUGIN_API_VERSION,
+        stage,
+        repo,
+        summary,
+    };
+    let stdin = match serde_json::to_string(&input) {
+        Ok(stdin) => stdin,
+        Err(e) => return error_result(plugin, e.to_string()),
+    };
+
+    match run_plugin(plugin, "evaluate", &stdin) {
+        Ok(output) => result_from_output(plugin, output),
+        Err(e) => error_result(plugin, e.to_string()),
+    }
+}
+
+fn result_from_output(plugin: &Plugin, output: PluginOutput) -> PolicyResult {
+    let exit_verdict = match output.exit_code {
+        Some(0) => Verdict::Pass,
+        Some(1) => Verdict::Fail,
+        Some(code) => {
+            return error_result(
+                plugin,
+                format!("exited with {}: {}", code, output.stderr.trim()),
+            );
+        }
+        None => return error_result(plugin, "terminated by signal".to_string()),
+    };
+
+    let stdout = output.stdout.trim();
+    let parsed = if stdout.is_empty() {
+        None
+    } else {
+        match serde_json::from_str::<PolicyOutput>(stdout) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => return error_result(plugin, format!("invalid JSON verdict: {}", e)),
+        }
+    };
+
+    let Some(parsed) = parsed else {
+        let stderr = output.stderr.trim();
+        return PolicyResult {
+            policy: plugin.name.clone(),
+            verdict: exit_verdict,
+            message: (!stderr.is_empty()).then(|| stderr.to_string()),
+            violations: Vec::new(),
+            overridden_with: None,
+        };
+    };
+
+    // A failing exit code can't be downgraded by the JSON verdict
+    let verdict = match (exit_verdict, parsed.verdict) {
+        (Verdict::Fail, _) => Verdict::Fail,
+        (_, Some(verdict)) => verdict,
+        (verdict, None) => verdict,
+    };
+    PolicyResult {
+        policy: plugin.name.clone(),
+        verdict,
+        message: parsed.message,
+        violations: parsed.violations,
+        overridden_with: None,
+    }
+}
+
+fn error_result(plugin: &Plugin, message: String) -> PolicyResult {
+    PolicyResult {
+        policy: plugin.name.clone(),
+        verdict: Verdict::Error,
+        message: Some(message),
+        violations: Vec::new(),
+        overridden_with: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn plugin() -> Plugin {
+        Plugin {
+            name: "max-ai".to_string(),
+            path: PathBuf::from("/bin/git-ai-policy-max-ai"),
+        }
+    }
+
+    fn output(exit_code: Option<i32>, stdout: &str) -> PluginOutput {
+        PluginOutput {
+            exit_code,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_exit_code_verdicts() {
+        assert_eq!(
+            result_from_output(&plugin(), output(Some(0), "")).verdict,
+            Verdict::Pass
+        );
+        assert_eq!(
+            result_from_output(&plugin(), output(Some(1), "")).verdict,
+            Verdict::Fail
+        );
+        assert_eq!(
+            result_from_output(&plugin(), output(Some(2), "")).verdict,
+            Verdict::Error
+        );
+        assert_eq!(
+            result_from_output(&plugin(), output(None, "")).verdict,
+            Verdict::Error
+        );
+    }
+
+    #[test]
+    fn test_json_verdict_refines_exit_code() {
+        let result = result_from_output(
+            &plugin(),
+            output(
+                Some(0),
+                r#"{"verdict":"warn","message":"close to limit","violations":[{"rule":"max-ai","message":"80% AI","file":"src/lib.rs"}]}"#,
+            ),
+        );
+        assert_eq!(result.verdict, Verdict::Warn);
+        assert_eq!(result.message.as_deref(), Some("close to limit"));
+        assert_eq!(result.violations[0].file.as_deref(), Some("src/lib.rs"));
+
+        // Exit 1 always fails, even if the JSON says otherwise
+        let result = result_from_output(&plugin(), output(Some(1), r#"{"verdict":"pass"}"#));
+        assert_eq!(result.verdict, Verdict::Fail);
+    }
+
+    #[test]
+    fn test_invalid_json_is_error() {
+        let result = result_from_output(&plugin(), output(Some(0), "not json"));
+        assert_eq!(result.verdict, Verdict::Error);
+    }
+}
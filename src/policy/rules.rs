@@ -0,0 +1,315 @@
+//! Built-in policy rules (`[[policy.rules]]` in the global config or a
+//! repository's `.git-ai.toml`), checked at commit time:
+//!
+//! ```toml
+//! [[policy.rules]]
+//! name = "crypto-review"
+//! paths = ["src/crypto/**"]
+//! max_ai_percent = 80
+//! override_code = "SEC-REVIEWED"
+//!
+//! [[policy.rules]]
+//! name = "transcripts"
+//! require_transcript = true
+//! ```
+//!
+//! A failing rule with an `override_code` passes as a warning when that code is
+//! listed in `GIT_AI_POLICY_OVERRIDE` (comma separated). `git commit --no-verify`
+//! lets every failing rule pass the same way, with the code `--no-verify`. Every
+//! override is recorded as a `policy_override` metrics event.
+
+use super::{AttributionSummary, PolicyReport, PolicyResult, Verdict, Violation};
+use crate::config::PolicyRule;
+use crate::metrics::{EventAttributes, PolicyOverrideValues};
+
+/// Override codes for failing rules, comma separated
+pub const ENV_POLICY_OVERRIDE: &str = "GIT_AI_POLICY_OVERRIDE";
+
+/// Override code recorded for rules bypassed with `--no-verify`
+pub const NO_VERIFY_OVERRIDE: &str = "--no-verify";
+
+pub fn override_codes_from_env() -> Vec<String> {
+    std::env::var(ENV_POLICY_OVERRIDE)
+        .map(|value| parse_override_codes(&value))
+        .unwrap_or_default()
+}
+
+fn parse_override_codes(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Check the pending changes against one rule. With `no_verify` a failure is
+/// bypassed whether or not the rule has an override code.
+pub fn evaluate_rule(
+    rule: &PolicyRule,
+    summary: &AttributionSummary,
+    override_codes: &[String],
+    no_verify: bool,
+) -> PolicyResult {
+    let files: Vec<_> = summary
+        .files
+        .iter()
+        .filter(|(path, _)| rule.matches_path(path))
+        .collect();
+    let mut violations = Vec::new();
+
+    if let Some(max_ai_percent) = rule.max_ai_percent {
+        let added: u32 = files.iter().map(|(_, file)| file.added_lines).sum();
+        let ai: u32 = files.iter().map(|(_, file)| file.ai_lines).sum();
+        let percent = if added == 0 {
+            0.0
+        } else {
+            ai as f64 * 100.0 / added as f64
+        };
+        if percent > max_ai_percent {
+            violations.push(Violation {
+                rule: Some("max-ai-percent".to_string()),
+                message: format!(
+                    "{:.0}% of {} added lines are AI-authored (limit {}%)",
+                    percent, added, max_ai_percent
+                ),
+                file: None,
+            });
+        }
+    }
+
+    if rule.require_transcript {
+        for (path, file) in &files {
+            if file.untranscribed_ai_checkpoints > 0 {
+                violations.push(Violation {
+                    rule: Some("require-transcript".to_string()),
+                    message: format!(
+                        "{} AI checkpoints recorded no transcript",
+                        file.untranscribed_ai_checkpoints
+                    ),
+                    file: Some(path.to_string()),
+                });
+            }
+        }
+    }
+
+    let mut result = PolicyResult {
+        policy: rule.name.clone(),
+        verdict: Verdict::Pass,
+        message: None,
+        violations,
+        overridden_with: None,
+    };
+    if result.violations.is_empty() {
+        return result;
+    }
+    match &rule.override_code {
+        _ if no_verify => {
+            result.verdict = Verdict::Warn;
+            result.overridden_with = Some(NO_VERIFY_OVERRIDE.to_string());
+        }
+        Some(code) if override_codes.contains(code) => {
+            result.verdict = Verdict::Warn;
+            result.overridden_with = Some(code.clone());
+        }
+        Some(code) => {
+            result.verdict = Verdict::Fail;
+            result.message = Some(format!("set {}={} to override", ENV_POLICY_OVERRIDE, code));
+        }
+        None => result.verdict = Verdict::Fail,
+    }
+    result
+}
+
+/// The line printed under a blocking report: the override codes of the failing
+/// rules when they all have one, and `--no-verify` otherwise
+pub fn block_hint(rules: &[PolicyRule], report: &PolicyReport) -> String {
+    let mut codes = Vec::new();
+    for result in report.results.iter().filter(|r| r.verdict == Verdict::Fail) {
+        match rules
+            .iter()
+            .find(|rule| rule.name == result.policy)
+            .and_then(|rule| rule.override_code.as_ref())
+        {
+            Some(code) if !codes.contains(code) => codes.push(code.clone()),
+            Some(_) => {}
+            None => {
+                return "Blocked by policy. Fix the violations above or re-run with --no-verify."
+                    .to_string();
+            }
+        }
+    }
+    format!(
+        "Blocked by policy. Fix the violations above or set {}={} to override.",
+        ENV_POLICY_OVERRIDE,
+        codes.join(",")
+    )
+}
+
+/// Record a metrics event for each rule that was overridden
+pub fn record_overrides(report: &PolicyReport, summary: &AttributionSummary) {
+    let mut attrs = EventAttributes::with_version(env!("CARGO_PKG_VERSION"));
+    if let Some(base_commit) = &summary.base_commit {
+        attrs = attrs.base_commit_sha(base_commit);
+    }
+
+    for result in &report.results {
+        let Some(code) = &result.overridden_with else {
+            continue;
+        };
+        let values = PolicyOverrideValues::new()
+            .rule(result.policy.clone())
+            .override_code(code.clone())
+            .stage(report.stage.as_str().to_string())
+            .message(
+                result
+                    .violations
+                    .iter()
+                    .map(|v| v.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            );
+        crate::metrics::record(values, attrs.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::FileSummary;
+
+    fn summary(files: &[(&str, u32, u32, u32)]) -> AttributionSummary {
+        AttributionSummary {
+            files: files
+                .iter()
+                .map(|(path, added_lines, ai_lines, untranscribed)| {
+                    (
+                        path.to_string(),
+                        FileSummary {
+                            added_lines: *added_lines,
+                            ai_lines: *ai_lines,
+                            untranscribed_ai_checkpoints: *untranscribed,
+                        },
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn crypto_rule() -> PolicyRule {
+        PolicyRule {
+            name: "crypto".to_string(),
+            paths: vec!["src/crypto/**".to_string()],
+            max_ai_percent: Some(80.0),
+            override_code: Some("SEC-42".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_max_ai_percent_only_counts_matching_paths() {
+        let rule = crypto_rule();
+
+        let mostly_ai = summary(&[("src/crypto/aes.rs", 10, 9, 0), ("src/lib.rs", 100, 0, 0)]);
+        let result = evaluate_rule(&rule, &mostly_ai, &[], false);
+        assert_eq!(result.verdict, Verdict::Fail);
+        assert_eq!(
+            result.violations[0].message,
+            "90% of 10 added lines are AI-authored (limit 80%)"
+        );
+        assert_eq!(
+            result.message.as_deref(),
+            Some("set GIT_AI_POLICY_OVERRIDE=SEC-42 to override")
+        );
+
+        let elsewhere = summary(&[("src/crypto/aes.rs", 10, 8, 0), ("src/lib.rs", 100, 100, 0)]);
+        assert_eq!(
+            evaluate_rule(&rule, &elsewhere, &[], false).verdict,
+            Verdict::Pass
+        );
+        assert_eq!(
+            evaluate_rule(&rule, &summary(&[]), &[], false).verdict,
+            Verdict::Pass
+        );
+    }
+
+    #[test]
+    fn test_require_transcript_reports_each_file() {
+        let rule = PolicyRule {
+            name: "transcripts".to_string(),
+            require_transcript: true,
+            ..Default::default()
+        };
+        let result = evaluate_rule(
+            &rule,
+            &summary(&[("a.rs", 1, 1, 2), ("b.rs", 1, 1, 0), ("c.rs", 1, 0, 1)]),
+            &[],
+            false,
+        );
+
+        assert_eq!(result.verdict, Verdict::Fail);
+        assert_eq!(result.message, None);
+        let files: Vec<_> = result
+            .violations
+            .iter()
+            .map(|v| v.file.as_deref().unwrap())
+            .collect();
+        assert_eq!(files, vec!["a.rs", "c.rs"]);
+    }
+
+    #[test]
+    fn test_override_code_turns_failure_into_warning() {
+        let rule = crypto_rule();
+        let mostly_ai = summary(&[("src/crypto/aes.rs", 10, 10, 0)]);
+
+        let codes = parse_override_codes("OTHER, SEC-42");
+        let result = evaluate_rule(&rule, &mostly_ai, &codes, false);
+        assert_eq!(result.verdict, Verdict::Warn);
+        assert_eq!(result.overridden_with.as_deref(), Some("SEC-42"));
+
+        let codes = parse_override_codes("OTHER");
+        assert_eq!(
+            evaluate_rule(&rule, &mostly_ai, &codes, false).verdict,
+            Verdict::Fail
+        );
+    }
+
+    #[test]
+    fn test_no_verify_bypasses_rules_without_override_code() {
+        let rule = PolicyRule {
+            name: "transcripts".to_string(),
+            require_transcript: true,
+            ..Default::default()
+        };
+        let result = evaluate_rule(&rule, &summary(&[("a.rs", 1, 1, 1)]), &[], true);
+        assert_eq!(result.verdict, Verdict::Warn);
+        assert_eq!(result.overridden_with.as_deref(), Some(NO_VERIFY_OVERRIDE));
+    }
+
+    #[test]
+    fn test_block_hint_names_override_codes() {
+        let rules = vec![
+            crypto_rule(),
+            PolicyRule {
+                name: "transcripts".to_string(),
+                require_transcript: true,
+                ..Default::default()
+            },
+        ];
+        let mostly_ai = summary(&[("src/crypto/aes.rs", 10, 10, 1)]);
+        let mut report = PolicyReport {
+            stage: crate::policy::PolicyStage::PreCommit,
+            results: vec![evaluate_rule(&rules[0], &mostly_ai, &[], false)],
+        };
+        assert_eq!(
+            block_hint(&rules, &report),
+            "Blocked by policy. Fix the violations above or set GIT_AI_POLICY_OVERRIDE=SEC-42 to override."
+        );
+
+        report
+            .results
+            .push(evaluate_rule(&rules[1], &mostly_ai, &[], false));
+        assert!(block_hint(&rules, &report).ends_with("re-run with --no-verify."));
+    }
+}
@@ -0,0 +1,429 @@
+//! Per-repository configuration.
+//!
+//! Two TOML files can override a subset of the global `~/.git-ai/config.json`
+//! for one repository, in increasing order of precedence:
+//!
+//! 1. `.git-ai.toml` at the repository root, checked in and shared by everyone
+//!    working on the repository;
+//! 2. `.git/ai/config.toml`, local to one clone and never committed.
+//!
+//! Only repository-level settings can be overridden: checkpoint behavior
+//! (`attribution_granularity`, `capture_environment`, `context_capture`),
+//! `exclude_paths`, `tracking`, `redaction`, `policy` and `notes_ref`. Machine settings (git path,
+//! API endpoints and keys, telemetry, updates) stay global, so cloning a
+//! repository can't redirect where git-ai runs or sends data. For the same
+//! reason a shared file can add redaction rules and turn redaction on, but
+//! only the local file can turn it off. Policy rules from either file are added
+//! to the global ones and can't remove them.
+
+use crate::config::{
+    ContextCaptureConfig, FileConfig, PolicyConfig, RedactionConfig, TrackingConfig,
+};
+use crate::git::cli_parser::parse_git_cli_args;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Checked-in config file at the repository root
+pub const SHARED_REPO_CONFIG_FILE: &str = ".git-ai.toml";
+
+/// Which repository config file a layer was read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepoConfigScope {
+    /// `.git-ai.toml`
+    Shared,
+    /// `.git/ai/config.toml`
+    Local,
+}
+
+/// The keys a repository config file may set
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+pub struct RepoFileConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_granularity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_environment: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_capture: Option<ContextCaptureConfig>,
+    /// Added to the global `exclude_paths`, not replacing them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_paths: Option<Vec<String>>,
+    /// Rules added to the global `tracking.exclude` / `tracking.generated`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking: Option<TrackingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionConfig>,
+    /// Rules added to the global `policy.rules`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_ref: Option<String>,
+    /// Keys that can't be set per repository, reported as warnings
+    #[serde(flatten, skip_serializing)]
+    pub unsupported: BTreeMap<String, toml::Value>,
+}
+
+/// One repository config file
+#[derive(Clone, Debug)]
+pub struct RepoConfigLayer {
+    pub scope: RepoConfigScope,
+    pub path: PathBuf,
+    pub config: RepoFileConfig,
+}
+
+impl RepoConfigLayer {
+    /// Read a layer; `Ok(None)` if the file doesn't exist
+    pub fn load(scope: RepoConfigScope, path: &Path) -> Result<Option<Self>, String> {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        let config = toml::from_str::<RepoFileConfig>(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(Some(Self {
+            scope,
+            path: path.to_path_buf(),
+            config,
+        }))
+    }
+
+    /// Problems worth telling the user about: keys that were ignored
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .config
+            .unsupported
+            .keys()
+            .map(|key| {
+                format!(
+                    "{}: '{}' can't be set per repository, ignoring it",
+                    self.path.display(),
+                    key
+                )
+            })
+            .collect();
+        if self.scope == RepoConfigScope::Shared
+            && let Some(redaction) = &self.config.redaction
+        {
+            for (key, value) in [
+                ("enabled", redaction.enabled),
+                ("entropy", redaction.entropy),
+                ("emails", redaction.emails),
+            ] {
+                if value == Some(false) {
+                    warnings.push(format!(
+                        "{}: redaction.{} = false is ignored; only .git/ai/config.toml can turn redaction off",
+                        self.path.display(),
+                        key
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Apply this layer over `cfg`
+    pub fn apply(&self, cfg: &mut FileConfig) {
+        let layer = &self.config;
+        if let Some(granularity) = &layer.attribution_granularity {
+            cfg.attribution_granularity = Some(granularity.clone());
+        }
+        if let Some(capture) = layer.capture_environment {
+            cfg.capture_environment = Some(capture);
+        }
+        if let Some(notes_ref) = &layer.notes_ref {
+            cfg.notes_ref = Some(notes_ref.clone());
+        }
+        if let Some(paths) = &layer.exclude_paths {
+            let existing = cfg.exclude_paths.get_or_insert_with(Vec::new);
+            for path in paths {
+                if !existing.contains(path) {
+                    existing.push(path.clone());
+                }
+            }
+        }
+        if let Some(tracking) = &layer.tracking {
+            let existing = cfg.tracking.get_or_insert_with(Default::default);
+            for (rules, target) in [
+                (&tracking.exclude, &mut existing.exclude),
+                (&tracking.generated, &mut existing.generated),
+            ] {
+                let Some(rules) = rules else {
+                    continue;
+                };
+                let target = target.get_or_insert_with(Vec::new);
+                for rule in rules {
+                    if !target.contains(rule) {
+                        target.push(rule.clone());
+                    }
+                }
+            }
+        }
+        if let Some(capture) = &layer.context_capture {
+            let existing = cfg.context_capture.get_or_insert_with(Default::default);
+            if capture.enabled.is_some() {
+                existing.enabled = capture.enabled;
+            }
+            if capture.context_lines.is_some() {
+                existing.context_lines = capture.context_lines;
+            }
+            if capture.max_bytes.is_some() {
+                existing.max_bytes = capture.max_bytes;
+            }
+            if let Some(exclude) = &capture.exclude {
+                existing
+                    .exclude
+                    .get_or_insert_with(Vec::new)
+                    .extend(exclude.iter().cloned());
+            }
+        }
+        if let Some(rules) = layer.policy.as_ref().and_then(|p| p.rules.as_ref()) {
+            cfg.policy
+                .get_or_insert_with(Default::default)
+                .rules
+                .get_or_insert_with(Vec::new)
+                .extend(rules.iter().cloned());
+        }
+        if let Some(redaction) = &layer.redaction {
+            let existing = cfg.redaction.get_or_insert_with(Default::default);
+            // A shared file can only make redaction stricter
+            let allowed = |value: Option<bool>| match self.scope {
+                RepoConfigScope::Shared => value.filter(|enabled| *enabled),
+                RepoConfigScope::Local => value,
+            };
+            if let Some(enabled) = allowed(redaction.enabled) {
+                existing.enabled = Some(enabled);
+            }
+            if let Some(entropy) = allowed(redaction.entropy) {
+                existing.entropy = Some(entropy);
+            }
+            if let Some(emails) = allowed(redaction.emails) {
+                existing.emails = Some(emails);
+            }
+            if let Some(rules) = &redaction.rules {
+                existing
+                    .rules
+                    .get_or_insert_with(BTreeMap::new)
+                    .extend(rules.clone());
+            }
+        }
+    }
+}
+
+/// Repository config layers for the repository git-ai was run in (honoring
+/// `-C`), lowest precedence first, along with warnings about them. Files that
+/// fail to parse are skipped with a warning.
+pub fn find_repo_config_layers_from_cwd() -> (Vec<RepoConfigLayer>, Vec<String>) {
+    let mut layers = Vec::new();
+    let mut warnings = Vec::new();
+    let Some((workdir, common_dir)) = repo_dirs_from_cwd() else {
+        return (layers, warnings);
+    };
+
+    for (scope, path) in repo_config_paths(&workdir, &common_dir) {
+        match RepoConfigLayer::load(scope, &path) {
+            Ok(Some(layer)) => {
+                warnings.extend(layer.warnings());
+                layers.push(layer);
+            }
+            Ok(None) => {}
+            Err(e) => warnings.push(e),
+        }
+    }
+    (layers, warnings)
+}
+
+/// Working directory and common git dir of the repository git-ai was run in
+/// (honoring `-C`)
+pub fn repo_dirs_from_cwd() -> Option<(PathBuf, PathBuf)> {
+    invocation_dir().and_then(|dir| find_repo_dirs(&dir))
+}
+
+/// The shared and local config paths for a repository, lowest precedence first
+pub fn repo_config_paths(workdir: &Path, common_dir: &Path) -> [(RepoConfigScope, PathBuf); 2] {
+    [
+        (
+            RepoConfigScope::Shared,
+            workdir.join(SHARED_REPO_CONFIG_FILE),
+        ),
+        (
+            RepoConfigScope::Local,
+            common_dir.join("ai").join("config.toml"),
+        ),
+    ]
+}
+
+/// The current directory, after any `-C` options on the command line
+fn invocation_dir() -> Option<PathBuf> {
+    let mut dir = std::env::current_dir().ok()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let global_args = parse_git_cli_args(&args).global_args;
+    let mut iter = global_args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-C"
+            && let Some(path) = iter.next()
+        {
+            dir = dir.join(path);
+        }
+    }
+    Some(dir)
+}
+
+/// The worktree root and the common git dir (shared by all worktrees) of the
+/// repository containing `start`, found the way git does: by walking up to the
+/// nearest `.git` directory or `gitdir:` file.
+fn find_repo_dirs(start: &Path) -> Option<(PathBuf, PathBuf)> {
+    for dir in start.ancestors() {
+        let dot_git = dir.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else if dot_git.is_file() {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            dir.join(target)
+        } else {
+            continue;
+        };
+        // Linked worktrees point at the main repository's git dir
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir,
+        };
+        return Some((dir.to_path_buf(), common_dir));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(scope: RepoConfigScope, toml: &str) -> RepoConfigLayer {
+        RepoConfigLayer {
+            scope,
+            path: PathBuf::from(".git-ai.toml"),
+            config: toml::from_str(toml).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let mut cfg = FileConfig {
+            notes_ref: Some("global".to_string()),
+            exclude_paths: Some(vec!["dist/**".to_string()]),
+            ..Default::default()
+        };
+        layer(
+            RepoConfigScope::Shared,
+            "notes_ref = \"team\"\nexclude_paths = [\"gen/**\"]\n[context_capture]\nenabled = true\ncontext_lines = 5\n",
+        )
+        .apply(&mut cfg);
+        layer(
+            RepoConfigScope::Local,
+            "notes_ref = \"mine\"\n[context_capture]\ncontext_lines = 1\n",
+        )
+        .apply(&mut cfg);
+
+        assert_eq!(cfg.notes_ref.as_deref(), Some("mine"));
+        assert_eq!(
+            cfg.exclude_paths,
+            Some(vec!["dist/**".to_string(), "gen/**".to_string()])
+        );
+        let capture = cfg.context_capture.unwrap();
+        assert_eq!(capture.enabled, Some(true));
+        assert_eq!(capture.context_lines, Some(1));
+    }
+
+    #[test]
+    fn test_tracking_rules_add_to_global_ones() {
+        let mut cfg = FileConfig {
+            tracking: Some(TrackingConfig {
+                generated: Some(vec!["*.lock".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        layer(
+            RepoConfigScope::Shared,
+            "[tracking]
+generated = [\"dist/**\", \"*.lock\"]
+exclude = [\"fixtures/**\"]
+",
+        )
+        .apply(&mut cfg);
+
+        let tracking = cfg.tracking.unwrap();
+        assert_eq!(
+            tracking.generated,
+            Some(vec!["*.lock".to_string(), "dist/**".to_string()])
+        );
+        assert_eq!(tracking.exclude, Some(vec!["fixtures/**".to_string()]));
+    }
+
+    #[test]
+    fn test_policy_rules_add_to_global_ones() {
+        let mut cfg = FileConfig {
+            policy: Some(PolicyConfig {
+                rules: Some(vec![Default::default()]),
+            }),
+            ..Default::default()
+        };
+        layer(
+            RepoConfigScope::Shared,
+            "[[policy.rules]]
+name = \"crypto\"
+paths = [\"src/crypto/**\"]
+max_ai_percent = 80
+override_code = \"SEC-42\"
+",
+        )
+        .apply(&mut cfg);
+
+        let rules = cfg.policy.unwrap().rules.unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].name.as_deref(), Some("crypto"));
+        assert_eq!(rules[1].max_ai_percent, Some(80.0));
+        assert_eq!(rules[1].override_code.as_deref(), Some("SEC-42"));
+    }
+
+    #[test]
+    fn test_shared_file_cannot_turn_redaction_off() {
+        let mut cfg = FileConfig {
+            redaction: Some(RedactionConfig {
+                enabled: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let shared = layer(
+            RepoConfigScope::Shared,
+            "[redaction]\nenabled = false\nemails = true\n[redaction.rules]\nticket = \"TICKET-[0-9]+\"\n",
+        );
+        assert_eq!(shared.warnings().len(), 1);
+        shared.apply(&mut cfg);
+        let redaction = cfg.redaction.clone().unwrap();
+        assert_eq!(redaction.enabled, Some(true));
+        assert_eq!(redaction.emails, Some(true));
+        assert!(redaction.rules.unwrap().contains_key("ticket"));
+
+        layer(RepoConfigScope::Local, "[redaction]\nenabled = false\n").apply(&mut cfg);
+        assert_eq!(cfg.redaction.unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_unsupported_keys_are_reported() {
+        let shared = layer(
+            RepoConfigScope::Shared,
+            "api_base_url = \"https://example.com\"\nnotes_ref = \"team\"\n",
+        );
+        let warnings = shared.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("api_base_url"), "{:?}", warnings);
+
+        let mut cfg = FileConfig::default();
+        shared.apply(&mut cfg);
+        assert!(cfg.api_base_url.is_none());
+        assert_eq!(cfg.notes_ref.as_deref(), Some("team"));
+    }
+}
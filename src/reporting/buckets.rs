@@ -0,0 +1,241 @@
+//! Time-zone aware date bucketing for daily/weekly rollups.
+//!
+//! Bucket boundaries are computed in the report's time zone, so a commit made at
+//! 23:30 local time lands in that local day even when it is already tomorrow in UTC.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Time zone that report buckets are aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportTimezone {
+    #[default]
+    Utc,
+    /// The machine's local time zone
+    Local,
+    /// A fixed UTC offset such as `+05:30`
+    Fixed(FixedOffset),
+    /// An IANA zone such as `America/New_York` (DST-aware)
+    Named(Tz),
+}
+
+impl FromStr for ReportTimezone {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let trimmed = input.trim();
+        match trimmed.to_lowercase().as_str() {
+            "utc" | "z" => return Ok(ReportTimezone::Utc),
+            "local" => return Ok(ReportTimezone::Local),
+            _ => {}
+        }
+
+        if trimmed.starts_with('+') || trimmed.starts_with('-') {
+            return parse_fixed_offset(trimmed)
+                .map(ReportTimezone::Fixed)
+                .ok_or_else(|| format!("invalid UTC offset: '{}'", trimmed));
+        }
+
+        trimmed
+            .parse::<Tz>()
+            .map(ReportTimezone::Named)
+            .map_err(|_| format!("unknown time zone: '{}'", trimmed))
+    }
+}
+
+impl fmt::Display for ReportTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportTimezone::Utc => write!(f, "UTC"),
+            ReportTimezone::Local => write!(f, "local"),
+            ReportTimezone::Fixed(offset) => write!(f, "{}", offset),
+            ReportTimezone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// Parse `+HH:MM`, `+HHMM` or `+HH` into a fixed offset.
+fn parse_fixed_offset(input: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match input.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Size of a rollup bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketSize {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+}
+
+impl FromStr for BucketSize {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "day" | "daily" => Ok(BucketSize::Day),
+            "week" | "weekly" => Ok(BucketSize::Week),
+            other => Err(format!("invalid bucket size: '{}'", other)),
+        }
+    }
+}
+
+impl ReportTimezone {
+    /// Calendar date of `ts` (seconds since epoch) in this time zone.
+    pub fn local_date(&self, ts: i64) -> NaiveDate {
+        let utc = DateTime::<Utc>::from_timestamp(ts, 0).unwrap_or_default();
+        match self {
+            ReportTimezone::Utc => utc.date_naive(),
+            ReportTimezone::Local => utc.with_timezone(&Local).date_naive(),
+            ReportTimezone::Fixed(offset) => utc.with_timezone(offset).date_naive(),
+            ReportTimezone::Named(tz) => utc.with_timezone(tz).date_naive(),
+        }
+    }
+}
+
+/// First local date of the bucket containing `ts`.
+pub fn bucket_start(ts: i64, size: BucketSize, tz: &ReportTimezone) -> NaiveDate {
+    let date = tz.local_date(ts);
+    match size {
+        BucketSize::Day => date,
+        BucketSize::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+    }
+}
+
+/// Group timestamped items into buckets keyed by each bucket's first local date.
+pub fn rollup<T>(
+    items: impl IntoIterator<Item = (i64, T)>,
+    size: BucketSize,
+    tz: &ReportTimezone,
+) -> BTreeMap<NaiveDate, Vec<T>> {
+    let mut buckets: BTreeMap<NaiveDate, Vec<T>> = BTreeMap::new();
+    for (ts, item) in items {
+        buckets
+            .entry(bucket_start(ts, size, tz))
+            .or_default()
+            .push(item);
+    }
+    buckets
+}
+
+/// Resolve the report time zone: an explicit `--tz` wins over `report.timezone`
+/// in config, which wins over UTC.
+pub fn resolve_report_timezone(cli_tz: Option<&str>) -> Result<ReportTimezone, String> {
+    if let Some(tz) = cli_tz {
+        return tz.parse();
+    }
+    match crate::config::Config::get().report_timezone() {
+        Some(tz) => tz.parse(),
+        None => Ok(ReportTimezone::Utc),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_timezones() {
+        assert_eq!("UTC".parse::<ReportTimezone>(), Ok(ReportTimezone::Utc));
+        assert_eq!("local".parse::<ReportTimezone>(), Ok(ReportTimezone::Local));
+        assert_eq!(
+            "+05:30".parse::<ReportTimezone>(),
+            Ok(ReportTimezone::Fixed(
+                FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+            ))
+        );
+        assert_eq!(
+            "-0800".parse::<ReportTimezone>(),
+            Ok(ReportTimezone::Fixed(
+                FixedOffset::west_opt(8 * 3600).unwrap()
+            ))
+        );
+        assert_eq!(
+            "America/New_York".parse::<ReportTimezone>(),
+            Ok(ReportTimezone::Named(chrono_tz::America::New_York))
+        );
+        assert!("Mars/Olympus".parse::<ReportTimezone>().is_err());
+        assert!("+25:00".parse::<ReportTimezone>().is_err());
+    }
+
+    #[test]
+    fn test_day_bucket_follows_local_date() {
+        // 2024-03-05 03:30 UTC is still 2024-03-04 in New York
+        let ts = Utc
+            .with_ymd_and_hms(2024, 3, 5, 3, 30, 0)
+            .unwrap()
+            .timestamp();
+        let ny: ReportTimezone = "America/New_York".parse().unwrap();
+
+        assert_eq!(
+            bucket_start(ts, BucketSize::Day, &ReportTimezone::Utc),
+            date(2024, 3, 5)
+        );
+        assert_eq!(bucket_start(ts, BucketSize::Day, &ny), date(2024, 3, 4));
+    }
+
+    #[test]
+    fn test_week_bucket_starts_on_monday() {
+        // Sunday 2024-03-10 23:00 in Tokyo is Sunday 14:00 UTC
+        let ts = Utc
+            .with_ymd_and_hms(2024, 3, 10, 14, 0, 0)
+            .unwrap()
+            .timestamp();
+        let tokyo: ReportTimezone = "Asia/Tokyo".parse().unwrap();
+        assert_eq!(bucket_start(ts, BucketSize::Week, &tokyo), date(2024, 3, 4));
+
+        // One hour later it is Monday in Tokyo but still Sunday in UTC
+        let ts = ts + 3600;
+        assert_eq!(
+            bucket_start(ts, BucketSize::Week, &tokyo),
+            date(2024, 3, 11)
+        );
+        assert_eq!(
+            bucket_start(ts, BucketSize::Week, &ReportTimezone::Utc),
+            date(2024, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_rollup_groups_by_local_bucket() {
+        let tz: ReportTimezone = "+09:00".parse().unwrap();
+        let base = Utc
+            .with_ymd_and_hms(2024, 1, 1, 14, 0, 0)
+            .unwrap()
+            .timestamp();
+        // 23:00, 00:00 (next day) and 01:00 local
+        let items = vec![(base, "a"), (base + 3600, "b"), (base + 7200, "c")];
+
+        let buckets = rollup(items, BucketSize::Day, &tz);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[&date(2024, 1, 1)], vec!["a"]);
+        assert_eq!(buckets[&date(2024, 1, 2)], vec!["b", "c"]);
+    }
+}
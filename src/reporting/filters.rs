@@ -0,0 +1,135 @@
+//! Author filters for reports.
+//!
+//! Lets AI-share numbers reflect the human team: commits by automation (renovate,
+//! dependabot, ...) can be dropped, and reports can be restricted to a known list
+//! of authors. Patterns are case-insensitive substrings of `Name <email>`.
+
+/// Authors excluded by `--exclude-bots` in addition to `report.bot_authors`.
+pub const DEFAULT_BOT_AUTHORS: &[&str] = &[
+    "[bot]",
+    "dependabot",
+    "renovate",
+    "github-actions",
+    "greenkeeper",
+    "snyk-bot",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorFilter {
+    /// Authors matching any of these are dropped
+    pub exclude: Vec<String>,
+    /// When non-empty, only authors matching one of these are kept
+    pub include: Vec<String>,
+}
+
+impl AuthorFilter {
+    /// Build the filter for a report from config and CLI flags.
+    ///
+    /// `report.bot_authors` and `report.authors` always apply unless `all_authors`
+    /// is set. `exclude_bots` adds [`DEFAULT_BOT_AUTHORS`]; `cli_authors` replaces
+    /// the configured author list.
+    pub fn for_report(exclude_bots: bool, cli_authors: &[String], all_authors: bool) -> Self {
+        if all_authors {
+            return Self::default();
+        }
+
+        let config = crate::config::Config::get();
+        let mut exclude: Vec<String> = config.report_bot_authors().to_vec();
+        if exclude_bots {
+            exclude.extend(DEFAULT_BOT_AUTHORS.iter().map(|s| s.to_string()));
+        }
+        let include = if cli_authors.is_empty() {
+            config.report_authors().to_vec()
+        } else {
+            cli_authors.to_vec()
+        };
+
+        Self { exclude, include }
+    }
+
+    /// Whether commits by `author` (formatted as `Name <email>`) count toward the report.
+    #[cfg(test)]
+    pub fn allows(&self, author: &str) -> bool {
+        self.allows_identity(author, author)
+    }
+
+    /// Whether commits by `author` count toward the report, matching patterns
+    /// against both the raw author and its canonical identity (see `identities.aliases`).
+    pub fn allows_identity(&self, author: &str, canonical: &str) -> bool {
+        let author = author.to_lowercase();
+        let canonical = canonical.to_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_lowercase();
+            author.contains(&pattern) || canonical.contains(&pattern)
+        };
+
+        if self.exclude.iter().any(matches) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bots() -> AuthorFilter {
+        AuthorFilter {
+            exclude: DEFAULT_BOT_AUTHORS.iter().map(|s| s.to_string()).collect(),
+            include: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_allows_everyone() {
+        let filter = AuthorFilter::default();
+        assert!(filter.allows("Alice <alice@example.com>"));
+        assert!(filter.allows("dependabot[bot] <support@github.com>"));
+    }
+
+    #[test]
+    fn test_default_bots_are_excluded() {
+        let filter = bots();
+        assert!(
+            !filter.allows("dependabot[bot] <49699333+dependabot[bot]@users.noreply.github.com>")
+        );
+        assert!(!filter.allows("Renovate Bot <bot@renovateapp.com>"));
+        assert!(
+            !filter
+                .allows("github-actions <41898282+github-actions[bot]@users.noreply.github.com>")
+        );
+        assert!(filter.allows("Alice <alice@example.com>"));
+    }
+
+    #[test]
+    fn test_include_list_restricts_authors() {
+        let filter = AuthorFilter {
+            exclude: Vec::new(),
+            include: vec!["@example.com".to_string(), "Bob".to_string()],
+        };
+        assert!(filter.allows("Alice <alice@example.com>"));
+        assert!(filter.allows("bob <bob@elsewhere.org>"));
+        assert!(!filter.allows("Carol <carol@elsewhere.org>"));
+    }
+
+    #[test]
+    fn test_include_matches_canonical_identity() {
+        let filter = AuthorFilter {
+            exclude: Vec::new(),
+            include: vec!["jane@corp.com".to_string()],
+        };
+        assert!(filter.allows_identity("jd <jane@home.net>", "Jane Doe <jane@corp.com>"));
+        assert!(!filter.allows_identity("jd <jane@home.net>", "jd <jane@home.net>"));
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let filter = AuthorFilter {
+            exclude: vec!["[bot]".to_string()],
+            include: vec!["@users.noreply.github.com".to_string()],
+        };
+        assert!(!filter.allows("dependabot[bot] <1+dependabot[bot]@users.noreply.github.com>"));
+        assert!(filter.allows("Alice <1+alice@users.noreply.github.com>"));
+    }
+}
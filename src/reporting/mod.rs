@@ -0,0 +1,9 @@
+//! Shared building blocks for reports.
+//!
+//! Aggregation concerns (which bucket an event falls into, which authors count)
+//! live here so that every report applies them identically, independent of how
+//! the result is rendered.
+
+pub mod buckets;
+pub mod filters;
+pub mod templates;
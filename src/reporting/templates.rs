@@ -0,0 +1,248 @@
+//! Template-driven report output.
+//!
+//! Reports render through minijinja so orgs can match their own formats. Built-in
+//! templates ship with the binary; a file with the same name in `~/.git-ai/templates`
+//! (e.g. `~/.git-ai/templates/pr-comment.md.j2`) overrides the built-in one.
+
+use crate::authorship::range_authorship::RangeAuthorshipStatsData;
+use crate::authorship::stats::CommitStats;
+use crate::config::{Config, TrustTier};
+use crate::error::GitAiError;
+use minijinja::Environment;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension for templates in the user templates directory
+const TEMPLATE_EXTENSION: &str = "md.j2";
+
+/// Templates bundled with git-ai: (name, source)
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("markdown", include_str!("templates/markdown.md.j2")),
+    ("digest", include_str!("templates/digest.md.j2")),
+    (
+        "release-notes",
+        include_str!("templates/release-notes.md.j2"),
+    ),
+    ("pr-comment", include_str!("templates/pr-comment.md.j2")),
+    (
+        "pr-annotation",
+        include_str!("templates/pr-annotation.md.j2"),
+    ),
+];
+
+/// The standalone page written by `git-ai report`. Overridable with
+/// `~/.git-ai/templates/report.html.j2`.
+const HTML_REPORT_TEMPLATE: &str = include_str!("templates/report.html.j2");
+
+/// Context passed to stats templates.
+#[derive(Debug, Serialize)]
+pub struct StatsTemplateContext<'a> {
+    /// Commit SHA or range being reported on
+    pub title: &'a str,
+    pub stats: &'a CommitStats,
+    /// Per-commit breakdown, only present for ranges
+    pub authorship: Option<&'a RangeAuthorshipStatsData>,
+    pub ai_percent: u32,
+    pub human_percent: u32,
+    /// `tool_model_breakdown` key -> trust tier of that agent (`trust.*`)
+    pub trust: BTreeMap<String, TrustTier>,
+}
+
+impl<'a> StatsTemplateContext<'a> {
+    pub fn new(
+        title: &'a str,
+        stats: &'a CommitStats,
+        authorship: Option<&'a RangeAuthorshipStatsData>,
+    ) -> Self {
+        let total = stats.human_additions + stats.ai_additions;
+        let ai_percent = if total > 0 {
+            ((stats.ai_additions as f64 / total as f64) * 100.0).round() as u32
+        } else {
+            0
+        };
+        let human_percent = if total > 0 { 100 - ai_percent } else { 0 };
+        let trust_settings = Config::get().trust();
+        let trust = stats
+            .tool_model_breakdown
+            .keys()
+            .map(|key| {
+                let (tool, model) = key.split_once("::").unwrap_or((key, ""));
+                (key.clone(), trust_settings.tier_for(tool, model))
+            })
+            .collect();
+        Self {
+            title,
+            stats,
+            authorship,
+            ai_percent,
+            human_percent,
+            trust,
+        }
+    }
+}
+
+/// Directory holding user-overridable templates (`~/.git-ai/templates`)
+pub fn templates_dir() -> Option<PathBuf> {
+    crate::config::git_ai_dir_path().map(|dir| dir.join("templates"))
+}
+
+/// Find the source for `name`.
+///
+/// Lookup order: an explicit path to a template file, then
+/// `<templates_dir>/<name>.md.j2`, then the built-in template of that name.
+fn load_template_source(name: &str, templates_dir: Option<&Path>) -> Result<String, GitAiError> {
+    let as_path = Path::new(name);
+    if as_path.is_file() {
+        return Ok(fs::read_to_string(as_path)?);
+    }
+
+    if let Some(dir) = templates_dir {
+        let user_template = dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION));
+        if user_template.is_file() {
+            return Ok(fs::read_to_string(user_template)?);
+        }
+    }
+
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, source)| source.to_string())
+        .ok_or_else(|| {
+            let names: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|(n, _)| *n).collect();
+            GitAiError::Generic(format!(
+                "Unknown template '{}'. Built-in templates: {}",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+fn render_source<S: Serialize>(source: &str, context: &S) -> Result<String, GitAiError> {
+    render_named_source("report", source, context)
+}
+
+/// Render `source` registered as `name`. The name's extension picks the
+/// auto-escaping, so `.html` templates escape values for HTML.
+fn render_named_source<S: Serialize>(
+    name: &str,
+    source: &str,
+    context: &S,
+) -> Result<String, GitAiError> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.add_template(name, source)
+        .map_err(|e| GitAiError::Generic(format!("Invalid template: {}", e)))?;
+    env.get_template(name)
+        .and_then(|template| template.render(context))
+        .map_err(|e| GitAiError::Generic(format!("Failed to render template: {}", e)))
+}
+
+/// Render the template `name` (see [`load_template_source`]) with `context`.
+pub fn render_template<S: Serialize>(name: &str, context: &S) -> Result<String, GitAiError> {
+    let source = load_template_source(name, templates_dir().as_deref())?;
+    render_source(&source, context)
+}
+
+/// Render the HTML report page with `context`, preferring
+/// `<templates_dir>/report.html.j2` over the built-in page.
+pub fn render_html_report<S: Serialize>(context: &S) -> Result<String, GitAiError> {
+    let source = match templates_dir().map(|dir| dir.join("report.html.j2")) {
+        Some(path) if path.is_file() => fs::read_to_string(path)?,
+        _ => HTML_REPORT_TEMPLATE.to_string(),
+    };
+    render_named_source("report.html", &source, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn sample_context() -> serde_json::Value {
+        json!({
+            "title": "abc1234",
+            "ai_percent": 60,
+            "human_percent": 40,
+            "stats": {
+                "human_additions": 4,
+                "ai_additions": 6,
+                "mixed_additions": 1,
+                "ai_accepted": 5,
+                "git_diff_added_lines": 10,
+                "tool_model_breakdown": {
+                    "cursor::claude-3-sonnet": { "ai_additions": 6, "ai_accepted": 5 }
+                }
+            },
+            "authorship": null,
+            "trust": { "cursor::claude-3-sonnet": "trusted" }
+        })
+    }
+
+    #[test]
+    fn test_builtin_templates_render() {
+        for (name, _) in BUILTIN_TEMPLATES {
+            let source = load_template_source(name, None).unwrap();
+            let output = render_source(&source, &sample_context()).unwrap();
+            assert!(output.contains("abc1234"), "{} output: {}", name, output);
+            assert!(output.contains("cursor::claude-3-sonnet"));
+            assert!(output.contains("trusted"), "{} output: {}", name, output);
+        }
+    }
+
+    #[test]
+    fn test_user_template_overrides_builtin() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("pr-comment.md.j2"),
+            "custom {{ ai_percent }}%",
+        )
+        .unwrap();
+
+        let source = load_template_source("pr-comment", Some(dir.path())).unwrap();
+        assert_eq!(
+            render_source(&source, &sample_context()).unwrap(),
+            "custom 60%"
+        );
+
+        // Other built-ins are still available
+        assert!(load_template_source("markdown", Some(dir.path())).is_ok());
+    }
+
+    #[test]
+    fn test_template_by_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mine.txt");
+        fs::write(&path, "{{ stats.ai_additions }} ai lines").unwrap();
+
+        let source = load_template_source(path.to_str().unwrap(), None).unwrap();
+        assert_eq!(
+            render_source(&source, &sample_context()).unwrap(),
+            "6 ai lines"
+        );
+    }
+
+    #[test]
+    fn test_stats_context_percentages() {
+        let stats = CommitStats {
+            human_additions: 1,
+            ai_additions: 2,
+            ..Default::default()
+        };
+        let context = StatsTemplateContext::new("HEAD", &stats, None);
+        assert_eq!(context.ai_percent, 67);
+        assert_eq!(context.human_percent, 33);
+
+        let empty = CommitStats::default();
+        let context = StatsTemplateContext::new("HEAD", &empty, None);
+        assert_eq!((context.ai_percent, context.human_percent), (0, 0));
+    }
+
+    #[test]
+    fn test_unknown_template_lists_builtins() {
+        let err = load_template_source("nope", None).unwrap_err();
+        assert!(err.to_string().contains("pr-comment"));
+    }
+}
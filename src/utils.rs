@@ -1,5 +1,6 @@
 use crate::error::GitAiError;
 use crate::git::diff_tree_to_tree::Diff;
+use crate::observability::log::{self, Level};
 use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
@@ -11,7 +12,7 @@ static DEBUG_ENABLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 static DEBUG_PERFORMANCE_LEVEL: std::sync::OnceLock<u8> = std::sync::OnceLock::new();
 static IS_TERMINAL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 
-fn is_debug_enabled() -> bool {
+pub(crate) fn is_debug_enabled() -> bool {
     *DEBUG_ENABLED.get_or_init(|| {
         (cfg!(debug_assertions)
             || std::env::var("GIT_AI_DEBUG").unwrap_or_default() == "1"
@@ -35,28 +36,27 @@ fn debug_performance_level() -> u8 {
 
 pub fn debug_performance_log(msg: &str) {
     if is_debug_performance_enabled() {
-        eprintln!("\x1b[1;33m[git-ai (perf)]\x1b[0m {}", msg);
+        log::write(Level::Debug, Some("perf"), msg, None);
     }
 }
 
 pub fn debug_performance_log_structured(json: serde_json::Value) {
     if debug_performance_level() >= 2 {
-        eprintln!("\x1b[1;33m[git-ai (perf-json)]\x1b[0m {}", json);
+        log::write(Level::Debug, Some("perf-json"), "", Some(json));
     }
 }
 
 /// Debug logging utility function
 ///
 /// Prints debug messages with a colored prefix when debug assertions are enabled or when
-/// the `GIT_AI_DEBUG` environment variable is set to "1".
+/// the `GIT_AI_DEBUG` environment variable is set to "1". With `GIT_AI_LOG_FORMAT=json`
+/// they are written as JSON records instead (see [`crate::observability::log`]).
 ///
 /// # Arguments
 ///
 /// * `msg` - The debug message to print
 pub fn debug_log(msg: &str) {
-    if is_debug_enabled() {
-        eprintln!("\x1b[1;33m[git-ai]\x1b[0m {}", msg);
-    }
+    log::event(Level::Debug, None, msg, None);
 }
 
 /// Print a git diff in a readable format
@@ -192,6 +192,18 @@ pub fn spawn_internal_git_ai_subcommand(
         .is_ok()
 }
 
+/// Run a git-ai subcommand in a child process and wait for it to finish.
+/// Output is discarded; callers only get the exit status.
+pub fn run_internal_git_ai_subcommand(
+    subcommand: &str,
+    extra_args: &[&str],
+) -> Result<std::process::ExitStatus, GitAiError> {
+    let exe = current_git_ai_exe()?;
+    let mut cmd = internal_git_ai_command_with_exe(exe, subcommand);
+    cmd.args(extra_args);
+    Ok(cmd.stdout(Stdio::null()).stderr(Stdio::null()).status()?)
+}
+
 pub fn is_interactive_terminal() -> bool {
     *IS_TERMINAL.get_or_init(|| std::io::stdin().is_terminal())
 }
@@ -345,10 +357,60 @@ pub fn unescape_git_path(path: &str) -> String {
     })
 }
 
+/// Run `f` over `items` on the blocking thread pool, at most `limit` at a time.
+/// Results come back in the same order as `items`, however the work interleaves.
+pub async fn map_blocking_bounded<T, R, F>(items: Vec<T>, limit: usize, f: F) -> Vec<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+{
+    let semaphore = std::sync::Arc::new(smol::lock::Semaphore::new(limit.max(1)));
+    let f = std::sync::Arc::new(f);
+    let tasks: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let semaphore = std::sync::Arc::clone(&semaphore);
+            let f = std::sync::Arc::clone(&f);
+            smol::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                smol::unblock(move || f(item)).await
+            })
+        })
+        .collect();
+    futures::future::join_all(tasks).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_map_blocking_bounded_limits_concurrency_and_keeps_order() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_in, peak_in) = (Arc::clone(&running), Arc::clone(&peak));
+
+        // Earlier items take longest, so they finish last
+        let results = smol::block_on(map_blocking_bounded((0..8u64).collect(), 3, move |i| {
+            let now = running_in.fetch_add(1, Ordering::SeqCst) + 1;
+            peak_in.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(40 - i * 5));
+            running_in.fetch_sub(1, Ordering::SeqCst);
+            i * 10
+        }));
+
+        assert_eq!(results, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(
+            peak.load(Ordering::SeqCst) > 1,
+            "items should run in parallel"
+        );
+    }
+
     // =========================================================================
     // LockFile Tests
     // =========================================================================
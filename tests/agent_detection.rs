@@ -0,0 +1,96 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+/// Claude Code exports CLAUDECODE=1 to the commands it runs
+const CLAUDE_ENV: &[(&str, &str)] = &[
+    ("GIT_AI_CHECKPOINT_INFER_AGENT", "true"),
+    ("CLAUDECODE", "1"),
+];
+
+#[test]
+fn test_bare_checkpoint_infers_agent_from_environment() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("README.md");
+    file.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(
+        repo.path().join("README.md"),
+        "# Project\nWritten by an agent\n",
+    )
+    .unwrap();
+    let output = repo.git_ai_with_env(&["checkpoint"], CLAUDE_ENV).unwrap();
+    assert!(
+        output.contains("Inferred agent claude from environment variable CLAUDECODE"),
+        "{}",
+        output
+    );
+
+    let commit = repo.stage_all_and_commit("Agent edit").unwrap();
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("inferred checkpoint should record a prompt");
+    assert_eq!(prompt.agent_id.tool, "claude");
+    assert!(prompt.agent_id.id.starts_with("inferred-"), "{:?}", prompt);
+    file.assert_lines_and_blame(lines!["# Project".human(), "Written by an agent".ai()]);
+}
+
+#[test]
+fn test_agent_commit_without_agent_hooks_is_attributed_when_inferred() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("README.md");
+    file.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // Flag off: a commit from inside an agent's shell stays human
+    fs::write(repo.path().join("README.md"), "# Project\nFirst\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    let commit = repo
+        .commit_with_env("Uninferred", &[("CLAUDECODE", "1")], None)
+        .unwrap();
+    assert!(commit.authorship_log.metadata.prompts.is_empty());
+
+    fs::write(repo.path().join("README.md"), "# Project\nFirst\nSecond\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    let commit = repo.commit_with_env("Inferred", CLAUDE_ENV, None).unwrap();
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("pre-commit should infer the agent");
+    assert_eq!(prompt.agent_id.tool, "claude");
+    file.assert_lines_and_blame(lines!["# Project".human(), "First".human(), "Second".ai(),]);
+
+    // Agent hooks that report on their own make leftovers at commit time human
+    fs::write(
+        repo.path().join("README.md"),
+        "# Project\nFirst\nSecond\nThird\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai", "README.md"])
+        .unwrap();
+    fs::write(
+        repo.path().join("README.md"),
+        "# Project\nFirst\nSecond\nThird\nFourth\n",
+    )
+    .unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env("Reported", CLAUDE_ENV, None).unwrap();
+    file.assert_lines_and_blame(lines![
+        "# Project".human(),
+        "First".human(),
+        "Second".ai(),
+        "Third".ai(),
+        "Fourth".human(),
+    ]);
+}
@@ -6,7 +6,9 @@ use git_ai::authorship::working_log::CheckpointKind;
 use git_ai::commands::checkpoint_agent::agent_presets::{
     AgentCheckpointFlags, AgentCheckpointPreset, AiTabPreset, ClaudePreset, CodexPreset,
     ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset, GithubCopilotPreset,
+    WindsurfPreset,
 };
+use git_ai::commands::checkpoint_agent::copilot_preset::CopilotPreset;
 use git_ai::error::GitAiError;
 use serde_json::json;
 use std::fs;
@@ -811,7 +813,7 @@ fn test_aitab_preset_invalid_hook_event_name() {
     match result {
         Err(GitAiError::PresetError(msg)) => {
             assert!(msg.contains("Unsupported hook_event_name"));
-            assert!(msg.contains("expected 'before_edit' or 'after_edit'"));
+            assert!(msg.contains("expected 'before_edit', 'after_edit' or 'completion_accepted'"));
         }
         _ => panic!("Expected PresetError"),
     }
@@ -962,6 +964,189 @@ fn test_aitab_preset_empty_repo_working_dir_filtered() {
     assert!(result.repo_working_dir.is_none());
 }
 
+// ==============================================================================
+// WindsurfPreset
+// ==============================================================================
+
+#[test]
+fn test_windsurf_preset_pre_write_is_human_checkpoint() {
+    let hook_input = json!({
+        "agent_action_name": "pre_write_code",
+        "trajectory_id": "traj-1",
+        "execution_id": "exec-1",
+        "tool_info": {"file_path": "/repo/src/main.rs"}
+    })
+    .to_string();
+
+    let result = WindsurfPreset
+        .run(AgentCheckpointFlags {
+            hook_input: Some(hook_input),
+        })
+        .expect("Should succeed");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+    assert_eq!(
+        result.will_edit_filepaths,
+        Some(vec!["/repo/src/main.rs".to_string()])
+    );
+    assert!(result.edited_filepaths.is_none());
+}
+
+#[test]
+fn test_windsurf_preset_post_write_is_ai_checkpoint() {
+    let hook_input = json!({
+        "agent_action_name": "post_write_code",
+        "trajectory_id": "traj-1",
+        "execution_id": "exec-2",
+        "tool_info": {
+            "file_path": "/repo/src/main.rs",
+            "edits": [{"old_string": "a", "new_string": "b"}]
+        }
+    })
+    .to_string();
+
+    let result = WindsurfPreset
+        .run(AgentCheckpointFlags {
+            hook_input: Some(hook_input),
+        })
+        .expect("Should succeed");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+    assert_eq!(result.agent_id.tool, "windsurf");
+    assert_eq!(result.agent_id.id, "traj-1");
+    assert_eq!(result.agent_id.model, "unknown");
+    assert_eq!(
+        result.edited_filepaths,
+        Some(vec!["/repo/src/main.rs".to_string()])
+    );
+}
+
+#[test]
+fn test_windsurf_preset_rejects_unsupported_event() {
+    let hook_input = json!({
+        "agent_action_name": "pre_read_code",
+        "trajectory_id": "traj-1"
+    })
+    .to_string();
+
+    match WindsurfPreset.run(AgentCheckpointFlags {
+        hook_input: Some(hook_input),
+    }) {
+        Err(GitAiError::PresetError(msg)) => {
+            assert!(msg.contains("Unsupported Windsurf hook event"));
+        }
+        _ => panic!("Expected PresetError for unsupported event"),
+    }
+}
+
+// ==============================================================================
+// CopilotPreset
+// ==============================================================================
+
+#[test]
+fn test_copilot_preset_pre_tool_use_is_human_checkpoint() {
+    let hook_input = json!({
+        "timestamp": 1771038145825u64,
+        "cwd": "/repo",
+        "toolName": "edit",
+        "toolArgs": "{\"path\":\"/repo/src/main.rs\",\"old_str\":\"a\",\"new_str\":\"b\"}"
+    })
+    .to_string();
+
+    let result = CopilotPreset
+        .run(AgentCheckpointFlags {
+            hook_input: Some(hook_input),
+        })
+        .expect("Should succeed");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+    assert_eq!(result.repo_working_dir, Some("/repo".to_string()));
+    assert_eq!(
+        result.will_edit_filepaths,
+        Some(vec!["/repo/src/main.rs".to_string()])
+    );
+    assert!(result.edited_filepaths.is_none());
+}
+
+#[test]
+fn test_copilot_preset_post_tool_use_is_ai_checkpoint() {
+    let hook_input = json!({
+        "timestamp": 1771038150631u64,
+        "cwd": "/repo",
+        "sessionId": "copilot-session-1",
+        "model": "claude-sonnet-4.5",
+        "toolName": "create",
+        "toolArgs": "{\"path\":\"/repo/src/lib.rs\",\"file_text\":\"fn lib() {}\"}",
+        "toolResult": {"resultType": "success", "textResultForLlm": "Created file"}
+    })
+    .to_string();
+
+    let result = CopilotPreset
+        .run(AgentCheckpointFlags {
+            hook_input: Some(hook_input),
+        })
+        .expect("Should succeed");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+    assert_eq!(result.agent_id.tool, "copilot");
+    assert_eq!(result.agent_id.id, "copilot-session-1");
+    assert_eq!(result.agent_id.model, "claude-sonnet-4.5");
+    assert_eq!(
+        result.edited_filepaths,
+        Some(vec!["/repo/src/lib.rs".to_string()])
+    );
+}
+
+#[test]
+fn test_copilot_preset_skips_tools_that_do_not_edit_files() {
+    let hook_input = json!({
+        "cwd": "/repo",
+        "toolName": "bash",
+        "toolArgs": "{\"command\":\"ls\"}"
+    })
+    .to_string();
+
+    match CopilotPreset.run(AgentCheckpointFlags {
+        hook_input: Some(hook_input),
+    }) {
+        Err(GitAiError::PresetError(msg)) => {
+            assert!(msg.contains("does not edit files"));
+        }
+        _ => panic!("Expected PresetError for a non-editing tool"),
+    }
+}
+
+#[test]
+fn test_copilot_session_paths_resolve_by_id_and_recency() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let session_state = temp_dir.path();
+    let fixture = fs::read_to_string(test_utils::fixture_path(
+        "copilot_session_event_stream.jsonl",
+    ))
+    .unwrap();
+    fs::write(session_state.join("older.jsonl"), &fixture).unwrap();
+    fs::create_dir_all(session_state.join("newer")).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    fs::write(session_state.join("newer").join("events.jsonl"), &fixture).unwrap();
+
+    assert_eq!(
+        CopilotPreset::session_path_for_id(session_state, "older"),
+        Some(session_state.join("older.jsonl"))
+    );
+    assert_eq!(
+        CopilotPreset::session_path_for_id(session_state, "newer"),
+        Some(session_state.join("newer").join("events.jsonl"))
+    );
+    assert_eq!(
+        CopilotPreset::session_path_for_id(session_state, "missing"),
+        None
+    );
+    assert_eq!(
+        CopilotPreset::latest_session_path(session_state),
+        Some(session_state.join("newer").join("events.jsonl"))
+    );
+}
+
 // ==============================================================================
 // Integration Tests - Cross-Preset Behavior
 // ==============================================================================
@@ -6,7 +6,7 @@ use serde_json::json;
 use std::fs;
 
 use git_ai::{
-    authorship::working_log::CheckpointKind,
+    authorship::working_log::{AiClassification, CheckpointKind},
     commands::checkpoint_agent::agent_presets::{
         AgentCheckpointFlags, AgentCheckpointPreset, AiTabPreset,
     },
@@ -271,7 +271,8 @@ fn test_ai_tab_e2e_marks_ai_lines() {
         }),
     );
 
-    repo.stage_all_and_commit("Accept AI tab completion")
+    let commit = repo
+        .stage_all_and_commit("Accept AI tab completion")
         .unwrap();
 
     let mut file = repo.filename(relative_path);
@@ -280,6 +281,16 @@ fn test_ai_tab_e2e_marks_ai_lines() {
         "// Log hello world".ai(),
         "console.log(\"hello from ai\");".ai(),
     ]);
+
+    // Inline completions are classified as assisted, not generated
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("ai tab prompt should be recorded");
+    assert_eq!(prompt.classification, Some(AiClassification::Assisted));
 }
 
 #[test]
@@ -366,3 +377,58 @@ fn test_ai_tab_e2e_handles_dirty_files_map() {
         "}".ai(),
     ]);
 }
+
+#[test]
+fn test_ai_tab_completion_accepted_records_baseline_and_completion() {
+    let repo = TestRepo::new();
+    let relative_path = "greet.py";
+    let file_path = repo.canonical_path().join(relative_path);
+
+    fs::write(&file_path, "def greet():\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // The human types a line, then accepts an inline completion
+    let content_before = "def greet():\n    name = input()\n".to_string();
+    let content_after =
+        "def greet():\n    name = input()\n    print(f\"hello {name}\")\n".to_string();
+    fs::write(&file_path, &content_after).unwrap();
+
+    let file_path_str = file_path.to_string_lossy().to_string();
+    run_ai_tab_checkpoint(
+        &repo,
+        json!({
+            "hook_event_name": "completion_accepted",
+            "tool": "github-copilot-tab",
+            "model": "default",
+            "completion_id": "c-1",
+            "file_path": file_path_str,
+            "content_before": content_before,
+            "content_after": content_after,
+        }),
+    );
+
+    repo.stage_all_and_commit("Accept completion").unwrap();
+
+    let mut file = repo.filename(relative_path);
+    file.assert_lines_and_blame(lines![
+        "def greet():".human(),
+        "    name = input()".human(),
+        "    print(f\"hello {name}\")".ai(),
+    ]);
+}
+
+#[test]
+fn test_ai_tab_completion_accepted_requires_content_after() {
+    let hook_input = json!({
+        "hook_event_name": "completion_accepted",
+        "tool": "github-copilot-tab",
+        "model": "default",
+        "file_path": "/tmp/file.rs",
+    })
+    .to_string();
+
+    let result = AiTabPreset.run(AgentCheckpointFlags {
+        hook_input: Some(hook_input),
+    });
+    assert!(matches!(result, Err(GitAiError::PresetError(_))));
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+mod repos;
+
+use git_ai::authorship::transcript::Message;
+use git_ai::commands::checkpoint_agent::agent_presets::AiderPreset;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+const CHAT_HISTORY: &str = "
+# aider chat started at 2025-03-01 09:15:02
+
+> /usr/local/bin/aider --model gpt-4o
+> Aider v0.60.0
+> Models: gpt-4o with diff edit format, weak model gpt-4o-mini
+
+#### fix the typo
+
+Done.
+
+# aider chat started at 2025-03-02 14:03:11
+
+> /usr/local/bin/aider
+> Aider v0.75.1
+> Main model: anthropic/claude-3-7-sonnet-20250219 with diff edit format, infinite output
+> Weak model: anthropic/claude-3-5-haiku-20241022
+> Git repo: .git with 3 files
+
+#### add a greeting line
+
+I'll add the greeting.
+
+#### now make it louder
+";
+
+#[test]
+fn test_parse_aider_chat_history_uses_latest_session() {
+    let (session, model, transcript) = AiderPreset::parse_chat_history(CHAT_HISTORY);
+
+    assert_eq!(session.as_deref(), Some("aider-2025-03-02T14:03:11"));
+    assert_eq!(
+        model.as_deref(),
+        Some("anthropic/claude-3-7-sonnet-20250219")
+    );
+
+    let prompts: Vec<&str> = transcript
+        .messages()
+        .iter()
+        .filter_map(|message| match message {
+            Message::User { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(prompts, vec!["add a greeting line", "now make it louder"]);
+}
+
+#[test]
+fn test_aider_lint_checkpoint_attributes_edited_files() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join(".gitignore"), ".aider*\n").unwrap();
+    let mut file = repo.filename("greeting.txt");
+    file.set_contents(lines!["hello"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join(".aider.chat.history.md"), CHAT_HISTORY).unwrap();
+    fs::write(repo.path().join("greeting.txt"), "hello\nHELLO WORLD\n").unwrap();
+
+    // What Aider runs after an edit: `<lint-cmd> <edited files>`
+    repo.git_ai(&["checkpoint", "aider", "greeting.txt"])
+        .unwrap();
+
+    let commit = repo.stage_all_and_commit("Aider edit").unwrap();
+    file.assert_lines_and_blame(lines!["hello".human(), "HELLO WORLD".ai()]);
+
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("aider prompt record");
+    assert_eq!(prompt.agent_id.tool, "aider");
+    assert_eq!(prompt.agent_id.id, "aider-2025-03-02T14:03:11");
+    assert_eq!(
+        prompt.agent_id.model,
+        "anthropic/claude-3-7-sonnet-20250219"
+    );
+}
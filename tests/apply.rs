@@ -0,0 +1,56 @@
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_apply_onboarding_manifest_is_idempotent() {
+    let repo = TestRepo::new();
+    let manifest = repo.path().join("onboarding.yaml");
+    fs::write(
+        &manifest,
+        "exclude_paths: [\"vendor/**\"]\nnotes_ref: team\nnotes:\n  remotes:\n    - name: notes-mirror\n      url: https://example.com/notes.git\nci:\n  github: true\n",
+    )
+    .unwrap();
+    let manifest = manifest.to_str().unwrap();
+
+    let output = repo.git_ai(&["apply", manifest, "--dry-run"]).unwrap();
+    assert!(output.contains("Would update"), "{}", output);
+    assert!(!repo.path().join(".git-ai.toml").exists());
+
+    let output = repo.git_ai(&["apply", manifest]).unwrap();
+    assert!(!output.contains("Unchanged"), "{}", output);
+    let config = fs::read_to_string(repo.path().join(".git-ai.toml")).unwrap();
+    assert!(config.contains("notes_ref = \"team\""), "{}", config);
+    assert!(config.contains("vendor/**"), "{}", config);
+    assert_eq!(
+        repo.git(&["remote", "get-url", "notes-mirror"])
+            .unwrap()
+            .trim(),
+        "https://example.com/notes.git"
+    );
+    assert!(
+        repo.path()
+            .join(".github")
+            .join("workflows")
+            .join("git-ai.yaml")
+            .exists()
+    );
+
+    let output = repo.git_ai(&["apply", manifest]).unwrap();
+    assert!(!output.contains("Updated"), "{}", output);
+    assert_eq!(output.matches("Unchanged").count(), 3, "{}", output);
+}
+
+#[test]
+fn test_apply_rejects_unknown_manifest_keys() {
+    let repo = TestRepo::new();
+    let manifest = repo.path().join("onboarding.yaml");
+    fs::write(&manifest, "api_key: secret\n").unwrap();
+
+    let err = repo
+        .git_ai(&["apply", manifest.to_str().unwrap()])
+        .expect_err("unknown keys are rejected");
+    assert!(err.contains("unknown key 'api_key'"), "{}", err);
+    assert!(!repo.path().join(".git-ai.toml").exists());
+}
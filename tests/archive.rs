@@ -0,0 +1,153 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn note_for(repo: &TestRepo, sha: &str) -> Option<String> {
+    repo.git_og(&["notes", "--ref=ai", "show", sha]).ok()
+}
+
+#[test]
+fn test_archive_and_unbundle_carry_notes_into_an_empty_repo() {
+    let source = TestRepo::new();
+    let mut file = source.filename("lib.rs");
+    file.set_contents(lines!["fn human() {}".human()]);
+    let first = source.stage_all_and_commit("Initial commit").unwrap();
+    file.set_contents(lines!["fn human() {}".human(), "fn generated() {}".ai()]);
+    let second = source.stage_all_and_commit("Add generated").unwrap();
+
+    let bundle = source.path().with_extension("bundle");
+    let sidecar = source.path().with_extension("json");
+    let branch = source.current_branch();
+    let output = source
+        .git_ai(&[
+            "archive",
+            &branch,
+            "--out",
+            bundle.to_str().unwrap(),
+            "--json",
+            sidecar.to_str().unwrap(),
+        ])
+        .expect("archive should succeed");
+    assert!(
+        output.contains("Archived 2 commits with 2 authorship notes"),
+        "{}",
+        output
+    );
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&sidecar).unwrap()).unwrap();
+    assert!(json["notes"][&second.commit_sha].is_string(), "{}", json);
+
+    let target = TestRepo::new();
+    let output = target
+        .git_ai(&[
+            "unbundle",
+            bundle.to_str().unwrap(),
+            "--json",
+            sidecar.to_str().unwrap(),
+        ])
+        .expect("unbundle should succeed");
+    assert!(
+        output.contains("Restored 1 refs and 2 authorship notes"),
+        "{}",
+        output
+    );
+
+    assert_eq!(
+        target.git_og(&["rev-parse", "HEAD"]).unwrap().trim(),
+        second.commit_sha
+    );
+    assert_eq!(
+        note_for(&target, &first.commit_sha),
+        note_for(&source, &first.commit_sha)
+    );
+    assert_eq!(
+        note_for(&target, &second.commit_sha),
+        note_for(&source, &second.commit_sha)
+    );
+    let mut restored = target.filename("lib.rs");
+    restored.assert_lines_and_blame(lines!["fn human() {}".human(), "fn generated() {}".ai()]);
+}
+
+#[test]
+fn test_unbundle_merges_notes_and_leaves_checked_out_branch_alone() {
+    let source = TestRepo::new();
+    let mut file = source.filename("a.txt");
+    file.set_contents(lines!["one".ai()]);
+    let first = source.stage_all_and_commit("First").unwrap();
+    source.git_og(&["branch", "feature"]).unwrap();
+    file.set_contents(lines!["one".ai(), "two".ai()]);
+    let second = source.stage_all_and_commit("Second").unwrap();
+
+    // The target already has the first commit (and its note) checked out
+    let target = TestRepo::new();
+    target
+        .git_og(&["fetch", source.path().to_str().unwrap(), "feature:feature"])
+        .unwrap();
+    target.git_og(&["checkout", "feature"]).unwrap();
+    target
+        .git_og(&[
+            "fetch",
+            source.path().to_str().unwrap(),
+            "refs/notes/ai:refs/notes/ai",
+        ])
+        .unwrap();
+    target
+        .git_og(&["notes", "--ref=ai", "remove", &first.commit_sha])
+        .unwrap();
+    target
+        .git_og(&[
+            "notes",
+            "--ref=ai",
+            "add",
+            "-m",
+            "local note",
+            &first.commit_sha,
+        ])
+        .unwrap();
+
+    let bundle = source.path().with_extension("bundle");
+    let branch = source.current_branch();
+    source
+        .git_ai(&[
+            "archive",
+            &branch,
+            "feature",
+            "--out",
+            bundle.to_str().unwrap(),
+        ])
+        .unwrap();
+
+    let output = target
+        .git_ai(&["unbundle", bundle.to_str().unwrap()])
+        .unwrap();
+    assert!(output.contains("Restored 1 refs"), "{}", output);
+    assert!(output.contains("Skipped feature"), "{}", output);
+
+    assert_eq!(
+        target.git_og(&["rev-parse", &branch]).unwrap().trim(),
+        second.commit_sha
+    );
+    assert_eq!(
+        note_for(&target, &first.commit_sha).unwrap().trim(),
+        "local note"
+    );
+    assert_eq!(
+        note_for(&target, &second.commit_sha),
+        note_for(&source, &second.commit_sha)
+    );
+}
+
+#[test]
+fn test_archive_requires_an_output_path() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let err = repo
+        .git_ai(&["archive", "HEAD"])
+        .expect_err("archive without --out should fail");
+    assert!(err.contains("--out"), "{}", err);
+}
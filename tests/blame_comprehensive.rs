@@ -570,6 +570,73 @@ fn test_blame_format_json_line_ranges() {
     );
 }
 
+#[test]
+fn test_blame_format_json_lines_records() {
+    // Output format: one JSON record per line
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines!["Line 1".human(), "Line 2".ai(), "Line 3".ai()]);
+    let commit = repo.stage_all_and_commit("Test").unwrap();
+
+    let output = repo.git_ai(&["blame", "--json-lines", "test.txt"]).unwrap();
+    let records: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each line should be a JSON record"))
+        .collect();
+
+    assert_eq!(records.len(), 3);
+    for (i, record) in records.iter().enumerate() {
+        assert_eq!(record["line"], i as u64 + 1);
+        assert_eq!(record["commit"], commit.commit_sha.as_str());
+        assert_eq!(record["author_id"], "test@example.com");
+    }
+
+    assert!(records[0]["prompt_id"].is_null());
+    assert!(records[0]["tool"].is_null());
+    assert!(records[0]["model"].is_null());
+
+    assert!(records[1]["prompt_id"].is_string());
+    assert_eq!(records[1]["prompt_id"], records[2]["prompt_id"]);
+    assert_eq!(records[1]["tool"], "mock_ai");
+    assert!(records[1]["model"].is_string());
+}
+
+#[test]
+fn test_blame_format_ai_porcelain_records() {
+    // Output format: one tab-separated record per line
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines!["Line 1".human(), "Line 2".ai(), "Line 3".human()]);
+    let commit = repo.stage_all_and_commit("Test").unwrap();
+
+    let output = repo
+        .git_ai(&["blame", "--ai-porcelain", "-L", "1,2", "test.txt"])
+        .unwrap();
+    let records: Vec<Vec<&str>> = output
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .collect();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        records[0],
+        vec![
+            "1",
+            commit.commit_sha.as_str(),
+            "test@example.com",
+            "-",
+            "-",
+            "-"
+        ]
+    );
+    assert_eq!(records[1][0], "2");
+    assert_eq!(records[1][1], commit.commit_sha);
+    assert_ne!(records[1][3], "-");
+    assert_eq!(records[1][4], "mock_ai");
+}
+
 #[test]
 fn test_blame_format_default_with_flags() {
     // Output format: Default format with various flags
@@ -632,6 +699,8 @@ fn test_blame_ai_authorship_hunk_splitting() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -652,6 +721,8 @@ fn test_blame_ai_authorship_hunk_splitting() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -712,6 +783,8 @@ fn test_blame_ai_authorship_no_splitting() {
             accepted_lines: 2,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -1124,6 +1124,8 @@ fn test_blame_ai_human_author() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -1145,6 +1147,8 @@ fn test_blame_ai_human_author() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -1185,3 +1189,82 @@ fn test_blame_ai_human_author() {
         ]
     );
 }
+
+#[test]
+fn test_blame_ignore_ai_shows_prompting_human() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["first line", "second line"]);
+    let initial_sha = repo
+        .stage_all_and_commit("Initial commit")
+        .unwrap()
+        .commit_sha;
+
+    let mut authorship_log = AuthorshipLog::new();
+    authorship_log.metadata.base_commit_sha = initial_sha.clone();
+    let prompt_hash = "abc1234567890def".to_string();
+    authorship_log.metadata.prompts.insert(
+        prompt_hash.clone(),
+        PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: "session_line1".to_string(),
+                model: "claude-3-sonnet".to_string(),
+            },
+            human_author: Some("Prompter <prompter@example.com>".to_string()),
+            messages: vec![Message::user("Add first line".to_string(), None)],
+            total_additions: 1,
+            total_deletions: 0,
+            accepted_lines: 1,
+            overriden_lines: 0,
+            messages_url: None,
+            messages_ref: None,
+            classification: None,
+        },
+    );
+    let mut file_attestation = FileAttestation::new("test.txt".to_string());
+    file_attestation.add_entry(AttestationEntry::new(
+        prompt_hash,
+        vec![LineRange::Single(1)],
+    ));
+    authorship_log.attestations.push(file_attestation);
+    let gitai_repo = GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+        .expect("Failed to find repository");
+    notes_add(
+        &gitai_repo,
+        &initial_sha,
+        &authorship_log.serialize_to_string().unwrap(),
+    )
+    .unwrap();
+
+    let output = repo.git_ai(&["blame", "--ignore-ai", "test.txt"]).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        extract_authors(&output),
+        vec!["Prompter".to_string(), "Test".to_string()],
+        "{}",
+        output
+    );
+    assert!(lines[0].contains("(Prompter  cursor "), "{}", output);
+    assert!(!lines[1].contains("cursor"), "{}", output);
+
+    let output = repo
+        .git_ai(&["blame", "--ignore-ai", "-e", "test.txt"])
+        .unwrap();
+    assert!(
+        output.contains("(Prompter <prompter@example.com> cursor "),
+        "{}",
+        output
+    );
+
+    for flag in ["--porcelain", "--line-porcelain", "--incremental", "--json"] {
+        let err = repo
+            .git_ai(&["blame", "--ignore-ai", flag, "test.txt"])
+            .unwrap_err();
+        assert!(
+            err.contains(&format!("--ignore-ai cannot be combined with {}", flag)),
+            "{}",
+            err
+        );
+    }
+}
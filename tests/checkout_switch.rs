@@ -418,3 +418,85 @@ fn test_checkout_pathspec_multiple_files() {
     file_b.assert_lines_and_blame(vec!["Original B".human()]);
     file_c.assert_lines_and_blame(vec!["Modified C by AI".ai()]);
 }
+
+/// Test that git restore removes attributions for the restored files only.
+#[test]
+fn test_restore_removes_file_attributions() {
+    let repo = TestRepo::new();
+
+    let mut original = repo.filename("original.txt");
+    original.set_contents(vec!["Original content".to_string()]);
+    let mut other = repo.filename("other.txt");
+    other.set_contents(vec!["Other content".to_string()]);
+    repo.stage_all_and_commit("initial commit")
+        .expect("initial commit should succeed");
+
+    original.set_contents(vec!["Modified by AI".ai()]);
+    other.set_contents(vec!["Other modified by AI".ai()]);
+    repo.git_ai(&["checkpoint", "mock_ai"])
+        .expect("checkpoint should succeed");
+
+    repo.git(&["restore", "--source", "HEAD", "original.txt"])
+        .expect("restore should succeed");
+
+    repo.stage_all_and_commit("commit after restore")
+        .expect("commit should succeed");
+
+    original.assert_lines_and_blame(vec!["Original content".human()]);
+    other.assert_lines_and_blame(vec!["Other modified by AI".ai()]);
+}
+
+/// Test that git restore from a subdirectory and with --source resolves pathspecs
+/// against the repository root.
+#[test]
+fn test_restore_source_from_subdirectory() {
+    let repo = TestRepo::new();
+
+    let mut nested = repo.filename("src/nested.txt");
+    nested.set_contents(vec!["Nested original".to_string()]);
+    let mut top = repo.filename("top.txt");
+    top.set_contents(vec!["Top original".to_string()]);
+    repo.stage_all_and_commit("initial commit")
+        .expect("initial commit should succeed");
+
+    nested.set_contents(vec!["Nested by AI".ai()]);
+    top.set_contents(vec!["Top by AI".ai()]);
+    repo.git_ai(&["checkpoint", "mock_ai"])
+        .expect("checkpoint should succeed");
+
+    repo.git_from_working_dir(
+        &repo.path().join("src"),
+        &["restore", "--source=HEAD", "--staged", "--worktree", "."],
+    )
+    .expect("restore should succeed");
+
+    repo.stage_all_and_commit("commit after restore")
+        .expect("commit should succeed");
+
+    nested.assert_lines_and_blame(vec!["Nested original".human()]);
+    top.assert_lines_and_blame(vec!["Top by AI".ai()]);
+}
+
+/// Test that git restore --staged leaves the working tree and its attributions alone.
+#[test]
+fn test_restore_staged_keeps_attributions() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(vec!["Original".to_string()]);
+    repo.stage_all_and_commit("initial commit")
+        .expect("initial commit should succeed");
+
+    file.set_contents(vec!["Changed by AI".ai()]);
+    repo.git_ai(&["checkpoint", "mock_ai"])
+        .expect("checkpoint should succeed");
+    repo.git(&["add", "file.txt"]).expect("add should succeed");
+
+    repo.git(&["restore", "--staged", "file.txt"])
+        .expect("restore should succeed");
+
+    repo.stage_all_and_commit("commit after unstaging")
+        .expect("commit should succeed");
+
+    file.assert_lines_and_blame(vec!["Changed by AI".ai()]);
+}
@@ -180,6 +180,8 @@ fn test_cherry_pick_preserves_prompt_only_commit_note_metadata() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -0,0 +1,133 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// A base commit, then a PR commit with AI lines in one file and human lines in another.
+/// Returns (base sha, head sha).
+fn repo_with_pr(repo: &TestRepo) -> (String, String) {
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn base() {}", "fn end() {}"]);
+    let base = repo.stage_all_and_commit("base").unwrap();
+
+    lib.insert_at(1, lines!["fn ai_one() {}".ai(), "fn ai_two() {}".ai()]);
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Notes".human()]);
+    let head = repo.stage_all_and_commit("PR change").unwrap();
+
+    (base.commit_sha, head.commit_sha)
+}
+
+#[test]
+fn test_annotate_pr_dry_run_breaks_down_files_and_tools() {
+    let repo = TestRepo::new();
+    let (base, head) = repo_with_pr(&repo);
+
+    let output = repo
+        .git_ai_with_env(
+            &[
+                "ci",
+                "annotate-pr",
+                "--base",
+                &base,
+                "--head",
+                &head,
+                "--dry-run",
+            ],
+            &[("GITHUB_EVENT_PATH", ""), ("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap();
+
+    assert!(
+        output.starts_with("<!-- git-ai:annotate-pr -->"),
+        "{output}"
+    );
+    assert!(
+        output.contains(&format!(
+            "**67%** of the 3 lines added in {}..{}",
+            &base[..7],
+            &head[..7]
+        )),
+        "{output}"
+    );
+    assert!(
+        output.contains("| `mock_ai::unknown` | standard | 2 | 2 | 0 |"),
+        "{output}"
+    );
+    assert!(
+        output.contains("| `src/lib.rs` | 2 | 2 | 100% |"),
+        "{output}"
+    );
+    assert!(output.contains("| `README.md` | 1 | 0 | 0% |"), "{output}");
+    // Files with the most AI lines come first
+    assert!(output.find("src/lib.rs").unwrap() < output.find("README.md").unwrap());
+}
+
+#[test]
+fn test_annotate_pr_reads_range_from_github_event() {
+    let repo = TestRepo::new();
+    let (base, head) = repo_with_pr(&repo);
+
+    let event_dir = tempfile::tempdir().unwrap();
+    let event_path = event_dir.path().join("event.json");
+    std::fs::write(
+        &event_path,
+        serde_json::json!({
+            "pull_request": {
+                "number": 7,
+                "base": { "sha": base },
+                "head": { "sha": head }
+            }
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = repo
+        .git_ai_with_env(
+            &["ci", "annotate-pr", "--dry-run"],
+            &[("GITHUB_EVENT_PATH", event_path.to_str().unwrap())],
+        )
+        .unwrap();
+    assert!(
+        output.contains(&format!("{}..{}", &base[..7], &head[..7])),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_annotate_pr_requires_token_to_post() {
+    let repo = TestRepo::new();
+    let (base, _head) = repo_with_pr(&repo);
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "ci",
+                "annotate-pr",
+                "--base",
+                &base,
+                "--pr",
+                "7",
+                "--repo",
+                "o/r",
+            ],
+            &[("GITHUB_EVENT_PATH", ""), ("GITHUB_TOKEN", "")],
+        )
+        .unwrap_err();
+    assert!(err.contains("GITHUB_TOKEN must be set"), "{err}");
+}
+
+#[test]
+fn test_annotate_pr_rejects_empty_range() {
+    let repo = TestRepo::new();
+    let (_base, head) = repo_with_pr(&repo);
+
+    let err = repo
+        .git_ai_with_env(
+            &["ci", "annotate-pr", "--base", &head, "--dry-run"],
+            &[("GITHUB_EVENT_PATH", ""), ("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap_err();
+    assert!(err.contains("has no commits"), "{err}");
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn setup_base(repo: &TestRepo) -> String {
+    std::fs::create_dir_all(repo.path().join("crates/core/src")).unwrap();
+    std::fs::create_dir_all(repo.path().join("docs")).unwrap();
+    std::fs::write(
+        repo.path().join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core\"\n",
+    )
+    .unwrap();
+    repo.filename("crates/core/src/lib.rs")
+        .set_contents(lines!["fn base() {}"]);
+    repo.filename("docs/guide.md")
+        .set_contents(lines!["# Guide"]);
+    repo.stage_all_and_commit("Base").unwrap().commit_sha
+}
+
+#[test]
+fn test_changed_ai_files_lists_only_files_with_ai_lines() {
+    let repo = TestRepo::new();
+    let base = setup_base(&repo);
+
+    repo.filename("crates/core/src/lib.rs")
+        .insert_at(1, lines!["fn ai() {}".ai(), "fn ai_too() {}".ai()]);
+    repo.filename("docs/guide.md")
+        .insert_at(1, lines!["Written by hand."]);
+    repo.stage_all_and_commit("Mixed change").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo
+        .git_ai(&["ci", "changed-ai-files", "--range", &range, "--no-fetch"])
+        .unwrap();
+    assert!(output.contains("crates/core/src/lib.rs"), "{output}");
+    assert!(!output.contains("docs/guide.md"), "{output}");
+    assert!(output.contains("1 of 2 changed files"), "{output}");
+
+    let output = repo
+        .git_ai(&[
+            "ci",
+            "changed-ai-files",
+            "--range",
+            &range,
+            "--no-fetch",
+            "--packages",
+        ])
+        .unwrap();
+    assert!(output.lines().any(|line| line == "crates/core"), "{output}");
+
+    let output = repo
+        .git_ai(&[
+            "ci",
+            "changed-ai-files",
+            "--range",
+            &range,
+            "--no-fetch",
+            "--json",
+        ])
+        .unwrap();
+    let report: serde_json::Value = serde_json::Deserializer::from_str(&output)
+        .into_iter::<serde_json::Value>()
+        .next()
+        .and_then(Result::ok)
+        .unwrap_or_else(|| panic!("no JSON report in: {output}"));
+    assert_eq!(report["changed_files"], 2);
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1, "{output}");
+    assert_eq!(files[0]["path"], "crates/core/src/lib.rs");
+    assert_eq!(files[0]["ai_lines"], 2);
+    assert_eq!(files[0]["package"], "crates/core");
+    assert_eq!(report["packages"], serde_json::json!(["crates/core"]));
+}
+
+#[test]
+fn test_changed_ai_files_is_empty_for_human_only_changes() {
+    let repo = TestRepo::new();
+    let base = setup_base(&repo);
+
+    repo.filename("docs/guide.md")
+        .insert_at(1, lines!["Written by hand."]);
+    repo.stage_all_and_commit("Docs").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo
+        .git_ai(&["ci", "changed-ai-files", "--range", &range, "--no-fetch"])
+        .unwrap();
+    assert!(output.contains("0 of 1 changed files"), "{output}");
+    assert!(!output.contains("guide.md\n"), "{output}");
+}
+
+#[test]
+fn test_changed_ai_files_rejects_malformed_range() {
+    let repo = TestRepo::new();
+    setup_base(&repo);
+    let err = repo
+        .git_ai(&["ci", "changed-ai-files", "--range", "HEAD", "--no-fetch"])
+        .unwrap_err();
+    assert!(err.contains("expected <base>..<head>"), "{err}");
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// A base commit, then an MR commit with AI lines. Returns (base sha, head sha).
+fn repo_with_mr(repo: &TestRepo) -> (String, String) {
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn base() {}", "fn end() {}"]);
+    let base = repo.stage_all_and_commit("base").unwrap();
+
+    lib.insert_at(1, lines!["fn ai_one() {}".ai(), "fn ai_two() {}".ai()]);
+    let head = repo.stage_all_and_commit("MR change").unwrap();
+
+    (base.commit_sha, head.commit_sha)
+}
+
+#[test]
+fn test_annotate_mr_reads_range_from_merge_request_pipeline() {
+    let repo = TestRepo::new();
+    let (base, head) = repo_with_mr(&repo);
+
+    let output = repo
+        .git_ai_with_env(
+            &["ci", "gitlab", "annotate-mr", "--dry-run"],
+            &[
+                ("CI_MERGE_REQUEST_IID", "12"),
+                ("CI_MERGE_REQUEST_DIFF_BASE_SHA", &base),
+                ("CI_COMMIT_SHA", &head),
+                ("GIT_AI_DEBUG", "0"),
+            ],
+        )
+        .unwrap();
+
+    assert!(
+        output.starts_with("<!-- git-ai:annotate-pr -->"),
+        "{output}"
+    );
+    assert!(
+        output.contains(&format!(
+            "**100%** of the 2 lines added in {}..{}",
+            &base[..7],
+            &head[..7]
+        )),
+        "{output}"
+    );
+    assert!(
+        output.contains("| `src/lib.rs` | 2 | 2 | 100% |"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_annotate_mr_requires_a_token_to_post() {
+    let repo = TestRepo::new();
+    let (base, head) = repo_with_mr(&repo);
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "ci",
+                "gitlab",
+                "annotate-mr",
+                "--base",
+                &base,
+                "--head",
+                &head,
+            ],
+            &[
+                ("CI_MERGE_REQUEST_IID", ""),
+                ("CI_PROJECT_ID", "42"),
+                ("GITLAB_TOKEN", ""),
+                ("CI_JOB_TOKEN", ""),
+                ("GIT_AI_DEBUG", "0"),
+            ],
+        )
+        .unwrap_err();
+    assert!(err.contains("--mr is required"), "{err}");
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "ci",
+                "gitlab",
+                "annotate-mr",
+                "--base",
+                &base,
+                "--head",
+                &head,
+                "--mr",
+                "!12",
+            ],
+            &[
+                ("CI_PROJECT_ID", "42"),
+                ("GITLAB_TOKEN", ""),
+                ("CI_JOB_TOKEN", ""),
+                ("GIT_AI_DEBUG", "0"),
+            ],
+        )
+        .unwrap_err();
+    assert!(
+        err.contains("GITLAB_TOKEN or CI_JOB_TOKEN must be set"),
+        "{err}"
+    );
+}
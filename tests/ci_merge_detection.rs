@@ -0,0 +1,160 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// Push an AI change on `feature`, squash it onto main without git-ai and push
+/// main. Returns the squash commit's SHA and the feature head's SHA.
+fn squash_merge_ai_feature(
+    local: &TestRepo,
+    message: &str,
+    advance_main: bool,
+) -> (String, String) {
+    let mut file = local.filename("feature.js");
+    file.set_contents(lines!["// Original code", "function original() {}"]);
+    local.stage_all_and_commit("Initial commit").unwrap();
+    local.git(&["branch", "-M", "main"]).unwrap();
+    local.git(&["push", "origin", "main"]).unwrap();
+
+    local.git(&["checkout", "-b", "feature"]).unwrap();
+    file.insert_at(
+        2,
+        lines![
+            "function aiFeature() {".ai(),
+            "  return 'ai';".ai(),
+            "}".ai()
+        ],
+    );
+    let feature = local.stage_all_and_commit("Add AI feature").unwrap();
+    local.git(&["push", "origin", "feature"]).unwrap();
+
+    local.git_og(&["checkout", "main"]).unwrap();
+    if advance_main {
+        std::fs::write(local.path().join("other.txt"), "unrelated\n").unwrap();
+        local.git_og(&["add", "-A"]).unwrap();
+        local.git_og(&["commit", "-m", "Unrelated change"]).unwrap();
+    }
+    local.git_og(&["merge", "--squash", "feature"]).unwrap();
+    local.git_og(&["commit", "-m", message]).unwrap();
+    let merge_sha = local
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+    local.git_og(&["push", "origin", "main"]).unwrap();
+    (merge_sha, feature.commit_sha)
+}
+
+/// What a merge queue does once the PR lands
+fn delete_feature_branch(local: &TestRepo) {
+    local
+        .git_og(&["push", "origin", "--delete", "feature"])
+        .unwrap();
+    local.git_og(&["branch", "-D", "feature"]).unwrap();
+}
+
+fn run_ci_merge(local: &TestRepo, merge_sha: &str) -> String {
+    local
+        .git_ai(&[
+            "ci",
+            "local",
+            "merge",
+            "--merge-commit-sha",
+            merge_sha,
+            "--base-ref",
+            "main",
+        ])
+        .unwrap()
+}
+
+fn assert_ai_authorship(local: &TestRepo, merge_sha: &str) {
+    let note = local
+        .git_og(&["notes", "--ref=ai", "show", merge_sha])
+        .unwrap();
+    assert!(note.contains("feature.js"), "{note}");
+    let mut file = local.filename("feature.js");
+    file.assert_lines_and_blame(lines![
+        "// Original code".human(),
+        "function original() {}".human(),
+        "function aiFeature() {".ai(),
+        "  return 'ai';".ai(),
+        "}".ai()
+    ]);
+}
+
+#[test]
+fn test_detects_deleted_branch_by_tree() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let (merge_sha, feature_sha) = squash_merge_ai_feature(&local, "Squash AI feature", false);
+    delete_feature_branch(&local);
+
+    let output = run_ci_merge(&local, &merge_sha);
+    assert!(
+        output.contains(&format!("Detected source head {}", feature_sha)),
+        "{output}"
+    );
+    assert!(output.contains("via identical tree"), "{output}");
+    assert_ai_authorship(&local, &merge_sha);
+}
+
+#[test]
+fn test_detects_deleted_branch_by_patch_id() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let (merge_sha, feature_sha) = squash_merge_ai_feature(&local, "Squash AI feature", true);
+    delete_feature_branch(&local);
+
+    let output = run_ci_merge(&local, &merge_sha);
+    assert!(
+        output.contains(&format!("Detected source head {}", feature_sha)),
+        "{output}"
+    );
+    assert!(output.contains("via matching patch-id"), "{output}");
+    assert_ai_authorship(&local, &merge_sha);
+}
+
+#[test]
+fn test_detects_pull_request_from_merge_message() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let (merge_sha, feature_sha) = squash_merge_ai_feature(&local, "Add AI feature (#7)", true);
+    local
+        .git_og(&["push", "origin", "feature:refs/pull/7/head"])
+        .unwrap();
+    delete_feature_branch(&local);
+
+    let output = run_ci_merge(&local, &merge_sha);
+    assert!(
+        output.contains(&format!(
+            "Detected source head {} (refs/pull/7/head) via pull request #7",
+            feature_sha
+        )),
+        "{output}"
+    );
+    assert_ai_authorship(&local, &merge_sha);
+}
+
+#[test]
+fn test_direct_push_is_not_a_merge() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let mut file = local.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    local.stage_all_and_commit("base").unwrap();
+    file.insert_at(1, lines!["more"]);
+    let commit = local.stage_all_and_commit("direct push").unwrap();
+    local.git(&["push", "origin", "HEAD:main"]).unwrap();
+
+    let output = local
+        .git_ai(&[
+            "ci",
+            "local",
+            "merge",
+            "--merge-commit-sha",
+            &commit.commit_sha,
+            "--base-ref",
+            "main",
+        ])
+        .unwrap();
+    assert!(
+        output.contains("could not detect the source branch"),
+        "{output}"
+    );
+}
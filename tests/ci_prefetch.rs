@@ -0,0 +1,40 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_ci_prefetch_fetches_queued_branches() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+
+    let mut file = local.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    local.stage_all_and_commit("base").unwrap();
+    local.git(&["push", "origin", "HEAD:main"]).unwrap();
+
+    local.git(&["checkout", "-b", "feature"]).unwrap();
+    file.insert_at(1, lines!["AI line".ai()]);
+    let feature = local.stage_all_and_commit("AI change").unwrap();
+    local.git(&["push", "origin", "feature"]).unwrap();
+
+    let output = local
+        .git_ai(&["ci", "prefetch", "--base", "main", "feature"])
+        .unwrap();
+    // The PR commit plus the base commit that last touched the same file
+    assert!(
+        output.contains("feature: warmed 2 authorship notes"),
+        "{output}"
+    );
+    assert!(output.contains("Prefetched 1 PRs"), "{output}");
+
+    let prefetched = local
+        .git(&["rev-parse", "refs/git-ai/prefetch/feature"])
+        .unwrap();
+    assert_eq!(prefetched.trim(), feature.commit_sha);
+}
+
+#[test]
+fn test_ci_prefetch_requires_prs() {
+    let repo = TestRepo::new();
+    assert!(repo.git_ai(&["ci", "prefetch"]).is_err());
+}
@@ -0,0 +1,110 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// The JSON report at the start of the (stdout + stderr) output
+fn report(output: &str) -> serde_json::Value {
+    serde_json::Deserializer::from_str(output)
+        .into_iter::<serde_json::Value>()
+        .next()
+        .and_then(Result::ok)
+        .unwrap_or_else(|| panic!("no JSON report in: {output}"))
+}
+
+fn setup_base(repo: &TestRepo) -> String {
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap().commit_sha
+}
+
+#[test]
+fn test_require_notes_passes_when_every_commit_has_a_note() {
+    let repo = TestRepo::new();
+    let base = setup_base(&repo);
+
+    let mut file = repo.filename("lib.rs");
+    file.insert_at(0, lines!["fn ai() {}".ai()]);
+    repo.stage_all_and_commit("AI change").unwrap();
+    file.insert_at(0, lines!["fn human() {}"]);
+    repo.stage_all_and_commit("Human change").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo
+        .git_ai(&["ci", "require-notes", "--range", &range, "--no-fetch"])
+        .unwrap();
+    let report = report(&output);
+    assert_eq!(report["passed"], true, "{output}");
+    assert_eq!(report["commits"], 2);
+    assert_eq!(report["required"], 2);
+    assert!(report["missing"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_require_notes_fails_on_commits_without_notes() {
+    let repo = TestRepo::new();
+    let base = setup_base(&repo);
+
+    std::fs::write(
+        repo.path().join("lib.rs"),
+        "fn base() {}\nfn untracked() {}\n",
+    )
+    .unwrap();
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "Committed without git-ai"])
+        .unwrap();
+    let unnoted = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let err = repo
+        .git_ai(&["ci", "require-notes", "--range", &range, "--no-fetch"])
+        .unwrap_err();
+    assert!(
+        err.contains("1 of 1 commits") && err.contains("Committed without git-ai"),
+        "{err}"
+    );
+    assert!(err.contains(&unnoted.trim()[..7]), "{err}");
+}
+
+#[test]
+fn test_require_notes_exempts_commits_touching_only_ignored_files() {
+    let repo = TestRepo::new();
+    let base = setup_base(&repo);
+
+    std::fs::write(repo.path().join("Cargo.lock"), "# lockfile\n").unwrap();
+    std::fs::write(repo.path().join("notes.txt"), "scratch\n").unwrap();
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "Bump lockfile"]).unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let err = repo
+        .git_ai(&["ci", "require-notes", "--range", &range, "--no-fetch"])
+        .unwrap_err();
+    assert!(err.contains("Bump lockfile"), "{err}");
+
+    let output = repo
+        .git_ai(&[
+            "ci",
+            "require-notes",
+            "--range",
+            &range,
+            "--no-fetch",
+            "--ignore",
+            "*.txt",
+        ])
+        .unwrap();
+    let report = report(&output);
+    assert_eq!(report["passed"], true, "{output}");
+    assert_eq!(report["commits"], 1);
+    assert_eq!(report["required"], 0);
+}
+
+#[test]
+fn test_require_notes_rejects_malformed_range() {
+    let repo = TestRepo::new();
+    setup_base(&repo);
+    let err = repo
+        .git_ai(&["ci", "require-notes", "--range", "HEAD", "--no-fetch"])
+        .unwrap_err();
+    assert!(err.contains("expected <base>..<head>"), "{err}");
+}
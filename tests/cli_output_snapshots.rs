@@ -0,0 +1,168 @@
+//! Golden-file snapshots of user-facing and machine-readable command output.
+//!
+//! Editors, CI integrations and scripts parse `blame --porcelain/--json/--ai-porcelain/
+//! --json-lines`, `stats --json` and `show`, so a change to one of these snapshots is a
+//! format change: review it with `cargo insta review`, and call it out in the changelog
+//! if it isn't purely additive. Commit SHAs, prompt ids, dates and version strings are
+//! normalized so the snapshots don't depend on when or where the tests run.
+
+#[macro_use]
+mod repos;
+
+use regex::Regex;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::collections::HashMap;
+
+const INITIAL_DATE: &str = "2024-01-15T10:00:00+00:00";
+const AI_DATE: &str = "2024-01-16T11:30:00+00:00";
+
+/// A human commit followed by a commit that adds an AI-written function
+fn fixture_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("src/lib.rs");
+    file.set_contents(lines!["fn one() {}", "fn two() {}", ""]);
+    commit_at(&repo, "Initial commit", INITIAL_DATE);
+
+    file.insert_at(2, lines!["fn three() {".ai(), "    one();".ai(), "}".ai()]);
+    commit_at(&repo, "Add three", AI_DATE);
+    repo
+}
+
+fn commit_at(repo: &TestRepo, message: &str, date: &str) {
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env(
+        message,
+        &[("GIT_AUTHOR_DATE", date), ("GIT_COMMITTER_DATE", date)],
+        None,
+    )
+    .unwrap();
+}
+
+/// Run git-ai without debug logging, so only the command's own output is captured
+fn run(repo: &TestRepo, args: &[&str]) -> String {
+    repo.git_ai_with_env(args, &[("GIT_AI_DEBUG", "0")])
+        .unwrap_or_else(|e| panic!("git-ai {:?} failed: {}", args, e))
+}
+
+/// Replace run-specific values with stable placeholders. Hex ids (commit SHAs and
+/// prompt hashes) are numbered by first appearance and keyed on their first seven
+/// characters, so an abbreviated SHA and its full form get the same placeholder.
+fn normalize(output: &str) -> String {
+    let ansi = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+    let version = Regex::new(r#""git_ai_version": "[^"]*""#).unwrap();
+    let agent_id = Regex::new(r"ai-thread-\d+").unwrap();
+    let iso_date =
+        Regex::new(r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?: ?[+-]\d{2}:?\d{2}|Z)?")
+            .unwrap();
+    let unix_time = Regex::new(r"\b\d{10}(?:\d{3})?\b").unwrap();
+    let hex = Regex::new(r"\b[0-9a-f]{7,64}\b").unwrap();
+
+    let output = ansi.replace_all(output, "");
+    let output = version.replace_all(&output, r#""git_ai_version": "<version>""#);
+    let output = agent_id.replace_all(&output, "ai-thread-<id>");
+    let output = iso_date.replace_all(&output, "<date>");
+    let output = unix_time.replace_all(&output, "<unix-time>");
+
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    let output = hex.replace_all(&output, |caps: &regex::Captures| {
+        let token = &caps[0];
+        if token.bytes().all(|b| b.is_ascii_digit()) {
+            return token.to_string();
+        }
+        let next = labels.len() + 1;
+        let label = *labels.entry(token[..7].to_string()).or_insert(next);
+        format!("<hex-{}>", label)
+    });
+
+    output
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_normalize_placeholders() {
+    let output = "4d6d0e1 (Test User 2024-01-16 11:30:00 +0000 1)\n\
+                  4d6d0e17021d432b35e92ef656da73a5d687b538 0a61a458b2940627 1705312800 42";
+    assert_eq!(
+        normalize(output),
+        "<hex-1> (Test User <date> 1)\n<hex-1> <hex-2> <unix-time> 42"
+    );
+}
+
+#[test]
+fn test_blame_output_snapshots() {
+    let repo = fixture_repo();
+
+    insta::assert_snapshot!(
+        "blame_default",
+        normalize(&run(&repo, &["blame", "src/lib.rs"]))
+    );
+    insta::assert_snapshot!(
+        "blame_porcelain",
+        normalize(&run(&repo, &["blame", "--porcelain", "src/lib.rs"]))
+    );
+    insta::assert_snapshot!(
+        "blame_json",
+        normalize(&run(&repo, &["blame", "--json", "src/lib.rs"]))
+    );
+    insta::assert_snapshot!(
+        "blame_ai_porcelain",
+        normalize(&run(&repo, &["blame", "--ai-porcelain", "src/lib.rs"]))
+    );
+    insta::assert_snapshot!(
+        "blame_json_lines",
+        normalize(&run(&repo, &["blame", "--json-lines", "src/lib.rs"]))
+    );
+}
+
+#[test]
+fn test_stats_output_snapshots() {
+    let repo = fixture_repo();
+
+    insta::assert_snapshot!("stats_default", normalize(&run(&repo, &["stats"])));
+    insta::assert_snapshot!("stats_json", normalize(&run(&repo, &["stats", "--json"])));
+    insta::assert_snapshot!(
+        "stats_range_breakdown",
+        normalize(&run(&repo, &["stats", "HEAD~1..HEAD", "--breakdown"]))
+    );
+}
+
+#[test]
+fn test_report_template_snapshots() {
+    let repo = fixture_repo();
+
+    insta::assert_snapshot!(
+        "report_markdown",
+        normalize(&run(&repo, &["stats", "--template", "markdown"]))
+    );
+    insta::assert_snapshot!(
+        "report_pr_comment",
+        normalize(&run(&repo, &["stats", "--template", "pr-comment"]))
+    );
+    insta::assert_snapshot!(
+        "report_digest",
+        normalize(&run(
+            &repo,
+            &["stats", "HEAD~1..HEAD", "--template", "digest"]
+        ))
+    );
+    insta::assert_snapshot!(
+        "report_release_notes",
+        normalize(&run(
+            &repo,
+            &["stats", "HEAD~1..HEAD", "--template", "release-notes"]
+        ))
+    );
+}
+
+#[test]
+fn test_show_output_snapshot() {
+    let repo = fixture_repo();
+
+    insta::assert_snapshot!("show_head", normalize(&run(&repo, &["show", "HEAD"])));
+}
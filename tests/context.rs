@@ -0,0 +1,98 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::{Value, json};
+
+fn context_json(repo: &TestRepo, file: &str, lines: &str) -> Value {
+    let output = repo
+        .git_ai_with_env(
+            &["context", "--file", file, "--lines", lines, "--json"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap_or_else(|e| panic!("git-ai context failed: {}", e));
+    serde_json::from_str(&output).unwrap_or_else(|e| panic!("{e}: {output}"))
+}
+
+#[test]
+fn test_context_returns_sessions_prompts_and_commits_for_span() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.mock_agent(&json!({
+        "agent_name": "mock-agent",
+        "model": "mock-model-1",
+        "conversation_id": "session-1",
+        "turns": [{
+            "prompt": "Add a greeting function",
+            "response": "Added greet()",
+            "edits": [
+                { "op": "write", "path": "src/lib.rs", "contents": "fn greet() {\n    println!(\"hi\");\n}\n" }
+            ]
+        }]
+    }))
+    .unwrap();
+    let commit = repo.stage_all_and_commit("Add greeting").unwrap();
+    let prompt_id = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .keys()
+        .next()
+        .unwrap()
+        .clone();
+
+    let context = context_json(&repo, "src/lib.rs", "1,2");
+    assert_eq!(context["file"], "src/lib.rs");
+    assert_eq!(context["start_line"], 1);
+    assert_eq!(context["end_line"], 2);
+
+    let sessions = context["sessions"].as_array().unwrap();
+    assert_eq!(sessions.len(), 1, "{context}");
+    assert_eq!(sessions[0]["prompt_id"], prompt_id);
+    assert_eq!(sessions[0]["tool"], "mock-agent");
+    assert_eq!(sessions[0]["session_id"], "session-1");
+    assert_eq!(sessions[0]["lines"], json!([1, 2]));
+    assert_eq!(sessions[0]["prompts"], json!(["Add a greeting function"]));
+
+    let commits = context["commits"].as_array().unwrap();
+    assert_eq!(commits.len(), 1, "{context}");
+    assert_eq!(commits[0]["sha"], commit.commit_sha);
+    assert_eq!(commits[0]["subject"], "Add greeting");
+
+    let related = context["related_commits"].as_array().unwrap();
+    assert_eq!(related.len(), 1, "{context}");
+    assert_eq!(related[0]["ai"], true);
+}
+
+#[test]
+fn test_context_for_human_lines_lists_history() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("notes.txt");
+    file.set_contents(lines!["alpha", "beta"]);
+    repo.stage_all_and_commit("Add notes").unwrap();
+    file.set_contents(lines!["alpha", "beta revised"]);
+    repo.stage_all_and_commit("Revise beta").unwrap();
+
+    let context = context_json(&repo, "notes.txt", "2");
+    assert!(context["sessions"].as_array().unwrap().is_empty());
+    let subjects: Vec<&str> = context["related_commits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["subject"].as_str().unwrap())
+        .collect();
+    assert_eq!(subjects, vec!["Revise beta", "Add notes"]);
+
+    let output = repo
+        .git_ai(&["context", "--file", "notes.txt", "--lines", "1,2"])
+        .unwrap();
+    assert!(
+        output.contains("No AI sessions wrote these lines."),
+        "{output}"
+    );
+    assert!(output.contains("Revise beta"), "{output}");
+}
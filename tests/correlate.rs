@@ -0,0 +1,88 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::{Value, json};
+use std::fs;
+
+#[test]
+fn test_correlate_attributes_sarif_findings_to_ai_and_human_lines() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("src/lib.rs");
+    file.set_contents(lines![
+        "fn human() {}".human(),
+        "fn generated() {}".ai(),
+        "fn generated_too() {}".ai(),
+        "fn other() {}".human()
+    ]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let root = repo.path().canonicalize().unwrap();
+    let result = |rule: &str, uri: &str, line: u32| {
+        json!({
+            "ruleId": rule,
+            "locations": [{"physicalLocation": {
+                "artifactLocation": {"uri": uri, "uriBaseId": "SRCROOT"},
+                "region": {"startLine": line}
+            }}]
+        })
+    };
+    let sarif = json!({
+        "version": "2.1.0",
+        "runs": [{
+            "originalUriBaseIds": {"SRCROOT": {"uri": format!("file://{}/", root.display())}},
+            "results": [
+                result("unwrap-used", "src/lib.rs", 2),
+                result("unwrap-used", "src/lib.rs", 3),
+                result("dead-code", "src/lib.rs", 1),
+                result("dead-code", "/elsewhere/lib.rs", 1)
+            ]
+        }]
+    });
+    let sarif_path = repo.path().join("results.sarif");
+    fs::write(&sarif_path, sarif.to_string()).unwrap();
+    let sarif_path = sarif_path.to_str().unwrap();
+
+    let output = repo
+        .git_ai_with_env(
+            &["correlate", "--sarif", sarif_path, "--json"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap();
+    // The skipped-results note follows on stderr
+    let report: Value = serde_json::Deserializer::from_str(&output)
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap_or_else(|e| panic!("{}: {}", e, output));
+    assert!(output.contains("Skipped 1 results"), "{}", output);
+    assert_eq!(report["files"], 1);
+    assert_eq!(report["ai_lines"], 2);
+    assert_eq!(report["human_lines"], 2);
+    assert_eq!(report["skipped_results"], 1);
+    assert_eq!(report["total"]["ai_findings"], 2);
+    assert_eq!(report["total"]["human_findings"], 1);
+    assert_eq!(report["rules"][0]["rule_id"], "unwrap-used");
+    assert_eq!(report["rules"][0]["ai_findings"], 2);
+    assert_eq!(report["rules"][0]["ai_per_kloc"], 1000.0);
+    assert_eq!(report["rules"][1]["rule_id"], "dead-code");
+    assert_eq!(report["rules"][1]["human_per_kloc"], 500.0);
+
+    let csv = repo
+        .git_ai_with_env(
+            &["correlate", "--sarif", sarif_path, "--csv"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap();
+    let rows: Vec<&str> = csv
+        .lines()
+        .filter(|row| !row.starts_with("Skipped"))
+        .collect();
+    assert_eq!(
+        rows[0],
+        "rule_id,ai_findings,human_findings,ai_per_kloc,human_per_kloc"
+    );
+    assert_eq!(rows[1], "all,2,1,1000.0000,500.0000");
+    assert_eq!(rows[2], "unwrap-used,2,0,1000.0000,0.0000");
+}
@@ -0,0 +1,125 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::os::unix::fs::PermissionsExt;
+
+fn doctor_row<'a>(output: &'a str, check: &str) -> &'a str {
+    output
+        .lines()
+        .find(|line| line.starts_with(check))
+        .unwrap_or_else(|| panic!("no {} row in:\n{}", check, output))
+}
+
+#[test]
+fn test_doctor_warns_about_refspecs_that_overwrite_notes() {
+    let repo = TestRepo::new();
+    let home = repo.path().with_extension("doctor-home");
+    std::fs::create_dir_all(&home).unwrap();
+    let home = home.to_str().unwrap();
+
+    let output = repo
+        .git_ai_with_env(&["doctor"], &[("HOME", home)])
+        .unwrap();
+    assert!(
+        doctor_row(&output, "notes sync").contains("pass"),
+        "{}",
+        output
+    );
+    assert!(
+        doctor_row(&output, "git version").contains("pass"),
+        "{}",
+        output
+    );
+
+    repo.git(&[
+        "config",
+        "--add",
+        "remote.origin.fetch",
+        "+refs/notes/*:refs/notes/*",
+    ])
+    .unwrap();
+    let output = repo
+        .git_ai_with_env(&["doctor"], &[("HOME", home)])
+        .unwrap();
+    let row = doctor_row(&output, "notes sync");
+    assert!(row.contains("warn"), "{}", output);
+    assert!(row.contains("+refs/notes/*:refs/notes/*"), "{}", output);
+}
+
+#[test]
+fn test_doctor_warns_when_pinned_git_changes() {
+    let repo = TestRepo::new();
+    let home = repo.path().with_extension("doctor-home");
+    std::fs::create_dir_all(&home).unwrap();
+    let home = home.to_str().unwrap();
+
+    let fake_git = repo.path().with_extension("fake-git");
+    let write_fake_git = |version: &str| {
+        std::fs::write(
+            &fake_git,
+            format!(
+                "#!/bin/sh\nif [ \"$1\" = --version ]; then echo 'git version {}'; else exec git \"$@\"; fi\n",
+                version
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    write_fake_git("2.99.1");
+    repo.git_ai_with_env(
+        &["git-path", "pin", fake_git.to_str().unwrap()],
+        &[("HOME", home)],
+    )
+    .unwrap();
+
+    write_fake_git("2.99.2");
+    let output = repo
+        .git_ai_with_env(&["doctor"], &[("HOME", home)])
+        .unwrap();
+    let row = doctor_row(&output, "git pin");
+    assert!(row.contains("warn"), "{}", output);
+    assert!(row.contains("2.99.1 -> 2.99.2"), "{}", output);
+}
+
+#[test]
+fn test_doctor_warns_about_files_tracked_by_nested_repositories() {
+    let repo = TestRepo::new();
+    let home = repo.path().with_extension("doctor-home");
+    std::fs::create_dir_all(&home).unwrap();
+    let home = home.to_str().unwrap();
+
+    let vendored = repo.path().join("vendor").join("lib");
+    std::fs::create_dir_all(&vendored).unwrap();
+    std::fs::write(vendored.join("lib.rs"), "fn lib() {}\n").unwrap();
+    repo.stage_all_and_commit("vendor lib").unwrap();
+
+    let output = repo
+        .git_ai_with_env(&["doctor"], &[("HOME", home)])
+        .unwrap();
+    assert!(
+        doctor_row(&output, "nested repos").contains("pass"),
+        "{}",
+        output
+    );
+
+    // The vendored copy becomes a checkout of its own
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(&vendored)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let output = repo
+        .git_ai_with_env(&["doctor"], &[("HOME", home)])
+        .unwrap();
+    let row = doctor_row(&output, "nested repos");
+    assert!(row.contains("warn"), "{}", output);
+    assert!(
+        row.contains("vendor/lib is its own repository"),
+        "{}",
+        output
+    );
+}
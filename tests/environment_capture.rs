@@ -0,0 +1,68 @@
+#[macro_use]
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use repos::test_repo::TestRepo;
+
+/// An agent_v1 checkpoint for `file` from an agent reporting `agent_version`
+fn agent_checkpoint(repo: &TestRepo, file: &str, agent_version: &str) {
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user("Add a greeting".to_string(), None));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": [file],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "conversation-1",
+        "agent_version": agent_version,
+    });
+    repo.git_ai(&[
+        "checkpoint",
+        "agent-v1",
+        "--hook-input",
+        &serde_json::to_string(&hook_input).unwrap(),
+    ])
+    .unwrap();
+}
+
+fn commit_agent_edit(repo: &TestRepo) -> repos::test_repo::NewCommit {
+    let mut file = repo.filename("greet.txt");
+    file.set_contents(lines!["hello"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    std::fs::write(repo.path().join("greet.txt"), "hello\nhello, world\n").unwrap();
+    agent_checkpoint(repo, "greet.txt", "1.4.2");
+    repo.stage_all_and_commit("Add greeting").unwrap()
+}
+
+#[test]
+fn test_environment_is_not_recorded_by_default() {
+    let repo = TestRepo::new();
+    let commit = commit_agent_edit(&repo);
+    assert!(commit.authorship_log.metadata.environment.is_none());
+}
+
+#[test]
+fn test_capture_environment_records_versions() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.capture_environment = Some(true);
+    });
+    let commit = commit_agent_edit(&repo);
+
+    let environment = commit
+        .authorship_log
+        .metadata
+        .environment
+        .expect("environment should be recorded");
+    assert_eq!(environment.os, std::env::consts::OS);
+    assert_eq!(environment.arch, std::env::consts::ARCH);
+    assert!(environment.git_version.is_some_and(|v| v.starts_with('2')));
+    assert!(!environment.git_ai_version.is_empty());
+    assert_eq!(
+        environment.agents.get("test-agent").map(String::as_str),
+        Some("1.4.2")
+    );
+}
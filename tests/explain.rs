@@ -0,0 +1,115 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::json;
+
+fn explain(repo: &TestRepo, target: &str) -> String {
+    repo.git_ai_with_env(&["explain", target], &[("GIT_AI_DEBUG", "0")])
+        .unwrap_or_else(|e| panic!("git-ai explain {} failed: {}", target, e))
+}
+
+#[test]
+fn test_explain_ai_line_names_agent_commit_and_prompt() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.mock_agent(&json!({
+        "agent_name": "mock-agent",
+        "model": "mock-model-1",
+        "conversation_id": "session-1",
+        "turns": [{
+            "prompt": "Add a greeting function",
+            "response": "Added greet()",
+            "edits": [
+                { "op": "write", "path": "src/lib.rs", "contents": "fn greet() {\n    println!(\"hi\");\n}\n" }
+            ]
+        }]
+    }))
+    .unwrap();
+    let commit = repo.stage_all_and_commit("Add greeting").unwrap();
+    let prompt_id = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .keys()
+        .next()
+        .unwrap();
+
+    let output = explain(&repo, "src/lib.rs:2");
+    assert!(output.contains("println!(\"hi\");"), "{}", output);
+    assert!(output.contains("Written by AI: mock-agent"), "{}", output);
+    assert!(output.contains("session-1"), "{}", output);
+    assert!(output.contains(&commit.commit_sha[..7]), "{}", output);
+    assert!(output.contains("\"Add greeting\""), "{}", output);
+    assert!(output.contains(prompt_id.as_str()), "{}", output);
+    assert!(
+        output.contains("The session began with: \"Add a greeting function\""),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("It hasn't been changed since that commit."),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_explain_human_line_reports_moves_and_rewrites() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("notes.txt");
+    file.set_contents(lines!["alpha", "beta"]);
+    repo.stage_all_and_commit("Add notes").unwrap();
+
+    file.set_contents(lines!["alpha", "beta revised"]);
+    repo.stage_all_and_commit("Revise beta").unwrap();
+
+    file.set_contents(lines!["header", "alpha", "beta revised"]);
+    repo.stage_all_and_commit("Add header").unwrap();
+
+    let output = explain(&repo, "notes.txt:3");
+    assert!(output.contains("beta revised"), "{}", output);
+    assert!(
+        output.contains("with no AI involvement recorded"),
+        "{}",
+        output
+    );
+    assert!(output.contains("\"Revise beta\""), "{}", output);
+    assert!(
+        output.contains("It was line 2 when it was committed"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("first written in") && output.contains("\"Add notes\""),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("rewritten in 1 later commit "),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_explain_rejects_bad_targets() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["only line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let err = repo
+        .git_ai_with_env(&["explain", "a.txt"], &[("GIT_AI_DEBUG", "0")])
+        .expect_err("missing line number should fail");
+    assert!(err.contains("<file>:<line>"), "{}", err);
+
+    let err = repo
+        .git_ai_with_env(&["explain", "a.txt:5"], &[("GIT_AI_DEBUG", "0")])
+        .expect_err("line past the end should fail");
+    assert!(err.contains("Invalid line range"), "{}", err);
+}
@@ -0,0 +1,112 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn archive_path(repo: &TestRepo) -> String {
+    repo.path()
+        .with_extension("tar.zst")
+        .to_string_lossy()
+        .to_string()
+}
+
+fn note_for(repo: &TestRepo, sha: &str) -> Option<String> {
+    repo.git_og(&["notes", "--ref=ai", "show", sha]).ok()
+}
+
+#[test]
+fn test_export_import_round_trip_into_clone_without_notes() {
+    let source = TestRepo::new();
+    let mut file = source.filename("lib.rs");
+    file.set_contents(lines!["fn human() {}".human()]);
+    let first = source.stage_all_and_commit("Initial commit").unwrap();
+    file.set_contents(lines!["fn human() {}".human(), "fn generated() {}".ai()]);
+    let second = source.stage_all_and_commit("Add generated").unwrap();
+
+    let archive = archive_path(&source);
+    let output = source
+        .git_ai(&["export", "-o", &archive])
+        .expect("export should succeed");
+    assert!(
+        output.contains("Exported 2 notes") && output.contains("from 2 commits"),
+        "{}",
+        output
+    );
+
+    // Only the commits travel; the notes come from the archive
+    let target = TestRepo::new();
+    target
+        .git_og(&["fetch", source.path().to_str().unwrap(), "HEAD"])
+        .unwrap();
+    assert!(note_for(&target, &second.commit_sha).is_none());
+
+    let output = target
+        .git_ai(&["import", &archive])
+        .expect("import should succeed");
+    assert!(output.contains("Imported 2 notes"), "{}", output);
+    assert_eq!(
+        note_for(&target, &first.commit_sha),
+        note_for(&source, &first.commit_sha)
+    );
+    assert_eq!(
+        note_for(&target, &second.commit_sha),
+        note_for(&source, &second.commit_sha)
+    );
+
+    let output = target.git_ai(&["import", &archive]).unwrap();
+    assert!(output.contains("Imported 0 notes"), "{}", output);
+    assert!(
+        output.contains("Skipped 2 notes for commits that already have one"),
+        "{}",
+        output
+    );
+
+    let output = target.git_ai(&["import", &archive, "--force"]).unwrap();
+    assert!(output.contains("Imported 2 notes"), "{}", output);
+}
+
+#[test]
+fn test_export_range_and_import_skips_unknown_commits() {
+    let source = TestRepo::new();
+    let mut file = source.filename("a.txt");
+    file.set_contents(lines!["one".ai()]);
+    let first = source.stage_all_and_commit("First").unwrap();
+    file.set_contents(lines!["one".ai(), "two".ai()]);
+    source.stage_all_and_commit("Second").unwrap();
+
+    let archive = archive_path(&source);
+    let range = format!("{}..HEAD", first.commit_sha);
+    let output = source
+        .git_ai(&["export", &range, "--output", &archive])
+        .unwrap();
+    assert!(output.contains("Exported 1 note,"), "{}", output);
+
+    let unrelated = TestRepo::new();
+    let mut other = unrelated.filename("b.txt");
+    other.set_contents(lines!["unrelated"]);
+    unrelated.stage_all_and_commit("Unrelated").unwrap();
+
+    let output = unrelated.git_ai(&["import", &archive]).unwrap();
+    assert!(output.contains("Imported 0 notes"), "{}", output);
+    assert!(
+        output.contains("Skipped 1 entry for a commit that doesn't exist in this clone"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_import_rejects_files_that_are_not_archives() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let bogus = repo.path().with_extension("bogus");
+    std::fs::write(&bogus, "not an archive").unwrap();
+    let err = repo
+        .git_ai(&["import", bogus.to_str().unwrap()])
+        .expect_err("import of a non-archive should fail");
+    assert!(err.contains("Import failed"), "{}", err);
+}
@@ -0,0 +1,89 @@
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use repos::test_repo::TestRepo;
+use std::fs;
+
+/// A repo with one AI commit whose prompt messages are still inline in its note
+fn repo_with_ai_commit() -> (TestRepo, String) {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.exclude_prompts_in_repositories = Some(vec![]);
+        patch.prompt_storage = Some("notes".to_string());
+    });
+    fs::write(repo.path().join("README.md"), "# Test Repo\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "initial commit"]).unwrap();
+
+    fs::write(repo.path().join("example.txt"), "AI line 1\n").unwrap();
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user("Write the example".to_string(), None));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": ["example.txt"],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "test-conversation-id",
+    });
+    let hook_input = serde_json::to_string(&hook_input).unwrap();
+    repo.git_ai(&["checkpoint", "agent-v1", "--hook-input", &hook_input])
+        .expect("checkpoint should succeed");
+    repo.git(&["add", "-A"]).unwrap();
+    let commit = repo.commit("Add example").unwrap();
+    (repo, commit.commit_sha)
+}
+
+fn note(repo: &TestRepo, commit: &str) -> String {
+    repo.git_og(&["notes", "--ref=ai", "show", commit]).unwrap()
+}
+
+#[test]
+fn test_frozen_notes_are_only_rewritten_with_force() {
+    let (repo, commit) = repo_with_ai_commit();
+    let original = note(&repo, &commit);
+
+    let output = repo
+        .git_ai(&["freeze", "HEAD~1..HEAD", "--reason", "v1.0 audit"])
+        .unwrap();
+    assert!(output.contains("Froze 1 commits"), "{}", output);
+
+    let err = repo.git_ai(&["intern-prompts"]).unwrap_err();
+    assert!(err.contains("frozen commit"), "{}", err);
+    assert_eq!(note(&repo, &commit), original);
+
+    let output = repo.git_ai(&["intern-prompts", "--force"]).unwrap();
+    assert!(
+        output.contains("Interned 1 prompts in 1 authorship notes"),
+        "{}",
+        output
+    );
+    assert!(note(&repo, &commit).contains("messages_ref"));
+}
+
+#[test]
+fn test_freeze_list_and_unfreeze() {
+    let (repo, commit) = repo_with_ai_commit();
+
+    // A single revision freezes it and its history, like rev-list
+    let output = repo
+        .git_ai(&["freeze", "HEAD", "--reason", "v1.0 audit"])
+        .unwrap();
+    assert!(output.contains("Froze 2 commits"), "{}", output);
+    let output = repo.git_ai(&["freeze", "HEAD~1..HEAD"]).unwrap();
+    assert!(output.contains("Froze 0 commits"), "{}", output);
+    assert!(output.contains("1 already frozen"), "{}", output);
+
+    let listing = repo.git_ai(&["freeze", "--list"]).unwrap();
+    assert!(listing.contains(&commit), "{}", listing);
+    assert!(listing.contains(": v1.0 audit"), "{}", listing);
+
+    let output = repo.git_ai(&["unfreeze", "HEAD"]).unwrap();
+    assert!(output.contains("Unfroze 2 commits"), "{}", output);
+    let listing = repo.git_ai(&["freeze", "--list"]).unwrap();
+    assert!(!listing.contains(&commit), "{}", listing);
+
+    repo.git_ai(&["intern-prompts"]).unwrap();
+    assert!(note(&repo, &commit).contains("messages_ref"));
+}
@@ -0,0 +1,159 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+const OLD_DATE: &[(&str, &str)] = &[
+    ("GIT_AUTHOR_DATE", "2015-01-01T12:00:00Z"),
+    ("GIT_COMMITTER_DATE", "2015-01-01T12:00:00Z"),
+];
+
+fn stats_json(repo: &TestRepo, commit: &str) -> Value {
+    let output = repo
+        .git_ai_with_env(&["stats", commit, "--json"], &[("GIT_AI_DEBUG", "0")])
+        .unwrap();
+    serde_json::from_str(&output).unwrap_or_else(|e| panic!("{}: {}", e, output))
+}
+
+fn note(repo: &TestRepo, commit: &str) -> String {
+    repo.git_og(&["notes", "--ref=ai", "show", commit]).unwrap()
+}
+
+#[test]
+fn test_gc_prunes_old_notes_and_keeps_their_stats() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("old.txt");
+    file.set_contents(lines!["human line", "ai line 1".ai(), "ai line 2".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    let old = repo.commit_with_env("Old work", OLD_DATE, None).unwrap();
+
+    let mut recent = repo.filename("recent.txt");
+    recent.set_contents(lines!["recent ai".ai()]);
+    let recent = repo.stage_all_and_commit("Recent work").unwrap();
+
+    let old_stats = stats_json(&repo, &old.commit_sha);
+    assert_eq!(old_stats["ai_accepted"], 2, "{}", old_stats);
+    let recent_note = note(&repo, &recent.commit_sha);
+    assert!(note(&repo, &old.commit_sha).contains("old.txt"));
+
+    let output = repo
+        .git_ai(&["gc", "--prune-notes-older-than", "2y", "--dry-run"])
+        .unwrap();
+    assert!(
+        output.contains("Would prune 1 authorship notes"),
+        "{}",
+        output
+    );
+    assert!(note(&repo, &old.commit_sha).contains("old.txt"));
+
+    let output = repo
+        .git_ai(&["gc", "--prune-notes-older-than", "2y"])
+        .unwrap();
+    assert!(output.contains("Pruned 1 authorship notes"), "{}", output);
+
+    let pruned_note = note(&repo, &old.commit_sha);
+    assert!(!pruned_note.contains("old.txt"), "{}", pruned_note);
+    assert!(pruned_note.contains("\"pruned\""), "{}", pruned_note);
+    assert_eq!(stats_json(&repo, &old.commit_sha), old_stats);
+    assert_eq!(note(&repo, &recent.commit_sha), recent_note);
+    // The replaced notes are no longer reachable from the notes ref
+    let history = repo
+        .git_og(&["rev-list", "--count", "refs/notes/ai"])
+        .unwrap();
+    assert_eq!(history.trim(), "1");
+
+    // Pruning again finds nothing left to prune
+    let output = repo
+        .git_ai(&["gc", "--prune-notes-older-than", "2y"])
+        .unwrap();
+    assert!(output.contains("Pruned 0 authorship notes"), "{}", output);
+}
+
+#[test]
+fn test_gc_skips_frozen_commits() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("old.txt");
+    file.set_contents(lines!["ai line".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    let old = repo.commit_with_env("Old work", OLD_DATE, None).unwrap();
+    repo.git_ai(&["freeze", &old.commit_sha]).unwrap();
+
+    let output = repo
+        .git_ai(&["gc", "--prune-notes-older-than", "2y"])
+        .unwrap();
+    assert!(output.contains("Pruned 0 authorship notes"), "{}", output);
+    assert!(
+        output.contains("1 frozen commits left untouched"),
+        "{}",
+        output
+    );
+    assert!(note(&repo, &old.commit_sha).contains("old.txt"));
+}
+
+#[test]
+fn test_gc_leaves_tag_notes_alone() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("old.txt");
+    file.set_contents(lines!["ai line".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env("Old work", OLD_DATE, None).unwrap();
+    repo.git_og(&["tag", "-a", "v1", "-m", "v1"]).unwrap();
+    let tag = repo.git_og(&["rev-parse", "v1"]).unwrap();
+    let tag_note = r#"{"schema_version":"tag-attribution/1.0.0"}"#;
+    repo.git_og(&["notes", "--ref=ai", "add", "-m", tag_note, tag.trim()])
+        .unwrap();
+
+    let output = repo
+        .git_ai(&["gc", "--prune-notes-older-than", "2y"])
+        .unwrap();
+    assert!(output.contains("Pruned 1 authorship notes"), "{}", output);
+    assert!(output.contains("1 tag notes left untouched"), "{}", output);
+    assert_eq!(note(&repo, tag.trim()).trim(), tag_note);
+}
+
+#[test]
+fn test_gc_squash_survives_push_and_fetch() {
+    let (local, upstream) = TestRepo::new_with_remote();
+
+    let mut file = local.filename("old.txt");
+    file.set_contents(lines!["human line", "ai line".ai()]);
+    local.git(&["add", "-A"]).unwrap();
+    let old = local.commit_with_env("Old work", OLD_DATE, None).unwrap();
+    local.git(&["push", "-u", "origin", "HEAD"]).unwrap();
+    let old_blob = local
+        .git_og(&["notes", "--ref=ai", "list", &old.commit_sha])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let output = local
+        .git_ai(&["gc", "--prune-notes-older-than", "2y"])
+        .unwrap();
+    assert!(output.contains("Pruned 1 authorship notes"), "{}", output);
+
+    let mut recent = local.filename("recent.txt");
+    recent.set_contents(lines!["recent ai".ai()]);
+    let recent = local.stage_all_and_commit("Recent work").unwrap();
+    local.git(&["push"]).unwrap();
+    local.git(&["fetch", "origin"]).unwrap();
+
+    // Neither side merged the squashed history back in
+    for repo in [&local, &upstream] {
+        let objects = repo.git_og(&["rev-list", "--objects", "--all"]).unwrap();
+        assert!(!objects.contains(&old_blob), "{}", objects);
+        let pruned_note = repo
+            .git_og(&["notes", "--ref=ai", "show", &old.commit_sha])
+            .unwrap();
+        assert!(pruned_note.contains("\"pruned\""), "{}", pruned_note);
+        assert!(
+            repo.git_og(&["notes", "--ref=ai", "show", &recent.commit_sha])
+                .unwrap()
+                .contains("recent.txt")
+        );
+    }
+}
@@ -0,0 +1,181 @@
+mod repos;
+
+use git_ai::authorship::authorship_log_serialization::{AuthorshipLog, GeneratedFiles};
+use git_ai::config::TrackingConfig;
+use repos::test_repo::TestRepo;
+use std::collections::BTreeMap;
+use std::fs;
+
+fn repo_with_tracking_rules() -> TestRepo {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.tracking = Some(TrackingConfig {
+            exclude: Some(vec!["fixtures/**".to_string()]),
+            generated: Some(vec!["dist/**".to_string(), "*.lock".to_string()]),
+        });
+    });
+    fs::write(repo.path().join("README.md"), "# Test Repo\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "initial commit"]).unwrap();
+    repo
+}
+
+fn write(repo: &TestRepo, path: &str, contents: &str) {
+    let path = repo.path().join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn test_generated_files_are_summarized_instead_of_attributed() {
+    let repo = repo_with_tracking_rules();
+
+    let bundle = "console.log(1);\n".repeat(100);
+    write(&repo, "src/main.rs", "fn main() {}\n");
+    write(&repo, "dist/app.js", &bundle);
+    write(&repo, "dist/logo.png", "\u{89}PNG\0\0binary");
+    write(&repo, "fixtures/data.txt", "fixture\n");
+    repo.git_ai(&[
+        "checkpoint",
+        "mock_ai",
+        "src/main.rs",
+        "dist/app.js",
+        "fixtures/data.txt",
+    ])
+    .unwrap();
+    write(&repo, "Cargo.lock", "# lock\n");
+
+    let commit = repo.stage_all_and_commit("Build").unwrap();
+    let log = &commit.authorship_log;
+
+    let attested: Vec<&str> = log
+        .attestations
+        .iter()
+        .map(|attestation| attestation.file_path.as_str())
+        .collect();
+    assert_eq!(attested, vec!["src/main.rs"]);
+
+    assert_eq!(
+        log.metadata.generated.get("dist/**"),
+        Some(&GeneratedFiles {
+            files: 2,
+            bytes: (bundle.len() + "\u{89}PNG\0\0binary".len()) as u64,
+            ai_files: 1,
+        })
+    );
+    assert_eq!(
+        log.metadata.generated.get("*.lock"),
+        Some(&GeneratedFiles {
+            files: 1,
+            bytes: 7,
+            ai_files: 0,
+        })
+    );
+    assert_eq!(log.metadata.generated.len(), 2);
+}
+
+#[test]
+fn test_agent_editing_only_generated_files_is_recorded() {
+    let repo = repo_with_tracking_rules();
+
+    write(&repo, "dist/app.js", "bundle\n");
+    repo.git_ai(&["checkpoint", "mock_ai", "dist/app.js"])
+        .unwrap();
+
+    let commit = repo.stage_all_and_commit("Rebuild").unwrap();
+    let log = &commit.authorship_log;
+    assert!(log.attestations.is_empty());
+    assert_eq!(
+        log.metadata.generated.get("dist/**"),
+        Some(&GeneratedFiles {
+            files: 1,
+            bytes: 7,
+            ai_files: 1,
+        })
+    );
+}
+
+fn generated_at_head(repo: &TestRepo) -> BTreeMap<String, GeneratedFiles> {
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    AuthorshipLog::deserialize_from_string(&note)
+        .unwrap()
+        .metadata
+        .generated
+}
+
+#[test]
+fn test_unscoped_agent_checkpoint_records_generated_files() {
+    let repo = repo_with_tracking_rules();
+
+    write(&repo, "dist/app.js", "bundle\n");
+    // An agent hook that doesn't say which files it edited
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "transcript": {"messages": []},
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "test-123"
+    });
+    repo.git_ai_with_stdin(
+        &["checkpoint", "agent-v1", "--hook-input", "stdin"],
+        hook_input.to_string().as_bytes(),
+    )
+    .unwrap();
+
+    let commit = repo.stage_all_and_commit("Rebuild").unwrap();
+    assert_eq!(
+        commit.authorship_log.metadata.generated.get("dist/**"),
+        Some(&GeneratedFiles {
+            files: 1,
+            bytes: 7,
+            ai_files: 1,
+        })
+    );
+}
+
+#[test]
+fn test_generated_files_survive_amend() {
+    let repo = repo_with_tracking_rules();
+
+    write(&repo, "dist/app.js", "bundle\n");
+    repo.git_ai(&["checkpoint", "mock_ai", "dist/app.js"])
+        .unwrap();
+    repo.stage_all_and_commit("Rebuild").unwrap();
+
+    write(&repo, "dist/app.css", "body {}\n");
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "--amend", "--no-edit"]).unwrap();
+
+    assert_eq!(
+        generated_at_head(&repo).get("dist/**"),
+        Some(&GeneratedFiles {
+            files: 2,
+            bytes: 15,
+            ai_files: 1,
+        })
+    );
+}
+
+#[test]
+fn test_generated_files_survive_rebase() {
+    let repo = repo_with_tracking_rules();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    write(&repo, "src/main.rs", "fn main() {}\n");
+    write(&repo, "dist/app.js", "bundle\n");
+    repo.git_ai(&["checkpoint", "mock_ai", "src/main.rs", "dist/app.js"])
+        .unwrap();
+    repo.stage_all_and_commit("Build").unwrap();
+    let expected = generated_at_head(&repo);
+    assert_eq!(expected.len(), 1);
+
+    repo.git(&["checkout", &default_branch]).unwrap();
+    write(&repo, "src/lib.rs", "pub fn lib() {}\n");
+    repo.stage_all_and_commit("Add lib").unwrap();
+    repo.git(&["checkout", "feature"]).unwrap();
+    repo.git(&["rebase", &default_branch]).unwrap();
+
+    assert_eq!(generated_at_head(&repo), expected);
+}
@@ -0,0 +1,176 @@
+#!/usr/bin/env python3
+"""
+Run the git-ai integration suite against multiple git versions.
+
+Each version in versions.txt is built from the kernel.org release tarball into a
+per-version prefix under the cache dir (built once, reused afterwards). `apple`
+uses the system Apple Git on macOS. For every version the suite runs with that
+git first on PATH and with GIT_AI_TEST_GIT_PATH pointing git-ai at it, so the
+version-specific code paths (e.g. `git_supports_ignore_revs_file`) see the same
+git as the tests do.
+
+Examples:
+    python3 tests/git-versions/run-matrix.py
+    python3 tests/git-versions/run-matrix.py --versions 2.23.0 latest -- --test blame_flags
+"""
+
+from __future__ import annotations
+
+import argparse
+import os
+import platform
+import re
+import shutil
+import subprocess
+import sys
+import tarfile
+import tempfile
+import urllib.request
+from pathlib import Path
+from typing import List, Optional, Tuple
+
+REPO_ROOT = Path(__file__).resolve().parents[2]
+DEFAULT_VERSIONS_FILE = REPO_ROOT / "tests" / "git-versions" / "versions.txt"
+DEFAULT_CACHE_DIR = Path(os.environ.get("XDG_CACHE_HOME", Path.home() / ".cache")) / "git-ai" / "git-versions"
+TARBALL_URL = "https://mirrors.edge.kernel.org/pub/software/scm/git/git-{version}.tar.gz"
+GIT_REPO_URL = "https://github.com/git/git.git"
+
+BUILD_FLAGS = [
+    "NO_GETTEXT=YesPlease",
+    "NO_TCLTK=YesPlease",
+    "NO_CURL=YesPlease",
+    "NO_EXPAT=YesPlease",
+]
+
+
+def read_versions(path: Path) -> List[str]:
+    versions: List[str] = []
+    for line in path.read_text(encoding="utf-8").splitlines():
+        stripped = line.split("#", 1)[0].strip()
+        if stripped:
+            versions.append(stripped)
+    if not versions:
+        raise ValueError(f"No versions found in {path}")
+    return versions
+
+
+def resolve_latest() -> str:
+    output = subprocess.run(
+        ["git", "ls-remote", "--tags", "--refs", GIT_REPO_URL, "refs/tags/v2.*"],
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout
+    releases: List[Tuple[int, ...]] = []
+    for line in output.splitlines():
+        m = re.search(r"refs/tags/v(\d+)\.(\d+)\.(\d+)$", line)
+        if m:
+            releases.append(tuple(int(part) for part in m.groups()))
+    if not releases:
+        raise RuntimeError("Could not determine the latest git release")
+    return ".".join(str(part) for part in max(releases))
+
+
+def git_version_of(git: Path) -> str:
+    output = subprocess.run([str(git), "--version"], check=True, capture_output=True, text=True).stdout
+    m = re.search(r"git version (\d+\.\d+(?:\.\d+)?)", output)
+    if not m:
+        raise RuntimeError(f"Unrecognized `git --version` output from {git}: {output!r}")
+    version = m.group(1)
+    return version if version.count(".") == 2 else f"{version}.0"
+
+
+def apple_git() -> Path:
+    if platform.system() != "Darwin":
+        raise RuntimeError("`apple` is only available on macOS")
+    path = subprocess.run(["xcrun", "--find", "git"], check=True, capture_output=True, text=True).stdout.strip()
+    return Path(path)
+
+
+def ensure_git_build(version: str, cache_dir: Path, jobs: int) -> Path:
+    prefix = cache_dir / version
+    git = prefix / "bin" / "git"
+    if git.exists():
+        return git
+
+    print(f"[+] Building git {version} into {prefix}")
+    with tempfile.TemporaryDirectory() as tmpdir:
+        tarball = Path(tmpdir) / f"git-{version}.tar.gz"
+        urllib.request.urlretrieve(TARBALL_URL.format(version=version), tarball)
+        with tarfile.open(tarball) as tar:
+            tar.extractall(tmpdir)
+        source_dir = Path(tmpdir) / f"git-{version}"
+        subprocess.run(
+            ["make", "-C", str(source_dir), f"-j{jobs}", f"prefix={prefix}", *BUILD_FLAGS, "install"],
+            check=True,
+            stdout=subprocess.DEVNULL,
+        )
+    if not git.exists():
+        shutil.rmtree(prefix, ignore_errors=True)
+        raise RuntimeError(f"Build of git {version} did not produce {git}")
+    return git
+
+
+def resolve_git(version: str, cache_dir: Path, jobs: int) -> Tuple[str, Path]:
+    if version == "apple":
+        return version, apple_git()
+    if version == "latest":
+        version = resolve_latest()
+    return version, ensure_git_build(version, cache_dir, jobs)
+
+
+def run_suite(git: Path, mode: str, cargo_args: List[str]) -> int:
+    env = os.environ.copy()
+    env["PATH"] = f"{git.parent}{os.pathsep}{env.get('PATH', '')}"
+    env["GIT_AI_TEST_GIT_PATH"] = str(git)
+    env["GIT_AI_TEST_EXPECT_GIT_VERSION"] = git_version_of(git)
+    env["GIT_AI_TEST_GIT_MODE"] = mode
+    cmd = ["cargo", "test", *cargo_args]
+    print(f"[+] {' '.join(cmd)} (git {env['GIT_AI_TEST_EXPECT_GIT_VERSION']} at {git}, {mode} mode)")
+    return subprocess.run(cmd, cwd=REPO_ROOT, env=env).returncode
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description="Run the git-ai test suite against multiple git versions.")
+    parser.add_argument("--versions-file", type=Path, default=DEFAULT_VERSIONS_FILE)
+    parser.add_argument("--versions", nargs="+", help="Override versions.txt (e.g. 2.23.0 latest apple)")
+    parser.add_argument("--cache-dir", type=Path, default=DEFAULT_CACHE_DIR)
+    parser.add_argument("--mode", choices=["wrapper", "hooks", "both"], default="wrapper")
+    parser.add_argument("--jobs", type=int, default=os.cpu_count() or 4)
+    parser.add_argument("cargo_args", nargs=argparse.REMAINDER, help="Arguments after `--` go to cargo test")
+    args = parser.parse_args()
+
+    cargo_args = args.cargo_args
+    if cargo_args and cargo_args[0] == "--":
+        cargo_args = cargo_args[1:]
+
+    versions = args.versions or read_versions(args.versions_file)
+    if platform.system() != "Darwin":
+        skipped = [v for v in versions if v == "apple"]
+        versions = [v for v in versions if v != "apple"]
+        if skipped:
+            print("[!] Skipping `apple` (not on macOS)")
+
+    results: List[Tuple[str, Optional[int]]] = []
+    for requested in versions:
+        try:
+            version, git = resolve_git(requested, args.cache_dir, args.jobs)
+        except (RuntimeError, subprocess.CalledProcessError, OSError) as e:
+            print(f"[!] Could not set up git {requested}: {e}")
+            results.append((requested, None))
+            continue
+        results.append((version, run_suite(git, args.mode, cargo_args)))
+
+    print("\n[+] Git version matrix")
+    for version, code in results:
+        status = "setup failed" if code is None else ("ok" if code == 0 else f"failed (exit {code})")
+        print(f"  - {version}: {status}")
+
+    return 0 if all(code == 0 for _, code in results) else 1
+
+
+if __name__ == "__main__":
+    try:
+        sys.exit(main())
+    except KeyboardInterrupt:
+        sys.exit(130)
@@ -0,0 +1,115 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// A git that reports `version` and hands everything else to the system git
+fn write_fake_git(path: &Path, version: &str) {
+    std::fs::write(
+        path,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = --version ]; then echo 'git version {}'; else exec git \"$@\"; fi\n",
+            version
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn isolated_home(repo: &TestRepo) -> PathBuf {
+    let home = repo.path().with_extension("pin-home");
+    std::fs::create_dir_all(&home).unwrap();
+    home
+}
+
+#[test]
+fn test_pin_records_version_and_hash_and_is_used() {
+    let repo = TestRepo::new();
+    let home = isolated_home(&repo);
+    let home = home.to_str().unwrap();
+    let fake_git = repo.path().with_extension("fake-git");
+    write_fake_git(&fake_git, "2.99.1");
+
+    let output = repo
+        .git_ai_with_env(
+            &["git-path", "pin", fake_git.to_str().unwrap()],
+            &[("HOME", home)],
+        )
+        .unwrap();
+    assert!(output.contains("2.99.1"), "{}", output);
+
+    let config: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(Path::new(home).join(".git-ai/config.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(config["git"]["path"], fake_git.to_str().unwrap());
+    assert_eq!(config["git"]["version"], "2.99.1");
+    assert_eq!(config["git"]["sha256"].as_str().unwrap().len(), 64);
+
+    let in_use = repo
+        .git_ai_with_env(&["git-path"], &[("HOME", home)])
+        .unwrap();
+    assert_eq!(in_use.trim(), fake_git.to_str().unwrap());
+
+    let check = repo
+        .git_ai_with_env(&["git-path", "check"], &[("HOME", home)])
+        .unwrap();
+    assert!(check.contains("unchanged"), "{}", check);
+
+    repo.git_ai_with_env(&["git-path", "unpin"], &[("HOME", home)])
+        .unwrap();
+    let in_use = repo
+        .git_ai_with_env(&["git-path"], &[("HOME", home)])
+        .unwrap();
+    assert_ne!(in_use.trim(), fake_git.to_str().unwrap());
+}
+
+#[test]
+fn test_check_warns_when_pinned_git_changes() {
+    let repo = TestRepo::new();
+    let home = isolated_home(&repo);
+    let home = home.to_str().unwrap();
+    let fake_git = repo.path().with_extension("fake-git");
+    write_fake_git(&fake_git, "2.99.1");
+    repo.git_ai_with_env(
+        &["git-path", "pin", fake_git.to_str().unwrap()],
+        &[("HOME", home)],
+    )
+    .unwrap();
+
+    // Upgraded in place, as package managers do
+    write_fake_git(&fake_git, "2.99.2");
+    let err = repo
+        .git_ai_with_env(&["git-path", "check"], &[("HOME", home)])
+        .unwrap_err();
+    assert!(err.contains("has changed"), "{}", err);
+    assert!(err.contains("2.99.1 -> 2.99.2"), "{}", err);
+
+    std::fs::remove_file(&fake_git).unwrap();
+    let err = repo
+        .git_ai_with_env(&["git-path", "check"], &[("HOME", home)])
+        .unwrap_err();
+    assert!(err.contains("no longer exists"), "{}", err);
+}
+
+#[test]
+fn test_pin_rejects_binaries_that_are_not_git() {
+    let repo = TestRepo::new();
+    let home = isolated_home(&repo);
+    let not_git = repo.path().with_extension("not-git");
+    std::fs::write(&not_git, "#!/bin/sh\necho hello\n").unwrap();
+    std::fs::set_permissions(&not_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let err = repo
+        .git_ai_with_env(
+            &["git-path", "pin", not_git.to_str().unwrap()],
+            &[("HOME", home.to_str().unwrap())],
+        )
+        .unwrap_err();
+    assert!(err.contains("did not report a git version"), "{}", err);
+    assert!(!home.join(".git-ai/config.json").exists());
+}
@@ -788,6 +788,42 @@ fn test_git_supports_ignore_revs_file() {
     );
 }
 
+#[test]
+fn test_git_version_matches_git_under_test() {
+    // tests/git-versions/run-matrix.py points both the suite and git-ai at one git
+    // build; make sure git-ai's feature detection sees that build
+    let test_repo = TestRepo::new();
+
+    let repo = find_repository(&[
+        "-C".to_string(),
+        test_repo.path().to_str().unwrap().to_string(),
+    ])
+    .unwrap();
+
+    let version = repo.git_version().expect("Should get git version");
+    let path_git_version = std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap();
+    let (major, minor, patch) = version;
+    assert!(
+        path_git_version.contains(&format!(" {}.{}", major, minor)),
+        "git-ai detected {:?} but the suite runs {}",
+        version,
+        path_git_version
+    );
+    if let Ok(expected) = std::env::var("GIT_AI_TEST_EXPECT_GIT_VERSION") {
+        assert_eq!(format!("{}.{}.{}", major, minor, patch), expected);
+    }
+
+    assert_eq!(
+        repo.git_supports_ignore_revs_file(),
+        version >= (2, 23, 0),
+        "--ignore-revs-file support should follow the git version"
+    );
+}
+
 // ============================================================================
 // Remote Operations Tests
 // ============================================================================
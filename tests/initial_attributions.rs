@@ -63,6 +63,8 @@ fn test_initial_only_no_blame_data() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -151,6 +153,8 @@ fn test_initial_wins_overlaps() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -228,6 +232,8 @@ fn test_initial_and_blame_merge() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
     prompts.insert(
@@ -245,6 +251,8 @@ fn test_initial_and_blame_merge() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -315,6 +323,8 @@ fn test_partial_file_coverage() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -403,6 +413,8 @@ fn test_initial_attributions_in_subsequent_checkpoint() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -1,4 +1,5 @@
 /// Tests for JetBrains plugin download and installation functionality
+use git_ai::config::PluginChannel;
 use git_ai::mdm::jetbrains::download::{
     download_plugin_from_marketplace, install_plugin_to_directory, install_plugin_via_cli,
 };
@@ -263,7 +264,12 @@ fn test_download_plugin_url_format() {
 
     // Test with invalid URL will fail quickly
     // The actual function will try to connect, so we just verify it's callable
-    let result = download_plugin_from_marketplace("test-plugin-id", "IU", "252.12345");
+    let result = download_plugin_from_marketplace(
+        "test-plugin-id",
+        "IU",
+        "252.12345",
+        PluginChannel::Stable,
+    );
 
     // Should return an error (network or 404), not panic
     assert!(
@@ -0,0 +1,66 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn short(sha: &str) -> &str {
+    &sha[..7]
+}
+
+#[test]
+fn test_log_shows_ai_share_per_commit() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}"]);
+    let human = repo.stage_all_and_commit("Human function").unwrap();
+
+    file.insert_at(
+        0,
+        lines!["fn two() {}".ai(), "fn three() {}".ai(), "fn four() {}"],
+    );
+    let mixed = repo.stage_all_and_commit("Mostly AI functions").unwrap();
+
+    let output = repo.git_ai(&["log"]).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2, "{output}");
+    assert!(
+        lines[0].starts_with(&format!("{}  67% AI (HEAD -> ", short(&mixed.commit_sha))),
+        "{output}"
+    );
+    assert!(lines[0].ends_with("Mostly AI functions"), "{output}");
+    assert!(
+        lines[1].starts_with(&format!(
+            "{}   0% AI Human function",
+            short(&human.commit_sha)
+        )),
+        "{output}"
+    );
+
+    let filtered = repo.git_ai(&["log", "--min-ai", "50"]).unwrap();
+    assert_eq!(filtered.lines().count(), 1, "{filtered}");
+    assert!(filtered.contains("Mostly AI functions"), "{filtered}");
+}
+
+#[test]
+fn test_log_passes_arguments_to_git_log() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai()]);
+    repo.stage_all_and_commit("First").unwrap();
+    // A commit made behind git-ai's back has no note
+    file.insert_at(0, lines!["fn two() {}"]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    let untracked = repo.git_og(&["commit", "-m", "Second"]);
+    assert!(untracked.is_ok());
+    let head = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+
+    let output = repo.git_ai(&["log", "-n", "1"]).unwrap();
+    assert_eq!(output.lines().count(), 1, "{output}");
+    assert!(
+        output.starts_with(&format!("{}       - (HEAD -> ", short(head.trim()))),
+        "{output}"
+    );
+
+    let err = repo.git_ai(&["log", "--min-ai", "abc"]).unwrap_err();
+    assert!(err.contains("invalid --min-ai 'abc'"), "{err}");
+}
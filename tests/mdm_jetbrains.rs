@@ -0,0 +1,59 @@
+mod repos;
+
+use repos::test_repo::TestRepo;
+
+fn local_config(repo: &TestRepo, key: &str) -> Option<String> {
+    repo.git_og(&["config", "--local", "--get", key])
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+#[test]
+fn test_jetbrains_pin_and_channel_recorded_in_repo_config() {
+    let repo = TestRepo::new();
+
+    let output = repo
+        .git_ai(&["mdm", "jetbrains", "--pin", "0.4.2", "--repo"])
+        .expect("pinning should succeed");
+    assert!(output.contains("pinned to 0.4.2"), "{}", output);
+    assert_eq!(
+        local_config(&repo, "git-ai.jetbrainsPluginVersion").as_deref(),
+        Some("0.4.2")
+    );
+
+    // --check previews another version without recording it
+    let output = repo
+        .git_ai(&["mdm", "jetbrains", "--pin", "0.3.0", "--check"])
+        .unwrap();
+    assert!(output.contains("pinned to 0.3.0"), "{}", output);
+    assert_eq!(
+        local_config(&repo, "git-ai.jetbrainsPluginVersion").as_deref(),
+        Some("0.4.2")
+    );
+
+    let output = repo
+        .git_ai(&["mdm", "jetbrains", "--unpin", "--channel", "beta", "--repo"])
+        .unwrap();
+    assert!(output.contains("follows the beta channel"), "{}", output);
+    assert_eq!(local_config(&repo, "git-ai.jetbrainsPluginVersion"), None);
+    assert_eq!(
+        local_config(&repo, "git-ai.jetbrainsPluginChannel").as_deref(),
+        Some("beta")
+    );
+}
+
+#[test]
+fn test_jetbrains_rejects_bad_channel_and_version() {
+    let repo = TestRepo::new();
+
+    let err = repo
+        .git_ai(&["mdm", "jetbrains", "--channel", "nightly", "--repo"])
+        .expect_err("unknown channels should be rejected");
+    assert!(err.contains("Invalid channel 'nightly'"), "{}", err);
+
+    let err = repo
+        .git_ai(&["mdm", "jetbrains", "--pin", "1.0&channel=beta", "--repo"])
+        .expect_err("malformed versions should be rejected");
+    assert!(err.contains("Invalid plugin version"), "{}", err);
+    assert_eq!(local_config(&repo, "git-ai.jetbrainsPluginVersion"), None);
+}
@@ -0,0 +1,112 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn test_mock_agent_edits_are_attributed_through_hooks() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(vec!["# Project".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.mock_agent(&json!({
+        "agent_name": "mock-agent",
+        "model": "mock-model-1",
+        "conversation_id": "session-1",
+        "turns": [{
+            "prompt": "Add a greeting function",
+            "response": "Added greet()",
+            "edits": [
+                { "op": "write", "path": "src/lib.rs", "contents": "fn greet() {\n    println!(\"hi\");\n}\n" }
+            ]
+        }]
+    }))
+    .expect("mock agent should succeed");
+
+    // A human edit after the agent finished
+    let lib_path = repo.path().join("src/lib.rs");
+    let mut contents = fs::read_to_string(&lib_path).unwrap();
+    contents.push_str("// reviewed\n");
+    fs::write(&lib_path, contents).unwrap();
+
+    let commit = repo.stage_all_and_commit("Add greeting").unwrap();
+
+    let mut file = repo.filename("src/lib.rs");
+    file.assert_lines_and_blame(lines![
+        "fn greet() {".ai(),
+        "    println!(\"hi\");".ai(),
+        "}".ai(),
+        "// reviewed".human(),
+    ]);
+
+    let prompts = &commit.authorship_log.metadata.prompts;
+    assert_eq!(prompts.len(), 1, "expected a single prompt record");
+    let prompt = prompts.values().next().unwrap();
+    assert_eq!(prompt.agent_id.tool, "mock-agent");
+    assert_eq!(prompt.agent_id.id, "session-1");
+    assert_eq!(prompt.agent_id.model, "mock-model-1");
+}
+
+#[test]
+fn test_mock_agent_multi_turn_session_across_files() {
+    let repo = TestRepo::new();
+    let mut main_rs = repo.filename("src/main.rs");
+    main_rs.set_contents(vec!["fn main() {".human(), "}".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.mock_agent(&json!({
+        "turns": [
+            {
+                "prompt": "Call a helper from main",
+                "edits": [
+                    { "op": "replace", "path": "src/main.rs", "from": "fn main() {\n", "to": "fn main() {\n    helper();\n" },
+                    { "op": "write", "path": "src/helper.rs", "contents": "pub fn helper() {}\n" }
+                ]
+            },
+            {
+                "prompt": "Document the helper",
+                "edits": [
+                    { "op": "append", "path": "src/helper.rs", "contents": "// helper does nothing yet\n" }
+                ]
+            }
+        ]
+    }))
+    .expect("mock agent should succeed");
+
+    let commit = repo.stage_all_and_commit("Add helper").unwrap();
+
+    let mut main_rs = repo.filename("src/main.rs");
+    main_rs.assert_lines_and_blame(lines![
+        "fn main() {".human(),
+        "    helper();".ai(),
+        "}".human(),
+    ]);
+    let mut helper = repo.filename("src/helper.rs");
+    helper.assert_lines_and_blame(lines![
+        "pub fn helper() {}".ai(),
+        "// helper does nothing yet".ai(),
+    ]);
+
+    // Both turns belong to the same conversation, so they share one prompt record
+    let prompts = &commit.authorship_log.metadata.prompts;
+    assert_eq!(prompts.len(), 1, "expected a single prompt record");
+    assert_eq!(prompts.values().next().unwrap().agent_id.tool, "mock-agent");
+}
+
+#[test]
+fn test_mock_agent_fails_on_bad_edit() {
+    let repo = TestRepo::new();
+    let err = repo
+        .mock_agent(&json!({
+            "turns": [{
+                "prompt": "Edit a file that doesn't exist",
+                "edits": [{ "op": "replace", "path": "missing.rs", "from": "a", "to": "b" }]
+            }]
+        }))
+        .expect_err("mock agent should fail");
+    assert!(err.contains("missing.rs"), "unexpected error: {}", err);
+}
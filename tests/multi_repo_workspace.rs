@@ -5,7 +5,7 @@
 //!
 //! 1. Detecting git repository from file paths when workspace root isn't a git repo
 //! 2. Grouping files by their containing repository
-//! 3. Handling submodules correctly (should be ignored in favor of parent repo)
+//! 3. Handling submodules correctly (edits belong to the submodule, not its parent)
 //! 4. Edge cases with nested git directories
 
 use git_ai::error::GitAiError;
@@ -586,6 +586,103 @@ fn test_find_repository_for_file_nested_repos() {
     cleanup_tmp_dir(&workspace);
 }
 
+#[test]
+fn test_find_repository_for_file_skips_git_entries_that_are_not_repositories() {
+    let workspace = create_unique_tmp_dir("git-ai-stray-git-test").unwrap();
+    let outer_repo = workspace.join("outer");
+    init_git_repo(&outer_repo).unwrap();
+
+    // Vendored copy that kept an empty .git directory
+    let vendored = outer_repo.join("vendor").join("lib");
+    fs::create_dir_all(vendored.join(".git")).unwrap();
+    let vendored_file = vendored.join("lib.rs");
+    create_file(&vendored_file, "fn lib() {}").unwrap();
+
+    // Gitfile pointing at a gitdir that no longer exists
+    let moved = outer_repo.join("moved");
+    create_file(&moved.join(".git"), "gitdir: ../../gone/.git\n").unwrap();
+    let moved_file = moved.join("main.rs");
+    create_file(&moved_file, "fn main() {}").unwrap();
+
+    let file_paths = vec![
+        vendored_file.to_string_lossy().to_string(),
+        moved_file.to_string_lossy().to_string(),
+    ];
+    let (repo_files, orphan_files) =
+        group_files_by_repository(&file_paths, Some(workspace.to_str().unwrap()));
+
+    assert!(orphan_files.is_empty(), "orphans: {:?}", orphan_files);
+    assert_eq!(repo_files.len(), 1);
+    let (repo, files) = repo_files.values().next().unwrap();
+    assert!(repo.workdir().unwrap().ends_with("outer"));
+    assert_eq!(files.len(), 2);
+
+    cleanup_tmp_dir(&workspace);
+}
+
+#[test]
+fn test_group_files_by_repository_gives_submodule_files_to_the_submodule() {
+    let workspace = create_unique_tmp_dir("git-ai-submodule-test").unwrap();
+    let library = workspace.join("library");
+    init_git_repo(&library).unwrap();
+    create_file(&library.join("lib.rs"), "fn lib() {}").unwrap();
+    let git = |dir: &PathBuf, args: &[&str]| {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+    git(&library, &["add", "."]);
+    git(&library, &["commit", "-m", "Initial commit"]);
+
+    let outer_repo = workspace.join("outer");
+    init_git_repo(&outer_repo).unwrap();
+    git(
+        &outer_repo,
+        &[
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            library.to_str().unwrap(),
+            "lib",
+        ],
+    );
+
+    let outer_file = outer_repo.join("main.rs");
+    create_file(&outer_file, "fn main() {}").unwrap();
+    let submodule_file = outer_repo.join("lib").join("lib.rs");
+    let file_paths = vec![
+        outer_file.to_string_lossy().to_string(),
+        submodule_file.to_string_lossy().to_string(),
+    ];
+    let (repo_files, orphan_files) =
+        group_files_by_repository(&file_paths, Some(workspace.to_str().unwrap()));
+
+    assert!(orphan_files.is_empty(), "orphans: {:?}", orphan_files);
+    assert_eq!(repo_files.len(), 2);
+    let submodule = find_repository_for_file(submodule_file.to_str().unwrap(), None).unwrap();
+    assert!(submodule.workdir().unwrap().ends_with("lib"));
+    assert!(
+        submodule
+            .storage
+            .repo_path
+            .to_string_lossy()
+            .contains("modules"),
+        "{}",
+        submodule.storage.repo_path.display()
+    );
+
+    cleanup_tmp_dir(&workspace);
+}
+
 #[test]
 fn test_find_repository_in_path_still_works() {
     // Ensure the original function still works for normal single-repo scenarios
@@ -0,0 +1,128 @@
+#[macro_use]
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+/// A one-shot HTTP server that answers a single request with 200 and returns
+/// the raw request (head and body)
+fn serve_one_request() -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 8192];
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(head_end) = text.find("\r\n\r\n") {
+                let content_length = text[..head_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= head_end + 4 + content_length || read == 0 {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8_lossy(&request).to_string()
+    });
+    (address, handle)
+}
+
+fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header.eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+#[test]
+fn test_report_uploads_to_s3_compatible_endpoint() {
+    let repo = TestRepo::new();
+    repo.filename("a.txt").set_contents(lines!["one"]);
+    let base = repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.filename("a.txt").set_contents(lines!["one", "two"]);
+    repo.stage_all_and_commit("Second").unwrap();
+
+    let (endpoint, server) = serve_one_request();
+    let range = format!("{}..HEAD", base.commit_sha);
+    repo.git_ai_with_env(
+        &[
+            "report",
+            &range,
+            "-o",
+            "s3://artifacts/reports/ai report.html",
+        ],
+        &[
+            ("AWS_ENDPOINT_URL", endpoint.as_str()),
+            ("AWS_ACCESS_KEY_ID", "AKIDTEST"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_SESSION_TOKEN", "session"),
+            ("AWS_REGION", "eu-central-1"),
+        ],
+    )
+    .unwrap();
+
+    let request = server.join().unwrap();
+    assert!(
+        request.starts_with("PUT /artifacts/reports/ai%20report.html HTTP/1.1"),
+        "{}",
+        request
+    );
+    let authorization = header(&request, "authorization").unwrap();
+    assert!(
+        authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDTEST/"),
+        "{}",
+        authorization
+    );
+    assert!(authorization.contains("/eu-central-1/s3/aws4_request"));
+    assert!(authorization.contains("x-amz-security-token"));
+    assert_eq!(header(&request, "x-amz-security-token"), Some("session"));
+    assert!(
+        header(&request, "content-type")
+            .unwrap()
+            .starts_with("text/html")
+    );
+    assert!(request.contains("<!DOCTYPE html>"));
+}
+
+#[test]
+fn test_export_uploads_to_gcs_emulator() {
+    let repo = TestRepo::new();
+    repo.filename("a.txt").set_contents(lines!["one"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let (endpoint, server) = serve_one_request();
+    let output = repo
+        .git_ai_with_env(
+            &["export", "-o", "gs://artifacts/exports/notes.tar.zst"],
+            &[("STORAGE_EMULATOR_HOST", endpoint.as_str())],
+        )
+        .unwrap();
+    assert!(
+        output.contains("gs://artifacts/exports/notes.tar.zst"),
+        "{}",
+        output
+    );
+
+    let request = server.join().unwrap();
+    assert!(
+        request.starts_with("PUT /artifacts/exports/notes.tar.zst HTTP/1.1"),
+        "{}",
+        request
+    );
+    assert_eq!(header(&request, "content-type"), Some("application/zstd"));
+    assert_eq!(header(&request, "authorization"), None);
+}
@@ -844,6 +844,130 @@ fn test_flush_logs_with_empty_directory() {
     assert_eq!(log_count, 0);
 }
 
+#[test]
+fn test_undeliverable_envelopes_are_queued_and_retried() {
+    let repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let logs_dir = home.path().join(".git-ai").join("internal").join("logs");
+    fs::create_dir_all(&logs_dir).unwrap();
+    let log_file = logs_dir.join("1.log");
+    fs::write(
+        &log_file,
+        json!({
+            "type": "error",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "message": "Test error message"
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let home_str = home.path().to_str().unwrap().to_string();
+    let metrics_db = home.path().join("metrics-db");
+    let metrics_db = metrics_db.to_str().unwrap();
+    // Nothing listens on the discard port, so every Sentry delivery fails
+    let env = |sentry_dsn: &'static str| {
+        vec![
+            ("HOME", home_str.clone()),
+            ("GIT_AI_TEST_METRICS_DB_PATH", metrics_db.to_string()),
+            ("SENTRY_OSS", sentry_dsn.to_string()),
+            ("SENTRY_ENTERPRISE", String::new()),
+            ("POSTHOG_API_KEY", String::new()),
+        ]
+    };
+    let run = |args: &[&str], env: Vec<(&str, String)>| {
+        let env: Vec<(&str, &str)> = env.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        repo.git_ai_with_env(args, &env)
+            .unwrap_or_else(|e| panic!("git-ai {:?} failed: {}", args, e))
+    };
+
+    let output = run(&["flush-logs", "--force"], env("http://key@127.0.0.1:9/1"));
+    assert!(
+        output.contains("queued 1 for retry"),
+        "unexpected output: {}",
+        output
+    );
+    assert!(!log_file.exists(), "queued log file should be deleted");
+
+    let output = run(
+        &["sync", "--retry-pending"],
+        env("http://key@127.0.0.1:9/1"),
+    );
+    assert!(
+        output.contains("Telemetry events: 0 sent, 1 failed, 0 dropped, 1 still queued"),
+        "unexpected output: {}",
+        output
+    );
+
+    // With the destination no longer configured, the event is dropped
+    let output = run(&["sync", "--retry-pending"], env(""));
+    assert!(
+        output.contains("Telemetry events: 0 sent, 0 failed, 1 dropped, 0 still queued"),
+        "unexpected output: {}",
+        output
+    );
+}
+
+#[test]
+fn test_metrics_are_copied_to_dual_write_destination() {
+    let mut repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let copies = tempfile::tempdir().unwrap();
+    let destination = copies.path().to_str().unwrap().to_string();
+    repo.patch_git_ai_config(|patch| {
+        patch.dual_write = Some(git_ai::config::DualWriteConfig {
+            destination: Some(destination),
+            headers: None,
+        });
+    });
+
+    let logs_dir = home.path().join(".git-ai").join("internal").join("logs");
+    fs::create_dir_all(&logs_dir).unwrap();
+    let event = create_test_metric_event(100, 50, 30);
+    fs::write(
+        logs_dir.join("1.log"),
+        json!({
+            "type": "metrics",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "version": METRICS_API_VERSION,
+            "events": [event]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let metrics_db = home.path().join("metrics-db");
+    repo.git_ai_with_env(
+        &["flush-logs", "--force"],
+        &[
+            ("HOME", home.path().to_str().unwrap()),
+            ("GIT_AI_TEST_METRICS_DB_PATH", metrics_db.to_str().unwrap()),
+            ("SENTRY_OSS", ""),
+            ("SENTRY_ENTERPRISE", ""),
+            ("POSTHOG_API_KEY", ""),
+        ],
+    )
+    .unwrap();
+
+    let day_dirs: Vec<_> = fs::read_dir(copies.path().join("metrics"))
+        .expect("metrics copied")
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(day_dirs.len(), 1);
+    let files: Vec<_> = fs::read_dir(&day_dirs[0])
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    let batch: MetricsBatch =
+        serde_json::from_str(&fs::read_to_string(&files[0]).unwrap()).unwrap();
+    assert_eq!(batch.events.len(), 1);
+    assert_eq!(
+        files[0].file_stem().unwrap().to_str().unwrap(),
+        batch.idempotency_key()
+    );
+}
+
 // ============================================================================
 // Envelope Transformation Tests (Sentry Event Format)
 // ============================================================================
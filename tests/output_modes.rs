@@ -0,0 +1,79 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn repo_with_mixed_commit() -> TestRepo {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}", "fn two() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    file.insert_at(2, lines!["fn three() {}".ai(), "fn four() {}".ai()]);
+    repo.stage_all_and_commit("Add AI functions").unwrap();
+    repo
+}
+
+#[test]
+fn test_no_color_and_ascii_strip_escapes_and_unicode() {
+    let repo = repo_with_mixed_commit();
+
+    let colored = repo.git_ai(&["stats"]).unwrap();
+    assert!(colored.contains('\u{1b}'), "{}", colored);
+    assert!(!colored.is_ascii(), "{}", colored);
+
+    let no_color = repo
+        .git_ai_with_env(&["stats"], &[("NO_COLOR", "1")])
+        .unwrap();
+    assert!(!no_color.contains('\u{1b}'), "{}", no_color);
+
+    let ascii = repo.git_ai(&["--ascii", "stats"]).unwrap();
+    assert!(ascii.is_ascii(), "{}", ascii);
+    let ascii_from_env = repo
+        .git_ai_with_env(&["stats"], &[("GIT_AI_ASCII", "1")])
+        .unwrap();
+    assert!(ascii_from_env.is_ascii(), "{}", ascii_from_env);
+}
+
+#[test]
+fn test_plain_stats_are_label_value_lines() {
+    let repo = repo_with_mixed_commit();
+
+    for output in [
+        repo.git_ai(&["stats", "--plain"]).unwrap(),
+        repo.git_ai_with_env(&["stats"], &[("GIT_AI_PLAIN", "1")])
+            .unwrap(),
+    ] {
+        assert!(output.is_ascii(), "{}", output);
+        let lines: Vec<&str> = output.lines().filter(|line| line.contains(": ")).collect();
+        assert!(lines.contains(&"ai: 67% (2 lines)"), "{}", output);
+        assert!(
+            lines.iter().any(|line| line.starts_with("human: ")),
+            "{}",
+            output
+        );
+    }
+}
+
+#[test]
+fn test_stats_follow_the_locale() {
+    let repo = repo_with_mixed_commit();
+
+    let german = repo
+        .git_ai_with_env(&["stats", "--plain"], &[("GIT_AI_LOCALE", "de_DE.UTF-8")])
+        .unwrap();
+    assert!(german.contains("KI: 67 % (2 Zeilen)"), "{}", german);
+
+    // POSIX variables are honored too, and unknown languages fall back to English
+    let from_lang = repo
+        .git_ai_with_env(
+            &["stats", "--plain"],
+            &[("GIT_AI_LOCALE", ""), ("LC_ALL", "de_AT")],
+        )
+        .unwrap();
+    assert!(from_lang.contains("Mensch: "), "{}", from_lang);
+    let unknown = repo
+        .git_ai_with_env(&["stats", "--plain"], &[("GIT_AI_LOCALE", "xx_YY")])
+        .unwrap();
+    assert!(unknown.contains("ai: 67% (2 lines)"), "{}", unknown);
+}
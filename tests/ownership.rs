@@ -0,0 +1,197 @@
+#[macro_use]
+mod repos;
+
+use git_ai::config::TrustConfig;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+fn ownership(repo: &TestRepo, file: &str) -> Value {
+    let output = repo
+        .git_ai_with_env(
+            &["ownership", "--file", file, "--json"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap_or_else(|e| panic!("git-ai ownership {} failed: {}", file, e));
+    serde_json::from_str(&output).unwrap_or_else(|e| panic!("{}: {}", e, output))
+}
+
+fn cache_entries(repo: &TestRepo) -> usize {
+    count_files(
+        &repo
+            .path()
+            .join(".git")
+            .join("ai")
+            .join("cache")
+            .join("ownership"),
+    )
+}
+
+fn count_files(dir: &std::path::Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            if entry.path().is_dir() {
+                count_files(&entry.path())
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+#[test]
+fn test_ownership_reports_fractions_and_last_contributors() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn human() {}".human(), "fn other() {}".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    file.set_contents(lines![
+        "fn human() {}".human(),
+        "fn other() {}".human(),
+        "fn generated() {}".ai(),
+        "fn generated_too() {}".ai()
+    ]);
+    let commit = repo.stage_all_and_commit("Add generated").unwrap();
+
+    let json = ownership(&repo, "lib.rs");
+    assert_eq!(json["file"], "lib.rs");
+    assert_eq!(json["commit"], commit.commit_sha.as_str());
+    assert_eq!(json["total_lines"], 4);
+    assert_eq!(json["ai_lines"], 2);
+    assert_eq!(json["human_lines"], 2);
+    assert_eq!(json["ai_fraction"], 0.5);
+    assert_eq!(json["human_fraction"], 0.5);
+
+    let ai = &json["last_ai_contributor"];
+    assert_eq!(ai["commit"], commit.commit_sha.as_str());
+    assert!(ai["tool"].as_str().is_some_and(|tool| !tool.is_empty()));
+    assert!(
+        commit
+            .authorship_log
+            .metadata
+            .prompts
+            .contains_key(ai["prompt_id"].as_str().unwrap())
+    );
+    assert_eq!(json["last_human_editor"]["name"], "Test User");
+}
+
+#[test]
+fn test_ownership_is_cached_per_head() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["one".ai()]);
+    repo.stage_all_and_commit("First").unwrap();
+
+    let first = ownership(&repo, "a.txt");
+    assert_eq!(cache_entries(&repo), 1);
+    assert_eq!(ownership(&repo, "a.txt"), first);
+    assert_eq!(cache_entries(&repo), 1);
+
+    file.set_contents(lines!["one".ai(), "two".human()]);
+    repo.stage_all_and_commit("Second").unwrap();
+    let second = ownership(&repo, "a.txt");
+    assert_eq!(second["total_lines"], 2);
+    assert_eq!(second["human_lines"], 1);
+    assert_eq!(cache_entries(&repo), 2);
+}
+
+#[test]
+fn test_ownership_reports_trust_tier_from_current_config() {
+    let mut repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["one".ai()]);
+    repo.stage_all_and_commit("First").unwrap();
+
+    let json = ownership(&repo, "a.txt");
+    assert_eq!(json["last_ai_contributor"]["trust"], "standard");
+
+    repo.patch_git_ai_config(|patch| {
+        patch.trust = Some(TrustConfig {
+            untrusted: Some(vec!["mock_ai".to_string()]),
+            ..Default::default()
+        });
+    });
+    // Served from the cache, but the tier follows the config
+    let json = ownership(&repo, "a.txt");
+    assert_eq!(json["last_ai_contributor"]["trust"], "untrusted");
+    assert_eq!(cache_entries(&repo), 1);
+}
+
+#[test]
+fn test_ownership_resolves_paths_and_rejects_missing_files() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("src/main.rs");
+    file.set_contents(lines!["fn main() {}".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let output = repo
+        .git_ai_from_working_dir(
+            &repo.path().join("src"),
+            &["ownership", "--file", "main.rs", "--json"],
+        )
+        .unwrap();
+    let json: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["file"], "src/main.rs");
+    assert_eq!(json["ai_lines"], 0);
+    assert!(json["last_ai_contributor"].is_null());
+
+    let err = repo
+        .git_ai(&["ownership", "--file", "missing.rs", "--json"])
+        .expect_err("a file missing at HEAD should fail");
+    assert!(err.contains("not found at HEAD"), "{}", err);
+}
+
+#[test]
+fn test_blame_directory_rolls_up_per_file_ownership() {
+    let repo = TestRepo::new();
+    let mut top = repo.filename("src/lib.rs");
+    top.set_contents(lines!["fn human() {}".human(), "fn generated() {}".ai()]);
+    let mut nested = repo.filename("src/util/mod.rs");
+    nested.set_contents(lines![
+        "fn a() {}".ai(),
+        "fn b() {}".ai(),
+        "fn c() {}".human(),
+        "fn d() {}".human()
+    ]);
+    let mut outside = repo.filename("README.md");
+    outside.set_contents(lines!["# readme".human()]);
+    let commit = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let output = repo
+        .git_ai_with_env(&["blame", "src", "--json"], &[("GIT_AI_DEBUG", "0")])
+        .unwrap();
+    let json: Value = serde_json::from_str(&output).unwrap_or_else(|e| panic!("{}: {}", e, output));
+    assert_eq!(json["directory"], "src");
+    assert_eq!(json["commit"], commit.commit_sha.as_str());
+
+    let files: Vec<&str> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(files, vec!["src/lib.rs", "src/util/mod.rs"]);
+    assert_eq!(json["files"][0]["ai_lines"], 1);
+    assert!(json["files"][0]["last_ai_timestamp"].is_i64());
+
+    let directories = json["directories"].as_array().unwrap();
+    assert_eq!(directories.len(), 2);
+    assert_eq!(directories[0]["path"], "src");
+    assert_eq!(directories[0]["total_lines"], 6);
+    assert_eq!(directories[0]["ai_lines"], 3);
+    assert_eq!(directories[0]["ai_fraction"], 0.5);
+    assert_eq!(directories[1]["path"], "src/util");
+    assert_eq!(directories[1]["ai_lines"], 2);
+
+    let table = repo
+        .git_ai_from_working_dir(&repo.path().join("src"), &["blame", "."])
+        .unwrap();
+    assert!(table.contains("src/util/mod.rs"), "{}", table);
+    assert!(table.contains("src/util/"), "{}", table);
+    assert!(table.contains("50.0%"), "{}", table);
+    assert!(!table.contains("README.md"), "{}", table);
+}
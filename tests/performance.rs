@@ -21,6 +21,9 @@ fn setup() {
         rewrite_stash: true,
         inter_commit_move: true,
         auth_keyring: false,
+        warm_blame_cache: false,
+        intern_prompts: false,
+        infer_agent: false,
     };
 
     git_ai::config::Config::set_test_feature_flags(test_flags.clone());
@@ -0,0 +1,56 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_exceeded_checkpoint_budget_reports_phase_breakdown() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.performance_budgets = Some(vec!["checkpoint<0ms".to_string()]);
+    });
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    std::fs::write(repo.path().join("file.txt"), "base\nAI line\n").unwrap();
+    let output = repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+
+    assert!(output.contains("[git-ai] checkpoint took"), "{output}");
+    assert!(output.contains("(budget 0ms): "), "{output}");
+    assert!(output.contains("status_scan"), "{output}");
+}
+
+#[test]
+fn test_budget_not_reported_when_within_limit() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.performance_budgets = Some(vec!["post_commit<60s".to_string()]);
+    });
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base", "AI line".ai()]);
+    let commit = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    assert!(!commit.stdout.contains("budget"), "{}", commit.stdout);
+}
+
+#[test]
+fn test_exceeded_post_commit_budget_is_reported() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.performance_budgets = Some(vec!["post_commit<0ms".to_string()]);
+    });
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base", "AI line".ai()]);
+    let commit = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    assert!(
+        commit.stdout.contains("[git-ai] post_commit took"),
+        "{}",
+        commit.stdout
+    );
+    assert!(commit.stdout.contains("note_write"), "{}", commit.stdout);
+}
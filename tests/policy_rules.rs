@@ -0,0 +1,101 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+const CRYPTO_RULE: &str = "[[policy.rules]]
+name = \"crypto-review\"
+paths = [\"src/crypto/**\"]
+max_ai_percent = 80
+override_code = \"SEC-42\"
+";
+
+fn head(repo: &TestRepo) -> String {
+    repo.git(&["rev-parse", "HEAD"]).unwrap().trim().to_string()
+}
+
+#[test]
+fn test_rule_blocks_ai_heavy_commit_in_matching_paths() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join(".git-ai.toml"), CRYPTO_RULE).unwrap();
+    repo.stage_all_and_commit("Add policy").unwrap();
+    let before = head(&repo);
+
+    fs::create_dir_all(repo.path().join("src/crypto")).unwrap();
+    let mut file = repo.filename("src/crypto/aes.rs");
+    file.set_contents(lines!["human line", "ai line 1".ai(), "ai line 2".ai()]);
+    let mut other = repo.filename("src/lib.rs");
+    other.set_contents(lines!["ai elsewhere".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+
+    // 2 of 3 added lines under src/crypto are AI: within the limit
+    repo.git(&["commit", "-m", "Within limit"]).unwrap();
+    assert_ne!(head(&repo), before);
+    let before = head(&repo);
+
+    file.set_contents(lines![
+        "human line",
+        "ai line 1".ai(),
+        "ai line 2".ai(),
+        "ai line 3".ai(),
+        "ai line 4".ai(),
+        "ai line 5".ai(),
+        "ai line 6".ai(),
+        "ai line 7".ai(),
+        "ai line 8".ai(),
+        "ai line 9".ai()
+    ]);
+    repo.git(&["add", "-A"]).unwrap();
+    let err = repo
+        .git(&["commit", "-m", "Too much AI"])
+        .expect_err("commit should be blocked");
+    assert!(err.contains("crypto-review"), "{}", err);
+    assert!(
+        err.contains("set GIT_AI_POLICY_OVERRIDE=SEC-42 to override."),
+        "{}",
+        err
+    );
+    assert_eq!(head(&repo), before);
+
+    let output = repo
+        .git_with_env(
+            &["commit", "-m", "Too much AI"],
+            &[("GIT_AI_POLICY_OVERRIDE", "SEC-42")],
+            None,
+        )
+        .unwrap();
+    assert!(output.contains("overridden with code SEC-42"), "{}", output);
+    assert_ne!(head(&repo), before);
+}
+
+#[test]
+fn test_rule_requires_transcripts_for_ai_checkpoints() {
+    let repo = TestRepo::new();
+    fs::write(
+        repo.path().join(".git-ai.toml"),
+        "[[policy.rules]]\nname = \"transcripts\"\nrequire_transcript = true\n",
+    )
+    .unwrap();
+    repo.stage_all_and_commit("Add policy").unwrap();
+
+    let mut file = repo.filename("notes.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Human work").unwrap();
+
+    // Mock AI checkpoints record no transcript
+    file.set_contents(lines!["human line", "ai line".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    let err = repo
+        .git(&["commit", "-m", "AI work"])
+        .expect_err("commit should be blocked");
+    assert!(err.contains("transcripts"), "{}", err);
+    assert!(err.contains("notes.txt"), "{}", err);
+    assert!(err.contains("re-run with --no-verify"), "{}", err);
+
+    let output = repo
+        .git(&["commit", "--no-verify", "-m", "AI work"])
+        .unwrap();
+    assert!(output.contains("bypassed with --no-verify"), "{}", output);
+}
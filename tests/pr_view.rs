@@ -0,0 +1,135 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::os::unix::fs::PermissionsExt;
+
+/// A base commit, then a PR commit with AI lines in one file and human lines in another.
+/// Returns (base sha, head sha).
+fn repo_with_pr(repo: &TestRepo) -> (String, String) {
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn base() {}", "fn end() {}"]);
+    let base = repo.stage_all_and_commit("base").unwrap();
+
+    lib.insert_at(1, lines!["fn ai_one() {}".ai(), "fn ai_two() {}".ai()]);
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Notes".human()]);
+    let head = repo.stage_all_and_commit("PR change").unwrap();
+
+    (base.commit_sha, head.commit_sha)
+}
+
+/// A `gh` stand-in that answers `pr view --json` and `pr diff` for PR #7, opened
+/// from `forker/widgets` when `from_fork`. Returns the directory to prepend to PATH.
+fn fake_gh(repo: &TestRepo, base: &str, head: &str, from_fork: bool) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let view = serde_json::json!({
+        "title": "Add AI helpers",
+        "url": "https://github.com/acme/widgets/pull/7",
+        "baseRefOid": base,
+        "headRefOid": head,
+        "isCrossRepository": from_fork,
+        "headRepository": {"name": "widgets"},
+        "headRepositoryOwner": {"login": "forker"},
+    });
+    std::fs::write(dir.path().join("view.json"), view.to_string()).unwrap();
+    let diff = repo.git_og(&["diff", base, head]).unwrap();
+    std::fs::write(dir.path().join("pr.diff"), diff).unwrap();
+
+    let script = dir.path().join("gh");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\ncase \"$2\" in\n  view) cat '{0}/view.json' ;;\n  diff) cat '{0}/pr.diff' ;;\n  *) exit 1 ;;\nesac\n",
+            dir.path().display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    dir
+}
+
+#[test]
+fn test_pr_view_annotates_hunks_with_ai_summary() {
+    let repo = TestRepo::new();
+    let (base, head) = repo_with_pr(&repo);
+    let gh_dir = fake_gh(&repo, &base, &head, false);
+    let path = format!(
+        "{}:{}",
+        gh_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = repo
+        .git_ai_with_env(
+            &["pr", "view", "7", "--no-fetch"],
+            &[("PATH", &path), ("GIT_AI_ASCII", "1")],
+        )
+        .unwrap();
+
+    assert!(output.starts_with("#7 Add AI helpers\n"), "{output}");
+    assert!(
+        output.contains("https://github.com/acme/widgets/pull/7"),
+        "{output}"
+    );
+    assert!(
+        output.contains("AI wrote 2 of 3 added lines (66%) across 1 session"),
+        "{output}"
+    );
+    assert!(output.contains("+fn ai_one() {}"), "{output}");
+    assert!(
+        output.contains("2 of 2 added lines by AI - mock_ai (unknown)"),
+        "{output}"
+    );
+    // The human-only hunk gets no summary
+    assert_eq!(output.matches("added lines by AI").count(), 1, "{output}");
+}
+
+#[test]
+fn test_pr_view_reads_fork_notes_without_merging_them() {
+    let fork = TestRepo::new();
+    let (base, head) = repo_with_pr(&fork);
+    let repo = TestRepo::new();
+    let fork_path = fork.path().to_str().unwrap();
+    repo.git_og(&["fetch", "--quiet", fork_path, "HEAD"])
+        .unwrap();
+    repo.git_og(&[
+        "config",
+        &format!("url.{}.insteadOf", fork_path),
+        "https://github.com/forker/widgets.git",
+    ])
+    .unwrap();
+    let gh_dir = fake_gh(&fork, &base, &head, true);
+    let path = format!(
+        "{}:{}",
+        gh_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = repo
+        .git_ai_with_env(
+            &["pr", "view", "7"],
+            &[("PATH", &path), ("GIT_AI_ASCII", "1")],
+        )
+        .unwrap();
+
+    assert!(
+        output.contains("AI wrote 2 of 3 added lines (66%) across 1 session"),
+        "{output}"
+    );
+    assert!(
+        repo.git_og(&["notes", "--ref=ai-pr/7", "show", &head])
+            .is_ok()
+    );
+    // The fork's notes never reach the authorship ref
+    assert!(repo.git_og(&["notes", "--ref=ai", "show", &head]).is_err());
+}
+
+#[test]
+fn test_pr_view_requires_a_number() {
+    let repo = TestRepo::new();
+    let err = repo.git_ai(&["pr", "view"]).unwrap_err();
+    assert!(err.contains("requires a pull request number"), "{err}");
+}
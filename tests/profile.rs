@@ -0,0 +1,104 @@
+#[macro_use]
+mod repos;
+use repos::test_repo::TestRepo;
+use std::path::Path;
+
+fn profile_files(dir: &Path, suffix: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(suffix))
+        .map(|entry| std::fs::read_to_string(entry.path()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_checkpoint_profile_writes_folded_stacks_and_trace() {
+    let repo = TestRepo::new();
+    let profile_dir = tempfile::tempdir().unwrap();
+    let profile_dir_str = profile_dir.path().to_str().unwrap();
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    std::fs::write(repo.path().join("file.txt"), "base\nAI line\n").unwrap();
+    let output = repo
+        .git_ai_with_env(
+            &["checkpoint", "mock_ai"],
+            &[
+                ("GIT_AI_PROFILE", "1"),
+                ("GIT_AI_PROFILE_DIR", profile_dir_str),
+            ],
+        )
+        .unwrap();
+    assert!(output.contains("profile written to"), "{output}");
+
+    let folded = profile_files(profile_dir.path(), ".folded");
+    assert_eq!(folded.len(), 1);
+    let folded = &folded[0];
+    assert!(
+        folded.contains("git-ai-checkpoint;checkpoint;status_scan "),
+        "{folded}"
+    );
+    for line in folded.lines() {
+        let (stack, self_us) = line.rsplit_once(' ').unwrap();
+        assert!(stack.starts_with("git-ai-checkpoint"), "{line}");
+        assert!(self_us.parse::<u64>().is_ok(), "{line}");
+    }
+
+    let traces = profile_files(profile_dir.path(), ".trace.json");
+    assert_eq!(traces.len(), 1);
+    let trace: serde_json::Value = serde_json::from_str(&traces[0]).unwrap();
+    let names: Vec<&str> = trace["traceEvents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"checkpoint"), "{names:?}");
+    assert!(names.contains(&"working_log_write"), "{names:?}");
+}
+
+#[test]
+fn test_no_profile_written_without_env() {
+    let repo = TestRepo::new();
+    let profile_dir = tempfile::tempdir().unwrap();
+    let profile_dir_str = profile_dir.path().to_str().unwrap();
+
+    std::fs::write(repo.path().join("file.txt"), "AI line\n").unwrap();
+    let output = repo
+        .git_ai_with_env(
+            &["checkpoint", "mock_ai"],
+            &[("GIT_AI_PROFILE_DIR", profile_dir_str)],
+        )
+        .unwrap();
+
+    assert!(!output.contains("profile written to"), "{output}");
+    assert!(profile_files(profile_dir.path(), ".folded").is_empty());
+}
+
+#[test]
+fn test_commit_profile_includes_post_commit_phases() {
+    let repo = TestRepo::new();
+    let profile_dir = tempfile::tempdir().unwrap();
+    let profile_dir_str = profile_dir.path().to_str().unwrap();
+
+    std::fs::write(repo.path().join("file.txt"), "AI line\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git_with_env(
+        &["commit", "-m", "AI commit"],
+        &[
+            ("GIT_AI_PROFILE", "1"),
+            ("GIT_AI_PROFILE_DIR", profile_dir_str),
+        ],
+        None,
+    )
+    .unwrap();
+
+    let folded = profile_files(profile_dir.path(), ".folded").join("");
+    assert!(folded.contains(";post_commit;note_write "), "{folded}");
+}
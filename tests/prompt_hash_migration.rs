@@ -335,3 +335,100 @@ fn test_prompt_hash_migration_unstaged_ai_lines_saved_to_working_log() {
         "ai_line7".ai(),
     ]);
 }
+
+#[test]
+fn test_prompt_hash_salt_rotation_keeps_uncommitted_attributions() {
+    use git_ai::authorship::authorship_log_serialization::short_hash_with_scheme;
+    use git_ai::config::{
+        PromptHashAlgorithm, PromptHashScheme, PromptHashSchemeConfig, PromptHashingConfig,
+    };
+
+    let mut repo = TestRepo::new();
+    repo.filename("test.ts")
+        .set_contents(lines!["base_line", ""]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // The AI edit is checkpointed under the first salt...
+    repo.patch_git_ai_config(|patch| {
+        patch.prompt_hashing = Some(PromptHashingConfig {
+            salt: Some("salt-2025".to_string()),
+            ..Default::default()
+        });
+    });
+    repo.filename("test.ts")
+        .set_contents(lines!["base_line".human(), "ai_line".ai()]);
+
+    // ...and committed after rotating to a new one
+    repo.patch_git_ai_config(|patch| {
+        patch.prompt_hashing = Some(PromptHashingConfig {
+            algorithm: Some("sha3-256".to_string()),
+            salt: Some("salt-2026".to_string()),
+            previous: Some(vec![PromptHashSchemeConfig {
+                algorithm: None,
+                salt: Some("salt-2025".to_string()),
+            }]),
+        });
+    });
+    let commit = repo.stage_all_and_commit("AI commit").unwrap();
+
+    let current = PromptHashScheme {
+        algorithm: PromptHashAlgorithm::Sha3_256,
+        salt: Some("salt-2026".to_string()),
+    };
+    let prompts = &commit.authorship_log.metadata.prompts;
+    assert_eq!(prompts.len(), 1);
+    let (prompt_id, record) = prompts.iter().next().unwrap();
+    assert_eq!(
+        *prompt_id,
+        short_hash_with_scheme(&current, &record.agent_id.id, &record.agent_id.tool)
+    );
+    for attestation in &commit.authorship_log.attestations {
+        for entry in &attestation.entries {
+            assert_eq!(entry.hash, *prompt_id);
+        }
+    }
+
+    repo.filename("test.ts")
+        .assert_lines_and_blame(lines!["base_line".human(), "ai_line".ai()]);
+
+    // The session's id under the retired scheme still finds the prompt
+    let previous = PromptHashScheme {
+        algorithm: PromptHashAlgorithm::Sha256,
+        salt: Some("salt-2025".to_string()),
+    };
+    let old_id = short_hash_with_scheme(&previous, &record.agent_id.id, &record.agent_id.tool);
+    assert_ne!(old_id, *prompt_id);
+    let output = repo
+        .git_ai(&["prompts", "show", &commit.commit_sha, &old_id, "--json"])
+        .unwrap();
+    assert!(output.contains(prompt_id.as_str()), "{}", output);
+    let output = repo
+        .git_ai(&["show-prompt", &old_id, "--commit", "HEAD"])
+        .unwrap();
+    assert!(output.contains(&commit.commit_sha), "{}", output);
+    let output = repo.git_ai(&["show-prompt", &old_id]).unwrap();
+    assert!(output.contains(&commit.commit_sha), "{}", output);
+}
+
+#[test]
+fn test_unknown_prompt_hash_algorithm_is_reported() {
+    use git_ai::config::PromptHashingConfig;
+
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.prompt_hashing = Some(PromptHashingConfig {
+            algorithm: Some("sha-512".to_string()),
+            ..Default::default()
+        });
+    });
+
+    let output = repo
+        .git_ai(&["config", "prompt_hashing.algorithm"])
+        .unwrap();
+    assert!(
+        output.contains("Unknown prompt_hashing.algorithm 'sha-512'"),
+        "{}",
+        output
+    );
+    assert!(output.contains("\"sha256\""), "{}", output);
+}
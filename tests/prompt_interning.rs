@@ -0,0 +1,154 @@
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use repos::test_repo::TestRepo;
+use std::fs;
+
+const INTERN_ENV: &[(&str, &str)] = &[("GIT_AI_INTERN_PROMPTS", "true")];
+
+/// Checkpoint `contents` into example.txt from one agent session whose transcript
+/// holds `message`, so every call adds to the same prompt
+fn checkpoint_with_prompt(repo: &TestRepo, contents: &str, message: &str) {
+    fs::write(repo.path().join("example.txt"), contents).unwrap();
+
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user(message.to_string(), None));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": ["example.txt"],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "test-conversation-id",
+    });
+    let hook_input = serde_json::to_string(&hook_input).unwrap();
+    repo.git_ai(&["checkpoint", "agent-v1", "--hook-input", &hook_input])
+        .expect("checkpoint should succeed");
+    repo.git(&["add", "-A"]).unwrap();
+}
+
+fn repo_storing_prompts_in_notes() -> TestRepo {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.exclude_prompts_in_repositories = Some(vec![]);
+        patch.prompt_storage = Some("notes".to_string());
+    });
+    fs::write(repo.path().join("README.md"), "# Test Repo\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "initial commit"]).unwrap();
+    repo
+}
+
+fn interned_entries(repo: &TestRepo) -> usize {
+    repo.git_og(&["notes", "--ref=ai-prompts", "list"])
+        .unwrap_or_default()
+        .lines()
+        .count()
+}
+
+/// show-prompt from a clone with its own (empty) prompt database, so messages can
+/// only come from the fetched notes
+fn show_prompt_in_clone(source: &TestRepo, prompt_id: &str) -> serde_json::Value {
+    let clone = TestRepo::new();
+    clone
+        .git_og(&[
+            "fetch",
+            source.path().to_str().unwrap(),
+            "HEAD:refs/heads/imported",
+            "+refs/notes/*:refs/notes/*",
+        ])
+        .unwrap();
+    let output = clone
+        .git_ai(&["show-prompt", prompt_id])
+        .expect("show-prompt should succeed");
+    // A fresh database also logs its migrations after the JSON
+    serde_json::Deserializer::from_str(&output)
+        .into_iter::<serde_json::Value>()
+        .next()
+        .and_then(Result::ok)
+        .unwrap_or_else(|| panic!("expected JSON: {}", output))
+}
+
+#[test]
+fn test_post_commit_interns_messages_shared_across_commits() {
+    let repo = repo_storing_prompts_in_notes();
+
+    checkpoint_with_prompt(&repo, "AI line 1\n", "Write the example");
+    let first = repo.commit_with_env("First", INTERN_ENV, None).unwrap();
+    checkpoint_with_prompt(&repo, "AI line 1\nAI line 2\n", "Write the example");
+    let second = repo.commit_with_env("Second", INTERN_ENV, None).unwrap();
+
+    let (prompt_id, first_prompt) = first
+        .authorship_log
+        .metadata
+        .prompts
+        .iter()
+        .next()
+        .expect("Expected a prompt in the authorship log");
+    let second_prompt = &second.authorship_log.metadata.prompts[prompt_id];
+    assert!(first_prompt.messages.is_empty());
+    assert!(first_prompt.messages_ref.is_some());
+    assert_eq!(first_prompt.messages_ref, second_prompt.messages_ref);
+    assert_eq!(interned_entries(&repo), 1);
+
+    let json = show_prompt_in_clone(&repo, prompt_id);
+    assert_eq!(
+        json["prompt"]["messages"][0]["text"].as_str(),
+        Some("Write the example"),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn test_intern_prompts_migrates_existing_notes() {
+    let repo = repo_storing_prompts_in_notes();
+    checkpoint_with_prompt(&repo, "AI line 1\n", "Write the example");
+    let commit = repo.commit("Add example").unwrap();
+    let prompt_id = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .keys()
+        .next()
+        .unwrap();
+    assert_eq!(interned_entries(&repo), 0);
+
+    let output = repo.git_ai(&["intern-prompts", "--dry-run"]).unwrap();
+    assert!(
+        output.contains("Would intern 1 prompts in 1 authorship notes"),
+        "{}",
+        output
+    );
+    assert_eq!(interned_entries(&repo), 0);
+
+    let output = repo.git_ai(&["intern-prompts"]).unwrap();
+    assert!(
+        output.contains("Interned 1 prompts in 1 authorship notes"),
+        "{}",
+        output
+    );
+    let note = repo
+        .git_og(&["notes", "--ref=ai", "show", &commit.commit_sha])
+        .unwrap();
+    assert!(note.contains("messages_ref"), "{}", note);
+    assert!(!note.contains("Write the example"), "{}", note);
+    assert_eq!(interned_entries(&repo), 1);
+
+    // Nothing left to intern the second time round
+    let output = repo.git_ai(&["intern-prompts"]).unwrap();
+    assert!(
+        output.contains("Interned 0 prompts in 0 authorship notes"),
+        "{}",
+        output
+    );
+
+    let json = show_prompt_in_clone(&repo, prompt_id);
+    assert_eq!(
+        json["prompt"]["messages"][0]["text"].as_str(),
+        Some("Write the example"),
+        "{}",
+        json
+    );
+}
@@ -0,0 +1,111 @@
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use git_ai::commands::prompts_show::parse_prompts_show_args;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+use std::fs;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn agent_edit(repo: &TestRepo, file: &str) {
+    fs::write(repo.path().join(file), "fn main() {}\n").unwrap();
+
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user(
+        "Add a main function".to_string(),
+        Some("2026-03-01T10:00:00Z".to_string()),
+    ));
+    transcript.add_message(Message::assistant(
+        "Added it to main.rs".to_string(),
+        Some("2026-03-01T10:00:30Z".to_string()),
+    ));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": [file],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "prompts-show-session",
+    });
+    let hook_input = serde_json::to_string(&hook_input).unwrap();
+    repo.git_ai(&["checkpoint", "agent-v1", "--hook-input", &hook_input])
+        .expect("checkpoint should succeed");
+}
+
+#[test]
+fn parse_prompts_show_args_requires_a_commit() {
+    assert_eq!(
+        parse_prompts_show_args(&args(&[])).unwrap_err(),
+        "prompts show requires a commit"
+    );
+    let parsed = parse_prompts_show_args(&args(&["HEAD", "abc123", "--json"])).unwrap();
+    assert_eq!(parsed.commit, "HEAD");
+    assert_eq!(parsed.prompt_id.as_deref(), Some("abc123"));
+    assert!(parsed.json);
+    assert!(parse_prompts_show_args(&args(&["HEAD", "a", "b"])).is_err());
+}
+
+#[test]
+fn test_prompts_show_lists_prompts_and_prints_transcripts() {
+    let repo = TestRepo::new();
+    agent_edit(&repo, "main.rs");
+    let commit = repo.stage_all_and_commit("Add main").unwrap();
+    let prompt_id = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .keys()
+        .next()
+        .expect("commit should record the agent's prompt")
+        .clone();
+
+    let output = repo
+        .git_ai_with_env(
+            &["prompts", "show", "HEAD", "--json"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap();
+    let json: Value = serde_json::from_str(&output).unwrap_or_else(|e| panic!("{}: {}", e, output));
+    assert_eq!(json["commit"], commit.commit_sha.as_str());
+    let prompts = json["prompts"].as_array().unwrap();
+    assert_eq!(prompts.len(), 1, "{json}");
+    assert_eq!(prompts[0]["id"], prompt_id.as_str());
+    assert_eq!(prompts[0]["tool"], "test-agent");
+    assert_eq!(prompts[0]["model"], "test-model");
+    assert_eq!(prompts[0]["files"][0], "main.rs");
+    assert_eq!(prompts[0]["first_message_at"], "2026-03-01T10:00:00Z");
+    assert_eq!(prompts[0]["last_message_at"], "2026-03-01T10:00:30Z");
+
+    let listing = repo.git_ai(&["prompts", "show", "HEAD"]).unwrap();
+    assert!(listing.contains(&prompt_id), "{}", listing);
+    assert!(listing.contains("test-agent (test-model)"), "{}", listing);
+
+    // A unique prefix is enough to pick the transcript
+    let transcript = repo
+        .git_ai(&["prompts", "show", "HEAD", &prompt_id[..6]])
+        .unwrap();
+    assert!(
+        transcript.contains(&format!("Prompt {} (test-agent, test-model)", prompt_id)),
+        "{}",
+        transcript
+    );
+    assert!(
+        transcript.contains("[user] 2026-03-01T10:00:00Z\n  Add a main function"),
+        "{}",
+        transcript
+    );
+    assert!(
+        transcript.contains("[assistant] 2026-03-01T10:00:30Z\n  Added it to main.rs"),
+        "{}",
+        transcript
+    );
+
+    let err = repo
+        .git_ai(&["prompts", "show", "HEAD", "does-not-exist"])
+        .expect_err("unknown prompt ids should fail");
+    assert!(err.contains("No prompt does-not-exist"), "{}", err);
+}
@@ -83,3 +83,36 @@ fn push_after_branch_set_upstream_pushes_authorship_notes() {
         "expected authorship notes to be pushed after setting upstream with git branch -u"
     );
 }
+
+#[test]
+fn repeated_push_advances_tracking_ref_to_remote_notes_tip() {
+    let (local, upstream) = TestRepo::new_with_remote();
+
+    let mut file = local.filename("tracked.rs");
+    file.set_contents(vec!["fn first() {}".ai()]);
+    let first = local
+        .stage_all_and_commit("first commit")
+        .expect("first commit should succeed");
+    local
+        .git(&["push", "-u", "origin", "HEAD"])
+        .expect("first push should succeed");
+
+    file.set_contents(vec!["fn first() {}".ai(), "fn second() {}".ai()]);
+    let second = local
+        .stage_all_and_commit("second commit")
+        .expect("second commit should succeed");
+    local.git(&["push"]).expect("second push should succeed");
+    // Nothing new to send: the remote already has every notes commit
+    local.git(&["push"]).expect("no-op push should succeed");
+
+    assert!(read_remote_authorship_note(&upstream, &first.commit_sha).is_some());
+    assert!(read_remote_authorship_note(&upstream, &second.commit_sha).is_some());
+
+    let local_notes = local
+        .git_og(&["rev-parse", "refs/notes/ai"])
+        .expect("local notes ref should exist");
+    let tracking = local
+        .git_og(&["rev-parse", "refs/notes/ai-remote/origin"])
+        .expect("tracking ref should exist");
+    assert_eq!(local_notes.trim(), tracking.trim());
+}
@@ -399,6 +399,8 @@ fn test_rebase_preserves_prompt_only_commit_note_metadata() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            messages_ref: None,
+            classification: None,
         },
     );
 
@@ -1449,3 +1451,209 @@ cat {} > "$1"
         "function feature3() {}".ai()
     ]);
 }
+
+/// Commit an agent session's write to `path`, returning the commit and its prompt id
+#[cfg(not(target_os = "windows"))]
+fn commit_agent_session(repo: &TestRepo, session: &str, path: &str) -> (String, String) {
+    repo.mock_agent(&serde_json::json!({
+        "conversation_id": session,
+        "turns": [{
+            "prompt": format!("write {}", path),
+            "edits": [{"op": "write", "path": path, "contents": format!("// {}\nfn f() {{}}\n", session)}],
+        }],
+    }))
+    .unwrap();
+    let commit = repo
+        .stage_all_and_commit(&format!("{} writes {}", session, path))
+        .unwrap();
+    let prompt_id = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .keys()
+        .next()
+        .expect("agent commit should record its prompt")
+        .clone();
+    (commit.commit_sha, prompt_id)
+}
+
+/// Run `git rebase -i onto`, editing the todo list with `sed_expression`
+#[cfg(not(target_os = "windows"))]
+fn rebase_interactive_with_sed(repo: &TestRepo, onto: &str, sed_expression: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script_path = repo.path().join("todo_script.sh");
+    std::fs::write(
+        &script_path,
+        format!("#!/bin/sh\nsed -i.bak '{}' \"$1\"\n", sed_expression),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    repo.git_with_env(
+        &["rebase", "-i", onto],
+        &[
+            ("GIT_SEQUENCE_EDITOR", script_path.to_str().unwrap()),
+            ("GIT_EDITOR", "true"),
+        ],
+        None,
+    )
+    .expect("interactive rebase should succeed");
+}
+
+/// Three agent commits on a feature branch, with the default branch advanced past
+/// their base. Returns the new base and the prompt id of each commit.
+#[cfg(not(target_os = "windows"))]
+fn setup_three_agent_commits(repo: &TestRepo) -> (String, [String; 3]) {
+    let mut base_file = repo.filename("base.txt");
+    base_file.set_contents(lines!["base content"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let (_, prompt_a) = commit_agent_session(repo, "session-a", "a.rs");
+    let (_, prompt_b) = commit_agent_session(repo, "session-b", "b.rs");
+    let (_, prompt_c) = commit_agent_session(repo, "session-c", "c.rs");
+
+    repo.git(&["checkout", &default_branch]).unwrap();
+    let mut main_file = repo.filename("main.txt");
+    main_file.set_contents(lines!["main work"]);
+    repo.stage_all_and_commit("Main advances").unwrap();
+    let onto = repo.git(&["rev-parse", "HEAD"]).unwrap().trim().to_string();
+    repo.git(&["checkout", "feature"]).unwrap();
+
+    (onto, [prompt_a, prompt_b, prompt_c])
+}
+
+#[cfg(not(target_os = "windows"))]
+fn authorship_log_for(repo: &TestRepo, rev: &str) -> AuthorshipLog {
+    let sha = repo.git(&["rev-parse", rev]).unwrap().trim().to_string();
+    let note = read_authorship_note(repo, &sha)
+        .unwrap_or_else(|| panic!("{} should have an authorship note", rev));
+    AuthorshipLog::deserialize_from_string(&note).expect("parse authorship note")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attested_files(log: &AuthorshipLog) -> Vec<&str> {
+    let mut files: Vec<&str> = log
+        .attestations
+        .iter()
+        .map(|attestation| attestation.file_path.as_str())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Dropping a commit in `rebase -i` must not leave its files or prompts in the notes
+/// of the commits rebased around it
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_rebase_interactive_drop_discards_dropped_commit_authorship() {
+    let repo = TestRepo::new();
+    let (onto, [prompt_a, prompt_b, prompt_c]) = setup_three_agent_commits(&repo);
+
+    rebase_interactive_with_sed(&repo, &onto, "2s/^pick/drop/");
+
+    assert!(!repo.path().join("b.rs").exists());
+    let first = authorship_log_for(&repo, "HEAD~1");
+    assert_eq!(attested_files(&first), vec!["a.rs"]);
+    assert!(first.metadata.prompts.contains_key(&prompt_a));
+    assert!(!first.metadata.prompts.contains_key(&prompt_b));
+
+    let second = authorship_log_for(&repo, "HEAD");
+    assert!(!attested_files(&second).contains(&"b.rs"));
+    assert!(attested_files(&second).contains(&"c.rs"));
+    assert!(second.metadata.prompts.contains_key(&prompt_c));
+    assert!(!second.metadata.prompts.contains_key(&prompt_b));
+
+    let mut file_c = repo.filename("c.rs");
+    file_c.assert_lines_and_blame(lines!["// session-c".ai(), "fn f() {}".ai()]);
+}
+
+/// Squash and fixup fold commits together, so the resulting commit's note carries
+/// every folded commit's attestations and prompt records
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_rebase_interactive_squash_and_fixup_merge_prompt_records() {
+    let repo = TestRepo::new();
+    let (onto, [prompt_a, prompt_b, prompt_c]) = setup_three_agent_commits(&repo);
+
+    rebase_interactive_with_sed(&repo, &onto, "2s/^pick/squash/; 3s/^pick/fixup/");
+
+    let commit_count = repo
+        .git(&["rev-list", "--count", &format!("{}..HEAD", onto)])
+        .unwrap();
+    assert_eq!(commit_count.trim(), "1");
+
+    let squashed = authorship_log_for(&repo, "HEAD");
+    assert_eq!(attested_files(&squashed), vec!["a.rs", "b.rs", "c.rs"]);
+    for prompt_id in [&prompt_a, &prompt_b, &prompt_c] {
+        assert!(
+            squashed.metadata.prompts.contains_key(prompt_id),
+            "squashed note should keep prompt {}",
+            prompt_id
+        );
+    }
+    assert_eq!(squashed.metadata.prompts[&prompt_b].total_additions, 2);
+
+    let mut file_b = repo.filename("b.rs");
+    file_b.assert_lines_and_blame(lines!["// session-b".ai(), "fn f() {}".ai()]);
+}
+
+/// A `commit --fixup=` made by another agent session is folded into its target by
+/// `rebase --autosquash`; the target's note must pick up the fixup's lines and prompt
+/// even when there's no reflog to tell how the todo list played out
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_rebase_autosquash_folds_fixup_attributions_into_target() {
+    let repo = TestRepo::new();
+    let (onto, [prompt_a, prompt_b, _]) = setup_three_agent_commits(&repo);
+    let target = repo
+        .git(&["rev-parse", "HEAD~2"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    repo.mock_agent(&serde_json::json!({
+        "conversation_id": "session-fix",
+        "turns": [{
+            "prompt": "fix a.rs",
+            "edits": [{"op": "write", "path": "a.rs", "contents": "// session-a\nfn f() {}\nfn fixed() {}\n"}],
+        }],
+    }))
+    .unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", &format!("--fixup={}", target)])
+        .unwrap();
+    let fixup_log = authorship_log_for(&repo, "HEAD");
+    let prompt_fix = fixup_log.metadata.prompts.keys().next().unwrap().clone();
+
+    // Without a HEAD reflog the fixup can only be paired by its subject
+    repo.git(&["config", "core.logAllRefUpdates", "false"])
+        .unwrap();
+    std::fs::remove_file(repo.path().join(".git/logs/HEAD")).unwrap();
+    repo.git_with_env(
+        &["rebase", "-i", "--autosquash", &onto],
+        &[("GIT_SEQUENCE_EDITOR", "true"), ("GIT_EDITOR", "true")],
+        None,
+    )
+    .expect("autosquash rebase should succeed");
+
+    let commit_count = repo
+        .git(&["rev-list", "--count", &format!("{}..HEAD", onto)])
+        .unwrap();
+    assert_eq!(commit_count.trim(), "3");
+
+    let folded = authorship_log_for(&repo, "HEAD~2");
+    assert_eq!(attested_files(&folded), vec!["a.rs"]);
+    assert!(folded.metadata.prompts.contains_key(&prompt_a));
+    assert!(folded.metadata.prompts.contains_key(&prompt_fix));
+    assert!(!folded.metadata.prompts.contains_key(&prompt_b));
+
+    let mut file_a = repo.filename("a.rs");
+    file_a.assert_lines_and_blame(lines![
+        "// session-a".ai(),
+        "fn f() {}".ai(),
+        "fn fixed() {}".ai(),
+    ]);
+}
@@ -0,0 +1,158 @@
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use repos::test_repo::TestRepo;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Run an agent-v1 checkpoint for `example.txt` whose transcript holds `message`,
+/// commit it and return the stored user message
+fn commit_with_prompt(repo: &TestRepo, message: &str, extra_args: &[&str]) -> String {
+    fs::write(repo.path().join("example.txt"), "AI Line 1\n").unwrap();
+
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user(message.to_string(), None));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": ["example.txt"],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": "test-conversation-id",
+    });
+    let hook_input = serde_json::to_string(&hook_input).unwrap();
+    let mut args = vec!["checkpoint", "agent-v1", "--hook-input", &hook_input];
+    args.extend_from_slice(extra_args);
+    repo.git_ai(&args).expect("checkpoint should succeed");
+
+    repo.git(&["add", "-A"]).unwrap();
+    let commit = repo.commit("Add example").expect("commit should succeed");
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("Expected a prompt in the authorship log");
+    match prompt.messages.first() {
+        Some(Message::User { text, .. }) => text.clone(),
+        other => panic!("Expected a user message, got {:?}", other),
+    }
+}
+
+fn repo_storing_prompts_in_notes() -> TestRepo {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.exclude_prompts_in_repositories = Some(vec![]);
+        patch.prompt_storage = Some("notes".to_string());
+    });
+    fs::write(repo.path().join("README.md"), "# Test Repo\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "initial commit"]).unwrap();
+    repo
+}
+
+#[test]
+fn test_checkpoint_redacts_tokens_and_emails_from_transcript() {
+    let repo = repo_storing_prompts_in_notes();
+    let token = format!("ghp_{}", "a1B2".repeat(9));
+
+    let text = commit_with_prompt(
+        &repo,
+        &format!("Deploy with {} and tell jane@example.com", token),
+        &[],
+    );
+    assert_eq!(
+        text,
+        "Deploy with [REDACTED:github-token] and tell [REDACTED:email]"
+    );
+}
+
+#[test]
+fn test_custom_redaction_rules_from_config() {
+    let mut repo = repo_storing_prompts_in_notes();
+    repo.patch_git_ai_config(|patch| {
+        patch.redaction_rules = Some(BTreeMap::from([(
+            "ticket".to_string(),
+            r"ACME-\d+".to_string(),
+        )]));
+    });
+
+    let text = commit_with_prompt(&repo, "Fix ACME-4821 please", &[]);
+    assert_eq!(text, "Fix [REDACTED:ticket] please");
+}
+
+#[test]
+fn test_no_redact_stores_transcript_as_is() {
+    let repo = repo_storing_prompts_in_notes();
+
+    let text = commit_with_prompt(&repo, "Ask jane@example.com", &["--no-redact"]);
+    assert_eq!(text, "Ask jane@example.com");
+}
+
+#[test]
+fn test_no_redact_survives_transcript_refetch_at_commit() {
+    let repo = repo_storing_prompts_in_notes();
+    let repo_root = repo.canonical_path();
+    let file_path = repo_root.join("example.txt");
+    let transcript_path = repo_root.join("claude-session.jsonl");
+
+    let user_line = serde_json::json!({
+        "type": "user",
+        "message": {"role": "user", "content": "Ask jane@example.com"},
+        "timestamp": "2025-09-11T13:28:05.638Z",
+    });
+    fs::write(&transcript_path, format!("{}\n", user_line)).unwrap();
+    let hook_input = serde_json::json!({
+        "cwd": repo_root.to_string_lossy().to_string(),
+        "hook_event_name": "PostToolUse",
+        "transcript_path": transcript_path.to_string_lossy().to_string(),
+        "tool_input": {"file_path": file_path.to_string_lossy().to_string()},
+    })
+    .to_string();
+
+    fs::write(&file_path, "AI Line 1\n").unwrap();
+    repo.git_ai(&[
+        "checkpoint",
+        "claude",
+        "--hook-input",
+        &hook_input,
+        "--no-redact",
+    ])
+    .expect("checkpoint should succeed");
+
+    // The session keeps going after the checkpoint, so post-commit refetches it
+    let assistant_line = serde_json::json!({
+        "type": "assistant",
+        "message": {
+            "role": "assistant",
+            "model": "claude-sonnet-4-20250514",
+            "content": [{"type": "text", "text": "Done"}],
+        },
+        "timestamp": "2025-09-11T13:28:09.638Z",
+    });
+    fs::write(
+        &transcript_path,
+        format!("{}\n{}\n", user_line, assistant_line),
+    )
+    .unwrap();
+
+    let commit = repo.stage_all_and_commit("Add example").unwrap();
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("Expected a prompt in the authorship log");
+    assert_eq!(
+        prompt.messages.len(),
+        2,
+        "Expected the refetched transcript"
+    );
+    match prompt.messages.first() {
+        Some(Message::User { text, .. }) => assert_eq!(text, "Ask jane@example.com"),
+        other => panic!("Expected a user message, got {:?}", other),
+    }
+}
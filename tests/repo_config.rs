@@ -0,0 +1,113 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn write_shared_config(repo: &TestRepo, content: &str) {
+    fs::write(repo.path().join(".git-ai.toml"), content).unwrap();
+}
+
+fn write_local_config(repo: &TestRepo, content: &str) {
+    let dir = repo.path().join(".git").join("ai");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("config.toml"), content).unwrap();
+}
+
+/// Commit through the wrapper; `TestRepo::commit` reads notes from the default ref
+fn commit_all(repo: &TestRepo, message: &str) -> String {
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", message]).unwrap();
+    repo.git(&["rev-parse", "HEAD"]).unwrap().trim().to_string()
+}
+
+fn notes_in(repo: &TestRepo, notes_ref: &str) -> String {
+    repo.git_og(&["notes", &format!("--ref={}", notes_ref), "list"])
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_shared_config_sets_notes_ref() {
+    let repo = TestRepo::new();
+    write_shared_config(&repo, "notes_ref = \"team-ai\"\n");
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai(), "fn two() {}"]);
+    let commit = commit_all(&repo, "AI function");
+
+    assert!(
+        notes_in(&repo, "team-ai").contains(&commit),
+        "note should be written to refs/notes/team-ai"
+    );
+    assert!(notes_in(&repo, "ai").is_empty());
+    file.assert_lines_and_blame(lines!["fn one() {}".ai(), "fn two() {}".human()]);
+
+    let output = repo.git_ai(&["config", "notes_ref"]).unwrap();
+    assert!(output.contains("team-ai"), "{output}");
+}
+
+#[test]
+fn test_local_config_overrides_shared_config() {
+    let repo = TestRepo::new();
+    write_shared_config(&repo, "notes_ref = \"team-ai\"\n");
+    write_local_config(&repo, "notes_ref = \"mine\"\n");
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai()]);
+    let commit = commit_all(&repo, "AI function");
+
+    assert!(notes_in(&repo, "mine").contains(&commit));
+    assert!(notes_in(&repo, "team-ai").is_empty());
+}
+
+#[test]
+fn test_exclude_paths_leave_files_out_of_stats() {
+    let repo = TestRepo::new();
+    write_shared_config(&repo, "exclude_paths = [\"generated/**\"]\n");
+    repo.stage_all_and_commit("Add config").unwrap();
+
+    let mut source = repo.filename("src/lib.rs");
+    source.set_contents(lines!["fn one() {}"]);
+    let mut generated = repo.filename("generated/schema.rs");
+    generated.set_contents(lines!["// one", "// two", "// three"]);
+    repo.stage_all_and_commit("Add files").unwrap();
+
+    let output = repo.git_ai(&["stats", "--json"]).unwrap();
+    let json = &output[output.find('{').unwrap()..=output.rfind('}').unwrap()];
+    let stats: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(stats["human_additions"], 1, "{output}");
+}
+
+#[test]
+fn test_show_origin_lists_layers_in_precedence_order() {
+    let repo = TestRepo::new();
+    write_shared_config(
+        &repo,
+        "notes_ref = \"team-ai\"\napi_base_url = \"https://example.com\"\n\n[redaction]\nenabled = false\n",
+    );
+    write_local_config(&repo, "notes_ref = \"mine\"\n");
+
+    let output = repo.git_ai(&["config", "--show-origin"]).unwrap();
+    let shared = format!(
+        "file:{}\tnotes_ref=team-ai",
+        repo.path().join(".git-ai.toml").display()
+    );
+    let local = format!(
+        "file:{}\tnotes_ref=mine",
+        repo.path().join(".git/ai/config.toml").display()
+    );
+    let shared_at = output.find(&shared).expect(&output);
+    let local_at = output.find(&local).expect(&output);
+    assert!(shared_at < local_at, "{output}");
+    assert!(
+        output.contains("'api_base_url' can't be set per repository"),
+        "{output}"
+    );
+    assert!(
+        output.contains("redaction.enabled = false is ignored"),
+        "{output}"
+    );
+
+    let redaction = repo.git_ai(&["config", "redaction.enabled"]).unwrap();
+    assert!(redaction.contains("true"), "{redaction}");
+}
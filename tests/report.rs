@@ -0,0 +1,72 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_report_writes_standalone_html() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project"]);
+    let base = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::create_dir_all(repo.path().join("src/api")).unwrap();
+    let mut handler = repo.filename("src/api/handler.rs");
+    handler.set_contents(lines![
+        "fn handle() {}".human(),
+        "fn generated() {}".ai(),
+        "fn generated_too() {}".ai(),
+        "fn generated_three() {}".ai()
+    ]);
+    repo.stage_all_and_commit("Add handler").unwrap();
+
+    readme.set_contents(lines!["# Project", "Written by hand"]);
+    repo.stage_all_and_commit("Docs").unwrap();
+
+    let output = repo.path().join("report.html");
+    let range = format!("{}..HEAD", base.commit_sha);
+    let stderr = repo
+        .git_ai(&[
+            "report",
+            &range,
+            "--bucket",
+            "day",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .unwrap();
+    assert!(stderr.contains("Wrote report to"), "{}", stderr);
+
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("daily buckets in UTC"), "{}", html);
+    // Two commits; rewriting README's last line counts it as added again
+    assert!(html.contains("<div class=\"value\">2</div><div class=\"label\">commits"));
+    assert!(html.contains("<div class=\"value\">50%</div>"), "{}", html);
+    // Heatmap rows for both directories, HTML-escaped
+    assert!(html.contains("<code>src&#x2f;api</code>"), "{}", html);
+    assert!(html.contains("title=\"3 AI / 4 lines\""), "{}", html);
+    assert!(html.contains("<code>.</code>"), "{}", html);
+    // All three AI lines survive, credited to one prompt
+    assert!(html.contains("<td class=\"num\">3</td>"), "{}", html);
+    assert!(html.contains("mock_ai"), "{}", html);
+    assert!(!html.contains("No AI lines from this range survive"));
+
+    // Without --output the page goes to stdout
+    let stdout = repo.git_ai(&["report", &range]).unwrap();
+    assert!(stdout.contains("AI authorship report"));
+}
+
+#[test]
+fn test_report_requires_a_range() {
+    let repo = TestRepo::new();
+    repo.filename("a.txt").set_contents(lines!["a"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let err = repo
+        .git_ai(&["report", "HEAD"])
+        .expect_err("a range is required");
+    assert!(err.contains("Usage: git-ai report"), "{}", err);
+}
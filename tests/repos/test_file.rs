@@ -7,6 +7,7 @@ use insta::assert_debug_snapshot;
 /// AI author names that indicate AI-generated content
 const AI_AUTHOR_NAMES: &[&str] = &[
     "mock_ai",
+    "mock-agent",
     "claude",
     "continue-cli",
     "gpt",
@@ -14,6 +15,8 @@ const AI_AUTHOR_NAMES: &[&str] = &[
     "cursor",
     "codex",
     "gemini",
+    "aider",
+    "zed",
 ];
 
 #[derive(Debug, Clone, PartialEq)]
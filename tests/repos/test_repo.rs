@@ -287,6 +287,8 @@ impl TestRepo {
             .args(["git-hooks", "ensure"]);
         self.configure_git_ai_env(&mut command);
         command.env("GIT_AI_TEST_DB_PATH", self.test_db_path.to_str().unwrap());
+        // Output assertions are in English whatever the developer's locale
+        command.env("GIT_AI_LOCALE", "en");
 
         let output = command
             .output()
@@ -355,6 +357,11 @@ impl TestRepo {
         &self.test_db_path
     }
 
+    /// Use `other`'s internal database, as two repositories on one machine would
+    pub fn share_db_with(&mut self, other: &TestRepo) {
+        self.test_db_path = other.test_db_path.clone();
+    }
+
     pub fn test_home_path(&self) -> &PathBuf {
         &self.test_home
     }
@@ -504,6 +511,7 @@ impl TestRepo {
             command.env("GIT_AI_TEST_CONFIG_PATCH", patch_json);
         }
         command.env("GIT_AI_TEST_DB_PATH", self.test_db_path.to_str().unwrap());
+        command.env("GIT_AI_LOCALE", "en");
 
         // Add custom environment variables
         for (key, value) in envs {
@@ -558,6 +566,7 @@ impl TestRepo {
         }
 
         command.env("GIT_AI_TEST_DB_PATH", self.test_db_path.to_str().unwrap());
+        command.env("GIT_AI_LOCALE", "en");
 
         let output = command
             .output()
@@ -596,6 +605,7 @@ impl TestRepo {
 
         // Add test database path for isolation
         command.env("GIT_AI_TEST_DB_PATH", self.test_db_path.to_str().unwrap());
+        command.env("GIT_AI_LOCALE", "en");
 
         // Add custom environment variables
         for (key, value) in envs {
@@ -680,6 +690,49 @@ impl TestRepo {
         }
     }
 
+    /// Run the `mock-agent` binary in this repo with the given script (see
+    /// src/bin/mock-agent.rs for the format). Its edits fire real agent-v1 hooks
+    /// against the test git-ai binary, with this repo's config patch and database.
+    pub fn mock_agent(&self, script: &serde_json::Value) -> Result<String, String> {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let mut command = Command::new(get_mock_agent_path());
+        command
+            .args(["--git-ai", get_binary_path().to_str().unwrap()])
+            .current_dir(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        self.configure_git_ai_env(&mut command);
+
+        if let Some(patch) = &self.config_patch
+            && let Ok(patch_json) = serde_json::to_string(patch)
+        {
+            command.env("GIT_AI_TEST_CONFIG_PATCH", patch_json);
+        }
+        command.env("GIT_AI_TEST_DB_PATH", self.test_db_path.to_str().unwrap());
+        command.env("GIT_AI_LOCALE", "en");
+
+        let mut child = command.spawn().expect("Failed to spawn mock-agent");
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(script.to_string().as_bytes())
+                .expect("Failed to write mock-agent script");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for mock-agent");
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if output.status.success() {
+            Ok(format!("{}{}", stdout, stderr))
+        } else {
+            Err(stderr)
+        }
+    }
+
     pub fn filename(&self, filename: &str) -> TestFile<'_> {
         let file_path = self.path.join(filename);
 
@@ -861,7 +914,15 @@ fn compile_binary() -> PathBuf {
 
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     let output = Command::new("cargo")
-        .args(["build", "--bin", "git-ai", "--features", "test-support"])
+        .args([
+            "build",
+            "--bin",
+            "git-ai",
+            "--bin",
+            "mock-agent",
+            "--features",
+            "test-support",
+        ])
         .current_dir(manifest_dir)
         .output()
         .expect("Failed to compile git-ai binary");
@@ -895,3 +956,9 @@ fn compile_binary() -> PathBuf {
 pub(crate) fn get_binary_path() -> &'static PathBuf {
     COMPILED_BINARY.get_or_init(compile_binary)
 }
+
+/// The `mock-agent` binary, built alongside git-ai by `compile_binary`
+pub(crate) fn get_mock_agent_path() -> PathBuf {
+    let git_ai = get_binary_path();
+    git_ai.with_file_name(format!("mock-agent{}", std::env::consts::EXE_SUFFIX))
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn authorship_log(
+    repo: &TestRepo,
+    commit_sha: &str,
+) -> git_ai::authorship::authorship_log_serialization::AuthorshipLog {
+    git_ai::git::refs::get_reference_as_authorship_log_v3(
+        &git_ai::git::find_repository_in_path(repo.path().to_str().unwrap()).unwrap(),
+        commit_sha,
+    )
+    .unwrap()
+}
+
+/// Reverting an AI commit records the undone lines as overridden
+#[test]
+fn test_revert_of_ai_commit_counts_lines_as_overridden() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["Initial content"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["AI line 1".ai(), "AI line 2".ai()]);
+    let ai_commit = repo.stage_all_and_commit("Add AI lines").unwrap();
+
+    repo.git(&["revert", "--no-edit", &ai_commit.commit_sha])
+        .unwrap();
+
+    file.assert_lines_and_blame(lines!["Initial content".human()]);
+
+    let head = repo.git(&["rev-parse", "HEAD"]).unwrap().trim().to_string();
+    let log = authorship_log(&repo, &head);
+    assert!(log.attestations.is_empty());
+    assert_eq!(log.metadata.prompts.len(), 1);
+    let prompt = log.metadata.prompts.values().next().unwrap();
+    assert_eq!(prompt.accepted_lines, 0);
+    assert_eq!(prompt.overriden_lines, 2);
+}
+
+/// Lines brought back by a revert keep the attribution they had before
+#[test]
+fn test_revert_restores_attribution_of_deleted_ai_lines() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines![
+        "Header",
+        "AI line 1".ai(),
+        "AI line 2".ai(),
+        "Footer"
+    ]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["Header", "Footer"]);
+    let deletion = repo.stage_all_and_commit("Remove AI lines").unwrap();
+
+    repo.git(&["revert", "--no-edit", &deletion.commit_sha])
+        .unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "Header".human(),
+        "AI line 1".ai(),
+        "AI line 2".ai(),
+        "Footer".human(),
+    ]);
+}
+
+/// `git revert --no-commit` leaves authorship to the commit that follows
+#[test]
+fn test_revert_no_commit_is_handled_by_following_commit() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["Initial content"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["AI line".ai()]);
+    let ai_commit = repo.stage_all_and_commit("Add AI line").unwrap();
+
+    repo.git(&["revert", "--no-commit", &ai_commit.commit_sha])
+        .unwrap();
+    assert_eq!(
+        repo.git(&["rev-parse", "HEAD"]).unwrap().trim(),
+        ai_commit.commit_sha
+    );
+
+    repo.stage_all_and_commit("Revert AI line").unwrap();
+    file.assert_lines_and_blame(lines!["Initial content".human()]);
+}
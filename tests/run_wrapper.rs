@@ -0,0 +1,52 @@
+#![cfg(unix)]
+
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_run_checkpoints_wrapped_agent_edits() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join("notes.txt"), "base\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // Pending human work is checkpointed before the agent starts
+    fs::write(repo.path().join("notes.txt"), "base\nhuman line\n").unwrap();
+
+    repo.git_ai(&[
+        "run",
+        "--model",
+        "test-model",
+        "--",
+        "sh",
+        "-c",
+        "printf 'agent line\\n' > generated.txt",
+    ])
+    .expect("git-ai run should succeed");
+
+    let commit = repo.stage_all_and_commit("Agent run").unwrap();
+    let prompts = &commit.authorship_log.metadata.prompts;
+    assert_eq!(prompts.len(), 1);
+    let prompt = prompts.values().next().unwrap();
+    assert_eq!(prompt.agent_id.tool, "sh");
+    assert_eq!(prompt.agent_id.model, "test-model");
+
+    let ai_files: Vec<&str> = commit
+        .authorship_log
+        .attestations
+        .iter()
+        .map(|a| a.file_path.as_str())
+        .collect();
+    assert_eq!(ai_files, vec!["generated.txt"]);
+}
+
+#[test]
+fn test_run_exits_with_wrapped_status() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join("README.md"), "hi\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let result = repo.git_ai(&["run", "--", "sh", "-c", "exit 3"]);
+    assert!(result.is_err());
+}
@@ -0,0 +1,91 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+fn schema(repo: &TestRepo, name: &str) -> Value {
+    let output = repo
+        .git_ai_with_env(&["schema", name], &[("GIT_AI_DEBUG", "0")])
+        .unwrap_or_else(|e| panic!("git-ai schema {} failed: {}", name, e));
+    serde_json::from_str(&output).expect("schema should be JSON")
+}
+
+/// Check the top-level shape of `value` against an object schema: every key is
+/// declared and every required key is present
+fn assert_matches_object_schema(value: &Value, schema: &Value) {
+    let properties = schema["properties"].as_object().expect("object schema");
+    let object = value.as_object().expect("JSON object");
+    for key in object.keys() {
+        assert!(properties.contains_key(key), "{} is not in the schema", key);
+    }
+    for required in schema["required"].as_array().into_iter().flatten() {
+        let required = required.as_str().unwrap();
+        assert!(object.contains_key(required), "{} is missing", required);
+    }
+}
+
+#[test]
+fn test_schema_lists_available_schemas() {
+    let repo = TestRepo::new();
+    let output = repo
+        .git_ai_with_env(&["schema"], &[("GIT_AI_DEBUG", "0")])
+        .unwrap();
+    for name in [
+        "blame-json",
+        "blame-json-lines",
+        "blame-directory-json",
+        "stats-json",
+        "diff-json",
+        "checkpoint-agent-v1",
+        "authorship-note-v3",
+        "authorship-note-v4",
+        "tag-attribution-note",
+        "require-notes-json",
+        "changed-ai-files-json",
+        "context-json",
+        "correlate-json",
+    ] {
+        assert!(output.contains(name), "{} not listed in:\n{}", name, output);
+    }
+
+    let err = repo
+        .git_ai_with_env(&["schema", "no-such-schema"], &[("GIT_AI_DEBUG", "0")])
+        .expect_err("unknown schema should fail");
+    assert!(err.contains("Unknown schema"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_json_outputs_match_their_schemas() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}", "fn two() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    file.insert_at(1, lines!["fn three() {}".ai()]);
+    repo.stage_all_and_commit("Add three").unwrap();
+
+    let blame_schema = schema(&repo, "blame-json-lines");
+    let blame = repo.git_ai(&["blame", "--json-lines", "lib.rs"]).unwrap();
+    let records: Vec<Value> = blame
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    assert_eq!(records.len(), 3);
+    for record in &records {
+        assert_matches_object_schema(record, &blame_schema);
+    }
+
+    let stats_schema = schema(&repo, "stats-json");
+    let stats = repo.git_ai(&["stats", "--json"]).unwrap();
+    let stats_line = stats
+        .lines()
+        .find(|line| line.starts_with('{'))
+        .expect("stats JSON");
+    assert_matches_object_schema(&serde_json::from_str(stats_line).unwrap(), &stats_schema);
+
+    let note_schema = schema(&repo, "authorship-note-v3");
+    let note = repo.git(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    let (_, metadata) = note.split_once("---\n").expect("note divider");
+    assert_matches_object_schema(&serde_json::from_str(metadata).unwrap(), &note_schema);
+}
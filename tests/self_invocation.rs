@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::{TestRepo, get_binary_path};
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+/// A "git" that is really a script exec'ing git-ai, as left behind by a broken install
+fn looping_git(repo: &TestRepo) -> std::path::PathBuf {
+    let script = repo.path().with_extension("fake-git");
+    std::fs::write(
+        &script,
+        format!("#!/bin/sh\nexec '{}' \"$@\"\n", get_binary_path().display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+#[test]
+fn test_wrapper_falls_back_when_git_resolves_to_git_ai() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["one".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let script = looping_git(&repo);
+    let output = Command::new(get_binary_path())
+        .args(["-C", repo.path().to_str().unwrap(), "log", "--format=%s"])
+        .env("GIT_AI", "git")
+        .env("GIT_AI_TEST_GIT_PATH", &script)
+        .output()
+        .expect("failed to run git-ai");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(stdout.trim(), "Initial commit");
+    assert!(stderr.contains("resolves back to git-ai"), "{}", stderr);
+    assert!(stderr.contains("falling back to"), "{}", stderr);
+}
+
+#[test]
+fn test_wrapper_stops_runaway_nesting() {
+    let repo = TestRepo::new();
+    let output = Command::new(get_binary_path())
+        .args(["-C", repo.path().to_str().unwrap(), "status"])
+        .env("GIT_AI", "git")
+        .env("GIT_AI_PROXY_DEPTH", "17")
+        .output()
+        .expect("failed to run git-ai");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("nested 17 levels deep"), "{}", stderr);
+}
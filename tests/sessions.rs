@@ -0,0 +1,102 @@
+mod repos;
+
+use git_ai::authorship::transcript::{AiTranscript, Message};
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+use std::fs;
+
+/// Checkpoint an edit to `file` by the agent session `session_id`
+fn agent_edit(repo: &TestRepo, session_id: &str, file: &str) {
+    fs::write(
+        repo.path().join(file),
+        format!("written by {}\n", session_id),
+    )
+    .unwrap();
+
+    let mut transcript = AiTranscript::new();
+    transcript.add_message(Message::user("Split the client out".to_string(), None));
+    let hook_input = serde_json::json!({
+        "type": "ai_agent",
+        "repo_working_dir": repo.path().to_str().unwrap(),
+        "edited_filepaths": [file],
+        "transcript": transcript,
+        "agent_name": "test-agent",
+        "model": "test-model",
+        "conversation_id": session_id,
+    });
+    let hook_input = serde_json::to_string(&hook_input).unwrap();
+    repo.git_ai(&["checkpoint", "agent-v1", "--hook-input", &hook_input])
+        .expect("checkpoint should succeed");
+}
+
+fn session_json(repo: &TestRepo, id: &str) -> Value {
+    let output = repo
+        .git_ai_with_env(
+            &["sessions", "show", id, "--json"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap_or_else(|e| panic!("sessions show failed: {}", e));
+    serde_json::from_str(&output).unwrap_or_else(|e| panic!("{}: {}", e, output))
+}
+
+#[test]
+fn test_sessions_show_stitches_repositories() {
+    let app = TestRepo::new();
+    let mut client = TestRepo::new();
+    client.share_db_with(&app);
+
+    agent_edit(&app, "shared-session", "app.txt");
+    let app_commit = app.stage_all_and_commit("Use the new client").unwrap();
+    agent_edit(&client, "shared-session", "client.txt");
+    let client_commit = client.stage_all_and_commit("Extract client").unwrap();
+    agent_edit(&client, "shared-session", "more.txt");
+
+    let json = session_json(&app, "shared-session");
+    assert_eq!(json["session_id"], "shared-session");
+    assert_eq!(json["tool"], "test-agent");
+
+    let repositories = json["repositories"].as_array().unwrap();
+    assert_eq!(repositories.len(), 2, "{json}");
+    let workdirs: Vec<&str> = repositories
+        .iter()
+        .map(|repo| repo["workdir"].as_str().unwrap())
+        .collect();
+    assert!(workdirs[0].ends_with(app.path().file_name().unwrap().to_str().unwrap()));
+    assert!(workdirs[1].ends_with(client.path().file_name().unwrap().to_str().unwrap()));
+
+    assert_eq!(
+        repositories[0]["commits"][0]["sha"],
+        app_commit.commit_sha.as_str()
+    );
+    assert_eq!(
+        repositories[0]["commits"][0]["subject"],
+        "Use the new client"
+    );
+    assert_eq!(repositories[0]["uncommitted"], false);
+    assert_eq!(
+        repositories[1]["commits"][0]["sha"],
+        client_commit.commit_sha.as_str()
+    );
+    assert_eq!(repositories[1]["uncommitted"], true);
+
+    // The prompt id resolves to the same session
+    let by_prompt_id = session_json(&client, json["prompt_id"].as_str().unwrap());
+    assert_eq!(by_prompt_id["repositories"], json["repositories"]);
+
+    let text = app
+        .git_ai_with_env(
+            &["sessions", "show", "shared-session"],
+            &[("GIT_AI_DEBUG", "0")],
+        )
+        .unwrap();
+    assert!(text.contains("2 repositories, 2 commits"), "{text}");
+    assert!(text.contains("Extract client"), "{text}");
+    assert!(text.contains("(uncommitted checkpoints)"), "{text}");
+}
+
+#[test]
+fn test_sessions_show_unknown_session() {
+    let repo = TestRepo::new();
+    let err = repo.git_ai(&["sessions", "show", "nope"]).unwrap_err();
+    assert!(err.contains("No session found"), "{err}");
+}
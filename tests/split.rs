@@ -0,0 +1,66 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_split_by_author_separates_human_and_ai_changes() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+
+    file.insert_at(0, lines!["fn human() {}", "fn ai() {}".ai()]);
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["written by hand"]);
+    let original = repo.stage_all_and_commit("Mixed change").unwrap();
+
+    let output = repo.git_ai(&["split", "HEAD", "--by-author"]).unwrap();
+    assert!(output.contains("split into 2 commits"), "{output}");
+
+    let log = repo.git_og(&["log", "--format=%s", "-n", "3"]).unwrap();
+    let subjects: Vec<&str> = log.lines().collect();
+    assert!(subjects[0].starts_with("Mixed change (mock_ai "), "{log}");
+    assert_eq!(subjects[1], "Mixed change (human)", "{log}");
+    assert_eq!(subjects[2], "Base", "{log}");
+
+    // Same final tree as before
+    let tree = repo.git_og(&["rev-parse", "HEAD^{tree}"]).unwrap();
+    let original_tree = repo
+        .git_og(&["rev-parse", &format!("{}^{{tree}}", original.commit_sha)])
+        .unwrap();
+    assert_eq!(tree, original_tree);
+
+    // The human commit has the human line and file but not the AI line
+    let human_file = repo.git_og(&["show", "HEAD~1:lib.rs"]).unwrap();
+    assert!(human_file.contains("fn human() {}"), "{human_file}");
+    assert!(!human_file.contains("fn ai() {}"), "{human_file}");
+    assert!(repo.git_og(&["show", "HEAD~1:notes.txt"]).is_ok());
+
+    file.assert_lines_and_blame(lines![
+        "fn human() {}".human(),
+        "fn ai() {}".ai(),
+        "fn base() {}".human(),
+    ]);
+
+    let shares = repo.git_ai(&["log", "-n", "2"]).unwrap();
+    let shares: Vec<&str> = shares.lines().collect();
+    assert!(shares[0].contains(" 100% AI "), "{shares:?}");
+    assert!(shares[1].contains("   0% AI "), "{shares:?}");
+}
+
+#[test]
+fn test_split_single_origin_is_a_no_op() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai(), "fn two() {}".ai()]);
+    let commit = repo.stage_all_and_commit("All AI").unwrap();
+
+    let output = repo.git_ai(&["split", "--by-author"]).unwrap();
+    assert!(output.contains("nothing to split"), "{output}");
+    let head = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+    assert_eq!(head.trim(), commit.commit_sha);
+
+    let err = repo.git_ai(&["split", "HEAD"]).unwrap_err();
+    assert!(err.contains("missing --by-author"), "{err}");
+}
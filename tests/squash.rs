@@ -0,0 +1,78 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_squash_merges_constituent_attributions() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    let base = repo.stage_all_and_commit("Base").unwrap();
+
+    file.insert_at(0, lines!["fn ai_one() {}".ai()]);
+    repo.stage_all_and_commit("Add AI function").unwrap();
+
+    file.insert_at(1, lines!["fn human() {}"]);
+    let mut other = repo.filename("other.rs");
+    other.set_contents(lines!["fn other_ai() {}".ai()]);
+    repo.stage_all_and_commit("Add human function and other file")
+        .unwrap();
+
+    file.insert_at(0, lines!["fn ai_two() {}".ai()]);
+    let tip = repo
+        .stage_all_and_commit("Add another AI function")
+        .unwrap();
+
+    let output = repo
+        .git_ai(&[
+            "squash",
+            &format!("{}..HEAD", base.commit_sha),
+            "-m",
+            "Functions",
+        ])
+        .unwrap();
+    assert!(output.contains("Squashed 3 commits"), "{output}");
+
+    let log = repo.git_og(&["log", "--format=%s"]).unwrap();
+    assert_eq!(log.lines().collect::<Vec<_>>(), vec!["Functions", "Base"]);
+    let tree = repo.git_og(&["rev-parse", "HEAD^{tree}"]).unwrap();
+    let tip_tree = repo
+        .git_og(&["rev-parse", &format!("{}^{{tree}}", tip.commit_sha)])
+        .unwrap();
+    assert_eq!(tree, tip_tree);
+
+    file.assert_lines_and_blame(lines![
+        "fn ai_two() {}".ai(),
+        "fn ai_one() {}".ai(),
+        "fn human() {}".human(),
+        "fn base() {}".human(),
+    ]);
+    other.assert_lines_and_blame(lines!["fn other_ai() {}".ai()]);
+
+    let shares = repo.git_ai(&["log", "-n", "1"]).unwrap();
+    assert!(shares.contains("  75% AI "), "{shares}");
+}
+
+#[test]
+fn test_squash_rejects_ranges_not_ending_at_head() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}"]);
+    let first = repo.stage_all_and_commit("One").unwrap();
+    file.insert_at(1, lines!["fn two() {}".ai()]);
+    let second = repo.stage_all_and_commit("Two").unwrap();
+    file.insert_at(2, lines!["fn three() {}"]);
+    repo.stage_all_and_commit("Three").unwrap();
+
+    let err = repo
+        .git_ai(&[
+            "squash",
+            &format!("{}..{}", first.commit_sha, second.commit_sha),
+        ])
+        .unwrap_err();
+    assert!(err.contains("must end at HEAD"), "{err}");
+
+    let err = repo.git_ai(&["squash", "HEAD~1"]).unwrap_err();
+    assert!(err.contains("nothing to squash"), "{err}");
+}
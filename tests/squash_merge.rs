@@ -293,3 +293,63 @@ fn test_prepare_working_log_squash_with_mixed_additions() {
         "Sum of accepted_lines across prompts should match ai_accepted stat"
     );
 }
+
+/// merge --squash across many files gives the same attribution whether files are
+/// blamed one at a time or in parallel
+#[test]
+fn test_squash_merge_many_files_same_attribution_at_any_blame_parallelism() {
+    for parallelism in [1, 8] {
+        let mut repo = TestRepo::new();
+        repo.patch_git_ai_config(|patch| {
+            patch.blame_parallelism = Some(parallelism);
+        });
+
+        let mut readme = repo.filename("README.md");
+        readme.set_contents(lines!["# Project"]);
+        repo.stage_all_and_commit("Initial commit").unwrap();
+        let default_branch = repo.current_branch();
+
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        let mut files = Vec::new();
+        for i in 0..12 {
+            let mut file = repo.filename(&format!("src/module_{}.rs", i));
+            if i % 3 == 0 {
+                file.set_contents(lines![format!("// human module {}", i)]);
+            } else {
+                file.set_contents(lines![
+                    format!("// ai module {}", i).ai(),
+                    format!("fn f{}() {{}}", i).ai()
+                ]);
+            }
+            files.push(file);
+        }
+        repo.stage_all_and_commit("Add modules").unwrap();
+
+        repo.git(&["checkout", &default_branch]).unwrap();
+        repo.git(&["merge", "--squash", "feature"]).unwrap();
+        repo.commit("Squashed modules").unwrap();
+
+        for (i, file) in files.iter_mut().enumerate() {
+            if i % 3 == 0 {
+                file.assert_lines_and_blame(lines![format!("// human module {}", i).human()]);
+            } else {
+                file.assert_lines_and_blame(lines![
+                    format!("// ai module {}", i).ai(),
+                    format!("fn f{}() {{}}", i).ai()
+                ]);
+            }
+        }
+
+        let stats = repo.stats().unwrap();
+        assert_eq!(
+            stats.ai_additions, 16,
+            "blame_parallelism = {}",
+            parallelism
+        );
+        assert_eq!(
+            stats.human_additions, 4,
+            "blame_parallelism = {}",
+            parallelism
+        );
+    }
+}
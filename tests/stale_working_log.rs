@@ -0,0 +1,125 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// A commit made without git-ai's hooks gets its note at the next checkpoint
+#[test]
+fn test_commit_without_hooks_is_noted_at_next_checkpoint() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+
+    file.insert_at(1, lines!["fn ai() {}".ai()]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "Committed by another tool"])
+        .unwrap();
+    let head = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["human notes"]);
+    repo.git_ai(&["checkpoint"]).unwrap();
+
+    assert!(
+        repo.git_og(&["notes", "--ref=ai", "show", head.trim()])
+            .is_ok(),
+        "external commit should have been noted"
+    );
+    file.assert_lines_and_blame(lines!["fn base() {}".human(), "fn ai() {}".ai()]);
+}
+
+/// When HEAD moves somewhere else without hooks, uncommitted AI lines carry over to
+/// the new base instead of being checkpointed against the old one
+#[test]
+fn test_uncommitted_attributions_follow_head_moved_without_hooks() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+
+    file.insert_at(1, lines!["fn ai() {}".ai()]);
+    repo.git_og(&["commit", "--allow-empty", "-m", "Empty one"])
+        .unwrap();
+    repo.git_og(&["commit", "--allow-empty", "-m", "Empty two"])
+        .unwrap();
+
+    file.insert_at(0, lines!["// header"]);
+    repo.stage_all_and_commit("Add functions").unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "// header".human(),
+        "fn base() {}".human(),
+        "fn ai() {}".ai(),
+    ]);
+}
+
+/// Several commits made while post-commit wasn't running are found in the reflog
+/// and noted by the next checkpoint; read-only commands leave the notes alone
+#[test]
+fn test_missed_commits_are_noted_from_reflog_by_next_checkpoint() {
+    let repo = TestRepo::new();
+    let mut first = repo.filename("first.rs");
+    let mut second = repo.filename("second.rs");
+    first.set_contents(lines!["fn base() {}"]);
+    second.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+
+    first.insert_at(1, lines!["fn first() {}".ai()]);
+    second.insert_at(1, lines!["fn second() {}".ai()]);
+    repo.git_og(&["add", "first.rs"]).unwrap();
+    repo.git_og(&["commit", "-m", "First without hooks"])
+        .unwrap();
+    let first_sha = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+    repo.git_og(&["add", "second.rs"]).unwrap();
+    repo.git_og(&["commit", "-m", "Second without hooks"])
+        .unwrap();
+    let second_sha = repo.git_og(&["rev-parse", "HEAD"]).unwrap();
+
+    repo.git_ai(&["stats", "HEAD"]).unwrap();
+    assert!(
+        repo.git_og(&["notes", "--ref=ai", "show", second_sha.trim()])
+            .is_err(),
+        "stats should not write notes"
+    );
+
+    repo.git_ai(&["checkpoint"]).unwrap();
+
+    for (sha, file) in [(&first_sha, "first.rs"), (&second_sha, "second.rs")] {
+        let note = repo
+            .git_og(&["notes", "--ref=ai", "show", sha.trim()])
+            .expect("missed commit should have been noted");
+        assert!(note.contains(file), "{}", note);
+    }
+    first.assert_lines_and_blame(lines!["fn base() {}".human(), "fn first() {}".ai()]);
+    second.assert_lines_and_blame(lines!["fn base() {}".human(), "fn second() {}".ai()]);
+}
+
+/// When HEAD moves to a commit that isn't a child of the working log's base (a
+/// checkout by another tool), uncommitted AI lines are rebuilt against the new HEAD
+#[test]
+fn test_uncommitted_attributions_follow_checkout_without_hooks() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+    let main = repo.current_branch();
+
+    repo.git_og(&["checkout", "-b", "other"]).unwrap();
+    let mut other = repo.filename("other.rs");
+    other.set_contents(lines!["fn other() {}"]);
+    repo.stage_all_and_commit("Other").unwrap();
+    repo.git_og(&["checkout", &main]).unwrap();
+
+    file.insert_at(1, lines!["fn ai() {}".ai()]);
+    repo.git_og(&["checkout", "other"]).unwrap();
+
+    file.insert_at(0, lines!["// header"]);
+    repo.stage_all_and_commit("Add functions").unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "// header".human(),
+        "fn base() {}".human(),
+        "fn ai() {}".ai(),
+    ]);
+}
@@ -997,3 +997,70 @@ fn test_stash_apply_reset_apply_again() {
         "Expected AI prompts in authorship log after multiple apply/reset cycles"
     );
 }
+
+#[test]
+fn test_stash_pop_remaps_lines_after_head_moves() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("notes.txt");
+    file.set_contents(vec!["one".human(), "two".human(), "three".human()]);
+    repo.stage_all_and_commit("initial commit")
+        .expect("commit should succeed");
+
+    file.set_contents(vec![
+        "one".human(),
+        "two".human(),
+        "three".human(),
+        "ai four".ai(),
+        "ai five".ai(),
+    ]);
+    repo.git(&["stash"]).expect("stash should succeed");
+
+    // Lines added above the stashed edits shift them down
+    file.set_contents(vec![
+        "zero a".human(),
+        "zero b".human(),
+        "one".human(),
+        "two".human(),
+        "three".human(),
+    ]);
+    repo.stage_all_and_commit("human adds header")
+        .expect("commit should succeed");
+
+    repo.git(&["stash", "pop"])
+        .expect("stash pop should succeed");
+    repo.stage_all_and_commit("apply stash")
+        .expect("commit should succeed");
+
+    file.assert_lines_and_blame(vec![
+        "zero a".human(),
+        "zero b".human(),
+        "one".human(),
+        "two".human(),
+        "three".human(),
+        "ai four".ai(),
+        "ai five".ai(),
+    ]);
+}
+
+#[test]
+fn test_stash_keeps_attribution_for_untracked_files_left_behind() {
+    let repo = TestRepo::new();
+
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(vec!["# Test Repo".to_string()]);
+    repo.stage_all_and_commit("initial commit")
+        .expect("commit should succeed");
+
+    // Tracked edit gets stashed; the untracked AI file stays in the worktree
+    readme.set_contents(vec!["# Test Repo".human(), "more".human()]);
+    let mut scratch = repo.filename("scratch.txt");
+    scratch.set_contents_no_stage(vec!["ai line 1".ai(), "ai line 2".ai()]);
+
+    repo.git(&["stash"]).expect("stash should succeed");
+    assert!(repo.read_file("scratch.txt").is_some());
+
+    repo.stage_all_and_commit("commit untracked file")
+        .expect("commit should succeed");
+    scratch.assert_lines_and_blame(vec!["ai line 1".ai(), "ai line 2".ai()]);
+}
@@ -187,6 +187,47 @@ fn test_stats_cli_range() {
     );
 }
 
+#[test]
+fn test_stats_cli_since_until_breakdown() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("range.txt");
+    file.set_contents(lines!["Line 1".human()]);
+    let first = repo.stage_all_and_commit("Initial human").unwrap();
+
+    file.set_contents(lines!["Line 1".human(), "Line 2".ai(), "Line 3".ai()]);
+    repo.stage_all_and_commit("AI adds lines").unwrap();
+
+    let since = format!("--since={}", first.commit_sha);
+    let raw = repo
+        .git_ai(&["stats", &since, "--until=HEAD", "--json"])
+        .expect("git-ai stats --since should succeed");
+    let stats: git_ai::authorship::range_authorship::RangeAuthorshipStats =
+        serde_json::from_str(&extract_json_object(&raw)).unwrap();
+
+    let breakdown = stats.breakdown.expect("--since reports the breakdown");
+    assert_eq!(breakdown.by_author.len(), 1);
+    let totals = breakdown.by_author.values().next().unwrap();
+    assert_eq!(totals.commits, 1);
+    assert_eq!(totals.ai_additions, 2);
+    assert_eq!(
+        breakdown
+            .by_tool
+            .values()
+            .map(|t| t.ai_additions)
+            .sum::<u32>(),
+        2
+    );
+    assert_eq!(
+        breakdown
+            .by_model
+            .values()
+            .map(|t| t.ai_additions)
+            .sum::<u32>(),
+        2
+    );
+}
+
 #[test]
 fn test_stats_cli_empty_tree_range() {
     let repo = TestRepo::new();
@@ -243,6 +284,7 @@ fn test_markdown_stats_deletion_only() {
         git_diff_deleted_lines: 5,
         git_diff_added_lines: 0,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -266,6 +308,7 @@ fn test_markdown_stats_all_human() {
         git_diff_deleted_lines: 0,
         git_diff_added_lines: 10,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -289,6 +332,7 @@ fn test_markdown_stats_all_ai() {
         git_diff_deleted_lines: 0,
         git_diff_added_lines: 15,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -312,6 +356,7 @@ fn test_markdown_stats_mixed() {
         git_diff_deleted_lines: 5,
         git_diff_added_lines: 30,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -335,6 +380,7 @@ fn test_markdown_stats_no_mixed() {
         git_diff_deleted_lines: 0,
         git_diff_added_lines: 20,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -359,6 +405,7 @@ fn test_markdown_stats_minimal_human() {
         git_diff_deleted_lines: 0,
         git_diff_added_lines: 100,
         tool_model_breakdown: BTreeMap::new(),
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -395,6 +442,7 @@ fn test_markdown_stats_formatting() {
         git_diff_deleted_lines: 2,
         git_diff_added_lines: 13,
         tool_model_breakdown,
+        human_contributors: BTreeMap::new(),
     };
 
     let markdown = write_stats_to_markdown(&stats);
@@ -620,3 +668,40 @@ fn test_post_commit_large_ignored_files_do_not_trigger_skip_warning() {
     assert_eq!(stats.ai_additions, 0);
     assert_eq!(stats.human_additions, 0);
 }
+
+#[test]
+fn test_stats_splits_human_additions_between_co_authors() {
+    let repo = TestRepo::new();
+    repo.filename("README.md").set_contents(lines!["# Repo"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.filename("src/lib.rs").set_contents(lines![
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "ai".ai()
+    ]);
+    let commit = repo
+        .stage_all_and_commit("Pair on lib\n\nCo-authored-by: Ana <ana@example.com>")
+        .unwrap();
+    assert_eq!(
+        commit.authorship_log.metadata.humans,
+        vec![
+            "Test User <test@example.com>".to_string(),
+            "Ana <ana@example.com>".to_string()
+        ]
+    );
+
+    let stats = stats_from_args(&repo, &["stats", "HEAD", "--json"]);
+    assert_eq!(stats.human_additions, 5);
+    assert_eq!(
+        stats.human_contributors.get("Test User <test@example.com>"),
+        Some(&3)
+    );
+    assert_eq!(
+        stats.human_contributors.get("Ana <ana@example.com>"),
+        Some(&2)
+    );
+}
@@ -0,0 +1,183 @@
+#[macro_use]
+mod repos;
+
+use git_ai::authorship::authorship_log::CharRange;
+use git_ai::authorship::authorship_log_serialization::{
+    AUTHORSHIP_LOG_VERSION, AUTHORSHIP_LOG_VERSION_V4, AuthorshipLog,
+};
+use repos::test_repo::TestRepo;
+
+/// Agent writes two lines, then a human swaps out one name on the second
+fn commit_lightly_edited_ai_line(repo: &TestRepo) -> repos::test_repo::NewCommit {
+    let mut file = repo.filename("calc.rs");
+    file.set_contents(lines!["// calc"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let path = repo.path().join("calc.rs");
+    std::fs::write(
+        &path,
+        "// calc\nlet subtotal = price * quantity;\nlet total = subtotal + shipping_cost;\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+
+    std::fs::write(
+        &path,
+        "// calc\nlet subtotal = price * quantity;\nlet total = subtotal + freight;\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint"]).unwrap();
+
+    repo.stage_all_and_commit("Add totals").unwrap()
+}
+
+#[test]
+fn test_line_granularity_writes_v3_without_char_ranges() {
+    let repo = TestRepo::new();
+    let commit = commit_lightly_edited_ai_line(&repo);
+
+    let log = &commit.authorship_log;
+    assert_eq!(log.metadata.schema_version, AUTHORSHIP_LOG_VERSION);
+    let file = &log.attestations[0];
+    assert!(file.entries.iter().all(|e| e.char_ranges.is_empty()));
+
+    let blame = repo.git_ai(&["blame", "calc.rs"]).unwrap();
+    assert!(!blame.contains("AI-assisted"), "{}", blame);
+}
+
+#[test]
+fn test_char_granularity_records_ai_parts_of_edited_lines() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.attribution_granularity = Some("char".to_string());
+    });
+    let commit = commit_lightly_edited_ai_line(&repo);
+
+    let log = &commit.authorship_log;
+    assert_eq!(log.metadata.schema_version, AUTHORSHIP_LOG_VERSION_V4);
+    let entry = &log.attestations[0].entries[0];
+    assert!(entry.line_ranges.iter().any(|range| range.contains(2)));
+    assert!(!entry.line_ranges.iter().any(|range| range.contains(3)));
+    // Everything but "freight" is still the agent's
+    assert_eq!(
+        entry.char_ranges,
+        vec![CharRange::new(3, 0, 23), CharRange::new(3, 30, 31)]
+    );
+
+    let blame = repo.git_ai(&["blame", "calc.rs"]).unwrap();
+    let line = blame
+        .lines()
+        .find(|line| line.contains("let total"))
+        .unwrap();
+    assert!(line.contains("AI-assisted"), "{}", blame);
+    let line = blame
+        .lines()
+        .find(|line| line.contains("let subtotal"))
+        .unwrap();
+    assert!(line.contains("mock_ai"), "{}", blame);
+}
+
+#[test]
+fn test_ai_assisted_threshold_above_ai_share_shows_human() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.attribution_granularity = Some("char".to_string());
+        patch.ai_assisted_threshold = Some(0.95);
+    });
+    commit_lightly_edited_ai_line(&repo);
+
+    let blame = repo.git_ai(&["blame", "calc.rs"]).unwrap();
+    let line = blame
+        .lines()
+        .find(|line| line.contains("let total"))
+        .unwrap();
+    assert!(!line.contains("AI-assisted"), "{}", blame);
+    assert!(line.contains("Test User"), "{}", blame);
+}
+
+fn char_ranges_at_head(repo: &TestRepo) -> (String, Vec<CharRange>) {
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    let log = AuthorshipLog::deserialize_from_string(&note).unwrap();
+    let char_ranges = log
+        .attestations
+        .iter()
+        .flat_map(|file| file.entries.iter())
+        .flat_map(|entry| entry.char_ranges.iter().cloned())
+        .collect();
+    (log.metadata.schema_version, char_ranges)
+}
+
+#[test]
+fn test_char_ranges_shift_through_rebase() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.attribution_granularity = Some("char".to_string());
+    });
+    let path = repo.path().join("calc.rs");
+    std::fs::write(&path, "// calc\nfn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    std::fs::write(
+        &path,
+        "// calc\nfn a() {}\nfn b() {}\nfn c() {}\nlet total = subtotal + shipping_cost;\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    std::fs::write(
+        &path,
+        "// calc\nfn a() {}\nfn b() {}\nfn c() {}\nlet total = subtotal + freight;\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint"]).unwrap();
+    repo.stage_all_and_commit("Add total").unwrap();
+    assert_eq!(
+        char_ranges_at_head(&repo).1,
+        vec![CharRange::new(5, 0, 23), CharRange::new(5, 30, 31)]
+    );
+
+    // The default branch adds a line above, so the rebased line moves down one
+    repo.git(&["checkout", &default_branch]).unwrap();
+    std::fs::write(
+        &path,
+        "// header\n// calc\nfn a() {}\nfn b() {}\nfn c() {}\n",
+    )
+    .unwrap();
+    repo.stage_all_and_commit("Add header").unwrap();
+    repo.git(&["checkout", "feature"]).unwrap();
+    repo.git(&["rebase", &default_branch]).unwrap();
+
+    let (schema_version, char_ranges) = char_ranges_at_head(&repo);
+    assert_eq!(schema_version, AUTHORSHIP_LOG_VERSION_V4);
+    assert_eq!(
+        char_ranges,
+        vec![CharRange::new(6, 0, 23), CharRange::new(6, 30, 31)]
+    );
+}
+
+#[test]
+fn test_char_ranges_survive_amend() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.attribution_granularity = Some("char".to_string());
+    });
+    commit_lightly_edited_ai_line(&repo);
+
+    // A human adds a line above the edited one and amends
+    let path = repo.path().join("calc.rs");
+    std::fs::write(
+        &path,
+        "// calc\n// totals\nlet subtotal = price * quantity;\nlet total = subtotal + freight;\n",
+    )
+    .unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "--amend", "--no-edit"]).unwrap();
+
+    let (schema_version, char_ranges) = char_ranges_at_head(&repo);
+    assert_eq!(schema_version, AUTHORSHIP_LOG_VERSION_V4);
+    assert_eq!(
+        char_ranges,
+        vec![CharRange::new(4, 0, 23), CharRange::new(4, 30, 31)]
+    );
+}
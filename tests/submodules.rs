@@ -0,0 +1,119 @@
+#[macro_use]
+mod repos;
+
+use git_ai::authorship::authorship_log_serialization::AuthorshipLog;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_submodule_edits_and_pointer_bumps_keep_attribution() {
+    let library = TestRepo::new();
+    std::fs::write(library.path().join("lib.rs"), "fn lib() {}\n").unwrap();
+    library.stage_all_and_commit("Initial commit").unwrap();
+
+    let repo = TestRepo::new();
+    repo.filename("main.rs")
+        .set_contents(lines!["fn main() {}".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.git_og(&[
+        "-c",
+        "protocol.file.allow=always",
+        "submodule",
+        "add",
+        library.path().to_str().unwrap(),
+        "lib",
+    ])
+    .unwrap();
+    let added = repo.commit("Add lib").unwrap();
+    let update = &added.authorship_log.metadata.submodules["lib"];
+    assert_eq!(update.from, None);
+    assert_eq!(
+        update.to,
+        library.git(&["rev-parse", "HEAD"]).unwrap().trim()
+    );
+    assert_eq!(update.ai_accepted, Some(0));
+
+    // An agent edits a file inside the submodule checkout
+    let submodule = repo.path().join("lib");
+    for (key, value) in [
+        ("user.name", "Test User"),
+        ("user.email", "test@example.com"),
+    ] {
+        repo.git_from_working_dir(&submodule, &["config", key, value])
+            .unwrap();
+    }
+    std::fs::write(
+        submodule.join("lib.rs"),
+        "fn lib() {}\nfn generated() {}\nfn generated_too() {}\n",
+    )
+    .unwrap();
+    repo.git_ai_from_working_dir(&submodule, &["checkpoint", "mock_ai"])
+        .unwrap();
+    repo.git_from_working_dir(&submodule, &["add", "-A"])
+        .unwrap();
+    repo.git_from_working_dir(&submodule, &["commit", "-m", "Generate"])
+        .unwrap();
+    let submodule_head = repo
+        .git_from_working_dir(&submodule, &["rev-parse", "HEAD"])
+        .unwrap();
+    let note = repo
+        .git_from_working_dir(
+            &submodule,
+            &["notes", "--ref=ai", "show", submodule_head.trim()],
+        )
+        .expect("the submodule commit has its own note");
+    assert!(note.contains("lib.rs"), "{}", note);
+
+    repo.git(&["add", "lib"]).unwrap();
+    let bumped = repo.commit("Bump lib").unwrap();
+    let update = &bumped.authorship_log.metadata.submodules["lib"];
+    assert_eq!(update.from.as_deref(), Some(added_to(&added)));
+    assert_eq!(update.to, submodule_head.trim());
+    assert_eq!(update.added_lines, Some(2));
+    assert_eq!(update.ai_accepted, Some(2));
+}
+
+#[test]
+fn test_submodule_bump_survives_amend() {
+    let library = TestRepo::new();
+    std::fs::write(library.path().join("lib.rs"), "fn lib() {}\n").unwrap();
+    library.stage_all_and_commit("Initial commit").unwrap();
+
+    let repo = TestRepo::new();
+    repo.filename("main.rs")
+        .set_contents(lines!["fn main() {}".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.git_og(&[
+        "-c",
+        "protocol.file.allow=always",
+        "submodule",
+        "add",
+        library.path().to_str().unwrap(),
+        "lib",
+    ])
+    .unwrap();
+    let added = repo.commit("Add lib").unwrap();
+
+    repo.filename("main.rs")
+        .set_contents(lines!["fn main() {}".human(), "fn helper() {}".ai()]);
+    repo.git(&["add", "main.rs"]).unwrap();
+    repo.git(&["commit", "--amend", "--no-edit"]).unwrap();
+
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    let log = AuthorshipLog::deserialize_from_string(&note).unwrap();
+    assert_eq!(
+        log.metadata.submodules["lib"],
+        added.authorship_log.metadata.submodules["lib"]
+    );
+    assert!(
+        log.attestations
+            .iter()
+            .any(|file| file.file_path == "main.rs"),
+        "{}",
+        note
+    );
+}
+
+fn added_to(commit: &repos::test_repo::NewCommit) -> &str {
+    &commit.authorship_log.metadata.submodules["lib"].to
+}
@@ -0,0 +1,79 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_suggest_cherry_picks_lists_mostly_ai_commits() {
+    let repo = TestRepo::new();
+    let mut base = repo.filename("base.rs");
+    base.set_contents(lines!["fn base() {}"]);
+    repo.stage_all_and_commit("Base").unwrap();
+    let main = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut fix = repo.filename("fix.rs");
+    fix.set_contents(lines!["fn fix() {}".ai(), "fn helper() {}".ai(), "// note"]);
+    let ai_commit = repo.stage_all_and_commit("AI fix").unwrap();
+
+    let mut docs = repo.filename("docs.rs");
+    docs.set_contents(lines!["// docs", "// more docs", "fn doc() {}".ai()]);
+    repo.stage_all_and_commit("Mostly human docs").unwrap();
+
+    repo.git(&["checkout", &main]).unwrap();
+
+    let output = repo
+        .git_ai(&["suggest-cherry-picks", "--from", "feature"])
+        .unwrap();
+    assert!(
+        output.contains(&format!(
+            "{}  67% (2/3 lines) AI fix",
+            &ai_commit.commit_sha[..7]
+        )),
+        "{output}"
+    );
+    assert!(!output.contains("Mostly human docs"), "{output}");
+    assert!(
+        output.contains(&format!("git cherry-pick {}", ai_commit.commit_sha)),
+        "{output}"
+    );
+
+    let lenient = repo
+        .git_ai(&[
+            "suggest-cherry-picks",
+            "--from",
+            "feature",
+            "--agent",
+            "mock_ai",
+            "--min-share",
+            "30",
+        ])
+        .unwrap();
+    assert!(lenient.contains("AI fix"), "{lenient}");
+    assert!(lenient.contains("Mostly human docs"), "{lenient}");
+
+    let other_agent = repo
+        .git_ai(&[
+            "suggest-cherry-picks",
+            "--from",
+            "feature",
+            "--agent",
+            "cursor",
+        ])
+        .unwrap();
+    assert!(
+        other_agent.starts_with("No commits on feature"),
+        "{other_agent}"
+    );
+}
+
+#[test]
+fn test_suggest_cherry_picks_requires_from() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let err = repo.git_ai(&["suggest-cherry-picks"]).unwrap_err();
+    assert!(err.contains("missing --from"), "{err}");
+}
@@ -0,0 +1,108 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn tag_attribution(repo: &TestRepo, tag: &str) -> serde_json::Value {
+    let output = repo.git_ai(&["show", "--tag", tag, "--json"]).unwrap();
+    serde_json::from_str(&output).unwrap_or_else(|e| panic!("{e}: {output}"))
+}
+
+#[test]
+fn test_annotated_tag_gets_attribution_since_previous_tag() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.annotate_tags = Some(true);
+    });
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}", "fn two() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.git(&["tag", "-a", "v1.0", "-m", "First release"])
+        .unwrap();
+
+    file.insert_at(1, lines!["fn three() {}".ai(), "fn four() {}".ai()]);
+    repo.stage_all_and_commit("AI functions").unwrap();
+    file.insert_at(1, lines!["fn five() {}"]);
+    let head = repo.stage_all_and_commit("Human function").unwrap();
+    repo.git(&["tag", "-a", "v1.1", "-m", "Second release"])
+        .unwrap();
+
+    let first = tag_attribution(&repo, "v1.0");
+    assert_eq!(first["previous_tag"], serde_json::Value::Null);
+    assert_eq!(first["commits"], 1);
+    assert_eq!(first["ai_additions"], 0);
+
+    let second = tag_attribution(&repo, "v1.1");
+    assert_eq!(second["schema_version"], "tag-attribution/1.0.0");
+    assert_eq!(second["commit"], head.commit_sha);
+    assert_eq!(second["previous_tag"], "v1.0");
+    assert_eq!(second["commits"], 2);
+    assert_eq!(second["ai_additions"], 2);
+    assert_eq!(second["human_additions"], 1);
+    assert_eq!(second["ai_percent"], 67);
+    assert_eq!(second["tools"].as_object().unwrap().len(), 1);
+
+    let summary = repo.git_ai(&["show", "--tag", "v1.1"]).unwrap();
+    assert!(summary.contains("since v1.0"), "{summary}");
+    assert!(summary.contains("AI share: 67%"), "{summary}");
+}
+
+#[test]
+fn test_tags_are_not_annotated_by_default() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.git(&["tag", "-a", "v1.0", "-m", "First release"])
+        .unwrap();
+
+    let output = repo.git_ai(&["show", "--tag", "v1.0"]).unwrap();
+    assert!(
+        output.contains("No attribution recorded for tag v1.0"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_lightweight_tags_are_rejected() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.annotate_tags = Some(true);
+    });
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo.git(&["tag", "light"]).unwrap();
+
+    let err = repo.git_ai(&["show", "--tag", "light"]).unwrap_err();
+    assert!(err.contains("light is not an annotated tag"), "{err}");
+}
+
+#[test]
+fn test_tag_notes_live_in_their_own_ref_and_push_with_authorship_notes() {
+    let (mut local, upstream) = TestRepo::new_with_remote();
+    local.patch_git_ai_config(|patch| {
+        patch.annotate_tags = Some(true);
+    });
+
+    let mut file = local.filename("lib.rs");
+    file.set_contents(lines!["fn one() {}".ai()]);
+    local.stage_all_and_commit("Initial commit").unwrap();
+    local
+        .git(&["tag", "-a", "v1.0", "-m", "First release"])
+        .unwrap();
+    let tag = local.git_og(&["rev-parse", "v1.0"]).unwrap();
+    let tag = tag.trim();
+
+    let tag_notes = local.git_og(&["notes", "--ref=ai-tags", "list"]).unwrap();
+    assert!(tag_notes.contains(tag), "{tag_notes}");
+    let authorship_notes = local.git_og(&["notes", "--ref=ai", "list"]).unwrap();
+    assert!(!authorship_notes.contains(tag), "{authorship_notes}");
+
+    local.git(&["push", "origin", "HEAD", "v1.0"]).unwrap();
+    let remote_tag_notes = upstream
+        .git_og(&["notes", "--ref=ai-tags", "list"])
+        .unwrap();
+    assert!(remote_tag_notes.contains(tag), "{remote_tag_notes}");
+}
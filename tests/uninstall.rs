@@ -0,0 +1,75 @@
+#[macro_use]
+mod repos;
+
+use repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_uninstall_dry_run_lists_cleanup_without_changes() {
+    let repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let hooks_dir = home.path().join(".git-ai").join("hooks");
+    let gitconfig = home.path().join(".gitconfig");
+    fs::write(
+        &gitconfig,
+        format!(
+            "[user]\n\tname = Test\n[core]\n\thooksPath = {}\n",
+            hooks_dir.display()
+        ),
+    )
+    .unwrap();
+    let internal_dir = home.path().join(".git-ai").join("internal");
+    fs::create_dir_all(&internal_dir).unwrap();
+    fs::write(internal_dir.join("distinct_id"), "id").unwrap();
+
+    let home_str = home.path().to_str().unwrap();
+    let gitconfig_str = gitconfig.to_str().unwrap();
+    let envs = [
+        ("HOME", home_str),
+        ("USERPROFILE", home_str),
+        ("GIT_CONFIG_GLOBAL", gitconfig_str),
+    ];
+
+    let output = repo
+        .git_ai_with_env(&["uninstall", "--dry-run"], &envs)
+        .unwrap();
+    assert!(
+        output.contains("Would unset global core.hooksPath"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains(&format!("Would remove {}", internal_dir.display())),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("Dry run. No changes were made."),
+        "{}",
+        output
+    );
+    assert!(internal_dir.join("distinct_id").exists());
+    assert!(
+        fs::read_to_string(&gitconfig)
+            .unwrap()
+            .contains("hooksPath")
+    );
+
+    let output = repo
+        .git_ai_with_env(&["uninstall", "--dry-run", "--keep-data"], &envs)
+        .unwrap();
+    assert!(
+        output.contains("Kept internal state (--keep-data)"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_uninstall_rejects_unknown_option() {
+    let repo = TestRepo::new();
+    let err = repo
+        .git_ai(&["uninstall", "--everything"])
+        .expect_err("unknown options are rejected");
+    assert!(err.contains("Usage: git-ai uninstall"), "{}", err);
+}
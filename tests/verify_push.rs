@@ -0,0 +1,206 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// One commit made through git-ai (has a note) followed by one made with hooks
+/// disabled (no note). Returns the sha of the unattributed commit.
+fn repo_with_unnoted_commit(local: &TestRepo) -> String {
+    let mut file = local.filename("lib.rs");
+    file.set_contents(vec!["fn tracked() {}".ai()]);
+    local
+        .stage_all_and_commit("tracked commit")
+        .expect("commit should succeed");
+
+    std::fs::write(local.path().join("untracked.rs"), "fn untracked() {}\n").unwrap();
+    local.git_og(&["add", "-A"]).expect("add should succeed");
+    local
+        .git_og(&["commit", "-m", "commit without note"])
+        .expect("commit should succeed");
+    local
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_verify_push_reports_commit_without_note() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let unnoted = repo_with_unnoted_commit(&local);
+
+    assert!(
+        local.git_ai(&["verify-push", "origin"]).is_err(),
+        "verify-push should fail when a commit has no note"
+    );
+
+    let output = local
+        .git_ai(&["verify-push", "origin", "--warn"])
+        .expect("--warn should not fail");
+    assert!(
+        output.contains("1 of 2 commits being pushed to origin has no authorship note"),
+        "unexpected output: {}",
+        output
+    );
+    assert!(
+        output.contains(&unnoted[..7]),
+        "unexpected output: {}",
+        output
+    );
+    assert!(output.contains("commit without note"));
+
+    let json = local
+        .git_ai(&["verify-push", "origin", "--warn", "--json"])
+        .expect("--json should not fail");
+    let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    assert_eq!(parsed["commits"], 2);
+    assert_eq!(parsed["missing"][0]["sha"], unnoted);
+    assert_eq!(parsed["stale"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_verify_push_passes_when_every_commit_has_a_note() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+
+    let mut file = local.filename("lib.rs");
+    file.set_contents(vec!["fn tracked() {}".ai()]);
+    local
+        .stage_all_and_commit("tracked commit")
+        .expect("commit should succeed");
+
+    let output = local
+        .git_ai(&["verify-push", "origin"])
+        .expect("verify-push should pass");
+    assert!(
+        output.contains("Every commit being pushed to origin has an authorship note"),
+        "unexpected output: {}",
+        output
+    );
+}
+
+#[test]
+fn test_verify_push_reports_stale_note() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+
+    let mut file = local.filename("lib.rs");
+    file.set_contents(vec!["fn tracked() {}".ai()]);
+    let commit = local
+        .stage_all_and_commit("tracked commit")
+        .expect("commit should succeed");
+    local
+        .git_og(&[
+            "notes",
+            "--ref=ai",
+            "add",
+            "-f",
+            "-m",
+            "not an authorship log",
+            &commit.commit_sha,
+        ])
+        .expect("overwriting note should succeed");
+
+    let output = local
+        .git_ai(&["verify-push", "origin", "--warn"])
+        .expect("--warn should not fail");
+    assert!(
+        output.contains("1 commit has a stale authorship note"),
+        "unexpected output: {}",
+        output
+    );
+}
+
+#[test]
+fn test_push_blocked_when_verify_push_is_block() {
+    let (mut local, upstream) = TestRepo::new_with_remote();
+    repo_with_unnoted_commit(&local);
+    local.patch_git_ai_config(|patch| {
+        patch.verify_push = Some("block".to_string());
+    });
+
+    let err = local
+        .git(&["push", "origin", "HEAD:main"])
+        .expect_err("push should be blocked");
+    assert!(
+        err.contains("has no authorship note") && err.contains("Push blocked"),
+        "unexpected error: {}",
+        err
+    );
+    assert!(
+        upstream.git_og(&["rev-parse", "--verify", "main"]).is_err(),
+        "nothing should have been pushed"
+    );
+
+    local
+        .git(&["push", "--no-verify", "origin", "HEAD:main"])
+        .expect("--no-verify should bypass verification");
+}
+
+#[test]
+fn test_push_warns_when_verify_push_is_warn() {
+    let (mut local, upstream) = TestRepo::new_with_remote();
+    repo_with_unnoted_commit(&local);
+    local.patch_git_ai_config(|patch| {
+        patch.verify_push = Some("warn".to_string());
+    });
+
+    let output = local
+        .git(&["push", "origin", "HEAD:main"])
+        .expect("push should succeed with a warning");
+    assert!(
+        output.contains("has no authorship note"),
+        "unexpected output: {}",
+        output
+    );
+    assert!(upstream.git_og(&["rev-parse", "--verify", "main"]).is_ok());
+}
+
+#[test]
+fn test_verify_push_checks_the_pushed_refs_not_head() {
+    let (local, _upstream) = TestRepo::new_with_remote();
+    let unnoted = repo_with_unnoted_commit(&local);
+    local
+        .git_og(&["branch", "noted", "HEAD~1"])
+        .expect("branch should succeed");
+
+    let output = local
+        .git_ai(&["verify-push", "origin", "noted:main"])
+        .expect("the pushed branch has notes");
+    assert!(
+        output.contains("Every commit being pushed to origin has an authorship note (1 checked)"),
+        "unexpected output: {}",
+        output
+    );
+
+    // Pre-push hook input names what is pushed; HEAD doesn't matter
+    let noted = local.git_og(&["rev-parse", "noted"]).unwrap();
+    let input = format!(
+        "refs/heads/noted {} refs/heads/main {}\n",
+        noted.trim(),
+        "0".repeat(40)
+    );
+    local
+        .git_ai_with_stdin(&["verify-push", "origin", "--stdin"], input.as_bytes())
+        .expect("the pushed branch has notes");
+
+    let input = format!(
+        "refs/heads/main {} refs/heads/main {}\n",
+        unnoted,
+        "0".repeat(40)
+    );
+    assert!(
+        local
+            .git_ai_with_stdin(&["verify-push", "origin", "--stdin"], input.as_bytes())
+            .is_err(),
+        "the unnoted commit is pushed"
+    );
+    let json = local
+        .git_ai_with_stdin(
+            &["verify-push", "origin", "--stdin", "--warn", "--json"],
+            input.as_bytes(),
+        )
+        .expect("--warn should not fail");
+    let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    assert_eq!(parsed["commits"], 2);
+    assert_eq!(parsed["missing"][0]["sha"], unnoted);
+}
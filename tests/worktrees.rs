@@ -0,0 +1,90 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+use std::path::PathBuf;
+
+/// Add a linked worktree next to the test repo on a new branch
+fn add_worktree(repo: &TestRepo, branch: &str) -> PathBuf {
+    let name = format!(
+        "{}-{}",
+        repo.path().file_name().unwrap().to_str().unwrap(),
+        branch
+    );
+    let path = repo.path().parent().unwrap().join(name);
+    repo.git(&["worktree", "add", "-b", branch, path.to_str().unwrap()])
+        .unwrap();
+    path
+}
+
+fn remove_worktree(repo: &TestRepo, path: &PathBuf) {
+    let _ = repo.git(&["worktree", "remove", "--force", path.to_str().unwrap()]);
+    let _ = fs::remove_dir_all(path);
+}
+
+/// Checkpoints in the main checkout and a linked worktree don't bleed into each other
+#[test]
+fn test_worktree_checkpoints_are_independent() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let worktree = add_worktree(&repo, "wt");
+
+    // AI edit in the worktree, left uncommitted while the main checkout commits
+    fs::write(worktree.join("wt.txt"), "AI line in worktree\n").unwrap();
+    repo.git_ai_from_working_dir(&worktree, &["checkpoint", "mock_ai", "wt.txt"])
+        .unwrap();
+
+    file.insert_at(1, lines!["human line in main"]);
+    repo.stage_all_and_commit("Main change").unwrap();
+    file.assert_lines_and_blame(lines!["base".human(), "human line in main".human()]);
+
+    repo.git_with_env(&["add", "-A"], &[], Some(&worktree))
+        .unwrap();
+    repo.git_with_env(&["commit", "-m", "Worktree change"], &[], Some(&worktree))
+        .unwrap();
+
+    let blame = repo
+        .git_ai_from_working_dir(&worktree, &["blame", "wt.txt"])
+        .unwrap();
+    assert!(blame.contains("mock_ai"), "{blame}");
+
+    remove_worktree(&repo, &worktree);
+}
+
+/// An agent running in the main checkout that edits a file in a linked worktree
+/// checkpoints into that worktree's working log
+#[test]
+fn test_checkpoint_routes_edits_to_other_worktree() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("file.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let worktree = add_worktree(&repo, "agent");
+
+    let edited = worktree.join("agent.txt");
+    fs::write(&edited, "AI line from another checkout\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai", edited.to_str().unwrap()])
+        .unwrap();
+
+    repo.git_with_env(&["add", "-A"], &[], Some(&worktree))
+        .unwrap();
+    repo.git_with_env(&["commit", "-m", "Agent change"], &[], Some(&worktree))
+        .unwrap();
+
+    let blame = repo
+        .git_ai_from_working_dir(&worktree, &["blame", "agent.txt"])
+        .unwrap();
+    assert!(blame.contains("mock_ai"), "{blame}");
+
+    // Nothing leaked into the main checkout's working log
+    file.insert_at(1, lines!["human line"]);
+    repo.stage_all_and_commit("Main change").unwrap();
+    file.assert_lines_and_blame(lines!["base".human(), "human line".human()]);
+
+    remove_worktree(&repo, &worktree);
+}
@@ -0,0 +1,115 @@
+#[macro_use]
+mod repos;
+
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::{Value, json};
+use std::fs;
+
+#[test]
+fn test_zed_checkpoint_attributes_after_edit() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("main.rs");
+    file.set_contents(lines!["fn main() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let repo_dir = repo.canonical_path().to_string_lossy().to_string();
+    let before = json!({
+        "phase": "before_edit",
+        "edited_filepaths": ["main.rs"],
+        "repo_working_dir": repo_dir,
+    })
+    .to_string();
+    repo.git_ai(&["checkpoint", "zed", "--hook-input", &before])
+        .unwrap();
+
+    fs::write(
+        repo.path().join("main.rs"),
+        "fn main() {}\nfn helper() {}\n",
+    )
+    .unwrap();
+    let after = json!({
+        "phase": "after_edit",
+        "edited_filepaths": ["main.rs"],
+        "thread_id": "thread-42",
+        "model": "claude-sonnet-4",
+        "repo_working_dir": repo_dir,
+    })
+    .to_string();
+    repo.git_ai(&["checkpoint", "zed", "--hook-input", &after])
+        .unwrap();
+
+    let commit = repo.stage_all_and_commit("Zed edit").unwrap();
+    file.assert_lines_and_blame(lines!["fn main() {}".human(), "fn helper() {}".ai()]);
+
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("zed prompt record");
+    assert_eq!(prompt.agent_id.tool, "zed");
+    assert_eq!(prompt.agent_id.id, "thread-42");
+    assert_eq!(prompt.agent_id.model, "claude-sonnet-4");
+}
+
+#[test]
+fn test_zed_checkpoint_rejects_unknown_phase() {
+    let repo = TestRepo::new();
+    let input = json!({"phase": "during_edit", "edited_filepaths": []}).to_string();
+    // Like every preset, a bad hook input is reported without failing the agent's edit
+    let output = repo
+        .git_ai(&["checkpoint", "zed", "--hook-input", &input])
+        .unwrap();
+    assert!(
+        output.contains("Unsupported Zed checkpoint phase: during_edit"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_mcp_server_checkpoints_through_tool_call() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("main.rs");
+    file.set_contents(lines!["fn main() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    fs::write(
+        repo.path().join("main.rs"),
+        "fn main() {}\nfn helper() {}\n",
+    )
+    .unwrap();
+
+    let messages = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {"protocolVersion": "2025-03-26", "capabilities": {}}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+            "name": "checkpoint",
+            "arguments": {"phase": "after_edit", "edited_filepaths": ["main.rs"]}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+            "name": "checkpoint",
+            "arguments": {"phase": "sometime", "edited_filepaths": ["main.rs"]}
+        }}),
+    ];
+    let stdin: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+    let output = repo
+        .git_ai_with_stdin(&["mcp", "zed"], stdin.as_bytes())
+        .unwrap();
+
+    let responses: Vec<Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line}")))
+        .collect();
+    // The notification gets no response
+    assert_eq!(responses.len(), 4, "{output}");
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(responses[1]["result"]["tools"][0]["name"], "checkpoint");
+    assert_eq!(responses[2]["result"]["isError"], false, "{output}");
+    assert_eq!(responses[3]["result"]["isError"], true, "{output}");
+
+    repo.stage_all_and_commit("Zed edit").unwrap();
+    file.assert_lines_and_blame(lines!["fn main() {}".human(), "fn helper() {}".ai()]);
+}